@@ -0,0 +1,34 @@
+-- Migration for persisting order book snapshots and deltas from the book topic
+
+CREATE TABLE IF NOT EXISTS public.book_data (
+    id UUID NOT NULL DEFAULT uuid_generate_v4(),
+    instrument_name VARCHAR(100) NOT NULL,
+    kind VARCHAR(10) NOT NULL,
+    sequence BIGINT NOT NULL,
+    side VARCHAR(10) NOT NULL,
+    price DOUBLE PRECISION NOT NULL,
+    amount DOUBLE PRECISION NOT NULL,
+    exchange_time DOUBLE PRECISION,
+    timestamp DOUBLE PRECISION NOT NULL,
+    processing_timestamp DOUBLE PRECISION,
+    time_ts TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id)
+);
+
+-- Populate time_ts from timestamp
+CREATE OR REPLACE FUNCTION book_update_time_ts()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.time_ts = to_timestamp(NEW.timestamp);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trigger_book_time_ts ON public.book_data;
+CREATE TRIGGER trigger_book_time_ts
+BEFORE INSERT OR UPDATE ON public.book_data
+FOR EACH ROW EXECUTE FUNCTION book_update_time_ts();
+
+-- Reconstruction looks up the latest snapshot before a time, then the deltas after it
+CREATE INDEX IF NOT EXISTS idx_book_instrument_kind_timestamp ON public.book_data (instrument_name, kind, timestamp DESC);
+CREATE INDEX IF NOT EXISTS idx_book_instrument_timestamp ON public.book_data (instrument_name, timestamp, sequence);
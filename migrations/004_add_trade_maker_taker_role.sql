@@ -0,0 +1,8 @@
+-- Migration for the typed maker/taker role added in trade/v3
+
+ALTER TABLE public.trade_data ADD COLUMN IF NOT EXISTS maker_taker_role VARCHAR(10);
+
+-- Backfill from the free-form string for rows written before v3
+UPDATE public.trade_data
+SET maker_taker_role = maker_taker
+WHERE maker_taker_role IS NULL AND maker_taker IN ('maker', 'taker');
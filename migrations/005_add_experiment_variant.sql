@@ -0,0 +1,4 @@
+-- Migration for the experiment variant added in ack/v4 and trade/v5
+
+ALTER TABLE public.ack_data ADD COLUMN IF NOT EXISTS variant_id VARCHAR(64);
+ALTER TABLE public.trade_data ADD COLUMN IF NOT EXISTS variant_id VARCHAR(64);
@@ -4,12 +4,14 @@ Contains all Kafka consumers and sink connectors used in the application.
 """
 
 from python_pipeline.consumers.base_sink_connector import BaseSinkConnector
+from python_pipeline.consumers.book_sink_connector import BookSinkConnector
 from python_pipeline.consumers.index_sink_connector import IndexSinkConnector
 from python_pipeline.consumers.order_sink_connector import OrderSinkConnector
 from python_pipeline.consumers.ticker_sink_connector import TickerSinkConnector
 
 __all__ = [
     'BaseSinkConnector',
+    'BookSinkConnector',
     'TickerSinkConnector',
     'OrderSinkConnector',
     'IndexSinkConnector'
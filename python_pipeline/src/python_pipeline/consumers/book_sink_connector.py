@@ -0,0 +1,33 @@
+"""
+Order book sink connector for PostgreSQL
+"""
+
+import logging
+from typing import Any, Dict
+
+from python_pipeline.consumers.base_sink_connector import BaseSinkConnector
+from python_pipeline.models.book import ThalexBookLevel
+
+# Configure logging
+logger = logging.getLogger(__name__)
+
+
+class BookSinkConnector(BaseSinkConnector):
+    """PostgreSQL sink connector for order book snapshots and deltas"""
+    
+    topic_key: str = "book"
+    connector_name_prefix: str = "postgres-sink"
+    
+    def __init__(self, config: Dict[str, Any]):
+        """
+        Initialize the book sink connector.
+        
+        Args:
+            config: Application configuration
+        """
+        super().__init__(
+            config=config,
+            model_class=ThalexBookLevel,
+            table_name="book_data",
+            primary_keys=["id"]
+        )
@@ -8,6 +8,7 @@ from typing import Any, Dict, Type
 
 from python_pipeline.consumers.ack_sink_connector import AckSinkConnector
 from python_pipeline.consumers.base_sink_connector import BaseSinkConnector
+from python_pipeline.consumers.book_sink_connector import BookSinkConnector
 from python_pipeline.consumers.index_sink_connector import IndexSinkConnector
 from python_pipeline.consumers.ticker_sink_connector import TickerSinkConnector
 from python_pipeline.consumers.trade_sink_connector import TradeSinkConnector
@@ -70,7 +71,8 @@ class ConnectorFactory:
         "ticker": TickerSinkConnector,
         "ack": AckSinkConnector,
         "trade": TradeSinkConnector,
-        "index": IndexSinkConnector
+        "index": IndexSinkConnector,
+        "book": BookSinkConnector
     }
     
     @classmethod
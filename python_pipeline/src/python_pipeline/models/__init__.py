@@ -4,6 +4,7 @@ Contains all data models used in the application.
 """
 
 from python_pipeline.models.ack import ThalexAck
+from python_pipeline.models.book import ThalexBookLevel
 from python_pipeline.models.index import PriceIndex, ThalexIndex
 from python_pipeline.models.model_base import ModelBase
 from python_pipeline.models.order import ThalexFill, ThalexOrder
@@ -14,6 +15,7 @@ __all__ = [
     'ModelBase',
     'ThalexTicker',
     'ThalexAck',
+    'ThalexBookLevel',
     'ThalexTrade',
     'ThalexOrder',
     'ThalexFill',
@@ -0,0 +1,28 @@
+"""
+Order book level model for CrypticsLabBot.
+Implements Pydantic models for book snapshot and delta records.
+"""
+
+from typing import ClassVar, Optional
+
+from pydantic import Field
+
+from python_pipeline.models.model_base import ModelBase
+
+
+class ThalexBookLevel(ModelBase):
+    """One level of a book snapshot, or one level change, published by the Rust engine"""
+    
+    # Class variables for the ModelBase
+    model_name: ClassVar[str] = "Book"
+    
+    # Field names that match the v1.avsc schema
+    instrument_name: str = Field(..., description="Name of the instrument")
+    kind: str = Field(..., description="snapshot or delta")
+    sequence: int = Field(..., description="Book notification the record came from, increasing per instrument")
+    side: str = Field(..., description="bid or ask")
+    price: float = Field(..., description="Level price")
+    amount: float = Field(..., description="Amount resting at the price; zero when a delta removes the level")
+    exchange_time: Optional[float] = Field(None, description="Exchange time of the book notification")
+    timestamp: float = Field(..., description="When the notification was received")
+    processing_timestamp: Optional[float] = Field(None, description="When the record was processed by the Rust engine")
@@ -0,0 +1,44 @@
+#![no_main]
+
+//! Parsed frames through notification dispatch, with no exchange connection and no Kafka
+//!
+//! Dispatch may fail (e.g. orders can't be sent while disconnected); it must not panic.
+
+use std::sync::{Arc, OnceLock};
+
+use cryptics_lab_bot::infrastructure::exchange::thalex::client::ThalexClient;
+use cryptics_lab_bot::strategies::thalex_market_maker::{
+    InboundMessage, MarketDataManager, NotificationHandler, OrderManager,
+};
+use libfuzzer_sys::fuzz_target;
+use tokio::runtime::Runtime;
+use tokio::sync::{Mutex, Notify};
+
+struct Harness {
+    runtime: Runtime,
+    handler: NotificationHandler,
+}
+
+fn harness() -> &'static Harness {
+    static HARNESS: OnceLock<Harness> = OnceLock::new();
+    HARNESS.get_or_init(|| {
+        let client = Arc::new(Mutex::new(ThalexClient::new()));
+        let market_data = Arc::new(MarketDataManager::new(Arc::new(Notify::new()), None));
+        let order_manager = Arc::new(OrderManager::new(client, market_data.clone(), None));
+        Harness {
+            runtime: tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap(),
+            handler: NotificationHandler::new(market_data, order_manager),
+        }
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(parsed) = serde_json::from_slice::<serde_json::Value>(data) else {
+        return;
+    };
+    let Some(message) = InboundMessage::from_json(parsed) else {
+        return;
+    };
+    let harness = harness();
+    let _ = harness.runtime.block_on(harness.handler.dispatch(message));
+});
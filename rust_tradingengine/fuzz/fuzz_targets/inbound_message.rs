@@ -0,0 +1,15 @@
+#![no_main]
+
+//! Raw WebSocket frames through the listen-path parsing and routing
+
+use cryptics_lab_bot::strategies::thalex_market_maker::InboundMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(parsed) = serde_json::from_slice::<serde_json::Value>(data) else {
+        return;
+    };
+    if let Some(message) = InboundMessage::from_json(parsed) {
+        let _ = message.priority();
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+//! Arbitrary JSON through the exchange message parsers
+
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = text.parse::<Channel>();
+    }
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(data) else {
+        return;
+    };
+    let _ = ThaleParser::parse_order_json(&value);
+    let _ = ThaleParser::parse_trade_json(&value, false);
+    let _ = ThaleParser::extract_trades_from_order(&value, false);
+    let _ = Ticker::from_json(&value, "BTC-PERPETUAL".to_string());
+});
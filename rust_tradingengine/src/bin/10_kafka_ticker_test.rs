@@ -1,6 +1,5 @@
 use anyhow::{anyhow, Result};
 use serde_json::json;
-use tokio;
 use std::collections::HashMap;
 use std::time::Duration;
 
@@ -11,7 +10,7 @@ use rdkafka::config::RDKafkaLogLevel;
 use apache_avro::{Schema, Reader};
 
 // Import the KafkaProducer and domain types
-use cryptics_lab_bot::infrastructure::kafka::KafkaProducer;
+use cryptics_lab_bot::infrastructure::kafka::{split_confluent_payload, KafkaProducer};
 use cryptics_lab_bot::infrastructure::kafka::helper::SchemaHelper;
 use cryptics_lab_bot::domain::model::ticker::Ticker;
 
@@ -126,16 +125,11 @@ async fn kafka_ticker_test() -> Result<()> {
                         println!("   Payload length: {} bytes", payload.len());
                         
                         // Validate that the payload follows the expected format
-                        if payload.len() >= 5 && payload[0] == 0 {
-                            let received_schema_id = i32::from_be_bytes([
-                                payload[1], payload[2], payload[3], payload[4]
-                            ]);
+                        if let Ok((received_schema_id, avro_data)) = split_confluent_payload(payload) {
                             
                             println!("   Received message with schema ID: {}", received_schema_id);
                             println!("   Message verified successfully!");
                             
-                            // Decode the message
-                            let avro_data = &payload[5..];
                             
                             // Get the schema from the registry
                             let schema_url = format!("{}/schemas/ids/{}", schema_registry_url, received_schema_id);
@@ -150,7 +144,7 @@ async fn kafka_ticker_test() -> Result<()> {
                                 let schema = Schema::parse_str(schema_str)?;
                                 
                                 // Decode the data
-                                match Reader::with_schema(&schema, &avro_data[..]) {
+                                match Reader::with_schema(&schema, avro_data) {
                                     Ok(mut reader) => {
                                         if let Some(Ok(value)) = reader.next() {
                                             println!("   Successfully decoded Avro data:");
@@ -289,10 +283,7 @@ async fn kafka_ticker_test() -> Result<()> {
         
         // Decode the second message
         if let Some(payload) = last_message_data {
-            if payload.len() >= 5 && payload[0] == 0 {
-                let received_schema_id = i32::from_be_bytes([
-                    payload[1], payload[2], payload[3], payload[4]
-                ]);
+            if let Ok((received_schema_id, avro_data)) = split_confluent_payload(&payload) {
                 
                 println!("14. Second message uses schema ID: {}", received_schema_id);
                 
@@ -308,9 +299,7 @@ async fn kafka_ticker_test() -> Result<()> {
                     // Parse the schema
                     let schema = Schema::parse_str(schema_str)?;
                     
-                    // Decode the Avro data
-                    let avro_data = &payload[5..];
-                    match Reader::with_schema(&schema, &avro_data[..]) {
+                    match Reader::with_schema(&schema, avro_data) {
                         Ok(mut reader) => {
                             if let Some(Ok(value)) = reader.next() {
                                 println!("15. Successfully decoded second message data:");
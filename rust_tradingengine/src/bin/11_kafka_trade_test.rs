@@ -1,6 +1,5 @@
 use anyhow::{anyhow, Result};
 use serde_json::json;
-use tokio;
 use std::collections::HashMap;
 use std::time::Duration;
 
@@ -11,7 +10,7 @@ use rdkafka::config::RDKafkaLogLevel;
 use apache_avro::{Schema, Reader};
 
 // Import the KafkaProducer and domain types
-use cryptics_lab_bot::infrastructure::kafka::KafkaProducer;
+use cryptics_lab_bot::infrastructure::kafka::{split_confluent_payload, KafkaProducer};
 use cryptics_lab_bot::infrastructure::kafka::helper::SchemaHelper;
 use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
 
@@ -46,7 +45,7 @@ async fn kafka_trade_test() -> Result<()> {
     });
     
     // Use the ThaleParser to parse the JSON data into a Trade
-    let trade = ThaleParser::parse_trade_json(&json_data1)?;
+    let trade = ThaleParser::parse_trade_json(&json_data1, false)?;
     println!("2. Successfully parsed JSON into Trade:");
     println!("   - Trade ID: {}", trade.trade_id);
     println!("   - Order ID: {}", trade.order_id);
@@ -109,16 +108,11 @@ async fn kafka_trade_test() -> Result<()> {
                         println!("   Payload length: {} bytes", payload.len());
                         
                         // Validate that the payload follows the expected format
-                        if payload.len() >= 5 && payload[0] == 0 {
-                            let received_schema_id = i32::from_be_bytes([
-                                payload[1], payload[2], payload[3], payload[4]
-                            ]);
+                        if let Ok((received_schema_id, avro_data)) = split_confluent_payload(payload) {
                             
                             println!("   Received message with schema ID: {}", received_schema_id);
                             println!("   Message verified successfully!");
                             
-                            // Decode the message
-                            let avro_data = &payload[5..];
                             
                             // Get the schema from the registry
                             let schema_url = format!("{}/schemas/ids/{}", schema_registry_url, received_schema_id);
@@ -133,7 +127,7 @@ async fn kafka_trade_test() -> Result<()> {
                                 let schema = Schema::parse_str(schema_str)?;
                                 
                                 // Decode the data
-                                match Reader::with_schema(&schema, &avro_data[..]) {
+                                match Reader::with_schema(&schema, avro_data) {
                                     Ok(mut reader) => {
                                         if let Some(Ok(value)) = reader.next() {
                                             println!("   Successfully decoded Avro data:");
@@ -216,7 +210,7 @@ async fn kafka_trade_test() -> Result<()> {
     });
     
     // Extract trades from the order data
-    let extracted_trades = ThaleParser::extract_trades_from_order(&order_data_with_fills)?;
+    let extracted_trades = ThaleParser::extract_trades_from_order(&order_data_with_fills, false)?;
     println!("9. Extracted {} trades from order data:", extracted_trades.len());
     for (i, trade) in extracted_trades.iter().enumerate() {
         println!("   Trade #{}: ID={}, Amount={}, Price={}", i+1, trade.trade_id, trade.amount, trade.price);
@@ -1,9 +1,7 @@
 use anyhow::{anyhow, Result};
 use serde_json::json;
-use tokio;
 use std::collections::HashMap;
 use std::time::Duration;
-use chrono;
 
 use rdkafka::consumer::{Consumer, StreamConsumer};
 use rdkafka::{ClientConfig, Message};
@@ -12,7 +10,7 @@ use rdkafka::config::RDKafkaLogLevel;
 use apache_avro::{Schema, Reader};
 
 // Import the KafkaProducer and domain types
-use cryptics_lab_bot::infrastructure::kafka::KafkaProducer;
+use cryptics_lab_bot::infrastructure::kafka::{split_confluent_payload, KafkaProducer};
 use cryptics_lab_bot::infrastructure::kafka::helper::SchemaHelper;
 use cryptics_lab_bot::domain::model::ticker::Ticker;
 
@@ -180,10 +178,7 @@ async fn existing_ticker_topic_test() -> Result<()> {
                                 println!("   Payload length: {} bytes", payload.len());
                                 
                                 // Validate that the payload follows the expected format
-                                if payload.len() >= 5 && payload[0] == 0 {
-                                    let received_schema_id = i32::from_be_bytes([
-                                        payload[1], payload[2], payload[3], payload[4]
-                                    ]);
+                                if let Ok((received_schema_id, avro_data)) = split_confluent_payload(payload) {
                                     
                                     println!("   Received message with schema ID: {}", received_schema_id);
                                     
@@ -195,8 +190,6 @@ async fn existing_ticker_topic_test() -> Result<()> {
                                                  schema_id, received_schema_id);
                                     }
                                     
-                                    // Decode the message
-                                    let avro_data = &payload[5..];
                                     
                                     // Get the schema from the registry
                                     let schema_url = format!("{}/schemas/ids/{}", schema_registry_url, received_schema_id);
@@ -211,7 +204,7 @@ async fn existing_ticker_topic_test() -> Result<()> {
                                         let schema = Schema::parse_str(schema_str)?;
                                         
                                         // Decode the data
-                                        match Reader::with_schema(&schema, &avro_data[..]) {
+                                        match Reader::with_schema(&schema, avro_data) {
                                             Ok(mut reader) => {
                                                 if let Some(Ok(value)) = reader.next() {
                                                     println!("   Successfully decoded Avro data:");
@@ -1,8 +1,6 @@
 use anyhow::{anyhow, Result};
 use serde::{Serialize, Deserialize};
 use serde_json::json;
-use tokio;
-use reqwest;
 use rdkafka::producer::{FutureProducer, FutureRecord};
 use rdkafka::consumer::{Consumer, StreamConsumer};
 use rdkafka::{ClientConfig, Message, TopicPartitionList};
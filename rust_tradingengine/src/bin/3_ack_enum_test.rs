@@ -1,8 +1,6 @@
 use anyhow::{anyhow, Result};
 use serde::{Serialize, Deserialize};
 use serde_json::json;
-use tokio;
-use reqwest;
 use rdkafka::producer::{FutureProducer, FutureRecord};
 use rdkafka::consumer::{Consumer, StreamConsumer};
 use rdkafka::{ClientConfig, Message, TopicPartitionList};
@@ -17,6 +15,7 @@ use apache_avro::{
 
 // Import the enums from the domain
 use cryptics_lab_bot::domain::enums::{OrderSide, OrderStatus};
+use cryptics_lab_bot::infrastructure::kafka::{frame_confluent_payload, split_confluent_payload};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AckMessage {
@@ -58,6 +57,7 @@ fn order_status_to_avro(status: &OrderStatus) -> AvroValue {
 }
 
 // Convert AckMessage to Avro Value
+#[allow(clippy::vec_init_then_push)]
 fn ack_to_avro_value(ack: &AckMessage) -> AvroValue {
     let mut fields = vec![];
     
@@ -205,11 +205,7 @@ async fn ack_enum_test() -> Result<()> {
     println!("Successfully encoded with apache-avro! Size: {} bytes", avro_bytes.len());
     
     // Now create an Avro message for Kafka with schema ID
-    // Format: [0x00, schema_id (4 bytes), avro_data]
-    let mut kafka_payload = Vec::with_capacity(5 + avro_bytes.len());
-    kafka_payload.push(0); // Magic byte
-    kafka_payload.extend_from_slice(&schema_id.to_be_bytes()); // Schema ID in big-endian format
-    kafka_payload.extend_from_slice(&avro_bytes); // Avro-encoded data
+    let kafka_payload = frame_confluent_payload(schema_id, &avro_bytes);
     
     println!("5. Sending to Kafka topic: {}", topic);
     let producer: FutureProducer = ClientConfig::new()
@@ -271,19 +267,15 @@ async fn ack_enum_test() -> Result<()> {
                         println!("Payload length: {} bytes", payload.len());
                         
                         // Validate that the payload follows the expected format
-                        if payload.len() >= 5 && payload[0] == 0 {
-                            let received_schema_id = i32::from_be_bytes([
-                                payload[1], payload[2], payload[3], payload[4]
-                            ]);
+                        if let Ok((received_schema_id, avro_data)) = split_confluent_payload(payload) {
                             
                             println!("Received message with schema ID: {}", received_schema_id);
                             println!("Successfully verified end-to-end process!");
                             
                             // For debugging: try to decode the Avro data
-                            if payload.len() > 5 {
-                                let avro_data = &payload[5..];
+                            if !avro_data.is_empty() {
                                 
-                                match Reader::with_schema(&schema, &avro_data[..]) {
+                                match Reader::with_schema(&schema, avro_data) {
                                     Ok(mut reader) => {
                                         if let Some(Ok(value)) = reader.next() {
                                             println!("Successfully decoded Avro data: {:?}", value);
@@ -1,7 +1,5 @@
 use anyhow::{anyhow, Result};
 use serde_json::json;
-use tokio;
-use reqwest;
 use std::fs;
 use std::path::Path;
 use std::time::Duration;
@@ -22,7 +20,8 @@ use rdkafka::config::RDKafkaLogLevel;
 // Import the ThaleParser and domain types
 use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
 use cryptics_lab_bot::domain::enums::{OrderSide, OrderStatus, OrderType, TimeInForce};
-use cryptics_lab_bot::domain::model::ack::Ack;
+use cryptics_lab_bot::domain::model::order::Order;
+use cryptics_lab_bot::infrastructure::kafka::{frame_confluent_payload, split_confluent_payload};
 
 // Helper function to convert an OrderSide to Avro enum value
 fn order_side_to_avro(side: &OrderSide) -> AvroValue {
@@ -60,7 +59,7 @@ fn order_status_to_avro(status: &OrderStatus) -> AvroValue {
 }
 
 // Convert Ack to Avro Value
-fn ack_to_avro_value(ack: &Ack) -> AvroValue {
+fn ack_to_avro_value(ack: &Order) -> AvroValue {
     let mut fields = vec![];
     
     // Add all fields in the correct order according to the schema
@@ -207,7 +206,7 @@ async fn schema_registry_load_test() -> Result<()> {
     });
     
     // Use the ThaleParser to parse the JSON data into an Ack
-    let ack = ThaleParser::parse_ack_json(&json_data)?;
+    let ack = ThaleParser::parse_order_json(&json_data)?;
     println!("4. Successfully parsed JSON into Ack:");
     println!("   - Order ID: {}", ack.order_id);
     println!("   - Direction: {:?}", ack.direction);
@@ -227,11 +226,7 @@ async fn schema_registry_load_test() -> Result<()> {
     println!("Successfully encoded with apache-avro! Size: {} bytes", avro_bytes.len());
     
     // Now create an Avro message for Kafka with schema ID
-    // Format: [0x00, schema_id (4 bytes), avro_data]
-    let mut kafka_payload = Vec::with_capacity(5 + avro_bytes.len());
-    kafka_payload.push(0); // Magic byte
-    kafka_payload.extend_from_slice(&schema_id.to_be_bytes()); // Schema ID in big-endian format
-    kafka_payload.extend_from_slice(&avro_bytes); // Avro-encoded data
+    let kafka_payload = frame_confluent_payload(schema_id, &avro_bytes);
     
     println!("6. Sending to Kafka topic: {}", topic);
     let producer: FutureProducer = ClientConfig::new()
@@ -293,19 +288,13 @@ async fn schema_registry_load_test() -> Result<()> {
                         println!("Payload length: {} bytes", payload.len());
                         
                         // Validate that the payload follows the expected format
-                        if payload.len() >= 5 && payload[0] == 0 {
-                            let received_schema_id = i32::from_be_bytes([
-                                payload[1], payload[2], payload[3], payload[4]
-                            ]);
-                            
+                        if let Ok((received_schema_id, avro_data)) = split_confluent_payload(payload) {
                             println!("Received message with schema ID: {}", received_schema_id);
                             println!("Successfully verified end-to-end process!");
                             
                             // For debugging: try to decode the Avro data
-                            if payload.len() > 5 {
-                                let avro_data = &payload[5..];
-                                
-                                match Reader::with_schema(&schema, &avro_data[..]) {
+                            if !avro_data.is_empty() {
+                                match Reader::with_schema(&schema, avro_data) {
                                     Ok(mut reader) => {
                                         if let Some(Ok(value)) = reader.next() {
                                             println!("Successfully decoded Avro data:");
@@ -1,6 +1,5 @@
 use anyhow::{anyhow, Result};
 use serde_json::json;
-use tokio;
 use std::collections::HashMap;
 use std::time::Duration;
 
@@ -11,7 +10,7 @@ use rdkafka::config::RDKafkaLogLevel;
 use apache_avro::{Schema, Reader};
 
 // Import the KafkaProducer and domain types
-use cryptics_lab_bot::infrastructure::kafka::KafkaProducer;
+use cryptics_lab_bot::infrastructure::kafka::{split_confluent_payload, KafkaProducer};
 use cryptics_lab_bot::infrastructure::kafka::helper::SchemaHelper;
 use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
 
@@ -53,7 +52,7 @@ async fn kafka_producer_test() -> Result<()> {
     });
     
     // Use the ThaleParser to parse the JSON data into an Ack
-    let ack1 = ThaleParser::parse_ack_json(&json_data1)?;
+    let ack1 = ThaleParser::parse_order_json(&json_data1)?;
     println!("2. Successfully parsed JSON into Ack:");
     println!("   - Order ID: {}", ack1.order_id);
     println!("   - Direction: {:?}", ack1.direction);
@@ -117,10 +116,7 @@ async fn kafka_producer_test() -> Result<()> {
                         println!("   Payload length: {} bytes", payload.len());
                         
                         // Validate that the payload follows the expected format
-                        if payload.len() >= 5 && payload[0] == 0 {
-                            let received_schema_id = i32::from_be_bytes([
-                                payload[1], payload[2], payload[3], payload[4]
-                            ]);
+                        if let Ok((received_schema_id, _)) = split_confluent_payload(payload) {
                             
                             println!("   Received message with schema ID: {}", received_schema_id);
                             println!("   First message verified successfully!");
@@ -170,7 +166,7 @@ async fn kafka_producer_test() -> Result<()> {
     });
     
     // Parse the second JSON data into an Ack
-    let ack2 = ThaleParser::parse_ack_json(&json_data2)?;
+    let ack2 = ThaleParser::parse_order_json(&json_data2)?;
     println!("10. Successfully parsed second JSON into Ack:");
     println!("    - Order ID: {}", ack2.order_id);
     println!("    - Direction: {:?}", ack2.direction);
@@ -238,10 +234,7 @@ async fn kafka_producer_test() -> Result<()> {
         
         // Decode the second message
         if let Some(payload) = last_message_data {
-            if payload.len() >= 5 && payload[0] == 0 {
-                let received_schema_id = i32::from_be_bytes([
-                    payload[1], payload[2], payload[3], payload[4]
-                ]);
+            if let Ok((received_schema_id, avro_data)) = split_confluent_payload(&payload) {
                 
                 println!("14. Second message uses schema ID: {}", received_schema_id);
                 
@@ -257,9 +250,7 @@ async fn kafka_producer_test() -> Result<()> {
                     // Parse the schema
                     let schema = Schema::parse_str(schema_str)?;
                     
-                    // Decode the Avro data
-                    let avro_data = &payload[5..];
-                    match Reader::with_schema(&schema, &avro_data[..]) {
+                    match Reader::with_schema(&schema, avro_data) {
                         Ok(mut reader) => {
                             if let Some(Ok(value)) = reader.next() {
                                 println!("15. Successfully decoded second message data:");
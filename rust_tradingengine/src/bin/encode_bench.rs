@@ -0,0 +1,85 @@
+//! Compare the per-record cost of encoding tickers in the Confluent wire format
+//!
+//! Usage: cargo run --release --bin encode_bench [RECORDS]
+//!
+//! "allocating" repeats the work the producer used to do for every record before encoders
+//! and buffers were reused: two schema clones, a cloned field vector, a fresh record and
+//! fresh payload buffers. The registry lookup the old encoder also made is left out, so the
+//! comparison understates the gain. "reused" goes through `encode_confluent_format` with a
+//! cached schema.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use anyhow::Result;
+use apache_avro::types::{Record, Value as AvroValue};
+use apache_avro::Schema;
+
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::infrastructure::kafka::helper::AvroConverter;
+use cryptics_lab_bot::infrastructure::kafka::{frame_confluent_payload, KafkaProducer};
+use cryptics_lab_bot::testing::fixtures;
+
+const TOPIC: &str = "bench.ticker";
+const SCHEMA_ID: i32 = 1;
+
+fn ticker(i: usize) -> Ticker {
+    let mark_price = fixtures::MARK + i as f64 * 0.5;
+    Ticker {
+        mark_price,
+        best_bid_price: mark_price - 1.0,
+        best_ask_price: mark_price + 1.0,
+        processing_timestamp: Some(fixtures::T0 + i as f64),
+        ..fixtures::ticker(fixtures::INSTRUMENT)
+    }
+}
+
+fn allocating_encode(schema: &Schema, fields: Vec<(String, AvroValue)>, event_id: i64) -> Result<Vec<u8>> {
+    // The cached schema was cloned once for validation and once for the event id
+    let validated = schema.clone();
+    let stamped = schema.clone();
+    let mut fields = fields;
+    if matches!(&stamped, Schema::Record(record) if record.lookup.contains_key("event_id")) {
+        fields.push(("event_id".to_string(), AvroValue::Union(1, Box::new(AvroValue::Long(event_id)))));
+    }
+    let data: Vec<(&str, AvroValue)> = fields.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+
+    let mut record = Record::new(&validated).expect("record schema");
+    for (name, value) in data {
+        record.put(name, value);
+    }
+    let datum = apache_avro::to_avro_datum(&validated, record)?;
+    Ok(frame_confluent_payload(SCHEMA_ID, &datum))
+}
+
+fn report(name: &str, records: usize, started: Instant, bytes: usize) {
+    let elapsed = started.elapsed();
+    println!("{:<12} {:>8} records  {:>9.1} ms  {:>7.0} ns/record  {:>9} bytes",
+        name, records, elapsed.as_secs_f64() * 1000.0, elapsed.as_nanos() as f64 / records as f64, bytes);
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let records: usize = std::env::args().nth(1).and_then(|arg| arg.parse().ok()).unwrap_or(200_000);
+    let schema = Schema::parse_str(&std::fs::read_to_string("../schemas/ticker/v3.avsc")?)?;
+    let producer = KafkaProducer::new("localhost:1", "http://localhost:1", HashMap::new(), "../schemas".to_string()).await?;
+    producer.cache_schema(TOPIC, SCHEMA_ID, schema.clone());
+    let tickers: Vec<Ticker> = (0..records.min(1000)).map(ticker).collect();
+
+    let started = Instant::now();
+    let mut bytes = 0;
+    for i in 0..records {
+        let fields = AvroConverter::ticker_to_avro_value(&tickers[i % tickers.len()])?;
+        bytes += allocating_encode(&schema, fields, i as i64)?.len();
+    }
+    report("allocating", records, started, bytes);
+
+    let started = Instant::now();
+    let mut bytes = 0;
+    for i in 0..records {
+        let fields = AvroConverter::ticker_to_avro_value(&tickers[i % tickers.len()])?;
+        bytes += producer.encode_confluent_format("ticker", fields, TOPIC).await?.len();
+    }
+    report("reused", records, started, bytes);
+    Ok(())
+}
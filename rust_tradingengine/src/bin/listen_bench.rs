@@ -0,0 +1,93 @@
+//! Measure messages/sec through the listen path, from raw frame to processing queue
+//!
+//! Usage: cargo run --release --bin listen_bench [MESSAGES]
+//!
+//! "value" parses each frame into a full `Value` first, as the listen loop used to, and
+//! "slice" reads the envelope in place with `InboundMessage::from_slice`; neither queues
+//! the message. "routed" goes through `ThalexQuoter::route`, which reads the envelope in place and
+//! queues the message for its priority. Both use a mix of ticker, order and RPC frames.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use cryptics_lab_bot::domain::enums::OrderSide;
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::strategies::thalex_market_maker::{InboundMessage, ThalexQuoter};
+use cryptics_lab_bot::testing::fixtures;
+
+fn frame(i: usize) -> String {
+    let price = fixtures::MARK + (i % 100) as f64 * 0.5;
+    match i % 10 {
+        0 => fixtures::orders_notification(&[fixtures::order(i as u64, OrderSide::Buy, price, 0.1)]),
+        1 => fixtures::rpc_result(i as u64, json!({"order_id": format!("00{}", i), "status": "open", "remaining_amount": 0.1})),
+        _ => fixtures::ticker_notification(&Ticker {
+            mark_price: price,
+            best_bid_price: price - 1.0,
+            best_ask_price: price + 1.0,
+            last_price: price,
+            ..fixtures::ticker(fixtures::INSTRUMENT)
+        }),
+    }.to_string()
+}
+
+fn report(name: &str, messages: usize, started: Instant) {
+    let elapsed = started.elapsed();
+    println!("{:<8} {:>8} messages  {:>9.1} ms  {:>10.0} messages/sec",
+        name, messages, elapsed.as_secs_f64() * 1000.0, messages as f64 / elapsed.as_secs_f64());
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let messages: usize = std::env::args().nth(1).and_then(|arg| arg.parse().ok()).unwrap_or(500_000);
+    let frames: Vec<String> = (0..messages.min(1000)).map(frame).collect();
+
+    let started = Instant::now();
+    let mut routed = 0;
+    for i in 0..messages {
+        let parsed: Value = serde_json::from_str(&frames[i % frames.len()])?;
+        if let Some(message) = InboundMessage::from_json(parsed) {
+            std::hint::black_box(message.priority());
+            routed += 1;
+        }
+    }
+    report("value", routed, started);
+
+    let started = Instant::now();
+    let mut routed = 0;
+    for i in 0..messages {
+        if let Some(message) = InboundMessage::from_slice(frames[i % frames.len()].as_bytes())? {
+            std::hint::black_box(message.priority());
+            routed += 1;
+        }
+    }
+    report("slice", routed, started);
+
+    let (private_tx, mut private_rx) = mpsc::channel::<InboundMessage>(1024);
+    let (market_tx, mut market_rx) = mpsc::channel::<InboundMessage>(1024);
+    let drain = tokio::spawn(async move {
+        let mut received = 0;
+        loop {
+            tokio::select! {
+                Some(_) = private_rx.recv() => received += 1,
+                Some(_) = market_rx.recv() => received += 1,
+                else => break received,
+            }
+        }
+    });
+
+    let started = Instant::now();
+    for i in 0..messages {
+        ThalexQuoter::route(&frames[i % frames.len()], &private_tx, &market_tx).await?;
+        if i % 512 == 0 {
+            // Let the consumer keep up so market data isn't dropped on a full queue
+            tokio::task::yield_now().await;
+        }
+    }
+    drop((private_tx, market_tx));
+    let received = drain.await?;
+    report("routed", received, started);
+    Ok(())
+}
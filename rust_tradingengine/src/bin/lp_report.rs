@@ -0,0 +1,211 @@
+//! Export daily liquidity-provision statistics from the persisted topics
+//!
+//! Usage: lp_report --from YYYY-MM-DD [--to YYYY-MM-DD] [--instrument NAME]
+//!                  [--max-distance-ticks N] [--variants] [--format csv|json] [--out FILE]
+//!
+//! Replays acks, tickers and trades from the database and writes one row per UTC day
+//! and instrument. Tick sizes are taken from the exchange's active instruments. With `--variants` the rows compare the variants of a parameter
+//! experiment instead, one per day, variant and instrument. The format follows the
+//! output file's extension unless given.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{NaiveDate, TimeZone, Utc};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tokio_postgres::{Client, NoTls};
+
+use cryptics_lab_bot::config_loader::AppConfig;
+use cryptics_lab_bot::domain::model::order::Order;
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::domain::model::trade::Trade;
+use cryptics_lab_bot::infrastructure::exchange::thalex::client::Network;
+use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
+use cryptics_lab_bot::infrastructure::exchange::thalex::rest::ThalexRestClient;
+use cryptics_lab_bot::reporting::{render, LpReportBuilder, ReportFormat, VariantReportBuilder};
+use cryptics_lab_bot::strategies::thalex_market_maker::UPTIME_MAX_DISTANCE_TICKS;
+
+struct Args {
+    from: NaiveDate,
+    to: NaiveDate,
+    instrument: Option<String>,
+    max_distance_ticks: f64,
+    variants: bool,
+    format: Option<ReportFormat>,
+    out: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = std::env::args().skip(1);
+    let (mut from, mut to, mut instrument, mut format, mut out) = (None, None, None, None, None);
+    let mut max_distance_ticks = UPTIME_MAX_DISTANCE_TICKS;
+    let mut variants = false;
+
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("Missing value for {}", flag));
+        match flag.as_str() {
+            "--from" => from = Some(value()?.parse::<NaiveDate>()?),
+            "--to" => to = Some(value()?.parse::<NaiveDate>()?),
+            "--instrument" => instrument = Some(value()?),
+            "--max-distance-ticks" => max_distance_ticks = value()?.parse()?,
+            "--variants" => variants = true,
+            "--format" => format = Some(value()?.parse()?),
+            "--out" => out = Some(PathBuf::from(value()?)),
+            other => return Err(anyhow!("Unknown argument: {}", other)),
+        }
+    }
+
+    let from = from.ok_or_else(|| anyhow!("--from is required"))?;
+    Ok(Args {
+        from,
+        to: to.unwrap_or(from),
+        instrument,
+        max_distance_ticks,
+        variants,
+        format,
+        out,
+    })
+}
+
+/// A persisted record, ordered for replay by its event time
+enum Event {
+    Ack(serde_json::Value),
+    Ticker(Ticker),
+    Trade(Trade),
+}
+
+async fn load_events(db: &Client, start: f64, end: f64, instrument: &Option<String>) -> Result<Vec<(f64, Event)>> {
+    let mut events = Vec::new();
+
+    let rows = db.query(
+        "SELECT instrument_name, mark_timestamp, mark_price, best_bid_price, best_ask_price FROM ticker_data \
+         WHERE mark_timestamp >= $1 AND mark_timestamp < $2 AND ($3::text IS NULL OR instrument_name = $3)",
+        &[&start, &end, instrument],
+    ).await.context("Failed to load tickers")?;
+    for row in rows {
+        let mut ticker = Ticker::new(row.get(0));
+        ticker.mark_timestamp = row.get(1);
+        ticker.mark_price = row.get(2);
+        ticker.best_bid_price = row.get(3);
+        ticker.best_ask_price = row.get(4);
+        events.push((ticker.mark_timestamp, Event::Ticker(ticker)));
+    }
+
+    let rows = db.query(
+        "SELECT order_id, instrument_name, direction, price, remaining_amount, status, \
+                COALESCE(processing_timestamp, create_time) AS event_time, create_time, variant_id FROM ack_data \
+         WHERE COALESCE(processing_timestamp, create_time) >= $1 AND COALESCE(processing_timestamp, create_time) < $2 \
+           AND ($3::text IS NULL OR instrument_name = $3)",
+        &[&start, &end, instrument],
+    ).await.context("Failed to load acks")?;
+    for row in rows {
+        let ack = json!({
+            "order_id": row.get::<_, String>(0),
+            "instrument_name": row.get::<_, String>(1),
+            "direction": row.get::<_, String>(2),
+            "price": row.get::<_, Option<f64>>(3),
+            "remaining_amount": row.get::<_, f64>(4),
+            "status": row.get::<_, String>(5),
+            "create_time": row.get::<_, f64>(7),
+            "variant_id": row.get::<_, Option<String>>(8),
+        });
+        events.push((row.get(6), Event::Ack(ack)));
+    }
+
+    let rows = db.query(
+        "SELECT trade_id, order_id, client_order_id, instrument_name, price, amount, maker_taker, time, variant_id FROM trade_data \
+         WHERE time >= $1 AND time < $2 AND ($3::text IS NULL OR instrument_name = $3)",
+        &[&start, &end, instrument],
+    ).await.context("Failed to load trades")?;
+    for row in rows {
+        let trade = Trade {
+            trade_id: row.get(0),
+            order_id: row.get(1),
+            client_order_id: row.get::<_, Option<i64>>(2).map(|id| id as u64),
+            instrument_name: row.get(3),
+            price: row.get(4),
+            amount: row.get(5),
+            maker_taker: row.get::<_, String>(6).parse().ok(),
+            time: row.get(7),
+            processing_timestamp: None,
+            variant_id: row.get(8),
+        };
+        events.push((trade.time, Event::Trade(trade)));
+    }
+
+    events.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Ok(events)
+}
+
+/// Ack row as an order, with the variant the exchange's format doesn't carry
+fn parse_ack(data: &serde_json::Value) -> Result<Order> {
+    let mut ack = ThaleParser::parse_order_json(data)?;
+    ack.variant_id = data["variant_id"].as_str().map(str::to_string);
+    Ok(ack)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = parse_args()?;
+    let config = AppConfig::from_file(Path::new("../config.toml"))
+        .or_else(|_| AppConfig::from_file(Path::new("./config.toml")))?;
+
+    let (db, connection) = tokio_postgres::connect(&config.database_url(), NoTls).await
+        .context("Failed to connect to the database")?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Database connection error: {}", e);
+        }
+    });
+
+    let start = Utc.from_utc_datetime(&args.from.and_hms_opt(0, 0, 0).unwrap()).timestamp() as f64;
+    let end = Utc.from_utc_datetime(&args.to.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap()).timestamp() as f64;
+
+    let events = load_events(&db, start, end, &args.instrument).await?;
+    let format = args.format
+        .or_else(|| args.out.as_deref().and_then(ReportFormat::from_path))
+        .unwrap_or(ReportFormat::Csv);
+    let (report, count) = if args.variants {
+        let mut builder = VariantReportBuilder::new();
+        for (_, event) in events {
+            match event {
+                Event::Ack(data) => match parse_ack(&data) {
+                    Ok(ack) => builder.on_ack(&ack),
+                    Err(e) => eprintln!("Skipping ack {}: {}", data["order_id"], e),
+                },
+                Event::Ticker(_) => {}
+                Event::Trade(trade) => builder.on_trade(&trade),
+            }
+        }
+        let rows = builder.finish();
+        (render(&rows, format)?, rows.len())
+    } else {
+        let mut builder = LpReportBuilder::new(args.max_distance_ticks);
+        let network: Network = config.app.network.parse()?;
+        let instruments = ThalexRestClient::for_network(&network, None).instruments().await
+            .context("Failed to load the instruments' tick sizes")?;
+        for instrument in &instruments {
+            builder.set_tick_size(&instrument.instrument_name, instrument.tick_size);
+        }
+        for (_, event) in events {
+            match event {
+                Event::Ack(data) => match parse_ack(&data) {
+                    Ok(ack) => builder.on_ack(&ack),
+                    Err(e) => eprintln!("Skipping ack {}: {}", data["order_id"], e),
+                },
+                Event::Ticker(ticker) => builder.on_ticker(&ticker),
+                Event::Trade(trade) => builder.on_trade(&trade),
+            }
+        }
+        let rows = builder.finish();
+        (render(&rows, format)?, rows.len())
+    };
+
+    match &args.out {
+        Some(path) => {
+            std::fs::write(path, report)?;
+            eprintln!("Wrote {} rows to {}", count, path.display());
+        }
+        None => print!("{}", report),
+    }
+    Ok(())
+}
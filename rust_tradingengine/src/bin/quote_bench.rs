@@ -0,0 +1,83 @@
+//! Compare the per-cycle cost of laying out the quote ladder
+//!
+//! Usage: cargo run --release --bin quote_bench [CYCLES]
+//!
+//! "build" lays out every level from the ladder shape on each cycle, as `make_quotes`
+//! used to. "template" prices a cached `QuoteTemplate`, which skips the work entirely
+//! while the center doesn't move, and copies the quotes out as `make_quotes` does.
+//! "priced" is the template's pricing pass alone, without the copy. The center moves on
+//! every fourth cycle, roughly the share of quote cycles woken by a changed index rather
+//! than by acks or fills. "scaled" is "template" with the size multiplier changing every
+//! cycle, as equity/volatility sizing does.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use anyhow::Result;
+
+use cryptics_lab_bot::config_loader::LadderShape;
+use cryptics_lab_bot::strategies::thalex_market_maker::LadderBuilder;
+use cryptics_lab_bot::testing::fixtures;
+
+const SPREAD: f64 = 25.0;
+const TICK: f64 = 1.0;
+const MOVE_EVERY: usize = 4;
+const VERSION: u64 = 0;
+
+fn center(i: usize) -> f64 {
+    fixtures::MARK + ((i / MOVE_EVERY) % 100) as f64 * 0.5
+}
+
+fn report(name: &str, cycles: usize, started: Instant, levels: usize) {
+    let elapsed = started.elapsed();
+    println!("{:<10} {:>9} cycles  {:>9.1} ms  {:>7.0} ns/cycle  {:>9} levels",
+        name, cycles, elapsed.as_secs_f64() * 1000.0, elapsed.as_nanos() as f64 / cycles as f64, levels);
+}
+
+fn main() -> Result<()> {
+    let cycles: usize = std::env::args().nth(1).and_then(|arg| arg.parse().ok()).unwrap_or(1_000_000);
+    let sizes = [0.2, 0.4, 0.8, 1.0, 1.0, 1.0];
+    let ladder = LadderBuilder::new(&LadderShape::Geometric { step: 5.0, ratio: 1.5 }, &sizes, &sizes)?;
+
+    let started = Instant::now();
+    let mut levels = 0;
+    for i in 0..cycles {
+        let quotes = black_box(ladder.build(center(i), SPREAD, TICK, 1.0));
+        levels += quotes.iter().map(Vec::len).sum::<usize>();
+    }
+    report("build", cycles, started, levels);
+
+    let started = Instant::now();
+    let mut levels = 0;
+    let mut template = ladder.template(SPREAD, TICK, VERSION);
+    for i in 0..cycles {
+        if !template.matches(VERSION, SPREAD, TICK) {
+            template = ladder.template(SPREAD, TICK, VERSION);
+        }
+        let quotes = black_box(template.quotes(center(i), 1.0));
+        levels += quotes.iter().map(Vec::len).sum::<usize>();
+    }
+    report("template", cycles, started, levels);
+
+    let started = Instant::now();
+    let mut levels = 0;
+    let mut template = ladder.template(SPREAD, TICK, VERSION);
+    for i in 0..cycles {
+        let quotes = black_box(template.priced(center(i), 1.0));
+        levels += quotes.iter().map(Vec::len).sum::<usize>();
+    }
+    report("priced", cycles, started, levels);
+
+    let started = Instant::now();
+    let mut levels = 0;
+    let mut template = ladder.template(SPREAD, TICK, VERSION);
+    for i in 0..cycles {
+        if !template.matches(VERSION, SPREAD, TICK) {
+            template = ladder.template(SPREAD, TICK, VERSION);
+        }
+        let quotes = black_box(template.quotes(center(i), 1.0 + (i % 7) as f64 * 0.01));
+        levels += quotes.iter().map(Vec::len).sum::<usize>();
+    }
+    report("scaled", cycles, started, levels);
+    Ok(())
+}
@@ -1,15 +1,118 @@
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
 use log::{debug, info};
 use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
+use crate::domain::enums::OptionType;
+use crate::domain::model::exchange::Instrument;
+use crate::infrastructure::exchange::symbology::Venue;
+use crate::infrastructure::exchange::thalex::channel::{Channel, DEFAULT_DELAY, DEFAULT_GROUPING};
+
+/// Environment variable that overrides the profile selected in the config file
+pub const PROFILE_ENV_VAR: &str = "CRYPTICS_PROFILE";
+
+/// Prefix of environment variables read by `AppConfig::from_env`; `__` separates nesting levels
+pub const CONFIG_ENV_PREFIX: &str = "CRYPTICS__";
+
+/// Supported config file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Detect the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "toml" => Some(ConfigFormat::Toml),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            "json" => Some(ConfigFormat::Json),
+            _ => None,
+        }
+    }
+    
+    /// Parse a document in this format
+    pub fn parse(&self, content: &str) -> Result<Value> {
+        match self {
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| anyhow!("Invalid TOML: {}", e)),
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| anyhow!("Invalid YAML: {}", e)),
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|e| anyhow!("Invalid JSON: {}", e)),
+        }
+    }
+}
+
 /// Top-level configuration structure containing all config sections
 #[derive(Debug, Clone, Deserialize)]
 pub struct AppConfig {
     pub kafka: KafkaConfig,
     pub topics: TopicsConfig,
     pub app: AppInfo,
+    
+    #[serde(default)]
+    pub thalex: ThalexConfig,
+    
+    #[serde(default)]
+    pub reconnect: ReconnectConfig,
+    
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    
+    #[serde(default)]
+    pub experiment: ExperimentConfig,
+    
+    #[serde(default)]
+    pub fees: FeeConfig,
+    
+    #[serde(default)]
+    pub risk: RiskConfig,
+    
+    #[serde(default)]
+    pub chaos: ChaosConfig,
+    
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    
+    #[serde(default)]
+    pub lease: LeaseConfig,
+    
+    #[serde(default)]
+    pub stats: StatsConfig,
+    
+    #[serde(default)]
+    pub inflight: InflightConfig,
+    
+    #[serde(default)]
+    pub bot_state: BotStateConfig,
+    
+    #[serde(default)]
+    pub trade_backfill: TradeBackfillConfig,
+    
+    #[serde(default)]
+    pub eod_export: EodExportConfig,
+    
+    #[serde(default)]
+    pub fair_value: FairValueConfig,
+    
+    #[serde(default)]
+    pub control: ControlConfig,
+    
+    #[serde(default)]
+    pub spread: SpreadConfig,
+    
+    #[serde(default)]
+    pub runtime: RuntimeConfig,
+    
+    #[serde(default)]
+    pub symbology: SymbologyConfig,
+    
+    #[serde(default)]
+    pub strategy: StrategyConfig,
     // Add more sections as needed
 }
 
@@ -38,6 +141,168 @@ pub struct KafkaConfig {
     
     #[serde(default = "default_skip_local_schemas")]
     pub skip_local_schemas: bool,
+    
+    /// Directory holding one sub-directory of versioned schemas per schema name
+    #[serde(default = "default_schema_dir")]
+    pub schema_dir: String,
+    
+    /// Retries and circuit breaker for schema registry calls
+    #[serde(default)]
+    pub registry_retry: RegistryRetryConfig,
+    
+    /// Stall detection and recovery of the publishing pipeline
+    #[serde(default)]
+    pub watchdog: ProducerWatchdogConfig,
+    
+    /// Buffering and retry of records Kafka didn't take
+    #[serde(default)]
+    pub spill: SpillConfig,
+    
+    /// JSON copies of Avro records for tools without schema registry support
+    #[serde(default)]
+    pub json_mirror: JsonMirrorConfig,
+    
+    /// Checking the broker's topic settings against the configured retention and compaction
+    #[serde(default)]
+    pub topic_admin: TopicAdminConfig,
+}
+
+/// Reconciliation of the broker's topic settings with the configured ones
+///
+/// Topics with a retention or compaction expectation are created with it when missing.
+/// Existing ones whose settings differ are reported, and altered when `alter` is set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TopicAdminConfig {
+    pub enabled: bool,
+    
+    /// Change drifted settings on the broker instead of only reporting them
+    pub alter: bool,
+}
+
+impl Default for TopicAdminConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            alter: false,
+        }
+    }
+}
+
+/// Mirroring of Avro topics to JSON topics
+///
+/// Each mirrored record is also sent JSON-encoded, with the same key and fields, to the
+/// topic named like the Avro one with `.avro` replaced by `.json`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct JsonMirrorConfig {
+    pub enabled: bool,
+    
+    /// Topic types to mirror; every Avro topic when empty
+    pub topic_types: Vec<String>,
+}
+
+/// Watchdog restarting the producer client when deliveries stall
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ProducerWatchdogConfig {
+    pub enabled: bool,
+    
+    /// How often the pipeline is checked (milliseconds)
+    pub check_interval_ms: u64,
+    
+    /// How long records may wait without any delivery before the pipeline counts as stalled (milliseconds)
+    pub stall_after_ms: u64,
+    
+    /// Write records to the journal instead of Kafka when a restart doesn't clear the stall
+    pub journal_fallback: bool,
+    
+    /// Journal of records that couldn't go to Kafka, one JSON line per record
+    pub journal_path: String,
+}
+
+impl Default for ProducerWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_ms: 1000,
+            stall_after_ms: 15_000,
+            journal_fallback: false,
+            journal_path: "state/kafka_journal.jsonl".to_string(),
+        }
+    }
+}
+
+/// Buffering of records whose delivery failed, so a broker outage delays them instead of losing them
+///
+/// Failed records are kept in memory and retried oldest first, with new records queued
+/// behind them. A record that failed `max_attempts` times, or the oldest one when more
+/// than `max_records` wait, goes to the dead-letter file, as do those still waiting
+/// when the session ends.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SpillConfig {
+    pub enabled: bool,
+    
+    /// Records kept for a retry before the oldest is dead-lettered
+    pub max_records: usize,
+    
+    /// Failed deliveries after which a record is dead-lettered
+    pub max_attempts: u32,
+    
+    /// How often spilled records are retried (milliseconds)
+    pub retry_interval_ms: u64,
+    
+    /// File records given up on are appended to, one JSON line per record
+    pub dead_letter_path: String,
+}
+
+impl Default for SpillConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_records: 100_000,
+            max_attempts: 30,
+            retry_interval_ms: 1000,
+            dead_letter_path: "state/kafka_dead_letter.jsonl".to_string(),
+        }
+    }
+}
+
+/// Retry and circuit breaker settings for schema registry calls
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RegistryRetryConfig {
+    /// Retries after the first attempt of a call
+    pub max_retries: u32,
+    
+    /// Delay before the first retry (milliseconds); doubles per retry, with jitter
+    pub initial_backoff_ms: u64,
+    
+    /// Upper bound on the delay between retries (milliseconds)
+    pub max_backoff_ms: u64,
+    
+    /// Consecutive failed attempts that open the circuit
+    pub failure_threshold: u32,
+    
+    /// How long an open circuit refuses calls before letting a trial through (milliseconds)
+    pub open_cooldown_ms: u64,
+    
+    /// Time allowed for one registry request, response included (milliseconds)
+    pub request_timeout_ms: u64,
+}
+
+impl Default for RegistryRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 2000,
+            failure_threshold: 5,
+            open_cooldown_ms: 30_000,
+            request_timeout_ms: 5000,
+        }
+    }
 }
 
 fn default_ack_topic() -> String {
@@ -60,6 +325,10 @@ fn default_skip_local_schemas() -> bool {
     false
 }
 
+fn default_schema_dir() -> String {
+    "../schemas".to_string()
+}
+
 /// Kafka topics configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct TopicsConfig {
@@ -68,58 +337,1749 @@ pub struct TopicsConfig {
     pub trade: String,
     pub index: String,
     pub base_name: String,
+    
+    #[serde(default = "default_features_topic")]
+    pub features: String,
+    
+    #[serde(default = "default_uptime_topic")]
+    pub uptime: String,
+    #[serde(default = "default_lifecycle_topic")]
+    pub lifecycle: String,
+    #[serde(default = "default_heartbeat_topic")]
+    pub heartbeat: String,
+    #[serde(default = "default_book_topic")]
+    pub book: String,
+    #[serde(default = "default_ticker_latest_topic")]
+    pub ticker_latest: String,
+    #[serde(default = "default_ticker_delta_topic")]
+    pub ticker_delta: String,
+    #[serde(default = "default_session_summary_topic")]
+    pub session_summary: String,
+    #[serde(default = "default_funding_basis_topic")]
+    pub funding_basis: String,
+    #[serde(default = "default_account_event_topic")]
+    pub account_event: String,
+    #[serde(default = "default_trade_correction_topic")]
+    pub trade_correction: String,
+    #[serde(default = "default_account_summary_topic")]
+    pub account_summary: String,
+    
+    /// Publish only changed ticker fields to `ticker_delta`, with periodic full snapshots,
+    /// instead of every full ticker to `ticker`
+    #[serde(default)]
+    pub ticker_delta_mode: bool,
+    
+    /// Raw JSON of notifications on channels without typed support; not schema-registered
+    #[serde(default = "default_unknown_channel_topic")]
+    pub unknown_channel: String,
+    
+    /// Additional topic types, or overrides of the ones above, keyed by topic type
+    #[serde(default)]
+    pub types: BTreeMap<String, TopicType>,
+    
+    /// Retention expected of topic types, keyed by topic type
+    #[serde(default)]
+    pub retention: BTreeMap<String, TopicRetention>,
 }
 
-/// Application information
-#[derive(Debug, Clone, Deserialize)]
-pub struct AppInfo {
-    pub rust_running_in_docker: bool,
-    // Add more app settings as needed
+/// How long a topic keeps its records; the broker's defaults apply to what's unset
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TopicRetention {
+    /// Longest time records are kept (milliseconds)
+    pub retention_ms: Option<i64>,
+    
+    /// Largest size kept per partition (bytes)
+    pub retention_bytes: Option<i64>,
 }
 
-impl AppConfig {
-    /// Load configuration from a TOML file
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref();
-        
-        // Read the file
-        let config_str = fs::read_to_string(path)
-            .map_err(|e| anyhow!("Failed to read config file '{}': {}", path.display(), e))?;
-        
-        // Parse the TOML
-        let config: AppConfig = toml::from_str(&config_str)
-            .map_err(|e| anyhow!("Failed to parse config file '{}': {}", path.display(), e))?;
-        
-        info!("Loaded configuration from {}", path.display());
-        debug!("Running in Docker: {}", config.app.rust_running_in_docker);
-        
-        Ok(config)
+impl TopicRetention {
+    pub fn is_set(&self) -> bool {
+        self.retention_ms.is_some() || self.retention_bytes.is_some()
     }
+}
+
+/// How records of one topic type are published
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TopicType {
+    pub topic: String,
     
-    /// Helper to get the appropriate Kafka bootstrap servers URL based on Docker status
-    pub fn kafka_bootstrap_servers(&self) -> &str {
-        if self.app.rust_running_in_docker {
-            &self.kafka.bootstrap_servers_internal
-        } else {
-            &self.kafka.bootstrap_servers
+    /// Schema directory name; defaults to the topic type
+    #[serde(default)]
+    pub schema: Option<String>,
+    
+    /// Registry subject; defaults to `<topic>-value`
+    #[serde(default)]
+    pub subject: Option<String>,
+    
+    /// Keep only the latest record per key
+    #[serde(default)]
+    pub compacted: bool,
+    
+    /// How long the topic keeps its records
+    #[serde(default)]
+    pub retention: TopicRetention,
+}
+
+impl TopicType {
+    pub fn new(topic: &str) -> Self {
+        Self {
+            topic: topic.to_string(),
+            schema: None,
+            subject: None,
+            compacted: false,
+            retention: TopicRetention::default(),
         }
     }
     
-    /// Helper to get the appropriate Schema Registry URL based on Docker status
-    pub fn kafka_schema_registry_url(&self) -> &str {
-        if self.app.rust_running_in_docker {
-            &self.kafka.schema_registry_url_internal
-        } else {
-            &self.kafka.schema_registry_url
+    /// Settings of a built-in topic type published to `topic`
+    ///
+    /// `ticker_latest` is a compacted view of the ticker stream and reuses its schema.
+    pub fn builtin(topic_type: &str, topic: &str) -> Self {
+        match topic_type {
+            "ticker_latest" => Self {
+                schema: Some("ticker".to_string()),
+                compacted: true,
+                ..Self::new(topic)
+            },
+            _ => Self::new(topic),
         }
     }
     
-    /// Helper to get the appropriate Kafka Connect URL based on Docker status
-    pub fn kafka_connect_url(&self) -> &str {
-        if self.app.rust_running_in_docker {
-            &self.kafka.connect_url_internal
-        } else {
-            &self.kafka.connect_url
+    /// Schema directory name for this topic type
+    pub fn schema_name<'a>(&'a self, topic_type: &'a str) -> &'a str {
+        self.schema.as_deref().unwrap_or(topic_type)
+    }
+    
+    /// Registry subject holding this topic's value schemas
+    pub fn subject(&self) -> String {
+        self.subject.clone().unwrap_or_else(|| format!("{}-value", self.topic))
+    }
+}
+
+impl TopicsConfig {
+    /// Every topic type to publish, keyed by topic type
+    pub fn topic_types(&self) -> HashMap<String, TopicType> {
+        let builtin = [
+            ("ticker", &self.ticker),
+            ("ack", &self.ack),
+            ("trade", &self.trade),
+            ("index", &self.index),
+            ("features", &self.features),
+            ("uptime", &self.uptime),
+            ("lifecycle", &self.lifecycle),
+            ("heartbeat", &self.heartbeat),
+            ("book", &self.book),
+            ("ticker_latest", &self.ticker_latest),
+            ("ticker_delta", &self.ticker_delta),
+            ("session_summary", &self.session_summary),
+            ("funding_basis", &self.funding_basis),
+            ("account_event", &self.account_event),
+            ("trade_correction", &self.trade_correction),
+            ("account_summary", &self.account_summary),
+        ];
+        let mut types: HashMap<String, TopicType> = builtin.into_iter()
+            .map(|(topic_type, topic)| (topic_type.to_string(), TopicType::builtin(topic_type, topic)))
+            .collect();
+        types.extend(self.types.iter().map(|(topic_type, spec)| (topic_type.clone(), spec.clone())));
+        for (topic_type, retention) in &self.retention {
+            if let Some(spec) = types.get_mut(topic_type) {
+                spec.retention = retention.clone();
+            }
+        }
+        types
+    }
+}
+
+fn default_features_topic() -> String {
+    "cryptics.thalex.features.avro".to_string()
+}
+
+fn default_uptime_topic() -> String {
+    "cryptics.thalex.uptime.avro".to_string()
+}
+
+fn default_lifecycle_topic() -> String {
+    "cryptics.thalex.lifecycle.avro".to_string()
+}
+
+fn default_heartbeat_topic() -> String {
+    "cryptics.thalex.heartbeat.avro".to_string()
+}
+
+fn default_ticker_delta_topic() -> String {
+    "cryptics.thalex.ticker_delta.avro".to_string()
+}
+
+fn default_session_summary_topic() -> String {
+    "cryptics.thalex.session_summary.avro".to_string()
+}
+
+fn default_funding_basis_topic() -> String {
+    "cryptics.thalex.funding_basis.avro".to_string()
+}
+
+fn default_account_event_topic() -> String {
+    "cryptics.thalex.account_event.avro".to_string()
+}
+
+fn default_trade_correction_topic() -> String {
+    "cryptics.thalex.trade_correction.avro".to_string()
+}
+
+fn default_account_summary_topic() -> String {
+    "cryptics.thalex.account_summary.avro".to_string()
+}
+
+fn default_book_topic() -> String {
+    "cryptics.thalex.book.avro".to_string()
+}
+
+fn default_ticker_latest_topic() -> String {
+    "cryptics.thalex.ticker_latest.avro".to_string()
+}
+
+fn default_unknown_channel_topic() -> String {
+    "cryptics.thalex.unknown_channel.json".to_string()
+}
+
+/// Thalex session settings
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThalexConfig {
+    /// Whether to ask the exchange to cancel our orders when the session drops
+    #[serde(default = "default_cancel_on_disconnect")]
+    pub cancel_on_disconnect: bool,
+    
+    /// Seconds without a message before the exchange cancels the session's orders
+    #[serde(default = "default_cancel_on_disconnect_timeout_sec")]
+    pub cancel_on_disconnect_timeout_sec: u64,
+    
+    /// Deadline for each request and synchronous receive (milliseconds)
+    #[serde(default = "default_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+    
+    /// Reject trades with a missing or unknown maker/taker role instead of recording them without one
+    #[serde(default)]
+    pub strict_parsing: bool,
+    
+    /// Order book subscriptions
+    #[serde(default)]
+    pub books: Vec<BookConfig>,
+    
+    /// Option instruments whose tickers are subscribed and that orders can be placed on
+    #[serde(default)]
+    pub options: Option<OptionsConfig>,
+    
+    /// Budgets of the requests sent to the exchange
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+}
+
+/// Token-bucket budgets of outgoing requests, so bursts stay under the exchange's rate limit
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    
+    /// Requests per second across all methods; 0 for no global budget
+    pub per_sec: f64,
+    
+    /// Requests that may go out at once after a quiet period
+    pub burst: f64,
+    
+    /// Budgets of single RPC methods, keyed by method name such as `private/amend`
+    pub methods: BTreeMap<String, RequestBudget>,
+    
+    /// What happens to amends and inserts beyond the budget
+    pub overflow: RateLimitOverflow,
+}
+
+/// Budget of one RPC method
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RequestBudget {
+    pub per_sec: f64,
+    pub burst: f64,
+}
+
+/// Handling of amends and inserts beyond the request budget; other requests always wait
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitOverflow {
+    /// Hold them in the outbound queue until the budget allows, or they go stale
+    #[default]
+    Queue,
+    /// Drop them; the next quote cycle sends fresh ones
+    Drop,
+}
+
+impl Default for ThalexConfig {
+    fn default() -> Self {
+        Self {
+            cancel_on_disconnect: default_cancel_on_disconnect(),
+            cancel_on_disconnect_timeout_sec: default_cancel_on_disconnect_timeout_sec(),
+            request_timeout_ms: default_request_timeout_ms(),
+            strict_parsing: false,
+            books: Vec::new(),
+            options: None,
+            rate_limit: RateLimitConfig::default(),
+        }
+    }
+}
+
+impl ThalexConfig {
+    /// Cancel-on-disconnect timeout, or None when disabled
+    pub fn cancel_on_disconnect_timeout(&self) -> Option<u64> {
+        self.cancel_on_disconnect.then_some(self.cancel_on_disconnect_timeout_sec)
+    }
+    
+    /// Validated book channels for the configured subscriptions
+    pub fn book_channels(&self) -> Result<Vec<Channel>> {
+        self.books.iter().map(BookConfig::channel).collect()
+    }
+}
+
+/// Order book subscription for one instrument
+#[derive(Debug, Clone, Deserialize)]
+pub struct BookConfig {
+    pub instrument: String,
+    
+    /// Number of price levels
+    pub depth: u32,
+    
+    /// Price grouping ("none" or a price step)
+    #[serde(default = "default_book_grouping")]
+    pub grouping: String,
+    
+    /// Update delay ("raw", "100ms", ...)
+    #[serde(default = "default_book_delay")]
+    pub delay: String,
+}
+
+impl BookConfig {
+    /// Book channel for this subscription, validated against what the exchange supports
+    pub fn channel(&self) -> Result<Channel> {
+        Channel::book_with(&self.instrument, &self.grouping, self.depth, &self.delay)
+            .map_err(|e| anyhow!("Invalid book subscription for {}: {}", self.instrument, e))
+    }
+}
+
+/// Which listed options to select, by underlying, time to expiry, strike and type
+#[derive(Debug, Clone, Deserialize)]
+pub struct OptionsConfig {
+    #[serde(default = "default_options_underlying")]
+    pub underlying: String,
+    
+    /// Expiries within this window (days from now) are selected
+    #[serde(default)]
+    pub min_days_to_expiry: f64,
+    #[serde(default = "default_options_max_days_to_expiry")]
+    pub max_days_to_expiry: f64,
+    
+    #[serde(default)]
+    pub min_strike: Option<f64>,
+    #[serde(default)]
+    pub max_strike: Option<f64>,
+    
+    /// Calls or puts only; both when unset
+    #[serde(default)]
+    pub option_type: Option<OptionType>,
+    
+    /// At most this many options, nearest expiry first
+    #[serde(default = "default_options_max_instruments")]
+    pub max_instruments: usize,
+}
+
+impl OptionsConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.min_days_to_expiry > self.max_days_to_expiry {
+            return Err(anyhow!("options.min_days_to_expiry must not exceed max_days_to_expiry"));
+        }
+        if let (Some(min), Some(max)) = (self.min_strike, self.max_strike) {
+            if min > max {
+                return Err(anyhow!("options.min_strike must not exceed max_strike"));
+            }
+        }
+        Ok(())
+    }
+    
+    /// Options among `instruments` matching the selection at `now` (seconds), by expiry then strike
+    pub fn select(&self, instruments: &[Instrument], now: f64) -> Vec<Instrument> {
+        let mut selected: Vec<Instrument> = instruments.iter()
+            .filter(|instrument| instrument.is_option() && instrument.underlying == self.underlying)
+            .filter(|instrument| instrument.days_to_expiry(now)
+                .is_some_and(|days| days > 0.0 && days >= self.min_days_to_expiry && days <= self.max_days_to_expiry))
+            .filter(|instrument| instrument.strike_price.is_some_and(|strike| {
+                self.min_strike.is_none_or(|min| strike >= min) && self.max_strike.is_none_or(|max| strike <= max)
+            }))
+            .filter(|instrument| self.option_type.is_none_or(|option_type| instrument.option_type == Some(option_type)))
+            .cloned()
+            .collect();
+        selected.sort_by(|a, b| {
+            a.expiration_timestamp.unwrap_or_default().total_cmp(&b.expiration_timestamp.unwrap_or_default())
+                .then(a.strike_price.unwrap_or_default().total_cmp(&b.strike_price.unwrap_or_default()))
+                .then(a.instrument_name.cmp(&b.instrument_name))
+        });
+        selected.truncate(self.max_instruments);
+        selected
+    }
+}
+
+fn default_options_underlying() -> String {
+    "BTCUSD".to_string()
+}
+
+fn default_options_max_days_to_expiry() -> f64 {
+    30.0
+}
+
+fn default_options_max_instruments() -> usize {
+    20
+}
+
+fn default_book_grouping() -> String {
+    DEFAULT_GROUPING.to_string()
+}
+
+fn default_book_delay() -> String {
+    DEFAULT_DELAY.to_string()
+}
+
+fn default_cancel_on_disconnect() -> bool {
+    true
+}
+
+fn default_cancel_on_disconnect_timeout_sec() -> u64 {
+    6
+}
+
+fn default_request_timeout_ms() -> u64 {
+    5000
+}
+
+/// Reconnection backoff policy
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ReconnectConfig {
+    /// Delay before the first reconnect attempt (milliseconds)
+    pub initial_delay_ms: u64,
+    
+    /// Upper bound on the delay between attempts (milliseconds)
+    pub max_delay_ms: u64,
+    
+    /// Factor the delay grows by after each consecutive failure
+    pub multiplier: f64,
+    
+    /// Consecutive attempts before giving up; 0 retries forever
+    pub max_attempts: u32,
+    
+    /// Sessions lasting at least this long reset the attempt count (seconds)
+    pub reset_after_sec: u64,
+    
+    /// Window over which reconnects are counted for churn alerts (seconds)
+    pub churn_window_sec: u64,
+    
+    /// Reconnects within the window that raise an alert
+    pub churn_alert_threshold: usize,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay_ms: 1000,
+            max_delay_ms: 60_000,
+            multiplier: 2.0,
+            max_attempts: 0,
+            reset_after_sec: 60,
+            churn_window_sec: 600,
+            churn_alert_threshold: 5,
         }
     }
 }
+
+/// Scheduled quoting parameter overrides
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScheduleConfig {
+    /// Daily windows, in UTC time of day
+    #[serde(default)]
+    pub windows: Vec<ScheduleWindow>,
+    
+    /// One-off events such as economic releases
+    #[serde(default)]
+    pub events: Vec<ScheduledEvent>,
+    
+    /// Override applied around every funding settlement of the perpetual
+    #[serde(default)]
+    pub funding: Option<FundingWindowConfig>,
+}
+
+impl ScheduleConfig {
+    /// Seconds between funding settlements, also when no override is applied around them
+    pub fn funding_interval_sec(&self) -> u64 {
+        self.funding.as_ref().map_or_else(default_funding_interval_sec, |funding| funding.interval_sec)
+    }
+
+    /// Parse the windows' times of day and the events' timestamps, and check the funding window
+    pub fn parse(&self) -> Result<Schedule> {
+        let parse_time = |s: &str| {
+            NaiveTime::parse_from_str(s, "%H:%M").map_err(|e| anyhow!("Invalid time of day '{}': {}", s, e))
+        };
+
+        let windows = self.windows.iter()
+            .map(|w| Ok(DailyWindow {
+                name: w.name.clone(),
+                start: parse_time(&w.start)?,
+                end: parse_time(&w.end)?,
+                params: w.params.clone(),
+            }))
+            .collect::<Result<Vec<_>>>()?;
+
+        let events = self.events.iter()
+            .map(|e| {
+                let at = DateTime::parse_from_rfc3339(&e.at)
+                    .map_err(|err| anyhow!("Invalid event time '{}' for {}: {}", e.at, e.name, err))?
+                    .with_timezone(&Utc);
+                Ok(EventWindow {
+                    name: e.name.clone(),
+                    from: at - Duration::seconds(e.before_sec as i64),
+                    until: at + Duration::seconds(e.after_sec as i64),
+                    params: e.params.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let funding = self.funding.as_ref().map(FundingWindow::from_config).transpose()?;
+
+        Ok(Schedule { windows, funding, events })
+    }
+}
+
+/// Schedule with its times parsed, as evaluated while quoting
+#[derive(Debug, Clone, Default)]
+pub struct Schedule {
+    windows: Vec<DailyWindow>,
+    funding: Option<FundingWindow>,
+    events: Vec<EventWindow>,
+}
+
+impl Schedule {
+    /// Overrides active at `now` with their names, daily windows in config order, then
+    /// the funding window, then events
+    pub fn active_at(&self, now: DateTime<Utc>) -> Vec<(&str, &ParamOverride)> {
+        let time = now.time();
+        let windows = self.windows.iter()
+            .filter(|w| w.contains(time))
+            .map(|w| (w.name.as_str(), &w.params));
+        let funding = self.funding.iter()
+            .filter(|f| f.contains(now))
+            .map(|f| ("funding", &f.params));
+        let events = self.events.iter()
+            .filter(|e| e.from <= now && now <= e.until)
+            .map(|e| (e.name.as_str(), &e.params));
+        windows.chain(funding).chain(events).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DailyWindow {
+    name: String,
+    start: NaiveTime,
+    end: NaiveTime,
+    params: ParamOverride,
+}
+
+impl DailyWindow {
+    fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            // Wraps midnight
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EventWindow {
+    name: String,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+    params: ParamOverride,
+}
+
+/// Window around the recurring funding settlements
+#[derive(Debug, Clone)]
+struct FundingWindow {
+    interval: i64,
+    offset: i64,
+    before: i64,
+    after: i64,
+    params: ParamOverride,
+}
+
+impl FundingWindow {
+    fn from_config(funding: &FundingWindowConfig) -> Result<Self> {
+        if funding.interval_sec == 0 {
+            return Err(anyhow!("Funding interval must be positive"));
+        }
+        if funding.before_sec + funding.after_sec >= funding.interval_sec {
+            return Err(anyhow!("Funding window of {}s before and {}s after covers the whole {}s interval",
+                funding.before_sec, funding.after_sec, funding.interval_sec));
+        }
+        Ok(Self {
+            interval: funding.interval_sec as i64,
+            offset: funding.offset_sec as i64,
+            before: funding.before_sec as i64,
+            after: funding.after_sec as i64,
+            params: funding.params.clone(),
+        })
+    }
+
+    fn contains(&self, now: DateTime<Utc>) -> bool {
+        // Seconds since the most recent settlement
+        let since = (now.timestamp() - self.offset).rem_euclid(self.interval);
+        since < self.after || since >= self.interval - self.before
+    }
+}
+
+/// Override applied in a window around each funding settlement
+///
+/// Settlements happen every `interval_sec`, starting `offset_sec` after midnight UTC.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FundingWindowConfig {
+    #[serde(default = "default_funding_interval_sec")]
+    pub interval_sec: u64,
+    #[serde(default)]
+    pub offset_sec: u64,
+    #[serde(default)]
+    pub before_sec: u64,
+    #[serde(default)]
+    pub after_sec: u64,
+    #[serde(flatten)]
+    pub params: ParamOverride,
+}
+
+fn default_funding_interval_sec() -> u64 {
+    8 * 3600
+}
+
+/// Override applied every day between `start` and `end` ("HH:MM" UTC; may wrap midnight)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleWindow {
+    pub name: String,
+    pub start: String,
+    pub end: String,
+    #[serde(flatten)]
+    pub params: ParamOverride,
+}
+
+/// Override applied around an RFC 3339 timestamp
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduledEvent {
+    pub name: String,
+    pub at: String,
+    #[serde(default)]
+    pub before_sec: u64,
+    #[serde(default)]
+    pub after_sec: u64,
+    #[serde(flatten)]
+    pub params: ParamOverride,
+}
+
+/// Quoting parameters replaced while an override is active; unset fields keep their value
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct ParamOverride {
+    /// Distance of the first level from the index, in ticks
+    pub spread: Option<f64>,
+    
+    /// Shift applied to both sides, in ticks (positive raises bids and asks)
+    pub skew: Option<f64>,
+    
+    /// Factor applied to every quote size
+    pub size_multiplier: Option<f64>,
+    
+    /// Pull all quotes while the override is active
+    pub pause: Option<bool>,
+}
+
+/// Live A/B test of quoting parameters: each variant overrides the scheduled parameters
+/// while it is assigned, and the orders and fills placed under it carry its id
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ExperimentConfig {
+    pub enabled: bool,
+    
+    /// Logged with every variant change
+    pub name: String,
+    
+    pub assignment: ExperimentAssignment,
+    
+    /// Length of each slice when variants alternate over time
+    pub slice_sec: u64,
+    
+    pub variants: Vec<VariantConfig>,
+}
+
+impl Default for ExperimentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            name: String::new(),
+            assignment: ExperimentAssignment::default(),
+            slice_sec: 3600,
+            variants: Vec::new(),
+        }
+    }
+}
+
+/// How experiment variants are assigned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentAssignment {
+    /// Variants take turns, one slice each, in config order
+    #[default]
+    TimeSlice,
+    /// Each variant quotes the instruments it lists
+    Instrument,
+}
+
+/// One arm of an experiment
+#[derive(Debug, Clone, Deserialize)]
+pub struct VariantConfig {
+    /// Tag written on the variant's orders and fills
+    pub id: String,
+    
+    /// Instruments quoted with this variant, for instrument assignment
+    #[serde(default)]
+    pub instruments: Vec<String>,
+    
+    #[serde(flatten)]
+    pub params: ParamOverride,
+}
+
+/// Quoting settings per instrument
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct StrategyConfig {
+    pub instruments: Vec<InstrumentStrategyConfig>,
+}
+
+impl StrategyConfig {
+    /// Settings for an instrument, if configured
+    pub fn instrument(&self, name: &str) -> Option<&InstrumentStrategyConfig> {
+        self.instruments.iter().find(|i| i.instrument == name)
+    }
+
+    /// Instruments configured as disabled
+    pub fn disabled_instruments(&self) -> impl Iterator<Item = &str> {
+        self.instruments.iter().filter(|i| i.disabled).map(|i| i.instrument.as_str())
+    }
+}
+
+/// Quoting settings for one instrument
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct InstrumentStrategyConfig {
+    pub instrument: String,
+    
+    /// Minimum price move (ticks) before an order is amended, per level; the last
+    /// value applies to deeper levels
+    #[serde(default)]
+    pub amend_thresholds: Vec<f64>,
+    
+    /// Relative size reduction tolerated before an order is amended
+    #[serde(default)]
+    pub size_tolerance: f64,
+    
+    /// Quote ladder; the built-in linear ladder when unset
+    #[serde(default)]
+    pub ladder: Option<LadderConfig>,
+    
+    /// Size scaling with equity and volatility; fixed ladder sizes when unset
+    #[serde(default)]
+    pub sizing: Option<SizingConfig>,
+    
+    /// Not quoted and its market data not subscribed until enabled by a control command
+    #[serde(default)]
+    pub disabled: bool,
+    
+    /// Position and order limits in USD notional and delta, on top of the leverage tiers
+    #[serde(default)]
+    pub limits: Option<RiskLimitsConfig>,
+    
+    /// Order update allowance of each quote side; 20 per second when unset
+    #[serde(default)]
+    pub side_budget: Option<SideBudgetConfig>,
+    
+    /// Clamping of quotes inside the exchange price collar; quotes ignore the collar when unset
+    #[serde(default)]
+    pub collar: Option<CollarConfig>,
+    
+    /// Quote the best level of each side with one mass quote per adjustment, deeper levels
+    /// with orders; individual orders for every level when unset
+    #[serde(default)]
+    pub mass_quote: Option<MassQuoteConfig>,
+}
+
+/// Quoting through the exchange's mass quotes
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MassQuoteConfig {
+    /// Levels that would cross the book are moved back by the exchange instead of taking
+    #[serde(default = "default_mass_quote_post_only")]
+    pub post_only: bool,
+}
+
+fn default_mass_quote_post_only() -> bool {
+    true
+}
+
+/// Quotes are kept `buffer_ticks` inside the collar, and sized down by `size_multiplier`
+/// while it is tight or moving fast
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CollarConfig {
+    #[serde(default = "default_collar_buffer_ticks")]
+    pub buffer_ticks: f64,
+    
+    /// Collar width, in basis points of the mark, below which it counts as tight
+    #[serde(default = "default_collar_tight_bps")]
+    pub tight_bps: f64,
+    
+    /// Move of the collar midpoint within `window_sec`, in basis points of the mark, that counts as fast
+    #[serde(default = "default_collar_fast_move_bps")]
+    pub fast_move_bps: f64,
+    
+    #[serde(default = "default_collar_window_sec")]
+    pub window_sec: f64,
+    
+    #[serde(default = "default_collar_size_multiplier")]
+    pub size_multiplier: f64,
+}
+
+fn default_collar_buffer_ticks() -> f64 {
+    2.0
+}
+
+fn default_collar_tight_bps() -> f64 {
+    50.0
+}
+
+fn default_collar_fast_move_bps() -> f64 {
+    25.0
+}
+
+fn default_collar_window_sec() -> f64 {
+    10.0
+}
+
+fn default_collar_size_multiplier() -> f64 {
+    0.5
+}
+
+/// Inserts and amends allowed per side within a sliding window
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SideBudgetConfig {
+    pub max_updates: usize,
+    pub window_ms: u64,
+}
+
+/// Limits converted to contracts at the mark price and the instrument's contract size;
+/// unset limits don't apply
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct RiskLimitsConfig {
+    /// Largest absolute position notional, in USD
+    #[serde(default)]
+    pub max_position_notional: Option<f64>,
+    
+    /// Largest notional of a single order, in USD
+    #[serde(default)]
+    pub max_order_notional: Option<f64>,
+    
+    /// Largest absolute position delta, in units of the underlying
+    #[serde(default)]
+    pub max_delta: Option<f64>,
+}
+
+/// Constant-risk sizing: the first level is sized so that a move of one realized
+/// volatility costs `risk_per_level` of equity; other levels keep their ladder ratios
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SizingConfig {
+    /// Fraction of equity at risk per level
+    pub risk_per_level: f64,
+    
+    /// Floor on realized volatility, so a quiet window doesn't blow up sizes
+    #[serde(default = "default_sizing_min_vol")]
+    pub min_vol: f64,
+    
+    /// Bounds on the resulting multiplier of the ladder sizes
+    #[serde(default = "default_sizing_min_multiplier")]
+    pub min_multiplier: f64,
+    
+    #[serde(default = "default_sizing_max_multiplier")]
+    pub max_multiplier: f64,
+}
+
+fn default_sizing_min_vol() -> f64 {
+    0.0005
+}
+
+fn default_sizing_min_multiplier() -> f64 {
+    0.1
+}
+
+fn default_sizing_max_multiplier() -> f64 {
+    2.0
+}
+
+/// How quote levels are spaced away from the first level, in ticks
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "shape", rename_all = "lowercase")]
+pub enum LadderShape {
+    /// Levels `step` apart
+    Linear { step: f64 },
+    /// The gap to the next level grows by `ratio` each level, starting at `step`
+    Geometric { step: f64, ratio: f64 },
+    /// Explicit offset of each level
+    Custom { offsets: Vec<f64> },
+}
+
+/// Quote ladder: spacing plus the size quoted at each level
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LadderConfig {
+    #[serde(flatten)]
+    pub shape: LadderShape,
+    
+    pub bid_sizes: Vec<f64>,
+    
+    pub ask_sizes: Vec<f64>,
+}
+
+/// Account fee tier, in basis points of notional
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FeeConfig {
+    /// Maker fee; negative for a rebate
+    pub maker_fee_bps: f64,
+    
+    /// Taker fee
+    pub taker_fee_bps: f64,
+}
+
+/// Equity and leverage tiers bounding the position the strategy may quote into
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// Equity backing the strategy (USD), used to size leverage limits
+    pub equity: f64,
+    
+    /// Leverage allowed per position notional bracket; sorted by `max_notional` when loaded
+    pub leverage_tiers: Vec<LeverageTier>,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            equity: 10_000.0,
+            leverage_tiers: vec![
+                LeverageTier { max_notional: 50_000.0, max_leverage: 5.0 },
+                LeverageTier { max_notional: 250_000.0, max_leverage: 3.0 },
+                LeverageTier { max_notional: 1_000_000.0, max_leverage: 2.0 },
+            ],
+        }
+    }
+}
+
+/// Leverage allowed while the position notional stays below `max_notional`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct LeverageTier {
+    /// Upper bound of position notional for this tier
+    pub max_notional: f64,
+    
+    /// Maximum leverage (notional / equity) permitted inside this tier
+    pub max_leverage: f64,
+}
+
+/// Fault injection on inbound exchange messages; only honoured in builds with the
+/// `chaos` feature
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ChaosConfig {
+    pub enabled: bool,
+    
+    /// Latency added to every delivered message (milliseconds)
+    pub latency_ms: u64,
+    
+    /// Extra uniform random latency on top of `latency_ms` (milliseconds)
+    pub jitter_ms: u64,
+    
+    /// Probability of dropping a message
+    pub drop_rate: f64,
+    
+    /// Probability of holding a message back behind the next one
+    pub reorder_rate: f64,
+    
+    /// Seed for reproducible runs
+    pub seed: Option<u64>,
+    
+    /// Faults on Kafka produce calls
+    pub kafka: FaultConfig,
+    
+    /// Faults on schema registry calls
+    pub registry: FaultConfig,
+}
+
+/// Fault injection on calls to one downstream service
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FaultConfig {
+    /// Probability of failing a call
+    pub error_rate: f64,
+    
+    /// Latency added to every call (milliseconds)
+    pub delay_ms: u64,
+    
+    /// Length of each outage (seconds)
+    pub outage_sec: u64,
+    
+    /// Period between outage starts (seconds); 0 disables outages
+    pub outage_every_sec: u64,
+}
+
+/// Where the quoting lease is kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaseBackend {
+    /// Lease files in a directory, for instances sharing one host
+    #[default]
+    File,
+    /// A row in the database, for instances on different hosts
+    Postgres,
+}
+
+/// Exclusive quoting lease so a second instance on the same account waits in standby
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LeaseConfig {
+    pub enabled: bool,
+    
+    pub backend: LeaseBackend,
+    
+    /// Directory holding lease files (file backend)
+    pub dir: String,
+    
+    /// What the lease protects; defaults to `thalex.<network>.<underlying>`
+    pub key: Option<String>,
+    
+    /// How long a lease stays valid without renewal (seconds)
+    pub ttl_sec: u64,
+    
+    /// How often the holder renews, and a standby instance retries (seconds)
+    pub renew_sec: u64,
+    
+    /// Keep a session open while in standby so market data and positions are
+    /// warm, and take over within `renew_sec` of the lease becoming free
+    pub hot_standby: bool,
+}
+
+impl Default for LeaseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: LeaseBackend::File,
+            dir: "/tmp/cryptics_lab_bot".to_string(),
+            key: None,
+            ttl_sec: 15,
+            renew_sec: 5,
+            hot_standby: false,
+        }
+    }
+}
+
+/// Daily trading statistics saved to disk so a restart continues the day's totals
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StatsConfig {
+    pub enabled: bool,
+    
+    /// Snapshot file, rewritten every few seconds
+    pub path: String,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "state/daily_stats.json".to_string(),
+        }
+    }
+}
+
+/// Daily export of the previous day's fills, PnL and quote uptime from the local journal
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EodExportConfig {
+    pub enabled: bool,
+    
+    /// Time of day the previous day is exported, "HH:MM" UTC
+    pub time: String,
+    
+    /// Where fills and each day's closing statistics are journaled
+    pub journal_dir: String,
+    
+    /// Where the report files are written, one directory per day
+    pub out_dir: String,
+    
+    /// Report formats: csv, json
+    pub formats: Vec<String>,
+    
+    /// Object store the report files are uploaded to after writing, if any
+    pub upload: Option<ObjectStoreConfig>,
+}
+
+impl Default for EodExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: "00:05".to_string(),
+            journal_dir: "state/journal".to_string(),
+            out_dir: "exports".to_string(),
+            formats: vec!["csv".to_string()],
+            upload: None,
+        }
+    }
+}
+
+/// Kind of object store
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectStoreProvider {
+    /// AWS S3
+    #[default]
+    S3,
+    /// Google Cloud Storage, with a service account key or application default credentials
+    Gcs,
+    /// MinIO or another self-hosted S3-compatible store; needs an endpoint
+    Minio,
+    /// Directory on the local filesystem; `bucket` is the directory
+    Local,
+}
+
+/// Bucket report files and archives are stored in
+#[derive(Debug, Clone, Deserialize)]
+pub struct ObjectStoreConfig {
+    #[serde(default)]
+    pub provider: ObjectStoreProvider,
+    /// Overrides the provider's endpoint, e.g. http://minio:9000
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    pub bucket: String,
+    #[serde(default = "default_object_store_region")]
+    pub region: String,
+    /// Key prefix objects are stored under
+    #[serde(default)]
+    pub prefix: String,
+    /// Credentials as secret references: env:NAME, file:PATH or keyring:ACCOUNT
+    #[serde(default = "default_object_store_access_key")]
+    pub access_key: String,
+    #[serde(default = "default_object_store_secret_key")]
+    pub secret_key: String,
+    /// GCS service account key (JSON) as a secret reference; application default
+    /// credentials when unset
+    #[serde(default)]
+    pub service_account: Option<String>,
+}
+
+fn default_object_store_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_object_store_access_key() -> String {
+    "env:OBJECT_STORE_ACCESS_KEY_ID".to_string()
+}
+
+fn default_object_store_secret_key() -> String {
+    "env:OBJECT_STORE_SECRET_ACCESS_KEY".to_string()
+}
+
+/// Journal of quote inserts awaiting the exchange, saved so the next session can tell
+/// which of them reached it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct InflightConfig {
+    pub enabled: bool,
+    
+    /// Journal file, rewritten before every insert and on every acknowledgement
+    pub path: String,
+}
+
+impl Default for InflightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "state/inflight_orders.json".to_string(),
+        }
+    }
+}
+
+/// Journal of the bot's state transitions, kept across restarts
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BotStateConfig {
+    pub enabled: bool,
+    
+    /// Journal file, one transition appended per line
+    pub path: String,
+}
+
+impl Default for BotStateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "state/bot_state.jsonl".to_string(),
+        }
+    }
+}
+
+/// Publishing of the account trades made while no session was publishing them, fetched
+/// from the exchange once a session is logged in
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TradeBackfillConfig {
+    pub enabled: bool,
+    
+    /// File holding the time up to which trades are known to be on the trade topic
+    pub path: String,
+    
+    /// Furthest back trades are fetched (seconds), also the window of the first backfill
+    pub lookback_sec: u64,
+    
+    /// Seconds before the recorded time fetched again, for trades reported late
+    pub overlap_sec: u64,
+    
+    /// Trades fetched per `private/trade_history` request
+    pub page_size: u32,
+}
+
+impl Default for TradeBackfillConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "state/trade_backfill.json".to_string(),
+            lookback_sec: 86_400,
+            overlap_sec: 60,
+            page_size: 100,
+        }
+    }
+}
+
+/// Where the price quotes are centered on comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FairValueSource {
+    /// The exchange index
+    #[default]
+    Index,
+    /// JSON messages on a Kafka topic
+    Kafka,
+    /// A polled HTTP endpoint returning JSON
+    Http,
+}
+
+/// External fair value, e.g. the mid of another venue, quoted around while it is fresh
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FairValueConfig {
+    pub source: FairValueSource,
+    
+    /// Topic carrying the fair value (kafka source)
+    pub topic: String,
+    
+    /// Endpoint returning the fair value (http source)
+    pub url: String,
+    
+    /// How often the endpoint is polled (http source)
+    pub poll_interval_ms: u64,
+    
+    /// Dotted path of the price in each JSON message, e.g. `result.mid`
+    pub field: String,
+    
+    /// Values older than this are stale and quoting falls back to the index
+    pub max_age_ms: u64,
+    
+    /// Values with more newer records than this still unread are skipped (kafka source); 0 leaves lag unchecked
+    pub max_lag: u64,
+}
+
+impl Default for FairValueConfig {
+    fn default() -> Self {
+        Self {
+            source: FairValueSource::Index,
+            topic: "cryptics.fair_value".to_string(),
+            url: String::new(),
+            poll_interval_ms: 1000,
+            field: "price".to_string(),
+            max_age_ms: 3000,
+            max_lag: 100,
+        }
+    }
+}
+
+/// Operator commands consumed from a Kafka topic
+///
+/// Commands produced longer than `max_age_ms` ago, past their `expires_at`, or read with
+/// more than `max_lag` newer records behind them are ignored, so a delayed consumer
+/// doesn't apply instructions meant for an earlier moment.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ControlConfig {
+    pub enabled: bool,
+    
+    /// Topic carrying JSON control commands
+    pub topic: String,
+    
+    pub max_age_ms: u64,
+    
+    /// 0 leaves lag unchecked
+    pub max_lag: u64,
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            topic: "cryptics.control".to_string(),
+            max_age_ms: 5000,
+            max_lag: 100,
+        }
+    }
+}
+
+/// Two-leg spread: fills of the quoted instrument are hedged on `hedge_instrument`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SpreadConfig {
+    pub enabled: bool,
+    
+    /// Instrument every quote fill is offset on, e.g. a dated future against the perpetual
+    pub hedge_instrument: String,
+    
+    /// Hedge-leg contracts per quoted-leg contract
+    pub hedge_ratio: f64,
+    
+    /// Largest unhedged quoted-leg amount before quoting is pulled
+    pub max_leg_risk: f64,
+}
+
+impl Default for SpreadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hedge_instrument: String::new(),
+            hedge_ratio: 1.0,
+            max_leg_risk: 0.5,
+        }
+    }
+}
+
+/// Tokio runtime layout, for deployments where quote latency jitter matters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    /// Worker threads of the quoting runtime; one per core when unset
+    pub worker_threads: Option<usize>,
+    
+    /// Run Kafka publishing and other background IO on a runtime of its own
+    pub io_runtime: bool,
+    
+    /// Worker threads of the IO runtime
+    pub io_worker_threads: usize,
+    
+    /// Cores the quoting runtime's workers are pinned to; unpinned when empty
+    pub quote_cores: Vec<usize>,
+    
+    /// Cores the IO runtime's workers are pinned to; unpinned when empty
+    pub io_cores: Vec<usize>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            worker_threads: None,
+            io_runtime: false,
+            io_worker_threads: 2,
+            quote_cores: Vec::new(),
+            io_cores: Vec::new(),
+        }
+    }
+}
+
+/// Instrument names that differ from a venue's naming convention
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SymbologyConfig {
+    pub overrides: Vec<SymbolOverride>,
+}
+
+/// What one venue calls one canonical instrument
+#[derive(Debug, Clone, Deserialize)]
+pub struct SymbolOverride {
+    /// e.g. `BTC-USDT-20251226-FUT`
+    pub canonical: String,
+    pub venue: Venue,
+    pub symbol: String,
+}
+
+/// Connection to the database the pipeline persists topics into
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub host_internal: String,
+    pub port: u16,
+    pub name: String,
+    pub user: String,
+    /// No default; set it in the config or with `CRYPTICS__DATABASE__PASSWORD`
+    pub password: String,
+    pub schema: String,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            host_internal: "timescaledb".to_string(),
+            port: 5432,
+            name: "cryptics".to_string(),
+            user: "postgres".to_string(),
+            password: String::new(),
+            schema: "public".to_string(),
+        }
+    }
+}
+
+/// Application information
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppInfo {
+    pub rust_running_in_docker: bool,
+    
+    /// Profile layered over the base config (test, staging, prod, ...)
+    #[serde(default)]
+    pub profile: Option<String>,
+    
+    /// Thalex network to connect to ("test" or "prod")
+    #[serde(default = "default_network")]
+    pub network: String,
+    
+    /// Log level filter (error, warn, info, debug, trace)
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    // Add more app settings as needed
+}
+
+fn default_network() -> String {
+    "test".to_string()
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+impl AppConfig {
+    /// Load configuration from a TOML, YAML or JSON file
+    ///
+    /// The format is detected from the extension; files without a known extension are
+    /// tried as TOML, then JSON, then YAML. The profile is taken from `CRYPTICS_PROFILE`
+    /// or `app.profile`; its `profiles.<name>` section is layered over the base config.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        
+        // Read the file
+        let config_str = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read config file '{}': {}", path.display(), e))?;
+        
+        // Parse the document
+        let raw = match ConfigFormat::from_path(path) {
+            Some(format) => format.parse(&config_str),
+            None => Self::detect_and_parse(&config_str),
+        }.map_err(|e| anyhow!("Failed to parse config file '{}': {}", path.display(), e))?;
+        
+        let profile = std::env::var(PROFILE_ENV_VAR).ok();
+        let config = Self::from_value(raw, profile.as_deref())
+            .map_err(|e| anyhow!("Invalid config file '{}': {}", path.display(), e))?;
+        
+        info!("Loaded configuration from {} (profile: {})", path.display(),
+            config.app.profile.as_deref().unwrap_or("none"));
+        debug!("Running in Docker: {}", config.app.rust_running_in_docker);
+        
+        Ok(config)
+    }
+    
+    /// Load configuration from an in-memory document
+    pub fn from_str(content: &str, format: ConfigFormat) -> Result<Self> {
+        let raw = format.parse(content)?;
+        let profile = std::env::var(PROFILE_ENV_VAR).ok();
+        Self::from_value(raw, profile.as_deref())
+    }
+    
+    /// Load configuration entirely from `CRYPTICS__`-prefixed environment variables
+    ///
+    /// `CRYPTICS__KAFKA__BOOTSTRAP_SERVERS=localhost:9092` sets `kafka.bootstrap_servers`.
+    /// Values are read as the type of the setting they set, so a number or boolean is
+    /// parsed only where one is expected and a numeric password stays a string.
+    pub fn from_env() -> Result<Self> {
+        let profile = std::env::var(PROFILE_ENV_VAR).ok();
+        Self::from_env_vars(std::env::vars(), profile.as_deref())
+    }
+    
+    /// Build the config from key/value pairs in the `CRYPTICS__` format, like `from_env`
+    pub fn from_env_vars<I: IntoIterator<Item = (String, String)>>(vars: I, profile_override: Option<&str>) -> Result<Self> {
+        let raw = Self::env_to_value(vars)?;
+        Self::from_document(raw, profile_override, true)
+    }
+    
+    /// Build a config document from key/value pairs in the `CRYPTICS__` format
+    ///
+    /// Arrays and tables given as JSON are kept as such; every other value is kept as
+    /// the string it was given. Fails when a variable nests below one setting another
+    /// to a plain value, e.g. `CRYPTICS__KAFKA=x` next to `CRYPTICS__KAFKA__TOPIC=y`.
+    pub fn env_to_value<I: IntoIterator<Item = (String, String)>>(vars: I) -> Result<Value> {
+        let mut root = Value::Object(serde_json::Map::new());
+        for (key, value) in vars {
+            let Some(path) = key.strip_prefix(CONFIG_ENV_PREFIX) else {
+                continue;
+            };
+            let parsed = match serde_json::from_str::<Value>(&value) {
+                Ok(structured) if structured.is_array() || structured.is_object() => structured,
+                _ => Value::String(value),
+            };
+            
+            let mut node = &mut root;
+            for segment in path.split("__") {
+                if node.is_null() {
+                    *node = Value::Object(serde_json::Map::new());
+                }
+                let Value::Object(table) = node else {
+                    return Err(anyhow!("{} nests below a variable that is not a table", key));
+                };
+                node = table.entry(segment.to_lowercase()).or_insert(Value::Null);
+            }
+            *node = parsed;
+        }
+        Ok(root)
+    }
+    
+    /// Try each supported format in turn
+    fn detect_and_parse(content: &str) -> Result<Value> {
+        [ConfigFormat::Toml, ConfigFormat::Json, ConfigFormat::Yaml]
+            .iter()
+            .find_map(|format| format.parse(content).ok().filter(|v| v.is_object()))
+            .ok_or_else(|| anyhow!("Unrecognized config format"))
+    }
+    
+    /// Build the config from a parsed document, applying the selected profile
+    ///
+    /// `profile_override` takes precedence over `app.profile` in the document.
+    pub fn from_value(raw: Value, profile_override: Option<&str>) -> Result<Self> {
+        Self::from_document(raw, profile_override, false)
+    }
+    
+    /// `from_value`, reading strings as numbers and booleans where those are expected when `lenient`
+    fn from_document(mut raw: Value, profile_override: Option<&str>, lenient: bool) -> Result<Self> {
+        let profile = profile_override
+            .map(str::to_string)
+            .or_else(|| raw.pointer("/app/profile").and_then(|v| v.as_str()).map(str::to_string));
+        
+        if let Some(profile) = &profile {
+            let overlay = raw.pointer(&format!("/profiles/{}", profile))
+                .cloned()
+                .ok_or_else(|| anyhow!("Profile '{}' not found in config", profile))?;
+            merge_values(&mut raw, overlay);
+            raw["app"]["profile"] = Value::String(profile.clone());
+        }
+        
+        let config: Self = match lenient {
+            true => Self::deserialize(Lenient(raw)),
+            false => serde_json::from_value(raw),
+        }.map_err(|e| anyhow!("Failed to deserialize config: {}", e))?;
+        config.thalex.book_channels()?;
+        if let Some(options) = &config.thalex.options {
+            options.validate()?;
+        }
+        config.schedule.parse()?;
+        Ok(config)
+    }
+    
+    /// Log level from the config, falling back to Info for unknown values
+    pub fn log_level(&self) -> log::LevelFilter {
+        self.app.log_level.parse().unwrap_or(log::LevelFilter::Info)
+    }
+    
+    /// Helper to get the appropriate Kafka bootstrap servers URL based on Docker status
+    pub fn kafka_bootstrap_servers(&self) -> &str {
+        if self.app.rust_running_in_docker {
+            &self.kafka.bootstrap_servers_internal
+        } else {
+            &self.kafka.bootstrap_servers
+        }
+    }
+    
+    /// Helper to get the appropriate Schema Registry URL based on Docker status
+    pub fn kafka_schema_registry_url(&self) -> &str {
+        if self.app.rust_running_in_docker {
+            &self.kafka.schema_registry_url_internal
+        } else {
+            &self.kafka.schema_registry_url
+        }
+    }
+    
+    /// Helper to get the database connection string based on Docker status
+    pub fn database_url(&self) -> String {
+        let db = &self.database;
+        let host = if self.app.rust_running_in_docker { &db.host_internal } else { &db.host };
+        let mut url = format!("host={} port={} dbname={} user={}", host, db.port, db.name, db.user);
+        if !db.password.is_empty() {
+            url.push_str(&format!(" password={}", db.password));
+        }
+        url
+    }
+    
+    /// Helper to get the appropriate Kafka Connect URL based on Docker status
+    pub fn kafka_connect_url(&self) -> &str {
+        if self.app.rust_running_in_docker {
+            &self.kafka.connect_url_internal
+        } else {
+            &self.kafka.connect_url
+        }
+    }
+}
+
+/// Recursively layer `overlay` over `base`; tables are merged, everything else is replaced
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Document whose strings are read as numbers or booleans where the target expects one
+///
+/// Environment variables are all strings; reading them by the type of the setting
+/// keeps e.g. a numeric password a string while still accepting `true` or `5` for
+/// flags and counts. Settings read without a type (flattened tables) take strings
+/// that parse as numbers or booleans as those.
+struct Lenient(Value);
+
+impl Lenient {
+    /// The value a string stands for when a scalar is expected, None if it's no scalar
+    fn scalar(&self) -> Option<Value> {
+        match &self.0 {
+            Value::String(text) => serde_json::from_str::<Value>(text.trim()).ok()
+                .filter(|value| value.is_number() || value.is_boolean()),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! lenient_scalars {
+    ($($method:ident)*) => {$(
+        fn $method<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            match self.scalar() {
+                Some(value) => value.$method(visitor),
+                None => self.0.$method(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> serde::Deserializer<'de> for Lenient {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if let Some(value) = self.scalar() {
+            return value.deserialize_any(visitor);
+        }
+        match self.0 {
+            Value::Object(table) => visitor.visit_map(LenientMap { entries: table.into_iter(), value: None }),
+            Value::Array(items) => visitor.visit_seq(LenientSeq(items.into_iter())),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    lenient_scalars! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32 deserialize_f64
+    }
+
+    fn deserialize_str<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Number(number) => visitor.visit_string(number.to_string()),
+            Value::Bool(flag) => visitor.visit_string(flag.to_string()),
+            value => value.deserialize_string(visitor),
+        }
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(Lenient(value)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 char bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct LenientMap {
+    entries: serde_json::map::IntoIter,
+    value: Option<Value>,
+}
+
+impl<'de> serde::de::MapAccess<'de> for LenientMap {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K: serde::de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(Lenient(Value::String(key))).map(Some)
+    }
+
+    fn next_value_seed<V: serde::de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        seed.deserialize(Lenient(self.value.take().unwrap_or(Value::Null)))
+    }
+}
+
+struct LenientSeq(std::vec::IntoIter<Value>);
+
+impl<'de> serde::de::SeqAccess<'de> for LenientSeq {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<T: serde::de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        self.0.next().map(|value| seed.deserialize(Lenient(value))).transpose()
+    }
+}
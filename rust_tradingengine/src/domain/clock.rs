@@ -0,0 +1,140 @@
+//! Source of the current time for processing timestamps, throttles and TTLs
+//!
+//! Code reads the time through `now_secs` and `instant` rather than `SystemTime::now()`
+//! and `Instant::now()`, so backtests can install a virtual clock for the whole process
+//! with `set_global`, and tests one for their own thread with `scoped`, and get the same
+//! output on every run. Waits that sleep on the runtime, and timestamps the venue checks
+//! against its own clock (auth tokens, request signatures, drift measurement), stay on
+//! real time.
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+
+/// Wall-clock and monotonic time
+pub trait Clock: Send + Sync {
+    /// Wall-clock time (seconds since epoch)
+    fn now_secs(&self) -> f64;
+
+    /// Monotonic time, for durations and deadlines
+    fn instant(&self) -> Instant;
+}
+
+/// The operating system's clocks
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_secs(&self) -> f64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64()
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Virtual time that stands still until advanced
+///
+/// Both readings move together: advancing by a second moves the wall clock and the
+/// monotonic clock by a second. Time never goes backwards.
+#[derive(Debug)]
+pub struct ManualClock {
+    start_secs: f64,
+    start_instant: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl ManualClock {
+    /// Clock reading `start_secs` (seconds since epoch) until advanced
+    pub fn new(start_secs: f64) -> Self {
+        Self { start_secs, start_instant: Instant::now(), elapsed: Mutex::new(Duration::ZERO) }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.elapsed.lock().unwrap() += by;
+    }
+
+    /// Move to `secs` (seconds since epoch); earlier times leave the clock where it is
+    pub fn advance_to(&self, secs: f64) {
+        let target = Duration::try_from_secs_f64(secs - self.start_secs).unwrap_or_default();
+        let mut elapsed = self.elapsed.lock().unwrap();
+        *elapsed = (*elapsed).max(target);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_secs(&self) -> f64 {
+        self.start_secs + self.elapsed.lock().unwrap().as_secs_f64()
+    }
+
+    fn instant(&self) -> Instant {
+        self.start_instant + *self.elapsed.lock().unwrap()
+    }
+}
+
+static GLOBAL: OnceLock<Arc<dyn Clock>> = OnceLock::new();
+
+thread_local! {
+    static SCOPED: RefCell<Option<Arc<dyn Clock>>> = const { RefCell::new(None) };
+}
+
+/// Install the process-wide clock; it can be set once, before anything reads the time
+pub fn set_global(clock: Arc<dyn Clock>) -> Result<()> {
+    GLOBAL.set(clock).map_err(|_| anyhow!("The global clock is already set"))
+}
+
+/// Read the time from this thread's scoped clock, else the global one, else the system
+fn with<R>(read: impl FnOnce(&dyn Clock) -> R) -> R {
+    SCOPED.with(|scoped| match scoped.borrow().as_deref() {
+        Some(clock) => read(clock),
+        None => match GLOBAL.get() {
+            Some(clock) => read(clock.as_ref()),
+            None => read(&SystemClock),
+        },
+    })
+}
+
+/// Current wall-clock time (seconds since epoch)
+pub fn now_secs() -> f64 {
+    with(|clock| clock.now_secs())
+}
+
+/// Current wall-clock time (milliseconds since epoch)
+pub fn now_millis() -> i64 {
+    (now_secs() * 1000.0) as i64
+}
+
+/// Current wall-clock time as a UTC date-time
+pub fn now_utc() -> DateTime<Utc> {
+    DateTime::from_timestamp_micros((now_secs() * 1e6) as i64).unwrap_or_default()
+}
+
+/// Current monotonic time
+pub fn instant() -> Instant {
+    with(|clock| clock.instant())
+}
+
+/// Use `clock` on the current thread until the returned guard is dropped
+///
+/// Meant for tests on a single-threaded runtime; work moved to other threads still
+/// reads the global clock.
+pub fn scoped(clock: Arc<dyn Clock>) -> ScopedClock {
+    let previous = SCOPED.with(|scoped| scoped.borrow_mut().replace(clock));
+    ScopedClock { previous }
+}
+
+/// Restores the thread's previous clock when dropped
+pub struct ScopedClock {
+    previous: Option<Arc<dyn Clock>>,
+}
+
+impl Drop for ScopedClock {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SCOPED.with(|scoped| *scoped.borrow_mut() = previous);
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Serialize, Deserialize, Serializer};
 use anyhow::{Result, anyhow};
+use std::str::FromStr;
 
 // Note: We're still using #[serde(rename_all = "lowercase")]
 // This doesn't change the type of the serialized value, just affects the value itself
@@ -24,6 +25,18 @@ impl Serialize for OrderSide {
     }
 }
 
+impl FromStr for OrderSide {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "buy" => Ok(OrderSide::Buy),
+            "sell" => Ok(OrderSide::Sell),
+            _ => Err(anyhow!("Unknown order side: {}", s)),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OrderType {
@@ -44,6 +57,18 @@ impl Serialize for OrderType {
     }
 }
 
+impl FromStr for OrderType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "limit" => Ok(OrderType::Limit),
+            "market" => Ok(OrderType::Market),
+            _ => Err(anyhow!("Unknown order type: {}", s)),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TimeInForce {
@@ -64,6 +89,18 @@ impl Serialize for TimeInForce {
     }
 }
 
+impl FromStr for TimeInForce {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "good_till_cancelled" => Ok(TimeInForce::GTC),
+            "immediate_or_cancel" => Ok(TimeInForce::IOC),
+            _ => Err(anyhow!("Unknown time in force: {}", s)),
+        }
+    }
+}
+
 // Represents a domain concept (the lifecycle of an order) and should be shared across modules
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -91,8 +128,10 @@ impl Serialize for OrderStatus {
     }
 }
 
-impl OrderStatus {
-    pub fn from_str(s: &str) -> Result<Self> {
+impl FromStr for OrderStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
         match s {
             "open" => Ok(OrderStatus::Open),
             "partially_filled" => Ok(OrderStatus::PartiallyFilled),
@@ -102,4 +141,81 @@ impl OrderStatus {
             _ => Err(anyhow!("Unknown order status: {}", s)),
         }
     }
-}
\ No newline at end of file
+}
+/// Liquidity role of our side of a trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MakerTaker {
+    Maker,
+    Taker,
+}
+
+impl MakerTaker {
+    /// Symbols of the Avro enum, in schema order
+    pub const SYMBOLS: [&'static str; 2] = ["maker", "taker"];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MakerTaker::Maker => "maker",
+            MakerTaker::Taker => "taker",
+        }
+    }
+
+    /// Position of the symbol in the Avro enum
+    pub fn index(&self) -> u32 {
+        match self {
+            MakerTaker::Maker => 0,
+            MakerTaker::Taker => 1,
+        }
+    }
+}
+
+impl Serialize for MakerTaker {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl FromStr for MakerTaker {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "maker" => Ok(MakerTaker::Maker),
+            "taker" => Ok(MakerTaker::Taker),
+            _ => Err(anyhow!("Unknown maker/taker role: {}", s)),
+        }
+    }
+}
+
+/// Right of an option contract
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+impl OptionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OptionType::Call => "call",
+            OptionType::Put => "put",
+        }
+    }
+}
+
+impl FromStr for OptionType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "call" | "C" => Ok(OptionType::Call),
+            "put" | "P" => Ok(OptionType::Put),
+            _ => Err(anyhow!("Unknown option type: {}", s)),
+        }
+    }
+}
@@ -1,3 +1,3 @@
-pub mod constants;
+pub mod clock;
 pub mod enums;
 pub mod model;
@@ -0,0 +1,59 @@
+use serde::{Serialize, Deserialize};
+
+/// Kind of account-level event reported by the exchange
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountEventType {
+    Deposit,
+    Withdrawal,
+    Liquidation,
+    MarginCall,
+    /// Account notification of a kind without dedicated handling
+    Other,
+}
+
+impl AccountEventType {
+    /// All event types, in Avro enum symbol order
+    pub const ALL: [AccountEventType; 5] = [
+        AccountEventType::Deposit,
+        AccountEventType::Withdrawal,
+        AccountEventType::Liquidation,
+        AccountEventType::MarginCall,
+        AccountEventType::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountEventType::Deposit => "deposit",
+            AccountEventType::Withdrawal => "withdrawal",
+            AccountEventType::Liquidation => "liquidation",
+            AccountEventType::MarginCall => "margin_call",
+            AccountEventType::Other => "other",
+        }
+    }
+}
+
+/// Deposit, withdrawal, liquidation or other account notification
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountEvent {
+    /// What happened
+    pub event_type: AccountEventType,
+
+    /// When the exchange reported it (seconds since epoch)
+    pub timestamp: f64,
+
+    /// Asset moved, for deposits and withdrawals
+    pub currency: Option<String>,
+
+    /// Amount moved or liquidated
+    pub amount: Option<f64>,
+
+    /// Instrument concerned, for liquidations
+    pub instrument_name: Option<String>,
+
+    /// Text of the exchange's notification
+    pub message: Option<String>,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
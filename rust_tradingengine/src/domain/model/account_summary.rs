@@ -0,0 +1,58 @@
+use serde::{Serialize, Deserialize};
+
+/// Balance of one currency held in the account
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Balance {
+    pub currency: String,
+
+    pub balance: f64,
+
+    /// Share of the balance's value counted as collateral, when the exchange reports it
+    pub collateral_factor: Option<f64>,
+}
+
+/// Balances and margin of the account at one moment
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountSummary {
+    /// When the summary was received (seconds since epoch)
+    pub timestamp: f64,
+
+    pub balances: Vec<Balance>,
+
+    /// Value of the balances counted as collateral, in quote currency
+    pub cash_collateral: f64,
+
+    /// Unrealised PnL of the open positions
+    pub unrealised_pnl: f64,
+
+    /// Account value margin is measured against, cash collateral plus unrealised PnL
+    pub margin: f64,
+
+    /// Margin the open positions and orders require
+    pub required_margin: f64,
+
+    /// Margin left for new positions and orders
+    pub remaining_margin: f64,
+
+    /// PnL realised in the current exchange session
+    pub session_realised_pnl: f64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
+
+impl AccountSummary {
+    /// Share of the account value the open positions and orders require, 0 for an empty account
+    pub fn margin_utilisation(&self) -> f64 {
+        if self.margin > 0.0 {
+            self.required_margin / self.margin
+        } else {
+            0.0
+        }
+    }
+
+    /// Balance of `currency`, if the account holds any
+    pub fn balance(&self, currency: &str) -> Option<f64> {
+        self.balances.iter().find(|balance| balance.currency == currency).map(|balance| balance.balance)
+    }
+}
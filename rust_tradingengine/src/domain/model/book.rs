@@ -0,0 +1,189 @@
+use serde::{Serialize, Deserialize};
+
+/// Side of the order book
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+impl BookSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookSide::Bid => "bid",
+            BookSide::Ask => "ask",
+        }
+    }
+}
+
+/// Whether a book record is part of a full snapshot or a change since the previous update
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookUpdateKind {
+    Snapshot,
+    Delta,
+}
+
+impl BookUpdateKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookUpdateKind::Snapshot => "snapshot",
+            BookUpdateKind::Delta => "delta",
+        }
+    }
+}
+
+/// One price level of a book snapshot, or one level change
+///
+/// The book at time t is the latest snapshot (all rows sharing its sequence)
+/// at or before t, with later deltas up to t applied in (timestamp, sequence)
+/// order; a delta amount of zero removes the level.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BookLevelUpdate {
+    /// Name of the instrument
+    pub instrument_name: String,
+
+    pub kind: BookUpdateKind,
+
+    /// Book notification the record came from, increasing per instrument
+    pub sequence: i64,
+
+    pub side: BookSide,
+
+    pub price: f64,
+
+    /// Amount resting at the price; zero when a delta removes the level
+    pub amount: f64,
+
+    /// Exchange time of the book notification (seconds since epoch)
+    pub exchange_time: Option<f64>,
+
+    /// When the notification was received (seconds since epoch)
+    pub timestamp: f64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
+
+/// Price and amount resting at one level of a book
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: f64,
+    pub amount: f64,
+}
+
+/// Live L2 order book of one instrument, kept from snapshot and delta records
+///
+/// Levels are sorted best first: bids by descending and asks by ascending price.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub instrument_name: String,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+
+    /// Sequence of the last record applied
+    pub sequence: i64,
+
+    /// Exchange time of the last update (seconds since epoch)
+    pub exchange_time: Option<f64>,
+
+    /// When the last update was received (seconds since epoch)
+    pub timestamp: f64,
+}
+
+impl OrderBook {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            bids: Vec::new(),
+            asks: Vec::new(),
+            sequence: 0,
+            exchange_time: None,
+            timestamp: 0.0,
+        }
+    }
+
+    /// Apply one record: the first snapshot row of a new sequence replaces the book,
+    /// deltas set a level or remove it when their amount is zero
+    pub fn apply(&mut self, update: &BookLevelUpdate) {
+        if update.kind == BookUpdateKind::Snapshot && update.sequence != self.sequence {
+            self.bids.clear();
+            self.asks.clear();
+        }
+        self.sequence = update.sequence;
+        self.exchange_time = update.exchange_time;
+        self.timestamp = update.timestamp;
+
+        let side = update.side;
+        let levels = match side {
+            BookSide::Bid => &mut self.bids,
+            BookSide::Ask => &mut self.asks,
+        };
+        // Position of the level, or where it belongs
+        let at = levels.partition_point(|level| match side {
+            BookSide::Bid => level.price > update.price,
+            BookSide::Ask => level.price < update.price,
+        });
+        let exists = levels.get(at).is_some_and(|level| level.price == update.price);
+        match (exists, update.amount > 0.0) {
+            (true, true) => levels[at].amount = update.amount,
+            (true, false) => {
+                levels.remove(at);
+            }
+            (false, true) => levels.insert(at, BookLevel { price: update.price, amount: update.amount }),
+            (false, false) => {}
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<BookLevel> {
+        self.bids.first().copied()
+    }
+
+    pub fn best_ask(&self) -> Option<BookLevel> {
+        self.asks.first().copied()
+    }
+
+    /// Best bid and ask, when both sides have a level
+    pub fn top(&self) -> Option<(BookLevel, BookLevel)> {
+        Some((self.best_bid()?, self.best_ask()?))
+    }
+
+    pub fn mid(&self) -> Option<f64> {
+        self.top().map(|(bid, ask)| (bid.price + ask.price) / 2.0)
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        self.top().map(|(bid, ask)| ask.price - bid.price)
+    }
+
+    /// Mid weighted towards the side with less resting size, where the price is likelier to move
+    pub fn microprice(&self) -> Option<f64> {
+        let (bid, ask) = self.top()?;
+        let size = bid.amount + ask.amount;
+        if size <= 0.0 {
+            return self.mid();
+        }
+        Some((bid.price * ask.amount + ask.price * bid.amount) / size)
+    }
+
+    /// Amount resting in the best `levels` levels of a side
+    pub fn depth(&self, side: BookSide, levels: usize) -> f64 {
+        let side = match side {
+            BookSide::Bid => &self.bids,
+            BookSide::Ask => &self.asks,
+        };
+        side.iter().take(levels).map(|level| level.amount).sum()
+    }
+
+    /// Imbalance of the best `levels` levels in [-1, 1], positive when bids outweigh asks
+    pub fn imbalance(&self, levels: usize) -> f64 {
+        let bids = self.depth(BookSide::Bid, levels);
+        let asks = self.depth(BookSide::Ask, levels);
+        if bids + asks > 0.0 {
+            (bids - asks) / (bids + asks)
+        } else {
+            0.0
+        }
+    }
+}
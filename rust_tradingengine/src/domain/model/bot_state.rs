@@ -0,0 +1,94 @@
+use serde::{Serialize, Deserialize};
+
+/// Phase of the bot's life, governing what its tasks may do
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotState {
+    /// Waiting for the lease, connecting or logging in; nothing may be sent to the exchange
+    Connecting,
+    /// Session up, subscribing and waiting for market data before the first quotes
+    Warmup,
+    /// Quotes are sent and adjusted
+    Quoting,
+    /// Open quotes may be cancelled but no new ones sent (paused, disabled, leg risk)
+    CancelOnly,
+    /// A kill switch tripped; nothing new is sent until the session is torn down
+    Halted,
+    /// The session is ending and its orders are being cancelled
+    Draining,
+    /// The process is exiting
+    Stopped,
+}
+
+impl BotState {
+    /// All states, in the order of their gauge values
+    pub const ALL: [BotState; 7] = [
+        BotState::Connecting,
+        BotState::Warmup,
+        BotState::Quoting,
+        BotState::CancelOnly,
+        BotState::Halted,
+        BotState::Draining,
+        BotState::Stopped,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BotState::Connecting => "connecting",
+            BotState::Warmup => "warmup",
+            BotState::Quoting => "quoting",
+            BotState::CancelOnly => "cancel_only",
+            BotState::Halted => "halted",
+            BotState::Draining => "draining",
+            BotState::Stopped => "stopped",
+        }
+    }
+
+    /// Position in `ALL`, published as the state gauge
+    pub fn index(&self) -> usize {
+        BotState::ALL.iter().position(|state| state == self).unwrap_or_default()
+    }
+
+    /// Whether the bot may move from this state to `to`
+    ///
+    /// A halt can only be left by tearing the session down, and a stopped bot goes nowhere.
+    pub fn can_transition_to(&self, to: BotState) -> bool {
+        use BotState::*;
+        matches!(
+            (self, to),
+            (Connecting, Warmup | Halted | Draining | Stopped)
+                | (Warmup, Quoting | CancelOnly | Halted | Draining)
+                | (Quoting, CancelOnly | Halted | Draining)
+                | (CancelOnly, Quoting | Halted | Draining)
+                | (Halted, Draining | Stopped)
+                | (Draining, Connecting | Stopped)
+        )
+    }
+
+    /// Whether new quotes may be sent
+    pub fn may_quote(&self) -> bool {
+        *self == BotState::Quoting
+    }
+
+    /// Whether open orders may be cancelled
+    pub fn may_cancel(&self) -> bool {
+        !matches!(self, BotState::Connecting | BotState::Stopped)
+    }
+}
+
+/// Persisted record of a change of `BotState`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateTransition {
+    /// Identifies the running process
+    pub instance_id: String,
+
+    pub from: BotState,
+
+    pub to: BotState,
+
+    /// Why the state changed
+    pub reason: String,
+
+    /// When the state changed (seconds since epoch)
+    pub timestamp: f64,
+}
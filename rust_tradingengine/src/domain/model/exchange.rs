@@ -1,4 +1,6 @@
 use crate::domain::enums::*;
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
 use serde::Deserialize;
 
 
@@ -13,6 +15,8 @@ pub struct OrderRequest {
     pub price: Option<f64>,
     pub client_order_id: Option<u64>,
     pub time_in_force: Option<TimeInForce>,
+    /// Free text the exchange reports back with the order and its trades
+    pub label: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -22,4 +26,78 @@ pub struct Instrument {
     pub type_field: String,
     pub underlying: String,
     pub tick_size: f64,
+    #[serde(default)]
+    pub volume_tick_size: Option<f64>,
+    /// Underlying units per contract
+    #[serde(default)]
+    pub contract_size: Option<f64>,
+    /// Strike of an option
+    #[serde(default)]
+    pub strike_price: Option<f64>,
+    #[serde(default)]
+    pub option_type: Option<OptionType>,
+    /// Expiry of a dated contract (seconds since epoch)
+    #[serde(default)]
+    pub expiration_timestamp: Option<f64>,
+}
+
+/// Hour of the day (UTC) Thalex contracts expire at
+const EXPIRY_HOUR_UTC: u32 = 8;
+
+impl Instrument {
+    pub fn is_option(&self) -> bool {
+        self.type_field == "option"
+    }
+
+    /// Fill in the strike, type and expiry an option's name carries (`BTC-27DEC24-100000-C`)
+    /// where the exchange left them out
+    pub fn with_name_details(mut self) -> Result<Self> {
+        if !self.is_option() {
+            return Ok(self);
+        }
+        let parts: Vec<&str> = self.instrument_name.split('-').collect();
+        let [_, expiry, strike, right] = parts.as_slice() else {
+            return Err(anyhow!("Unexpected option name: {}", self.instrument_name));
+        };
+        if self.strike_price.is_none() {
+            let strike = strike.parse::<f64>().ok()
+                .filter(|strike| strike.is_finite() && *strike > 0.0)
+                .ok_or_else(|| anyhow!("Invalid strike in {}", self.instrument_name))?;
+            self.strike_price = Some(strike);
+        }
+        if self.option_type.is_none() {
+            self.option_type = Some(right.parse()?);
+        }
+        if self.expiration_timestamp.is_none() {
+            let date = NaiveDate::parse_from_str(expiry, "%d%b%y")
+                .map_err(|_| anyhow!("Invalid expiry in {}", self.instrument_name))?;
+            let expiry = date.and_hms_opt(EXPIRY_HOUR_UTC, 0, 0).unwrap_or_default().and_utc();
+            self.expiration_timestamp = Some(expiry.timestamp() as f64);
+        }
+        Ok(self)
+    }
+
+    /// Days from `now` (seconds) to expiry; None for perpetuals
+    pub fn days_to_expiry(&self, now: f64) -> Option<f64> {
+        self.expiration_timestamp.map(|expiry| (expiry - now) / 86_400.0)
+    }
+
+    /// Price and amount of an order moved onto the instrument's ticks
+    ///
+    /// The amount is rounded down to whole volume ticks so an order never grows;
+    /// expired contracts and amounts below one tick are rejected.
+    pub fn conform(&self, price: f64, amount: f64, now: f64) -> Result<(f64, f64)> {
+        if self.days_to_expiry(now).is_some_and(|days| days <= 0.0) {
+            return Err(anyhow!("{} has expired", self.instrument_name));
+        }
+        let price = self.tick_size * (price / self.tick_size).round();
+        let amount = match self.volume_tick_size {
+            Some(step) if step > 0.0 => step * (amount / step + 1e-9).floor(),
+            _ => amount,
+        };
+        if amount <= 0.0 {
+            return Err(anyhow!("Amount below the minimum of {}", self.instrument_name));
+        }
+        Ok((price, amount))
+    }
 }
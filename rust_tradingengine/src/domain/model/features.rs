@@ -0,0 +1,35 @@
+use serde::{Serialize, Deserialize};
+
+/// Rolling microstructure features computed from the bot's own market view
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MarketFeatures {
+    /// Name of the instrument
+    pub instrument_name: String,
+
+    /// Time the features were computed (seconds since epoch)
+    pub timestamp: f64,
+
+    /// Length of the rolling window the features were computed over (seconds)
+    pub window_secs: f64,
+
+    /// Top-of-book imbalance: (bid_amount - ask_amount) / (bid_amount + ask_amount)
+    pub imbalance: f64,
+
+    /// Best ask minus best bid
+    pub spread: f64,
+
+    /// Number of market trades per second over the window, from the public trades channel
+    pub trade_intensity: f64,
+
+    /// Realized volatility of mid-price log returns over the window
+    pub realized_vol: f64,
+
+    /// Relative basis of the mark price over the index: (mark - index) / index
+    pub funding_basis: f64,
+
+    /// Current funding rate reported by the exchange
+    pub funding_rate: f64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
@@ -0,0 +1,49 @@
+use serde::{Serialize, Deserialize};
+
+/// Basis and funding carry of a perpetual and of our position in it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FundingBasis {
+    /// Name of the perpetual
+    pub instrument_name: String,
+
+    /// When the sample was taken (seconds since epoch)
+    pub timestamp: f64,
+
+    /// Length of the window the rolling basis is averaged over
+    pub window_secs: f64,
+
+    /// Mark price of the perpetual
+    pub mark_price: f64,
+
+    /// Index price of the underlying
+    pub index_price: f64,
+
+    /// Mark price minus index price
+    pub basis: f64,
+
+    /// Basis in basis points of the index
+    pub basis_bps: f64,
+
+    /// Average basis over the window, in basis points of the index
+    pub rolling_basis_bps: f64,
+
+    /// Funding rate from the ticker, per funding period
+    pub funding_rate: f64,
+
+    /// Funding rate scaled to a year
+    pub annualized_funding_rate: f64,
+
+    /// Our position in the perpetual
+    pub position: f64,
+
+    /// Funding received by our positions since the process started, in quote currency;
+    /// negative when paid
+    pub cumulative_funding: f64,
+
+    /// Annualized funding the current position receives per unit of notional; negative
+    /// when it pays, 0 when flat
+    pub annualized_carry: f64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
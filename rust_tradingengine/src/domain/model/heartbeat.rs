@@ -0,0 +1,32 @@
+use serde::{Serialize, Deserialize};
+
+/// Periodic liveness record of the bot itself
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Heartbeat {
+    /// Identifies the running process
+    pub instance_id: String,
+
+    /// Increments with every heartbeat of the instance; gaps mean lost heartbeats
+    pub sequence: i64,
+
+    /// Fingerprint of the current positions, changes whenever any position does
+    pub positions_hash: String,
+
+    /// Last ticker notification (seconds since epoch)
+    pub last_ticker_time: Option<f64>,
+
+    /// Last index notification (seconds since epoch)
+    pub last_index_time: Option<f64>,
+
+    /// Last order update notification (seconds since epoch)
+    pub last_order_time: Option<f64>,
+
+    /// Last own-trade notification (seconds since epoch)
+    pub last_trade_time: Option<f64>,
+
+    /// When the heartbeat was emitted (seconds since epoch)
+    pub timestamp: f64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
@@ -0,0 +1,61 @@
+use serde::{Serialize, Deserialize};
+
+/// Session state change of the bot
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleEventType {
+    Connected,
+    LoggedIn,
+    Subscribed,
+    QuotingStarted,
+    Reconnect,
+    KillSwitch,
+    Shutdown,
+}
+
+impl LifecycleEventType {
+    /// All event types, in Avro enum symbol order
+    pub const ALL: [LifecycleEventType; 7] = [
+        LifecycleEventType::Connected,
+        LifecycleEventType::LoggedIn,
+        LifecycleEventType::Subscribed,
+        LifecycleEventType::QuotingStarted,
+        LifecycleEventType::Reconnect,
+        LifecycleEventType::KillSwitch,
+        LifecycleEventType::Shutdown,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LifecycleEventType::Connected => "connected",
+            LifecycleEventType::LoggedIn => "logged_in",
+            LifecycleEventType::Subscribed => "subscribed",
+            LifecycleEventType::QuotingStarted => "quoting_started",
+            LifecycleEventType::Reconnect => "reconnect",
+            LifecycleEventType::KillSwitch => "kill_switch",
+            LifecycleEventType::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// Structured record of a bot state change, for correlating data gaps with bot state
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LifecycleEvent {
+    /// Identifies the running process
+    pub instance_id: String,
+
+    /// What happened
+    pub event_type: LifecycleEventType,
+
+    /// Why it happened, when there's more to say than the event type
+    pub reason: Option<String>,
+
+    /// Exchange network the bot trades on
+    pub network: String,
+
+    /// When the event happened (seconds since epoch)
+    pub timestamp: f64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
@@ -2,5 +2,16 @@ pub mod exchange;
 pub mod order;
 pub mod quote;
 pub mod ticker;
-pub mod ack;
+pub mod ticker_delta;
 pub mod trade;
+pub mod features;
+pub mod uptime;
+pub mod lifecycle;
+pub mod heartbeat;
+pub mod book;
+pub mod session_summary;
+pub mod funding_basis;
+pub mod account_event;
+pub mod trade_correction;
+pub mod account_summary;
+pub mod bot_state;
@@ -1,58 +1,137 @@
 // Domain model for orders
-use anyhow::{anyhow, Result};
-use serde_json::Value;
+use serde::{Serialize, Deserialize};
 
-use crate::domain::enums::OrderStatus;
-use crate::domain::enums::OrderSide;
+use crate::domain::enums::{OrderSide, OrderStatus, OrderType, TimeInForce};
+use crate::domain::clock;
 
-/// Represents an order with ID, price, amount, and status
-#[derive(Clone, Debug)]
+/// Where an order is from our side: sent and waiting for the exchange, or known to it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OrderState {
+    /// Insert sent, no order notification seen yet
+    Pending,
+    /// Status and amounts come from the exchange
+    #[default]
+    Acknowledged,
+    /// Cancel sent, waiting for the exchange to report the order closed
+    Cancelling,
+}
+
+/// An order as reported by the exchange; the order manager's book and the `ack` topic
+/// both use this representation
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Order {
-    pub id: u64,
-    pub price: f64,
+    /// Exchange order ID (empty while pending)
+    pub order_id: String,
+    
+    /// Client-assigned order ID
+    pub client_order_id: Option<u64>,
+    
+    /// Name of the instrument
+    pub instrument_name: String,
+    
+    /// Order direction (buy/sell)
+    pub direction: OrderSide,
+    
+    /// Order price (if limit order)
+    pub price: Option<f64>,
+    
+    /// Total order amount
     pub amount: f64,
-    pub filled_amount: Option<f64>,
-    pub status: Option<OrderStatus>,
+    
+    /// Amount that has been filled
+    pub filled_amount: f64,
+    
+    /// Amount still in the order book
+    pub remaining_amount: f64,
+    
+    /// Current order status
+    pub status: OrderStatus,
+    
+    /// Order type (limit, market)
+    pub order_type: OrderType,
+    
+    /// Time in force setting
+    pub time_in_force: TimeInForce,
+    
+    /// Reason for order status change
+    pub change_reason: String,
+    
+    /// Reason for order deletion (if applicable)
+    pub delete_reason: Option<String>,
+    
+    /// Reason for order insertion (if applicable)
+    pub insert_reason: Option<String>,
+    
+    /// Order creation timestamp (seconds since epoch)
+    pub create_time: f64,
+    
+    /// Whether order is persistent
+    pub persistent: bool,
+    
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+    
+    /// Experiment variant the order was placed under; not part of the exchange's report
+    #[serde(default)]
+    pub variant_id: Option<String>,
+    
+    /// Local lifecycle state; not part of the exchange's report
+    #[serde(skip)]
+    pub state: OrderState,
 }
 
 impl Order {
-    pub fn new(id: u64, price: f64, amount: f64, status: Option<OrderStatus>) -> Self {
-        Self { id, price, amount, filled_amount: None, status }
+    /// Limit order we're about to insert, before the exchange has acknowledged it
+    pub fn pending(client_order_id: u64, instrument_name: String, direction: OrderSide, price: f64, amount: f64) -> Self {
+        Self {
+            order_id: String::new(),
+            client_order_id: Some(client_order_id),
+            instrument_name,
+            direction,
+            price: Some(price),
+            amount,
+            filled_amount: 0.0,
+            remaining_amount: amount,
+            status: OrderStatus::Open,
+            order_type: OrderType::Limit,
+            time_in_force: TimeInForce::GTC,
+            change_reason: String::new(),
+            delete_reason: None,
+            insert_reason: None,
+            create_time: 0.0,
+            persistent: false,
+            processing_timestamp: None,
+            variant_id: None,
+            state: OrderState::Pending,
+        }
     }
-
+    
+    /// Acknowledged by the exchange and still resting in the book
     pub fn is_open(&self) -> bool {
-        matches!(self.status, Some(OrderStatus::Open) | Some(OrderStatus::PartiallyFilled))
+        self.state == OrderState::Acknowledged
+            && matches!(self.status, OrderStatus::Open | OrderStatus::PartiallyFilled)
+    }
+    
+    /// Acknowledged by the exchange and no longer resting in the book
+    pub fn is_closed(&self) -> bool {
+        self.state == OrderState::Acknowledged && !self.is_open()
+    }
+    
+    /// When this state of the order was reported (seconds since epoch)
+    ///
+    /// Order notifications carry no update time, so this is when the notification was
+    /// received, or the order's creation time for a report not received live.
+    pub fn reported_at(&self) -> f64 {
+        self.processing_timestamp.unwrap_or(self.create_time)
+    }
+    
+    /// Add processing timestamp to the Order
+    pub fn with_processing_timestamp(mut self) -> Self {
+        let now = clock::now_secs();
+            
+        self.processing_timestamp = Some(now);
+        self
     }
-}
-
-/// Helper function to convert order data from JSON
-pub fn order_from_data(data: &Value) -> Result<Order> {
-    let client_order_id = data["client_order_id"].as_u64()
-        .ok_or_else(|| anyhow!("Missing client_order_id"))?;
-    
-    let price = data["price"].as_f64()
-        .ok_or_else(|| anyhow!("Missing price"))?;
-    
-    let amount = data["remaining_amount"].as_f64()
-        .ok_or_else(|| anyhow!("Missing remaining_amount"))?;
-    
-    let filled_amount = data["filled_amount"].as_f64();
-    
-    let status_str = data["status"].as_str()
-        .ok_or_else(|| anyhow!("Missing status"))?;
-    
-    let status = match status_str {
-        "open" => Some(OrderStatus::Open),
-        "partially_filled" => Some(OrderStatus::PartiallyFilled),
-        "cancelled" => Some(OrderStatus::Cancelled),
-        "cancelled_partially_filled" => Some(OrderStatus::CancelledPartiallyFilled),
-        "filled" => Some(OrderStatus::Filled),
-        _ => None,
-    };
-
-    let mut order = Order::new(client_order_id, price, amount, status);
-    order.filled_amount = filled_amount;
-    Ok(order)
 }
 
 /// Helper function to convert OrderSide to string
@@ -12,3 +12,14 @@ impl SideQuote {
         Self { price, amount }
     }
 }
+
+/// Best bid and ask of one instrument, both sent in a single mass quote
+///
+/// The venue keeps one quote per side and instrument: a side left as None stays as it
+/// is, one with a zero amount is pulled.
+#[derive(Clone, Debug)]
+pub struct MassQuote {
+    pub instrument_name: String,
+    pub bid: Option<SideQuote>,
+    pub ask: Option<SideQuote>,
+}
@@ -0,0 +1,48 @@
+use serde::{Serialize, Deserialize};
+
+/// Totals of one bot process run, from start to shutdown
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    /// Identifies the running process
+    pub instance_id: String,
+
+    /// When the process started (seconds since epoch)
+    pub started_at: f64,
+
+    /// When the summary was taken at shutdown (seconds since epoch)
+    pub ended_at: f64,
+
+    /// Quote orders sent
+    pub quotes_sent: i64,
+
+    /// Amends sent
+    pub amends: i64,
+
+    /// Cancels sent for individual quote orders
+    pub cancels: i64,
+
+    /// Our fills
+    pub fills: i64,
+
+    /// Filled amount
+    pub volume: f64,
+
+    /// Realized PnL in quote currency
+    pub realized_pnl: f64,
+
+    /// Largest absolute position reported by the venue
+    pub max_inventory: f64,
+
+    /// Reconnects to the exchange
+    pub reconnects: i64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
+
+impl SessionSummary {
+    /// Length of the run in seconds
+    pub fn duration_secs(&self) -> f64 {
+        (self.ended_at - self.started_at).max(0.0)
+    }
+}
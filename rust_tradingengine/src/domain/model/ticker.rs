@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Result};
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
+use crate::domain::clock;
 
 /// Ticker data structure to represent market data across the application
 /// This is the single, consolidated Ticker model for the entire application
@@ -66,10 +67,7 @@ impl Ticker {
     /// Create a Ticker from JSON notification data
     pub fn from_json(data: &Value, instrument_name: String) -> Result<Self> {
         // Get current time as processing timestamp
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs_f64();
+        let now = clock::now_secs();
             
         Ok(Self {
             instrument_name,
@@ -89,7 +87,8 @@ impl Ticker {
             index_price: data["index"].as_f64().ok_or_else(|| anyhow!("Missing index"))?,
             forward: data.get("forward").and_then(|v| v.as_f64()).unwrap_or(0.0),
             funding_mark: data.get("funding_mark").and_then(|v| v.as_f64()).unwrap_or(0.0),
-            funding_rate: data["funding_rate"].as_f64().ok_or_else(|| anyhow!("Missing funding_rate"))?,
+            // Options and futures don't fund
+            funding_rate: data.get("funding_rate").and_then(|v| v.as_f64()).unwrap_or(0.0),
             collar_low: data.get("collar_low").and_then(|v| v.as_f64()).unwrap_or(0.0),
             collar_high: data.get("collar_high").and_then(|v| v.as_f64()).unwrap_or(0.0),
             realised_funding_24h: data.get("realised_funding_24h").and_then(|v| v.as_f64()).unwrap_or(0.0),
@@ -99,6 +98,91 @@ impl Ticker {
         })
     }
 
+    /// Names of the numeric fields, in schema order
+    pub const NUMERIC_FIELDS: [&'static str; 22] = [
+        "mark_price",
+        "mark_timestamp",
+        "best_bid_price",
+        "best_bid_amount",
+        "best_ask_price",
+        "best_ask_amount",
+        "last_price",
+        "delta",
+        "volume_24h",
+        "value_24h",
+        "low_price_24h",
+        "high_price_24h",
+        "change_24h",
+        "index_price",
+        "forward",
+        "funding_mark",
+        "funding_rate",
+        "collar_low",
+        "collar_high",
+        "realised_funding_24h",
+        "average_funding_rate_24h",
+        "open_interest",
+    ];
+
+    /// Values of the numeric fields, in `NUMERIC_FIELDS` order
+    pub fn numeric_values(&self) -> [f64; 22] {
+        [
+            self.mark_price,
+            self.mark_timestamp,
+            self.best_bid_price,
+            self.best_bid_amount,
+            self.best_ask_price,
+            self.best_ask_amount,
+            self.last_price,
+            self.delta,
+            self.volume_24h,
+            self.value_24h,
+            self.low_price_24h,
+            self.high_price_24h,
+            self.change_24h,
+            self.index_price,
+            self.forward,
+            self.funding_mark,
+            self.funding_rate,
+            self.collar_low,
+            self.collar_high,
+            self.realised_funding_24h,
+            self.average_funding_rate_24h,
+            self.open_interest,
+        ]
+    }
+
+    /// Set a numeric field by name; false when there is no such field
+    pub fn set_numeric(&mut self, name: &str, value: f64) -> bool {
+        let field = match name {
+            "mark_price" => &mut self.mark_price,
+            "mark_timestamp" => &mut self.mark_timestamp,
+            "best_bid_price" => &mut self.best_bid_price,
+            "best_bid_amount" => &mut self.best_bid_amount,
+            "best_ask_price" => &mut self.best_ask_price,
+            "best_ask_amount" => &mut self.best_ask_amount,
+            "last_price" => &mut self.last_price,
+            "delta" => &mut self.delta,
+            "volume_24h" => &mut self.volume_24h,
+            "value_24h" => &mut self.value_24h,
+            "low_price_24h" => &mut self.low_price_24h,
+            "high_price_24h" => &mut self.high_price_24h,
+            "change_24h" => &mut self.change_24h,
+            "index_price" => &mut self.index_price,
+            "forward" => &mut self.forward,
+            "funding_mark" => &mut self.funding_mark,
+            "funding_rate" => &mut self.funding_rate,
+            "collar_low" => &mut self.collar_low,
+            "collar_high" => &mut self.collar_high,
+            "realised_funding_24h" => &mut self.realised_funding_24h,
+            "average_funding_rate_24h" => &mut self.average_funding_rate_24h,
+            "open_interest" => &mut self.open_interest,
+            _ => return false,
+        };
+        *field = value;
+        true
+    }
+
     /// Get best bid price, returning None if not available
     pub fn best_bid(&self) -> Option<f64> {
         if self.best_bid_price > 0.0 {
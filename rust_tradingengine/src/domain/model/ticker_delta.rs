@@ -0,0 +1,60 @@
+use serde::{Serialize, Deserialize};
+
+use super::ticker::Ticker;
+
+/// Whether a ticker delta record carries every field or only the changed ones
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TickerDeltaKind {
+    Snapshot,
+    Delta,
+}
+
+impl TickerDeltaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TickerDeltaKind::Snapshot => "snapshot",
+            TickerDeltaKind::Delta => "delta",
+        }
+    }
+}
+
+/// Ticker fields that changed since the instrument's previous record
+///
+/// The ticker at a given sequence is the latest snapshot with every later delta up to
+/// it applied; fields missing from a delta kept their previous value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TickerDelta {
+    /// Name of the instrument
+    pub instrument_name: String,
+
+    pub kind: TickerDeltaKind,
+
+    /// Ticker notification the record came from, increasing per instrument
+    pub sequence: i64,
+
+    /// Changed numeric fields as (name, value), in `Ticker::NUMERIC_FIELDS` order;
+    /// every field in a snapshot
+    pub changes: Vec<(String, f64)>,
+
+    /// When the notification was received (seconds since epoch)
+    pub timestamp: f64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
+
+impl TickerDelta {
+    /// New value of a field, None when it didn't change
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.changes.iter().find(|(field, _)| field == name).map(|(_, value)| *value)
+    }
+
+    /// Bring `ticker` up to date with this record
+    pub fn apply(&self, ticker: &mut Ticker) {
+        for (name, value) in &self.changes {
+            ticker.set_numeric(name, *value);
+        }
+        ticker.processing_timestamp = self.processing_timestamp;
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt;
 use serde::{Serialize, Deserialize};
 
+use crate::domain::enums::MakerTaker;
+use crate::domain::clock;
+
 /// Represents a trade (fill) execution
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Trade {
@@ -22,14 +25,18 @@ pub struct Trade {
     /// Amount of the instrument that was traded
     pub amount: f64,
     
-    /// Whether the trade was as a maker or taker
-    pub maker_taker: String,
+    /// Whether the trade was as a maker or taker; None when the exchange didn't say
+    pub maker_taker: Option<MakerTaker>,
     
     /// Timestamp of the trade (seconds since epoch with decimal precision)
     pub time: f64,
     
     /// Timestamp when record was processed by Rust system (seconds since epoch)
     pub processing_timestamp: Option<f64>,
+    
+    /// Experiment variant of the filled order; not part of the exchange's report
+    #[serde(default)]
+    pub variant_id: Option<String>,
 }
 
 impl fmt::Display for Trade {
@@ -51,12 +58,14 @@ impl Trade {
         }
     }
     
+    /// Maker/taker role as written to the `maker_taker` string field
+    pub fn maker_taker_str(&self) -> &'static str {
+        self.maker_taker.map_or("unknown", |role| role.as_str())
+    }
+    
     /// Create a new Trade with current processing_timestamp
     pub fn with_processing_timestamp(mut self) -> Self {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs_f64();
+        let now = clock::now_secs();
             
         self.processing_timestamp = Some(now);
         self
@@ -0,0 +1,56 @@
+use serde::{Serialize, Deserialize};
+
+use crate::domain::enums::OrderSide;
+
+/// How the exchange revised a trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrectionKind {
+    /// The trade was cancelled and never happened
+    Bust,
+    /// The trade stands at a different price or amount
+    Correction,
+}
+
+impl CorrectionKind {
+    /// All kinds, in Avro enum symbol order
+    pub const ALL: [CorrectionKind; 2] = [CorrectionKind::Bust, CorrectionKind::Correction];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CorrectionKind::Bust => "bust",
+            CorrectionKind::Correction => "correction",
+        }
+    }
+}
+
+/// A bust or correction of one of our trades after it was reported
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TradeCorrection {
+    pub kind: CorrectionKind,
+
+    /// Trade being revised
+    pub trade_id: String,
+    pub order_id: Option<String>,
+    pub instrument_name: Option<String>,
+
+    /// Side of our fill, from the notification or the journaled fill
+    pub direction: Option<OrderSide>,
+
+    /// Price and amount the trade was first reported at
+    pub original_price: Option<f64>,
+    pub original_amount: Option<f64>,
+
+    /// Price and amount that replace the original, for corrections
+    pub corrected_price: Option<f64>,
+    pub corrected_amount: Option<f64>,
+
+    /// Text of the exchange's notification
+    pub message: Option<String>,
+
+    /// When the exchange reported the revision (seconds since epoch)
+    pub timestamp: f64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
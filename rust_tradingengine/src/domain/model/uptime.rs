@@ -0,0 +1,32 @@
+use serde::{Serialize, Deserialize};
+
+/// Share of time the bot kept two-sided quotes near the mark, over one UTC day
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuoteUptime {
+    /// Name of the instrument
+    pub instrument_name: String,
+
+    /// UTC day the statistics cover (YYYY-MM-DD)
+    pub date: String,
+
+    /// Start of the measured period (seconds since epoch)
+    pub period_start: f64,
+
+    /// End of the measured period (seconds since epoch)
+    pub period_end: f64,
+
+    /// Seconds observed in the period
+    pub observed_secs: f64,
+
+    /// Seconds with open bid and ask orders within the allowed distance of the mark
+    pub compliant_secs: f64,
+
+    /// compliant_secs / observed_secs as a percentage
+    pub uptime_pct: f64,
+
+    /// Maximum distance from the mark (in ticks) that counts as quoting
+    pub max_distance_ticks: f64,
+
+    /// Timestamp when record was processed by Rust system (seconds since epoch)
+    pub processing_timestamp: Option<f64>,
+}
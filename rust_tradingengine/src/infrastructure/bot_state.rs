@@ -0,0 +1,140 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+
+use crate::config_loader::BotStateConfig;
+use crate::domain::clock;
+use crate::domain::model::bot_state::{BotState, StateTransition};
+use crate::infrastructure::metrics;
+
+/// Gauge of the current state, its position in `BotState::ALL`
+pub const METRIC_BOT_STATE: &str = "bot.state";
+
+/// Gauge of when the current state was entered (seconds since epoch)
+pub const METRIC_BOT_STATE_SINCE: &str = "bot.state_since";
+
+/// Counter of transitions, suffixed with the state moved to
+pub const METRIC_STATE_TRANSITIONS: &str = "bot.state_transitions";
+
+/// Counter of transitions refused because the current state doesn't allow them
+pub const METRIC_STATE_TRANSITIONS_REFUSED: &str = "bot.state_transitions_refused";
+
+/// The bot's state, shared by every task and kept across sessions
+///
+/// Tasks ask the machine what they may do instead of deriving it from flags of their
+/// own, and move it on as the session progresses. Every transition is logged,
+/// published as gauges and, with a journal, appended to it with its reason so the
+/// history survives the process.
+pub struct BotStateMachine {
+    instance_id: String,
+    current: RwLock<(BotState, f64)>,
+    journal: Option<PathBuf>,
+}
+
+impl BotStateMachine {
+    /// Machine in `Connecting`, appending its transitions to `journal` if given
+    pub fn new(instance_id: impl Into<String>, journal: Option<PathBuf>) -> Self {
+        let since = clock::now_secs();
+        publish(BotState::Connecting, since);
+        Self { instance_id: instance_id.into(), current: RwLock::new((BotState::Connecting, since)), journal }
+    }
+
+    pub fn from_config(config: &BotStateConfig, instance_id: impl Into<String>) -> Self {
+        let journal = config.enabled.then(|| PathBuf::from(&config.path));
+        if let Some(path) = &journal {
+            match Self::last_transition(path) {
+                Ok(Some(last)) => info!("Previous run ended {} at {:.0}: {}", last.to.as_str(), last.timestamp, last.reason),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to read the state journal: {:#}", e),
+            }
+        }
+        Self::new(instance_id, journal)
+    }
+
+    pub fn current(&self) -> BotState {
+        self.current.read().unwrap().0
+    }
+
+    /// When the current state was entered (seconds since epoch)
+    pub fn since(&self) -> f64 {
+        self.current.read().unwrap().1
+    }
+
+    /// Whether new quotes may be sent
+    pub fn may_quote(&self) -> bool {
+        self.current().may_quote()
+    }
+
+    /// Whether open orders may be cancelled
+    pub fn may_cancel(&self) -> bool {
+        self.current().may_cancel()
+    }
+
+    /// Move to `to` because of `reason`; returns the state left, None when already in `to`
+    ///
+    /// Fails, leaving the state as it is, when the current state doesn't lead to `to`.
+    pub fn transition(&self, to: BotState, reason: &str) -> Result<Option<BotState>> {
+        let transition = {
+            let mut current = self.current.write().unwrap();
+            let from = current.0;
+            if from == to {
+                return Ok(None);
+            }
+            if !from.can_transition_to(to) {
+                metrics::global().incr(METRIC_STATE_TRANSITIONS_REFUSED, 1);
+                return Err(anyhow!("Can't move from {} to {} ({})", from.as_str(), to.as_str(), reason));
+            }
+            let now = clock::now_secs();
+            *current = (to, now);
+            StateTransition { instance_id: self.instance_id.clone(), from, to, reason: reason.to_string(), timestamp: now }
+        };
+
+        info!("State: {} -> {} ({})", transition.from.as_str(), to.as_str(), reason);
+        publish(to, transition.timestamp);
+        metrics::global().incr(&format!("{}.{}", METRIC_STATE_TRANSITIONS, to.as_str()), 1);
+        if let Some(path) = &self.journal {
+            // The state has changed either way; a lost record only shortens the history
+            if let Err(e) = Self::append(path, &transition) {
+                warn!("Failed to persist the state transition: {:#}", e);
+            }
+        }
+        Ok(Some(transition.from))
+    }
+
+    /// Append `transition` to the journal at `path`, one JSON record per line
+    pub fn append(path: &Path, transition: &StateTransition) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(transition)?)?;
+        Ok(())
+    }
+
+    /// Transitions journaled at `path`, oldest first; empty when the file doesn't exist yet
+    pub fn history(path: &Path) -> Result<Vec<StateTransition>> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        content.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).with_context(|| format!("Invalid state transition in {}", path.display())))
+            .collect()
+    }
+
+    /// Last transition journaled at `path`
+    pub fn last_transition(path: &Path) -> Result<Option<StateTransition>> {
+        Ok(Self::history(path)?.pop())
+    }
+}
+
+fn publish(state: BotState, since: f64) {
+    metrics::global().set_gauge(METRIC_BOT_STATE, state.index() as f64);
+    metrics::global().set_gauge(METRIC_BOT_STATE_SINCE, since);
+}
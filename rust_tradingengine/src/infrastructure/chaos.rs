@@ -0,0 +1,139 @@
+use log::debug;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+
+use crate::config_loader::{ChaosConfig, FaultConfig};
+use crate::infrastructure::metrics;
+
+/// Inbound messages dropped by fault injection
+pub const METRIC_CHAOS_DROPPED: &str = "chaos.dropped";
+
+/// Inbound messages delivered out of order by fault injection
+pub const METRIC_CHAOS_REORDERED: &str = "chaos.reordered";
+
+/// Degrades the inbound message stream with latency, drops and reordering
+///
+/// Sits between the WebSocket reader and the strategy's routing so throttling,
+/// staleness and reconnect handling can be exercised against a misbehaving feed.
+pub struct ChaosLayer {
+    config: ChaosConfig,
+    rng: StdRng,
+
+    /// Message held back to be delivered after the next one
+    held: Option<String>,
+}
+
+impl ChaosLayer {
+    pub fn new(config: ChaosConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self { config, rng, held: None }
+    }
+
+    /// Messages to deliver in place of `msg`, in order
+    ///
+    /// Empty when the message is dropped or held back; a held message is released
+    /// right after the next one that gets through.
+    pub fn plan(&mut self, msg: String) -> Vec<String> {
+        if self.rng.gen_bool(self.config.drop_rate.clamp(0.0, 1.0)) {
+            debug!("Chaos: dropping message");
+            metrics::global().incr(METRIC_CHAOS_DROPPED, 1);
+            return self.held.take().into_iter().collect();
+        }
+
+        match self.held.take() {
+            Some(held) => {
+                metrics::global().incr(METRIC_CHAOS_REORDERED, 1);
+                vec![msg, held]
+            }
+            None if self.rng.gen_bool(self.config.reorder_rate.clamp(0.0, 1.0)) => {
+                debug!("Chaos: holding message back");
+                self.held = Some(msg);
+                Vec::new()
+            }
+            None => vec![msg],
+        }
+    }
+
+    /// Latency to add before delivering, base plus uniform jitter
+    pub fn delay(&mut self) -> Duration {
+        let jitter = match self.config.jitter_ms {
+            0 => 0,
+            jitter => self.rng.gen_range(0..=jitter),
+        };
+        Duration::from_millis(self.config.latency_ms + jitter)
+    }
+
+    /// Apply drops and reordering to `msg`, sleeping for the injected latency
+    pub async fn inject(&mut self, msg: String) -> Vec<String> {
+        let messages = self.plan(msg);
+        if !messages.is_empty() {
+            tokio::time::sleep(self.delay()).await;
+        }
+        messages
+    }
+}
+
+/// Downstream service a fault is injected into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultTarget {
+    Kafka,
+    Registry,
+}
+
+/// Random errors, delays and periodic outages for calls to one downstream service
+pub struct FaultInjector {
+    target: FaultTarget,
+    config: FaultConfig,
+    rng: std::sync::Mutex<StdRng>,
+    started: std::time::Instant,
+}
+
+impl FaultInjector {
+    pub fn new(target: FaultTarget, config: FaultConfig, seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            target,
+            config,
+            rng: std::sync::Mutex::new(rng),
+            started: std::time::Instant::now(),
+        }
+    }
+
+    pub fn target(&self) -> FaultTarget {
+        self.target
+    }
+
+    /// Whether the service is inside an outage window `elapsed` after start
+    ///
+    /// Outages last `outage_sec` at the start of every `outage_every_sec` period.
+    pub fn is_unavailable(&self, elapsed: Duration) -> bool {
+        if self.config.outage_every_sec == 0 || self.config.outage_sec == 0 {
+            return false;
+        }
+        elapsed.as_secs() % self.config.outage_every_sec < self.config.outage_sec
+    }
+
+    /// Delay the call, then fail it if the service is down or a random error fires
+    pub async fn check(&self) -> anyhow::Result<()> {
+        if self.config.delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.config.delay_ms)).await;
+        }
+        if self.is_unavailable(self.started.elapsed()) {
+            metrics::global().incr(&format!("chaos.{:?}.unavailable", self.target).to_lowercase(), 1);
+            return Err(anyhow::anyhow!("Injected {:?} outage", self.target));
+        }
+        let fail = self.rng.lock().unwrap().gen_bool(self.config.error_rate.clamp(0.0, 1.0));
+        if fail {
+            metrics::global().incr(&format!("chaos.{:?}.errors", self.target).to_lowercase(), 1);
+            return Err(anyhow::anyhow!("Injected {:?} error", self.target));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,401 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use log::debug;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tungstenite::Message;
+use url::Url;
+
+use crate::domain::enums::{OrderSide, OrderType, TimeInForce};
+use crate::domain::model::exchange::OrderRequest;
+use crate::infrastructure::exchange::{ExchangeClient, OrderGateway, Venue};
+
+use super::rest::BybitRestClient;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Private topics, which are subscribed on the authenticated socket
+const PRIVATE_TOPICS: &[&str] = &["order", "execution", "position", "wallet"];
+
+/// Window a WebSocket order request stays valid after its timestamp (ms)
+const RECV_WINDOW_MS: u64 = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BybitNetwork {
+    Testnet,
+    Mainnet,
+}
+
+impl BybitNetwork {
+    /// Base of the WebSocket endpoints
+    pub fn ws_url(&self) -> &'static str {
+        match self {
+            BybitNetwork::Testnet => "wss://stream-testnet.bybit.com",
+            BybitNetwork::Mainnet => "wss://stream.bybit.com",
+        }
+    }
+
+    pub fn rest_url(&self) -> &'static str {
+        match self {
+            BybitNetwork::Testnet => "https://api-testnet.bybit.com",
+            BybitNetwork::Mainnet => "https://api.bybit.com",
+        }
+    }
+
+    /// Suffix of the per-network credential environment variables
+    pub fn env_suffix(&self) -> &'static str {
+        match self {
+            BybitNetwork::Testnet => "TEST",
+            BybitNetwork::Mainnet => "PROD",
+        }
+    }
+}
+
+impl std::str::FromStr for BybitNetwork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "test" | "testnet" => Ok(BybitNetwork::Testnet),
+            "prod" | "mainnet" => Ok(BybitNetwork::Mainnet),
+            _ => Err(anyhow!("Unknown Bybit network: {}", s)),
+        }
+    }
+}
+
+/// Whether a topic is one of the account topics, e.g. `order` or `position.linear`
+pub fn is_private_topic(topic: &str) -> bool {
+    let base = topic.split('.').next().unwrap_or(topic);
+    PRIVATE_TOPICS.contains(&base)
+}
+
+/// Bybit v5 connector for one product category (perpetuals are `linear`)
+///
+/// Bybit splits its WebSocket API over three sockets: public market data, private
+/// account topics and the trade socket orders are sent on. They are opened together
+/// and read as one stream. Order requests name their instrument, so the symbol of each
+/// client order id is remembered from its insert for later amends and cancels.
+/// Requests the WebSocket API doesn't serve go over REST, and their results are
+/// queued as `{"reqId": .., "op": .., "data": ..}` messages for `receive`.
+pub struct BybitClient {
+    category: String,
+    settle_coin: String,
+    public: Option<WsStream>,
+    private: Option<WsStream>,
+    trade: Option<WsStream>,
+    rest: Option<BybitRestClient>,
+    inbox: VecDeque<String>,
+    symbols: HashMap<u64, String>,
+}
+
+impl BybitClient {
+    /// Client for USDT-settled linear perpetuals
+    pub fn new() -> Self {
+        Self::for_category("linear", "USDT")
+    }
+
+    pub fn for_category(category: &str, settle_coin: &str) -> Self {
+        Self {
+            category: category.to_string(),
+            settle_coin: settle_coin.to_string(),
+            public: None,
+            private: None,
+            trade: None,
+            rest: None,
+            inbox: VecDeque::new(),
+            symbols: HashMap::new(),
+        }
+    }
+
+    /// REST client used for open orders and cancel-all
+    pub fn set_rest(&mut self, rest: BybitRestClient) {
+        self.rest = Some(rest);
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    /// Symbol an order was inserted for, by client order id
+    pub fn symbol_of(&self, client_order_id: u64) -> Option<&str> {
+        self.symbols.get(&client_order_id).map(String::as_str)
+    }
+
+    /// Keep the connections alive; Bybit drops sockets without a ping for 20 seconds
+    pub async fn ping(&mut self) -> Result<()> {
+        let ping = json!({"op": "ping"}).to_string();
+        for socket in [&mut self.public, &mut self.private, &mut self.trade].into_iter().flatten() {
+            socket.send(Message::Text(ping.clone())).await?;
+        }
+        Ok(())
+    }
+
+    /// `order.create` request on the trade socket
+    pub fn create_request(&self, order: &OrderRequest, id: Option<u64>) -> Value {
+        let mut args = json!({
+            "category": self.category,
+            "symbol": order.symbol,
+            "side": match order.side {
+                OrderSide::Buy => "Buy",
+                OrderSide::Sell => "Sell",
+            },
+            "orderType": match order.order_type {
+                OrderType::Limit => "Limit",
+                OrderType::Market => "Market",
+            },
+            "qty": order.quantity.to_string(),
+            "timeInForce": match order.time_in_force {
+                Some(TimeInForce::IOC) => "IOC",
+                _ => "GTC",
+            },
+        });
+        if let Some(price) = order.price {
+            args["price"] = json!(price.to_string());
+        }
+        if let Some(cid) = order.client_order_id {
+            args["orderLinkId"] = json!(cid.to_string());
+        }
+        Self::trade_request("order.create", args, id)
+    }
+
+    /// `order.amend` request; only the parts given are changed
+    pub fn amend_request(
+        &self,
+        symbol: &str,
+        quantity: Option<f64>,
+        price: Option<f64>,
+        order_id: Option<String>,
+        client_order_id: Option<u64>,
+        id: Option<u64>,
+    ) -> Value {
+        let mut args = Self::order_ref(&self.category, symbol, order_id, client_order_id);
+        if let Some(quantity) = quantity {
+            args["qty"] = json!(quantity.to_string());
+        }
+        if let Some(price) = price {
+            args["price"] = json!(price.to_string());
+        }
+        Self::trade_request("order.amend", args, id)
+    }
+
+    /// `order.cancel` request
+    pub fn cancel_request(&self, symbol: &str, order_id: Option<String>, client_order_id: Option<u64>, id: Option<u64>) -> Value {
+        Self::trade_request("order.cancel", Self::order_ref(&self.category, symbol, order_id, client_order_id), id)
+    }
+
+    fn order_ref(category: &str, symbol: &str, order_id: Option<String>, client_order_id: Option<u64>) -> Value {
+        let mut args = json!({"category": category, "symbol": symbol});
+        match (order_id, client_order_id) {
+            (Some(order_id), _) => args["orderId"] = json!(order_id),
+            (None, Some(cid)) => args["orderLinkId"] = json!(cid.to_string()),
+            (None, None) => {}
+        }
+        args
+    }
+
+    fn trade_request(op: &str, args: Value, id: Option<u64>) -> Value {
+        let mut request = json!({
+            "header": {
+                "X-BAPI-TIMESTAMP": chrono::Utc::now().timestamp_millis().to_string(),
+                "X-BAPI-RECV-WINDOW": RECV_WINDOW_MS.to_string(),
+            },
+            "op": op,
+            "args": [args],
+        });
+        if let Some(id) = id {
+            request["reqId"] = json!(id.to_string());
+        }
+        request
+    }
+
+    /// Symbol of an order being amended or cancelled
+    fn order_symbol(&self, order_id: &Option<String>, client_order_id: Option<u64>) -> Result<String> {
+        match client_order_id.and_then(|cid| self.symbols.get(&cid)) {
+            Some(symbol) => Ok(symbol.clone()),
+            None => Err(anyhow!(
+                "Unknown symbol for order {}",
+                order_id.clone().or(client_order_id.map(|cid| cid.to_string())).unwrap_or_default()
+            )),
+        }
+    }
+
+    async fn write(socket: &mut Option<WsStream>, name: &str, request: &Value) -> Result<()> {
+        let Some(socket) = socket else {
+            return Err(anyhow!("Bybit {} socket not connected", name));
+        };
+        socket.send(Message::Text(request.to_string())).await?;
+        Ok(())
+    }
+
+    fn rest(&self) -> Result<&BybitRestClient> {
+        self.rest.as_ref().ok_or_else(|| anyhow!("Bybit REST client not configured"))
+    }
+
+    /// Queue a REST result for `receive`
+    fn deliver(&mut self, op: &str, id: Option<u64>, data: Value) {
+        self.inbox.push_back(json!({"reqId": id.map(|id| id.to_string()), "op": op, "data": data}).to_string());
+    }
+}
+
+impl Default for BybitClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Next frame of a socket; never resolves when the socket isn't open
+async fn next_frame(socket: &mut Option<WsStream>) -> Option<Result<Message, tungstenite::Error>> {
+    match socket {
+        Some(socket) => socket.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[async_trait]
+impl OrderGateway for BybitClient {
+    async fn insert(&mut self, order: OrderRequest, id: Option<u64>) -> Result<()> {
+        let request = self.create_request(&order, id);
+        Self::write(&mut self.trade, "trade", &request).await?;
+        if let Some(cid) = order.client_order_id {
+            self.symbols.insert(cid, order.symbol);
+        }
+        Ok(())
+    }
+
+    async fn amend(
+        &mut self,
+        amount: f64,
+        price: f64,
+        order_id: Option<String>,
+        client_order_id: Option<u64>,
+        id: Option<u64>,
+    ) -> Result<()> {
+        let symbol = self.order_symbol(&order_id, client_order_id)?;
+        let request = self.amend_request(&symbol, Some(amount), Some(price), order_id, client_order_id, id);
+        Self::write(&mut self.trade, "trade", &request).await
+    }
+
+    async fn cancel(&mut self, order_id: Option<String>, client_order_id: Option<u64>, id: Option<u64>) -> Result<()> {
+        let symbol = self.order_symbol(&order_id, client_order_id)?;
+        let request = self.cancel_request(&symbol, order_id, client_order_id, id);
+        Self::write(&mut self.trade, "trade", &request).await
+    }
+
+    async fn open_orders(&mut self, id: Option<u64>) -> Result<()> {
+        let orders = self.rest()?.open_orders(&self.category, &self.settle_coin).await?;
+        self.deliver("open_orders", id, Value::Array(orders));
+        Ok(())
+    }
+
+    /// Bybit orders aren't tied to a session, so this cancels every open order
+    async fn cancel_session(&mut self, id: Option<u64>) -> Result<()> {
+        ExchangeClient::cancel_all(self, id).await
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for BybitClient {
+    fn venue(&self) -> Venue {
+        Venue::Bybit
+    }
+
+    /// Open the public, private and trade sockets under the base URL, e.g. `BybitNetwork::ws_url`
+    async fn connect(&mut self, url: &str) -> Result<()> {
+        let base = url.trim_end_matches('/');
+        let (public, _) = connect_async(Url::parse(&format!("{}/v5/public/{}", base, self.category))?).await?;
+        let (private, _) = connect_async(Url::parse(&format!("{}/v5/private", base))?).await?;
+        let (trade, _) = connect_async(Url::parse(&format!("{}/v5/trade", base))?).await?;
+        self.public = Some(public);
+        self.private = Some(private);
+        self.trade = Some(trade);
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        if !self.connected() {
+            return Err(anyhow!("Bybit sockets not connected"));
+        }
+        for socket in [self.public.take(), self.private.take(), self.trade.take()].into_iter().flatten() {
+            let mut socket = socket;
+            socket.close(None).await?;
+        }
+        Ok(())
+    }
+
+    fn connected(&self) -> bool {
+        self.public.is_some() || self.private.is_some() || self.trade.is_some()
+    }
+
+    /// Authenticate the private and trade sockets; `token` is `BybitKeys::make_auth_token`
+    async fn login(&mut self, token: String, _account: Option<String>, id: Option<u64>) -> Result<()> {
+        let args: Value = serde_json::from_str(&token).map_err(|e| anyhow!("Invalid Bybit auth token: {}", e))?;
+        let mut request = json!({"op": "auth", "args": args});
+        if let Some(id) = id {
+            request["req_id"] = json!(id.to_string());
+        }
+        Self::write(&mut self.private, "private", &request).await?;
+        Self::write(&mut self.trade, "trade", &request).await
+    }
+
+    async fn subscribe(&mut self, channels: Vec<String>, private: bool, id: Option<u64>) -> Result<()> {
+        let mut request = json!({"op": "subscribe", "args": channels});
+        if let Some(id) = id {
+            request["req_id"] = json!(id.to_string());
+        }
+        if private {
+            Self::write(&mut self.private, "private", &request).await
+        } else {
+            Self::write(&mut self.public, "public", &request).await
+        }
+    }
+
+    async fn unsubscribe(&mut self, channels: Vec<String>, id: Option<u64>) -> Result<()> {
+        let (private, public): (Vec<String>, Vec<String>) = channels.into_iter().partition(|c| is_private_topic(c));
+        for (topics, socket, name) in [(private, &mut self.private, "private"), (public, &mut self.public, "public")] {
+            if topics.is_empty() {
+                continue;
+            }
+            let mut request = json!({"op": "unsubscribe", "args": topics});
+            if let Some(id) = id {
+                request["req_id"] = json!(id.to_string());
+            }
+            Self::write(socket, name, &request).await?;
+        }
+        Ok(())
+    }
+
+    /// Next text message from any of the sockets, or a queued REST result
+    ///
+    /// Returns `Ok(None)` for control frames and an error once a socket has closed.
+    async fn receive(&mut self) -> Result<Option<String>> {
+        if let Some(message) = self.inbox.pop_front() {
+            return Ok(Some(message));
+        }
+        if !self.connected() {
+            return Err(anyhow!("Bybit sockets not connected"));
+        }
+        let frame = tokio::select! {
+            frame = next_frame(&mut self.public) => frame,
+            frame = next_frame(&mut self.private) => frame,
+            frame = next_frame(&mut self.trade) => frame,
+        };
+        match frame {
+            Some(Ok(Message::Text(text))) => Ok(Some(text)),
+            Some(Ok(other)) => {
+                debug!("Ignoring Bybit frame {:?}", other);
+                Ok(None)
+            }
+            Some(Err(e)) => Err(anyhow!("WebSocket error: {}", e)),
+            None => Err(anyhow!("WebSocket stream ended")),
+        }
+    }
+
+    async fn cancel_all(&mut self, id: Option<u64>) -> Result<()> {
+        let result = self.rest()?.cancel_all(&self.category, &self.settle_coin).await?;
+        self.deliver("cancel_all", id, result);
+        Ok(())
+    }
+}
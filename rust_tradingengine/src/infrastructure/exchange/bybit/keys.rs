@@ -0,0 +1,50 @@
+use anyhow::{anyhow, Result};
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+
+use super::client::BybitNetwork;
+
+/// API key and secret of a Bybit account
+#[derive(Clone)]
+pub struct BybitKeys {
+    pub api_key: String,
+    api_secret: String,
+}
+
+impl std::fmt::Debug for BybitKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BybitKeys").field("api_key", &self.api_key).finish_non_exhaustive()
+    }
+}
+
+impl BybitKeys {
+    pub fn new(api_key: &str, api_secret: &str) -> Self {
+        Self { api_key: api_key.to_string(), api_secret: api_secret.to_string() }
+    }
+
+    /// Keys of the network from `BYBIT_API_KEY_<NET>` and `BYBIT_API_SECRET_<NET>`
+    pub fn load(network: &BybitNetwork) -> Result<Self> {
+        let key_var = format!("BYBIT_API_KEY_{}", network.env_suffix());
+        let secret_var = format!("BYBIT_API_SECRET_{}", network.env_suffix());
+        let api_key = std::env::var(&key_var).map_err(|_| anyhow!("Missing {}", key_var))?;
+        let api_secret = std::env::var(&secret_var).map_err(|_| anyhow!("Missing {}", secret_var))?;
+        Ok(Self { api_key, api_secret })
+    }
+
+    /// Hex HMAC-SHA256 of `payload` under the API secret
+    pub fn sign(&self, payload: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.api_secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Arguments of the WebSocket `auth` operation, valid until `expires_ms`
+    ///
+    /// Returned as JSON text so it can be passed to `ExchangeClient::login` as the token.
+    pub fn make_auth_token(&self, expires_ms: i64) -> String {
+        let signature = self.sign(&format!("GET/realtime{}", expires_ms));
+        json!([self.api_key, expires_ms, signature]).to_string()
+    }
+}
@@ -0,0 +1,9 @@
+pub mod client;
+pub mod keys;
+pub mod parsers;
+pub mod rest;
+
+pub use client::{BybitClient, BybitNetwork};
+pub use keys::BybitKeys;
+pub use parsers::BybitParser;
+pub use rest::BybitRestClient;
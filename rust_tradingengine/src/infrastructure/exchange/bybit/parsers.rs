@@ -0,0 +1,170 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::domain::enums::{MakerTaker, OrderSide, OrderStatus, OrderType, TimeInForce};
+use crate::domain::model::order::{Order, OrderState};
+use crate::domain::model::ticker::Ticker;
+use crate::domain::model::trade::Trade;
+use crate::domain::clock;
+
+/// Maps Bybit v5 messages onto the domain models
+///
+/// Bybit sends numbers as strings and times in milliseconds; the models use floats
+/// and seconds.
+pub struct BybitParser;
+
+impl BybitParser {
+    /// Number sent either as a string or as a JSON number; None when missing or empty
+    fn number(data: &Value, key: &str) -> Option<f64> {
+        match data.get(key)? {
+            Value::String(s) if s.is_empty() => None,
+            Value::String(s) => s.parse().ok(),
+            value => value.as_f64(),
+        }
+    }
+
+    fn required_number(data: &Value, key: &str) -> Result<f64> {
+        Self::number(data, key).ok_or_else(|| anyhow!("Missing {}", key))
+    }
+
+    fn required_str<'a>(data: &'a Value, key: &str) -> Result<&'a str> {
+        data.get(key).and_then(|v| v.as_str()).ok_or_else(|| anyhow!("Missing {}", key))
+    }
+
+    /// Milliseconds field as seconds
+    fn seconds(data: &Value, key: &str) -> Option<f64> {
+        Self::number(data, key).map(|ms| ms / 1000.0)
+    }
+
+    fn now() -> f64 {
+        clock::now_secs()
+    }
+
+    fn side(data: &Value) -> Result<OrderSide> {
+        match Self::required_str(data, "side")? {
+            "Buy" => Ok(OrderSide::Buy),
+            "Sell" => Ok(OrderSide::Sell),
+            other => Err(anyhow!("Unknown side: {}", other)),
+        }
+    }
+
+    /// Client order id from `orderLinkId`, when it is one of ours
+    fn client_order_id(data: &Value) -> Option<u64> {
+        data.get("orderLinkId").and_then(|v| v.as_str()).and_then(|id| id.parse().ok())
+    }
+
+    /// Apply a `tickers.<symbol>` snapshot or delta to `ticker`; a delta carries only
+    /// the fields that changed, so the others keep their values
+    pub fn apply_ticker(data: &Value, ts_ms: Option<f64>, ticker: &mut Ticker) {
+        let fields: [(&str, &mut f64); 14] = [
+            ("markPrice", &mut ticker.mark_price),
+            ("bid1Price", &mut ticker.best_bid_price),
+            ("bid1Size", &mut ticker.best_bid_amount),
+            ("ask1Price", &mut ticker.best_ask_price),
+            ("ask1Size", &mut ticker.best_ask_amount),
+            ("lastPrice", &mut ticker.last_price),
+            ("indexPrice", &mut ticker.index_price),
+            ("volume24h", &mut ticker.volume_24h),
+            ("turnover24h", &mut ticker.value_24h),
+            ("highPrice24h", &mut ticker.high_price_24h),
+            ("lowPrice24h", &mut ticker.low_price_24h),
+            ("price24hPcnt", &mut ticker.change_24h),
+            ("fundingRate", &mut ticker.funding_rate),
+            ("openInterest", &mut ticker.open_interest),
+        ];
+        for (key, field) in fields {
+            if let Some(value) = Self::number(data, key) {
+                *field = value;
+            }
+        }
+        if let Some(ts) = ts_ms {
+            ticker.mark_timestamp = ts / 1000.0;
+        }
+        ticker.processing_timestamp = Some(Self::now());
+    }
+
+    /// Ticker from a `tickers.<symbol>` snapshot
+    pub fn parse_ticker(data: &Value, ts_ms: Option<f64>) -> Result<Ticker> {
+        let mut ticker = Ticker::new(Self::required_str(data, "symbol")?.to_string());
+        // Perpetuals move one for one with the underlying
+        ticker.delta = 1.0;
+        Self::apply_ticker(data, ts_ms, &mut ticker);
+        Ok(ticker)
+    }
+
+    /// Order from an `order` topic entry or an open orders listing
+    pub fn parse_order(data: &Value) -> Result<Order> {
+        let amount = Self::required_number(data, "qty")?;
+        let filled_amount = Self::number(data, "cumExecQty").unwrap_or_default();
+        let status = match Self::required_str(data, "orderStatus")? {
+            "New" | "Created" | "Untriggered" | "Triggered" => OrderStatus::Open,
+            "PartiallyFilled" => OrderStatus::PartiallyFilled,
+            "Filled" => OrderStatus::Filled,
+            "PartiallyFilledCanceled" => OrderStatus::CancelledPartiallyFilled,
+            "Cancelled" | "Rejected" | "Deactivated" if filled_amount > 0.0 => OrderStatus::CancelledPartiallyFilled,
+            "Cancelled" | "Rejected" | "Deactivated" => OrderStatus::Cancelled,
+            other => return Err(anyhow!("Unknown order status: {}", other)),
+        };
+        Ok(Order {
+            order_id: Self::required_str(data, "orderId")?.to_string(),
+            client_order_id: Self::client_order_id(data),
+            instrument_name: Self::required_str(data, "symbol")?.to_string(),
+            direction: Self::side(data)?,
+            price: Self::number(data, "price").filter(|price| *price > 0.0),
+            amount,
+            filled_amount,
+            remaining_amount: Self::number(data, "leavesQty").unwrap_or(amount - filled_amount),
+            status,
+            order_type: match data.get("orderType").and_then(|v| v.as_str()) {
+                Some("Market") => OrderType::Market,
+                _ => OrderType::Limit,
+            },
+            time_in_force: match data.get("timeInForce").and_then(|v| v.as_str()) {
+                Some("IOC") | Some("FOK") => TimeInForce::IOC,
+                _ => TimeInForce::GTC,
+            },
+            change_reason: data.get("orderStatus").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            delete_reason: data.get("rejectReason").and_then(|v| v.as_str())
+                .filter(|reason| *reason != "EC_NoError")
+                .map(str::to_string),
+            insert_reason: None,
+            create_time: Self::seconds(data, "createdTime").unwrap_or_default(),
+            persistent: false,
+            processing_timestamp: Some(Self::now()),
+            variant_id: None,
+            state: OrderState::Acknowledged,
+        })
+    }
+
+    /// Trade from an `execution` topic entry
+    pub fn parse_execution(data: &Value) -> Result<Trade> {
+        Ok(Trade {
+            trade_id: Self::required_str(data, "execId")?.to_string(),
+            order_id: Self::required_str(data, "orderId")?.to_string(),
+            client_order_id: Self::client_order_id(data),
+            instrument_name: Self::required_str(data, "symbol")?.to_string(),
+            price: Self::required_number(data, "execPrice")?,
+            amount: Self::required_number(data, "execQty")?,
+            maker_taker: data.get("isMaker").and_then(|v| v.as_bool())
+                .map(|maker| if maker { MakerTaker::Maker } else { MakerTaker::Taker }),
+            time: Self::seconds(data, "execTime").unwrap_or_else(Self::now),
+            processing_timestamp: Some(Self::now()),
+            variant_id: None,
+        })
+    }
+
+    /// Side of an `execution` topic entry, which the trade model doesn't carry
+    pub fn execution_side(data: &Value) -> Result<OrderSide> {
+        Self::side(data)
+    }
+
+    /// Symbol and signed size from a `position` topic entry; shorts are negative
+    pub fn parse_position(data: &Value) -> Result<(String, f64)> {
+        let size = Self::required_number(data, "size")?;
+        let signed = match data.get("side").and_then(|v| v.as_str()) {
+            Some("Sell") => -size,
+            _ => size,
+        };
+        Ok((Self::required_str(data, "symbol")?.to_string(), signed))
+    }
+}
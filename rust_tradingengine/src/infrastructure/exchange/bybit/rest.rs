@@ -0,0 +1,96 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+use super::keys::BybitKeys;
+
+/// Default time a signed request stays valid after its timestamp (ms)
+pub const DEFAULT_RECV_WINDOW_MS: u64 = 5000;
+
+/// Signed calls to the Bybit v5 REST API
+///
+/// Used for what the WebSocket APIs don't offer: instrument specifications, open order
+/// snapshots and cancelling everything at once.
+pub struct BybitRestClient {
+    base_url: String,
+    keys: BybitKeys,
+    recv_window_ms: u64,
+    http: reqwest::Client,
+}
+
+impl BybitRestClient {
+    pub fn new(base_url: &str, keys: BybitKeys) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            keys,
+            recv_window_ms: DEFAULT_RECV_WINDOW_MS,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Signature headers of a request whose query string or body is `payload`
+    pub fn auth_headers(&self, timestamp_ms: i64, payload: &str) -> Vec<(&'static str, String)> {
+        let signature = self.keys.sign(&format!(
+            "{}{}{}{}", timestamp_ms, self.keys.api_key, self.recv_window_ms, payload
+        ));
+        vec![
+            ("X-BAPI-API-KEY", self.keys.api_key.clone()),
+            ("X-BAPI-TIMESTAMP", timestamp_ms.to_string()),
+            ("X-BAPI-RECV-WINDOW", self.recv_window_ms.to_string()),
+            ("X-BAPI-SIGN", signature),
+        ]
+    }
+
+    /// Signed GET; returns the `result` of a successful response
+    pub async fn get(&self, path: &str, query: &str) -> Result<Value> {
+        let mut request = self.http.get(format!("{}{}?{}", self.base_url, path, query));
+        for (name, value) in self.auth_headers(chrono::Utc::now().timestamp_millis(), query) {
+            request = request.header(name, value);
+        }
+        Self::result(request.send().await?.json().await?)
+    }
+
+    /// Signed POST with a JSON body; returns the `result` of a successful response
+    pub async fn post(&self, path: &str, body: &Value) -> Result<Value> {
+        let body = body.to_string();
+        let mut request = self.http.post(format!("{}{}", self.base_url, path))
+            .header("Content-Type", "application/json");
+        for (name, value) in self.auth_headers(chrono::Utc::now().timestamp_millis(), &body) {
+            request = request.header(name, value);
+        }
+        Self::result(request.body(body).send().await?.json().await?)
+    }
+
+    /// `result` of a response envelope, or its error
+    pub fn result(response: Value) -> Result<Value> {
+        match response["retCode"].as_i64() {
+            Some(0) => Ok(response["result"].clone()),
+            code => Err(anyhow!(
+                "Bybit error {}: {}",
+                code.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string()),
+                response["retMsg"].as_str().unwrap_or("no message")
+            )),
+        }
+    }
+
+    /// Specifications of the instruments in `category`, e.g. tick and lot sizes
+    pub async fn instruments(&self, category: &str) -> Result<Value> {
+        self.get("/v5/market/instruments-info", &format!("category={}", category)).await
+    }
+
+    /// Open orders of the account in `category`
+    pub async fn open_orders(&self, category: &str, settle_coin: &str) -> Result<Vec<Value>> {
+        let result = self.get("/v5/order/realtime", &format!("category={}&settleCoin={}", category, settle_coin)).await?;
+        Ok(result["list"].as_array().cloned().unwrap_or_default())
+    }
+
+    /// Positions of the account in `category`
+    pub async fn positions(&self, category: &str, settle_coin: &str) -> Result<Vec<Value>> {
+        let result = self.get("/v5/position/list", &format!("category={}&settleCoin={}", category, settle_coin)).await?;
+        Ok(result["list"].as_array().cloned().unwrap_or_default())
+    }
+
+    /// Cancel every open order of the account in `category`
+    pub async fn cancel_all(&self, category: &str, settle_coin: &str) -> Result<Value> {
+        self.post("/v5/order/cancel-all", &json!({"category": category, "settleCoin": settle_coin})).await
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::gateway::OrderGateway;
+use super::symbology::Venue;
+
+/// Session and market data operations the strategy needs from a venue, on top of order entry
+///
+/// Strategies hold the client behind this trait so another venue can be plugged in by
+/// implementing it. As with `OrderGateway`, `id` is the request id echoed back in the
+/// venue's response.
+#[async_trait]
+pub trait ExchangeClient: OrderGateway {
+    /// Venue the client talks to
+    fn venue(&self) -> Venue;
+
+    /// Open the connection to the venue's WebSocket endpoint at `url`
+    async fn connect(&mut self, url: &str) -> Result<()>;
+
+    /// Close the connection
+    async fn disconnect(&mut self) -> Result<()>;
+
+    fn connected(&self) -> bool;
+
+    /// Authenticate the session with a signed token, optionally for a sub-account
+    async fn login(&mut self, token: String, account: Option<String>, id: Option<u64>) -> Result<()>;
+
+    /// Subscribe to channels; `private` ones need the session to be logged in
+    async fn subscribe(&mut self, channels: Vec<String>, private: bool, id: Option<u64>) -> Result<()>;
+
+    async fn unsubscribe(&mut self, channels: Vec<String>, id: Option<u64>) -> Result<()>;
+
+    /// Next message from the venue, None once the connection is closed
+    async fn receive(&mut self) -> Result<Option<String>>;
+
+    /// Cancel every open order of the account
+    async fn cancel_all(&mut self, id: Option<u64>) -> Result<()>;
+}
@@ -0,0 +1,67 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::domain::model::exchange::OrderRequest;
+use crate::domain::model::quote::MassQuote;
+
+/// Order entry operations the strategy needs from a venue
+///
+/// `id` is the request id echoed back in the venue's response, used to correlate
+/// results with the call that caused them.
+#[async_trait]
+pub trait OrderGateway: Send {
+    /// Submit a new order
+    async fn insert(&mut self, order: OrderRequest, id: Option<u64>) -> Result<()>;
+
+    /// Change price and amount of an open order, identified by exactly one of the ids
+    ///
+    /// `amount` is the order's new total amount including what already filled, as the
+    /// venues take it; a part that doesn't change is sent at its current value.
+    async fn amend(
+        &mut self,
+        amount: f64,
+        price: f64,
+        order_id: Option<String>,
+        client_order_id: Option<u64>,
+        id: Option<u64>,
+    ) -> Result<()>;
+
+    /// Cancel an open order, identified by exactly one of the ids
+    async fn cancel(&mut self, order_id: Option<String>, client_order_id: Option<u64>, id: Option<u64>) -> Result<()>;
+
+    /// Request the open orders of the account
+    async fn open_orders(&mut self, id: Option<u64>) -> Result<()>;
+
+    /// Cancel every order placed through this session, including those still on their
+    /// way, in one request; venues without a session-scoped cancel refuse it
+    async fn cancel_session(&mut self, _id: Option<u64>) -> Result<()> {
+        Err(anyhow!("Cancelling a session is not supported by this venue"))
+    }
+
+    /// Replace the quotes of each instrument with its ladder in one request; venues
+    /// without mass quotes refuse it
+    async fn mass_quote(&mut self, _quotes: Vec<MassQuote>, _label: Option<String>, _post_only: bool, _id: Option<u64>) -> Result<()> {
+        Err(anyhow!("Mass quotes are not supported by this venue"))
+    }
+
+    /// Request the account's balances and margin; venues without it refuse it
+    async fn account_summary(&mut self, _id: Option<u64>) -> Result<()> {
+        Err(anyhow!("Account summaries are not supported by this venue"))
+    }
+
+    /// Request the account's positions; venues without it refuse it
+    async fn portfolio(&mut self, _id: Option<u64>) -> Result<()> {
+        Err(anyhow!("Position queries are not supported by this venue"))
+    }
+
+    /// Hold back requests for a while after the venue reported a rate limit
+    fn throttle(&mut self, _duration: Duration) {}
+
+    /// Drop the inserts of `client_order_ids` still waiting to be sent, returning the
+    /// client order ids of those dropped; venues sending right away have none waiting
+    fn drop_queued_inserts(&mut self, _client_order_ids: &HashSet<u64>) -> Vec<u64> {
+        Vec::new()
+    }
+}
@@ -1 +1,10 @@
+pub mod bybit;
+pub mod client;
+pub mod gateway;
+pub mod okx;
+pub mod symbology;
 pub mod thalex;
+
+pub use client::ExchangeClient;
+pub use gateway::OrderGateway;
+pub use symbology::{CanonicalInstrument, Symbology, Venue};
@@ -0,0 +1,396 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use log::debug;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tungstenite::Message;
+use url::Url;
+
+use crate::domain::enums::{OrderSide, OrderType, TimeInForce};
+use crate::domain::model::exchange::OrderRequest;
+use crate::infrastructure::exchange::{ExchangeClient, OrderGateway, Venue};
+
+use super::rest::OkxRestClient;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Private channels, which are subscribed on the authenticated socket
+const PRIVATE_CHANNELS: &[&str] = &["account", "positions", "balance_and_position", "orders", "fills", "liquidation-warning"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OkxNetwork {
+    /// Demo trading: the sandbox sockets, with REST calls flagged as simulated
+    Demo,
+    Live,
+}
+
+impl OkxNetwork {
+    /// Base of the WebSocket endpoints
+    pub fn ws_url(&self) -> &'static str {
+        match self {
+            OkxNetwork::Demo => "wss://wspap.okx.com:8443",
+            OkxNetwork::Live => "wss://ws.okx.com:8443",
+        }
+    }
+
+    /// Demo trading shares the live REST host
+    pub fn rest_url(&self) -> &'static str {
+        "https://www.okx.com"
+    }
+
+    /// Whether requests carry the `x-simulated-trading` flag
+    pub fn simulated(&self) -> bool {
+        matches!(self, OkxNetwork::Demo)
+    }
+
+    /// Suffix of the per-network credential environment variables
+    pub fn env_suffix(&self) -> &'static str {
+        match self {
+            OkxNetwork::Demo => "TEST",
+            OkxNetwork::Live => "PROD",
+        }
+    }
+}
+
+impl std::str::FromStr for OkxNetwork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "demo" | "test" => Ok(OkxNetwork::Demo),
+            "live" | "prod" => Ok(OkxNetwork::Live),
+            _ => Err(anyhow!("Unknown OKX network: {}", s)),
+        }
+    }
+}
+
+/// Whether a channel is one of the account channels, e.g. `orders`
+pub fn is_private_channel(channel: &str) -> bool {
+    let name = channel.split(':').next().unwrap_or(channel);
+    PRIVATE_CHANNELS.contains(&name)
+}
+
+/// Subscription argument of a channel string
+///
+/// `tickers:BTC-USDT-SWAP` names an instrument; account channels such as `orders` are
+/// scoped to the client's instrument type.
+pub fn channel_arg(channel: &str, inst_type: &str) -> Value {
+    match channel.split_once(':') {
+        Some((name, inst_id)) => json!({"channel": name, "instId": inst_id}),
+        None if is_private_channel(channel) && channel != "account" => json!({"channel": channel, "instType": inst_type}),
+        None => json!({"channel": channel}),
+    }
+}
+
+/// OKX v5 connector for one instrument type (perpetuals are `SWAP`)
+///
+/// Market data comes on the public socket; account channels and order operations
+/// share the private socket. Order operations name their instrument, so the instrument
+/// of each client order id is remembered from its insert for later amends and cancels.
+/// Requests the WebSocket API doesn't serve go over REST, and their results are queued
+/// as `{"id": .., "op": .., "data": ..}` messages for `receive`.
+pub struct OkxClient {
+    inst_type: String,
+    td_mode: String,
+    public: Option<WsStream>,
+    private: Option<WsStream>,
+    rest: Option<OkxRestClient>,
+    inbox: VecDeque<String>,
+    symbols: HashMap<u64, String>,
+}
+
+impl OkxClient {
+    /// Client for perpetual swaps on cross margin
+    pub fn new() -> Self {
+        Self::for_inst_type("SWAP", "cross")
+    }
+
+    pub fn for_inst_type(inst_type: &str, td_mode: &str) -> Self {
+        Self {
+            inst_type: inst_type.to_string(),
+            td_mode: td_mode.to_string(),
+            public: None,
+            private: None,
+            rest: None,
+            inbox: VecDeque::new(),
+            symbols: HashMap::new(),
+        }
+    }
+
+    /// REST client used for open orders and cancel-all
+    pub fn set_rest(&mut self, rest: OkxRestClient) {
+        self.rest = Some(rest);
+    }
+
+    pub fn inst_type(&self) -> &str {
+        &self.inst_type
+    }
+
+    /// Instrument an order was inserted for, by client order id
+    pub fn symbol_of(&self, client_order_id: u64) -> Option<&str> {
+        self.symbols.get(&client_order_id).map(String::as_str)
+    }
+
+    /// Keep the connections alive; OKX drops sockets that are quiet for 30 seconds
+    pub async fn ping(&mut self) -> Result<()> {
+        for socket in [&mut self.public, &mut self.private].into_iter().flatten() {
+            socket.send(Message::Text("ping".to_string())).await?;
+        }
+        Ok(())
+    }
+
+    /// `order` operation on the private socket
+    pub fn create_request(&self, order: &OrderRequest, id: Option<u64>) -> Value {
+        let mut args = json!({
+            "instId": order.symbol,
+            "tdMode": self.td_mode,
+            "side": match order.side {
+                OrderSide::Buy => "buy",
+                OrderSide::Sell => "sell",
+            },
+            "ordType": match (&order.order_type, &order.time_in_force) {
+                (OrderType::Market, _) => "market",
+                (OrderType::Limit, Some(TimeInForce::IOC)) => "ioc",
+                (OrderType::Limit, _) => "limit",
+            },
+            "sz": order.quantity.to_string(),
+        });
+        if let Some(price) = order.price {
+            args["px"] = json!(price.to_string());
+        }
+        if let Some(cid) = order.client_order_id {
+            args["clOrdId"] = json!(cid.to_string());
+        }
+        Self::request("order", vec![args], id)
+    }
+
+    /// `amend-order` operation; only the parts given are changed
+    pub fn amend_request(
+        &self,
+        inst_id: &str,
+        quantity: Option<f64>,
+        price: Option<f64>,
+        order_id: Option<String>,
+        client_order_id: Option<u64>,
+        id: Option<u64>,
+    ) -> Value {
+        let mut args = Self::order_ref(inst_id, order_id, client_order_id);
+        if let Some(quantity) = quantity {
+            args["newSz"] = json!(quantity.to_string());
+        }
+        if let Some(price) = price {
+            args["newPx"] = json!(price.to_string());
+        }
+        Self::request("amend-order", vec![args], id)
+    }
+
+    /// `cancel-order` operation
+    pub fn cancel_request(&self, inst_id: &str, order_id: Option<String>, client_order_id: Option<u64>, id: Option<u64>) -> Value {
+        Self::request("cancel-order", vec![Self::order_ref(inst_id, order_id, client_order_id)], id)
+    }
+
+    fn order_ref(inst_id: &str, order_id: Option<String>, client_order_id: Option<u64>) -> Value {
+        let mut args = json!({"instId": inst_id});
+        match (order_id, client_order_id) {
+            (Some(order_id), _) => args["ordId"] = json!(order_id),
+            (None, Some(cid)) => args["clOrdId"] = json!(cid.to_string()),
+            (None, None) => {}
+        }
+        args
+    }
+
+    fn request(op: &str, args: Vec<Value>, id: Option<u64>) -> Value {
+        let mut request = json!({"op": op, "args": args});
+        if let Some(id) = id {
+            request["id"] = json!(id.to_string());
+        }
+        request
+    }
+
+    /// Instrument of an order being amended or cancelled
+    fn order_symbol(&self, order_id: &Option<String>, client_order_id: Option<u64>) -> Result<String> {
+        match client_order_id.and_then(|cid| self.symbols.get(&cid)) {
+            Some(symbol) => Ok(symbol.clone()),
+            None => Err(anyhow!(
+                "Unknown instrument for order {}",
+                order_id.clone().or(client_order_id.map(|cid| cid.to_string())).unwrap_or_default()
+            )),
+        }
+    }
+
+    async fn write(socket: &mut Option<WsStream>, name: &str, request: &Value) -> Result<()> {
+        let Some(socket) = socket else {
+            return Err(anyhow!("OKX {} socket not connected", name));
+        };
+        socket.send(Message::Text(request.to_string())).await?;
+        Ok(())
+    }
+
+    fn rest(&self) -> Result<&OkxRestClient> {
+        self.rest.as_ref().ok_or_else(|| anyhow!("OKX REST client not configured"))
+    }
+
+    /// Queue a REST result for `receive`
+    fn deliver(&mut self, op: &str, id: Option<u64>, data: Value) {
+        self.inbox.push_back(json!({"id": id.map(|id| id.to_string()), "op": op, "data": data}).to_string());
+    }
+}
+
+impl Default for OkxClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Next frame of a socket; never resolves when the socket isn't open
+async fn next_frame(socket: &mut Option<WsStream>) -> Option<Result<Message, tungstenite::Error>> {
+    match socket {
+        Some(socket) => socket.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[async_trait]
+impl OrderGateway for OkxClient {
+    async fn insert(&mut self, order: OrderRequest, id: Option<u64>) -> Result<()> {
+        let request = self.create_request(&order, id);
+        Self::write(&mut self.private, "private", &request).await?;
+        if let Some(cid) = order.client_order_id {
+            self.symbols.insert(cid, order.symbol);
+        }
+        Ok(())
+    }
+
+    async fn amend(
+        &mut self,
+        amount: f64,
+        price: f64,
+        order_id: Option<String>,
+        client_order_id: Option<u64>,
+        id: Option<u64>,
+    ) -> Result<()> {
+        let inst_id = self.order_symbol(&order_id, client_order_id)?;
+        let request = self.amend_request(&inst_id, Some(amount), Some(price), order_id, client_order_id, id);
+        Self::write(&mut self.private, "private", &request).await
+    }
+
+    async fn cancel(&mut self, order_id: Option<String>, client_order_id: Option<u64>, id: Option<u64>) -> Result<()> {
+        let inst_id = self.order_symbol(&order_id, client_order_id)?;
+        let request = self.cancel_request(&inst_id, order_id, client_order_id, id);
+        Self::write(&mut self.private, "private", &request).await
+    }
+
+    async fn open_orders(&mut self, id: Option<u64>) -> Result<()> {
+        let orders = self.rest()?.open_orders(&self.inst_type).await?;
+        self.deliver("open_orders", id, Value::Array(orders));
+        Ok(())
+    }
+
+    /// OKX orders aren't tied to a session, so this cancels every open order
+    async fn cancel_session(&mut self, id: Option<u64>) -> Result<()> {
+        ExchangeClient::cancel_all(self, id).await
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for OkxClient {
+    fn venue(&self) -> Venue {
+        Venue::Okx
+    }
+
+    /// Open the public and private sockets under the base URL, e.g. `OkxNetwork::ws_url`
+    async fn connect(&mut self, url: &str) -> Result<()> {
+        let base = url.trim_end_matches('/');
+        let (public, _) = connect_async(Url::parse(&format!("{}/ws/v5/public", base))?).await?;
+        let (private, _) = connect_async(Url::parse(&format!("{}/ws/v5/private", base))?).await?;
+        self.public = Some(public);
+        self.private = Some(private);
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        if !self.connected() {
+            return Err(anyhow!("OKX sockets not connected"));
+        }
+        for socket in [self.public.take(), self.private.take()].into_iter().flatten() {
+            let mut socket = socket;
+            socket.close(None).await?;
+        }
+        Ok(())
+    }
+
+    fn connected(&self) -> bool {
+        self.public.is_some() || self.private.is_some()
+    }
+
+    /// Authenticate the private socket; `token` is `OkxKeys::make_auth_token`
+    async fn login(&mut self, token: String, _account: Option<String>, id: Option<u64>) -> Result<()> {
+        let arg: Value = serde_json::from_str(&token).map_err(|e| anyhow!("Invalid OKX auth token: {}", e))?;
+        let request = Self::request("login", vec![arg], id);
+        Self::write(&mut self.private, "private", &request).await
+    }
+
+    async fn subscribe(&mut self, channels: Vec<String>, private: bool, id: Option<u64>) -> Result<()> {
+        let args = channels.iter().map(|c| channel_arg(c, &self.inst_type)).collect();
+        let request = Self::request("subscribe", args, id);
+        if private {
+            Self::write(&mut self.private, "private", &request).await
+        } else {
+            Self::write(&mut self.public, "public", &request).await
+        }
+    }
+
+    async fn unsubscribe(&mut self, channels: Vec<String>, id: Option<u64>) -> Result<()> {
+        let (private, public): (Vec<String>, Vec<String>) = channels.into_iter().partition(|c| is_private_channel(c));
+        for (channels, socket, name) in [(private, &mut self.private, "private"), (public, &mut self.public, "public")] {
+            if channels.is_empty() {
+                continue;
+            }
+            let args = channels.iter().map(|c| channel_arg(c, &self.inst_type)).collect();
+            Self::write(socket, name, &Self::request("unsubscribe", args, id)).await?;
+        }
+        Ok(())
+    }
+
+    /// Next text message from either socket, or a queued REST result
+    ///
+    /// Returns `Ok(None)` for control frames and `pong` replies, and an error once a
+    /// socket has closed.
+    async fn receive(&mut self) -> Result<Option<String>> {
+        if let Some(message) = self.inbox.pop_front() {
+            return Ok(Some(message));
+        }
+        if !self.connected() {
+            return Err(anyhow!("OKX sockets not connected"));
+        }
+        let frame = tokio::select! {
+            frame = next_frame(&mut self.public) => frame,
+            frame = next_frame(&mut self.private) => frame,
+        };
+        match frame {
+            Some(Ok(Message::Text(text))) if text == "pong" => Ok(None),
+            Some(Ok(Message::Text(text))) => Ok(Some(text)),
+            Some(Ok(other)) => {
+                debug!("Ignoring OKX frame {:?}", other);
+                Ok(None)
+            }
+            Some(Err(e)) => Err(anyhow!("WebSocket error: {}", e)),
+            None => Err(anyhow!("WebSocket stream ended")),
+        }
+    }
+
+    /// OKX has no cancel-all, so the open orders are listed and cancelled in batches
+    async fn cancel_all(&mut self, id: Option<u64>) -> Result<()> {
+        let rest = self.rest()?;
+        let orders: Vec<(String, String)> = rest.open_orders(&self.inst_type).await?
+            .iter()
+            .filter_map(|order| Some((order["instId"].as_str()?.to_string(), order["ordId"].as_str()?.to_string())))
+            .collect();
+        let result = rest.cancel_orders(&orders).await?;
+        self.deliver("cancel_all", id, Value::Array(result));
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+
+use super::client::OkxNetwork;
+
+/// API key, secret and passphrase of an OKX account
+///
+/// Demo-trading keys are created separately from live ones and only work on the demo
+/// network.
+#[derive(Clone)]
+pub struct OkxKeys {
+    pub api_key: String,
+    api_secret: String,
+    passphrase: String,
+}
+
+impl std::fmt::Debug for OkxKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OkxKeys").field("api_key", &self.api_key).finish_non_exhaustive()
+    }
+}
+
+impl OkxKeys {
+    pub fn new(api_key: &str, api_secret: &str, passphrase: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            api_secret: api_secret.to_string(),
+            passphrase: passphrase.to_string(),
+        }
+    }
+
+    /// Keys of the network from `OKX_API_KEY_<NET>`, `OKX_API_SECRET_<NET>` and
+    /// `OKX_PASSPHRASE_<NET>`
+    pub fn load(network: &OkxNetwork) -> Result<Self> {
+        let var = |name: &str| {
+            let var = format!("OKX_{}_{}", name, network.env_suffix());
+            std::env::var(&var).map_err(|_| anyhow!("Missing {}", var))
+        };
+        Ok(Self {
+            api_key: var("API_KEY")?,
+            api_secret: var("API_SECRET")?,
+            passphrase: var("PASSPHRASE")?,
+        })
+    }
+
+    pub fn passphrase(&self) -> &str {
+        &self.passphrase
+    }
+
+    /// Base64 HMAC-SHA256 of `payload` under the API secret
+    pub fn sign(&self, payload: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.api_secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    /// Argument of the WebSocket `login` operation, signed at `timestamp_sec`
+    ///
+    /// Returned as JSON text so it can be passed to `ExchangeClient::login` as the token.
+    pub fn make_auth_token(&self, timestamp_sec: i64) -> String {
+        let signature = self.sign(&format!("{}GET/users/self/verify", timestamp_sec));
+        json!({
+            "apiKey": self.api_key,
+            "passphrase": self.passphrase,
+            "timestamp": timestamp_sec.to_string(),
+            "sign": signature,
+        }).to_string()
+    }
+}
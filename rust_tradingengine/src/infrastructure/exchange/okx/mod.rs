@@ -0,0 +1,9 @@
+pub mod client;
+pub mod keys;
+pub mod parsers;
+pub mod rest;
+
+pub use client::{OkxClient, OkxNetwork};
+pub use keys::OkxKeys;
+pub use parsers::OkxParser;
+pub use rest::OkxRestClient;
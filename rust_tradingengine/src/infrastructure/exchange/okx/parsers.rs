@@ -0,0 +1,186 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::domain::enums::{MakerTaker, OrderSide, OrderStatus, OrderType, TimeInForce};
+use crate::domain::model::order::{Order, OrderState};
+use crate::domain::model::ticker::Ticker;
+use crate::domain::model::trade::Trade;
+use crate::domain::clock;
+
+/// Maps OKX v5 messages onto the domain models
+///
+/// OKX sends numbers as strings and times in milliseconds; the models use floats and
+/// seconds. Fills arrive on the `orders` channel, as fields of the order update.
+pub struct OkxParser;
+
+impl OkxParser {
+    /// Number sent either as a string or as a JSON number; None when missing or empty
+    fn number(data: &Value, key: &str) -> Option<f64> {
+        match data.get(key)? {
+            Value::String(s) if s.is_empty() => None,
+            Value::String(s) => s.parse().ok(),
+            value => value.as_f64(),
+        }
+    }
+
+    fn required_number(data: &Value, key: &str) -> Result<f64> {
+        Self::number(data, key).ok_or_else(|| anyhow!("Missing {}", key))
+    }
+
+    fn required_str<'a>(data: &'a Value, key: &str) -> Result<&'a str> {
+        data.get(key).and_then(|v| v.as_str()).ok_or_else(|| anyhow!("Missing {}", key))
+    }
+
+    /// String field, None when missing or empty
+    fn text<'a>(data: &'a Value, key: &str) -> Option<&'a str> {
+        data.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty())
+    }
+
+    /// Milliseconds field as seconds
+    fn seconds(data: &Value, key: &str) -> Option<f64> {
+        Self::number(data, key).map(|ms| ms / 1000.0)
+    }
+
+    fn now() -> f64 {
+        clock::now_secs()
+    }
+
+    /// Client order id from `clOrdId`, when it is one of ours
+    fn client_order_id(data: &Value) -> Option<u64> {
+        Self::text(data, "clOrdId").and_then(|id| id.parse().ok())
+    }
+
+    /// Side of an order or fill
+    pub fn side(data: &Value) -> Result<OrderSide> {
+        match Self::required_str(data, "side")? {
+            "buy" => Ok(OrderSide::Buy),
+            "sell" => Ok(OrderSide::Sell),
+            other => Err(anyhow!("Unknown side: {}", other)),
+        }
+    }
+
+    /// Apply a `tickers` push to `ticker`; fields missing from the push keep their values
+    ///
+    /// Mark and index prices come on their own channels and are applied the same way.
+    pub fn apply_ticker(data: &Value, ticker: &mut Ticker) {
+        let fields: [(&str, &mut f64); 13] = [
+            ("markPx", &mut ticker.mark_price),
+            ("bidPx", &mut ticker.best_bid_price),
+            ("bidSz", &mut ticker.best_bid_amount),
+            ("askPx", &mut ticker.best_ask_price),
+            ("askSz", &mut ticker.best_ask_amount),
+            ("last", &mut ticker.last_price),
+            ("idxPx", &mut ticker.index_price),
+            ("vol24h", &mut ticker.volume_24h),
+            ("volCcy24h", &mut ticker.value_24h),
+            ("high24h", &mut ticker.high_price_24h),
+            ("low24h", &mut ticker.low_price_24h),
+            ("fundingRate", &mut ticker.funding_rate),
+            ("oi", &mut ticker.open_interest),
+        ];
+        for (key, field) in fields {
+            if let Some(value) = Self::number(data, key) {
+                *field = value;
+            }
+        }
+        // OKX sends the opening price of the window rather than the change
+        if let (Some(last), Some(open)) = (Self::number(data, "last"), Self::number(data, "open24h")) {
+            if open > 0.0 {
+                ticker.change_24h = last / open - 1.0;
+            }
+        }
+        if let Some(ts) = Self::seconds(data, "ts") {
+            ticker.mark_timestamp = ts;
+        }
+        ticker.processing_timestamp = Some(Self::now());
+    }
+
+    /// Ticker from a `tickers` push
+    pub fn parse_ticker(data: &Value) -> Result<Ticker> {
+        let mut ticker = Ticker::new(Self::required_str(data, "instId")?.to_string());
+        // Perpetuals move one for one with the underlying
+        ticker.delta = 1.0;
+        Self::apply_ticker(data, &mut ticker);
+        Ok(ticker)
+    }
+
+    /// Order from an `orders` channel entry or an open orders listing
+    pub fn parse_order(data: &Value) -> Result<Order> {
+        let amount = Self::required_number(data, "sz")?;
+        let filled_amount = Self::number(data, "accFillSz").unwrap_or_default();
+        let status = match Self::required_str(data, "state")? {
+            "live" => OrderStatus::Open,
+            "partially_filled" => OrderStatus::PartiallyFilled,
+            "filled" => OrderStatus::Filled,
+            "canceled" | "mmp_canceled" if filled_amount > 0.0 => OrderStatus::CancelledPartiallyFilled,
+            "canceled" | "mmp_canceled" => OrderStatus::Cancelled,
+            other => return Err(anyhow!("Unknown order state: {}", other)),
+        };
+        let ord_type = data.get("ordType").and_then(|v| v.as_str()).unwrap_or_default();
+        Ok(Order {
+            order_id: Self::required_str(data, "ordId")?.to_string(),
+            client_order_id: Self::client_order_id(data),
+            instrument_name: Self::required_str(data, "instId")?.to_string(),
+            direction: Self::side(data)?,
+            price: Self::number(data, "px").filter(|price| *price > 0.0),
+            amount,
+            filled_amount,
+            remaining_amount: amount - filled_amount,
+            status,
+            order_type: match ord_type {
+                "market" => OrderType::Market,
+                _ => OrderType::Limit,
+            },
+            time_in_force: match ord_type {
+                "ioc" | "fok" | "optimal_limit_ioc" => TimeInForce::IOC,
+                _ => TimeInForce::GTC,
+            },
+            change_reason: data.get("state").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            delete_reason: Self::text(data, "cancelSourceReason")
+                .or_else(|| Self::text(data, "cancelSource"))
+                .map(str::to_string),
+            insert_reason: None,
+            create_time: Self::seconds(data, "cTime").unwrap_or_default(),
+            persistent: false,
+            processing_timestamp: Some(Self::now()),
+            variant_id: None,
+            state: OrderState::Acknowledged,
+        })
+    }
+
+    /// Fill carried by an `orders` channel entry; None for updates without one
+    pub fn parse_fill(data: &Value) -> Result<Option<Trade>> {
+        let Some(trade_id) = Self::text(data, "tradeId") else {
+            return Ok(None);
+        };
+        Ok(Some(Trade {
+            trade_id: trade_id.to_string(),
+            order_id: Self::required_str(data, "ordId")?.to_string(),
+            client_order_id: Self::client_order_id(data),
+            instrument_name: Self::required_str(data, "instId")?.to_string(),
+            price: Self::required_number(data, "fillPx")?,
+            amount: Self::required_number(data, "fillSz")?,
+            maker_taker: match Self::text(data, "execType") {
+                Some("M") => Some(MakerTaker::Maker),
+                Some("T") => Some(MakerTaker::Taker),
+                _ => None,
+            },
+            time: Self::seconds(data, "fillTime").unwrap_or_else(Self::now),
+            processing_timestamp: Some(Self::now()),
+            variant_id: None,
+        }))
+    }
+
+    /// Instrument and signed size from a `positions` channel entry; shorts are negative
+    ///
+    /// In net mode `pos` is already signed; in long/short mode the side comes from `posSide`.
+    pub fn parse_position(data: &Value) -> Result<(String, f64)> {
+        let pos = Self::required_number(data, "pos")?;
+        let signed = match data.get("posSide").and_then(|v| v.as_str()) {
+            Some("short") => -pos.abs(),
+            Some("long") => pos.abs(),
+            _ => pos,
+        };
+        Ok((Self::required_str(data, "instId")?.to_string(), signed))
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+use super::keys::OkxKeys;
+
+/// Largest number of orders one batch cancel may name
+pub const MAX_BATCH_SIZE: usize = 20;
+
+/// Signed calls to the OKX v5 REST API
+///
+/// Used for what the WebSocket API doesn't offer: instrument specifications, open order
+/// and position snapshots. Demo trading uses the live host, with every request marked
+/// as simulated.
+pub struct OkxRestClient {
+    base_url: String,
+    keys: OkxKeys,
+    simulated: bool,
+    http: reqwest::Client,
+}
+
+impl OkxRestClient {
+    pub fn new(base_url: &str, keys: OkxKeys, simulated: bool) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            keys,
+            simulated,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Request timestamp in the ISO format OKX signs, e.g. `2026-10-15T12:00:00.000Z`
+    pub fn timestamp(now: chrono::DateTime<chrono::Utc>) -> String {
+        now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+    }
+
+    /// Signature headers of a request to `path` (query string included) with `body`
+    pub fn auth_headers(&self, timestamp: &str, method: &str, path: &str, body: &str) -> Vec<(&'static str, String)> {
+        let signature = self.keys.sign(&format!("{}{}{}{}", timestamp, method, path, body));
+        let mut headers = vec![
+            ("OK-ACCESS-KEY", self.keys.api_key.clone()),
+            ("OK-ACCESS-SIGN", signature),
+            ("OK-ACCESS-TIMESTAMP", timestamp.to_string()),
+            ("OK-ACCESS-PASSPHRASE", self.keys.passphrase().to_string()),
+        ];
+        if self.simulated {
+            headers.push(("x-simulated-trading", "1".to_string()));
+        }
+        headers
+    }
+
+    /// Signed GET; returns the `data` of a successful response
+    pub async fn get(&self, path: &str) -> Result<Value> {
+        let mut request = self.http.get(format!("{}{}", self.base_url, path));
+        for (name, value) in self.auth_headers(&Self::timestamp(chrono::Utc::now()), "GET", path, "") {
+            request = request.header(name, value);
+        }
+        Self::result(request.send().await?.json().await?)
+    }
+
+    /// Signed POST with a JSON body; returns the `data` of a successful response
+    pub async fn post(&self, path: &str, body: &Value) -> Result<Value> {
+        let body = body.to_string();
+        let mut request = self.http.post(format!("{}{}", self.base_url, path))
+            .header("Content-Type", "application/json");
+        for (name, value) in self.auth_headers(&Self::timestamp(chrono::Utc::now()), "POST", path, &body) {
+            request = request.header(name, value);
+        }
+        Self::result(request.body(body).send().await?.json().await?)
+    }
+
+    /// `data` of a response envelope, or its error
+    pub fn result(response: Value) -> Result<Value> {
+        match response["code"].as_str() {
+            Some("0") => Ok(response["data"].clone()),
+            code => Err(anyhow!(
+                "OKX error {}: {}",
+                code.unwrap_or("?"),
+                response["msg"].as_str().filter(|msg| !msg.is_empty()).unwrap_or("no message")
+            )),
+        }
+    }
+
+    /// Specifications of the instruments of `inst_type`, e.g. tick and lot sizes
+    pub async fn instruments(&self, inst_type: &str) -> Result<Vec<Value>> {
+        let data = self.get(&format!("/api/v5/public/instruments?instType={}", inst_type)).await?;
+        Ok(data.as_array().cloned().unwrap_or_default())
+    }
+
+    /// Open orders of the account in `inst_type`
+    pub async fn open_orders(&self, inst_type: &str) -> Result<Vec<Value>> {
+        let data = self.get(&format!("/api/v5/trade/orders-pending?instType={}", inst_type)).await?;
+        Ok(data.as_array().cloned().unwrap_or_default())
+    }
+
+    /// Positions of the account in `inst_type`
+    pub async fn positions(&self, inst_type: &str) -> Result<Vec<Value>> {
+        let data = self.get(&format!("/api/v5/account/positions?instType={}", inst_type)).await?;
+        Ok(data.as_array().cloned().unwrap_or_default())
+    }
+
+    /// Cancel the given `(instrument, order id)` pairs, `MAX_BATCH_SIZE` per request
+    pub async fn cancel_orders(&self, orders: &[(String, String)]) -> Result<Vec<Value>> {
+        let mut results = Vec::new();
+        for batch in orders.chunks(MAX_BATCH_SIZE) {
+            let body: Vec<Value> = batch.iter()
+                .map(|(inst_id, ord_id)| json!({"instId": inst_id, "ordId": ord_id}))
+                .collect();
+            let data = self.post("/api/v5/trade/cancel-batch-orders", &Value::Array(body)).await?;
+            results.extend(data.as_array().cloned().unwrap_or_default());
+        }
+        Ok(results)
+    }
+}
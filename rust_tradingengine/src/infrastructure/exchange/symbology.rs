@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::config_loader::SymbologyConfig;
+
+/// Exchange whose naming an instrument symbol follows
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Venue {
+    Thalex,
+    Bybit,
+    Okx,
+}
+
+impl Venue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Venue::Thalex => "thalex",
+            Venue::Bybit => "bybit",
+            Venue::Okx => "okx",
+        }
+    }
+}
+
+impl fmt::Display for Venue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Venue {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "thalex" => Ok(Venue::Thalex),
+            "bybit" => Ok(Venue::Bybit),
+            "okx" => Ok(Venue::Okx),
+            _ => Err(anyhow!("Unknown venue: {}", s)),
+        }
+    }
+}
+
+/// Call or put
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptionRight {
+    Call,
+    Put,
+}
+
+impl OptionRight {
+    fn letter(&self) -> &'static str {
+        match self {
+            OptionRight::Call => "C",
+            OptionRight::Put => "P",
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self> {
+        match s {
+            "C" => Ok(OptionRight::Call),
+            "P" => Ok(OptionRight::Put),
+            _ => Err(anyhow!("Unknown option right: {}", s)),
+        }
+    }
+}
+
+/// What kind of contract an instrument is
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContractKind {
+    Perpetual,
+    Future { expiry: NaiveDate },
+    Option { expiry: NaiveDate, strike: f64, right: OptionRight },
+}
+
+/// Venue-independent identity of an instrument
+///
+/// Written as `BTC-USD-PERP`, `BTC-USD-20251226-FUT` or `BTC-USD-20251226-100000-C`, so
+/// the same contract joins across venues whatever each venue calls it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CanonicalInstrument {
+    pub base: String,
+    pub quote: String,
+    pub kind: ContractKind,
+}
+
+impl CanonicalInstrument {
+    pub fn perpetual(base: &str, quote: &str) -> Self {
+        Self { base: base.to_string(), quote: quote.to_string(), kind: ContractKind::Perpetual }
+    }
+}
+
+impl fmt::Display for CanonicalInstrument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ContractKind::Perpetual => write!(f, "{}-{}-PERP", self.base, self.quote),
+            ContractKind::Future { expiry } => write!(f, "{}-{}-{}-FUT", self.base, self.quote, expiry.format("%Y%m%d")),
+            ContractKind::Option { expiry, strike, right } => write!(f, "{}-{}-{}-{}-{}",
+                self.base, self.quote, expiry.format("%Y%m%d"), format_strike(*strike), right.letter()),
+        }
+    }
+}
+
+impl FromStr for CanonicalInstrument {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.split('-').collect();
+        let kind = match parts.as_slice() {
+            [_, _, "PERP"] => ContractKind::Perpetual,
+            [_, _, expiry, "FUT"] => ContractKind::Future { expiry: parse_date(expiry, "%Y%m%d")? },
+            [_, _, expiry, strike, right] => ContractKind::Option {
+                expiry: parse_date(expiry, "%Y%m%d")?,
+                strike: parse_strike(strike)?,
+                right: OptionRight::parse(right)?,
+            },
+            _ => return Err(anyhow!("Invalid canonical instrument: {}", s)),
+        };
+        if parts[0].is_empty() || parts[1].is_empty() {
+            return Err(anyhow!("Invalid canonical instrument: {}", s));
+        }
+        Ok(Self { base: parts[0].to_string(), quote: parts[1].to_string(), kind })
+    }
+}
+
+/// Quote currencies recognized at the end of concatenated symbols, longest first
+const CONCATENATED_QUOTES: &[&str] = &["USDT", "USDC", "USD"];
+
+/// Maps canonical instruments to each venue's symbols and back
+///
+/// Each venue's naming convention is built in; configured overrides take precedence
+/// for contracts the conventions don't cover or name differently.
+#[derive(Debug, Default)]
+pub struct Symbology {
+    to_venue: HashMap<(Venue, String), String>,
+    from_venue: HashMap<(Venue, String), CanonicalInstrument>,
+}
+
+impl Symbology {
+    pub fn from_config(config: &SymbologyConfig) -> Result<Self> {
+        let mut symbology = Self::default();
+        for mapping in &config.overrides {
+            let canonical = mapping.canonical.parse()
+                .with_context(|| format!("Invalid symbology override for {} {}", mapping.venue, mapping.symbol))?;
+            symbology.add_override(&canonical, mapping.venue, &mapping.symbol);
+        }
+        Ok(symbology)
+    }
+
+    /// Name `instrument` `symbol` on `venue`, in both directions
+    pub fn add_override(&mut self, instrument: &CanonicalInstrument, venue: Venue, symbol: &str) {
+        self.to_venue.insert((venue, instrument.to_string()), symbol.to_string());
+        self.from_venue.insert((venue, symbol.to_string()), instrument.clone());
+    }
+
+    /// Symbol of `instrument` on `venue`
+    pub fn to_venue(&self, instrument: &CanonicalInstrument, venue: Venue) -> Result<String> {
+        if let Some(symbol) = self.to_venue.get(&(venue, instrument.to_string())) {
+            return Ok(symbol.clone());
+        }
+        match venue {
+            Venue::Thalex => thalex_symbol(instrument),
+            Venue::Bybit => bybit_symbol(instrument),
+            Venue::Okx => Ok(okx_symbol(instrument)),
+        }
+    }
+
+    /// Canonical instrument of `symbol` on `venue`
+    pub fn from_venue(&self, venue: Venue, symbol: &str) -> Result<CanonicalInstrument> {
+        if let Some(instrument) = self.from_venue.get(&(venue, symbol.to_string())) {
+            return Ok(instrument.clone());
+        }
+        match venue {
+            Venue::Thalex => parse_thalex(symbol),
+            Venue::Bybit => parse_bybit(symbol),
+            Venue::Okx => parse_okx(symbol),
+        }
+        .with_context(|| format!("Unknown {} symbol: {}", venue, symbol))
+    }
+
+    /// Symbol on `to` of the instrument `from` calls `symbol`
+    pub fn translate(&self, from: Venue, symbol: &str, to: Venue) -> Result<String> {
+        self.to_venue(&self.from_venue(from, symbol)?, to)
+    }
+}
+
+/// Thalex: `BTC-PERPETUAL`, `BTC-26DEC25`, `BTC-26DEC25-100000-C`, all quoted in USD
+fn thalex_symbol(instrument: &CanonicalInstrument) -> Result<String> {
+    if instrument.quote != "USD" {
+        return Err(anyhow!("Thalex has no {} contracts", instrument));
+    }
+    Ok(match &instrument.kind {
+        ContractKind::Perpetual => format!("{}-PERPETUAL", instrument.base),
+        ContractKind::Future { expiry } => format!("{}-{}", instrument.base, thalex_date(expiry)),
+        ContractKind::Option { expiry, strike, right } => format!("{}-{}-{}-{}",
+            instrument.base, thalex_date(expiry), format_strike(*strike), right.letter()),
+    })
+}
+
+fn parse_thalex(symbol: &str) -> Result<CanonicalInstrument> {
+    let parts: Vec<&str> = symbol.split('-').collect();
+    let kind = match parts.as_slice() {
+        [_, "PERPETUAL"] => ContractKind::Perpetual,
+        [_, expiry] => ContractKind::Future { expiry: parse_date(expiry, "%d%b%y")? },
+        [_, expiry, strike, right] => ContractKind::Option {
+            expiry: parse_date(expiry, "%d%b%y")?,
+            strike: parse_strike(strike)?,
+            right: OptionRight::parse(right)?,
+        },
+        _ => return Err(anyhow!("Unexpected format")),
+    };
+    Ok(CanonicalInstrument { base: parts[0].to_string(), quote: "USD".to_string(), kind })
+}
+
+fn thalex_date(date: &NaiveDate) -> String {
+    date.format("%d%b%y").to_string().to_uppercase()
+}
+
+/// Bybit perpetuals: `BTCUSDT` and inverse `BTCUSD`, with USDC perpetuals as `BTCPERP`;
+/// dated contracts need overrides
+fn bybit_symbol(instrument: &CanonicalInstrument) -> Result<String> {
+    match (&instrument.kind, instrument.quote.as_str()) {
+        (ContractKind::Perpetual, "USDC") => Ok(format!("{}PERP", instrument.base)),
+        (ContractKind::Perpetual, quote) => Ok(format!("{}{}", instrument.base, quote)),
+        _ => Err(anyhow!("No Bybit naming convention for {}, configure an override", instrument)),
+    }
+}
+
+fn parse_bybit(symbol: &str) -> Result<CanonicalInstrument> {
+    if let Some(base) = symbol.strip_suffix("PERP").filter(|base| !base.is_empty()) {
+        return Ok(CanonicalInstrument::perpetual(base, "USDC"));
+    }
+    CONCATENATED_QUOTES.iter()
+        .find_map(|quote| symbol.strip_suffix(quote)
+            .filter(|base| !base.is_empty())
+            .map(|base| CanonicalInstrument::perpetual(base, quote)))
+        .ok_or_else(|| anyhow!("Unexpected format"))
+}
+
+/// OKX: `BTC-USDT-SWAP`, `BTC-USD-251226`, `BTC-USD-251226-100000-C`
+fn okx_symbol(instrument: &CanonicalInstrument) -> String {
+    match &instrument.kind {
+        ContractKind::Perpetual => format!("{}-{}-SWAP", instrument.base, instrument.quote),
+        ContractKind::Future { expiry } => format!("{}-{}-{}", instrument.base, instrument.quote, expiry.format("%y%m%d")),
+        ContractKind::Option { expiry, strike, right } => format!("{}-{}-{}-{}-{}",
+            instrument.base, instrument.quote, expiry.format("%y%m%d"), format_strike(*strike), right.letter()),
+    }
+}
+
+fn parse_okx(symbol: &str) -> Result<CanonicalInstrument> {
+    let parts: Vec<&str> = symbol.split('-').collect();
+    let kind = match parts.as_slice() {
+        [_, _, "SWAP"] => ContractKind::Perpetual,
+        [_, _, expiry] => ContractKind::Future { expiry: parse_date(expiry, "%y%m%d")? },
+        [_, _, expiry, strike, right] => ContractKind::Option {
+            expiry: parse_date(expiry, "%y%m%d")?,
+            strike: parse_strike(strike)?,
+            right: OptionRight::parse(right)?,
+        },
+        _ => return Err(anyhow!("Unexpected format")),
+    };
+    Ok(CanonicalInstrument { base: parts[0].to_string(), quote: parts[1].to_string(), kind })
+}
+
+fn parse_date(s: &str, format: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, format).map_err(|_| anyhow!("Invalid expiry: {}", s))
+}
+
+fn parse_strike(s: &str) -> Result<f64> {
+    s.parse::<f64>().ok()
+        .filter(|strike| strike.is_finite() && *strike > 0.0)
+        .ok_or_else(|| anyhow!("Invalid strike: {}", s))
+}
+
+/// Whole strikes without a decimal point, as every venue writes them
+fn format_strike(strike: f64) -> String {
+    if strike.fract() == 0.0 {
+        format!("{:.0}", strike)
+    } else {
+        strike.to_string()
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::config_loader::TradeBackfillConfig;
+use crate::domain::model::trade::Trade;
+use crate::infrastructure::kafka::producer::KafkaProducer;
+use crate::infrastructure::metrics;
+
+use super::models::TradeHistoryResult;
+use super::rest::{ThalexRestClient, TradeHistoryQuery};
+
+/// Counter of trades fetched from the trade history and published
+pub const METRIC_BACKFILLED_TRADES: &str = "thalex.backfilled_trades";
+
+/// Time up to which the account's trades are known to be on the trade topic
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Watermark {
+    /// Seconds since epoch
+    through: f64,
+}
+
+/// Publishes the account trades made while no session was publishing them
+///
+/// Live trades reach the topic from the order notifications of a session, so trades
+/// made while the bot was down or reconnecting would be missing. Once a session is
+/// logged in, the trades since the recorded watermark are fetched page by page from
+/// `private/trade_history`, parsed by `ThaleParser` and published oldest first. A
+/// session that caught up moves the watermark to its end when it closes; one that
+/// didn't leaves it, so the next backfill covers the session too. Trades may be
+/// published twice, never skipped.
+pub struct TradeBackfill {
+    config: TradeBackfillConfig,
+    path: PathBuf,
+    caught_up: AtomicBool,
+    /// Reject trades without a known maker/taker role, like the live path does
+    strict_parsing: bool,
+}
+
+impl TradeBackfill {
+    pub fn new(config: &TradeBackfillConfig, strict_parsing: bool) -> Self {
+        Self { config: config.clone(), path: PathBuf::from(&config.path), caught_up: AtomicBool::new(false), strict_parsing }
+    }
+
+    /// Recorded watermark (seconds since epoch), None before the first backfill
+    pub fn watermark(&self) -> Result<Option<f64>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&self.path).with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let watermark: Watermark = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid backfill watermark in {}", self.path.display()))?;
+        Ok(Some(watermark.through))
+    }
+
+    /// Record that trades up to `through` are on the topic, replacing the file atomically
+    pub fn mark(&self, through: f64) -> Result<()> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(&Watermark { through })?)?;
+        fs::rename(&tmp, &self.path).with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// Earliest trade time fetched by a backfill at `now`
+    pub fn window_start(&self, watermark: Option<f64>, now: f64) -> f64 {
+        let earliest = now - self.config.lookback_sec as f64;
+        match watermark {
+            Some(through) => (through - self.config.overlap_sec as f64).max(earliest),
+            None => earliest,
+        }
+    }
+
+    /// Trades of `pages` made from `since` on, each once and oldest first
+    pub fn ordered(pages: &[TradeHistoryResult], since: f64, strict: bool) -> Result<Vec<Trade>> {
+        let mut seen = HashSet::new();
+        let mut trades = Vec::new();
+        for page in pages {
+            for trade in page.parsed(strict)? {
+                if trade.time >= since && seen.insert(trade.trade_id.clone()) {
+                    trades.push(trade);
+                }
+            }
+        }
+        trades.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(trades)
+    }
+
+    /// Fetch every page of the account's trades between `since` and `until`
+    pub async fn fetch(&self, rest: &ThalexRestClient, since: f64, until: f64) -> Result<Vec<TradeHistoryResult>> {
+        let mut query = TradeHistoryQuery {
+            time_low: Some(since),
+            time_high: Some(until),
+            limit: Some(self.config.page_size),
+            ..TradeHistoryQuery::default()
+        };
+        let mut pages = Vec::new();
+        loop {
+            let page = rest.trade_history(&query).await?;
+            let bookmark = page.bookmark.clone().filter(|_| !page.trades.is_empty());
+            pages.push(page);
+            match bookmark {
+                Some(bookmark) => query.bookmark = Some(bookmark),
+                None => return Ok(pages),
+            }
+        }
+    }
+
+    /// Publish the trades made since the watermark, up to `now`; returns how many were published
+    ///
+    /// The watermark moves to `now` only once every trade was delivered.
+    pub async fn run(&self, rest: &ThalexRestClient, producer: &KafkaProducer, now: f64) -> Result<usize> {
+        self.caught_up.store(false, Ordering::Relaxed);
+        let since = self.window_start(self.watermark()?, now);
+        let pages = self.fetch(rest, since, now).await?;
+        let trades = Self::ordered(&pages, since, self.strict_parsing)?;
+        for trade in &trades {
+            producer.send_trade(trade).await?;
+            metrics::global().incr(METRIC_BACKFILLED_TRADES, 1);
+        }
+        self.mark(now)?;
+        self.caught_up.store(true, Ordering::Relaxed);
+        info!("Backfilled {} trades made since {:.0}", trades.len(), since);
+        Ok(trades.len())
+    }
+
+    /// The session is over: if its backfill caught up, its live trades were published up to `now`
+    pub fn session_ended(&self, now: f64) {
+        if self.caught_up.swap(false, Ordering::Relaxed) {
+            if let Err(e) = self.mark(now) {
+                warn!("Failed to record the backfill watermark: {:#}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use log::warn;
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use crate::infrastructure::metrics;
+
+/// Requests sent but not yet answered
+pub const METRIC_PENDING_CALLS: &str = "thalex.pending_calls";
+
+/// Unanswered requests kept before the oldest are forgotten
+pub const MAX_PENDING_CALLS: usize = 10_000;
+
+/// RPC methods the bot sends to Thalex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcMethod {
+    Login,
+    Instruments,
+    Instrument,
+    PublicSubscribe,
+    PrivateSubscribe,
+    Unsubscribe,
+    SetCancelOnDisconnect,
+    CancelSession,
+    CancelAll,
+    Insert,
+    Amend,
+    Cancel,
+    MassQuote,
+    OpenOrders,
+    AccountSummary,
+    Portfolio,
+}
+
+impl RpcMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcMethod::Login => "public/login",
+            RpcMethod::Instruments => "public/instruments",
+            RpcMethod::Instrument => "public/instrument",
+            RpcMethod::PublicSubscribe => "public/subscribe",
+            RpcMethod::PrivateSubscribe => "private/subscribe",
+            RpcMethod::Unsubscribe => "unsubscribe",
+            RpcMethod::SetCancelOnDisconnect => "private/set_cancel_on_disconnect",
+            RpcMethod::CancelSession => "private/cancel_session",
+            RpcMethod::CancelAll => "private/cancel_all",
+            RpcMethod::Insert => "private/insert",
+            RpcMethod::Amend => "private/amend",
+            RpcMethod::Cancel => "private/cancel",
+            RpcMethod::MassQuote => "private/mass_quote",
+            RpcMethod::OpenOrders => "private/open_orders",
+            RpcMethod::AccountSummary => "private/account_summary",
+            RpcMethod::Portfolio => "private/portfolio",
+        }
+    }
+}
+
+impl fmt::Display for RpcMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A request awaiting its response
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingCall {
+    pub method: RpcMethod,
+    /// What the request was about, e.g. the client order id of an order request
+    pub context: Option<String>,
+}
+
+/// The exchange's answer to a request
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallResponse {
+    Result(Value),
+    Error(Value),
+}
+
+/// Future of the response to one request, from `CallRegistry::allocate_awaited`
+#[derive(Debug)]
+pub struct CallWaiter {
+    id: u64,
+    method: RpcMethod,
+    receiver: oneshot::Receiver<CallResponse>,
+}
+
+impl CallWaiter {
+    /// Request id to send the call with
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Wait up to `timeout` for the response and return its result
+    ///
+    /// Fails with the exchange's error, when the request was dropped without being
+    /// answered (expired in the queue or forgotten), or when no answer came in time. A
+    /// timed out request may still have reached the exchange.
+    pub async fn response(self, timeout: Duration) -> Result<Value> {
+        match tokio::time::timeout(timeout, self.receiver).await {
+            Ok(Ok(CallResponse::Result(result))) => Ok(result),
+            Ok(Ok(CallResponse::Error(error))) => Err(anyhow!("{} cid={} failed: {}", self.method, self.id, error)),
+            Ok(Err(_)) => Err(anyhow!("{} cid={} was dropped without a response", self.method, self.id)),
+            Err(_) => Err(anyhow!("No response to {} cid={} within {:?}", self.method, self.id, timeout)),
+        }
+    }
+}
+
+/// Hands out request ids and remembers what each outstanding request was
+///
+/// Ids increase monotonically, so concurrent requests of the same kind get distinct
+/// ids and their responses can be attributed to the call that caused them. Callers
+/// that need the outcome of a request allocate it with `allocate_awaited` and await
+/// the waiter, which the response handler resolves.
+pub struct CallRegistry {
+    state: Mutex<CallState>,
+}
+
+struct CallState {
+    next_id: u64,
+    pending: BTreeMap<u64, PendingCall>,
+    /// Senders of the waiters of awaited requests
+    waiters: HashMap<u64, oneshot::Sender<CallResponse>>,
+}
+
+impl Default for CallRegistry {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(CallState { next_id: 1, pending: BTreeMap::new(), waiters: HashMap::new() }),
+        }
+    }
+}
+
+impl CallRegistry {
+    /// Reserve an id for a request of `method` and register it as pending
+    pub fn allocate(&self, method: RpcMethod, context: Option<String>) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.pending.insert(id, PendingCall { method, context });
+        
+        // Requests whose response never came (e.g. lost on a dropped connection)
+        while state.pending.len() > MAX_PENDING_CALLS {
+            if let Some((old_id, call)) = state.pending.pop_first() {
+                warn!("Forgetting unanswered request cid={} ({})", old_id, call.method);
+                state.waiters.remove(&old_id);
+            }
+        }
+        metrics::global().set_gauge(METRIC_PENDING_CALLS, state.pending.len() as f64);
+        id
+    }
+
+    /// Reserve an id like `allocate` and return a waiter for the request's response
+    pub fn allocate_awaited(&self, method: RpcMethod, context: Option<String>) -> CallWaiter {
+        let id = self.allocate(method, context);
+        let (sender, receiver) = oneshot::channel();
+        self.state.lock().unwrap().waiters.insert(id, sender);
+        CallWaiter { id, method, receiver }
+    }
+
+    /// Remove and return the pending request answered by a response with `id`
+    ///
+    /// A waiter of the request fails as dropped; use `resolve` to hand it the response.
+    pub fn complete(&self, id: u64) -> Option<PendingCall> {
+        let mut state = self.state.lock().unwrap();
+        let call = state.pending.remove(&id);
+        state.waiters.remove(&id);
+        metrics::global().set_gauge(METRIC_PENDING_CALLS, state.pending.len() as f64);
+        call
+    }
+
+    /// Complete the request `id` with its result or error, handing it to the request's waiter
+    pub fn resolve(&self, id: u64, response: Result<&Value, &Value>) -> Option<PendingCall> {
+        let mut state = self.state.lock().unwrap();
+        let call = state.pending.remove(&id);
+        if let Some(waiter) = state.waiters.remove(&id) {
+            let response = match response {
+                Ok(result) => CallResponse::Result(result.clone()),
+                Err(error) => CallResponse::Error(error.clone()),
+            };
+            // The waiter may have given up already
+            let _ = waiter.send(response);
+        }
+        metrics::global().set_gauge(METRIC_PENDING_CALLS, state.pending.len() as f64);
+        call
+    }
+
+    /// Look up a pending request without completing it
+    pub fn get(&self, id: u64) -> Option<PendingCall> {
+        self.state.lock().unwrap().pending.get(&id).cloned()
+    }
+
+    /// Number of requests awaiting a response
+    pub fn outstanding(&self) -> usize {
+        self.state.lock().unwrap().pending.len()
+    }
+}
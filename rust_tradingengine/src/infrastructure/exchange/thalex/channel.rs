@@ -0,0 +1,188 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Default delay used for ticker and book subscriptions
+pub const DEFAULT_DELAY: &str = "raw";
+
+/// Default price grouping used for book subscriptions
+pub const DEFAULT_GROUPING: &str = "none";
+
+/// Trade category of public trade subscriptions: every trade, whatever its kind
+pub const DEFAULT_TRADES_CATEGORY: &str = "all";
+
+/// Book depths (number of levels) the exchange publishes
+pub const SUPPORTED_BOOK_DEPTHS: &[u32] = &[1, 5, 10, 50];
+
+/// Update delays the exchange supports for ticker and book channels
+pub const SUPPORTED_DELAYS: &[&str] = &["raw", "100ms", "200ms", "500ms", "1000ms", "5000ms", "60000ms"];
+
+/// Typed representation of a Thalex subscription channel name
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// `ticker.<instrument>.<delay>`
+    Ticker { instrument: String, delay: String },
+
+    /// `book.<instrument>.<grouping>.<depth>.<delay>`
+    Book { instrument: String, grouping: String, depth: u32, delay: String },
+
+    /// `price_index.<underlying>`
+    Index(String),
+
+    /// `recent_trades.<instrument>.<category>`: the market's trades in an instrument
+    RecentTrades { instrument: String, category: String },
+
+    /// `session.orders`
+    Orders,
+
+    /// `account.trade_history`
+    Trades,
+
+    /// `account.portfolio`
+    Portfolio,
+
+    /// `user.inbox_notifications`: deposits, withdrawals, liquidations and other account events
+    AccountEvents,
+}
+
+impl Channel {
+    /// Ticker channel with the default (raw) delay
+    pub fn ticker(instrument: &str) -> Self {
+        Channel::Ticker {
+            instrument: instrument.to_string(),
+            delay: DEFAULT_DELAY.to_string(),
+        }
+    }
+
+    /// Public trades channel of an instrument, every category
+    pub fn recent_trades(instrument: &str) -> Self {
+        Channel::RecentTrades {
+            instrument: instrument.to_string(),
+            category: DEFAULT_TRADES_CATEGORY.to_string(),
+        }
+    }
+
+    /// Book channel with default grouping and delay
+    pub fn book(instrument: &str, depth: u32) -> Self {
+        Channel::Book {
+            instrument: instrument.to_string(),
+            grouping: DEFAULT_GROUPING.to_string(),
+            depth,
+            delay: DEFAULT_DELAY.to_string(),
+        }
+    }
+
+    /// Book channel with explicit grouping and delay, validated against what the exchange supports
+    pub fn book_with(instrument: &str, grouping: &str, depth: u32, delay: &str) -> Result<Self> {
+        let channel = Channel::Book {
+            instrument: instrument.to_string(),
+            grouping: grouping.to_string(),
+            depth,
+            delay: delay.to_string(),
+        };
+        channel.validate()?;
+        Ok(channel)
+    }
+
+    /// Check the channel parameters against what the exchange supports
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Channel::Ticker { delay, .. } => validate_delay(delay),
+            Channel::Book { grouping, depth, delay, .. } => {
+                if !SUPPORTED_BOOK_DEPTHS.contains(depth) {
+                    return Err(anyhow!("Unsupported book depth {} (supported: {:?})", depth, SUPPORTED_BOOK_DEPTHS));
+                }
+                if grouping != DEFAULT_GROUPING && !grouping.parse::<f64>().is_ok_and(|g| g > 0.0) {
+                    return Err(anyhow!("Invalid book grouping '{}': expected \"{}\" or a positive price step", grouping, DEFAULT_GROUPING));
+                }
+                validate_delay(delay)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the channel requires the private subscribe call
+    pub fn is_private(&self) -> bool {
+        matches!(self, Channel::Orders | Channel::Trades | Channel::Portfolio | Channel::AccountEvents)
+    }
+
+    /// Whether a channel name is that of a private channel, without parsing it
+    pub fn is_private_name(name: &str) -> bool {
+        matches!(name, "session.orders" | "account.trade_history" | "account.portfolio" | "user.inbox_notifications")
+    }
+
+    /// Instrument the channel refers to, if any
+    pub fn instrument(&self) -> Option<&str> {
+        match self {
+            Channel::Ticker { instrument, .. } | Channel::Book { instrument, .. } | Channel::RecentTrades { instrument, .. } => Some(instrument),
+            _ => None,
+        }
+    }
+}
+
+fn validate_delay(delay: &str) -> Result<()> {
+    if SUPPORTED_DELAYS.contains(&delay) {
+        Ok(())
+    } else {
+        Err(anyhow!("Unsupported delay '{}' (supported: {:?})", delay, SUPPORTED_DELAYS))
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Channel::Ticker { instrument, delay } => write!(f, "ticker.{}.{}", instrument, delay),
+            Channel::Book { instrument, grouping, depth, delay } => {
+                write!(f, "book.{}.{}.{}.{}", instrument, grouping, depth, delay)
+            }
+            Channel::Index(underlying) => write!(f, "price_index.{}", underlying),
+            Channel::RecentTrades { instrument, category } => write!(f, "recent_trades.{}.{}", instrument, category),
+            Channel::Orders => write!(f, "session.orders"),
+            Channel::Trades => write!(f, "account.trade_history"),
+            Channel::Portfolio => write!(f, "account.portfolio"),
+            Channel::AccountEvents => write!(f, "user.inbox_notifications"),
+        }
+    }
+}
+
+impl FromStr for Channel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // A fractional grouping such as "0.5" has a dot of its own, so book channels are
+        // split around it: the instrument from the left, depth and delay from the right
+        if let Some(rest) = s.strip_prefix("book.") {
+            let parsed = rest.split_once('.').and_then(|(instrument, rest)| {
+                let (rest, delay) = rest.rsplit_once('.')?;
+                let (grouping, depth) = rest.rsplit_once('.')?;
+                Some((instrument, grouping, depth, delay))
+            });
+            return match parsed {
+                Some((instrument, grouping, depth, delay)) if !instrument.is_empty() && !grouping.is_empty() => Ok(Channel::Book {
+                    instrument: instrument.to_string(),
+                    grouping: grouping.to_string(),
+                    depth: depth.parse().map_err(|_| anyhow!("Invalid book depth in channel: {}", s))?,
+                    delay: delay.to_string(),
+                }),
+                _ => Err(anyhow!("Unknown channel: {}", s)),
+            };
+        }
+        let parts: Vec<&str> = s.split('.').collect();
+        match parts.as_slice() {
+            ["session", "orders"] => Ok(Channel::Orders),
+            ["account", "trade_history"] => Ok(Channel::Trades),
+            ["account", "portfolio"] => Ok(Channel::Portfolio),
+            ["user", "inbox_notifications"] => Ok(Channel::AccountEvents),
+            ["price_index", underlying] if !underlying.is_empty() => Ok(Channel::Index(underlying.to_string())),
+            ["ticker", instrument, delay] if !instrument.is_empty() => Ok(Channel::Ticker {
+                instrument: instrument.to_string(),
+                delay: delay.to_string(),
+            }),
+            ["recent_trades", instrument, category] if !instrument.is_empty() && !category.is_empty() => Ok(Channel::RecentTrades {
+                instrument: instrument.to_string(),
+                category: category.to_string(),
+            }),
+            _ => Err(anyhow!("Unknown channel: {}", s)),
+        }
+    }
+}
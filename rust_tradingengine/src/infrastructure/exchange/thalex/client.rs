@@ -1,7 +1,12 @@
 use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
 use tokio::net::TcpStream;
+use tokio::sync::{mpsc, Notify};
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
 use tungstenite::Message;
 use url::Url;
@@ -9,11 +14,24 @@ use url::Url;
 use chrono::Utc;
 use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
 use serde::Serialize;
-use log::{debug, error};
+use log::{debug, error, warn};
 use anyhow::{anyhow};
 
 use crate::domain::enums::*;
 use crate::domain::model::exchange::*;
+use crate::domain::model::quote::{MassQuote, SideQuote};
+use crate::domain::clock;
+use super::error::ClientError;
+use super::keys::KeySource;
+use super::calls::CallRegistry;
+use super::rate_limit::{RateLimiter, METRIC_RATE_LIMIT_DROPPED};
+use super::liveness::Liveness;
+use super::outbound::{dropped_response, OutboundQueue, OutboundRequest, METRIC_OUTBOUND_DISCARDED};
+use crate::infrastructure::exchange::{ExchangeClient, OrderGateway, Venue};
+use crate::infrastructure::metrics;
+
+/// Deadline applied to each request and synchronous receive unless configured otherwise
+pub const DEFAULT_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 pub struct ThalexKeys {
@@ -34,9 +52,62 @@ impl Network {
             Network::PROD => "wss://thalex.com/ws/api/v2",
         }
     }
+
+    pub fn rest_url(&self) -> &'static str {
+        match self {
+            Network::TEST => "https://testnet.thalex.com/api/v2",
+            Network::PROD => "https://thalex.com/api/v2",
+        }
+    }
+
+    /// Suffix of the per-network credential environment variables
+    pub fn env_suffix(&self) -> &'static str {
+        match self {
+            Network::TEST => "TEST",
+            Network::PROD => "PROD",
+        }
+    }
+}
+
+impl std::str::FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "test" | "testnet" => Ok(Network::TEST),
+            "prod" | "production" => Ok(Network::PROD),
+            _ => Err(anyhow!("Unknown network: {}", s)),
+        }
+    }
 }
 
 impl ThalexKeys {
+    /// Load the key id and private key for the network
+    ///
+    /// The key id comes from `THALEX_KID_<NET>`. The private key is read from the first
+    /// available of: `THALEX_KEY_<NET>` (PEM text), `THALEX_KEY_FILE_<NET>` (PEM file,
+    /// optionally PKCS#8-encrypted) or the OS keyring entry `THALEX_KEY_<NET>`.
+    pub fn load(env: &Network) -> Result<Self> {
+        let suffix = env.env_suffix();
+        let kid_var = format!("THALEX_KID_{}", suffix);
+        let kid = std::env::var(&kid_var).map_err(|_| anyhow!("Missing {}", kid_var))?;
+        let private_key = Self::key_source(env).load()?;
+        Ok(Self { kid, private_key })
+    }
+
+    /// Where the private key for the network will be loaded from
+    pub fn key_source(env: &Network) -> KeySource {
+        let key_var = format!("THALEX_KEY_{}", env.env_suffix());
+        let file_var = format!("THALEX_KEY_FILE_{}", env.env_suffix());
+        if std::env::var(&key_var).is_ok() {
+            KeySource::Env(key_var)
+        } else if let Ok(path) = std::env::var(&file_var) {
+            KeySource::File(path)
+        } else {
+            KeySource::Keyring(key_var)
+        }
+    }
+
     pub fn from_env(env: &Network) -> Self {
         match env {
             Network::TEST => Self {
@@ -73,8 +144,105 @@ impl ThalexKeys {
     }
 }
 
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Read half of the WebSocket connection
+///
+/// Detached from the client with `ThalexClient::take_reader` so the listen loop can
+/// wait for messages without holding the client lock that senders need.
+pub struct ThalexReader {
+    stream: SplitStream<WsStream>,
+    liveness: Arc<Liveness>,
+}
+
+impl ThalexReader {
+    /// Receive with a deadline, failing with `ClientError::Timeout` if nothing arrives
+    pub async fn receive_timeout(&mut self, timeout: std::time::Duration) -> Result<Option<String>> {
+        tokio::time::timeout(timeout, self.receive())
+            .await
+            .map_err(|_| ClientError::Timeout { operation: "receive".to_string(), after: timeout })?
+    }
+
+    /// Receive a message from the WebSocket server and return as a String
+    ///
+    /// Returns `Ok(None)` for non-text frames and an error once the stream has ended.
+    pub async fn receive(&mut self) -> Result<Option<String>> {
+        match self.stream.next().await {
+            Some(Ok(msg)) => {
+                // Handle each type of WebSocket message
+                match msg {
+                    Message::Text(text) => {
+                        // The text itself is logged once it has been routed
+                        // Return the text message as a String
+                        Ok(Some(text))
+                    }
+                    Message::Binary(_) => {
+                        debug!("Received binary message");
+                        Ok(None) // Binary messages are ignored, return None
+                    }
+                    Message::Ping(_) => {
+                        debug!("Received ping, automatically responding with pong");
+                        Ok(None) // No String to return for Ping, return None
+                    }
+                    Message::Pong(payload) => {
+                        match self.liveness.pong_received(&payload, clock::instant()) {
+                            Some(rtt) => debug!("Received pong, rtt {:?}", rtt),
+                            None => debug!("Received unmatched pong"),
+                        }
+                        Ok(None) // No String to return for Pong, return None
+                    }
+                    Message::Close(_) => {
+                        debug!("Received close frame");
+                        Ok(None) // No String to return for Close, return None
+                    }
+                    Message::Frame(_) => {
+                        debug!("Received raw frame");
+                        Ok(None) // No String to return for Frame, return None
+                    }
+                }
+            }
+            Some(Err(e)) => {
+                error!("Error receiving message: {}", e);
+                Err(anyhow!("WebSocket error: {}", e)) // Return the error wrapped in Result
+            }
+            None => {
+                debug!("WebSocket stream ended");
+                Err(anyhow!("WebSocket stream ended"))
+            }
+        }
+    }
+}
+
 pub struct ThalexClient {
-    pub socket: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    /// Write half of the WebSocket connection
+    writer: Option<SplitSink<WsStream, Message>>,
+    
+    /// Read half, until detached by the listen loop
+    reader: Option<ThalexReader>,
+    
+    /// Deadline for each request and synchronous receive
+    request_timeout: std::time::Duration,
+    
+    /// Ping/pong tracking for the current connection
+    liveness: Arc<Liveness>,
+    
+    /// Request ids and the requests still awaiting a response
+    calls: Arc<CallRegistry>,
+    
+    /// Requests held back while disconnected or throttled, written in priority order
+    outbound: OutboundQueue,
+    
+    /// Hold back requests until then after the venue reported a rate limit
+    throttled_until: Option<std::time::Instant>,
+    
+    /// Request budgets, so bursts don't trip the venue's rate limit in the first place
+    rate_limiter: RateLimiter,
+    
+    /// Where rejections of requests dropped unsent are reported, next to the venue's responses
+    rejections: Option<mpsc::UnboundedSender<String>>,
+    
+    /// Signalled when a send leaves requests queued, to wake whoever drains the queue
+    outbound_ready: Arc<Notify>,
 }
 
 impl Default for ThalexClient {
@@ -85,29 +253,185 @@ impl Default for ThalexClient {
 
 impl ThalexClient {
     pub fn new() -> Self {
-        ThalexClient { socket: None }
+        ThalexClient {
+            writer: None,
+            reader: None,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            liveness: Arc::new(Liveness::default()),
+            calls: Arc::new(CallRegistry::default()),
+            outbound: OutboundQueue::default(),
+            throttled_until: None,
+            rate_limiter: RateLimiter::unlimited(),
+            rejections: None,
+            outbound_ready: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Report requests dropped before they were written on `rejections`, as error responses
+    ///
+    /// Without it a dropped request's call is just completed, and whoever waits for its
+    /// response never hears of it.
+    pub fn set_rejections(&mut self, rejections: mpsc::UnboundedSender<String>) {
+        self.rejections = Some(rejections);
+    }
+
+    /// Settle a request dropped before it was written
+    fn dropped(&self, request: &OutboundRequest, reason: &str) {
+        let Some(id) = request.id else {
+            return;
+        };
+        let reported = match (&self.rejections, dropped_response(request, reason)) {
+            (Some(rejections), Some(response)) => rejections.send(response).is_ok(),
+            _ => false,
+        };
+        if !reported {
+            self.calls.complete(id);
+        }
+    }
+
+    /// Drop every queued request, e.g. once the session they were queued for ended
+    ///
+    /// Returns how many were discarded. Order requests are stale by the next session,
+    /// which quotes afresh and replays its subscriptions, so nothing is carried over.
+    pub fn discard_outbound(&mut self) -> usize {
+        let discarded = self.outbound.drain();
+        if !discarded.is_empty() {
+            warn!("Discarding {} queued requests of the ended session", discarded.len());
+            metrics::global().incr(METRIC_OUTBOUND_DISCARDED, discarded.len() as u64);
+        }
+        for request in &discarded {
+            self.dropped(request, "session ended");
+        }
+        discarded.len()
+    }
+
+    /// Set the deadline applied to each request and synchronous receive
+    pub fn set_request_timeout(&mut self, timeout: std::time::Duration) {
+        self.request_timeout = timeout;
+    }
+
+    /// Drop the socket after a timeout so later calls fail fast and the session reconnects
+    fn timed_out(&mut self, operation: &str) -> anyhow::Error {
+        error!("{} timed out after {:?}, dropping connection", operation, self.request_timeout);
+        self.reader = None;
+        self.writer = None;
+        ClientError::Timeout { operation: operation.to_string(), after: self.request_timeout }.into()
+    }
+
+    /// Write a frame to the socket within the request deadline
+    async fn write(&mut self, operation: &str, message: Message) -> Result<()> {
+        let Some(writer) = &mut self.writer else {
+            return Err(ClientError::NotConnected.into());
+        };
+        match tokio::time::timeout(self.request_timeout, writer.send(message)).await {
+            Ok(result) => Ok(result?),
+            Err(_) => Err(self.timed_out(operation)),
+        }
     }
 
     pub async fn connect(&mut self, network: Network) -> Result<()> {
-        let url = Url::parse(network.url())?;
+        self.connect_url(network.url()).await
+    }
+
+    async fn connect_url(&mut self, url: &str) -> Result<()> {
+        let url = Url::parse(url)?;
         let (socket, _) = connect_async(url).await?;
-        self.socket = Some(socket);
+        let (writer, stream) = socket.split();
+        self.liveness = Arc::new(Liveness::default());
+        self.writer = Some(writer);
+        self.reader = Some(ThalexReader { stream, liveness: self.liveness.clone() });
         Ok(())
     }
 
     pub async fn disconnect(&mut self) -> Result<()> {
-        if let Some(mut socket) = self.socket.take() {
-            socket.close(None).await?;
+        self.reader = None;
+        if let Some(mut writer) = self.writer.take() {
+            writer.close().await?;
             Ok(())
         } else {
-            Err(anyhow::anyhow!("WebSocket not connected"))
+            Err(ClientError::NotConnected.into())
         }
     }
 
     pub fn connected(&self) -> bool {
-        self.socket.is_some()
+        self.writer.is_some()
+    }
+
+    /// Detach the read half so messages can be received without holding the client
+    pub fn take_reader(&mut self) -> Option<ThalexReader> {
+        self.reader.take()
+    }
+
+    /// Send a WebSocket ping frame, tracked until its pong arrives
+    pub async fn ping(&mut self) -> Result<()> {
+        if !self.connected() {
+            return Err(ClientError::NotConnected.into());
+        }
+        let payload = self.liveness.ping_sent(clock::instant());
+        self.write("ping", Message::Ping(payload)).await
+    }
+
+    /// Ping/pong state of the current connection
+    pub fn liveness(&self) -> Arc<Liveness> {
+        self.liveness.clone()
+    }
+
+    /// Request id allocator shared with whoever handles the responses
+    pub fn calls(&self) -> Arc<CallRegistry> {
+        self.calls.clone()
+    }
+
+    /// Budget the requests sent from now on
+    pub fn set_rate_limiter(&mut self, rate_limiter: RateLimiter) {
+        self.rate_limiter = rate_limiter;
+    }
+
+    /// Hold back requests for `duration`, e.g. after the venue reported a rate limit
+    pub fn throttle(&mut self, duration: std::time::Duration) {
+        warn!("Throttling outbound requests for {:?}", duration);
+        self.throttled_until = Some(clock::instant() + duration);
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        self.throttled_until.is_some_and(|until| clock::instant() < until)
+    }
+
+    /// Number of requests waiting to be written
+    pub fn outbound_depth(&self) -> usize {
+        self.outbound.len()
     }
 
+    /// Notified whenever a send leaves requests in the outbound queue
+    pub fn outbound_ready(&self) -> Arc<Notify> {
+        self.outbound_ready.clone()
+    }
+
+    /// When a `flush` next has something to do, None while the queue is empty
+    ///
+    /// That's when the throttle ends and a queued request's budgets refill, or when the
+    /// oldest amend or insert expires, whichever comes first. While disconnected only
+    /// the expiry counts.
+    pub fn next_flush(&mut self) -> Option<std::time::Instant> {
+        if self.outbound.is_empty() {
+            return None;
+        }
+        let now = clock::instant();
+        let expiry = self.outbound.next_expiry();
+        let writable = self.connected().then(|| {
+            let limiter = &mut self.rate_limiter;
+            let ready = self.outbound.fronts().map(|request| limiter.ready_at(&request.method, now)).min().unwrap_or(now);
+            self.throttled_until.map_or(ready, |until| ready.max(until))
+        });
+        match (expiry, writable) {
+            (Some(expiry), Some(writable)) => Some(expiry.min(writable)),
+            (expiry, writable) => expiry.or(writable),
+        }
+    }
+
+    /// Queue a request and write whatever the socket currently accepts
+    ///
+    /// While disconnected or throttled the request stays queued and this returns Ok;
+    /// it goes out on a later `flush` unless it's an amend or insert that went stale.
     async fn send(
         &mut self,
         method: &str,
@@ -124,66 +448,79 @@ impl ThalexClient {
         }
 
         let request_text = serde_json::to_string(&request)?;
-        println!("Sending request: {}", request_text);
+        self.outbound.push(OutboundRequest {
+            method: method.to_string(),
+            id,
+            text: request_text,
+            queued_at: clock::instant(),
+        });
+        self.flush().await?;
+        if !self.outbound.is_empty() {
+            self.outbound_ready.notify_one();
+        }
+        Ok(())
+    }
 
-        if let Some(socket) = &mut self.socket {
-            socket.send(Message::Text(request_text)).await?;
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("WebSocket not connected"))
+    /// Drop the queued amends and inserts their budget has no room for
+    fn drop_over_budget(&mut self, now: std::time::Instant) {
+        let limiter = &mut self.rate_limiter;
+        let dropped = self.outbound.drop_expiring(|request| !limiter.admits(&request.method, now));
+        if dropped.is_empty() {
+            return;
+        }
+        warn!("Dropping {} requests over the rate limit budget", dropped.len());
+        metrics::global().incr(METRIC_RATE_LIMIT_DROPPED, dropped.len() as u64);
+        for request in &dropped {
+            self.dropped(request, "over its request budget");
         }
     }
 
+    /// Write queued requests in priority order while the socket accepts them
+    ///
+    /// Returns the number of requests written.
+    pub async fn flush(&mut self) -> Result<usize> {
+        for expired in self.outbound.expire(clock::instant()) {
+            warn!("Dropping {} queued for {:?}", expired.method, clock::instant().saturating_duration_since(expired.queued_at));
+            self.dropped(&expired, "expired in the outbound queue");
+        }
+
+        let mut sent = 0;
+        while self.connected() && !self.is_throttled() {
+            let now = clock::instant();
+            let limiter = &mut self.rate_limiter;
+            let Some(request) = self.outbound.pop_admitted(|request| limiter.try_acquire(&request.method, now)) else {
+                if self.rate_limiter.drops_overflow() {
+                    self.drop_over_budget(now);
+                }
+                break;
+            };
+            debug!("Sending request: {}", request.text);
+            self.write(&request.method, Message::Text(request.text)).await?;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
     /// Receive a message from the WebSocket server and return as a String
+    ///
+    /// Only available until the reader has been detached with `take_reader`.
     pub async fn receive(&mut self) -> Result<Option<String>> {
-        if let Some(socket) = &mut self.socket {
-            match socket.next().await {
-                Some(Ok(msg)) => {
-                    // Handle each type of WebSocket message
-                    match msg {
-                        Message::Text(text) => {
-                            debug!("Received text: {}", text);
-                            // Return the text message as a String
-                            Ok(Some(text))
-                        }
-                        Message::Binary(_) => {
-                            debug!("Received binary message");
-                            Ok(None) // Binary messages are ignored, return None
-                        }
-                        Message::Ping(_) => {
-                            debug!("Received ping, automatically responding with pong");
-                            Ok(None) // No String to return for Ping, return None
-                        }
-                        Message::Pong(_) => {
-                            debug!("Received pong");
-                            Ok(None) // No String to return for Pong, return None
-                        }
-                        Message::Close(_) => {
-                            debug!("Received close frame");
-                            Ok(None) // No String to return for Close, return None
-                        }
-                        Message::Frame(_) => {
-                            debug!("Received raw frame");
-                            Ok(None) // No String to return for Frame, return None
-                        }
-                    }
-                }
-                Some(Err(e)) => {
-                    error!("Error receiving message: {}", e);
+        let timeout = self.request_timeout;
+        match &mut self.reader {
+            Some(reader) => match tokio::time::timeout(timeout, reader.receive()).await {
+                Ok(Ok(msg)) => Ok(msg),
+                Err(_) => Err(self.timed_out("receive")),
+                Ok(Err(e)) => {
                     // Connection likely broken, clear the socket and propagate the error
-                    self.socket = None;
-                    Err(anyhow!("WebSocket error: {}", e)) // Return the error wrapped in Result
-                }
-                None => {
-                    debug!("WebSocket stream ended");
-                    // Connection closed, clear the socket
-                    self.socket = None;
-                    Ok(None) // Return Ok(None) when the WebSocket stream ends
+                    self.reader = None;
+                    self.writer = None;
+                    Err(e)
                 }
+            },
+            None => {
+                error!("Not connected to WebSocket server");
+                Err(ClientError::NotConnected.into()) // Handle if the socket is not available
             }
-        } else {
-            error!("Not connected to WebSocket server");
-            Err(anyhow!("Not connected to WebSocket server")) // Handle if the socket is not available
         }
     }
 
@@ -227,6 +564,9 @@ impl ThalexClient {
         }) {
             params["time_in_force"]  = json!(time_in_force);
         }
+        if let Some(label) = order.label {
+            params["label"] = json!(label);
+        }
 
         self.send("private/insert", id, params).await
     }
@@ -246,30 +586,76 @@ impl ThalexClient {
         self.send("private/cancel", id, params).await
     }
 
+    /// Amend the price and amount of an order
+    ///
+    /// `private/amend` requires both. `amount` is the order's new total amount, the part
+    /// already filled included, so keeping a partially filled order's remaining size
+    /// means sending its filled amount plus that remaining size.
     pub async fn amend(
         &mut self,
-        quantity: f64, 
+        amount: f64,
         price: f64,
         order_id: Option<String>,
         client_order_id: Option<u64>,
         id: Option<u64>,
     ) -> Result<()> {
-        // Correct: build an object with a key
+        let params = Self::amend_params(amount, price, order_id, client_order_id)?;
+        self.send("private/amend", id, params).await
+    }
+
+    /// Parameters of `private/amend` for the order identified by exactly one of the ids
+    pub fn amend_params(amount: f64, price: f64, order_id: Option<String>, client_order_id: Option<u64>) -> Result<serde_json::Value> {
         let mut params = match (order_id, client_order_id) {
             (Some(oid), None) => json!({ "order_id": oid }),
             (None, Some(cid)) => json!({ "client_order_id": cid }),
             _ => return Err(anyhow::anyhow!("Exactly one of `client_order_id` or `order_id` must be specified.")),
         };
-    
-        // Now safely mutate the JSON object
-        if let Some(obj) = params.as_object_mut() {
-            obj.insert("price".to_string(), json!(price));
-            obj.insert("amount".to_string(), json!(quantity));
-        } else {
-            return Err(anyhow::anyhow!("Failed to build JSON params"));
+        params["price"] = json!(price);
+        params["amount"] = json!(amount);
+        Ok(params)
+    }
+
+    /// Replace the quotes of each instrument with its ladder in one request
+    ///
+    /// Mass quotes rest under the session rather than as individual orders, so they
+    /// carry no client order ids; fills are reported with `label`. With `post_only`
+    /// levels that would cross the book are adjusted by the exchange rather than taken.
+    pub async fn mass_quote(
+        &mut self,
+        quotes: Vec<MassQuote>,
+        label: Option<String>,
+        post_only: bool,
+        id: Option<u64>,
+    ) -> Result<()> {
+        if quotes.is_empty() {
+            return Err(anyhow::anyhow!("Mass quote needs at least one instrument."));
         }
-    
-        self.send("private/amend", id, params).await
+        let params = Self::mass_quote_params(&quotes, label, post_only);
+        self.send("private/mass_quote", id, params).await
+    }
+
+    /// Parameters of `private/mass_quote`: one `{p, a}` per side and instrument, a side
+    /// left out stays as it is
+    pub fn mass_quote_params(quotes: &[MassQuote], label: Option<String>, post_only: bool) -> serde_json::Value {
+        let level = |level: &SideQuote| json!({ "p": level.price, "a": level.amount });
+        let quotes: Vec<serde_json::Value> = quotes.iter()
+            .map(|quote| {
+                let mut entry = json!({ "i": quote.instrument_name });
+                if let Some(bid) = &quote.bid {
+                    entry["b"] = level(bid);
+                }
+                if let Some(ask) = &quote.ask {
+                    entry["a"] = level(ask);
+                }
+                entry
+            })
+            .collect();
+
+        let mut params = json!({ "quotes": quotes, "post_only": post_only });
+        if let Some(label) = label {
+            params["label"] = json!(label);
+        }
+        params
     }
 
     pub async fn set_cancel_on_disconnect(&mut self, timeout_secs: u64, id: Option<u64>)
@@ -278,12 +664,54 @@ impl ThalexClient {
         self.send("private/set_cancel_on_disconnect", id, params).await
     }
 
-    // Bulk cancel all orders in session
+    // Bulk cancel all orders in session; amends and inserts still queued would be written
+    // after it, so they are dropped
     pub async fn cancel_session(&mut self, id: Option<u64>) -> Result<()>{
+        for request in self.outbound.drop_expiring(|_| true) {
+            self.dropped(&request, "superseded by a session cancel");
+        }
         self.send("private/cancel_session", id, json!({})).await?;
         Ok(())
     }
 
+    /// Drop the queued inserts of `client_order_ids`, so they're never sent, returning the
+    /// client order ids of those dropped
+    ///
+    /// A cancel goes ahead of queued inserts, so an insert still queued would be placed
+    /// after the cancel meant for it. Each dropped insert is reported as rejected.
+    pub fn drop_queued_inserts(&mut self, client_order_ids: &HashSet<u64>) -> Vec<u64> {
+        let client_order_id = |request: &OutboundRequest| serde_json::from_str::<serde_json::Value>(&request.text).ok()
+            .and_then(|request| request["params"]["client_order_id"].as_u64());
+        let dropped = self.outbound.drop_expiring(|request| {
+            request.method == "private/insert" && client_order_id(request).is_some_and(|id| client_order_ids.contains(&id))
+        });
+        for request in &dropped {
+            self.dropped(request, "cancelled before it was sent");
+        }
+        dropped.iter().filter_map(client_order_id).collect()
+    }
+
+    // Bulk cancel all orders of the account, including those of other sessions
+    pub async fn cancel_all(&mut self, id: Option<u64>) -> Result<()>{
+        self.send("private/cancel_all", id, json!({})).await?;
+        Ok(())
+    }
+
+    /// Request the account's open orders
+    pub async fn open_orders(&mut self, id: Option<u64>) -> Result<()> {
+        self.send("private/open_orders", id, json!({})).await
+    }
+
+    /// Request the account's positions
+    pub async fn portfolio(&mut self, id: Option<u64>) -> Result<()> {
+        self.send("private/portfolio", id, json!({})).await
+    }
+
+    /// Request the account's balances and margin
+    pub async fn account_summary(&mut self, id: Option<u64>) -> Result<()> {
+        self.send("private/account_summary", id, json!({})).await
+    }
+
     pub async fn instruments(&mut self, id: Option<u64>) -> Result<()> {
         self.send("public/instruments",    id,  json!({})).await?;
         Ok(())
@@ -307,3 +735,96 @@ impl ThalexClient {
         self.send("unsubscribe", id, params).await
     }
 }
+
+#[async_trait]
+impl OrderGateway for ThalexClient {
+    async fn insert(&mut self, order: OrderRequest, id: Option<u64>) -> Result<()> {
+        ThalexClient::insert(self, order, id).await
+    }
+
+    async fn amend(
+        &mut self,
+        amount: f64,
+        price: f64,
+        order_id: Option<String>,
+        client_order_id: Option<u64>,
+        id: Option<u64>,
+    ) -> Result<()> {
+        ThalexClient::amend(self, amount, price, order_id, client_order_id, id).await
+    }
+
+    async fn cancel(&mut self, order_id: Option<String>, client_order_id: Option<u64>, id: Option<u64>) -> Result<()> {
+        ThalexClient::cancel(self, order_id, client_order_id, id).await
+    }
+
+    async fn open_orders(&mut self, id: Option<u64>) -> Result<()> {
+        ThalexClient::open_orders(self, id).await
+    }
+
+    async fn mass_quote(&mut self, quotes: Vec<MassQuote>, label: Option<String>, post_only: bool, id: Option<u64>) -> Result<()> {
+        ThalexClient::mass_quote(self, quotes, label, post_only, id).await
+    }
+
+    async fn account_summary(&mut self, id: Option<u64>) -> Result<()> {
+        ThalexClient::account_summary(self, id).await
+    }
+
+    async fn portfolio(&mut self, id: Option<u64>) -> Result<()> {
+        ThalexClient::portfolio(self, id).await
+    }
+
+    async fn cancel_session(&mut self, id: Option<u64>) -> Result<()> {
+        ThalexClient::cancel_session(self, id).await
+    }
+
+    fn throttle(&mut self, duration: std::time::Duration) {
+        ThalexClient::throttle(self, duration)
+    }
+
+    fn drop_queued_inserts(&mut self, client_order_ids: &HashSet<u64>) -> Vec<u64> {
+        ThalexClient::drop_queued_inserts(self, client_order_ids)
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for ThalexClient {
+    fn venue(&self) -> Venue {
+        Venue::Thalex
+    }
+
+    async fn connect(&mut self, url: &str) -> Result<()> {
+        self.connect_url(url).await
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        ThalexClient::disconnect(self).await
+    }
+
+    fn connected(&self) -> bool {
+        ThalexClient::connected(self)
+    }
+
+    async fn login(&mut self, token: String, account: Option<String>, id: Option<u64>) -> Result<()> {
+        ThalexClient::login(self, token, account, id).await
+    }
+
+    async fn subscribe(&mut self, channels: Vec<String>, private: bool, id: Option<u64>) -> Result<()> {
+        if private {
+            self.private_subscribe(channels, id).await
+        } else {
+            self.public_subscribe(channels, id).await
+        }
+    }
+
+    async fn unsubscribe(&mut self, channels: Vec<String>, id: Option<u64>) -> Result<()> {
+        ThalexClient::unsubscribe(self, channels, id).await
+    }
+
+    async fn receive(&mut self) -> Result<Option<String>> {
+        ThalexClient::receive(self).await
+    }
+
+    async fn cancel_all(&mut self, id: Option<u64>) -> Result<()> {
+        ThalexClient::cancel_all(self, id).await
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::{anyhow, Result};
+use chrono::DateTime;
+use log::debug;
+
+use super::client::Network;
+
+/// Outcome of comparing the local clock with exchange time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockStatus {
+    /// Drift within the warning threshold
+    InSync,
+    /// Drift above the warning threshold but below the halt threshold
+    Drifted,
+    /// Drift large enough that auth tokens and timestamps can no longer be trusted
+    Halt,
+}
+
+impl ClockStatus {
+    /// Classify an absolute drift against warn/halt thresholds (seconds)
+    pub fn from_drift(drift_sec: f64, warn_sec: f64, halt_sec: f64) -> Self {
+        let drift = drift_sec.abs();
+        if drift >= halt_sec {
+            ClockStatus::Halt
+        } else if drift >= warn_sec {
+            ClockStatus::Drifted
+        } else {
+            ClockStatus::InSync
+        }
+    }
+}
+
+/// Estimate local clock drift against the exchange (seconds, positive when local is ahead)
+///
+/// Uses the `Date` header of a REST request, compared against the midpoint of the
+/// local request/response times. The header has one second resolution, so the
+/// server time is taken as the middle of that second.
+pub async fn measure_drift(network: &Network) -> Result<f64> {
+    let client = reqwest::Client::new();
+
+    let sent = now();
+    let response = client.head(network.rest_url()).send().await
+        .map_err(|e| anyhow!("Failed to query exchange time: {}", e))?;
+    let received = now();
+
+    let date = response.headers()
+        .get(reqwest::header::DATE)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| anyhow!("Exchange response has no Date header"))?;
+    let server_time = DateTime::parse_from_rfc2822(date)
+        .map_err(|e| anyhow!("Invalid Date header '{}': {}", date, e))?
+        .timestamp() as f64 + 0.5;
+
+    let drift = (sent + received) / 2.0 - server_time;
+    debug!("Clock drift {:.3}s (round trip {:.3}s)", drift, received - sent);
+    Ok(drift)
+}
+
+fn now() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Typed failures of the Thalex client that callers may want to react to
+///
+/// Returned wrapped in `anyhow::Error`; use `ClientError::is_timeout` or
+/// `downcast_ref` to inspect.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The operation did not complete before its deadline; the socket is dropped
+    #[error("{operation} timed out after {after:?}")]
+    Timeout { operation: String, after: Duration },
+
+    /// No socket is available (never connected, or dropped after a failure)
+    #[error("WebSocket not connected")]
+    NotConnected,
+}
+
+impl ClientError {
+    /// Whether an error is (or wraps) a client timeout
+    pub fn is_timeout(error: &anyhow::Error) -> bool {
+        matches!(error.downcast_ref::<ClientError>(), Some(ClientError::Timeout { .. }))
+    }
+}
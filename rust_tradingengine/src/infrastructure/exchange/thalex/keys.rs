@@ -0,0 +1,83 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use pkcs8::der::pem::LineEnding;
+use pkcs8::{EncryptedPrivateKeyInfo, SecretDocument};
+use std::path::Path;
+
+/// PEM label of a passphrase-protected PKCS#8 key
+pub const ENCRYPTED_KEY_LABEL: &str = "ENCRYPTED PRIVATE KEY";
+
+/// Environment variable holding the passphrase for an encrypted key file
+pub const PASSPHRASE_ENV_VAR: &str = "THALEX_KEY_PASSPHRASE";
+
+/// Keyring service under which the private keys are stored
+pub const KEYRING_SERVICE: &str = "cryptics_lab_bot";
+
+/// Where the RSA private key is loaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource {
+    /// PEM text in an environment variable
+    Env(String),
+
+    /// PEM file, optionally PKCS#8-encrypted
+    File(String),
+
+    /// OS keyring entry (service `cryptics_lab_bot`, account as given)
+    Keyring(String),
+}
+
+impl KeySource {
+    /// Load the PEM private key, decrypting it if necessary
+    pub fn load(&self) -> Result<String> {
+        let pem = match self {
+            KeySource::Env(var) => std::env::var(var)
+                .map_err(|_| anyhow!("Missing {}", var))?
+                .replace("\\n", "\n"),
+            KeySource::File(path) => std::fs::read_to_string(Path::new(path))
+                .map_err(|e| anyhow!("Failed to read key file '{}': {}", path, e))?,
+            KeySource::Keyring(account) => keyring::Entry::new(KEYRING_SERVICE, account)
+                .and_then(|entry| entry.get_password())
+                .map_err(|e| anyhow!("Failed to read key '{}' from keyring: {}", account, e))?,
+        };
+
+        if is_encrypted(&pem) {
+            info!("Decrypting private key from {:?}", self);
+            decrypt_pem(&pem, &passphrase()?)
+        } else {
+            Ok(pem)
+        }
+    }
+}
+
+/// Whether the PEM text holds an encrypted PKCS#8 key
+pub fn is_encrypted(pem: &str) -> bool {
+    pem.contains(&format!("-----BEGIN {}-----", ENCRYPTED_KEY_LABEL))
+}
+
+/// Decrypt an encrypted PKCS#8 PEM key into an unencrypted PKCS#8 PEM key
+pub fn decrypt_pem(pem: &str, passphrase: &str) -> Result<String> {
+    let (label, document) = SecretDocument::from_pem(pem.trim())
+        .map_err(|e| anyhow!("Invalid PEM key: {}", e))?;
+    if label != ENCRYPTED_KEY_LABEL {
+        return Err(anyhow!("Expected an encrypted PKCS#8 key, found '{}'", label));
+    }
+
+    let decrypted = EncryptedPrivateKeyInfo::try_from(document.as_bytes())
+        .map_err(|e| anyhow!("Invalid encrypted key: {}", e))?
+        .decrypt(passphrase)
+        .map_err(|e| anyhow!("Failed to decrypt key (wrong passphrase?): {}", e))?;
+
+    let pem = decrypted
+        .to_pem("PRIVATE KEY", LineEnding::LF)
+        .map_err(|e| anyhow!("Failed to encode decrypted key: {}", e))?;
+    Ok(pem.to_string())
+}
+
+/// Passphrase from the environment, or from an interactive prompt
+fn passphrase() -> Result<String> {
+    match std::env::var(PASSPHRASE_ENV_VAR) {
+        Ok(passphrase) => Ok(passphrase),
+        Err(_) => rpassword::prompt_password("Thalex key passphrase: ")
+            .map_err(|e| anyhow!("No {} set and prompt failed: {}", PASSPHRASE_ENV_VAR, e)),
+    }
+}
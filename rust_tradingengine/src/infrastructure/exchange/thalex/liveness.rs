@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::infrastructure::metrics;
+
+/// Round trip time of the last answered ping (milliseconds)
+pub const METRIC_PING_RTT_MS: &str = "thalex.ping_rtt_ms";
+
+/// Pings sent without a matching pong yet
+pub const METRIC_MISSED_PONGS: &str = "thalex.missed_pongs";
+
+/// Tracks pings and their pongs to detect a dead connection and measure round trip time
+///
+/// Shared between the client (which sends pings) and the detached reader (which
+/// sees the pongs). Each ping carries a sequence number echoed back in the pong.
+#[derive(Default)]
+pub struct Liveness {
+    state: Mutex<LivenessState>,
+}
+
+#[derive(Default)]
+struct LivenessState {
+    next_seq: u64,
+    outstanding: VecDeque<(u64, Instant)>,
+    last_rtt: Option<Duration>,
+}
+
+impl Liveness {
+    /// Register a ping sent at `now` and return the payload to send with it
+    pub fn ping_sent(&self, now: Instant) -> Vec<u8> {
+        let mut state = self.state.lock().unwrap();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.outstanding.push_back((seq, now));
+        metrics::global().set_gauge(METRIC_MISSED_PONGS, state.outstanding.len() as f64);
+        seq.to_be_bytes().to_vec()
+    }
+
+    /// Register a pong received at `now`, returning the round trip time if it matches a ping
+    ///
+    /// A pong also accounts for every older ping still outstanding.
+    pub fn pong_received(&self, payload: &[u8], now: Instant) -> Option<Duration> {
+        let seq = u64::from_be_bytes(payload.try_into().ok()?);
+        let mut state = self.state.lock().unwrap();
+        let sent = state.outstanding.iter().find(|(s, _)| *s == seq).map(|(_, t)| *t)?;
+        state.outstanding.retain(|(s, _)| *s > seq);
+
+        let rtt = now.saturating_duration_since(sent);
+        state.last_rtt = Some(rtt);
+        metrics::global().set_gauge(METRIC_PING_RTT_MS, rtt.as_secs_f64() * 1000.0);
+        metrics::global().set_gauge(METRIC_MISSED_PONGS, state.outstanding.len() as f64);
+        Some(rtt)
+    }
+
+    /// Pings sent since the last pong
+    pub fn missed_pongs(&self) -> usize {
+        self.state.lock().unwrap().outstanding.len()
+    }
+
+    /// Round trip time of the last answered ping
+    pub fn last_rtt(&self) -> Option<Duration> {
+        self.state.lock().unwrap().last_rtt
+    }
+}
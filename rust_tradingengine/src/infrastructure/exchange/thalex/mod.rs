@@ -1,5 +1,26 @@
+pub mod backfill;
+pub mod calls;
+pub mod channel;
 pub mod client;
+pub mod clock;
+pub mod error;
+pub mod keys;
+pub mod liveness;
 pub mod models;
+pub mod outbound;
 pub mod parsers;
+pub mod rate_limit;
+pub mod rest;
+pub mod session;
+pub mod subscriptions;
 
-pub use parsers::ThaleParser;
\ No newline at end of file
+pub use backfill::TradeBackfill;
+pub use calls::{CallRegistry, CallResponse, CallWaiter, PendingCall, RpcMethod};
+pub use channel::Channel;
+pub use error::ClientError;
+pub use outbound::{OutboundQueue, RequestPriority};
+pub use parsers::ThaleParser;
+pub use rate_limit::{RateLimiter, TokenBucket};
+pub use rest::{ThalexRestClient, TradeHistoryQuery};
+pub use session::{MessageReader, SessionClient};
+pub use subscriptions::SubscriptionManager;
\ No newline at end of file
@@ -1,9 +1,206 @@
 // Models for Thalex API responses
+use anyhow::{Context, Result};
+use log::warn;
 use serde::Deserialize;
+use serde_json::Value;
+
+use crate::domain::model::account_summary::{self, Balance};
 use crate::domain::model::exchange::Instrument;
+use crate::domain::model::trade::Trade;
+
+use super::calls::RpcMethod;
+use super::parsers::ThaleParser;
 
 #[derive(Debug, Deserialize)]
 pub struct InstrumentResponse {
     pub id: u64,
     pub result: Vec<Instrument>,
 }
+
+/// Result of `public/login`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LoginResult {
+    #[serde(default)]
+    pub account_number: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+/// Result of `private/cancel_all`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CancelAllResult {
+    #[serde(default)]
+    pub n_cancelled: Option<u64>,
+}
+
+/// Result of `private/mass_quote`: levels the exchange took and those it refused
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MassQuoteResult {
+    #[serde(default)]
+    pub n_success: u64,
+    #[serde(default)]
+    pub n_fail: u64,
+    /// Reasons of the refused levels, as sent
+    #[serde(default)]
+    pub errors: Vec<Value>,
+}
+
+/// Order status returned by `private/insert`, `private/amend` and `private/cancel`, and
+/// per order by `private/open_orders`
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderResult {
+    #[serde(default)]
+    pub order_id: Option<String>,
+    #[serde(default)]
+    pub client_order_id: Option<u64>,
+    #[serde(default)]
+    pub instrument_name: Option<String>,
+    #[serde(default)]
+    pub direction: Option<String>,
+    #[serde(default)]
+    pub price: Option<f64>,
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub filled_amount: Option<f64>,
+    #[serde(default)]
+    pub remaining_amount: Option<f64>,
+    #[serde(default)]
+    pub label: Option<String>,
+    pub status: String,
+}
+
+/// Position in one instrument, per instrument by `private/portfolio`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PortfolioEntry {
+    pub instrument_name: String,
+    pub position: f64,
+    #[serde(default)]
+    pub mark_price: Option<f64>,
+    #[serde(default)]
+    pub average_price: Option<f64>,
+}
+
+/// Balance of one currency in `private/account_summary`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CashBalance {
+    pub currency: String,
+    pub balance: f64,
+    #[serde(default)]
+    pub collateral_factor: Option<f64>,
+}
+
+/// Result of `private/account_summary`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AccountSummary {
+    #[serde(default)]
+    pub cash: Vec<CashBalance>,
+    #[serde(default)]
+    pub unrealised_pnl: f64,
+    #[serde(default)]
+    pub cash_collateral: f64,
+    /// Account value margin is measured against, cash collateral plus unrealised PnL
+    #[serde(default)]
+    pub margin: f64,
+    #[serde(default)]
+    pub required_margin: f64,
+    #[serde(default)]
+    pub remaining_margin: f64,
+    #[serde(default)]
+    pub session_realised_pnl: f64,
+}
+
+impl AccountSummary {
+    /// The summary as the domain model, received at `timestamp`
+    pub fn to_domain(&self, timestamp: f64) -> account_summary::AccountSummary {
+        account_summary::AccountSummary {
+            timestamp,
+            balances: self.cash.iter()
+                .map(|cash| Balance {
+                    currency: cash.currency.clone(),
+                    balance: cash.balance,
+                    collateral_factor: cash.collateral_factor,
+                })
+                .collect(),
+            cash_collateral: self.cash_collateral,
+            unrealised_pnl: self.unrealised_pnl,
+            margin: self.margin,
+            required_margin: self.required_margin,
+            remaining_margin: self.remaining_margin,
+            session_realised_pnl: self.session_realised_pnl,
+            processing_timestamp: Some(timestamp),
+        }
+    }
+}
+
+/// One page of `private/trade_history`
+///
+/// Trades are kept as sent, since they carry the direction and label the trade model
+/// doesn't; `parsed` maps them onto the model.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TradeHistoryResult {
+    #[serde(default)]
+    pub trades: Vec<Value>,
+    /// Passed back to fetch the next page; None on the last one
+    #[serde(default)]
+    pub bookmark: Option<String>,
+}
+
+impl TradeHistoryResult {
+    pub fn parsed(&self, strict: bool) -> Result<Vec<Trade>> {
+        self.trades.iter().map(|trade| ThaleParser::parse_trade_json(trade, strict)).collect()
+    }
+}
+
+/// Typed result of an RPC call, selected by the method of the request it answers
+#[derive(Debug, Clone)]
+pub enum RpcResult {
+    Instruments(Vec<Instrument>),
+    Instrument(Instrument),
+    /// Channels the subscribe/unsubscribe call applied to
+    Subscribe(Vec<String>),
+    Login(LoginResult),
+    CancelSession,
+    CancelOnDisconnect,
+    CancelAll(CancelAllResult),
+    Order(OrderResult),
+    MassQuote(MassQuoteResult),
+    OpenOrders(Vec<OrderResult>),
+    AccountSummary(AccountSummary),
+    Portfolio(Vec<PortfolioEntry>),
+    /// Responses that can't be attributed to a request
+    Other(Value),
+}
+
+impl RpcResult {
+    /// Deserialize the `result` of a response to the type expected for its request method
+    pub fn parse(method: RpcMethod, result: &Value) -> Result<Self> {
+        let typed = match method {
+            RpcMethod::Instruments => Self::Instruments(Vec::<Instrument>::deserialize(result)?),
+            RpcMethod::Instrument => Self::Instrument(Instrument::deserialize(result)?),
+            RpcMethod::PublicSubscribe | RpcMethod::PrivateSubscribe | RpcMethod::Unsubscribe => {
+                Self::Subscribe(Vec::<String>::deserialize(result)?)
+            }
+            RpcMethod::Login => Self::Login(LoginResult::deserialize(result)?),
+            RpcMethod::CancelSession => Self::CancelSession,
+            RpcMethod::SetCancelOnDisconnect => Self::CancelOnDisconnect,
+            RpcMethod::CancelAll => Self::CancelAll(CancelAllResult::deserialize(result)?),
+            RpcMethod::Insert | RpcMethod::Amend | RpcMethod::Cancel => Self::Order(OrderResult::deserialize(result)?),
+            RpcMethod::MassQuote => Self::MassQuote(MassQuoteResult::deserialize(result)?),
+            RpcMethod::OpenOrders => Self::OpenOrders(Vec::<OrderResult>::deserialize(result)?),
+            RpcMethod::AccountSummary => Self::AccountSummary(AccountSummary::deserialize(result)?),
+            RpcMethod::Portfolio => Self::Portfolio(Vec::<PortfolioEntry>::deserialize(result)?),
+        };
+        Ok(typed)
+    }
+
+    /// Like `parse`, keeping the raw value when the result doesn't have the expected shape
+    pub fn parse_or_raw(method: RpcMethod, result: &Value) -> Self {
+        Self::parse(method, result)
+            .with_context(|| format!("Unexpected {} result: {}", method, result))
+            .unwrap_or_else(|e| {
+                warn!("{:#}", e);
+                Self::Other(result.clone())
+            })
+    }
+}
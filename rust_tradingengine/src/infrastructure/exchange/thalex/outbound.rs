@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::infrastructure::metrics;
+
+/// Requests waiting to be written to the socket, all priorities
+pub const METRIC_OUTBOUND_DEPTH: &str = "thalex.outbound_queue_depth";
+
+/// Queued amends and inserts dropped because they waited longer than the queue's max age
+pub const METRIC_OUTBOUND_EXPIRED: &str = "thalex.outbound_expired";
+
+/// Queued requests discarded because their session ended before they could be written
+pub const METRIC_OUTBOUND_DISCARDED: &str = "thalex.outbound_discarded";
+
+/// Error code of the rejections reported for requests dropped before they were written
+pub const DROPPED_ERROR_CODE: i64 = -32099;
+
+/// How long an amend or insert may wait before it's too stale to send
+pub const DEFAULT_OUTBOUND_MAX_AGE: Duration = Duration::from_secs(2);
+
+/// Order in which queued requests are written; earlier variants go first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RequestPriority {
+    /// Login, subscriptions and other session requests later requests depend on
+    Session,
+    Cancel,
+    Amend,
+    Insert,
+}
+
+impl RequestPriority {
+    pub const ALL: [RequestPriority; 4] = [
+        RequestPriority::Session,
+        RequestPriority::Cancel,
+        RequestPriority::Amend,
+        RequestPriority::Insert,
+    ];
+
+    /// Priority of a request by its RPC method name
+    pub fn of(method: &str) -> Self {
+        match method {
+            "private/cancel" | "private/cancel_all" | "private/cancel_session" => RequestPriority::Cancel,
+            // A mass quote replaces resting quotes like an amend and goes stale as fast
+            "private/amend" | "private/mass_quote" => RequestPriority::Amend,
+            "private/insert" => RequestPriority::Insert,
+            _ => RequestPriority::Session,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequestPriority::Session => "session",
+            RequestPriority::Cancel => "cancel",
+            RequestPriority::Amend => "amend",
+            RequestPriority::Insert => "insert",
+        }
+    }
+
+    /// Whether a request of this priority is dropped once it's older than the max age
+    pub fn expires(&self) -> bool {
+        matches!(self, RequestPriority::Amend | RequestPriority::Insert)
+    }
+
+    /// Gauge with the queue depth of this priority
+    pub fn depth_metric(&self) -> String {
+        format!("{}.{}", METRIC_OUTBOUND_DEPTH, self.as_str())
+    }
+}
+
+/// A serialized request waiting for the socket
+#[derive(Debug, Clone)]
+pub struct OutboundRequest {
+    pub method: String,
+    pub id: Option<u64>,
+    pub text: String,
+    pub queued_at: Instant,
+}
+
+/// Requests held back while the socket is unavailable or the venue throttles us
+///
+/// Cancels preempt amends, which preempt inserts; within a priority requests keep
+/// their order. Session requests (login, subscriptions) go before everything so a
+/// reconnected socket is usable by the time the order requests reach it.
+pub struct OutboundQueue {
+    queues: [VecDeque<OutboundRequest>; 4],
+    max_age: Duration,
+}
+
+impl Default for OutboundQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_OUTBOUND_MAX_AGE)
+    }
+}
+
+impl OutboundQueue {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            queues: Default::default(),
+            max_age,
+        }
+    }
+
+    pub fn push(&mut self, request: OutboundRequest) {
+        let priority = RequestPriority::of(&request.method);
+        self.queues[priority as usize].push_back(request);
+        self.publish_depth();
+    }
+
+    /// Oldest request of the highest non-empty priority
+    pub fn pop(&mut self) -> Option<OutboundRequest> {
+        let request = self.queues.iter_mut().find_map(|queue| queue.pop_front());
+        self.publish_depth();
+        request
+    }
+
+    /// Oldest request of the highest priority whose oldest request `admit` accepts
+    ///
+    /// A priority whose oldest request is refused is skipped as a whole, so requests
+    /// within a priority keep their order.
+    pub fn pop_admitted(&mut self, mut admit: impl FnMut(&OutboundRequest) -> bool) -> Option<OutboundRequest> {
+        let request = self.queues.iter_mut()
+            .find(|queue| queue.front().is_some_and(&mut admit))
+            .and_then(VecDeque::pop_front);
+        self.publish_depth();
+        request
+    }
+
+    /// Remove the queued amends and inserts `reject` refuses
+    pub fn drop_expiring(&mut self, mut reject: impl FnMut(&OutboundRequest) -> bool) -> Vec<OutboundRequest> {
+        let mut dropped = Vec::new();
+        for priority in RequestPriority::ALL.iter().filter(|p| p.expires()) {
+            let queue = &mut self.queues[*priority as usize];
+            let (refused, kept) = std::mem::take(queue).into_iter().partition(|request| reject(request));
+            *queue = kept;
+            dropped.extend::<VecDeque<_>>(refused);
+        }
+        if !dropped.is_empty() {
+            self.publish_depth();
+        }
+        dropped
+    }
+
+    /// Remove amends and inserts that have waited longer than the max age
+    pub fn expire(&mut self, now: Instant) -> Vec<OutboundRequest> {
+        let mut expired = Vec::new();
+        for priority in RequestPriority::ALL.iter().filter(|p| p.expires()) {
+            let queue = &mut self.queues[*priority as usize];
+            while queue.front().is_some_and(|r| now.duration_since(r.queued_at) > self.max_age) {
+                expired.extend(queue.pop_front());
+            }
+        }
+        if !expired.is_empty() {
+            metrics::global().incr(METRIC_OUTBOUND_EXPIRED, expired.len() as u64);
+            self.publish_depth();
+        }
+        expired
+    }
+
+    /// Oldest request of each non-empty priority, the ones the next write picks from
+    pub fn fronts(&self) -> impl Iterator<Item = &OutboundRequest> {
+        self.queues.iter().filter_map(VecDeque::front)
+    }
+
+    /// When the oldest queued amend or insert is past the max age, None if there is none
+    pub fn next_expiry(&self) -> Option<Instant> {
+        RequestPriority::ALL.iter()
+            .filter(|p| p.expires())
+            .filter_map(|p| self.queues[*p as usize].front())
+            // Expired once strictly older than the max age
+            .map(|request| request.queued_at + self.max_age + Duration::from_millis(1))
+            .min()
+    }
+
+    /// Remove every queued request, e.g. when the socket they were queued for is gone
+    pub fn drain(&mut self) -> Vec<OutboundRequest> {
+        let drained: Vec<OutboundRequest> = self.queues.iter_mut().flat_map(std::mem::take).collect();
+        if !drained.is_empty() {
+            self.publish_depth();
+        }
+        drained
+    }
+
+    pub fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.iter().all(VecDeque::is_empty)
+    }
+
+    /// Number of queued requests of one priority
+    pub fn depth(&self, priority: RequestPriority) -> usize {
+        self.queues[priority as usize].len()
+    }
+
+    fn publish_depth(&self) {
+        let metrics = metrics::global();
+        metrics.set_gauge(METRIC_OUTBOUND_DEPTH, self.len() as f64);
+        for priority in RequestPriority::ALL {
+            metrics.set_gauge(&priority.depth_metric(), self.depth(priority) as f64);
+        }
+    }
+}
+
+/// Error response reporting that `request` was dropped before it was written
+///
+/// Shaped like the venue's own errors so the response handler treats it like a reject:
+/// the request's call is resolved and an insert's level freed for the next quote.
+pub fn dropped_response(request: &OutboundRequest, reason: &str) -> Option<String> {
+    let id = request.id?;
+    Some(serde_json::json!({
+        "id": id,
+        "error": {"code": DROPPED_ERROR_CODE, "message": format!("{} dropped unsent: {}", request.method, reason)},
+    }).to_string())
+}
+
+/// Whether an RPC error reports that we exceeded the venue's request rate
+pub fn is_rate_limit_error(error: &Value) -> bool {
+    let message = error.get("message").and_then(Value::as_str).unwrap_or_default().to_lowercase();
+    message.contains("rate limit") || message.contains("throttl")
+}
@@ -1,77 +1,98 @@
 use anyhow::{anyhow, Result};
+use log::warn;
 use serde_json::Value;
-use crate::domain::enums::{OrderSide, OrderStatus, OrderType, TimeInForce};
-use crate::domain::model::ack::Ack;
+use crate::domain::enums::{MakerTaker, OrderType, TimeInForce};
+use crate::domain::model::account_event::{AccountEvent, AccountEventType};
+use crate::domain::model::order::{Order, OrderState};
 use crate::domain::model::ticker::Ticker;
 use crate::domain::model::trade::Trade;
+use crate::domain::model::trade_correction::{CorrectionKind, TradeCorrection};
+use crate::domain::clock;
 
 /// Parses JSON data for Kafka
 pub struct ThaleParser;
 
 impl ThaleParser {
-    /// Parses JSON for an Order Acknowledgement
-    pub fn parse_ack_json(data: &Value) -> Result<Ack> {
+    /// Maker/taker role of a trade or fill
+    ///
+    /// `strict` rejects a missing or unknown role instead of recording the trade without one.
+    fn maker_taker(data: &Value, strict: bool) -> Result<Option<MakerTaker>> {
+        let role = match data.get("maker_taker").and_then(|v| v.as_str()) {
+            Some(role) => role.parse().map_err(|e| (Some(role), e)),
+            None => Err((None, anyhow!("Missing maker_taker"))),
+        };
+        match role {
+            Ok(role) => Ok(Some(role)),
+            Err((_, e)) if strict => Err(e),
+            Err((role, _)) => {
+                warn!("Recording trade without maker/taker role (got {:?})", role);
+                Ok(None)
+            }
+        }
+    }
+    
+    /// Parses an order notification or acknowledgement
+    pub fn parse_order_json(data: &Value) -> Result<Order> {
         // Get current time for processing_timestamp
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs_f64();
+        let now = clock::now_secs();
             
-        // Create the Ack struct with proper enum types from the JSON
-        let ack = Ack {
+        // Create the Order with proper enum types from the JSON; enum values the
+        // exchange didn't send are defaulted, values we don't recognise are rejected
+        let ack = Order {
             order_id: data["order_id"].as_str().unwrap_or_default().to_string(),
             client_order_id: data["client_order_id"].as_u64(),
             instrument_name: data["instrument_name"].as_str().unwrap_or_default().to_string(),
-            direction: match data["direction"].as_str().unwrap_or_default() {
-                "buy" => OrderSide::Buy,
-                "sell" => OrderSide::Sell,
-                _ => OrderSide::Buy, // Default
-            },
-            price: if data["price"].is_null() { 
-                None 
-            } else { 
-                Some(data["price"].as_f64().unwrap_or_default()) 
-            },
+            direction: Self::required_str(data, "direction")?.parse()?,
+            price: Self::optional_f64(data, "price")?,
             amount: data["amount"].as_f64().unwrap_or_default(),
             filled_amount: data["filled_amount"].as_f64().unwrap_or_default(),
             remaining_amount: data["remaining_amount"].as_f64().unwrap_or_default(),
-            status: match data["status"].as_str().unwrap_or_default() {
-                "open" => OrderStatus::Open,
-                "partially_filled" => OrderStatus::PartiallyFilled,
-                "cancelled" => OrderStatus::Cancelled,
-                "cancelled_partially_filled" => OrderStatus::CancelledPartiallyFilled,
-                "filled" => OrderStatus::Filled,
-                _ => OrderStatus::Open, // Default
-            },
-            order_type: match data["order_type"].as_str().unwrap_or_default() {
-                "limit" => OrderType::Limit,
-                "market" => OrderType::Market,
-                _ => OrderType::Limit, // Default
+            status: Self::required_str(data, "status")?.parse()?,
+            order_type: match Self::optional_str(data, "order_type")? {
+                Some(order_type) => order_type.parse()?,
+                None => OrderType::Limit,
             },
-            time_in_force: match data["time_in_force"].as_str().unwrap_or_default() {
-                "good_till_cancelled" => TimeInForce::GTC,
-                "immediate_or_cancel" => TimeInForce::IOC,
-                _ => TimeInForce::GTC, // Default
+            time_in_force: match Self::optional_str(data, "time_in_force")? {
+                Some(tif) => tif.parse()?,
+                None => TimeInForce::GTC,
             },
             change_reason: data["change_reason"].as_str().unwrap_or_default().to_string(),
-            delete_reason: if data["delete_reason"].is_null() {
-                None
-            } else {
-                Some(data["delete_reason"].as_str().unwrap_or_default().to_string())
-            },
-            insert_reason: if data["insert_reason"].is_null() {
-                None
-            } else {
-                Some(data["insert_reason"].as_str().unwrap_or_default().to_string())
-            },
+            delete_reason: Self::optional_str(data, "delete_reason")?.map(str::to_string),
+            insert_reason: Self::optional_str(data, "insert_reason")?.map(str::to_string),
             create_time: data["create_time"].as_f64().unwrap_or_default(),
             persistent: data["persistent"].as_bool().unwrap_or_default(),
             processing_timestamp: Some(now),
+            variant_id: None,
+            state: OrderState::Acknowledged,
         };
         
         Ok(ack)
     }
     
+    /// String field that must be present
+    fn required_str<'a>(data: &'a Value, field: &str) -> Result<&'a str> {
+        Self::optional_str(data, field)?.ok_or_else(|| anyhow!("Missing {}", field))
+    }
+    
+    /// String field that may be absent or null, but not of another type
+    fn optional_str<'a>(data: &'a Value, field: &str) -> Result<Option<&'a str>> {
+        match &data[field] {
+            Value::Null => Ok(None),
+            Value::String(s) => Ok(Some(s)),
+            other => Err(anyhow!("Invalid {}: expected string, got {}", field, other)),
+        }
+    }
+    
+    /// Numeric field that may be absent or null, but not of another type
+    fn optional_f64(data: &Value, field: &str) -> Result<Option<f64>> {
+        match &data[field] {
+            Value::Null => Ok(None),
+            value => value.as_f64()
+                .map(Some)
+                .ok_or_else(|| anyhow!("Invalid {}: expected number, got {}", field, value)),
+        }
+    }
+    
     /// Parses the ticker data
     pub fn parse_ticker_json(ticker: &Ticker) -> Result<Value> {
         let ticker_json = serde_json::json!({
@@ -88,13 +109,126 @@ impl ThaleParser {
         Ok(ticker_json)
     }
     
+    /// Parses an account notification
+    ///
+    /// The kind comes from the notification's `category` (or `type`), or from keywords
+    /// of its title when it has neither; unrecognised kinds are kept as `Other`.
+    pub fn parse_account_event_json(data: &Value) -> Result<AccountEvent> {
+        let now = clock::now_secs();
+        
+        let kind = match Self::optional_str(data, "category")? {
+            Some(kind) => Some(kind),
+            None => Self::optional_str(data, "type")?,
+        };
+        let title = Self::optional_str(data, "title")?;
+        let message = Self::optional_str(data, "message")?.or(title);
+        let event_type = kind.map(Self::account_event_type).unwrap_or(AccountEventType::Other);
+        let currency = match Self::optional_str(data, "currency")? {
+            Some(currency) => Some(currency),
+            None => Self::optional_str(data, "asset_name")?,
+        };
+        
+        Ok(AccountEvent {
+            event_type,
+            timestamp: Self::optional_f64(data, "create_time")?.unwrap_or(now),
+            currency: currency.map(str::to_string),
+            amount: Self::optional_f64(data, "amount")?,
+            instrument_name: Self::optional_str(data, "instrument_name")?.map(str::to_string),
+            message: message.map(str::to_string),
+            processing_timestamp: Some(now),
+        })
+    }
+    
+    /// Parses an account notification that busts or corrects one of our trades
+    ///
+    /// Returns None for other notifications. The kind comes from the `category`, `type`
+    /// or title like for account events; a bust or correction must name its `trade_id`.
+    pub fn parse_trade_correction_json(data: &Value) -> Result<Option<TradeCorrection>> {
+        let now = clock::now_secs();
+        
+        let title = Self::optional_str(data, "title")?;
+        let kind = match Self::optional_str(data, "category")? {
+            Some(kind) => Some(kind),
+            None => Self::optional_str(data, "type")?,
+        };
+        let Some(kind) = kind.or(title).and_then(Self::correction_kind) else {
+            return Ok(None);
+        };
+        let first_f64 = |fields: &[&str]| -> Result<Option<f64>> {
+            for field in fields {
+                if let Some(value) = Self::optional_f64(data, field)? {
+                    return Ok(Some(value));
+                }
+            }
+            Ok(None)
+        };
+        
+        Ok(Some(TradeCorrection {
+            kind,
+            trade_id: Self::required_str(data, "trade_id")?.to_string(),
+            order_id: Self::optional_str(data, "order_id")?.map(str::to_string),
+            instrument_name: Self::optional_str(data, "instrument_name")?.map(str::to_string),
+            direction: Self::optional_str(data, "direction")?.map(str::parse).transpose()?,
+            original_price: first_f64(&["original_price", "price"])?,
+            original_amount: first_f64(&["original_amount", "amount"])?,
+            corrected_price: first_f64(&["corrected_price", "new_price"])?,
+            corrected_amount: first_f64(&["corrected_amount", "new_amount"])?,
+            message: Self::optional_str(data, "message")?.or(title).map(str::to_string),
+            timestamp: Self::optional_f64(data, "create_time")?.unwrap_or(now),
+            processing_timestamp: Some(now),
+        }))
+    }
+    
+    /// Funding paid to the account by a funding notification, negative when it paid
+    ///
+    /// Returns None for other notifications. Only the `category` or `type` identifies one,
+    /// and it must carry the `amount`.
+    pub fn parse_funding_payment_json(data: &Value) -> Result<Option<f64>> {
+        let kind = match Self::optional_str(data, "category")? {
+            Some(kind) => Some(kind),
+            None => Self::optional_str(data, "type")?,
+        };
+        if !kind.is_some_and(|kind| matches!(kind.to_lowercase().as_str(), "funding" | "funding_payment")) {
+            return Ok(None);
+        }
+        Self::optional_f64(data, "amount")?
+            .map(Some)
+            .ok_or_else(|| anyhow!("Funding notification without an amount"))
+    }
+    
+    /// Correction kind named by a category or title, None when it's about something else
+    fn correction_kind(text: &str) -> Option<CorrectionKind> {
+        let text = text.to_lowercase();
+        if text.contains("bust") || (text.contains("trade") && text.contains("cancel")) {
+            Some(CorrectionKind::Bust)
+        } else if text.contains("trade") && text.contains("correct") {
+            Some(CorrectionKind::Correction)
+        } else {
+            None
+        }
+    }
+    
+    /// Account event kind of a `category` or `type` field
+    ///
+    /// Only the exchange's identifiers count: titles and messages are free text, and a
+    /// notification merely mentioning a liquidation must not halt trading.
+    fn account_event_type(kind: &str) -> AccountEventType {
+        match kind.to_lowercase().as_str() {
+            "liquidation" => AccountEventType::Liquidation,
+            "margin_call" => AccountEventType::MarginCall,
+            "deposit" => AccountEventType::Deposit,
+            "withdrawal" => AccountEventType::Withdrawal,
+            _ => AccountEventType::Other,
+        }
+    }
+    
     /// Parses a trade message from JSON
-    pub fn parse_trade_json(data: &Value) -> Result<Trade> {
+    ///
+    /// `strict` rejects trades whose maker/taker role is missing or unknown, instead of
+    /// recording them without a role.
+    pub fn parse_trade_json(data: &Value, strict: bool) -> Result<Trade> {
         // Get current time for processing_timestamp
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs_f64();
+        let now = clock::now_secs();
             
         // Extract fields from the trade notification JSON
         let trade_id = match data.get("trade_id").and_then(|v| v.as_str()) {
@@ -126,20 +260,12 @@ impl ThaleParser {
             .and_then(|v| v.as_f64())
             .ok_or_else(|| anyhow!("Missing amount"))?;
         
-        let maker_taker = data.get("maker_taker")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown")
-            .to_string();
+        let maker_taker = Self::maker_taker(data, strict)?;
         
         let time = data.get("timestamp")
             .or_else(|| data.get("time"))
             .and_then(|v| v.as_f64())
-            .unwrap_or_else(|| {
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs_f64()
-            });
+            .unwrap_or_else(clock::now_secs);
         
         // Create the Trade struct
         let trade = Trade {
@@ -152,20 +278,18 @@ impl ThaleParser {
             maker_taker,
             time,
             processing_timestamp: Some(now),
+            variant_id: None,
         };
         
         Ok(trade)
     }
     
-    /// Extracts trades (fills) from order notification JSON
-    pub fn extract_trades_from_order(order_data: &Value) -> Result<Vec<Trade>> {
+    /// Extracts trades (fills) from order notification JSON; `strict` as for `parse_trade_json`
+    pub fn extract_trades_from_order(order_data: &Value, strict: bool) -> Result<Vec<Trade>> {
         let mut trades = Vec::new();
         
         // Get current time for processing_timestamp
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs_f64();
+        let now = clock::now_secs();
             
         // Check if the order has fills
         if let Some(fills) = order_data.get("fills").and_then(|v| v.as_array()) {
@@ -202,22 +326,14 @@ impl ThaleParser {
                     .and_then(|v| v.as_f64())
                     .ok_or_else(|| anyhow!("Missing amount in fill"))?;
                 
-                let maker_taker = fill.get("maker_taker")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
-                    .to_string();
+                let maker_taker = Self::maker_taker(fill, strict)?;
                 
                 let time = fill.get("time")
                     .and_then(|v| v.as_f64())
                     .unwrap_or_else(|| {
                         order_data.get("create_time")
                             .and_then(|v| v.as_f64())
-                            .unwrap_or_else(|| {
-                                std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap_or_default()
-                                    .as_secs_f64()
-                            })
+                            .unwrap_or_else(clock::now_secs)
                     });
                 
                 // Create a Trade from the fill data
@@ -231,6 +347,7 @@ impl ThaleParser {
                     maker_taker,
                     time,
                     processing_timestamp: Some(now),
+                    variant_id: None,
                 };
                 
                 trades.push(trade);
@@ -249,7 +366,7 @@ impl ThaleParser {
             "instrument_name": trade.instrument_name,
             "price": trade.price,
             "amount": trade.amount,
-            "maker_taker": trade.maker_taker,
+            "maker_taker": trade.maker_taker_str(),
             "time": trade.time,
             "processing_timestamp": trade.processing_timestamp
         })
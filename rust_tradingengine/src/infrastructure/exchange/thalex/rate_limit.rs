@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::config_loader::{RateLimitConfig, RateLimitOverflow};
+use crate::domain::clock;
+use crate::infrastructure::metrics;
+
+/// Requests held in the outbound queue because their budget was spent
+pub const METRIC_RATE_LIMIT_HELD: &str = "thalex.rate_limit_held";
+
+/// Amends and inserts dropped because their budget was spent
+pub const METRIC_RATE_LIMIT_DROPPED: &str = "thalex.rate_limit_dropped";
+
+/// Budget of `per_sec` requests per second with bursts of up to `burst`
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    per_sec: f64,
+    burst: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket; the burst is at least one request
+    pub fn new(per_sec: f64, burst: f64, now: Instant) -> Self {
+        let burst = burst.max(1.0);
+        Self { per_sec, burst, tokens: burst, updated: now }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_sec).min(self.burst);
+        self.updated = now;
+    }
+
+    /// Whether a request fits the budget at `now`
+    pub fn available(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= 1.0
+    }
+
+    /// When the next request fits the budget: `now` if it already does
+    pub fn ready_at(&mut self, now: Instant) -> Instant {
+        self.refill(now);
+        if self.tokens >= 1.0 || self.per_sec <= 0.0 {
+            return now;
+        }
+        now + Duration::from_secs_f64((1.0 - self.tokens) / self.per_sec)
+    }
+
+    /// Spend one request of the budget, refilled up to `now`
+    pub fn take(&mut self, now: Instant) -> bool {
+        if !self.available(now) {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// Global and per-method request budgets in front of the socket
+///
+/// A request is sent only when both its method's budget (if it has one) and the global
+/// budget have room. Requests over budget wait in the outbound queue, or, with the
+/// drop overflow, amends and inserts are dropped since the next quote cycle sends
+/// fresh ones.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    global: Option<TokenBucket>,
+    methods: HashMap<String, TokenBucket>,
+    drop_overflow: bool,
+}
+
+impl RateLimiter {
+    /// Limiter letting every request through
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn from_config(config: &RateLimitConfig) -> Self {
+        if !config.enabled {
+            return Self::unlimited();
+        }
+        let now = clock::instant();
+        Self {
+            global: (config.per_sec > 0.0).then(|| TokenBucket::new(config.per_sec, config.burst, now)),
+            methods: config.methods.iter()
+                .filter(|(_, budget)| budget.per_sec > 0.0)
+                .map(|(method, budget)| (method.clone(), TokenBucket::new(budget.per_sec, budget.burst, now)))
+                .collect(),
+            drop_overflow: config.overflow == RateLimitOverflow::Drop,
+        }
+    }
+
+    /// Whether a request of `method` fits both its budgets at `now`, without spending them
+    pub fn admits(&mut self, method: &str, now: Instant) -> bool {
+        let method_ok = self.methods.get_mut(method).is_none_or(|bucket| bucket.available(now));
+        method_ok && self.global.as_mut().is_none_or(|bucket| bucket.available(now))
+    }
+
+    /// When a request of `method` fits both its budgets: `now` if it already does
+    pub fn ready_at(&mut self, method: &str, now: Instant) -> Instant {
+        let method_ready = self.methods.get_mut(method).map_or(now, |bucket| bucket.ready_at(now));
+        let global_ready = self.global.as_mut().map_or(now, |bucket| bucket.ready_at(now));
+        method_ready.max(global_ready)
+    }
+
+    /// Spend a request of `method` from its budgets; false, spending nothing, when either is used up
+    pub fn try_acquire(&mut self, method: &str, now: Instant) -> bool {
+        if !self.admits(method, now) {
+            metrics::global().incr(METRIC_RATE_LIMIT_HELD, 1);
+            return false;
+        }
+        if let Some(bucket) = self.methods.get_mut(method) {
+            bucket.take(now);
+        }
+        if let Some(bucket) = self.global.as_mut() {
+            bucket.take(now);
+        }
+        true
+    }
+
+    /// Whether amends and inserts over budget are dropped instead of queued
+    pub fn drops_overflow(&self) -> bool {
+        self.drop_overflow
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::domain::model::exchange::Instrument;
+
+use super::client::{Network, ThalexKeys};
+use super::models::{AccountSummary, OrderResult, TradeHistoryResult};
+
+/// Filters of a `private/trade_history` request; unset fields are left to the exchange
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TradeHistoryQuery {
+    /// Earliest trade time, seconds since the epoch
+    pub time_low: Option<f64>,
+    pub time_high: Option<f64>,
+    pub limit: Option<u32>,
+    /// Bookmark of the previous page
+    pub bookmark: Option<String>,
+    pub instrument_names: Vec<String>,
+}
+
+impl TradeHistoryQuery {
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(time_low) = self.time_low {
+            params.push(("time_low", time_low.to_string()));
+        }
+        if let Some(time_high) = self.time_high {
+            params.push(("time_high", time_high.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(bookmark) = &self.bookmark {
+            params.push(("bookmark", bookmark.clone()));
+        }
+        if !self.instrument_names.is_empty() {
+            params.push(("instrument_names", self.instrument_names.join(",")));
+        }
+        params
+    }
+}
+
+/// Calls to the Thalex REST API, independent of the WebSocket session
+///
+/// For requests that are awkward while the listen loop owns the socket, from strategies
+/// or from the bin tools. Private calls are authenticated with the same token the
+/// WebSocket login uses, a fresh one per request.
+pub struct ThalexRestClient {
+    base_url: String,
+    keys: Option<ThalexKeys>,
+    http: reqwest::Client,
+}
+
+impl ThalexRestClient {
+    /// Client for `base_url`; without keys only public methods can be called
+    pub fn new(base_url: &str, keys: Option<ThalexKeys>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            keys,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn for_network(network: &Network, keys: Option<ThalexKeys>) -> Self {
+        Self::new(network.rest_url(), keys)
+    }
+
+    /// Value of the `Authorization` header of private calls
+    pub fn auth_header(&self) -> Result<String> {
+        let keys = self.keys.as_ref().ok_or_else(|| anyhow!("Thalex REST client has no keys for private calls"))?;
+        let token = keys.make_auth_token().map_err(|e| anyhow!("Failed to create auth token: {}", e))?;
+        Ok(format!("Bearer {}", token))
+    }
+
+    /// Call a `public/` method; returns the `result` of a successful response
+    pub async fn public(&self, method: &str, params: &[(&str, String)]) -> Result<Value> {
+        self.call(&format!("public/{}", method), params, None).await
+    }
+
+    /// Call a `private/` method; returns the `result` of a successful response
+    pub async fn private(&self, method: &str, params: &[(&str, String)]) -> Result<Value> {
+        let auth = self.auth_header()?;
+        self.call(&format!("private/{}", method), params, Some(auth)).await
+    }
+
+    async fn call(&self, method: &str, params: &[(&str, String)], auth: Option<String>) -> Result<Value> {
+        let mut request = self.http.get(format!("{}/{}", self.base_url, method)).query(params);
+        if let Some(auth) = auth {
+            request = request.header(reqwest::header::AUTHORIZATION, auth);
+        }
+        let response = request.send().await.map_err(|e| anyhow!("Thalex {} failed: {}", method, e))?;
+        let status = response.status();
+        let body: Value = response.json().await
+            .map_err(|e| anyhow!("Thalex {} returned {} without a JSON body: {}", method, status, e))?;
+        Self::result(body)
+    }
+
+    /// `result` of a response envelope, or its error
+    pub fn result(response: Value) -> Result<Value> {
+        if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+            return Err(anyhow!(
+                "Thalex error {}: {}",
+                error["code"].as_i64().map(|c| c.to_string()).unwrap_or_else(|| "?".to_string()),
+                error["message"].as_str().unwrap_or("no message")
+            ));
+        }
+        response.get("result").cloned().ok_or_else(|| anyhow!("Thalex response without a result: {}", response))
+    }
+
+    fn typed<T: DeserializeOwned>(method: &str, result: Value) -> Result<T> {
+        serde_json::from_value(result).map_err(|e| anyhow!("Unexpected {} result: {}", method, e))
+    }
+
+    /// Every active instrument
+    pub async fn instruments(&self) -> Result<Vec<Instrument>> {
+        Self::typed("instruments", self.public("instruments", &[]).await?)
+    }
+
+    pub async fn instrument(&self, instrument_name: &str) -> Result<Instrument> {
+        let params = [("instrument_name", instrument_name.to_string())];
+        Self::typed("instrument", self.public("instrument", &params).await?)
+    }
+
+    /// Balances, margin and PnL of the account
+    pub async fn account_summary(&self) -> Result<AccountSummary> {
+        Self::typed("account_summary", self.private("account_summary", &[]).await?)
+    }
+
+    /// Open orders of the account, across sessions
+    pub async fn open_orders(&self) -> Result<Vec<OrderResult>> {
+        Self::typed("open_orders", self.private("open_orders", &[]).await?)
+    }
+
+    /// One page of the account's trades; pass the returned bookmark for the next
+    pub async fn trade_history(&self, query: &TradeHistoryQuery) -> Result<TradeHistoryResult> {
+        Self::typed("trade_history", self.private("trade_history", &query.params()).await?)
+    }
+}
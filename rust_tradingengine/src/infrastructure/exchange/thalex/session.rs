@@ -0,0 +1,121 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::{mpsc, Notify};
+
+use crate::infrastructure::exchange::ExchangeClient;
+
+use super::calls::CallRegistry;
+use super::client::{ThalexClient, ThalexReader};
+use super::liveness::Liveness;
+
+/// Receiving half of a connection, detached so receiving never holds the client
+#[async_trait]
+pub trait MessageReader: Send {
+    /// Next text message, `Ok(None)` for frames without one
+    async fn receive(&mut self) -> Result<Option<String>>;
+}
+
+/// What the quoter needs from its connection on top of `ExchangeClient`
+///
+/// `ThalexQuoter` holds its client behind this trait rather than as a `ThalexClient`,
+/// so anything speaking the Thalex protocol, such as a recording proxy or a test
+/// double, can stand in for the WebSocket client.
+#[async_trait]
+pub trait SessionClient: ExchangeClient {
+    /// Request id allocator shared with whoever handles the responses
+    fn calls(&self) -> Arc<CallRegistry>;
+
+    /// Ping/pong state of the current connection
+    fn liveness(&self) -> Arc<Liveness>;
+
+    /// Send a ping, tracked until its pong arrives
+    async fn ping(&mut self) -> Result<()>;
+
+    /// Request the listed instruments
+    async fn instruments(&mut self, id: Option<u64>) -> Result<()>;
+
+    /// Have the venue cancel the session's orders once the connection is gone for `timeout_secs`
+    async fn set_cancel_on_disconnect(&mut self, timeout_secs: u64, id: Option<u64>) -> Result<()>;
+
+    /// Report requests dropped before they were written on `rejections`, as error responses
+    fn set_rejections(&mut self, rejections: mpsc::UnboundedSender<String>);
+
+    /// Detach the receiving half; None when it's gone or there is no connection
+    fn take_reader(&mut self) -> Option<Box<dyn MessageReader>>;
+
+    /// Write queued requests while the connection and the request budgets allow; returns how many
+    async fn flush(&mut self) -> Result<usize>;
+
+    /// Number of requests waiting to be written
+    fn outbound_depth(&self) -> usize;
+
+    /// Notified whenever a send leaves requests queued
+    fn outbound_ready(&self) -> Arc<Notify>;
+
+    /// When a `flush` next has something to do, None while nothing is queued
+    fn next_flush(&mut self) -> Option<Instant>;
+
+    /// Drop every queued request once the session they were queued for ended; returns how many
+    fn discard_outbound(&mut self) -> usize;
+}
+
+#[async_trait]
+impl MessageReader for ThalexReader {
+    async fn receive(&mut self) -> Result<Option<String>> {
+        ThalexReader::receive(self).await
+    }
+}
+
+#[async_trait]
+impl SessionClient for ThalexClient {
+    fn calls(&self) -> Arc<CallRegistry> {
+        ThalexClient::calls(self)
+    }
+
+    fn liveness(&self) -> Arc<Liveness> {
+        ThalexClient::liveness(self)
+    }
+
+    async fn ping(&mut self) -> Result<()> {
+        ThalexClient::ping(self).await
+    }
+
+    async fn instruments(&mut self, id: Option<u64>) -> Result<()> {
+        ThalexClient::instruments(self, id).await
+    }
+
+    async fn set_cancel_on_disconnect(&mut self, timeout_secs: u64, id: Option<u64>) -> Result<()> {
+        ThalexClient::set_cancel_on_disconnect(self, timeout_secs, id).await
+    }
+
+    fn set_rejections(&mut self, rejections: mpsc::UnboundedSender<String>) {
+        ThalexClient::set_rejections(self, rejections)
+    }
+
+    fn take_reader(&mut self) -> Option<Box<dyn MessageReader>> {
+        ThalexClient::take_reader(self).map(|reader| Box::new(reader) as Box<dyn MessageReader>)
+    }
+
+    async fn flush(&mut self) -> Result<usize> {
+        ThalexClient::flush(self).await
+    }
+
+    fn outbound_depth(&self) -> usize {
+        ThalexClient::outbound_depth(self)
+    }
+
+    fn outbound_ready(&self) -> Arc<Notify> {
+        ThalexClient::outbound_ready(self)
+    }
+
+    fn next_flush(&mut self) -> Option<Instant> {
+        ThalexClient::next_flush(self)
+    }
+
+    fn discard_outbound(&mut self) -> usize {
+        ThalexClient::discard_outbound(self)
+    }
+}
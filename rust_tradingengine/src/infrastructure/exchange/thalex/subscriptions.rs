@@ -0,0 +1,78 @@
+use std::sync::Mutex;
+
+use crate::infrastructure::exchange::thalex::channel::Channel;
+use crate::infrastructure::metrics;
+
+/// Channels subscribed again on a new session because an earlier one had them
+pub const METRIC_RESTORED_CHANNELS: &str = "thalex.restored_channels";
+
+/// Session state the exchange forgets when the WebSocket drops
+#[derive(Debug, Default)]
+struct Recorded {
+    /// Subscribed channels, in the order they were first subscribed
+    channels: Vec<Channel>,
+    /// Cancel-on-disconnect timeout (seconds) the exchange last accepted
+    cancel_on_disconnect: Option<u64>,
+}
+
+/// Subscriptions and cancel-on-disconnect setting of the session, kept across reconnects
+///
+/// A new session starts from nothing on the exchange side, so channels subscribed at
+/// runtime (enabled instruments, retried subscriptions) would be lost with the old
+/// socket. Held by the caller across sessions, the manager records every subscribe,
+/// unsubscribe and cancel-on-disconnect change, and hands them back to be replayed once
+/// the next session is logged in.
+#[derive(Debug, Default)]
+pub struct SubscriptionManager {
+    recorded: Mutex<Recorded>,
+}
+
+impl SubscriptionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record channels subscribed to; channels already recorded keep their place
+    pub fn subscribed(&self, channels: &[Channel]) {
+        let mut recorded = self.recorded.lock().unwrap();
+        for channel in channels {
+            if !recorded.channels.contains(channel) {
+                recorded.channels.push(channel.clone());
+            }
+        }
+    }
+
+    /// Record channels unsubscribed from
+    pub fn unsubscribed(&self, channels: &[Channel]) {
+        self.recorded.lock().unwrap().channels.retain(|channel| !channels.contains(channel));
+    }
+
+    /// Record the cancel-on-disconnect timeout the exchange accepted (seconds)
+    pub fn cancel_on_disconnect_set(&self, timeout_secs: u64) {
+        self.recorded.lock().unwrap().cancel_on_disconnect = Some(timeout_secs);
+    }
+
+    /// Cancel-on-disconnect timeout to set again (seconds), None if none was set
+    pub fn cancel_on_disconnect(&self) -> Option<u64> {
+        self.recorded.lock().unwrap().cancel_on_disconnect
+    }
+
+    /// Channels currently recorded as subscribed
+    pub fn channels(&self) -> Vec<Channel> {
+        self.recorded.lock().unwrap().channels.clone()
+    }
+
+    /// Channels a new session subscribes to: `defaults`, followed by the recorded channels
+    /// of the same kind (private or public) that aren't among them
+    pub fn restore(&self, mut defaults: Vec<Channel>, private: bool) -> Vec<Channel> {
+        let restored: Vec<Channel> = self.recorded.lock().unwrap().channels.iter()
+            .filter(|channel| channel.is_private() == private && !defaults.contains(channel))
+            .cloned()
+            .collect();
+        if !restored.is_empty() {
+            metrics::global().incr(METRIC_RESTORED_CHANNELS, restored.len() as u64);
+        }
+        defaults.extend(restored);
+        defaults
+    }
+}
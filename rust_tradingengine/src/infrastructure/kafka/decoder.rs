@@ -0,0 +1,449 @@
+use anyhow::{anyhow, Context, Result};
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{from_avro_datum, Schema};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use crate::config_loader::RegistryRetryConfig;
+use crate::domain::enums::{MakerTaker, OrderSide, OrderStatus, OrderType, TimeInForce};
+use crate::domain::model::book::{BookLevelUpdate, BookSide, BookUpdateKind};
+use crate::domain::model::features::MarketFeatures;
+use crate::domain::model::heartbeat::Heartbeat;
+use crate::domain::model::session_summary::SessionSummary;
+use crate::domain::model::funding_basis::FundingBasis;
+use crate::domain::model::account_event::{AccountEvent, AccountEventType};
+use crate::domain::model::lifecycle::{LifecycleEvent, LifecycleEventType};
+use crate::domain::model::order::{Order, OrderState};
+use crate::domain::model::ticker::Ticker;
+use crate::domain::model::ticker_delta::{TickerDelta, TickerDeltaKind};
+use crate::domain::model::trade::Trade;
+use crate::domain::model::uptime::QuoteUptime;
+use crate::infrastructure::kafka::registry::RegistryClient;
+
+/// First byte of every Confluent-framed payload
+pub const CONFLUENT_MAGIC_BYTE: u8 = 0;
+
+/// Magic byte plus the big-endian schema id
+pub const CONFLUENT_HEADER_LEN: usize = 5;
+
+/// Start a Confluent-framed payload in `buffer`: the magic byte and the big-endian schema id
+pub fn write_confluent_header(buffer: &mut Vec<u8>, schema_id: i32) {
+    buffer.push(CONFLUENT_MAGIC_BYTE);
+    buffer.extend_from_slice(&schema_id.to_be_bytes());
+}
+
+/// Confluent-framed payload of an Avro `body` encoded with the schema `schema_id`
+pub fn frame_confluent_payload(schema_id: i32, body: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(CONFLUENT_HEADER_LEN + body.len());
+    write_confluent_header(&mut payload, schema_id);
+    payload.extend_from_slice(body);
+    payload
+}
+
+/// Schema id and Avro body of a Confluent-framed payload
+pub fn split_confluent_payload(bytes: &[u8]) -> Result<(i32, &[u8])> {
+    if bytes.len() < CONFLUENT_HEADER_LEN {
+        return Err(anyhow!("Payload of {} bytes is shorter than the Confluent header", bytes.len()));
+    }
+    if bytes[0] != CONFLUENT_MAGIC_BYTE {
+        return Err(anyhow!("Payload starts with {:#04x}, not the Confluent magic byte", bytes[0]));
+    }
+    let schema_id = i32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+    Ok((schema_id, &bytes[CONFLUENT_HEADER_LEN..]))
+}
+
+/// A record read from one of our topics, as its domain type where the crate has one
+#[derive(Debug, Clone)]
+pub enum TypedRecord {
+    Ack(Order),
+    Trade(Trade),
+    Ticker(Ticker),
+    TickerDelta(TickerDelta),
+    Features(MarketFeatures),
+    Uptime(QuoteUptime),
+    Lifecycle(LifecycleEvent),
+    Heartbeat(Heartbeat),
+    SessionSummary(SessionSummary),
+    FundingBasis(FundingBasis),
+    AccountEvent(AccountEvent),
+    Book(BookLevelUpdate),
+    /// Record of a schema without a domain type, such as the index topic
+    Other {
+        name: String,
+        fields: Vec<(String, AvroValue)>,
+    },
+}
+
+impl TypedRecord {
+    /// Map a decoded record by its schema name
+    ///
+    /// Fields added by later schema versions are optional here, so records written with
+    /// any version under `schemas/` map.
+    pub fn from_avro(name: &str, value: AvroValue) -> Result<Self> {
+        let AvroValue::Record(fields) = value else {
+            return Err(anyhow!("Expected a record for {}, got {:?}", name, value));
+        };
+        let f = Fields(&fields);
+        let record = match name {
+            "Ack" => TypedRecord::Ack(Order {
+                order_id: f.string("order_id")?,
+                client_order_id: f.opt_long("client_order_id")?.map(|id| id as u64),
+                instrument_name: f.string("instrument_name")?,
+                direction: OrderSide::from_str(f.symbol("direction")?)?,
+                price: f.opt_double("price")?,
+                amount: f.double("amount")?,
+                filled_amount: f.double("filled_amount")?,
+                remaining_amount: f.double("remaining_amount")?,
+                status: OrderStatus::from_str(f.symbol("status")?)?,
+                order_type: OrderType::from_str(f.symbol("order_type")?)?,
+                time_in_force: TimeInForce::from_str(f.symbol("time_in_force")?)?,
+                change_reason: f.string("change_reason")?,
+                delete_reason: f.opt_string("delete_reason")?,
+                insert_reason: f.opt_string("insert_reason")?,
+                create_time: f.double("create_time")?,
+                persistent: f.boolean("persistent")?,
+                processing_timestamp: f.opt_double("processing_timestamp")?,
+                variant_id: f.opt_string("variant_id")?,
+                state: OrderState::Acknowledged,
+            }),
+            "ThalexTrade" => {
+                // The typed role only exists from trade/v3; older records carry the string
+                let maker_taker = match f.opt_symbol("maker_taker_role")? {
+                    Some(symbol) => Some(MakerTaker::from_str(symbol)?),
+                    None => f.opt_string("maker_taker")?.and_then(|role| MakerTaker::from_str(&role).ok()),
+                };
+                TypedRecord::Trade(Trade {
+                    trade_id: f.string("trade_id")?,
+                    order_id: f.string("order_id")?,
+                    client_order_id: f.opt_long("client_order_id")?.map(|id| id as u64),
+                    instrument_name: f.string("instrument_name")?,
+                    price: f.double("price")?,
+                    amount: f.double("amount")?,
+                    maker_taker,
+                    time: f.double("time")?,
+                    processing_timestamp: f.opt_double("processing_timestamp")?,
+                    variant_id: f.opt_string("variant_id")?,
+                })
+            }
+            "ThalexTicker" => TypedRecord::Ticker(Ticker {
+                instrument_name: f.string("instrument_name")?,
+                mark_price: f.double("mark_price")?,
+                mark_timestamp: f.double("mark_timestamp")?,
+                best_bid_price: f.double("best_bid_price")?,
+                best_bid_amount: f.double("best_bid_amount")?,
+                best_ask_price: f.double("best_ask_price")?,
+                best_ask_amount: f.double("best_ask_amount")?,
+                last_price: f.double("last_price")?,
+                delta: f.double("delta")?,
+                volume_24h: f.double("volume_24h")?,
+                value_24h: f.double("value_24h")?,
+                low_price_24h: f.double("low_price_24h")?,
+                high_price_24h: f.double("high_price_24h")?,
+                change_24h: f.double("change_24h")?,
+                index_price: f.double("index_price")?,
+                forward: f.double("forward")?,
+                funding_mark: f.double("funding_mark")?,
+                funding_rate: f.double("funding_rate")?,
+                collar_low: f.double("collar_low")?,
+                collar_high: f.double("collar_high")?,
+                realised_funding_24h: f.double("realised_funding_24h")?,
+                average_funding_rate_24h: f.double("average_funding_rate_24h")?,
+                open_interest: f.double("open_interest")?,
+                processing_timestamp: f.opt_double("processing_timestamp")?,
+            }),
+            "ThalexTickerDelta" => TypedRecord::TickerDelta(TickerDelta {
+                instrument_name: f.string("instrument_name")?,
+                kind: match f.symbol("kind")? {
+                    "snapshot" => TickerDeltaKind::Snapshot,
+                    "delta" => TickerDeltaKind::Delta,
+                    other => return Err(anyhow!("Unknown ticker delta kind: {}", other)),
+                },
+                sequence: f.long("sequence")?,
+                changes: Ticker::NUMERIC_FIELDS.iter()
+                    .filter_map(|name| f.opt_double(name).transpose().map(|value| value.map(|v| (name.to_string(), v))))
+                    .collect::<Result<_>>()?,
+                timestamp: f.double("timestamp")?,
+                processing_timestamp: f.opt_double("processing_timestamp")?,
+            }),
+            "ThalexFeatures" => TypedRecord::Features(MarketFeatures {
+                instrument_name: f.string("instrument_name")?,
+                timestamp: f.double("timestamp")?,
+                window_secs: f.double("window_secs")?,
+                imbalance: f.double("imbalance")?,
+                spread: f.double("spread")?,
+                trade_intensity: f.double("trade_intensity")?,
+                realized_vol: f.double("realized_vol")?,
+                funding_basis: f.double("funding_basis")?,
+                funding_rate: f.double("funding_rate")?,
+                processing_timestamp: f.opt_double("processing_timestamp")?,
+            }),
+            "ThalexQuoteUptime" => TypedRecord::Uptime(QuoteUptime {
+                instrument_name: f.string("instrument_name")?,
+                date: f.string("date")?,
+                period_start: f.double("period_start")?,
+                period_end: f.double("period_end")?,
+                observed_secs: f.double("observed_secs")?,
+                compliant_secs: f.double("compliant_secs")?,
+                uptime_pct: f.double("uptime_pct")?,
+                max_distance_ticks: f.double("max_distance_ticks")?,
+                processing_timestamp: f.opt_double("processing_timestamp")?,
+            }),
+            "ThalexLifecycleEvent" => {
+                let symbol = f.symbol("event_type")?;
+                let event_type = LifecycleEventType::ALL.into_iter()
+                    .find(|t| t.as_str() == symbol)
+                    .ok_or_else(|| anyhow!("Unknown lifecycle event type: {}", symbol))?;
+                TypedRecord::Lifecycle(LifecycleEvent {
+                    instance_id: f.string("instance_id")?,
+                    event_type,
+                    reason: f.opt_string("reason")?,
+                    network: f.string("network")?,
+                    timestamp: f.double("timestamp")?,
+                    processing_timestamp: f.opt_double("processing_timestamp")?,
+                })
+            }
+            "ThalexHeartbeat" => TypedRecord::Heartbeat(Heartbeat {
+                instance_id: f.string("instance_id")?,
+                sequence: f.long("sequence")?,
+                positions_hash: f.string("positions_hash")?,
+                last_ticker_time: f.opt_double("last_ticker_time")?,
+                last_index_time: f.opt_double("last_index_time")?,
+                last_order_time: f.opt_double("last_order_time")?,
+                last_trade_time: f.opt_double("last_trade_time")?,
+                timestamp: f.double("timestamp")?,
+                processing_timestamp: f.opt_double("processing_timestamp")?,
+            }),
+            "ThalexSessionSummary" => TypedRecord::SessionSummary(SessionSummary {
+                instance_id: f.string("instance_id")?,
+                started_at: f.double("started_at")?,
+                ended_at: f.double("ended_at")?,
+                quotes_sent: f.long("quotes_sent")?,
+                amends: f.long("amends")?,
+                cancels: f.long("cancels")?,
+                fills: f.long("fills")?,
+                volume: f.double("volume")?,
+                realized_pnl: f.double("realized_pnl")?,
+                max_inventory: f.double("max_inventory")?,
+                reconnects: f.long("reconnects")?,
+                processing_timestamp: f.opt_double("processing_timestamp")?,
+            }),
+            "ThalexFundingBasis" => TypedRecord::FundingBasis(FundingBasis {
+                instrument_name: f.string("instrument_name")?,
+                timestamp: f.double("timestamp")?,
+                window_secs: f.double("window_secs")?,
+                mark_price: f.double("mark_price")?,
+                index_price: f.double("index_price")?,
+                basis: f.double("basis")?,
+                basis_bps: f.double("basis_bps")?,
+                rolling_basis_bps: f.double("rolling_basis_bps")?,
+                funding_rate: f.double("funding_rate")?,
+                annualized_funding_rate: f.double("annualized_funding_rate")?,
+                position: f.double("position")?,
+                cumulative_funding: f.double("cumulative_funding")?,
+                annualized_carry: f.double("annualized_carry")?,
+                processing_timestamp: f.opt_double("processing_timestamp")?,
+            }),
+            "ThalexAccountEvent" => {
+                let symbol = f.symbol("event_type")?;
+                let event_type = AccountEventType::ALL.into_iter()
+                    .find(|t| t.as_str() == symbol)
+                    .ok_or_else(|| anyhow!("Unknown account event type: {}", symbol))?;
+                TypedRecord::AccountEvent(AccountEvent {
+                    event_type,
+                    timestamp: f.double("timestamp")?,
+                    currency: f.opt_string("currency")?,
+                    amount: f.opt_double("amount")?,
+                    instrument_name: f.opt_string("instrument_name")?,
+                    message: f.opt_string("message")?,
+                    processing_timestamp: f.opt_double("processing_timestamp")?,
+                })
+            }
+            "ThalexBookLevel" => TypedRecord::Book(BookLevelUpdate {
+                instrument_name: f.string("instrument_name")?,
+                kind: match f.symbol("kind")? {
+                    "snapshot" => BookUpdateKind::Snapshot,
+                    "delta" => BookUpdateKind::Delta,
+                    other => return Err(anyhow!("Unknown book update kind: {}", other)),
+                },
+                sequence: f.long("sequence")?,
+                side: match f.symbol("side")? {
+                    "bid" => BookSide::Bid,
+                    "ask" => BookSide::Ask,
+                    other => return Err(anyhow!("Unknown book side: {}", other)),
+                },
+                price: f.double("price")?,
+                amount: f.double("amount")?,
+                exchange_time: f.opt_double("exchange_time")?,
+                timestamp: f.double("timestamp")?,
+                processing_timestamp: f.opt_double("processing_timestamp")?,
+            }),
+            _ => TypedRecord::Other {
+                name: name.to_string(),
+                fields,
+            },
+        };
+        Ok(record)
+    }
+}
+
+/// Event id stamped by the producer, None for records written before schemas carried one
+pub fn event_id(value: &AvroValue) -> Option<i64> {
+    let AvroValue::Record(fields) = value else {
+        return None;
+    };
+    Fields(fields).opt_long("event_id").ok().flatten()
+}
+
+/// Decode the Avro body of a payload written with `schema`
+pub fn decode_avro(schema: &Schema, body: &[u8]) -> Result<AvroValue> {
+    from_avro_datum(schema, &mut &body[..], None).context("Failed to decode Avro body")
+}
+
+/// Decodes Confluent-framed payloads, fetching writer schemas from the registry by id
+///
+/// Schemas are cached for the lifetime of the decoder, since registered ids never change.
+pub struct ConfluentDecoder {
+    schema_registry_url: String,
+    registry: RegistryClient,
+    schemas: RwLock<HashMap<i32, Schema>>,
+}
+
+impl ConfluentDecoder {
+    pub fn new(schema_registry_url: &str) -> Self {
+        Self::with_registry_retry(schema_registry_url, RegistryRetryConfig::default())
+    }
+
+    pub fn with_registry_retry(schema_registry_url: &str, registry_retry: RegistryRetryConfig) -> Self {
+        Self {
+            schema_registry_url: schema_registry_url.to_string(),
+            registry: RegistryClient::new(registry_retry),
+            schemas: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Use `schema` for payloads with `schema_id` without asking the registry
+    pub fn insert_schema(&self, schema_id: i32, schema: Schema) {
+        self.schemas.write().unwrap().insert(schema_id, schema);
+    }
+
+    /// Writer schema of `schema_id`, from the cache or the registry
+    pub async fn schema(&self, schema_id: i32) -> Result<Schema> {
+        if let Some(schema) = self.schemas.read().unwrap().get(&schema_id) {
+            return Ok(schema.clone());
+        }
+
+        let url = format!("{}/schemas/ids/{}", self.schema_registry_url, schema_id);
+        let response = self.registry.send("schema fetch", |client| client.get(&url)).await
+            .context("Failed to fetch schema from registry")?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Registry has no schema {} ({})", schema_id, response.status()));
+        }
+        let body = response.json::<serde_json::Value>().await?;
+        let text = body["schema"].as_str()
+            .ok_or_else(|| anyhow!("Registry response for schema {} has no schema", schema_id))?;
+        let schema = Schema::parse_str(text).context("Failed to parse schema from registry")?;
+
+        self.insert_schema(schema_id, schema.clone());
+        Ok(schema)
+    }
+
+    /// Schema id and decoded Avro value of a payload
+    pub async fn decode_value(&self, bytes: &[u8]) -> Result<(i32, AvroValue)> {
+        let (schema_id, body) = split_confluent_payload(bytes)?;
+        let schema = self.schema(schema_id).await?;
+        let value = decode_avro(&schema, body)
+            .with_context(|| format!("Payload doesn't match schema {}", schema_id))?;
+        Ok((schema_id, value))
+    }
+
+    /// Decode a payload into the domain type of its schema
+    pub async fn decode_confluent_payload(&self, bytes: &[u8]) -> Result<TypedRecord> {
+        let (schema_id, body) = split_confluent_payload(bytes)?;
+        let schema = self.schema(schema_id).await?;
+        let Schema::Record(record) = &schema else {
+            return Err(anyhow!("Schema {} is not a record schema", schema_id));
+        };
+        let value = decode_avro(&schema, body)
+            .with_context(|| format!("Payload doesn't match schema {}", schema_id))?;
+        TypedRecord::from_avro(&record.name.name, value)
+    }
+}
+
+/// Typed access to the fields of a decoded record
+struct Fields<'a>(&'a [(String, AvroValue)]);
+
+impl<'a> Fields<'a> {
+    /// Value of a field with unions unwrapped; None when the field is absent or null
+    fn get(&self, name: &str) -> Option<&'a AvroValue> {
+        let mut value = &self.0.iter().find(|(field, _)| field == name)?.1;
+        while let AvroValue::Union(_, inner) = value {
+            value = inner;
+        }
+        (*value != AvroValue::Null).then_some(value)
+    }
+
+    fn required(&self, name: &str) -> Result<&'a AvroValue> {
+        self.get(name).ok_or_else(|| anyhow!("Field '{}' is missing", name))
+    }
+
+    fn opt_string(&self, name: &str) -> Result<Option<String>> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(AvroValue::String(s)) => Ok(Some(s.clone())),
+            Some(other) => Err(anyhow!("Field '{}': expected string, got {:?}", name, other)),
+        }
+    }
+
+    fn string(&self, name: &str) -> Result<String> {
+        self.opt_string(name)?.ok_or_else(|| anyhow!("Field '{}' is missing", name))
+    }
+
+    fn opt_double(&self, name: &str) -> Result<Option<f64>> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(AvroValue::Double(v)) => Ok(Some(*v)),
+            Some(AvroValue::Float(v)) => Ok(Some(*v as f64)),
+            Some(AvroValue::Long(v)) => Ok(Some(*v as f64)),
+            Some(AvroValue::Int(v)) => Ok(Some(*v as f64)),
+            Some(other) => Err(anyhow!("Field '{}': expected double, got {:?}", name, other)),
+        }
+    }
+
+    fn double(&self, name: &str) -> Result<f64> {
+        self.opt_double(name)?.ok_or_else(|| anyhow!("Field '{}' is missing", name))
+    }
+
+    fn opt_long(&self, name: &str) -> Result<Option<i64>> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(AvroValue::Long(v)) => Ok(Some(*v)),
+            Some(AvroValue::Int(v)) => Ok(Some(*v as i64)),
+            Some(other) => Err(anyhow!("Field '{}': expected long, got {:?}", name, other)),
+        }
+    }
+
+    fn long(&self, name: &str) -> Result<i64> {
+        self.opt_long(name)?.ok_or_else(|| anyhow!("Field '{}' is missing", name))
+    }
+
+    fn boolean(&self, name: &str) -> Result<bool> {
+        match self.required(name)? {
+            AvroValue::Boolean(v) => Ok(*v),
+            other => Err(anyhow!("Field '{}': expected boolean, got {:?}", name, other)),
+        }
+    }
+
+    /// Symbol of an enum field; strings are accepted for schemas that predate the enum
+    fn opt_symbol(&self, name: &str) -> Result<Option<&'a str>> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(AvroValue::Enum(_, symbol)) | Some(AvroValue::String(symbol)) => Ok(Some(symbol.as_str())),
+            Some(other) => Err(anyhow!("Field '{}': expected enum, got {:?}", name, other)),
+        }
+    }
+
+    fn symbol(&self, name: &str) -> Result<&'a str> {
+        self.opt_symbol(name)?.ok_or_else(|| anyhow!("Field '{}' is missing", name))
+    }
+}
@@ -0,0 +1,176 @@
+use std::io::Write;
+use std::ops::Deref;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use apache_avro::schema::RecordSchema;
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{GenericSingleObjectWriter, Schema};
+
+use super::decoder::{write_confluent_header, CONFLUENT_HEADER_LEN};
+
+/// Length of the Avro single-object header the writer puts in front of every datum
+const SINGLE_OBJECT_HEADER_LEN: usize = 10;
+
+/// Encoding buffers kept for reuse; more than this are dropped when returned
+const MAX_POOLED_BUFFERS: usize = 64;
+
+/// Reusable payload buffers shared by every topic
+#[derive(Default)]
+pub struct BufferPool {
+    buffers: Mutex<Vec<Vec<u8>>>,
+}
+
+impl BufferPool {
+    /// An empty buffer able to hold at least `capacity` bytes
+    pub fn take(&self, capacity: usize) -> Vec<u8> {
+        let mut buffer = self.buffers.lock().unwrap().pop().unwrap_or_default();
+        buffer.clear();
+        buffer.reserve(capacity);
+        buffer
+    }
+
+    pub fn give(&self, buffer: Vec<u8>) {
+        let mut buffers = self.buffers.lock().unwrap();
+        if buffers.len() < MAX_POOLED_BUFFERS {
+            buffers.push(buffer);
+        }
+    }
+
+    /// Buffers currently waiting for reuse
+    pub fn len(&self) -> usize {
+        self.buffers.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// An encoded payload whose buffer goes back to its pool once the record has been sent
+pub struct EncodedPayload<'a> {
+    buffer: Vec<u8>,
+    pool: &'a BufferPool,
+    /// JSON copy of the record and the topic it is mirrored to
+    mirror: Option<(String, Vec<u8>)>,
+}
+
+impl<'a> EncodedPayload<'a> {
+    pub fn new(buffer: Vec<u8>, pool: &'a BufferPool) -> Self {
+        Self { buffer, pool, mirror: None }
+    }
+
+    /// Send `json` to `topic` along with the record
+    pub fn with_mirror(mut self, topic: &str, json: Vec<u8>) -> Self {
+        self.mirror = Some((topic.to_string(), json));
+        self
+    }
+
+    /// Mirror topic and JSON copy of the record, if it is mirrored
+    pub fn mirror(&self) -> Option<(&str, &[u8])> {
+        self.mirror.as_ref().map(|(topic, json)| (topic.as_str(), json.as_slice()))
+    }
+}
+
+impl Deref for EncodedPayload<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl Drop for EncodedPayload<'_> {
+    fn drop(&mut self) {
+        self.pool.give(std::mem::take(&mut self.buffer));
+    }
+}
+
+/// Encoder of one topic's records with the schema registered for it
+///
+/// The resolved schema and the writer's scratch buffer are built once per schema version
+/// instead of once per record.
+pub struct TopicEncoder {
+    schema_id: i32,
+    record: RecordSchema,
+    writer: GenericSingleObjectWriter,
+    /// Size of the last payload, used to size the next buffer
+    last_len: usize,
+}
+
+impl TopicEncoder {
+    pub fn new(schema_id: i32, schema: &Schema) -> Result<Self> {
+        let Schema::Record(record) = schema else {
+            return Err(anyhow!("Schema {} is not a record", schema_id));
+        };
+        Ok(Self {
+            schema_id,
+            record: record.clone(),
+            writer: GenericSingleObjectWriter::new_with_capacity(schema, 256)?,
+            last_len: CONFLUENT_HEADER_LEN,
+        })
+    }
+
+    pub fn schema_id(&self) -> i32 {
+        self.schema_id
+    }
+
+    /// Whether every schema field missing from `fields` is optional with a null default;
+    /// other records are left to the registry encoder
+    pub fn can_encode(&self, fields: &[(String, AvroValue)]) -> bool {
+        self.record.fields.iter().all(|field| {
+            fields.iter().any(|(name, _)| *name == field.name)
+                || is_null_default(&field.schema, field.default.as_ref())
+        })
+    }
+
+    /// Encode `fields` in the Confluent wire format into a buffer from `pool`
+    ///
+    /// Fields are moved into the record in schema order, so nothing is cloned, and missing
+    /// optional fields are written as null.
+    pub fn encode<'a>(&mut self, mut fields: Vec<(String, AvroValue)>, pool: &'a BufferPool) -> Result<EncodedPayload<'a>> {
+        let mut values = Vec::with_capacity(self.record.fields.len());
+        for field in &self.record.fields {
+            let value = match fields.iter().position(|(name, _)| *name == field.name) {
+                Some(position) => fields.swap_remove(position).1,
+                None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+            };
+            values.push((field.name.clone(), value));
+        }
+
+        let mut buffer = pool.take(self.last_len);
+        write_confluent_header(&mut buffer, self.schema_id);
+        let mut out = SkipHeader { out: &mut buffer, skip: SINGLE_OBJECT_HEADER_LEN };
+        if let Err(e) = self.writer.write_value_ref(&AvroValue::Record(values), &mut out) {
+            pool.give(buffer);
+            return Err(anyhow!("Failed to encode record with schema {}: {}", self.schema_id, e));
+        }
+        self.last_len = buffer.len();
+        Ok(EncodedPayload::new(buffer, pool))
+    }
+}
+
+/// Whether a missing field is an optional field defaulting to null
+fn is_null_default(schema: &Schema, default: Option<&serde_json::Value>) -> bool {
+    matches!(default, Some(serde_json::Value::Null))
+        && matches!(schema, Schema::Union(union) if matches!(union.variants().first(), Some(Schema::Null)))
+}
+
+/// Writes through to a buffer, dropping the first `skip` bytes
+struct SkipHeader<'a> {
+    out: &'a mut Vec<u8>,
+    skip: usize,
+}
+
+impl Write for SkipHeader<'_> {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        let skipped = self.skip.min(bytes.len());
+        self.skip -= skipped;
+        self.out.extend_from_slice(&bytes[skipped..]);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
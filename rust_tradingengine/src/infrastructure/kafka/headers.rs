@@ -0,0 +1,72 @@
+use rdkafka::message::{Header, OwnedHeaders};
+
+/// Header naming the kind of record, the topic type it was encoded as (`ticker`, `ack`, ...)
+pub const HEADER_EVENT_TYPE: &str = "event_type";
+
+/// Header carrying the registry id of the schema the payload was encoded with
+pub const HEADER_SCHEMA_ID: &str = "schema_id";
+
+/// Header naming the instrument the record is about, when it is about one
+pub const HEADER_INSTRUMENT: &str = "instrument";
+
+/// Header carrying when the record was produced (milliseconds since epoch)
+pub const HEADER_PRODUCED_AT: &str = "produced_at";
+
+/// What stream processors need to know of a record without decoding its payload
+///
+/// The record timestamp is set to `event_time`, when the exchange gave one, so
+/// event-time windows line up with the market rather than with the producer's queue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordMeta<'a> {
+    pub event_type: &'a str,
+    pub instrument: Option<&'a str>,
+    /// Exchange time of the event (seconds since epoch)
+    pub event_time: Option<f64>,
+}
+
+impl<'a> RecordMeta<'a> {
+    pub fn new(event_type: &'a str) -> Self {
+        Self { event_type, instrument: None, event_time: None }
+    }
+
+    pub fn instrument(mut self, instrument: Option<&'a str>) -> Self {
+        self.instrument = instrument;
+        self
+    }
+
+    pub fn at(mut self, event_time: f64) -> Self {
+        self.event_time = Some(event_time);
+        self
+    }
+
+    /// Record timestamp (milliseconds since epoch); None leaves it to the producer's clock
+    pub fn timestamp_millis(&self) -> Option<i64> {
+        self.event_time.and_then(timestamp_millis)
+    }
+
+    /// Headers of a record produced at `produced_at_ms` with a payload encoded with the
+    /// registry schema `schema_id`
+    pub fn headers(&self, schema_id: Option<i32>, produced_at_ms: i64) -> Vec<(&'static str, String)> {
+        let mut headers = vec![(HEADER_EVENT_TYPE, self.event_type.to_string())];
+        if let Some(schema_id) = schema_id {
+            headers.push((HEADER_SCHEMA_ID, schema_id.to_string()));
+        }
+        if let Some(instrument) = self.instrument.filter(|instrument| !instrument.is_empty()) {
+            headers.push((HEADER_INSTRUMENT, instrument.to_string()));
+        }
+        headers.push((HEADER_PRODUCED_AT, produced_at_ms.to_string()));
+        headers
+    }
+}
+
+/// Milliseconds since epoch of `seconds`; None for unset (zero or negative) times
+pub fn timestamp_millis(seconds: f64) -> Option<i64> {
+    (seconds.is_finite() && seconds > 0.0).then(|| (seconds * 1000.0).round() as i64)
+}
+
+/// Kafka headers holding `headers`, values as UTF-8
+pub fn to_owned_headers(headers: &[(&'static str, String)]) -> OwnedHeaders {
+    headers.iter().fold(OwnedHeaders::new_with_capacity(headers.len()), |owned, (key, value)| {
+        owned.insert(Header { key, value: Some(value.as_bytes()) })
+    })
+}
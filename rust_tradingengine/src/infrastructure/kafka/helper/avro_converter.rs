@@ -3,8 +3,19 @@ use apache_avro::types::Value as AvroValue;
 use log::debug;
 
 use crate::domain::enums::{OrderSide, OrderStatus, OrderType, TimeInForce};
-use crate::domain::model::ack::Ack;
+use crate::domain::model::order::{side_to_string, Order};
+use crate::domain::model::features::MarketFeatures;
+use crate::domain::model::uptime::QuoteUptime;
+use crate::domain::model::lifecycle::LifecycleEvent;
+use crate::domain::model::heartbeat::Heartbeat;
+use crate::domain::model::book::{BookLevelUpdate, BookSide, BookUpdateKind};
+use crate::domain::model::session_summary::SessionSummary;
+use crate::domain::model::funding_basis::FundingBasis;
+use crate::domain::model::account_summary::AccountSummary;
+use crate::domain::model::account_event::{AccountEvent, AccountEventType};
+use crate::domain::model::trade_correction::{CorrectionKind, TradeCorrection};
 use crate::domain::model::ticker::Ticker;
+use crate::domain::model::ticker_delta::{TickerDelta, TickerDeltaKind};
 use crate::domain::model::trade::Trade;
 
 /// Converter for domain models to Avro format
@@ -46,9 +57,9 @@ impl AvroConverter {
         }
     }
 
-    /// Convert an Ack domain model to Avro Value
-    pub fn ack_to_avro_value(ack: &Ack) -> Vec<(String, AvroValue)> {
-        debug!("Converting Ack to Avro: {:?}", ack);
+    /// Convert an Order to the `ack` topic's Avro Value
+    pub fn ack_to_avro_value(ack: &Order) -> Vec<(String, AvroValue)> {
+        debug!("Converting Order to Avro: {:?}", ack);
         let mut fields = Vec::with_capacity(17);  // Pre-allocate for all fields
         
         // Add all fields in the correct order according to the schema
@@ -108,7 +119,7 @@ impl AvroConverter {
 
     /// Convert a Trade domain model to Avro Value
     pub fn trade_to_avro_value(trade: &Trade) -> Result<Vec<(String, AvroValue)>> {
-        let mut fields = Vec::with_capacity(9);  // Pre-allocate for all fields including processing_timestamp
+        let mut fields = Vec::with_capacity(10);  // Pre-allocate for all fields including processing_timestamp
         
         // Add fields in the same order as the schema
         fields.push(("trade_id".to_string(), AvroValue::String(trade.trade_id.clone())));
@@ -124,7 +135,7 @@ impl AvroConverter {
         fields.push(("instrument_name".to_string(), AvroValue::String(trade.instrument_name.clone())));
         fields.push(("price".to_string(), AvroValue::Double(trade.price)));
         fields.push(("amount".to_string(), AvroValue::Double(trade.amount)));
-        fields.push(("maker_taker".to_string(), AvroValue::String(trade.maker_taker.clone())));
+        fields.push(("maker_taker".to_string(), AvroValue::String(trade.maker_taker_str().to_string())));
         fields.push(("time".to_string(), AvroValue::Double(trade.time)));
         
         // Handle processing_timestamp field (optional)
@@ -134,6 +145,13 @@ impl AvroConverter {
         };
         fields.push(("processing_timestamp".to_string(), processing_timestamp_value));
         
+        // Typed role (v3); the string field above stays for older readers
+        let maker_taker_role_value = match trade.maker_taker {
+            Some(role) => AvroValue::Union(1, Box::new(AvroValue::Enum(role.index(), role.as_str().to_string()))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        fields.push(("maker_taker_role".to_string(), maker_taker_role_value));
+        
         Ok(fields)
     }
 
@@ -178,4 +196,295 @@ impl AvroConverter {
         // Return the fields directly, not wrapped in an AvroValue::Record
         Ok(fields)
     }
-}
\ No newline at end of file
+
+    /// Convert a MarketFeatures record to Avro field vector
+    pub fn features_to_avro_value(features: &MarketFeatures) -> Result<Vec<(String, AvroValue)>> {
+        let mut fields = Vec::with_capacity(10); // Pre-allocate for all fields including processing_timestamp
+        
+        // Add fields in the same order as the schema
+        fields.push(("instrument_name".to_string(), AvroValue::String(features.instrument_name.clone())));
+        fields.push(("timestamp".to_string(), AvroValue::Double(features.timestamp)));
+        fields.push(("window_secs".to_string(), AvroValue::Double(features.window_secs)));
+        fields.push(("imbalance".to_string(), AvroValue::Double(features.imbalance)));
+        fields.push(("spread".to_string(), AvroValue::Double(features.spread)));
+        fields.push(("trade_intensity".to_string(), AvroValue::Double(features.trade_intensity)));
+        fields.push(("realized_vol".to_string(), AvroValue::Double(features.realized_vol)));
+        fields.push(("funding_basis".to_string(), AvroValue::Double(features.funding_basis)));
+        fields.push(("funding_rate".to_string(), AvroValue::Double(features.funding_rate)));
+        
+        // Handle processing_timestamp field (optional)
+        let processing_timestamp_value = match features.processing_timestamp {
+            Some(ts) => AvroValue::Union(1, Box::new(AvroValue::Double(ts))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        fields.push(("processing_timestamp".to_string(), processing_timestamp_value));
+        
+        Ok(fields)
+    }
+
+    /// Convert a QuoteUptime record to Avro field vector
+    pub fn uptime_to_avro_value(uptime: &QuoteUptime) -> Result<Vec<(String, AvroValue)>> {
+        let mut fields = Vec::with_capacity(9);
+        
+        // Add fields in the same order as the schema
+        fields.push(("instrument_name".to_string(), AvroValue::String(uptime.instrument_name.clone())));
+        fields.push(("date".to_string(), AvroValue::String(uptime.date.clone())));
+        fields.push(("period_start".to_string(), AvroValue::Double(uptime.period_start)));
+        fields.push(("period_end".to_string(), AvroValue::Double(uptime.period_end)));
+        fields.push(("observed_secs".to_string(), AvroValue::Double(uptime.observed_secs)));
+        fields.push(("compliant_secs".to_string(), AvroValue::Double(uptime.compliant_secs)));
+        fields.push(("uptime_pct".to_string(), AvroValue::Double(uptime.uptime_pct)));
+        fields.push(("max_distance_ticks".to_string(), AvroValue::Double(uptime.max_distance_ticks)));
+        
+        // Handle processing_timestamp field (optional)
+        let processing_timestamp_value = match uptime.processing_timestamp {
+            Some(ts) => AvroValue::Union(1, Box::new(AvroValue::Double(ts))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        fields.push(("processing_timestamp".to_string(), processing_timestamp_value));
+        
+        Ok(fields)
+    }
+
+    /// Convert a LifecycleEvent to Avro field vector
+    pub fn lifecycle_to_avro_value(event: &LifecycleEvent) -> Result<Vec<(String, AvroValue)>> {
+        let mut fields = Vec::with_capacity(6);
+        
+        // Add fields in the same order as the schema
+        fields.push(("instance_id".to_string(), AvroValue::String(event.instance_id.clone())));
+        
+        let index = crate::domain::model::lifecycle::LifecycleEventType::ALL
+            .iter()
+            .position(|t| *t == event.event_type)
+            .unwrap_or_default() as u32;
+        fields.push(("event_type".to_string(), AvroValue::Enum(index, event.event_type.as_str().to_string())));
+        
+        let reason_value = match &event.reason {
+            Some(reason) => AvroValue::Union(1, Box::new(AvroValue::String(reason.clone()))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        fields.push(("reason".to_string(), reason_value));
+        fields.push(("network".to_string(), AvroValue::String(event.network.clone())));
+        fields.push(("timestamp".to_string(), AvroValue::Double(event.timestamp)));
+        
+        // Handle processing_timestamp field (optional)
+        let processing_timestamp_value = match event.processing_timestamp {
+            Some(ts) => AvroValue::Union(1, Box::new(AvroValue::Double(ts))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        fields.push(("processing_timestamp".to_string(), processing_timestamp_value));
+        
+        Ok(fields)
+    }
+
+    /// Convert an AccountEvent to Avro field vector
+    pub fn account_event_to_avro_value(event: &AccountEvent) -> Result<Vec<(String, AvroValue)>> {
+        let optional_string = |value: &Option<String>| match value {
+            Some(v) => AvroValue::Union(1, Box::new(AvroValue::String(v.clone()))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        let optional_double = |value: Option<f64>| match value {
+            Some(v) => AvroValue::Union(1, Box::new(AvroValue::Double(v))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        let index = AccountEventType::ALL
+            .iter()
+            .position(|t| *t == event.event_type)
+            .unwrap_or_default() as u32;
+        
+        // Fields in the same order as the schema
+        Ok(vec![
+            ("event_type".to_string(), AvroValue::Enum(index, event.event_type.as_str().to_string())),
+            ("timestamp".to_string(), AvroValue::Double(event.timestamp)),
+            ("currency".to_string(), optional_string(&event.currency)),
+            ("amount".to_string(), optional_double(event.amount)),
+            ("instrument_name".to_string(), optional_string(&event.instrument_name)),
+            ("message".to_string(), optional_string(&event.message)),
+            ("processing_timestamp".to_string(), optional_double(event.processing_timestamp)),
+        ])
+    }
+
+    /// Convert a TradeCorrection to Avro field vector
+    pub fn trade_correction_to_avro_value(correction: &TradeCorrection) -> Result<Vec<(String, AvroValue)>> {
+        let optional_string = |value: Option<String>| match value {
+            Some(v) => AvroValue::Union(1, Box::new(AvroValue::String(v))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        let optional_double = |value: Option<f64>| match value {
+            Some(v) => AvroValue::Union(1, Box::new(AvroValue::Double(v))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        let index = CorrectionKind::ALL
+            .iter()
+            .position(|k| *k == correction.kind)
+            .unwrap_or_default() as u32;
+        
+        // Fields in the same order as the schema
+        Ok(vec![
+            ("kind".to_string(), AvroValue::Enum(index, correction.kind.as_str().to_string())),
+            ("trade_id".to_string(), AvroValue::String(correction.trade_id.clone())),
+            ("timestamp".to_string(), AvroValue::Double(correction.timestamp)),
+            ("order_id".to_string(), optional_string(correction.order_id.clone())),
+            ("instrument_name".to_string(), optional_string(correction.instrument_name.clone())),
+            ("direction".to_string(), optional_string(correction.direction.as_ref().map(|side| side_to_string(side).to_string()))),
+            ("original_price".to_string(), optional_double(correction.original_price)),
+            ("original_amount".to_string(), optional_double(correction.original_amount)),
+            ("corrected_price".to_string(), optional_double(correction.corrected_price)),
+            ("corrected_amount".to_string(), optional_double(correction.corrected_amount)),
+            ("message".to_string(), optional_string(correction.message.clone())),
+            ("processing_timestamp".to_string(), optional_double(correction.processing_timestamp)),
+        ])
+    }
+
+    /// Convert an AccountSummary to Avro field vector, one nested record per balance
+    pub fn account_summary_to_avro_value(summary: &AccountSummary) -> Result<Vec<(String, AvroValue)>> {
+        let optional_double = |value: Option<f64>| match value {
+            Some(v) => AvroValue::Union(1, Box::new(AvroValue::Double(v))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        let balances = summary.balances.iter()
+            .map(|balance| AvroValue::Record(vec![
+                ("currency".to_string(), AvroValue::String(balance.currency.clone())),
+                ("balance".to_string(), AvroValue::Double(balance.balance)),
+                ("collateral_factor".to_string(), optional_double(balance.collateral_factor)),
+            ]))
+            .collect();
+        
+        // Fields in the same order as the schema
+        Ok(vec![
+            ("timestamp".to_string(), AvroValue::Double(summary.timestamp)),
+            ("balances".to_string(), AvroValue::Array(balances)),
+            ("cash_collateral".to_string(), AvroValue::Double(summary.cash_collateral)),
+            ("unrealised_pnl".to_string(), AvroValue::Double(summary.unrealised_pnl)),
+            ("margin".to_string(), AvroValue::Double(summary.margin)),
+            ("required_margin".to_string(), AvroValue::Double(summary.required_margin)),
+            ("remaining_margin".to_string(), AvroValue::Double(summary.remaining_margin)),
+            ("session_realised_pnl".to_string(), AvroValue::Double(summary.session_realised_pnl)),
+            ("processing_timestamp".to_string(), optional_double(summary.processing_timestamp)),
+        ])
+    }
+
+    /// Convert a Heartbeat to Avro field vector
+    pub fn heartbeat_to_avro_value(heartbeat: &Heartbeat) -> Result<Vec<(String, AvroValue)>> {
+        let optional_double = |value: Option<f64>| match value {
+            Some(v) => AvroValue::Union(1, Box::new(AvroValue::Double(v))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        
+        // Fields in the same order as the schema
+        Ok(vec![
+            ("instance_id".to_string(), AvroValue::String(heartbeat.instance_id.clone())),
+            ("sequence".to_string(), AvroValue::Long(heartbeat.sequence)),
+            ("positions_hash".to_string(), AvroValue::String(heartbeat.positions_hash.clone())),
+            ("last_ticker_time".to_string(), optional_double(heartbeat.last_ticker_time)),
+            ("last_index_time".to_string(), optional_double(heartbeat.last_index_time)),
+            ("last_order_time".to_string(), optional_double(heartbeat.last_order_time)),
+            ("last_trade_time".to_string(), optional_double(heartbeat.last_trade_time)),
+            ("timestamp".to_string(), AvroValue::Double(heartbeat.timestamp)),
+            ("processing_timestamp".to_string(), optional_double(heartbeat.processing_timestamp)),
+        ])
+    }
+
+    /// Convert a BookLevelUpdate to Avro field vector
+    pub fn book_level_to_avro_value(update: &BookLevelUpdate) -> Result<Vec<(String, AvroValue)>> {
+        let optional_double = |value: Option<f64>| match value {
+            Some(v) => AvroValue::Union(1, Box::new(AvroValue::Double(v))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        let kind_index = match update.kind {
+            BookUpdateKind::Snapshot => 0,
+            BookUpdateKind::Delta => 1,
+        };
+        let side_index = match update.side {
+            BookSide::Bid => 0,
+            BookSide::Ask => 1,
+        };
+        
+        // Fields in the same order as the schema
+        Ok(vec![
+            ("instrument_name".to_string(), AvroValue::String(update.instrument_name.clone())),
+            ("kind".to_string(), AvroValue::Enum(kind_index, update.kind.as_str().to_string())),
+            ("sequence".to_string(), AvroValue::Long(update.sequence)),
+            ("side".to_string(), AvroValue::Enum(side_index, update.side.as_str().to_string())),
+            ("price".to_string(), AvroValue::Double(update.price)),
+            ("amount".to_string(), AvroValue::Double(update.amount)),
+            ("exchange_time".to_string(), optional_double(update.exchange_time)),
+            ("timestamp".to_string(), AvroValue::Double(update.timestamp)),
+            ("processing_timestamp".to_string(), optional_double(update.processing_timestamp)),
+        ])
+    }
+
+    /// Convert a SessionSummary to Avro field vector
+    pub fn session_summary_to_avro_value(summary: &SessionSummary) -> Result<Vec<(String, AvroValue)>> {
+        let processing_timestamp_value = match summary.processing_timestamp {
+            Some(ts) => AvroValue::Union(1, Box::new(AvroValue::Double(ts))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        
+        // Fields in the same order as the schema
+        Ok(vec![
+            ("instance_id".to_string(), AvroValue::String(summary.instance_id.clone())),
+            ("started_at".to_string(), AvroValue::Double(summary.started_at)),
+            ("ended_at".to_string(), AvroValue::Double(summary.ended_at)),
+            ("quotes_sent".to_string(), AvroValue::Long(summary.quotes_sent)),
+            ("amends".to_string(), AvroValue::Long(summary.amends)),
+            ("cancels".to_string(), AvroValue::Long(summary.cancels)),
+            ("fills".to_string(), AvroValue::Long(summary.fills)),
+            ("volume".to_string(), AvroValue::Double(summary.volume)),
+            ("realized_pnl".to_string(), AvroValue::Double(summary.realized_pnl)),
+            ("max_inventory".to_string(), AvroValue::Double(summary.max_inventory)),
+            ("reconnects".to_string(), AvroValue::Long(summary.reconnects)),
+            ("processing_timestamp".to_string(), processing_timestamp_value),
+        ])
+    }
+
+    /// Convert FundingBasis to Avro field vector
+    pub fn funding_basis_to_avro_value(sample: &FundingBasis) -> Result<Vec<(String, AvroValue)>> {
+        let processing_timestamp_value = match sample.processing_timestamp {
+            Some(ts) => AvroValue::Union(1, Box::new(AvroValue::Double(ts))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        
+        // Fields in the same order as the schema
+        Ok(vec![
+            ("instrument_name".to_string(), AvroValue::String(sample.instrument_name.clone())),
+            ("timestamp".to_string(), AvroValue::Double(sample.timestamp)),
+            ("window_secs".to_string(), AvroValue::Double(sample.window_secs)),
+            ("mark_price".to_string(), AvroValue::Double(sample.mark_price)),
+            ("index_price".to_string(), AvroValue::Double(sample.index_price)),
+            ("basis".to_string(), AvroValue::Double(sample.basis)),
+            ("basis_bps".to_string(), AvroValue::Double(sample.basis_bps)),
+            ("rolling_basis_bps".to_string(), AvroValue::Double(sample.rolling_basis_bps)),
+            ("funding_rate".to_string(), AvroValue::Double(sample.funding_rate)),
+            ("annualized_funding_rate".to_string(), AvroValue::Double(sample.annualized_funding_rate)),
+            ("position".to_string(), AvroValue::Double(sample.position)),
+            ("cumulative_funding".to_string(), AvroValue::Double(sample.cumulative_funding)),
+            ("annualized_carry".to_string(), AvroValue::Double(sample.annualized_carry)),
+            ("processing_timestamp".to_string(), processing_timestamp_value),
+        ])
+    }
+
+    /// Convert a TickerDelta to Avro field vector; unchanged fields are null
+    pub fn ticker_delta_to_avro_value(delta: &TickerDelta) -> Result<Vec<(String, AvroValue)>> {
+        let optional_double = |value: Option<f64>| match value {
+            Some(v) => AvroValue::Union(1, Box::new(AvroValue::Double(v))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        let kind_index = match delta.kind {
+            TickerDeltaKind::Snapshot => 0,
+            TickerDeltaKind::Delta => 1,
+        };
+        
+        // Fields in the same order as the schema
+        let mut fields = Vec::with_capacity(Ticker::NUMERIC_FIELDS.len() + 5);
+        fields.push(("instrument_name".to_string(), AvroValue::String(delta.instrument_name.clone())));
+        fields.push(("kind".to_string(), AvroValue::Enum(kind_index, delta.kind.as_str().to_string())));
+        fields.push(("sequence".to_string(), AvroValue::Long(delta.sequence)));
+        for name in Ticker::NUMERIC_FIELDS {
+            fields.push((name.to_string(), optional_double(delta.get(name))));
+        }
+        fields.push(("timestamp".to_string(), AvroValue::Double(delta.timestamp)));
+        fields.push(("processing_timestamp".to_string(), optional_double(delta.processing_timestamp)));
+        Ok(fields)
+    }
+}
@@ -2,7 +2,10 @@
 pub mod schema_helper;
 // Avro conversion helpers
 pub mod avro_converter;
+// Pre-send validation of Avro records
+pub mod schema_validator;
 
 // Re-export helpers
-pub use schema_helper::SchemaHelper;
+pub use schema_helper::{compare_schemas, SchemaChange, SchemaHelper};
 pub use avro_converter::AvroConverter;
+pub use schema_validator::validate_record;
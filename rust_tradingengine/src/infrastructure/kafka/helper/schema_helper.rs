@@ -1,8 +1,69 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use apache_avro::Schema;
+use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use log::{info, warn};
 
+/// How a local schema differs from the version registered for its subject
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaChange {
+    Unchanged,
+    /// Only new fields with defaults; readers of either version resolve the other's records
+    AddedOptionalFields(Vec<String>),
+    /// Any other change; needs a deliberate migration rather than an automatic upgrade
+    Incompatible(String),
+}
+
+/// Compare the top-level record fields of a local schema against the registered one
+///
+/// Field docs are ignored; a field whose type or default changed, a removed field, or an
+/// added field without a default makes the change incompatible.
+pub fn compare_schemas(registered: &str, local: &str) -> Result<SchemaChange> {
+    Schema::parse_str(registered).context("Failed to parse registered schema")?;
+    Schema::parse_str(local).context("Failed to parse local schema")?;
+    
+    let registered_fields = record_fields(registered)?;
+    let local_fields = record_fields(local)?;
+    
+    let mut added = Vec::new();
+    for (name, field) in &local_fields {
+        match registered_fields.get(name) {
+            Some(existing) => {
+                if existing.get("type") != field.get("type") || existing.get("default") != field.get("default") {
+                    return Ok(SchemaChange::Incompatible(format!("field {} changed", name)));
+                }
+            }
+            None if field.get("default").is_some() => added.push(name.clone()),
+            None => return Ok(SchemaChange::Incompatible(format!("field {} added without a default", name))),
+        }
+    }
+    if let Some(name) = registered_fields.keys().find(|name| !local_fields.contains_key(*name)) {
+        return Ok(SchemaChange::Incompatible(format!("field {} removed", name)));
+    }
+    
+    if added.is_empty() {
+        Ok(SchemaChange::Unchanged)
+    } else {
+        added.sort();
+        Ok(SchemaChange::AddedOptionalFields(added))
+    }
+}
+
+/// Fields of a record schema by name
+fn record_fields(schema: &str) -> Result<HashMap<String, Value>> {
+    let schema: Value = serde_json::from_str(schema)?;
+    let fields = schema["fields"].as_array()
+        .ok_or_else(|| anyhow!("Schema is not a record"))?;
+    fields.iter()
+        .map(|field| {
+            let name = field["name"].as_str().ok_or_else(|| anyhow!("Field without a name"))?;
+            Ok((name.to_string(), field.clone()))
+        })
+        .collect()
+}
+
 /// Helper for finding and managing Avro schemas
 pub struct SchemaHelper {
     schema_dir: String,
@@ -30,7 +91,9 @@ impl SchemaHelper {
             return Ok(schema_json_path);
         }
         
-        // If schema.json doesn't exist, look for any .json file
+        // If schema.json doesn't exist, take the highest vN file, or else the most
+        // recently modified .json/.avsc file
+        let mut latest_version = None;
         let mut latest_modified = None;
         let mut latest_path = None;
         
@@ -42,21 +105,23 @@ impl SchemaHelper {
             }
         };
         
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_file() {
-                    if let Some(ext) = path.extension() {
-                        if ext == "json" || ext == "avsc" {
-                            // Get file metadata to find the most recently modified
-                            if let Ok(metadata) = fs::metadata(&path) {
-                                if let Ok(modified) = metadata.modified() {
-                                    if latest_modified.is_none() || modified > latest_modified.unwrap() {
-                                        latest_modified = Some(modified);
-                                        latest_path = Some(path);
-                                    }
-                                }
-                            }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() {
+                if let Some(ext) = path.extension() {
+                    if ext == "json" || ext == "avsc" {
+                        let version = Self::schema_version(&path);
+                        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                        let newer = match (version, latest_version) {
+                            (Some(v), Some(latest)) => v > latest,
+                            (Some(_), None) => true,
+                            (None, Some(_)) => false,
+                            (None, None) => modified.is_some() && (latest_modified.is_none() || modified > latest_modified),
+                        };
+                        if newer {
+                            latest_version = version;
+                            latest_modified = modified;
+                            latest_path = Some(path);
                         }
                     }
                 }
@@ -71,6 +136,11 @@ impl SchemaHelper {
         Err(anyhow!("Could not find a schema file for {}", schema_type))
     }
     
+    /// Version number of a `vN.avsc` schema file
+    fn schema_version(path: &Path) -> Option<u32> {
+        path.file_stem()?.to_str()?.strip_prefix('v')?.parse().ok()
+    }
+    
     /// Read the schema content from a file
     pub fn read_schema_file(&self, path: &Path) -> Result<String> {
         match fs::read_to_string(path) {
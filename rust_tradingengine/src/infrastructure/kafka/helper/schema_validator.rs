@@ -0,0 +1,110 @@
+use std::borrow::Cow;
+
+use anyhow::{anyhow, Result};
+use apache_avro::schema::{RecordSchema, Schema};
+use apache_avro::types::Value as AvroValue;
+
+/// Check a record's field vector against its schema before it reaches the encoder
+///
+/// Every schema field must be present unless it has a default, no unknown fields may
+/// appear, and each value must match its field's type. Errors name the offending field.
+pub fn validate_record(schema: &Schema, fields: &[(String, AvroValue)]) -> Result<()> {
+    match schema {
+        Schema::Record(record) => validate_fields(record, fields, ""),
+        other => Err(anyhow!("Expected a record schema, got {:?}", Kind(other))),
+    }
+}
+
+fn validate_fields(record: &RecordSchema, fields: &[(String, AvroValue)], prefix: &str) -> Result<()> {
+    for (name, _) in fields {
+        if !record.lookup.contains_key(name) {
+            return Err(anyhow!("Field '{}{}' is not in schema {}", prefix, name, record.name));
+        }
+    }
+
+    for field in &record.fields {
+        // Top-level fields, the common case, need no path of their own
+        let path = if prefix.is_empty() {
+            Cow::Borrowed(field.name.as_str())
+        } else {
+            Cow::Owned(format!("{}{}", prefix, field.name))
+        };
+        match fields.iter().find(|(name, _)| *name == field.name) {
+            Some((_, value)) => validate_value(value, &field.schema, &path)?,
+            None if field.default.is_some() => {}
+            None => return Err(anyhow!("Field '{}' is missing and has no default", path)),
+        }
+    }
+    Ok(())
+}
+
+fn validate_value(value: &AvroValue, schema: &Schema, path: &str) -> Result<()> {
+    match (value, schema) {
+        (AvroValue::Enum(index, symbol), Schema::Enum(enum_schema)) => {
+            match enum_schema.symbols.get(*index as usize) {
+                Some(expected) if expected == symbol => Ok(()),
+                _ if enum_schema.symbols.contains(symbol) => Err(anyhow!(
+                    "Field '{}': enum symbol '{}' has index {}, expected {}",
+                    path, symbol, index, enum_schema.symbols.iter().position(|s| s == symbol).unwrap_or_default()
+                )),
+                _ => Err(anyhow!(
+                    "Field '{}': enum symbol '{}' not in [{}]",
+                    path, symbol, enum_schema.symbols.join(", ")
+                )),
+            }
+        }
+        (AvroValue::Union(index, inner), Schema::Union(union)) => {
+            let Some(branch) = union.variants().get(*index as usize) else {
+                return Err(anyhow!("Field '{}': union branch {} out of range ({} branches)", path, index, union.variants().len()));
+            };
+            validate_value(inner, branch, path)
+        }
+        (_, Schema::Union(union)) => {
+            // A bare value is fine as long as some branch accepts it
+            if union.variants().iter().any(|branch| validate_value(value, branch, path).is_ok()) {
+                Ok(())
+            } else {
+                Err(mismatch(value, schema, path))
+            }
+        }
+        (AvroValue::Record(fields), Schema::Record(record)) => {
+            validate_fields(record, fields, &format!("{}.", path))
+        }
+        (AvroValue::Array(items), Schema::Array(array)) => {
+            items.iter().enumerate()
+                .try_for_each(|(i, item)| validate_value(item, &array.items, &format!("{}[{}]", path, i)))
+        }
+        (AvroValue::Map(entries), Schema::Map(map)) => {
+            entries.iter()
+                .try_for_each(|(key, item)| validate_value(item, &map.types, &format!("{}[{}]", path, key)))
+        }
+        _ if value.validate(schema) => Ok(()),
+        _ => Err(mismatch(value, schema, path)),
+    }
+}
+
+fn mismatch(value: &AvroValue, schema: &Schema, path: &str) -> anyhow::Error {
+    anyhow!("Field '{}': expected {:?}, got {:?}", path, Kind(schema), value)
+}
+
+/// Short description of a schema for error messages, without the full definition
+struct Kind<'a>(&'a Schema);
+
+impl std::fmt::Debug for Kind<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Schema::Record(record) => write!(f, "record {}", record.name),
+            Schema::Enum(enum_schema) => write!(f, "enum {}", enum_schema.name),
+            Schema::Union(union) => {
+                let branches: Vec<String> = union.variants().iter().map(|b| format!("{:?}", Kind(b))).collect();
+                write!(f, "union [{}]", branches.join(", "))
+            }
+            Schema::Array(array) => write!(f, "array<{:?}>", Kind(&array.items)),
+            Schema::Map(map) => write!(f, "map<{:?}>", Kind(&map.types)),
+            other => {
+                let kind = format!("{:?}", apache_avro::schema::SchemaKind::from(other));
+                f.write_str(&kind.to_lowercase())
+            }
+        }
+    }
+}
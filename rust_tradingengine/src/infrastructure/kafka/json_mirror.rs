@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use apache_avro::types::Value as AvroValue;
+use serde_json::Value;
+
+use crate::config_loader::{JsonMirrorConfig, TopicType};
+
+/// JSON topic mirroring the Avro topic `topic`: a `.avro` suffix becomes `.json`, other
+/// names get `.json` appended
+pub fn mirror_topic(topic: &str) -> String {
+    format!("{}.json", topic.strip_suffix(".avro").unwrap_or(topic))
+}
+
+/// Mirror topic of each mirrored Avro topic
+pub fn mirror_topics(config: &JsonMirrorConfig, topics: &HashMap<String, TopicType>) -> HashMap<String, String> {
+    if !config.enabled {
+        return HashMap::new();
+    }
+    topics.iter()
+        .filter(|(topic_type, _)| config.topic_types.is_empty() || config.topic_types.contains(topic_type))
+        .map(|(_, spec)| (spec.topic.clone(), mirror_topic(&spec.topic)))
+        .collect()
+}
+
+/// JSON document of a record's fields; unions are written as their value and enums as
+/// their symbol
+pub fn record_to_json(fields: &[(String, AvroValue)]) -> Result<Vec<u8>> {
+    let value = Value::try_from(AvroValue::Record(fields.to_vec()))
+        .map_err(|e| anyhow!("Record can't be written as JSON: {}", e))?;
+    Ok(serde_json::to_vec(&value)?)
+}
@@ -1,5 +1,19 @@
 pub mod producer;
 pub mod helper;
+pub mod registry;
+pub mod decoder;
+pub mod encoder;
+pub mod json_mirror;
+pub mod watchdog;
+pub mod topic_admin;
+pub mod headers;
+pub mod spill;
 
 pub use producer::KafkaProducer;
-pub use helper::SchemaHelper;
\ No newline at end of file
+pub use helper::SchemaHelper;
+pub use registry::RegistryClient;
+pub use decoder::{decode_avro, event_id, frame_confluent_payload, split_confluent_payload, write_confluent_header, ConfluentDecoder, TypedRecord};
+pub use watchdog::{ProducerWatchdog, WatchdogAction};
+pub use topic_admin::{ConfigDrift, TopicAdmin};
+pub use headers::RecordMeta;
+pub use spill::{SpillBuffer, SpilledRecord};
@@ -1,37 +1,117 @@
 use anyhow::{anyhow, Context, Result};
 use apache_avro::Schema;
 use log::{debug, error, info, warn};
-use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::message::{Headers, ToBytes};
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
 use rdkafka::ClientConfig;
-use reqwest;
 use schema_registry_converter::async_impl::avro::AvroEncoder;
 use schema_registry_converter::async_impl::schema_registry::SrSettings;
 use schema_registry_converter::schema_registry_common::SubjectNameStrategy;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
-use crate::domain::model::ack::Ack;
+use crate::domain::clock;
+use crate::domain::model::order::Order;
+use crate::domain::model::features::MarketFeatures;
 use crate::domain::model::ticker::Ticker;
+use crate::domain::model::ticker_delta::TickerDelta;
 use crate::domain::model::trade::Trade;
+use crate::domain::model::uptime::QuoteUptime;
+use crate::domain::model::lifecycle::LifecycleEvent;
+use crate::domain::model::heartbeat::Heartbeat;
+use crate::domain::model::book::BookLevelUpdate;
+use crate::domain::model::session_summary::SessionSummary;
+use crate::domain::model::funding_basis::FundingBasis;
+use crate::domain::model::account_event::AccountEvent;
+use crate::domain::model::trade_correction::TradeCorrection;
+use crate::domain::model::account_summary::AccountSummary;
 use crate::infrastructure::exchange::thalex::parsers::ThaleParser;
-use crate::infrastructure::kafka::helper::{SchemaHelper, AvroConverter};
+use crate::config_loader::{JsonMirrorConfig, RegistryRetryConfig, SpillConfig, TopicAdminConfig, TopicType};
+use crate::infrastructure::kafka::helper::{compare_schemas, validate_record, SchemaChange, SchemaHelper, AvroConverter};
+use crate::infrastructure::kafka::decoder::split_confluent_payload;
+use crate::infrastructure::kafka::encoder::{BufferPool, EncodedPayload, TopicEncoder};
+use crate::infrastructure::kafka::headers::{to_owned_headers, RecordMeta};
+use crate::infrastructure::kafka::json_mirror;
+use crate::infrastructure::kafka::topic_admin::{ConfigDrift, TopicAdmin};
+use crate::infrastructure::kafka::registry::{encoder_failure, RegistryClient};
+use crate::infrastructure::kafka::spill::{SpillBuffer, SpilledRecord};
+use crate::infrastructure::kafka::watchdog::{METRIC_JOURNAL_ONLY, METRIC_PRODUCER_RESTARTS};
+use crate::infrastructure::metrics;
+#[cfg(feature = "chaos")]
+use crate::config_loader::ChaosConfig;
+#[cfg(feature = "chaos")]
+use crate::infrastructure::chaos::{FaultInjector, FaultTarget};
+
+/// Messages queued inside librdkafka awaiting delivery
+pub const METRIC_QUEUE_DEPTH: &str = "kafka.queue_depth";
+
+/// Sends still being encoded or awaiting delivery
+pub const METRIC_PENDING_SENDS: &str = "kafka.pending_sends";
+
+/// Schema versions registered automatically because the local schema added optional fields
+pub const METRIC_SCHEMA_UPGRADES: &str = "kafka.schema_upgrades";
+
+/// Records rejected before encoding because they don't match their topic's schema
+pub const METRIC_VALIDATION_FAILURES: &str = "kafka.validation_failures";
+
+/// JSON mirror copies that couldn't be written or delivered
+pub const METRIC_JSON_MIRROR_FAILURES: &str = "kafka.json_mirror_failures";
+
+/// Keeps the pending-send count up to date for the lifetime of one send
+struct PendingSend<'a> {
+    producer: &'a KafkaProducer,
+}
+
+impl<'a> PendingSend<'a> {
+    fn new(producer: &'a KafkaProducer) -> Self {
+        producer.pending_sends.fetch_add(1, Ordering::SeqCst);
+        producer.record_queue_metrics();
+        Self { producer }
+    }
+}
+
+impl Drop for PendingSend<'_> {
+    fn drop(&mut self) {
+        self.producer.pending_sends.fetch_sub(1, Ordering::SeqCst);
+        self.producer.record_queue_metrics();
+    }
+}
 
 /// Cached schema info
 struct SchemaInfo {
     id: i32,
-    schema: Schema,
+    schema: Arc<Schema>,
 }
 
 /// Kafka producer with schema registry support
 pub struct KafkaProducer {
-    /// Kafka producer client
-    producer: FutureProducer,
+    /// Kafka producer client, replaced when the watchdog restarts it
+    producer: RwLock<FutureProducer>,
     
-    /// Topic names
-    topics: HashMap<String, String>,
+    /// Settings every client is created with
+    client_config: ClientConfig,
+    
+    /// When a record was last delivered, or the producer created
+    last_delivery: Mutex<Instant>,
+    
+    /// Journal records are written to in journal-only mode
+    journal: Option<PathBuf>,
+    
+    /// Records go to the journal instead of Kafka
+    journal_only: AtomicBool,
+    
+    /// Records whose delivery failed, waiting for a retry; failures are returned when None
+    spill: Option<SpillBuffer>,
+    
+    /// Topic, schema and subject of every topic type
+    topics: HashMap<String, TopicType>,
     
     /// Schema helper
     schema_helper: SchemaHelper,
@@ -44,16 +124,66 @@ pub struct KafkaProducer {
     
     /// Schema Registry settings
     sr_settings: Arc<SrSettings>,
+    
+    /// Retrying HTTP access to the registry, shared by every registry call
+    registry: RegistryClient,
+    
+    /// Sends that started but haven't completed yet
+    pending_sends: AtomicUsize,
+    
+    /// Encoder per topic, for topics whose schema is cached
+    encoders: Mutex<HashMap<String, TopicEncoder>>,
+    
+    /// Payload buffers reused across sends
+    buffers: BufferPool,
+    
+    /// Last event id handed out per topic
+    event_ids: Mutex<HashMap<String, i64>>,
+    
+    /// JSON topic of each Avro topic mirrored to JSON
+    json_mirrors: HashMap<String, String>,
+    
+    /// Trades without a known maker/taker role are rejected instead of published without one
+    strict_parsing: bool,
+    
+    /// Event id sequences start here: the producer's creation time in microseconds, so
+    /// ids keep increasing across restarts
+    event_id_base: i64,
+    
+    /// Fault injection on Kafka and registry calls
+    #[cfg(feature = "chaos")]
+    faults: Vec<FaultInjector>,
 }
 
 impl KafkaProducer {
     /// Creates a new Kafka producer with the given configuration
     pub async fn new(bootstrap_servers: &str, schema_registry_url: &str, topics: HashMap<String, String>, schema_dir: String) -> Result<Self> {
+        let topics = topics.iter()
+            .map(|(topic_type, topic)| (topic_type.clone(), TopicType::builtin(topic_type, topic)))
+            .collect();
+        let producer = Self::with_registry_retry(bootstrap_servers, schema_registry_url, topics, schema_dir, RegistryRetryConfig::default()).await?;
+        if let Err(e) = producer.reconcile_topics(&TopicAdminConfig::default()).await {
+            warn!("Failed to reconcile topic settings: {}", e);
+        }
+        Ok(producer)
+    }
+    
+    /// Creates a new Kafka producer for the given topic types whose registry calls follow `registry_retry`
+    ///
+    /// Topics aren't created here; call `reconcile_topics` once the producer is set up.
+    pub async fn with_registry_retry(
+        bootstrap_servers: &str,
+        schema_registry_url: &str,
+        topics: HashMap<String, TopicType>,
+        schema_dir: String,
+        registry_retry: RegistryRetryConfig,
+    ) -> Result<Self> {
         // Set up Kafka producer
-        let producer: FutureProducer = ClientConfig::new()
+        let mut client_config = ClientConfig::new();
+        client_config
             .set("bootstrap.servers", bootstrap_servers)
-            .set("message.timeout.ms", "5000")
-            .create()
+            .set("message.timeout.ms", "5000");
+        let producer: FutureProducer = client_config.create()
             .context("Failed to create Kafka producer")?;
         
         // Create schema helper
@@ -64,17 +194,34 @@ impl KafkaProducer {
         
         // Create the KafkaProducer
         let producer = Self {
-            producer,
+            producer: RwLock::new(producer),
+            client_config,
+            last_delivery: Mutex::new(clock::instant()),
+            journal: None,
+            journal_only: AtomicBool::new(false),
+            spill: None,
             topics,
             schema_helper,
             schema_registry_url: schema_registry_url.to_string(),
             cached_schemas: RwLock::new(HashMap::new()),
             sr_settings,
+            registry: RegistryClient::new(registry_retry),
+            pending_sends: AtomicUsize::new(0),
+            encoders: Mutex::new(HashMap::new()),
+            buffers: BufferPool::default(),
+            event_ids: Mutex::new(HashMap::new()),
+            json_mirrors: HashMap::new(),
+            strict_parsing: false,
+            event_id_base: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as i64,
+            #[cfg(feature = "chaos")]
+            faults: Vec::new(),
         };
         
-        // Preload schemas for common topics during initialization
+        // Preload schemas for every configured topic type during initialization
         info!("Preloading schemas from registry...");
-        for topic_type in ["ticker", "ack", "trade", "index"] {
+        let mut topic_types: Vec<&String> = producer.topics.keys().collect();
+        topic_types.sort();
+        for topic_type in topic_types {
             if let Err(e) = producer.preload_schema(topic_type).await {
                 warn!("Failed to preload schema for {}: {}", topic_type, e);
             }
@@ -83,13 +230,376 @@ impl KafkaProducer {
         Ok(producer)
     }
     
-    /// Helper method to encode data in Confluent format
-    async fn encode_confluent_format(&self, record_name: &str, value: Vec<(String, apache_avro::types::Value)>, topic: &str) -> Result<Vec<u8>> {
-        // Create subject name strategy for the topic
-        let subject_strategy = SubjectNameStrategy::TopicNameStrategy(
-            topic.to_string(),
-            false // is it a key
-        );
+    /// Create the topics with retention or compaction expectations and check the broker's settings
+    ///
+    /// Auto-created topics get the broker's defaults, so these are created up front.
+    /// Returns the settings still differing from the configuration.
+    pub async fn reconcile_topics(&self, config: &TopicAdminConfig) -> Result<Vec<ConfigDrift>> {
+        let bootstrap_servers = self.client_config.get("bootstrap.servers").unwrap_or_default();
+        TopicAdmin::new(bootstrap_servers, config.clone())?.reconcile(&self.topics).await
+    }
+    
+    /// Enable fault injection on Kafka and registry calls
+    #[cfg(feature = "chaos")]
+    pub fn set_faults(&mut self, chaos: &ChaosConfig) {
+        self.faults = if chaos.enabled {
+            vec![
+                FaultInjector::new(FaultTarget::Kafka, chaos.kafka.clone(), chaos.seed),
+                FaultInjector::new(FaultTarget::Registry, chaos.registry.clone(), chaos.seed),
+            ]
+        } else {
+            Vec::new()
+        };
+    }
+    
+    /// Apply injected faults for a call to `target`
+    #[cfg(feature = "chaos")]
+    async fn inject(&self, target: FaultTarget) -> Result<()> {
+        for fault in self.faults.iter().filter(|f| f.target() == target) {
+            fault.check().await?;
+        }
+        Ok(())
+    }
+    
+    /// Current client
+    fn client(&self) -> FutureProducer {
+        self.producer.read().unwrap().clone()
+    }
+    
+    /// Replace the client with a fresh one; records still queued in the old client are
+    /// delivered or time out there
+    pub fn restart_client(&self) -> Result<()> {
+        let client: FutureProducer = self.client_config.create()
+            .context("Failed to create Kafka producer")?;
+        *self.producer.write().unwrap() = client;
+        metrics::global().incr(METRIC_PRODUCER_RESTARTS, 1);
+        self.record_queue_metrics();
+        Ok(())
+    }
+    
+    /// Check that a broker answers within `timeout`
+    pub async fn probe(&self, timeout: Duration) -> Result<()> {
+        let client = self.client();
+        tokio::task::spawn_blocking(move || client.client().fetch_metadata(None, timeout)).await??;
+        Ok(())
+    }
+    
+    /// Journal the journal-only mode writes to
+    pub fn set_journal(&mut self, path: impl Into<PathBuf>) {
+        self.journal = Some(path.into());
+    }
+    
+    /// Keep records whose delivery fails for a retry instead of failing their send
+    pub fn set_spill(&mut self, config: &SpillConfig) {
+        self.spill = config.enabled.then(|| SpillBuffer::new(config));
+    }
+    
+    /// Records waiting in the spill buffer for a retry
+    pub fn spilled(&self) -> usize {
+        self.spill.as_ref().map_or(0, SpillBuffer::len)
+    }
+    
+    /// Retry the spilled records, oldest first, until one fails; returns how many were delivered
+    pub async fn retry_spilled(&self) -> usize {
+        let Some(spill) = &self.spill else {
+            return 0;
+        };
+        spill.retry(|record| async move {
+            self.send_now(record.to_record(), Duration::from_secs(5)).await
+                .map(|_| ())
+                .map_err(|(e, _)| e)
+        }).await
+    }
+    
+    /// Reject trades without a known maker/taker role when parsing notifications
+    pub fn set_strict_parsing(&mut self, strict: bool) {
+        self.strict_parsing = strict;
+    }
+    
+    /// Mirror the configured topics to JSON topics
+    pub fn set_json_mirror(&mut self, config: &JsonMirrorConfig) {
+        self.json_mirrors = json_mirror::mirror_topics(config, &self.topics);
+        for (topic, json_topic) in &self.json_mirrors {
+            info!("Mirroring {} to {}", topic, json_topic);
+        }
+    }
+    
+    /// JSON topic `topic` is mirrored to, if any
+    pub fn json_mirror(&self, topic: &str) -> Option<&str> {
+        self.json_mirrors.get(topic).map(String::as_str)
+    }
+    
+    /// Switch between publishing to Kafka and writing to the journal
+    pub fn set_journal_only(&self, journal_only: bool) -> Result<()> {
+        if journal_only && self.journal.is_none() {
+            return Err(anyhow!("No journal configured"));
+        }
+        self.journal_only.store(journal_only, Ordering::SeqCst);
+        metrics::global().set_gauge(METRIC_JOURNAL_ONLY, if journal_only { 1.0 } else { 0.0 });
+        Ok(())
+    }
+    
+    pub fn is_journal_only(&self) -> bool {
+        self.journal_only.load(Ordering::SeqCst)
+    }
+    
+    /// When a record was last delivered to Kafka
+    pub fn last_delivery(&self) -> Instant {
+        *self.last_delivery.lock().unwrap()
+    }
+    
+    /// Records waiting to be delivered, spilled ones included
+    pub fn backlog(&self) -> usize {
+        self.pending_sends().max(self.queue_depth().max(0) as usize) + self.spilled()
+    }
+    
+    /// Hand a record to the current client, or to the journal in journal-only mode, and
+    /// wait for it to be delivered
+    ///
+    /// With a spill buffer, a record that fails is spilled for a retry and counts as
+    /// sent, and records queue behind those already spilled so they keep their order.
+    async fn deliver<K, P>(&self, record: FutureRecord<'_, K, P>, queue_timeout: Duration) -> Result<(i32, i64)>
+    where
+        K: ToBytes + ?Sized,
+        P: ToBytes + ?Sized,
+    {
+        if let Some(spill) = self.spill.as_ref().filter(|spill| !spill.is_empty() && !self.is_journal_only()) {
+            spill.spill(SpilledRecord::from_record(&record));
+            return Ok((-1, -1));
+        }
+        match self.send_now(record, queue_timeout).await {
+            Ok(delivered) => Ok(delivered),
+            Err((e, failed)) => match &self.spill {
+                Some(spill) => {
+                    warn!("Kafka delivery to {} failed, spilling the record for a retry: {}", failed.topic, e);
+                    spill.spill(failed);
+                    Ok((-1, -1))
+                }
+                None => Err(e),
+            },
+        }
+    }
+    
+    /// Deliver a record right away, returning it with the error when that fails
+    async fn send_now<K, P>(&self, record: FutureRecord<'_, K, P>, queue_timeout: Duration) -> std::result::Result<(i32, i64), (anyhow::Error, SpilledRecord)>
+    where
+        K: ToBytes + ?Sized,
+        P: ToBytes + ?Sized,
+    {
+        if self.is_journal_only() {
+            if let Some(path) = &self.journal {
+                return match append_to_journal(path, &record) {
+                    Ok(()) => Ok((-1, -1)),
+                    Err(e) => Err((e, SpilledRecord::from_record(&record))),
+                };
+            }
+        }
+        #[cfg(feature = "chaos")]
+        if let Err(e) = self.inject(FaultTarget::Kafka).await {
+            return Err((e, SpilledRecord::from_record(&record)));
+        }
+        let (partition, offset) = self.client().send(record, queue_timeout).await
+            .map_err(|(err, message)| (anyhow!(err), SpilledRecord::from_message(&message)))?;
+        *self.last_delivery.lock().unwrap() = clock::instant();
+        Ok((partition, offset))
+    }
+    
+    /// Deliver an encoded record, then its JSON copy when the topic is mirrored
+    ///
+    /// The mirror is best effort: a failed copy is logged and counted but doesn't fail
+    /// the send.
+    ///
+    /// Both carry the headers of `meta` and its event time as the record timestamp.
+    async fn deliver_encoded(&self, topic: &str, key: &str, payload: &EncodedPayload<'_>, meta: RecordMeta<'_>, queue_timeout: Duration) -> Result<(i32, i64)> {
+        let produced_at = clock::now_millis();
+        let schema_id = split_confluent_payload(payload).ok().map(|(schema_id, _)| schema_id);
+        let headers = meta.headers(schema_id, produced_at);
+        let record = |topic, payload| {
+            let record = FutureRecord::to(topic).payload(payload).key(key).headers(to_owned_headers(&headers));
+            match meta.timestamp_millis() {
+                Some(timestamp) => record.timestamp(timestamp),
+                None => record,
+            }
+        };
+        let delivered = self.deliver(record(topic, &payload[..]), queue_timeout).await?;
+        if let Some((json_topic, json)) = payload.mirror() {
+            if let Err(e) = self.deliver(record(json_topic, json), queue_timeout).await {
+                warn!("Failed to mirror record to {}: {}", json_topic, e);
+                metrics::global().incr(METRIC_JSON_MIRROR_FAILURES, 1);
+            }
+        }
+        Ok(delivered)
+    }
+    
+    /// Number of messages queued inside the producer awaiting delivery
+    pub fn queue_depth(&self) -> i32 {
+        self.client().in_flight_count()
+    }
+    
+    /// Number of sends still being encoded or awaiting delivery
+    pub fn pending_sends(&self) -> usize {
+        self.pending_sends.load(Ordering::SeqCst)
+    }
+    
+    /// Publish queue depth gauges
+    fn record_queue_metrics(&self) {
+        let metrics = metrics::global();
+        metrics.set_gauge(METRIC_QUEUE_DEPTH, self.queue_depth() as f64);
+        metrics.set_gauge(METRIC_PENDING_SENDS, self.pending_sends() as f64);
+    }
+    
+    /// Drain outstanding messages before shutdown
+    ///
+    /// Waits for sends that are still encoding to hand their records to the producer,
+    /// then blocks until librdkafka has delivered everything or the timeout expires.
+    pub async fn flush(&self, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        while self.pending_sends() > 0 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        self.retry_spilled().await;
+        
+        let producer = self.client();
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let result = tokio::task::spawn_blocking(move || producer.flush(remaining)).await?;
+        self.record_queue_metrics();
+        
+        if let Some(spill) = &self.spill {
+            let dead_lettered = spill.dead_letter_all();
+            if dead_lettered > 0 {
+                error!("Kafka still failing at shutdown, dead-lettered {} spilled records", dead_lettered);
+            }
+        }
+        
+        match result {
+            Ok(()) => {
+                info!("Kafka producer flushed");
+                Ok(())
+            }
+            Err(e) => Err(anyhow!("Kafka flush incomplete, {} messages still queued: {}", self.queue_depth(), e)),
+        }
+    }
+    
+    /// Latest cached schema of a topic and its id, if one has been loaded
+    fn latest_cached_schema(&self, topic: &str) -> Option<(i32, Arc<Schema>)> {
+        let prefix = format!("{}:", topic);
+        let cache = self.cached_schemas.read().unwrap();
+        cache.iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .max_by_key(|(_, info)| info.id)
+            .map(|(_, info)| (info.id, info.schema.clone()))
+    }
+    
+    /// Cache a schema registered for `topic` under `schema_id`, e.g. one fetched elsewhere
+    pub fn cache_schema(&self, topic: &str, schema_id: i32, schema: Schema) {
+        self.cached_schemas.write().unwrap().insert(format!("{}:{}", topic, schema_id), SchemaInfo {
+            id: schema_id,
+            schema: Arc::new(schema),
+        });
+    }
+    
+    /// Check a record against its topic's schema so mismatches name the offending field
+    /// instead of surfacing as an opaque encoder error
+    ///
+    /// Records of topics without a cached schema are left to the encoder.
+    pub fn validate(&self, record_name: &str, value: &[(String, apache_avro::types::Value)], topic: &str) -> Result<()> {
+        let Some((_, schema)) = self.latest_cached_schema(topic) else {
+            debug!("No cached schema for {}, skipping validation of {}", topic, record_name);
+            return Ok(());
+        };
+        Self::validate_against(record_name, &schema, value, topic)
+    }
+    
+    fn validate_against(record_name: &str, schema: &Schema, value: &[(String, apache_avro::types::Value)], topic: &str) -> Result<()> {
+        validate_record(schema, value).map_err(|e| {
+            metrics::global().incr(METRIC_VALIDATION_FAILURES, 1);
+            error!("Invalid {} record for {}: {}", record_name, topic, e);
+            anyhow!("Invalid {} record: {}", record_name, e)
+        })
+    }
+    
+    /// Next event id of `topic`
+    ///
+    /// Ids of a topic increase by one with every record this producer publishes to it, so
+    /// consumers can detect gaps and duplicates without relying on Kafka offsets.
+    pub fn next_event_id(&self, topic: &str) -> i64 {
+        let mut event_ids = self.event_ids.lock().unwrap();
+        let last = event_ids.entry(topic.to_string()).or_insert(self.event_id_base);
+        *last += 1;
+        *last
+    }
+    
+    /// Tag a record with its experiment variant, when there is one and the topic's schema
+    /// has the field
+    ///
+    /// Like the event id, the field is left out for schemas registered before it.
+    fn tag_variant(&self, value: &mut Vec<(String, apache_avro::types::Value)>, variant_id: Option<&str>, topic: &str) {
+        let Some(variant_id) = variant_id else {
+            return;
+        };
+        let has_variant = self.latest_cached_schema(topic)
+            .is_some_and(|(_, schema)| matches!(&*schema, Schema::Record(record) if record.lookup.contains_key("variant_id")));
+        if has_variant {
+            value.push(("variant_id".to_string(), apache_avro::types::Value::Union(1, Box::new(apache_avro::types::Value::String(variant_id.to_string())))));
+        }
+    }
+    
+    /// Stamp a record with the next event id of its topic, when the topic's schema has one
+    ///
+    /// Topics whose registered schema predates `event_id` are left as they are, since the
+    /// encoder would reject the unknown field.
+    fn stamp_event_id(&self, value: &mut Vec<(String, apache_avro::types::Value)>, schema: &Schema, topic: &str) {
+        let has_event_id = matches!(schema, Schema::Record(record) if record.lookup.contains_key("event_id"));
+        if has_event_id {
+            let event_id = self.next_event_id(topic);
+            value.push(("event_id".to_string(), apache_avro::types::Value::Union(1, Box::new(apache_avro::types::Value::Long(event_id)))));
+        }
+    }
+    
+    /// Encode a record in the Confluent wire format
+    ///
+    /// Topics with a cached schema are encoded locally by the topic's encoder into a pooled
+    /// buffer; others go through the registry encoder.
+    pub async fn encode_confluent_format<'a>(&'a self, record_name: &str, mut value: Vec<(String, apache_avro::types::Value)>, topic: &str) -> Result<EncodedPayload<'a>> {
+        // One schema lookup serves validation, the event id and encoding
+        let cached = self.latest_cached_schema(topic);
+        match &cached {
+            Some((_, schema)) => {
+                Self::validate_against(record_name, schema, &value, topic)?;
+                self.stamp_event_id(&mut value, schema, topic);
+            }
+            None => debug!("No cached schema for {}, skipping validation of {}", topic, record_name),
+        }
+        
+        // The JSON copy carries the same fields, event id included
+        let mirror = self.json_mirror(topic).and_then(|json_topic| match json_mirror::record_to_json(&value) {
+            Ok(json) => Some((json_topic, json)),
+            Err(e) => {
+                warn!("Not mirroring {} to {}: {}", record_name, json_topic, e);
+                metrics::global().incr(METRIC_JSON_MIRROR_FAILURES, 1);
+                None
+            }
+        });
+        let with_mirror = |payload: EncodedPayload<'a>| match &mirror {
+            Some((json_topic, json)) => payload.with_mirror(json_topic, json.clone()),
+            None => payload,
+        };
+        
+        #[cfg(feature = "chaos")]
+        self.inject(FaultTarget::Registry).await?;
+        
+        if let Some((schema_id, schema)) = &cached {
+            if let Some(payload) = self.encode_cached(topic, *schema_id, schema, &mut value)? {
+                return Ok(with_mirror(payload));
+            }
+        }
+        
+        // Subjects configured explicitly are used verbatim, others follow the topic name
+        let configured = self.topics.values().find(|spec| spec.topic == topic).and_then(|spec| spec.subject.clone());
+        let subject_strategy = match configured {
+            Some(subject) => SubjectNameStrategy::RecordNameStrategy(subject),
+            None => SubjectNameStrategy::TopicNameStrategy(topic.to_string(), false),
+        };
         
         // Convert Vec<(String, Value)> to Vec<(&str, Value)> for the encoder
         let data: Vec<(&str, apache_avro::types::Value)> = value
@@ -102,11 +612,14 @@ impl KafkaProducer {
         let sr_settings = (*self.sr_settings).clone();
         let encoder = AvroEncoder::new(sr_settings);
         
-        // Encode with the Confluent format
-        match encoder.encode(data, subject_strategy).await {
+        // Encode with the Confluent format; the encoder looks the schema up in the registry
+        let encoded = self.registry.call("encode", || async {
+            encoder.encode(data.clone(), subject_strategy.clone()).await.map_err(encoder_failure)
+        }).await;
+        match encoded {
             Ok(payload) => {
                 debug!("Successfully encoded {} with Confluent format, size: {} bytes", record_name, payload.len());
-                Ok(payload)
+                Ok(with_mirror(EncodedPayload::new(payload, &self.buffers)))
             },
             Err(e) => {
                 error!("Failed to encode {} with Confluent format: {}", record_name, e);
@@ -115,6 +628,25 @@ impl KafkaProducer {
         }
     }
     
+    /// Encode with the topic's cached encoder, rebuilt when the schema version changes;
+    /// None when the record needs the registry encoder
+    fn encode_cached(&self, topic: &str, schema_id: i32, schema: &Schema, value: &mut Vec<(String, apache_avro::types::Value)>) -> Result<Option<EncodedPayload<'_>>> {
+        let mut encoders = self.encoders.lock().unwrap();
+        if encoders.get(topic).map(|encoder| encoder.schema_id()) != Some(schema_id) {
+            encoders.insert(topic.to_string(), TopicEncoder::new(schema_id, schema)?);
+        }
+        let encoder = encoders.get_mut(topic).expect("encoder inserted above");
+        if !encoder.can_encode(value) {
+            return Ok(None);
+        }
+        let encoded = encoder.encode(std::mem::take(value), &self.buffers);
+        if encoded.is_err() {
+            // A failed write can leave the encoder's scratch buffer dirty
+            encoders.remove(topic);
+        }
+        encoded.map(Some)
+    }
+    
     /// Preload schema for a given topic type
     /// First tries to get the schema ID from registry, and if not found, registers it
     async fn preload_schema(&self, topic_type: &str) -> Result<(String, i32)> {
@@ -125,7 +657,13 @@ impl KafkaProducer {
         match self.get_schema_id(&topic).await {
             Ok(schema_id) => {
                 info!("Found existing schema for {} with ID: {}", topic_type, schema_id);
-                Ok((topic, schema_id))
+                match self.upgrade_schema(topic_type, &topic, schema_id).await {
+                    Ok(upgraded_id) => Ok((topic, upgraded_id)),
+                    Err(e) => {
+                        warn!("Keeping registered schema {} for {}: {}", schema_id, topic_type, e);
+                        Ok((topic, schema_id))
+                    }
+                }
             },
             Err(_) => {
                 // Schema doesn't exist, load from file and register
@@ -135,16 +673,93 @@ impl KafkaProducer {
         }
     }
     
+    /// Register the local schema as a new version when it only adds optional fields to
+    /// the registered one; returns the schema id to publish with
+    async fn upgrade_schema(&self, topic_type: &str, topic: &str, registered_id: i32) -> Result<i32> {
+        let registered = self.fetch_schema_str(registered_id).await?;
+        let local = self.schema_helper.get_schema_content(self.schema_name(topic_type))?;
+        
+        let added = match compare_schemas(&registered, &local)? {
+            SchemaChange::Unchanged => return Ok(registered_id),
+            SchemaChange::AddedOptionalFields(added) => added,
+            SchemaChange::Incompatible(reason) => {
+                warn!("Local schema for {} can't be upgraded automatically ({}), keeping registered id {}",
+                    topic_type, reason, registered_id);
+                return Ok(registered_id);
+            }
+        };
+        
+        if !self.check_compatibility(topic, &local).await? {
+            warn!("Registry rejects the local schema for {} as incompatible, keeping registered id {}",
+                topic_type, registered_id);
+            return Ok(registered_id);
+        }
+        
+        let schema_id = self.register_schema(topic, &local).await?;
+        info!("Schema evolution on {}: id {} -> {}, added optional fields {:?}",
+            topic, registered_id, schema_id, added);
+        metrics::global().incr(METRIC_SCHEMA_UPGRADES, 1);
+        
+        // Publish with the new version only
+        let schema = Schema::parse_str(&local)?;
+        {
+            let mut cache = self.cached_schemas.write().unwrap();
+            cache.retain(|key, _| !key.starts_with(&format!("{}:", topic)));
+            cache.insert(format!("{}:{}", topic, schema_id), SchemaInfo {
+                id: schema_id,
+                schema: Arc::new(schema),
+            });
+        }
+        Ok(schema_id)
+    }
+    
+    /// Ask the registry whether a schema is compatible with the subject's latest version
+    pub async fn check_compatibility(&self, topic: &str, schema_content: &str) -> Result<bool> {
+        #[cfg(feature = "chaos")]
+        self.inject(FaultTarget::Registry).await?;
+        
+        let subject = self.subject(topic);
+        let url = format!("{}/compatibility/subjects/{}/versions/latest", self.schema_registry_url, subject);
+        
+        let request = serde_json::json!({ "schema": schema_content });
+        let response = self.registry.send("compatibility check", |client| client.post(&url).json(&request))
+            .await
+            .context("Failed to send compatibility check")?;
+        
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(anyhow!("Compatibility check failed with status {}: {}", status, response.text().await?));
+        }
+        let result = response.json::<serde_json::Value>().await?;
+        Ok(result["is_compatible"].as_bool().unwrap_or(false))
+    }
+    
     /// Gets the topic name for a given topic type
     pub fn get_topic(&self, topic_type: &str) -> String {
         self.topics.get(topic_type)
+            .map(|spec| &spec.topic)
             .cloned()
             .unwrap_or_else(|| format!("cryptics.thalex.{}.avro", topic_type))
     }
-    
+
+    /// Schema directory name for a topic type
+    pub fn schema_name<'a>(&'a self, topic_type: &'a str) -> &'a str {
+        self.topics.get(topic_type).map_or(topic_type, |spec| spec.schema_name(topic_type))
+    }
+
+    /// Registry subject holding the value schemas of a topic
+    pub fn subject(&self, topic: &str) -> String {
+        self.topics.values()
+            .find(|spec| spec.topic == topic)
+            .map_or_else(|| format!("{}-value", topic), TopicType::subject)
+    }
+
     /// Register a schema with the schema registry
     pub async fn register_schema(&self, topic: &str, schema_content: &str) -> Result<i32> {
-        let subject = format!("{}-value", topic);
+        #[cfg(feature = "chaos")]
+        self.inject(FaultTarget::Registry).await?;
+        
+        let subject = self.subject(topic);
         let register_url = format!("{}/subjects/{}/versions", self.schema_registry_url, subject);
         
         info!("Registering schema for topic: {}", topic);
@@ -158,10 +773,7 @@ impl KafkaProducer {
             "schema": schema_content
         });
         
-        let client = reqwest::Client::new();
-        let response = client.post(&register_url)
-            .json(&schema_request)
-            .send()
+        let response = self.registry.send("schema registration", |client| client.post(&register_url).json(&schema_request))
             .await
             .context("Failed to send schema registration request")?;
         
@@ -178,7 +790,7 @@ impl KafkaProducer {
                 
                 // Try to get the schema ID from the registry
                 let get_url = format!("{}/subjects/{}/versions/latest", self.schema_registry_url, subject);
-                let get_response = client.get(&get_url).send().await?;
+                let get_response = self.registry.send("schema lookup", |client| client.get(&get_url)).await?;
                 
                 if get_response.status().is_success() {
                     let schema_info = get_response.json::<serde_json::Value>().await?;
@@ -196,12 +808,10 @@ impl KafkaProducer {
         }
     }
     
-    /// Fetch schema from registry and cache it
-    async fn fetch_and_cache_schema(&self, schema_id: i32) -> Result<Schema> {
-        // Get the schema from the registry
+    /// Fetch the text of a schema from the registry
+    async fn fetch_schema_str(&self, schema_id: i32) -> Result<String> {
         let schema_url = format!("{}/schemas/ids/{}", self.schema_registry_url, schema_id);
-        let client = reqwest::Client::new();
-        let response = client.get(&schema_url).send().await
+        let response = self.registry.send("schema fetch", |client| client.get(&schema_url)).await
             .context("Failed to fetch schema from registry")?;
         
         if !response.status().is_success() {
@@ -209,23 +819,31 @@ impl KafkaProducer {
         }
         
         let schema_response = response.json::<serde_json::Value>().await?;
-        let schema_str = schema_response["schema"].as_str().unwrap();
+        schema_response["schema"].as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Registry response for schema {} has no schema", schema_id))
+    }
+    
+    /// Fetch schema from registry and cache it
+    async fn fetch_and_cache_schema(&self, schema_id: i32) -> Result<Schema> {
+        let schema_str = self.fetch_schema_str(schema_id).await?;
         
         // Parse the schema
-        let schema = Schema::parse_str(schema_str)
+        let schema = Schema::parse_str(&schema_str)
             .context("Failed to parse schema from registry")?;
         
         Ok(schema)
     }
     
     /// Get schema for a topic (from cache or registry)
+    #[allow(dead_code)]
     async fn get_schema_for_topic(&self, topic: &str, schema_id: i32) -> Result<Schema> {
         // Check cache first
         let cache_key = format!("{}:{}", topic, schema_id);
         {
             let cache = self.cached_schemas.read().unwrap();
             if let Some(schema_info) = cache.get(&cache_key) {
-                return Ok(schema_info.schema.clone());
+                return Ok(schema_info.schema.as_ref().clone());
             }
         }
         
@@ -237,7 +855,7 @@ impl KafkaProducer {
             let mut cache = self.cached_schemas.write().unwrap();
             cache.insert(cache_key, SchemaInfo {
                 id: schema_id,
-                schema: schema.clone(),
+                schema: Arc::new(schema.clone()),
             });
         }
         
@@ -269,7 +887,7 @@ impl KafkaProducer {
         let topic = self.get_topic(topic_type);
         
         // Load schema from file
-        let schema_content = self.schema_helper.get_schema_content(topic_type)?;
+        let schema_content = self.schema_helper.get_schema_content(self.schema_name(topic_type))?;
         debug!("Loaded schema for {}: {}", topic_type, schema_content);
         
         // Register schema with the registry
@@ -282,7 +900,7 @@ impl KafkaProducer {
             let mut cache = self.cached_schemas.write().unwrap();
             cache.insert(cache_key, SchemaInfo {
                 id: schema_id,
-                schema,
+                schema: Arc::new(schema),
             });
         }
         
@@ -291,11 +909,13 @@ impl KafkaProducer {
     
     /// Get schema ID for an existing topic
     pub async fn get_schema_id(&self, topic: &str) -> Result<i32> {
-        let subject = format!("{}-value", topic);
+        #[cfg(feature = "chaos")]
+        self.inject(FaultTarget::Registry).await?;
+        
+        let subject = self.subject(topic);
         let url = format!("{}/subjects/{}/versions/latest", self.schema_registry_url, subject);
         
-        let client = reqwest::Client::new();
-        let response = client.get(&url).send().await?;
+        let response = self.registry.send("schema lookup", |client| client.get(&url)).await?;
         
         if response.status().is_success() {
             let schema_info = response.json::<serde_json::Value>().await?;
@@ -308,7 +928,7 @@ impl KafkaProducer {
                 let mut cache = self.cached_schemas.write().unwrap();
                 cache.insert(cache_key, SchemaInfo {
                     id: schema_id,
-                    schema,
+                    schema: Arc::new(schema),
                 });
             }
             
@@ -319,9 +939,10 @@ impl KafkaProducer {
     }
     
     /// Serialize an Ack to Avro bytes with schema ID
-    async fn serialize_ack_to_avro(&self, ack: &Ack, topic: &str, _schema_id: i32) -> Result<Vec<u8>> {
+    async fn serialize_ack_to_avro(&self, ack: &Order, topic: &str, _schema_id: i32) -> Result<EncodedPayload<'_>> {
         // Convert Ack to Avro field vector using our AvroConverter
-        let avro_fields = AvroConverter::ack_to_avro_value(ack);
+        let mut avro_fields = AvroConverter::ack_to_avro_value(ack);
+        self.tag_variant(&mut avro_fields, ack.variant_id.as_deref(), topic);
         debug!("Successfully converted Ack to Avro fields");
         
         // Encode using the Confluent format helper
@@ -329,7 +950,8 @@ impl KafkaProducer {
     }
 
     /// Publish an Ack to Kafka using Apache Avro serialization
-    pub async fn publish_ack(&self, ack: &Ack, topic: &str, schema_id: i32) -> Result<()> {
+    pub async fn publish_ack(&self, ack: &Order, topic: &str, schema_id: i32) -> Result<()> {
+        let _pending = PendingSend::new(self);
         // Serialize the Ack to Avro bytes
         let kafka_payload = match self.serialize_ack_to_avro(ack, topic, schema_id).await {
             Ok(payload) => payload,
@@ -341,14 +963,7 @@ impl KafkaProducer {
         };
         
         // Send to Kafka
-        let delivery_result = self.producer
-            .send(
-                FutureRecord::to(topic)
-                    .payload(&kafka_payload)
-                    .key(&format!("ack-{}", Uuid::new_v4())),
-                Duration::from_secs(5),
-            )
-            .await;
+        let delivery_result = self.deliver_encoded(topic, &format!("ack-{}", Uuid::new_v4()), &kafka_payload, RecordMeta::new("ack").instrument(Some(&ack.instrument_name)).at(ack.reported_at()), Duration::from_secs(5)).await;
         
         match delivery_result {
             Ok((partition, offset)) => {
@@ -356,7 +971,7 @@ impl KafkaProducer {
                       topic, partition, offset);
                 Ok(())
             },
-            Err((err, _)) => {
+            Err(err) => {
                 error!("Failed to send Ack message: {}, Ack data: {:?}", err, ack);
                 Err(anyhow!("Failed to send Ack message: {}", err))
             }
@@ -365,23 +980,27 @@ impl KafkaProducer {
     
     /// Parse JSON data and publish as an Ack, and extract any trades if present
     pub async fn publish_order_notification(&self, order_data: &Value) -> Result<()> {
-        // Parse the order data into our Ack format and publish it
-        let ack = ThaleParser::parse_ack_json(order_data)?;
-        
+        let order = ThaleParser::parse_order_json(order_data)?;
+        self.publish_order(&order, order_data).await
+    }
+    
+    /// Publish an already parsed order as an Ack, and any trades in its notification
+    pub async fn publish_order(&self, order: &Order, order_data: &Value) -> Result<()> {
         // Get topic and schema ID from cache
         let topic_type = "ack";
         let (topic, schema_id) = self.get_cached_schema(topic_type).await?;
         
         // Publish the Ack
-        self.publish_ack(&ack, &topic, schema_id).await?;
+        self.publish_ack(order, &topic, schema_id).await?;
         
         // Check for and publish any trades in the order data
-        if let Ok(trades) = ThaleParser::extract_trades_from_order(order_data) {
+        if let Ok(trades) = ThaleParser::extract_trades_from_order(order_data, self.strict_parsing) {
             if !trades.is_empty() {
                 info!("Found {} trades in order notification", trades.len());
                 
-                // Publish each trade
-                for trade in trades {
+                // Publish each trade, tagged like its order
+                for mut trade in trades {
+                    trade.variant_id = order.variant_id.clone();
                     self.send_trade(&trade).await?;
                 }
             }
@@ -392,6 +1011,7 @@ impl KafkaProducer {
     
     /// Send ticker data to Kafka
     pub async fn send_ticker(&self, ticker: &Ticker) -> Result<()> {
+        let _pending = PendingSend::new(self);
         let topic_type = "ticker";
         // Use cached schema
         let (topic, _) = self.get_cached_schema(topic_type).await?;
@@ -403,14 +1023,7 @@ impl KafkaProducer {
         let kafka_payload = self.encode_confluent_format("ticker", avro_fields, &topic).await?;
         
         // Send to Kafka
-        let delivery_result = self.producer
-            .send(
-                FutureRecord::to(&topic)
-                    .payload(&kafka_payload)
-                    .key(&format!("ticker-{}-{}", ticker.instrument_name, Uuid::new_v4())),
-                Duration::from_secs(5),
-            )
-            .await;
+        let delivery_result = self.deliver_encoded(&topic, &format!("ticker-{}-{}", ticker.instrument_name, Uuid::new_v4()), &kafka_payload, RecordMeta::new(topic_type).instrument(Some(&ticker.instrument_name)).at(ticker.mark_timestamp), Duration::from_secs(5)).await;
         
         match delivery_result {
             Ok((partition, offset)) => {
@@ -418,33 +1031,84 @@ impl KafkaProducer {
                       topic, partition, offset);
                 Ok(())
             },
-            Err((err, _)) => {
+            Err(err) => {
                 Err(anyhow!("Failed to send Ticker message: {}", err))
             }
         }
     }
     
+    /// Send the latest ticker of an instrument to the compacted ticker topic
+    pub async fn send_ticker_latest(&self, ticker: &Ticker) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "ticker_latest";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Same record as the full ticker stream
+        let avro_fields = AvroConverter::ticker_to_avro_value(ticker)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("ticker_latest", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by instrument alone so compaction keeps one record per instrument
+        let delivery_result = self.deliver_encoded(&topic, &ticker.instrument_name, &kafka_payload, RecordMeta::new(topic_type).instrument(Some(&ticker.instrument_name)).at(ticker.mark_timestamp), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent latest Ticker to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send latest Ticker message: {}", err))
+            }
+        }
+    }
+    
+    /// Send the changed fields of a ticker to the ticker delta topic
+    pub async fn send_ticker_delta(&self, delta: &TickerDelta) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "ticker_delta";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert delta to Avro field vector
+        let avro_fields = AvroConverter::ticker_delta_to_avro_value(delta)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("ticker_delta", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by instrument so deltas stay ordered after their snapshot
+        let delivery_result = self.deliver_encoded(&topic, &delta.instrument_name, &kafka_payload, RecordMeta::new(topic_type).instrument(Some(&delta.instrument_name)).at(delta.timestamp), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent TickerDelta to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send TickerDelta message: {}", err))
+            }
+        }
+    }
+    
     /// Send trade data to Kafka
     pub async fn send_trade(&self, trade: &Trade) -> Result<()> {
+        let _pending = PendingSend::new(self);
         let topic_type = "trade";
         // Use cached schema
         let (topic, _) = self.get_cached_schema(topic_type).await?;
         
         // Convert trade to Avro field vector
-        let avro_fields = AvroConverter::trade_to_avro_value(trade)?;
+        let mut avro_fields = AvroConverter::trade_to_avro_value(trade)?;
+        self.tag_variant(&mut avro_fields, trade.variant_id.as_deref(), &topic);
         
         // Encode using the Confluent format helper
         let kafka_payload = self.encode_confluent_format("trade", avro_fields, &topic).await?;
         
         // Send to Kafka
-        let delivery_result = self.producer
-            .send(
-                FutureRecord::to(&topic)
-                    .payload(&kafka_payload)
-                    .key(&format!("trade-{}", Uuid::new_v4())),
-                Duration::from_secs(5),
-            )
-            .await;
+        let delivery_result = self.deliver_encoded(&topic, &format!("trade-{}", Uuid::new_v4()), &kafka_payload, RecordMeta::new(topic_type).instrument(Some(&trade.instrument_name)).at(trade.time), Duration::from_secs(5)).await;
         
         match delivery_result {
             Ok((partition, offset)) => {
@@ -452,29 +1116,335 @@ impl KafkaProducer {
                       topic, partition, offset);
                 Ok(())
             },
-            Err((err, _)) => {
+            Err(err) => {
                 Err(anyhow!("Failed to send Trade message: {}", err))
             }
         }
     }
     
+    /// Send microstructure features to Kafka
+    pub async fn send_features(&self, features: &MarketFeatures) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "features";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert features to Avro field vector
+        let avro_fields = AvroConverter::features_to_avro_value(features)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("features", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by instrument so features stay ordered per instrument
+        let delivery_result = self.deliver_encoded(&topic, &features.instrument_name, &kafka_payload, RecordMeta::new(topic_type).instrument(Some(&features.instrument_name)).at(features.timestamp), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent Features to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send Features message: {}", err))
+            }
+        }
+    }
+    
+    /// Send daily quote uptime statistics to Kafka
+    pub async fn send_uptime(&self, uptime: &QuoteUptime) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "uptime";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert uptime to Avro field vector
+        let avro_fields = AvroConverter::uptime_to_avro_value(uptime)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("uptime", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by instrument
+        let delivery_result = self.deliver_encoded(&topic, &uptime.instrument_name, &kafka_payload, RecordMeta::new(topic_type).instrument(Some(&uptime.instrument_name)).at(uptime.period_end), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent Uptime to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send Uptime message: {}", err))
+            }
+        }
+    }
+    
+    /// Send a LifecycleEvent to Kafka
+    pub async fn send_lifecycle(&self, event: &LifecycleEvent) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "lifecycle";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert event to Avro field vector
+        let avro_fields = AvroConverter::lifecycle_to_avro_value(event)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("lifecycle", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by instance so one process's events stay ordered
+        let delivery_result = self.deliver_encoded(&topic, &event.instance_id, &kafka_payload, RecordMeta::new(topic_type).at(event.timestamp), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent LifecycleEvent to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send LifecycleEvent message: {}", err))
+            }
+        }
+    }
+    
+    /// Send a Heartbeat to Kafka
+    pub async fn send_heartbeat(&self, heartbeat: &Heartbeat) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "heartbeat";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert heartbeat to Avro field vector
+        let avro_fields = AvroConverter::heartbeat_to_avro_value(heartbeat)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("heartbeat", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by instance
+        let delivery_result = self.deliver_encoded(&topic, &heartbeat.instance_id, &kafka_payload, RecordMeta::new(topic_type).at(heartbeat.timestamp), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent Heartbeat to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send Heartbeat message: {}", err))
+            }
+        }
+    }
+    
+    /// Send the summary of a bot run to Kafka
+    pub async fn send_session_summary(&self, summary: &SessionSummary) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "session_summary";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert summary to Avro field vector
+        let avro_fields = AvroConverter::session_summary_to_avro_value(summary)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("session_summary", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by instance
+        let delivery_result = self.deliver_encoded(&topic, &summary.instance_id, &kafka_payload, RecordMeta::new(topic_type).at(summary.ended_at), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent SessionSummary to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send SessionSummary message: {}", err))
+            }
+        }
+    }
+    
+    /// Send a funding and basis sample to Kafka
+    pub async fn send_funding_basis(&self, sample: &FundingBasis) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "funding_basis";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert sample to Avro field vector
+        let avro_fields = AvroConverter::funding_basis_to_avro_value(sample)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("funding_basis", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by instrument
+        let delivery_result = self.deliver_encoded(&topic, &sample.instrument_name, &kafka_payload, RecordMeta::new(topic_type).instrument(Some(&sample.instrument_name)).at(sample.timestamp), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent FundingBasis to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send FundingBasis message: {}", err))
+            }
+        }
+    }
+    
+    /// Send an account event to Kafka
+    pub async fn send_account_event(&self, event: &AccountEvent) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "account_event";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert event to Avro field vector
+        let avro_fields = AvroConverter::account_event_to_avro_value(event)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("account_event", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by event type
+        let delivery_result = self.deliver_encoded(&topic, event.event_type.as_str(), &kafka_payload, RecordMeta::new(topic_type).instrument(event.instrument_name.as_deref()).at(event.timestamp), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent AccountEvent to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send AccountEvent message: {}", err))
+            }
+        }
+    }
+    
+    /// Send a trade bust or correction to Kafka
+    pub async fn send_trade_correction(&self, correction: &TradeCorrection) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "trade_correction";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert correction to Avro field vector
+        let avro_fields = AvroConverter::trade_correction_to_avro_value(correction)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("trade_correction", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by trade ID
+        let delivery_result = self.deliver_encoded(&topic, &correction.trade_id, &kafka_payload, RecordMeta::new(topic_type).instrument(correction.instrument_name.as_deref()).at(correction.timestamp), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent TradeCorrection to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send TradeCorrection message: {}", err))
+            }
+        }
+    }
+    
+    /// Send an account summary to Kafka
+    pub async fn send_account_summary(&self, summary: &AccountSummary) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "account_summary";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert summary to Avro field vector
+        let avro_fields = AvroConverter::account_summary_to_avro_value(summary)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("account_summary", avro_fields, &topic).await?;
+        
+        // Send to Kafka; there is one account, so one key
+        let delivery_result = self.deliver_encoded(&topic, "account", &kafka_payload, RecordMeta::new(topic_type).at(summary.timestamp), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent AccountSummary to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send AccountSummary message: {}", err))
+            }
+        }
+    }
+    
+    /// Send a book snapshot level or delta to Kafka
+    pub async fn send_book_level(&self, update: &BookLevelUpdate) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let topic_type = "book";
+        // Use cached schema
+        let (topic, _) = self.get_cached_schema(topic_type).await?;
+        
+        // Convert update to Avro field vector
+        let avro_fields = AvroConverter::book_level_to_avro_value(update)?;
+        
+        // Encode using the Confluent format helper
+        let kafka_payload = self.encode_confluent_format("book", avro_fields, &topic).await?;
+        
+        // Send to Kafka, keyed by instrument so each book's records stay ordered
+        let delivery_result = self.deliver_encoded(&topic, &update.instrument_name, &kafka_payload, RecordMeta::new(topic_type).instrument(Some(&update.instrument_name)).at(update.exchange_time.unwrap_or(update.timestamp)), Duration::from_secs(5)).await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent BookLevelUpdate to topic: {}, partition: {}, offset: {}", 
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send BookLevelUpdate message: {}", err))
+            }
+        }
+    }
+    
     /// Send an Ack to Kafka
-    pub async fn send_ack(&self, ack: &Ack) -> Result<()> {
+    pub async fn send_ack(&self, ack: &Order) -> Result<()> {
         let topic_type = "ack";
         // Use cached schema
         let (topic, schema_id) = self.get_cached_schema(topic_type).await?;
         self.publish_ack(ack, &topic, schema_id).await
     }
     
+    /// Send a raw JSON document to `topic`, bypassing the schema registry
+    pub async fn send_json(&self, topic: &str, key: &str, value: &Value) -> Result<()> {
+        let _pending = PendingSend::new(self);
+        let payload = serde_json::to_vec(value)?;
+        
+        let delivery_result = self
+            .deliver(
+                FutureRecord::to(topic)
+                    .payload(&payload)
+                    .key(key),
+                Duration::from_secs(5),
+            )
+            .await;
+        
+        match delivery_result {
+            Ok((partition, offset)) => {
+                debug!("Successfully sent JSON to topic: {}, partition: {}, offset: {}",
+                      topic, partition, offset);
+                Ok(())
+            },
+            Err(err) => {
+                Err(anyhow!("Failed to send JSON message: {}", err))
+            }
+        }
+    }
+    
     /// Parse JSON data and publish based on topic type
     pub async fn publish_json_data(&self, data: &Value, topic_type: &str) -> Result<()> {
         match topic_type {
             "ack" => {
-                let ack = ThaleParser::parse_ack_json(data)?;
+                let ack = ThaleParser::parse_order_json(data)?;
                 self.send_ack(&ack).await
             },
             "trade" => {
-                let trade = ThaleParser::parse_trade_json(data)?;
+                let trade = ThaleParser::parse_trade_json(data, self.strict_parsing)?;
                 self.send_trade(&trade).await
             },
             "ticker" => {
@@ -492,4 +1462,30 @@ impl KafkaProducer {
             _ => Err(anyhow!("Unsupported topic type: {}", topic_type))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Append a record to the journal as one JSON line, with the payload hex encoded
+pub(crate) fn append_to_journal<K, P>(path: &Path, record: &FutureRecord<'_, K, P>) -> Result<()>
+where
+    K: ToBytes + ?Sized,
+    P: ToBytes + ?Sized,
+{
+    let hex = |bytes: &[u8]| bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+    let line = serde_json::json!({
+        "topic": record.topic,
+        "key": record.key.map(|key| String::from_utf8_lossy(key.to_bytes()).into_owned()),
+        "payload": record.payload.map(|payload| hex(payload.to_bytes())),
+        "timestamp": record.timestamp,
+        "headers": record.headers.as_ref().map(|headers| headers.iter()
+            .map(|header| (header.key.to_string(), header.value.map(|value| String::from_utf8_lossy(value).into_owned())))
+            .collect::<HashMap<_, _>>()),
+        "journaled_at": clock::now_secs(),
+    });
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)
+        .with_context(|| format!("Failed to open journal {}", path.display()))?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use rand::Rng;
+use reqwest::{Client, RequestBuilder, Response};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config_loader::RegistryRetryConfig;
+use crate::domain::clock;
+use crate::infrastructure::metrics;
+
+/// Registry calls repeated after a transient failure
+pub const METRIC_REGISTRY_RETRIES: &str = "registry.retries";
+
+/// Registry calls refused without trying because the circuit was open
+pub const METRIC_REGISTRY_SHORT_CIRCUITED: &str = "registry.short_circuited";
+
+/// 1 while the registry circuit is open, 0 otherwise
+pub const METRIC_REGISTRY_CIRCUIT_OPEN: &str = "registry.circuit_open";
+
+/// A failed attempt, and whether it's worth repeating
+#[derive(Debug)]
+pub enum Failure {
+    /// Timeouts, refused connections, 5xx and 429 responses
+    Transient(anyhow::Error),
+    Permanent(anyhow::Error),
+}
+
+/// Stops calling the registry for a while after consecutive failures
+///
+/// Once the cooldown has passed one trial call is let through; it closes the circuit
+/// on success and reopens it on failure.
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            failures: 0,
+            open_until: None,
+        }
+    }
+
+    /// Ok when a call may go ahead, otherwise the time left until the next trial
+    pub fn check(&self, now: Instant) -> Result<(), Duration> {
+        match self.open_until {
+            Some(until) if now < until => Err(until - now),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_open(&self, now: Instant) -> bool {
+        self.check(now).is_err()
+    }
+
+    pub fn on_success(&mut self) {
+        if self.open_until.take().is_some() {
+            info!("Schema registry reachable again, closing circuit");
+        }
+        self.failures = 0;
+        metrics::global().set_gauge(METRIC_REGISTRY_CIRCUIT_OPEN, 0.0);
+    }
+
+    pub fn on_failure(&mut self, now: Instant) {
+        self.failures += 1;
+        if self.failures >= self.threshold {
+            if !matches!(self.open_until, Some(until) if until > now) {
+                error!("Schema registry failed {} times in a row, pausing calls for {:?}", self.failures, self.cooldown);
+            }
+            self.open_until = Some(now + self.cooldown);
+            metrics::global().set_gauge(METRIC_REGISTRY_CIRCUIT_OPEN, 1.0);
+        }
+    }
+}
+
+/// HTTP access to the schema registry with bounded retries, jittered backoff and a
+/// circuit breaker shared by every call
+pub struct RegistryClient {
+    http: Client,
+    config: RegistryRetryConfig,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+impl RegistryClient {
+    pub fn new(config: RegistryRetryConfig) -> Self {
+        let breaker = CircuitBreaker::new(config.failure_threshold, Duration::from_millis(config.open_cooldown_ms));
+        let http = Client::builder()
+            .timeout(Duration::from_millis(config.request_timeout_ms))
+            .build()
+            .expect("Failed to build the schema registry HTTP client");
+        Self {
+            http,
+            config,
+            breaker: Mutex::new(breaker),
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based): exponential, capped, with jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let base = self.config.initial_backoff_ms as f64 * 2f64.powi(retry.saturating_sub(1) as i32);
+        let capped = base.min(self.config.max_backoff_ms as f64);
+        let jittered = capped * rand::thread_rng().gen_range(0.5..=1.0);
+        Duration::from_millis(jittered as u64)
+    }
+
+    /// Run `op` until it succeeds, fails permanently, or runs out of retries
+    pub async fn call<T, F, Fut>(&self, operation: &str, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Failure>>,
+    {
+        let mut retry = 0;
+        loop {
+            if let Err(remaining) = self.breaker.lock().unwrap().check(clock::instant()) {
+                metrics::global().incr(METRIC_REGISTRY_SHORT_CIRCUITED, 1);
+                return Err(anyhow!("Schema registry circuit open, {} not attempted (retry in {:?})", operation, remaining));
+            }
+
+            match op().await {
+                Ok(value) => {
+                    self.breaker.lock().unwrap().on_success();
+                    return Ok(value);
+                }
+                Err(Failure::Permanent(e)) => {
+                    // The registry answered, so it's up
+                    self.breaker.lock().unwrap().on_success();
+                    return Err(e);
+                }
+                Err(Failure::Transient(e)) => {
+                    self.breaker.lock().unwrap().on_failure(clock::instant());
+                    if retry >= self.config.max_retries {
+                        return Err(e.context(format!("{} failed after {} retries", operation, retry)));
+                    }
+                    retry += 1;
+                    let delay = self.backoff(retry);
+                    warn!("{} failed ({}), retry {}/{} in {:?}", operation, e, retry, self.config.max_retries, delay);
+                    metrics::global().incr(METRIC_REGISTRY_RETRIES, 1);
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Send a request built by `request`, retrying connection errors, timeouts, 5xx and 429
+    ///
+    /// Other responses, including 4xx, are returned for the caller to interpret.
+    pub async fn send<F>(&self, operation: &str, request: F) -> Result<Response>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        self.call(operation, || async {
+            match request(&self.http).send().await {
+                Ok(response) if response.status().is_server_error() || response.status().as_u16() == 429 => {
+                    Err(Failure::Transient(anyhow!("registry returned {}", response.status())))
+                }
+                Ok(response) => Ok(response),
+                Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => {
+                    Err(Failure::Transient(anyhow!(e)))
+                }
+                Err(e) => Err(Failure::Permanent(anyhow!(e))),
+            }
+        }).await
+    }
+
+    /// Whether calls are currently being refused
+    pub fn is_open(&self) -> bool {
+        self.breaker.lock().unwrap().is_open(clock::instant())
+    }
+}
+
+impl Default for RegistryClient {
+    fn default() -> Self {
+        Self::new(RegistryRetryConfig::default())
+    }
+}
+
+/// `Failure` for an error of the registry client used by the Avro encoder
+pub fn encoder_failure(error: schema_registry_converter::error::SRCError) -> Failure {
+    if error.retriable {
+        Failure::Transient(anyhow!(error))
+    } else {
+        Failure::Permanent(anyhow!(error))
+    }
+}
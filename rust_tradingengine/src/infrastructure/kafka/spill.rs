@@ -0,0 +1,216 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use anyhow::Result;
+use log::{error, info, warn};
+use rdkafka::message::{Header, Headers, Message, OwnedHeaders, OwnedMessage, ToBytes};
+use rdkafka::producer::FutureRecord;
+
+use crate::config_loader::SpillConfig;
+use crate::infrastructure::metrics;
+
+use super::producer::append_to_journal;
+
+/// Counter of records spilled after a failed delivery
+pub const METRIC_SPILLED: &str = "kafka.spilled";
+
+/// Records waiting in the spill buffer
+pub const METRIC_SPILL_DEPTH: &str = "kafka.spill_depth";
+
+/// Counter of spilled records delivered on a retry
+pub const METRIC_SPILL_DELIVERED: &str = "kafka.spill_delivered";
+
+/// Counter of records written to the dead-letter file
+pub const METRIC_DEAD_LETTERED: &str = "kafka.dead_lettered";
+
+/// A record Kafka didn't take, owned so it can wait for the broker
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpilledRecord {
+    pub topic: String,
+    pub key: Option<Vec<u8>>,
+    pub payload: Option<Vec<u8>>,
+    /// Record timestamp (milliseconds since epoch)
+    pub timestamp: Option<i64>,
+    pub headers: Vec<(String, Option<Vec<u8>>)>,
+    /// Failed deliveries so far
+    pub attempts: u32,
+    /// Position in the buffer, so a retry removes the record it delivered
+    id: u64,
+}
+
+impl SpilledRecord {
+    /// Copy of a record that couldn't be handed to the client
+    pub fn from_record<K, P>(record: &FutureRecord<'_, K, P>) -> Self
+    where
+        K: ToBytes + ?Sized,
+        P: ToBytes + ?Sized,
+    {
+        Self {
+            topic: record.topic.to_string(),
+            key: record.key.map(|key| key.to_bytes().to_vec()),
+            payload: record.payload.map(|payload| payload.to_bytes().to_vec()),
+            timestamp: record.timestamp,
+            headers: record.headers.as_ref().map(owned_headers).unwrap_or_default(),
+            attempts: 1,
+            id: 0,
+        }
+    }
+
+    /// Record the client gave back after failing to deliver it
+    pub fn from_message(message: &OwnedMessage) -> Self {
+        Self {
+            topic: message.topic().to_string(),
+            key: message.key().map(<[u8]>::to_vec),
+            payload: message.payload().map(<[u8]>::to_vec),
+            timestamp: message.timestamp().to_millis(),
+            headers: message.headers().map(owned_headers).unwrap_or_default(),
+            attempts: 1,
+            id: 0,
+        }
+    }
+
+    /// Record to hand to the client again
+    pub fn to_record(&self) -> FutureRecord<'_, [u8], [u8]> {
+        let mut record = FutureRecord::to(&self.topic);
+        if let Some(key) = &self.key {
+            record = record.key(&key[..]);
+        }
+        if let Some(payload) = &self.payload {
+            record = record.payload(&payload[..]);
+        }
+        if let Some(timestamp) = self.timestamp {
+            record = record.timestamp(timestamp);
+        }
+        if !self.headers.is_empty() {
+            let headers = self.headers.iter().fold(OwnedHeaders::new_with_capacity(self.headers.len()), |owned, (key, value)| {
+                owned.insert(Header { key, value: value.as_deref() })
+            });
+            record = record.headers(headers);
+        }
+        record
+    }
+}
+
+fn owned_headers(headers: &OwnedHeaders) -> Vec<(String, Option<Vec<u8>>)> {
+    headers.iter().map(|header| (header.key.to_string(), header.value.map(<[u8]>::to_vec))).collect()
+}
+
+/// Records whose delivery failed, kept in order for a retry once the broker is back
+///
+/// Records are retried oldest first and a retry stops at the first failure, since the
+/// broker is most likely still down. A record that failed `max_attempts` times is
+/// dead-lettered and the next one tried; when more than `max_records` wait, the oldest
+/// is dead-lettered to make room. The dead-letter file uses the journal's format.
+pub struct SpillBuffer {
+    max_records: usize,
+    max_attempts: u32,
+    dead_letter_path: PathBuf,
+    records: Mutex<VecDeque<SpilledRecord>>,
+    next_id: AtomicU64,
+    /// Held by the retry in progress, so records leave the buffer in order
+    retrying: tokio::sync::Mutex<()>,
+}
+
+impl SpillBuffer {
+    pub fn new(config: &SpillConfig) -> Self {
+        Self {
+            max_records: config.max_records.max(1),
+            max_attempts: config.max_attempts.max(1),
+            dead_letter_path: PathBuf::from(&config.dead_letter_path),
+            records: Mutex::new(VecDeque::new()),
+            next_id: AtomicU64::new(0),
+            retrying: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Records waiting for a retry
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Keep a record for a retry, behind those already waiting
+    pub fn spill(&self, mut record: SpilledRecord) {
+        record.id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let evicted = {
+            let mut records = self.records.lock().unwrap();
+            records.push_back(record);
+            metrics::global().set_gauge(METRIC_SPILL_DEPTH, records.len() as f64);
+            (records.len() > self.max_records).then(|| records.pop_front()).flatten()
+        };
+        metrics::global().incr(METRIC_SPILLED, 1);
+        if let Some(evicted) = evicted {
+            warn!("Spill buffer full ({} records), dead-lettering the oldest", self.max_records);
+            self.dead_letter(&evicted);
+        }
+    }
+
+    /// Retry the spilled records oldest first with `deliver`, until one fails
+    ///
+    /// Returns how many were delivered.
+    pub async fn retry<F, Fut>(&self, deliver: F) -> usize
+    where
+        F: Fn(SpilledRecord) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let _retrying = self.retrying.lock().await;
+        let mut delivered = 0;
+        loop {
+            let Some(record) = self.records.lock().unwrap().front().cloned() else {
+                break;
+            };
+            let id = record.id;
+            let result = deliver(record).await;
+            let mut records = self.records.lock().unwrap();
+            // The record may have been evicted to the dead-letter file meanwhile
+            let Some(front) = records.front_mut().filter(|front| front.id == id) else {
+                continue;
+            };
+            match result {
+                Ok(()) => {
+                    records.pop_front();
+                    delivered += 1;
+                }
+                Err(e) => {
+                    front.attempts += 1;
+                    if front.attempts < self.max_attempts {
+                        break;
+                    }
+                    let record = records.pop_front().expect("front record");
+                    drop(records);
+                    error!("Giving up on a record for {} after {} attempts: {}", record.topic, record.attempts, e);
+                    self.dead_letter(&record);
+                }
+            }
+        }
+        metrics::global().set_gauge(METRIC_SPILL_DEPTH, self.len() as f64);
+        if delivered > 0 {
+            metrics::global().incr(METRIC_SPILL_DELIVERED, delivered as u64);
+            info!("Delivered {} spilled records, {} still waiting", delivered, self.len());
+        }
+        delivered
+    }
+
+    /// Dead-letter every waiting record, e.g. when the session ends; returns how many
+    pub fn dead_letter_all(&self) -> usize {
+        let records: Vec<SpilledRecord> = self.records.lock().unwrap().drain(..).collect();
+        metrics::global().set_gauge(METRIC_SPILL_DEPTH, 0.0);
+        for record in &records {
+            self.dead_letter(record);
+        }
+        records.len()
+    }
+
+    fn dead_letter(&self, record: &SpilledRecord) {
+        metrics::global().incr(METRIC_DEAD_LETTERED, 1);
+        if let Err(e) = append_to_journal(&self.dead_letter_path, &record.to_record()) {
+            error!("Failed to dead-letter a record for {}, dropping it: {:#}", record.topic, e);
+        }
+    }
+}
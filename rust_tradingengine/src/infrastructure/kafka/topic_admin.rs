@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info, warn};
+use rdkafka::admin::{AdminClient, AdminOptions, AlterConfig, ConfigSource, NewTopic, ResourceSpecifier, TopicReplication};
+use rdkafka::client::DefaultClientContext;
+use rdkafka::error::RDKafkaErrorCode;
+use rdkafka::ClientConfig;
+
+use crate::config_loader::{TopicAdminConfig, TopicType};
+use crate::infrastructure::metrics;
+
+/// Topic settings on the broker that differ from the configured ones
+pub const METRIC_TOPIC_CONFIG_DRIFT: &str = "kafka.topic_config_drift";
+
+/// A topic setting the broker holds differently from the configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDrift {
+    pub topic: String,
+    pub name: String,
+    pub expected: String,
+    /// Value on the broker; `None` when the broker didn't report the setting
+    pub actual: Option<String>,
+}
+
+/// Topic settings expected of `spec`; empty when it has no retention or compaction expectation
+pub fn expected_configs(spec: &TopicType) -> Vec<(&'static str, String)> {
+    if !spec.compacted && !spec.retention.is_set() {
+        return Vec::new();
+    }
+    let policy = if spec.compacted { "compact" } else { "delete" };
+    let mut configs = vec![("cleanup.policy", policy.to_string())];
+    if let Some(retention_ms) = spec.retention.retention_ms {
+        configs.push(("retention.ms", retention_ms.to_string()));
+    }
+    if let Some(retention_bytes) = spec.retention.retention_bytes {
+        configs.push(("retention.bytes", retention_bytes.to_string()));
+    }
+    configs
+}
+
+/// Expected settings of `topic` that `actual` holds differently
+pub fn config_drift(topic: &str, expected: &[(&'static str, String)], actual: &HashMap<String, String>) -> Vec<ConfigDrift> {
+    expected.iter()
+        .filter(|(name, value)| actual.get(*name) != Some(value))
+        .map(|(name, value)| ConfigDrift {
+            topic: topic.to_string(),
+            name: name.to_string(),
+            expected: value.clone(),
+            actual: actual.get(*name).cloned(),
+        })
+        .collect()
+}
+
+/// Creates the topics the bot has expectations of and keeps the broker's settings in line
+pub struct TopicAdmin {
+    admin: AdminClient<DefaultClientContext>,
+    config: TopicAdminConfig,
+}
+
+impl TopicAdmin {
+    pub fn new(bootstrap_servers: &str, config: TopicAdminConfig) -> Result<Self> {
+        let admin = ClientConfig::new()
+            .set("bootstrap.servers", bootstrap_servers)
+            .create()
+            .context("Failed to create Kafka admin client")?;
+        Ok(Self { admin, config })
+    }
+
+    /// Create missing topics with their expected settings and check the existing ones
+    ///
+    /// Topics without expectations are left to auto-creation. Returns the drift still on
+    /// the broker afterwards, which is also published as a gauge.
+    pub async fn reconcile(&self, topics: &HashMap<String, TopicType>) -> Result<Vec<ConfigDrift>> {
+        if !self.config.enabled {
+            return Ok(Vec::new());
+        }
+        let mut expected: Vec<(&str, Vec<(&'static str, String)>)> = topics.values()
+            .map(|spec| (spec.topic.as_str(), expected_configs(spec)))
+            .filter(|(_, configs)| !configs.is_empty())
+            .collect();
+        expected.sort_by_key(|(topic, _)| *topic);
+        expected.dedup_by_key(|(topic, _)| *topic);
+        if expected.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.create_topics(&expected).await?;
+        let mut drift = Vec::new();
+        for (topic, configs) in &expected {
+            let actual = self.describe(topic).await?;
+            let topic_drift = config_drift(topic, configs, &actual.values);
+            if topic_drift.is_empty() {
+                continue;
+            }
+            for d in &topic_drift {
+                warn!("Topic {} has {}={} instead of {}", d.topic, d.name, d.actual.as_deref().unwrap_or("<unset>"), d.expected);
+            }
+            if self.config.alter && self.alter(topic, configs, &actual.overrides).await? {
+                continue;
+            }
+            drift.extend(topic_drift);
+        }
+        metrics::global().set_gauge(METRIC_TOPIC_CONFIG_DRIFT, drift.len() as f64);
+        Ok(drift)
+    }
+
+    async fn create_topics(&self, expected: &[(&str, Vec<(&'static str, String)>)]) -> Result<()> {
+        let new_topics: Vec<NewTopic> = expected.iter()
+            .map(|(topic, configs)| configs.iter().fold(
+                // -1 takes the broker's default partitions and replication factor
+                NewTopic::new(topic, -1, TopicReplication::Fixed(-1)),
+                |new_topic, (name, value)| new_topic.set(name, value),
+            ))
+            .collect();
+        for result in self.admin.create_topics(&new_topics, &AdminOptions::new()).await? {
+            match result {
+                Ok(topic) => info!("Created topic {}", topic),
+                Err((topic, RDKafkaErrorCode::TopicAlreadyExists)) => debug!("Topic {} already exists", topic),
+                Err((topic, code)) => warn!("Failed to create topic {}: {}", topic, code),
+            }
+        }
+        Ok(())
+    }
+
+    async fn describe(&self, topic: &str) -> Result<TopicConfigs> {
+        let mut configs = TopicConfigs::default();
+        for result in self.admin.describe_configs(&[ResourceSpecifier::Topic(topic)], &AdminOptions::new()).await? {
+            let resource = result.map_err(|code| anyhow!("Failed to describe topic {}: {}", topic, code))?;
+            for entry in resource.entries {
+                let Some(value) = entry.value else {
+                    continue;
+                };
+                if matches!(entry.source, ConfigSource::DynamicTopic) && !entry.is_read_only && !entry.is_sensitive {
+                    configs.overrides.insert(entry.name.clone(), value.clone());
+                }
+                configs.values.insert(entry.name, value);
+            }
+        }
+        Ok(configs)
+    }
+
+    /// Set the expected settings on `topic`, returning whether the broker took them
+    ///
+    /// The broker replaces the whole topic configuration, so the topic's other overrides
+    /// are sent along unchanged.
+    async fn alter(&self, topic: &str, expected: &[(&'static str, String)], overrides: &HashMap<String, String>) -> Result<bool> {
+        let mut alter = AlterConfig::new(ResourceSpecifier::Topic(topic));
+        for (name, value) in overrides {
+            alter = alter.set(name, value);
+        }
+        for (name, value) in expected {
+            alter = alter.set(name, value);
+        }
+        let mut altered = true;
+        for result in self.admin.alter_configs(&[alter], &AdminOptions::new()).await? {
+            match result {
+                Ok(_) => info!("Altered the settings of topic {}", topic),
+                Err((_, code)) => {
+                    warn!("Failed to alter the settings of topic {}: {}", topic, code);
+                    altered = false;
+                }
+            }
+        }
+        Ok(altered)
+    }
+}
+
+/// Settings of a topic as the broker reports them
+#[derive(Default)]
+struct TopicConfigs {
+    /// Every reported setting
+    values: HashMap<String, String>,
+    /// Settings overridden on the topic itself
+    overrides: HashMap<String, String>,
+}
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+/// Counter of stalls detected in the publishing pipeline
+pub const METRIC_PRODUCER_STALLS: &str = "kafka.producer_stalls";
+
+/// Counter of producer client restarts
+pub const METRIC_PRODUCER_RESTARTS: &str = "kafka.producer_restarts";
+
+/// 1 while records are written to the local journal instead of Kafka
+pub const METRIC_JOURNAL_ONLY: &str = "kafka.journal_only";
+
+/// What the producer should do about the pipeline after a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogAction {
+    /// Records are flowing, or haven't waited long enough to call it a stall
+    None,
+    /// Replace the client with a fresh one
+    Restart,
+    /// A restart didn't help; persist to the journal until Kafka is reachable again
+    DegradeToJournal,
+}
+
+/// Stall detection for the Kafka publishing pipeline
+///
+/// The pipeline is stalled when records have been waiting for delivery for `stall_after`
+/// without a single successful delivery. The first stall restarts the client; a stall
+/// that survives the restart degrades to journal-only persistence when that is allowed.
+pub struct ProducerWatchdog {
+    stall_after: Duration,
+
+    journal_fallback: bool,
+
+    /// Since when records have been waiting for delivery
+    backlog_since: Option<Instant>,
+
+    /// Restart made for the current stall
+    restarted_at: Option<Instant>,
+}
+
+impl ProducerWatchdog {
+    pub fn new(stall_after: Duration, journal_fallback: bool) -> Self {
+        Self {
+            stall_after,
+            journal_fallback,
+            backlog_since: None,
+            restarted_at: None,
+        }
+    }
+
+    /// Judge the pipeline at `now` from the number of records awaiting delivery and the
+    /// time of the last successful delivery
+    pub fn check(&mut self, backlog: usize, last_delivery: Instant, now: Instant) -> WatchdogAction {
+        if backlog == 0 {
+            self.reset();
+            return WatchdogAction::None;
+        }
+        if self.restarted_at.is_some_and(|restarted_at| last_delivery > restarted_at) {
+            // The restart got records moving again
+            self.restarted_at = None;
+        }
+
+        let backlog_since = *self.backlog_since.get_or_insert(now);
+        let progress = [Some(backlog_since), Some(last_delivery), self.restarted_at]
+            .into_iter()
+            .flatten()
+            .max()
+            .unwrap_or(now);
+        if now.saturating_duration_since(progress) < self.stall_after {
+            return WatchdogAction::None;
+        }
+
+        if self.restarted_at.is_some() && self.journal_fallback {
+            return WatchdogAction::DegradeToJournal;
+        }
+        self.restarted_at = Some(now);
+        WatchdogAction::Restart
+    }
+
+    /// Forget the current stall, e.g. after switching to the journal
+    pub fn reset(&mut self) {
+        self.backlog_since = None;
+        self.restarted_at = None;
+    }
+}
@@ -0,0 +1,267 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio_postgres::NoTls;
+
+use crate::config_loader::{AppConfig, LeaseBackend, LeaseConfig};
+use crate::infrastructure::metrics;
+use crate::domain::clock::now_secs;
+
+/// Gauge set to 1 while this instance holds the quoting lease, 0 in standby
+pub const METRIC_LEASE_HELD: &str = "lease.held";
+
+/// Counter of leases lost while quoting
+pub const METRIC_LEASE_LOST: &str = "lease.lost";
+
+/// Who holds a lease and until when
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LeaseRecord {
+    pub holder: String,
+
+    /// Seconds since epoch
+    pub expires_at: f64,
+}
+
+/// Shared storage for leases
+#[async_trait]
+pub trait LeaseStore: Send + Sync {
+    /// Take `key` for `holder` until `expires_at` if it is free, expired or
+    /// already held by `holder`; returns the record in force afterwards
+    async fn acquire(&self, key: &str, holder: &str, expires_at: f64, now: f64) -> Result<LeaseRecord>;
+
+    /// Give up `key` if `holder` has it
+    async fn release(&self, key: &str, holder: &str) -> Result<()>;
+}
+
+/// Leases as JSON files in a directory, for instances on one host
+///
+/// Writes go through a rename, and every acquire reads the file back, so two
+/// instances racing for a free lease can at worst both win until the next
+/// renewal, when the one that was overwritten steps down.
+pub struct FileLeaseStore {
+    dir: PathBuf,
+}
+
+impl FileLeaseStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create lease directory {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.lease", key))
+    }
+
+    fn read(&self, key: &str) -> Option<LeaseRecord> {
+        let content = std::fs::read_to_string(self.path(key)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+}
+
+#[async_trait]
+impl LeaseStore for FileLeaseStore {
+    async fn acquire(&self, key: &str, holder: &str, expires_at: f64, now: f64) -> Result<LeaseRecord> {
+        if let Some(current) = self.read(key) {
+            if current.holder != holder && current.expires_at > now {
+                return Ok(current);
+            }
+        }
+
+        let record = LeaseRecord { holder: holder.to_string(), expires_at };
+        let tmp = self.dir.join(format!("{}.{}.tmp", key, holder));
+        std::fs::write(&tmp, serde_json::to_string(&record)?)?;
+        std::fs::rename(&tmp, self.path(key))?;
+
+        Ok(self.read(key).unwrap_or(record))
+    }
+
+    async fn release(&self, key: &str, holder: &str) -> Result<()> {
+        if self.read(key).is_some_and(|current| current.holder == holder) {
+            std::fs::remove_file(self.path(key))?;
+        }
+        Ok(())
+    }
+}
+
+/// Leases as rows of `<schema>.bot_lease`, for instances on different hosts
+pub struct PostgresLeaseStore {
+    client: tokio_postgres::Client,
+    table: String,
+}
+
+impl PostgresLeaseStore {
+    pub async fn connect(url: &str, schema: &str) -> Result<Self> {
+        let (client, connection) = tokio_postgres::connect(url, NoTls).await
+            .context("Failed to connect to the lease database")?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                error!("Lease database connection error: {}", e);
+            }
+        });
+
+        let table = format!("{}.bot_lease", schema);
+        client.batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                lease_key TEXT PRIMARY KEY,
+                holder TEXT NOT NULL,
+                expires_at DOUBLE PRECISION NOT NULL
+            )",
+            table
+        )).await?;
+        Ok(Self { client, table })
+    }
+}
+
+#[async_trait]
+impl LeaseStore for PostgresLeaseStore {
+    async fn acquire(&self, key: &str, holder: &str, expires_at: f64, now: f64) -> Result<LeaseRecord> {
+        // The conditional upsert is atomic, so only one instance can take a free lease
+        self.client.execute(
+            &format!(
+                "INSERT INTO {table} (lease_key, holder, expires_at) VALUES ($1, $2, $3)
+                 ON CONFLICT (lease_key) DO UPDATE
+                 SET holder = EXCLUDED.holder, expires_at = EXCLUDED.expires_at
+                 WHERE {table}.holder = EXCLUDED.holder OR {table}.expires_at <= $4",
+                table = self.table
+            ),
+            &[&key, &holder, &expires_at, &now],
+        ).await?;
+
+        let row = self.client.query_one(
+            &format!("SELECT holder, expires_at FROM {} WHERE lease_key = $1", self.table),
+            &[&key],
+        ).await?;
+        Ok(LeaseRecord { holder: row.get(0), expires_at: row.get(1) })
+    }
+
+    async fn release(&self, key: &str, holder: &str) -> Result<()> {
+        self.client.execute(
+            &format!("DELETE FROM {} WHERE lease_key = $1 AND holder = $2", self.table),
+            &[&key, &holder],
+        ).await?;
+        Ok(())
+    }
+}
+
+/// This instance's claim on the quoting lease
+pub struct LeaseManager {
+    store: Arc<dyn LeaseStore>,
+    key: String,
+    holder: String,
+    ttl_sec: f64,
+    renew: Duration,
+}
+
+impl LeaseManager {
+    pub fn new(store: Arc<dyn LeaseStore>, key: &str, holder: &str, config: &LeaseConfig) -> Self {
+        Self {
+            store,
+            key: key.to_string(),
+            holder: holder.to_string(),
+            ttl_sec: config.ttl_sec as f64,
+            renew: Duration::from_secs(config.renew_sec),
+        }
+    }
+
+    /// Open the configured store; None when the lease is disabled
+    pub async fn from_config(config: &AppConfig, default_key: &str, holder: &str) -> Result<Option<Self>> {
+        let lease = &config.lease;
+        if !lease.enabled {
+            return Ok(None);
+        }
+        if lease.renew_sec == 0 || lease.renew_sec >= lease.ttl_sec {
+            return Err(anyhow!("Lease renew_sec ({}) must be positive and below ttl_sec ({})", lease.renew_sec, lease.ttl_sec));
+        }
+
+        let store: Arc<dyn LeaseStore> = match lease.backend {
+            LeaseBackend::File => Arc::new(FileLeaseStore::new(&lease.dir)?),
+            LeaseBackend::Postgres => Arc::new(PostgresLeaseStore::connect(&config.database_url(), &config.database.schema).await?),
+        };
+        let key = lease.key.clone().unwrap_or_else(|| default_key.to_string());
+        Ok(Some(Self::new(store, &key, holder, lease)))
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Take or extend the lease; returns the record in force afterwards
+    pub async fn try_acquire(&self, now: f64) -> Result<LeaseRecord> {
+        let record = self.store.acquire(&self.key, &self.holder, now + self.ttl_sec, now).await?;
+        let held = record.holder == self.holder;
+        metrics::global().set_gauge(METRIC_LEASE_HELD, if held { 1.0 } else { 0.0 });
+        Ok(record)
+    }
+
+    /// Whether `record` belongs to this instance
+    pub fn is_mine(&self, record: &LeaseRecord) -> bool {
+        record.holder == self.holder
+    }
+
+    pub async fn release(&self) {
+        match self.store.release(&self.key, &self.holder).await {
+            Ok(()) => info!("Released lease {}", self.key),
+            Err(e) => warn!("Failed to release lease {}: {}", self.key, e),
+        }
+        metrics::global().set_gauge(METRIC_LEASE_HELD, 0.0);
+    }
+
+    /// Retry until this instance holds the lease, logging once while in standby
+    pub async fn wait_until_held(&self) {
+        let mut standby_logged = false;
+        loop {
+            match self.try_acquire(now_secs()).await {
+                Ok(record) if self.is_mine(&record) => {
+                    info!("Acquired lease {}", self.key);
+                    return;
+                }
+                Ok(record) if !standby_logged => {
+                    warn!("Lease {} held by {}, waiting in standby", self.key, record.holder);
+                    standby_logged = true;
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Failed to acquire lease {}: {}", self.key, e),
+            }
+            tokio::time::sleep(self.renew).await;
+        }
+    }
+
+    /// Keep renewing the lease; returns an error once it is lost or has
+    /// expired without a successful renewal
+    pub async fn hold(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let mut expires_at = now_secs() + self.ttl_sec;
+        let mut interval = tokio::time::interval(self.renew);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let now = now_secs();
+                    match self.try_acquire(now).await {
+                        Ok(record) if self.is_mine(&record) => expires_at = record.expires_at,
+                        Ok(record) => {
+                            metrics::global().incr(METRIC_LEASE_LOST, 1);
+                            return Err(anyhow!("Lease {} taken over by {}", self.key, record.holder));
+                        }
+                        Err(e) if now < expires_at => warn!("Failed to renew lease {}: {}", self.key, e),
+                        Err(e) => {
+                            metrics::global().incr(METRIC_LEASE_LOST, 1);
+                            return Err(anyhow!("Lease {} expired, last renewal error: {}", self.key, e));
+                        }
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Lease task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{info, warn};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::domain::model::lifecycle::{LifecycleEvent, LifecycleEventType};
+use crate::domain::model::session_summary::SessionSummary;
+use crate::infrastructure::kafka::KafkaProducer;
+use crate::infrastructure::metrics;
+use crate::domain::clock;
+
+/// Counter of lifecycle events emitted, suffixed with the event type
+pub const METRIC_LIFECYCLE_EVENTS: &str = "lifecycle.events";
+
+/// Counter of lifecycle events dropped because the buffer was full
+pub const METRIC_LIFECYCLE_DROPPED: &str = "lifecycle.dropped";
+
+/// Events kept while no producer is attached
+pub const MAX_PENDING_EVENTS: usize = 64;
+
+/// Publishes session lifecycle events for one bot process
+///
+/// Lives across sessions so every event, and every heartbeat, carries the same
+/// instance id and heartbeat sequences keep counting up through reconnects. Events
+/// emitted before a producer is attached (e.g. `connected` on the first
+/// session) are buffered and sent on `attach`.
+pub struct LifecyclePublisher {
+    instance_id: String,
+    network: String,
+    producer: Mutex<Option<Arc<KafkaProducer>>>,
+    pending: Mutex<VecDeque<LifecycleEvent>>,
+    heartbeat_sequence: AtomicI64,
+}
+
+impl LifecyclePublisher {
+    pub fn new(network: impl Into<String>) -> Self {
+        Self {
+            instance_id: Uuid::new_v4().to_string(),
+            network: network.into(),
+            producer: Mutex::new(None),
+            pending: Mutex::new(VecDeque::new()),
+            heartbeat_sequence: AtomicI64::new(0),
+        }
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Sequence number for the next heartbeat, starting at 1
+    pub fn next_heartbeat_sequence(&self) -> i64 {
+        self.heartbeat_sequence.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Build an event stamped with the current time
+    pub fn event(&self, event_type: LifecycleEventType, reason: Option<String>) -> LifecycleEvent {
+        let now = clock::now_secs();
+        LifecycleEvent {
+            instance_id: self.instance_id.clone(),
+            event_type,
+            reason,
+            network: self.network.clone(),
+            timestamp: now,
+            processing_timestamp: Some(now),
+        }
+    }
+
+    /// Record an event; publishing failures are logged, never returned
+    pub async fn emit(&self, event_type: LifecycleEventType, reason: Option<String>) {
+        let event = self.event(event_type, reason);
+        match &event.reason {
+            Some(reason) => info!("Lifecycle: {} ({})", event_type.as_str(), reason),
+            None => info!("Lifecycle: {}", event_type.as_str()),
+        }
+        metrics::global().incr(&format!("{}.{}", METRIC_LIFECYCLE_EVENTS, event_type.as_str()), 1);
+
+        let producer = self.producer.lock().await.clone();
+        match producer {
+            Some(producer) => Self::send(&producer, &event).await,
+            None => self.buffer(event).await,
+        }
+    }
+
+    /// Log the summary of the run and send it on the attached producer, if any
+    pub async fn publish_session_summary(&self, summary: &SessionSummary) {
+        info!("Session summary: {:.0}s, {} quotes sent, {} amends, {} cancels, {} fills, volume {}, realized PnL {:.2}, max inventory {}, {} reconnects",
+            summary.duration_secs(), summary.quotes_sent, summary.amends, summary.cancels, summary.fills,
+            summary.volume, summary.realized_pnl, summary.max_inventory, summary.reconnects);
+        let producer = self.producer.lock().await.clone();
+        if let Some(producer) = producer {
+            if let Err(e) = producer.send_session_summary(summary).await {
+                warn!("Failed to send session summary to Kafka: {}", e);
+            }
+        }
+    }
+
+    /// Use `producer` for subsequent events and send anything buffered so far
+    pub async fn attach(&self, producer: Option<Arc<KafkaProducer>>) {
+        *self.producer.lock().await = producer.clone();
+        let Some(producer) = producer else {
+            return;
+        };
+        let pending: Vec<LifecycleEvent> = self.pending.lock().await.drain(..).collect();
+        for event in &pending {
+            Self::send(&producer, event).await;
+        }
+    }
+
+    /// Wait for sends in flight on the attached producer, if any
+    pub async fn flush(&self, timeout: Duration) {
+        let producer = self.producer.lock().await.clone();
+        if let Some(producer) = producer {
+            if let Err(e) = producer.flush(timeout).await {
+                warn!("{}", e);
+            }
+        }
+    }
+
+    /// Events waiting for a producer, oldest first
+    pub async fn pending(&self) -> Vec<LifecycleEvent> {
+        self.pending.lock().await.iter().cloned().collect()
+    }
+
+    async fn buffer(&self, event: LifecycleEvent) {
+        let mut pending = self.pending.lock().await;
+        if pending.len() >= MAX_PENDING_EVENTS {
+            pending.pop_front();
+            metrics::global().incr(METRIC_LIFECYCLE_DROPPED, 1);
+        }
+        pending.push_back(event);
+    }
+
+    async fn send(producer: &KafkaProducer, event: &LifecycleEvent) {
+        if let Err(e) = producer.send_lifecycle(event).await {
+            warn!("Failed to send lifecycle event to Kafka: {}", e);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Process-wide registry of named counters and gauges
+///
+/// Values are plain atomics so hot paths can update them without locking once the
+/// handle has been looked up.
+#[derive(Default)]
+pub struct Metrics {
+    counters: RwLock<HashMap<String, Arc<AtomicU64>>>,
+    gauges: RwLock<HashMap<String, Arc<AtomicU64>>>,
+}
+
+static GLOBAL: OnceLock<Metrics> = OnceLock::new();
+
+/// The global metrics registry
+pub fn global() -> &'static Metrics {
+    GLOBAL.get_or_init(Metrics::default)
+}
+
+impl Metrics {
+    /// Handle to a counter, created at zero on first use
+    pub fn counter(&self, name: &str) -> Arc<AtomicU64> {
+        Self::entry(&self.counters, name)
+    }
+
+    /// Add to a counter
+    pub fn incr(&self, name: &str, by: u64) {
+        self.counter(name).fetch_add(by, Ordering::Relaxed);
+    }
+
+    /// Set a gauge to a value
+    pub fn set_gauge(&self, name: &str, value: f64) {
+        Self::entry(&self.gauges, name).store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Add to a gauge, e.g. one accumulating a fractional total
+    pub fn add_gauge(&self, name: &str, by: f64) {
+        let gauge = Self::entry(&self.gauges, name);
+        let _ = gauge.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| Some((f64::from_bits(bits) + by).to_bits()));
+    }
+
+    /// Raise a gauge to `value` if that is higher than its current value
+    pub fn max_gauge(&self, name: &str, value: f64) {
+        let gauge = Self::entry(&self.gauges, name);
+        let _ = gauge.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+            (value > f64::from_bits(bits)).then(|| value.to_bits())
+        });
+    }
+
+    /// Current value of a counter
+    pub fn counter_value(&self, name: &str) -> u64 {
+        self.counters.read().unwrap()
+            .get(name)
+            .map(|c| c.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// Current value of a gauge, if it was ever set
+    pub fn gauge_value(&self, name: &str) -> Option<f64> {
+        self.gauges.read().unwrap()
+            .get(name)
+            .map(|g| f64::from_bits(g.load(Ordering::Relaxed)))
+    }
+
+    /// All counters and gauges by name
+    pub fn snapshot(&self) -> BTreeMap<String, f64> {
+        let mut values: BTreeMap<String, f64> = self.counters.read().unwrap()
+            .iter()
+            .map(|(name, c)| (name.clone(), c.load(Ordering::Relaxed) as f64))
+            .collect();
+        values.extend(self.gauges.read().unwrap()
+            .iter()
+            .map(|(name, g)| (name.clone(), f64::from_bits(g.load(Ordering::Relaxed)))));
+        values
+    }
+
+    fn entry(map: &RwLock<HashMap<String, Arc<AtomicU64>>>, name: &str) -> Arc<AtomicU64> {
+        if let Some(value) = map.read().unwrap().get(name) {
+            return value.clone();
+        }
+        map.write().unwrap()
+            .entry(name.to_string())
+            .or_default()
+            .clone()
+    }
+}
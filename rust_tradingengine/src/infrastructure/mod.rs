@@ -1,2 +1,11 @@
+pub mod bot_state;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod exchange;
 pub mod kafka;
+pub mod lease;
+pub mod lifecycle;
+pub mod metrics;
+pub mod persistence;
+pub mod reconnect;
+pub mod runtime;
@@ -0,0 +1,3 @@
+pub mod object_store;
+
+pub use self::object_store::{CloudStore, LocalStore, ObjectStore};
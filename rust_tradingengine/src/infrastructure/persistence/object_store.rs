@@ -0,0 +1,198 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use object_store::aws::AmazonS3Builder;
+use object_store::buffered::BufWriter;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore as Bucket, PutPayload, RetryConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+use crate::config_loader::{ObjectStoreConfig, ObjectStoreProvider};
+use crate::infrastructure::exchange::thalex::keys::KEYRING_SERVICE;
+
+/// Size of the parts large files are uploaded in; S3 needs at least 5 MiB per part
+pub const UPLOAD_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Where report files and archives are stored once written locally
+///
+/// Keys are `/`-separated paths relative to the store's prefix.
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Store `body` at `key`, replacing any existing object
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<()>;
+
+    /// Where objects go, for log messages
+    fn location(&self) -> String;
+
+    /// Store the file at `path` under `key`
+    async fn put_file(&self, path: &Path, key: &str) -> Result<()> {
+        let body = tokio::fs::read(path).await.with_context(|| format!("Failed to read {}", path.display()))?;
+        self.put(key, body).await
+    }
+}
+
+/// Store for the configured provider, with its credentials resolved
+pub fn from_config(config: &ObjectStoreConfig) -> Result<Arc<dyn ObjectStore>> {
+    let bucket: Arc<dyn Bucket> = match config.provider {
+        ObjectStoreProvider::Local => {
+            return Ok(Arc::new(LocalStore::new(Path::new(&config.bucket).join(&config.prefix))));
+        }
+        ObjectStoreProvider::Gcs => {
+            let mut builder = GoogleCloudStorageBuilder::from_env()
+                .with_bucket_name(&config.bucket)
+                .with_retry(RetryConfig::default());
+            if let Some(service_account) = &config.service_account {
+                builder = builder.with_service_account_key(secret(service_account).context("Object store service account")?);
+            }
+            Arc::new(builder.build()?)
+        }
+        provider => {
+            if config.endpoint.is_none() && provider != ObjectStoreProvider::S3 {
+                return Err(anyhow!("{:?} object store needs an endpoint", provider));
+            }
+            let access_key = secret(&config.access_key).context("Object store access key")?;
+            let secret_key = secret(&config.secret_key).context("Object store secret key")?;
+            let mut builder = AmazonS3Builder::new()
+                .with_bucket_name(&config.bucket)
+                .with_region(&config.region)
+                .with_access_key_id(access_key)
+                .with_secret_access_key(secret_key)
+                .with_retry(RetryConfig::default());
+            if let Some(endpoint) = &config.endpoint {
+                builder = builder.with_endpoint(endpoint).with_allow_http(endpoint.starts_with("http://"));
+            }
+            Arc::new(builder.build()?)
+        }
+    };
+    let location = match &config.endpoint {
+        Some(endpoint) => format!("{}/{}/{}", endpoint.trim_end_matches('/'), config.bucket, config.prefix),
+        None => format!("{}://{}/{}", scheme(config.provider), config.bucket, config.prefix),
+    };
+    Ok(Arc::new(CloudStore::new(bucket, &config.prefix, location)))
+}
+
+fn scheme(provider: ObjectStoreProvider) -> &'static str {
+    match provider {
+        ObjectStoreProvider::Gcs => "gs",
+        _ => "s3",
+    }
+}
+
+/// Resolve a secret reference: `env:NAME`, `file:PATH` or `keyring:ACCOUNT`
+///
+/// Keyring entries are read from the same service as the exchange keys. Plain values
+/// are refused so credentials don't end up in the config file.
+pub fn secret(reference: &str) -> Result<String> {
+    let (kind, name) = reference.split_once(':')
+        .ok_or_else(|| anyhow!("Secret must be env:, file: or keyring:, got '{}'", reference))?;
+    let value = match kind {
+        "env" => std::env::var(name).map_err(|_| anyhow!("Missing {}", name))?,
+        "file" => fs::read_to_string(name).with_context(|| format!("Failed to read {}", name))?,
+        "keyring" => keyring::Entry::new(KEYRING_SERVICE, name)
+            .and_then(|entry| entry.get_password())
+            .map_err(|e| anyhow!("Failed to read '{}' from keyring: {}", name, e))?,
+        _ => return Err(anyhow!("Unknown secret source '{}'", kind)),
+    };
+    Ok(value.trim().to_string())
+}
+
+/// Store on the local filesystem, e.g. a mounted network share
+pub struct LocalStore {
+    root: PathBuf,
+}
+
+impl LocalStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LocalStore {
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        let path = self.root.join(key);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, body).await?;
+        tokio::fs::rename(&tmp, &path).await.with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    fn location(&self) -> String {
+        self.root.display().to_string()
+    }
+}
+
+/// Bucket of a cloud provider (S3, GCS) or an S3-compatible store such as MinIO
+///
+/// Requests are retried with backoff on transient errors. Files larger than a part are
+/// streamed in a multipart upload, so a report is never held in memory whole.
+pub struct CloudStore {
+    bucket: Arc<dyn Bucket>,
+    prefix: String,
+    location: String,
+    part_size: usize,
+}
+
+impl CloudStore {
+    pub fn new(bucket: Arc<dyn Bucket>, prefix: &str, location: impl Into<String>) -> Self {
+        Self {
+            bucket,
+            prefix: prefix.trim_matches('/').to_string(),
+            location: location.into(),
+            part_size: UPLOAD_PART_SIZE,
+        }
+    }
+
+    /// Upload files larger than `part_size` bytes in parts of that size
+    pub fn with_part_size(mut self, part_size: usize) -> Self {
+        self.part_size = part_size;
+        self
+    }
+
+    /// Object key of `name` under the configured prefix
+    pub fn key(&self, name: &str) -> String {
+        if self.prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", self.prefix, name)
+        }
+    }
+
+    fn path(&self, name: &str) -> Result<ObjectPath> {
+        ObjectPath::parse(self.key(name)).with_context(|| format!("Invalid object key {}", name))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for CloudStore {
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        self.bucket.put(&self.path(key)?, PutPayload::from(body)).await
+            .with_context(|| format!("Upload of {} failed", self.key(key)))?;
+        Ok(())
+    }
+
+    fn location(&self) -> String {
+        self.location.clone()
+    }
+
+    async fn put_file(&self, path: &Path, key: &str) -> Result<()> {
+        let mut file = tokio::fs::File::open(path).await.with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut writer = BufWriter::with_capacity(self.bucket.clone(), self.path(key)?, self.part_size);
+        let uploaded = async {
+            tokio::io::copy(&mut file, &mut writer).await?;
+            writer.shutdown().await
+        }.await;
+        if let Err(e) = uploaded {
+            // Don't leave the parts of a failed multipart upload behind
+            let _ = writer.abort().await;
+            return Err(anyhow!("Upload of {} failed: {}", self.key(key), e));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use log::error;
+
+use crate::config_loader::ReconnectConfig;
+use crate::infrastructure::metrics;
+
+/// Counter of reconnects performed
+pub const METRIC_RECONNECTS: &str = "reconnect.count";
+
+/// Counter of reconnect churn alerts raised
+pub const METRIC_CHURN_ALERTS: &str = "reconnect.churn_alerts";
+
+/// Exponential backoff between sessions, with an attempt cap and churn alerting
+pub struct ReconnectPolicy {
+    config: ReconnectConfig,
+
+    /// Consecutive short-lived sessions
+    attempts: u32,
+
+    /// When recent reconnects happened, within the churn window
+    recent: VecDeque<Instant>,
+}
+
+impl ReconnectPolicy {
+    pub fn new(config: ReconnectConfig) -> Self {
+        Self {
+            config,
+            attempts: 0,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Record the end of a session and return how long to wait before reconnecting
+    ///
+    /// Returns None once `max_attempts` consecutive sessions ended before
+    /// `reset_after_sec`; a session that lasted longer resets the count.
+    pub fn next_delay(&mut self, session_duration: Duration, now: Instant) -> Option<Duration> {
+        if session_duration >= Duration::from_secs(self.config.reset_after_sec) {
+            self.attempts = 0;
+        }
+        self.attempts += 1;
+        if self.config.max_attempts > 0 && self.attempts > self.config.max_attempts {
+            return None;
+        }
+
+        self.record_reconnect(now);
+
+        let delay_ms = self.config.initial_delay_ms as f64
+            * self.config.multiplier.powi(self.attempts as i32 - 1);
+        Some(Duration::from_millis(delay_ms.min(self.config.max_delay_ms as f64) as u64))
+    }
+
+    /// Consecutive attempts since the last stable session
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Reconnects within the churn window
+    pub fn churn(&self) -> usize {
+        self.recent.len()
+    }
+
+    /// Whether reconnect churn is at or above the alert threshold
+    pub fn is_churning(&self) -> bool {
+        self.churn() >= self.config.churn_alert_threshold
+    }
+
+    fn record_reconnect(&mut self, now: Instant) {
+        let window = Duration::from_secs(self.config.churn_window_sec);
+        while self.recent.front().is_some_and(|t| now.duration_since(*t) > window) {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(now);
+
+        metrics::global().incr(METRIC_RECONNECTS, 1);
+        if self.is_churning() {
+            metrics::global().incr(METRIC_CHURN_ALERTS, 1);
+            error!("ALERT: {} reconnects within {}s (threshold {})",
+                self.churn(), self.config.churn_window_sec, self.config.churn_alert_threshold);
+        }
+    }
+}
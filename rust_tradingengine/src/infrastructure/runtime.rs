@@ -0,0 +1,121 @@
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::task::JoinHandle;
+
+use crate::config_loader::RuntimeConfig;
+
+/// Runtime `spawn_io` sends work to, once a dedicated IO runtime is installed
+static IO_HANDLE: OnceLock<Handle> = OnceLock::new();
+
+/// The process's tokio runtimes
+///
+/// The main runtime drives the latency-critical path: the exchange connection, quoting and
+/// order handling. With `io_runtime` enabled, Kafka publishing and other background IO run
+/// on a second runtime so their bursts don't delay quote updates.
+pub struct RuntimeTopology {
+    pub main: Runtime,
+    pub io: Option<Runtime>,
+}
+
+impl RuntimeTopology {
+    pub fn build(config: &RuntimeConfig) -> Result<Self> {
+        let main = build_runtime("quote", config.worker_threads, config.quote_cores.clone())?;
+        let io = if config.io_runtime {
+            Some(build_runtime("io", Some(config.io_worker_threads), config.io_cores.clone())?)
+        } else {
+            if !config.io_cores.is_empty() {
+                warn!("io_cores ignored without a dedicated IO runtime");
+            }
+            None
+        };
+        info!("Runtime topology: {} quote workers pinned to {:?}, IO runtime: {}",
+            config.worker_threads.map_or("default".to_string(), |threads| threads.to_string()),
+            config.quote_cores,
+            if config.io_runtime {
+                format!("{} workers pinned to {:?}", config.io_worker_threads, config.io_cores)
+            } else {
+                "shared".to_string()
+            });
+        Ok(Self { main, io })
+    }
+
+    /// Route `spawn_io` to the IO runtime, if there is one; only the first install counts
+    pub fn install(&self) {
+        if let Some(io) = &self.io {
+            if IO_HANDLE.set(io.handle().clone()).is_err() {
+                warn!("IO runtime already installed");
+            }
+        }
+    }
+
+    /// Stop the IO runtime, giving its blocking tasks `timeout` to finish
+    pub fn shutdown(self, timeout: Duration) {
+        if let Some(io) = self.io {
+            io.shutdown_timeout(timeout);
+        }
+        self.main.shutdown_timeout(timeout);
+    }
+}
+
+fn build_runtime(name: &str, worker_threads: Option<usize>, cores: Vec<usize>) -> Result<Runtime> {
+    let mut builder = Builder::new_multi_thread();
+    builder.enable_all().thread_name(format!("{}-worker", name));
+    if let Some(threads) = worker_threads {
+        builder.worker_threads(threads.max(1));
+    }
+    if !cores.is_empty() {
+        let name = name.to_string();
+        builder.on_thread_start(move || {
+            if let Err(e) = pin_current_thread(&cores) {
+                warn!("Failed to pin {} thread to cores {:?}: {:#}", name, cores, e);
+            }
+        });
+    }
+    builder.build().with_context(|| format!("Failed to build the {} runtime", name))
+}
+
+/// Spawn background IO such as Kafka publishing on the IO runtime, or on the current
+/// runtime when there is no dedicated one
+pub fn spawn_io<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match IO_HANDLE.get() {
+        Some(handle) => handle.spawn(future),
+        None => tokio::spawn(future),
+    }
+}
+
+/// Restrict the calling thread to the given CPU cores
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(cores: &[usize]) -> Result<()> {
+    if cores.is_empty() {
+        return Err(anyhow!("No cores given"));
+    }
+    // SAFETY: cpu_set_t is plain data, and every core is checked against the set's size
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        for &core in cores {
+            if core >= libc::CPU_SETSIZE as usize {
+                return Err(anyhow!("Core {} out of range", core));
+            }
+            libc::CPU_SET(core, &mut set);
+        }
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(anyhow!("sched_setaffinity failed: {}", std::io::Error::last_os_error()));
+        }
+    }
+    Ok(())
+}
+
+/// Restrict the calling thread to the given CPU cores
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(_cores: &[usize]) -> Result<()> {
+    Err(anyhow!("CPU pinning is only supported on Linux"))
+}
@@ -1,16 +1,26 @@
 pub mod config_loader;
 pub mod domain;
 pub mod infrastructure;
+pub mod reporting;
 pub mod strategies;
+pub mod testing;
 
-pub use domain::constants::*;
 pub use domain::enums::*;
 pub use domain::model::exchange::*;
 pub use domain::model::order::*;
 pub use domain::model::quote::*;
 pub use domain::model::ticker::*;
-pub use domain::model::ack::*;
 pub use domain::model::trade::*;
+pub use domain::model::features::*;
+pub use domain::model::uptime::*;
+pub use domain::model::lifecycle::*;
+pub use domain::model::heartbeat::*;
+pub use domain::model::book::*;
+pub use domain::model::session_summary::*;
+pub use domain::model::funding_basis::*;
+pub use domain::model::account_event::*;
+pub use domain::model::trade_correction::*;
+pub use domain::model::account_summary::*;
 pub use infrastructure::exchange::thalex::*;
 pub use infrastructure::kafka::*;
 pub use strategies::thalex_market_maker::*;
@@ -1,9 +1,11 @@
 // Standard library imports
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::path::Path;
+use std::time::Instant;
 
 // External crate imports
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use dotenv::dotenv;
 use log::{debug, error, info, warn};
 use tokio::sync::{broadcast, Mutex};
@@ -12,18 +14,35 @@ use tokio::select;
 
 // Internal crate imports
 use cryptics_lab_bot::config_loader::AppConfig;
+use cryptics_lab_bot::domain::clock::now_secs;
+use cryptics_lab_bot::infrastructure::exchange::thalex::backfill::TradeBackfill;
+use cryptics_lab_bot::infrastructure::exchange::thalex::calls::RpcMethod;
 use cryptics_lab_bot::infrastructure::exchange::thalex::client::{Network, ThalexClient, ThalexKeys};
-use cryptics_lab_bot::domain::constants::*;
+use cryptics_lab_bot::infrastructure::exchange::thalex::clock::ClockStatus;
+use cryptics_lab_bot::infrastructure::exchange::thalex::error::ClientError;
+use cryptics_lab_bot::infrastructure::exchange::thalex::rate_limit::RateLimiter;
+use cryptics_lab_bot::infrastructure::exchange::thalex::rest::ThalexRestClient;
+use cryptics_lab_bot::infrastructure::exchange::thalex::session::SessionClient;
+use cryptics_lab_bot::infrastructure::exchange::thalex::subscriptions::SubscriptionManager;
+use cryptics_lab_bot::infrastructure::lease::LeaseManager;
+use cryptics_lab_bot::infrastructure::bot_state::BotStateMachine;
+use cryptics_lab_bot::infrastructure::lifecycle::LifecyclePublisher;
+use cryptics_lab_bot::infrastructure::metrics;
+use cryptics_lab_bot::infrastructure::reconnect::ReconnectPolicy;
+use cryptics_lab_bot::infrastructure::runtime::{self, RuntimeTopology};
+use cryptics_lab_bot::domain::model::bot_state::BotState;
+use cryptics_lab_bot::domain::model::lifecycle::LifecycleEventType;
 use cryptics_lab_bot::strategies::thalex_market_maker::*;
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     // Initialize logging
     dotenv().ok();
-    // Use a more explicit Builder that doesn't check environment variables
+    // Use a more explicit Builder that doesn't check environment variables.
+    // The logger accepts everything; the effective level is set from the config below.
     env_logger::Builder::new()
-        .filter_level(log::LevelFilter::Info)
+        .filter_level(log::LevelFilter::Trace)
         .init();
+    log::set_max_level(log::LevelFilter::Info);
     info!("Logger initialized");
 
     // Load configuration from TOML file (first try relative path, then absolute path as backup)
@@ -40,77 +59,279 @@ async fn main() -> Result<()> {
         }
     };
     
+    // Apply the profile's log level
+    log::set_max_level(config.log_level());
+    
     // Wrap config in Arc for thread-safe sharing
     let config = Arc::new(config);
     info!("Configuration loaded, running in docker: {}", config.app.rust_running_in_docker);
     
-    // Run the bot with the configuration
-    run_bot(config).await
+    // Build the runtimes the config asks for, then run the bot on the quoting runtime
+    let topology = RuntimeTopology::build(&config.runtime)?;
+    topology.install();
+    let result = topology.main.block_on(run_bot(config));
+    topology.shutdown(Duration::from_secs(1));
+    result
 }
 
 /// Main bot run function
 async fn run_bot(config: Arc<AppConfig>) -> Result<()> {
-    let network = Network::TEST;
-    let keys = ThalexKeys::from_env(&network);
+    let started_at = now_secs();
+    let network: Network = config.app.network.parse()?;
+    info!("Using {:?} network", network);
+    let keys = ThalexKeys::load(&network)?;
+    let mut reconnect = ReconnectPolicy::new(config.reconnect.clone());
+    let lifecycle = Arc::new(LifecyclePublisher::new(config.app.network.clone()));
+    info!("Bot instance id: {}", lifecycle.instance_id());
+    let default_lease_key = format!("thalex.{}.{}", config.app.network, UNDERLYING);
+    let lease = LeaseManager::from_config(&config, &default_lease_key, lifecycle.instance_id()).await?.map(Arc::new);
+    let shared = SessionShared {
+        lifecycle: lifecycle.clone(),
+        subscriptions: Arc::new(SubscriptionManager::new()),
+        state: Arc::new(BotStateMachine::from_config(&config.bot_state, lifecycle.instance_id())),
+        backfill: config.trade_backfill.enabled.then(|| Arc::new(TradeBackfill::new(&config.trade_backfill, config.thalex.strict_parsing))),
+        disabled_instruments: Mutex::new(None),
+        carry: Mutex::new(None),
+    };
+    let state = shared.state.clone();
 
     // Set up signal handler for SIGINT (Ctrl+C)
     let mut sigint = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt())?;
 
-    loop {
+    let result = loop {
+        // Another instance may be quoting the same account; stay in standby until it stops.
+        // A hot standby waits inside the session instead, with market data flowing
+        if let Some(lease) = lease.as_ref().filter(|_| !config.lease.hot_standby) {
+            if !wait_for_lease(lease, &mut sigint).await {
+                info!("SIGINT received in standby, exiting program");
+                lifecycle.emit(LifecycleEventType::Shutdown, Some("SIGINT in standby".to_string())).await;
+                lifecycle.flush(Duration::from_secs(KAFKA_FLUSH_TIMEOUT_SEC)).await;
+                break Ok(());
+            }
+        }
+        
+        if let Err(e) = state.transition(BotState::Connecting, "starting a session") {
+            warn!("{:#}", e);
+        }
         info!("Launching bot with new session");
-        let token = keys.make_auth_token()?;
-
-        // Create and connect client
-        let mut raw_client = ThalexClient::new();
-        raw_client.connect(network.clone()).await?;
+        
+        // Auth tokens carry `iat`, so refuse to start with a badly drifted clock
+        match ThalexQuoter::check_clock(&network).await {
+            Ok(ClockStatus::Halt) => {
+                let reason = "Local clock drift exceeds the halt threshold";
+                if let Err(e) = state.transition(BotState::Halted, reason) {
+                    warn!("{:#}", e);
+                }
+                lifecycle.emit(LifecycleEventType::KillSwitch, Some(reason.to_string())).await;
+                lifecycle.emit(LifecycleEventType::Shutdown, Some(reason.to_string())).await;
+                lifecycle.flush(Duration::from_secs(KAFKA_FLUSH_TIMEOUT_SEC)).await;
+                break Err(anyhow!(reason));
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Could not verify clock against exchange time: {}", e),
+        }
 
-        if let Some(msg) = raw_client.receive().await? {
-            debug!("Initial connection response: {}", msg);
+        let started = Instant::now();
+        let ended = match run_session(&config, &network, &keys, &shared, lease.clone(), &mut sigint).await {
+            // If we received a termination signal, exit the loop
+            Ok(true) => {
+                info!("Exiting program");
+                break Ok(());
+            }
+            Ok(false) => "session ended".to_string(),
+            Err(e) if ClientError::is_timeout(&e) => {
+                error!("Session timed out: {}", e);
+                format!("session timed out: {}", e)
+            }
+            Err(e) => {
+                error!("Session failed: {:?}", e);
+                format!("session failed: {}", e)
+            }
+        };
+        if let Err(e) = state.transition(BotState::Draining, &ended) {
+            warn!("{:#}", e);
         }
 
-        raw_client.login(token.clone(), None, Some(CALL_ID_LOGIN)).await?;
-        if let Some(msg) = raw_client.receive().await? {
-            debug!("Login response: {}", msg);
+        // Otherwise prepare to reconnect
+        let Some(delay) = reconnect.next_delay(started.elapsed(), Instant::now()) else {
+            let reason = format!("Giving up after {} consecutive reconnect attempts", reconnect.attempts() - 1);
+            lifecycle.emit(LifecycleEventType::Shutdown, Some(reason.clone())).await;
+            lifecycle.flush(Duration::from_secs(KAFKA_FLUSH_TIMEOUT_SEC)).await;
+            break Err(anyhow!(reason));
+        };
+        warn!("Reconnecting in {:?} (attempt {})...", delay, reconnect.attempts());
+        lifecycle.emit(
+            LifecycleEventType::Reconnect,
+            Some(format!("{}; retrying in {:?} (attempt {})", ended, delay, reconnect.attempts())),
+        ).await;
+        select! {
+            _ = sleep(delay) => {}
+            _ = sigint.recv() => {
+                info!("SIGINT received while waiting to reconnect, exiting program");
+                lifecycle.emit(LifecycleEventType::Shutdown, Some("SIGINT while reconnecting".to_string())).await;
+                lifecycle.flush(Duration::from_secs(KAFKA_FLUSH_TIMEOUT_SEC)).await;
+                break Ok(());
+            }
         }
+    };
 
-        // Create a broadcast channel for shutdown signaling
-        let (shutdown_tx, _) = broadcast::channel::<()>(3);
-        let shared_client = Arc::new(Mutex::new(raw_client));
-        
-        // Initialize the quoter with the client and config
-        let quoter = Arc::new(ThalexQuoter::new(
-            shared_client.clone(), 
-            Some(config.clone())
-        ).await);
+    // Hand the account over to a standby instance right away
+    if let Some(lease) = &lease {
+        lease.release().await;
+    }
+    let stopped = match &result {
+        Ok(()) => "exiting".to_string(),
+        Err(e) => e.to_string(),
+    };
+    if let Err(e) = state.transition(BotState::Stopped, &stopped) {
+        warn!("{:#}", e);
+    }
+
+    let summary = summarize_session(metrics::global(), lifecycle.instance_id(), started_at, now_secs());
+    lifecycle.publish_session_summary(&summary).await;
+    lifecycle.flush(Duration::from_secs(KAFKA_FLUSH_TIMEOUT_SEC)).await;
+    result
+}
 
-        // Start the trading tasks
-        let (should_exit, _) = run_tasks(quoter, shutdown_tx, &mut sigint).await?;
+/// What every session of the process shares, kept across reconnects
+struct SessionShared {
+    lifecycle: Arc<LifecyclePublisher>,
+    subscriptions: Arc<SubscriptionManager>,
+    state: Arc<BotStateMachine>,
+    backfill: Option<Arc<TradeBackfill>>,
+    /// Instruments disabled when the last session ended, None before the first one did
+    disabled_instruments: Mutex<Option<HashSet<String>>>,
+    /// Basis window and funding received when the last session ended, None before the first one did
+    carry: Mutex<Option<CarryTracker>>,
+}
 
-        // Clean up the client connection
-        info!("Running cleanup...");
-        cleanup(shared_client.clone()).await;
+/// Block until this instance holds the lease; returns false if SIGINT arrives first
+async fn wait_for_lease(lease: &LeaseManager, sigint: &mut tokio::signal::unix::Signal) -> bool {
+    select! {
+        _ = lease.wait_until_held() => true,
+        _ = sigint.recv() => false,
+    }
+}
 
-        // If we received a termination signal, exit the loop
-        if should_exit {
-            info!("Exiting program");
-            break;
+/// Connect, run one trading session and clean up; returns whether the program should exit
+async fn run_session(
+    config: &Arc<AppConfig>,
+    network: &Network,
+    keys: &ThalexKeys,
+    shared: &SessionShared,
+    lease: Option<Arc<LeaseManager>>,
+    sigint: &mut tokio::signal::unix::Signal,
+) -> Result<bool> {
+    let lifecycle = &shared.lifecycle;
+    let token = keys.make_auth_token()?;
+
+    // Create and connect client
+    let mut raw_client = ThalexClient::new();
+    raw_client.set_request_timeout(Duration::from_millis(config.thalex.request_timeout_ms));
+    raw_client.set_rate_limiter(RateLimiter::from_config(&config.thalex.rate_limit));
+    raw_client.connect(network.clone()).await?;
+    lifecycle.emit(LifecycleEventType::Connected, None).await;
+
+    if let Some(msg) = raw_client.receive().await? {
+        debug!("Initial connection response: {}", msg);
+    }
+
+    let login_id = raw_client.calls().allocate(RpcMethod::Login, None);
+    raw_client.login(token.clone(), None, Some(login_id)).await?;
+    if let Some(msg) = raw_client.receive().await? {
+        debug!("Login response: {}", msg);
+    }
+    raw_client.calls().complete(login_id);
+    lifecycle.emit(LifecycleEventType::LoggedIn, None).await;
+    shared.state.transition(BotState::Warmup, "logged in")?;
+
+    // Create a broadcast channel for shutdown signaling
+    let (shutdown_tx, _) = broadcast::channel::<()>(3);
+    // Initialize the quoter with the client and config
+    let mut quoter = ThalexQuoter::new(
+        Arc::new(Mutex::new(raw_client)),
+        Some(config.clone())
+    ).await;
+    
+    // Keep one instance id across sessions and publish on this session's producer
+    quoter.lifecycle = lifecycle.clone();
+    // Replay the previous session's subscriptions and cancel on disconnect
+    quoter.subscriptions = shared.subscriptions.clone();
+    quoter.state = shared.state.clone();
+    lifecycle.attach(quoter.market_data.kafka_producer.clone()).await;
+    if lease.is_some() && config.lease.hot_standby {
+        quoter.market_data.standby.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    // Instruments enabled or disabled at runtime stay so, rather than going back to the config
+    if let Some(disabled) = shared.disabled_instruments.lock().await.clone() {
+        *quoter.order_manager.disabled_instruments.write().await = disabled;
+    }
+    // Funding received adds up over the whole run, not per session
+    if let Some(carry) = shared.carry.lock().await.clone() {
+        *quoter.order_manager.carry.write().await = carry;
+    }
+    let quoter = Arc::new(quoter);
+
+    // Publish the trades made while no session was publishing them; a hot standby leaves
+    // that to the primary
+    if let (Some(backfill), Some(kafka_producer)) = (&shared.backfill, &quoter.market_data.kafka_producer) {
+        if !quoter.market_data.is_standby() {
+            let backfill = backfill.clone();
+            let kafka_producer = kafka_producer.clone();
+            let rest = ThalexRestClient::for_network(network, Some(keys.clone()));
+            runtime::spawn_io(async move {
+                if let Err(e) = backfill.run(&rest, &kafka_producer, now_secs()).await {
+                    error!("Trade backfill failed: {:#}", e);
+                }
+            });
         }
+    }
 
-        // Otherwise prepare to reconnect
-        sleep(Duration::from_secs(1)).await;
-        warn!("Reconnecting...");
+    // Start the trading tasks
+    let outcome = run_tasks(quoter.clone(), network.clone(), lease, shutdown_tx, sigint).await;
+    *shared.disabled_instruments.lock().await = Some(quoter.order_manager.disabled_instruments.read().await.clone());
+    *shared.carry.lock().await = Some(quoter.order_manager.carry.read().await.clone());
+    let (should_exit, task_error) = outcome?;
+
+    // Clean up the client connection
+    if let Err(e) = shared.state.transition(BotState::Draining, "session ending") {
+        warn!("{:#}", e);
+    }
+    info!("Running cleanup...");
+    cleanup(quoter.client.clone()).await;
+    if should_exit {
+        lifecycle.emit(LifecycleEventType::Shutdown, Some("SIGINT".to_string())).await;
+    }
+    
+    // Deliver the session's last acks and trades before the producer is dropped
+    if let Some(kafka_producer) = &quoter.market_data.kafka_producer {
+        match kafka_producer.flush(Duration::from_secs(KAFKA_FLUSH_TIMEOUT_SEC)).await {
+            Ok(()) => {
+                if let Some(backfill) = &shared.backfill {
+                    backfill.session_ended(now_secs());
+                }
+            }
+            Err(e) => error!("{}", e),
+        }
     }
 
-    Ok(())
+    // A task that failed ended the session: report why, e.g. as a timeout, once cleaned up
+    match task_error {
+        Some(e) if !should_exit => Err(e),
+        _ => Ok(should_exit),
+    }
 }
 
 /// Run the necessary trading tasks
 async fn run_tasks(
     quoter: Arc<ThalexQuoter>,
+    network: Network,
+    lease: Option<Arc<LeaseManager>>,
     shutdown_tx: broadcast::Sender<()>,
     sigint: &mut tokio::signal::unix::Signal,
 ) -> Result<(bool, Option<anyhow::Error>)> {
-    // Create separate variables for each task handle
+    // Create separate variables for each task handle; publishing and other background IO
+    // go to the IO runtime when one is configured
     let mut quote_handle = tokio::spawn({
         let quoter = quoter.clone();
         let shutdown_rx = shutdown_tx.subscribe();
@@ -146,6 +367,220 @@ async fn run_tasks(
             Ok(())
         }
     });
+
+    let mut features_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.features_task(shutdown_rx).await {
+                error!("Features task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut carry_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.carry_task(shutdown_rx).await {
+                error!("Carry task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut account_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.account_task(shutdown_rx).await {
+                error!("Account task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut outbound_handle = tokio::spawn({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.outbound_task(shutdown_rx).await {
+                error!("Outbound task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut schedule_handle = tokio::spawn({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.schedule_task(shutdown_rx).await {
+                error!("Schedule task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut subscription_handle = tokio::spawn({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.subscription_task(shutdown_rx).await {
+                error!("Subscription task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut uptime_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.uptime_task(shutdown_rx).await {
+                error!("Uptime task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut ticker_sample_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.ticker_sample_task(shutdown_rx).await {
+                error!("Ticker sample task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut stats_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.stats_task(shutdown_rx).await {
+                error!("Stats task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut eod_export_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.eod_export_task(shutdown_rx).await {
+                error!("End-of-day export task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut fair_value_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.fair_value_task(shutdown_rx).await {
+                error!("Fair value task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut control_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.control_task(shutdown_rx).await {
+                error!("Control task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut producer_watchdog_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.producer_watchdog_task(shutdown_rx).await {
+                error!("Producer watchdog task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut spill_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.spill_task(shutdown_rx).await {
+                error!("Spill task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut heartbeat_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.heartbeat_task(shutdown_rx).await {
+                error!("Heartbeat task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+
+    let mut clock_handle = runtime::spawn_io({
+        let quoter = quoter.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            if let Err(e) = quoter.clock_task(network, shutdown_rx).await {
+                error!("Clock task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
+    
+    let mut lease_handle = tokio::spawn({
+        let quoter = quoter.clone();
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        async move {
+            let Some(lease) = lease else {
+                return std::future::pending().await;
+            };
+            if quoter.market_data.is_standby() {
+                select! {
+                    _ = lease.wait_until_held() => {}
+                    _ = shutdown_rx.recv() => return Ok(()),
+                }
+                quoter.take_over().await?;
+            }
+            if let Err(e) = lease.hold(shutdown_rx).await {
+                error!("Lease task failed: {:?}", e);
+                return Err(e);
+            }
+            Ok(())
+        }
+    });
     
     // Flag to track if we need to break out of the main loop (e.g., after Ctrl+C)
     let mut should_exit = false;
@@ -183,6 +618,176 @@ async fn run_tasks(
                 Err(e) => error!("Ping task panicked: {:?}", e),
             }
         }
+        res = &mut features_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Features task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Features task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Features task panicked: {:?}", e),
+            }
+        }
+        res = &mut schedule_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Schedule task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Schedule task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Schedule task panicked: {:?}", e),
+            }
+        }
+        res = &mut subscription_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Subscription task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Subscription task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Subscription task panicked: {:?}", e),
+            }
+        }
+        res = &mut carry_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Carry task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Carry task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Carry task panicked: {:?}", e),
+            }
+        }
+        res = &mut account_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Account task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Account task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Account task panicked: {:?}", e),
+            }
+        }
+        res = &mut outbound_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Outbound task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Outbound task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Outbound task panicked: {:?}", e),
+            }
+        }
+        res = &mut uptime_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Uptime task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Uptime task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Uptime task panicked: {:?}", e),
+            }
+        }
+        res = &mut ticker_sample_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Ticker sample task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Ticker sample task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Ticker sample task panicked: {:?}", e),
+            }
+        }
+        res = &mut stats_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Stats task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Stats task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Stats task panicked: {:?}", e),
+            }
+        }
+        res = &mut eod_export_handle => {
+            match res {
+                Ok(Ok(_)) => info!("End-of-day export task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("End-of-day export task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("End-of-day export task panicked: {:?}", e),
+            }
+        }
+        res = &mut fair_value_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Fair value task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Fair value task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Fair value task panicked: {:?}", e),
+            }
+        }
+        res = &mut control_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Control task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Control task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Control task panicked: {:?}", e),
+            }
+        }
+        res = &mut producer_watchdog_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Producer watchdog task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Producer watchdog task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Producer watchdog task panicked: {:?}", e),
+            }
+        }
+        res = &mut spill_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Spill task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Spill task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Spill task panicked: {:?}", e),
+            }
+        }
+        res = &mut heartbeat_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Heartbeat task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Heartbeat task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Heartbeat task panicked: {:?}", e),
+            }
+        }
+        res = &mut clock_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Clock task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Clock task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Clock task panicked: {:?}", e),
+            }
+        }
+        res = &mut lease_handle => {
+            match res {
+                Ok(Ok(_)) => info!("Lease task completed successfully"),
+                Ok(Err(e)) => {
+                    error!("Lease task returned error: {:?}", e);
+                    err = Some(e);
+                },
+                Err(e) => error!("Lease task panicked: {:?}", e),
+            }
+        }
         _ = sigint.recv() => {
             warn!("SIGINT (Ctrl+C) received. Attempting graceful shutdown...");
             should_exit = true; // We'll exit the main loop after cleanup
@@ -203,7 +808,24 @@ async fn run_tasks(
     for (name, handle) in [
         ("quote", &mut quote_handle),
         ("listen", &mut listen_handle), 
-        ("ping", &mut ping_handle)
+        ("ping", &mut ping_handle),
+        ("features", &mut features_handle),
+        ("carry", &mut carry_handle),
+        ("account", &mut account_handle),
+        ("outbound", &mut outbound_handle),
+        ("schedule", &mut schedule_handle),
+        ("subscription", &mut subscription_handle),
+        ("uptime", &mut uptime_handle),
+        ("ticker_sample", &mut ticker_sample_handle),
+        ("stats", &mut stats_handle),
+        ("eod_export", &mut eod_export_handle),
+        ("fair_value", &mut fair_value_handle),
+        ("control", &mut control_handle),
+        ("producer_watchdog", &mut producer_watchdog_handle),
+        ("spill", &mut spill_handle),
+        ("heartbeat", &mut heartbeat_handle),
+        ("clock", &mut clock_handle),
+        ("lease", &mut lease_handle)
     ] {
         if !handle.is_finished() {
             info!("Aborting {} task", name);
@@ -214,7 +836,7 @@ async fn run_tasks(
     Ok((should_exit, err))
 }
 
-async fn cleanup(shared_client: Arc<Mutex<ThalexClient>>) {
+async fn cleanup(shared_client: Arc<Mutex<dyn SessionClient>>) {
     // Add a timeout to make sure cleanup operations don't hang
     let cleanup_future = async {
         // Use lock() to ensure we block and wait until we acquire the lock
@@ -233,9 +855,10 @@ async fn cleanup(shared_client: Arc<Mutex<ThalexClient>>) {
 
         if client.connected() {
             info!("Attempting to cancel session...");
+            let id = client.calls().allocate(RpcMethod::CancelSession, None);
             match tokio::time::timeout(
                 Duration::from_secs(3),
-                client.cancel_session(Some(CALL_ID_CANCEL_SESSION))
+                client.cancel_session(Some(id))
             ).await {
                 Ok(Ok(_)) => info!("Session cancellation successful."),
                 Ok(Err(e)) => error!("Failed to cancel session: {}", e),
@@ -254,6 +877,9 @@ async fn cleanup(shared_client: Arc<Mutex<ThalexClient>>) {
         } else {
             info!("Client is not connected, skipping cancellation and disconnect.");
         }
+
+        // The next session starts with a new socket; what this one couldn't write is stale
+        client.discard_outbound();
     };
     
     // Set an overall timeout for the entire cleanup process
@@ -0,0 +1,382 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::config_loader::EodExportConfig;
+use crate::domain::enums::{MakerTaker, OrderSide};
+use crate::infrastructure::persistence::object_store::{self, ObjectStore};
+use crate::domain::model::order::side_to_string;
+use crate::domain::model::trade::Trade;
+use crate::domain::model::trade_correction::{CorrectionKind, TradeCorrection};
+use crate::strategies::thalex_market_maker::{day_of, DailyStats};
+use super::export::{render, CsvRecord, ReportFormat};
+
+/// One of our fills, as journaled and exported
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Fill {
+    /// Exchange time of the fill (seconds since epoch)
+    pub time: f64,
+    pub trade_id: String,
+    pub order_id: String,
+    pub client_order_id: Option<u64>,
+    pub instrument_name: String,
+    pub direction: OrderSide,
+    pub price: f64,
+    pub amount: f64,
+    pub maker_taker: Option<MakerTaker>,
+}
+
+impl Fill {
+    pub fn new(trade: &Trade, direction: OrderSide) -> Self {
+        Self {
+            time: trade.time,
+            trade_id: trade.trade_id.clone(),
+            order_id: trade.order_id.clone(),
+            client_order_id: trade.client_order_id,
+            instrument_name: trade.instrument_name.clone(),
+            direction,
+            price: trade.price,
+            amount: trade.amount,
+            maker_taker: trade.maker_taker,
+        }
+    }
+}
+
+impl CsvRecord for Fill {
+    fn header() -> &'static [&'static str] {
+        &[
+            "time", "trade_id", "order_id", "client_order_id", "instrument_name",
+            "direction", "price", "amount", "maker_taker",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            DateTime::from_timestamp_millis((self.time * 1000.0).round() as i64)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            self.trade_id.clone(),
+            self.order_id.clone(),
+            self.client_order_id.map(|id| id.to_string()).unwrap_or_default(),
+            self.instrument_name.clone(),
+            side_to_string(&self.direction).to_string(),
+            self.price.to_string(),
+            self.amount.to_string(),
+            self.maker_taker.map(|role| role.as_str().to_string()).unwrap_or_default(),
+        ]
+    }
+}
+
+/// PnL and quoting statistics of one UTC day
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DaySummary {
+    /// UTC date, YYYY-MM-DD
+    pub date: String,
+    pub fills: u64,
+    pub volume: f64,
+    pub notional: f64,
+    pub realized_pnl: f64,
+    /// Position carried into the next day and its average entry price
+    pub position: f64,
+    pub avg_price: f64,
+    /// Time the quotes were observed (seconds)
+    pub observed_secs: f64,
+    /// Share of observed time with both sides quoted close to the mark
+    pub uptime_pct: f64,
+}
+
+impl From<&DailyStats> for DaySummary {
+    fn from(stats: &DailyStats) -> Self {
+        Self {
+            date: stats.date.clone(),
+            fills: stats.fills,
+            volume: stats.volume,
+            notional: stats.notional,
+            realized_pnl: stats.realized_pnl,
+            position: stats.position,
+            avg_price: stats.avg_price,
+            observed_secs: stats.uptime_observed_secs,
+            uptime_pct: if stats.uptime_observed_secs > 0.0 {
+                100.0 * stats.uptime_compliant_secs / stats.uptime_observed_secs
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+impl CsvRecord for DaySummary {
+    fn header() -> &'static [&'static str] {
+        &[
+            "date", "fills", "volume", "notional", "realized_pnl",
+            "position", "avg_price", "observed_secs", "uptime_pct",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.date.clone(),
+            self.fills.to_string(),
+            self.volume.to_string(),
+            format!("{:.2}", self.notional),
+            format!("{:.2}", self.realized_pnl),
+            self.position.to_string(),
+            format!("{:.2}", self.avg_price),
+            format!("{:.0}", self.observed_secs),
+            format!("{:.2}", self.uptime_pct),
+        ]
+    }
+}
+
+/// Local record of each day's fills and closing statistics, one directory per UTC day
+///
+/// Fills are appended as they happen and a day's statistics are written once it
+/// closes, so the export has the whole day even across restarts.
+#[derive(Clone, Debug)]
+pub struct Journal {
+    dir: PathBuf,
+}
+
+impl Journal {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn day_dir(&self, date: &str) -> PathBuf {
+        self.dir.join(date)
+    }
+
+    /// Append a fill to the journal of its day
+    pub fn append_fill(&self, fill: &Fill) -> Result<()> {
+        let dir = self.day_dir(&day_of(fill.time).to_string());
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("fills.jsonl");
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(fill)?)?;
+        Ok(())
+    }
+
+    /// Fills journaled on `date`, oldest first, with their busts and corrections applied
+    pub fn fills(&self, date: NaiveDate) -> Result<Vec<Fill>> {
+        let mut fills: Vec<Fill> = self.read_lines(date, "fills.jsonl")?;
+        for correction in self.corrections(date)? {
+            match correction.kind {
+                CorrectionKind::Bust => fills.retain(|fill| fill.trade_id != correction.trade_id),
+                CorrectionKind::Correction => {
+                    for fill in fills.iter_mut().filter(|fill| fill.trade_id == correction.trade_id) {
+                        fill.price = correction.corrected_price.unwrap_or(fill.price);
+                        fill.amount = correction.corrected_amount.unwrap_or(fill.amount);
+                    }
+                }
+            }
+        }
+        fills.sort_by(|a: &Fill, b: &Fill| a.time.total_cmp(&b.time));
+        Ok(fills)
+    }
+
+    /// Record a bust or correction of a fill journaled on `date`
+    pub fn append_correction(&self, date: NaiveDate, correction: &TradeCorrection) -> Result<()> {
+        let dir = self.day_dir(&date.to_string());
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("corrections.jsonl");
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(correction)?)?;
+        Ok(())
+    }
+
+    /// Busts and corrections of fills journaled on `date`, in the order they were recorded
+    pub fn corrections(&self, date: NaiveDate) -> Result<Vec<TradeCorrection>> {
+        self.read_lines(date, "corrections.jsonl")
+    }
+
+    /// Most recent journaled fill with `trade_id`, searching back `days` days from `date`
+    pub fn find_fill(&self, trade_id: &str, date: NaiveDate, days: u64) -> Result<Option<Fill>> {
+        for back in 0..=days {
+            let Some(day) = date.checked_sub_days(Days::new(back)) else {
+                break;
+            };
+            if let Some(fill) = self.fills(day)?.into_iter().find(|fill| fill.trade_id == trade_id) {
+                return Ok(Some(fill));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Records of a JSON lines file of `date`; empty when the file doesn't exist
+    fn read_lines<T: serde::de::DeserializeOwned>(&self, date: NaiveDate, name: &str) -> Result<Vec<T>> {
+        let path = self.day_dir(&date.to_string()).join(name);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut records = Vec::new();
+        for (n, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(record) => records.push(record),
+                // A crash can leave the last line half written
+                Err(e) => warn!("Skipping line {} of {}: {}", n + 1, path.display(), e),
+            }
+        }
+        Ok(records)
+    }
+
+    /// Record the final statistics of a closed day
+    pub fn close_day(&self, stats: &DailyStats) -> Result<()> {
+        stats.save(&self.day_dir(&stats.date).join("stats.json"))
+    }
+
+    /// Final statistics of `date`, None if the day wasn't closed in the journal
+    pub fn day_stats(&self, date: NaiveDate) -> Result<Option<DailyStats>> {
+        DailyStats::load(&self.day_dir(&date.to_string()).join("stats.json"))
+    }
+
+    /// Last day exported, None before the first export
+    pub fn last_exported(&self) -> Result<Option<NaiveDate>> {
+        let path = self.dir.join("last_export");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let date = content.trim().parse().with_context(|| format!("Invalid date in {}", path.display()))?;
+        Ok(Some(date))
+    }
+
+    /// Record `date` as exported; an export of an earlier day leaves the record as it is
+    pub fn mark_exported(&self, date: NaiveDate) -> Result<()> {
+        if self.last_exported()?.is_some_and(|last| last >= date) {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join("last_export");
+        fs::write(&path, date.to_string()).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Writes a day's fills and summary from the journal to report files, optionally
+/// uploading them
+pub struct EodExporter {
+    pub journal: Journal,
+    out_dir: PathBuf,
+    formats: Vec<ReportFormat>,
+    store: Option<Arc<dyn ObjectStore>>,
+}
+
+impl EodExporter {
+    pub fn new(journal: Journal, out_dir: impl Into<PathBuf>, formats: Vec<ReportFormat>, store: Option<Arc<dyn ObjectStore>>) -> Self {
+        Self { journal, out_dir: out_dir.into(), formats, store }
+    }
+
+    pub fn from_config(config: &EodExportConfig) -> Result<Self> {
+        let formats = config.formats.iter()
+            .map(|format| format.parse())
+            .collect::<Result<Vec<ReportFormat>>>()?;
+        let store = config.upload.as_ref().map(object_store::from_config).transpose()?;
+        Ok(Self::new(Journal::new(&config.journal_dir), &config.out_dir, formats, store))
+    }
+
+    /// Write the report files of `date`; returns the paths written
+    ///
+    /// A day without closing statistics in the journal is summarized from its fills alone.
+    pub fn write(&self, date: NaiveDate) -> Result<Vec<PathBuf>> {
+        let fills = self.journal.fills(date)?;
+        let stats = match self.journal.day_stats(date)? {
+            Some(stats) => stats,
+            None => DailyStats {
+                fills: fills.len() as u64,
+                volume: fills.iter().map(|fill| fill.amount).sum(),
+                notional: fills.iter().map(|fill| fill.price * fill.amount).sum(),
+                ..DailyStats::new(date)
+            },
+        };
+        let summary = [DaySummary::from(&stats)];
+
+        let dir = self.out_dir.join(date.to_string());
+        fs::create_dir_all(&dir)?;
+        let mut written = Vec::new();
+        for format in &self.formats {
+            let extension = extension(*format);
+            let path = dir.join(format!("fills.{}", extension));
+            fs::write(&path, render(&fills, *format)?).with_context(|| format!("Failed to write {}", path.display()))?;
+            written.push(path);
+            let path = dir.join(format!("summary.{}", extension));
+            fs::write(&path, render(&summary, *format)?).with_context(|| format!("Failed to write {}", path.display()))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Write the report files of `date` and upload them when an upload target is set
+    pub async fn export(&self, date: NaiveDate) -> Result<Vec<PathBuf>> {
+        let written = self.write(date)?;
+        info!("Exported {} to {}", date, self.out_dir.join(date.to_string()).display());
+        if let Some(store) = &self.store {
+            for path in &written {
+                let key = format!("{}/{}", date, path.file_name().and_then(|n| n.to_str()).unwrap_or_default());
+                store.put_file(path, &key).await?;
+            }
+            info!("Uploaded {} report files of {} to {}", written.len(), date, store.location());
+        }
+        self.journal.mark_exported(date)?;
+        Ok(written)
+    }
+
+    /// Export every day due by `now` with the export at `at` that wasn't exported yet,
+    /// oldest first; returns the days exported
+    ///
+    /// Runs missed while the bot was down or reconnecting are made up for here. The
+    /// first failed day stops the catch-up so it's retried before any later one.
+    pub async fn catch_up(&self, now: DateTime<Utc>, at: NaiveTime) -> Result<Vec<NaiveDate>> {
+        let mut exported = Vec::new();
+        for date in due_exports(now, at, self.journal.last_exported()?) {
+            self.export(date).await.with_context(|| format!("End-of-day export of {} failed", date))?;
+            exported.push(date);
+        }
+        Ok(exported)
+    }
+}
+
+fn extension(format: ReportFormat) -> &'static str {
+    match format {
+        ReportFormat::Csv => "csv",
+        ReportFormat::Json => "json",
+    }
+}
+
+/// Next time of day `at` (UTC) strictly after `now`
+pub fn next_run(now: DateTime<Utc>, at: NaiveTime) -> DateTime<Utc> {
+    let today = now.date_naive().and_time(at).and_utc();
+    if today > now {
+        today
+    } else {
+        today + chrono::Duration::days(1)
+    }
+}
+
+/// Day an export running at `run` covers: the UTC day before it
+pub fn export_date(run: DateTime<Utc>) -> NaiveDate {
+    run.date_naive().checked_sub_days(Days::new(1)).unwrap_or_default()
+}
+
+/// Days due for export by `now` with the export at `at`, after the last one exported
+///
+/// Before the first export only the most recent due day is, rather than the whole journal.
+pub fn due_exports(now: DateTime<Utc>, at: NaiveTime, last_exported: Option<NaiveDate>) -> Vec<NaiveDate> {
+    let latest = export_date(next_run(now, at) - chrono::Duration::days(1));
+    let first = match last_exported {
+        Some(last) => last.succ_opt().unwrap_or(last),
+        None => latest,
+    };
+    first.iter_days().take_while(|date| *date <= latest).collect()
+}
+
+/// Parse the export time of day, "HH:MM" UTC
+pub fn parse_time(s: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M").with_context(|| format!("Invalid export time '{}'", s))
+}
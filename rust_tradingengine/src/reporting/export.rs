@@ -0,0 +1,70 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// Output format of a report
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Header row followed by one row per record
+    Csv,
+    /// Array of objects
+    Json,
+}
+
+impl ReportFormat {
+    /// Pick the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(ReportFormat::Csv),
+            "json" => Some(ReportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ReportFormat::Csv),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(anyhow!("Unknown report format: {}", s)),
+        }
+    }
+}
+
+/// A flat record that can be written as one CSV row
+pub trait CsvRecord {
+    /// Column names, in output order
+    fn header() -> &'static [&'static str];
+
+    /// Cell values, in the same order as `header`
+    fn cells(&self) -> Vec<String>;
+}
+
+/// Render records as CSV or JSON
+pub fn render<T: Serialize + CsvRecord>(rows: &[T], format: ReportFormat) -> Result<String> {
+    match format {
+        ReportFormat::Json => Ok(serde_json::to_string_pretty(rows)?),
+        ReportFormat::Csv => {
+            let mut out = T::header().join(",");
+            out.push('\n');
+            for row in rows {
+                let cells: Vec<String> = row.cells().iter().map(|cell| csv_escape(cell)).collect();
+                out.push_str(&cells.join(","));
+                out.push('\n');
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Quote a cell if it contains a separator, quote or line break
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
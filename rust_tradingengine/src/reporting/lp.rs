@@ -0,0 +1,276 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::domain::enums::{MakerTaker, OrderSide, OrderStatus};
+use crate::domain::model::order::Order;
+use crate::domain::model::ticker::Ticker;
+use crate::domain::model::trade::Trade;
+use crate::strategies::thalex_market_maker::day_of;
+use super::export::CsvRecord;
+
+/// Longest gap between ticker samples that still counts as observed time (seconds)
+///
+/// Longer gaps mean missing data rather than a quiet market, so they are left out of
+/// the denominator instead of being credited to whatever state preceded them.
+pub const MAX_SAMPLE_GAP_SEC: f64 = 60.0;
+
+/// One day of quoting statistics for one instrument, in the shape MM programs ask for
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LpDailyStats {
+    /// UTC date, YYYY-MM-DD
+    pub date: String,
+    pub instrument_name: String,
+
+    /// Time covered by ticker samples (seconds)
+    pub observed_secs: f64,
+
+    /// Share of observed time with both sides quoted within the allowed distance of the mark
+    pub uptime_pct: f64,
+
+    /// Share of observed time with an order at the best bid / best ask
+    pub at_best_bid_pct: f64,
+    pub at_best_ask_pct: f64,
+
+    /// Time-weighted average resting size at the best bid / best ask
+    pub avg_size_at_best_bid: f64,
+    pub avg_size_at_best_ask: f64,
+
+    /// Filled volume by liquidity role
+    pub maker_volume: f64,
+    pub taker_volume: f64,
+
+    /// Filled notional across both roles
+    pub notional: f64,
+    pub trade_count: u64,
+}
+
+impl CsvRecord for LpDailyStats {
+    fn header() -> &'static [&'static str] {
+        &[
+            "date", "instrument_name", "observed_secs", "uptime_pct",
+            "at_best_bid_pct", "at_best_ask_pct", "avg_size_at_best_bid", "avg_size_at_best_ask",
+            "maker_volume", "taker_volume", "notional", "trade_count",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.date.clone(),
+            self.instrument_name.clone(),
+            format!("{:.0}", self.observed_secs),
+            format!("{:.2}", self.uptime_pct),
+            format!("{:.2}", self.at_best_bid_pct),
+            format!("{:.2}", self.at_best_ask_pct),
+            format!("{:.4}", self.avg_size_at_best_bid),
+            format!("{:.4}", self.avg_size_at_best_ask),
+            self.maker_volume.to_string(),
+            self.taker_volume.to_string(),
+            format!("{:.2}", self.notional),
+            self.trade_count.to_string(),
+        ]
+    }
+}
+
+/// Last known state of one of our orders
+#[derive(Clone, Debug)]
+struct RestingOrder {
+    instrument: String,
+    side: OrderSide,
+    price: f64,
+    remaining: f64,
+}
+
+/// Per-day accumulator
+#[derive(Default)]
+struct DayTotals {
+    observed: f64,
+    two_sided: f64,
+    at_bid: f64,
+    at_ask: f64,
+    size_at_bid: f64,
+    size_at_ask: f64,
+    maker_volume: f64,
+    taker_volume: f64,
+    notional: f64,
+    trades: u64,
+}
+
+impl DayTotals {
+    /// Credit `secs` of observed time with the quoting state in force during them
+    fn credit(&mut self, secs: f64, two_sided: bool, bid_size: f64, ask_size: f64) {
+        self.observed += secs;
+        if two_sided {
+            self.two_sided += secs;
+        }
+        if bid_size > 0.0 {
+            self.at_bid += secs;
+        }
+        if ask_size > 0.0 {
+            self.at_ask += secs;
+        }
+        self.size_at_bid += bid_size * secs;
+        self.size_at_ask += ask_size * secs;
+    }
+}
+
+/// Market state as of the last ticker sample
+#[derive(Clone, Copy)]
+struct Sample {
+    time: f64,
+    mark: f64,
+    best_bid: f64,
+    best_ask: f64,
+}
+
+/// Replays persisted acks, tickers and trades into daily quoting statistics
+///
+/// Events must be fed in time order. Each ticker sample closes the interval since the
+/// previous one, which is credited with the order state in force during it; an interval
+/// spanning midnight is split between the two days. Uptime is measured like the bot's
+/// `UptimeTracker`: both sides quoted within a number of ticks of the mark.
+pub struct LpReportBuilder {
+    /// Quotes further than this from the mark (in ticks) don't count towards uptime
+    max_distance_ticks: f64,
+    tick_sizes: HashMap<String, f64>,
+
+    orders: HashMap<String, RestingOrder>,
+    last: HashMap<String, Sample>,
+    days: BTreeMap<(String, String), DayTotals>,
+}
+
+impl LpReportBuilder {
+    pub fn new(max_distance_ticks: f64) -> Self {
+        Self {
+            max_distance_ticks,
+            tick_sizes: HashMap::new(),
+            orders: HashMap::new(),
+            last: HashMap::new(),
+            days: BTreeMap::new(),
+        }
+    }
+
+    /// Tick size of an instrument; without one, its quotes never count towards uptime
+    pub fn set_tick_size(&mut self, instrument: &str, tick_size: f64) {
+        self.tick_sizes.insert(instrument.to_string(), tick_size);
+    }
+
+    /// Apply an order update
+    pub fn on_ack(&mut self, ack: &Order) {
+        let open = matches!(ack.status, OrderStatus::Open | OrderStatus::PartiallyFilled);
+        match (open, ack.price) {
+            (true, Some(price)) => {
+                self.orders.insert(ack.order_id.clone(), RestingOrder {
+                    instrument: ack.instrument_name.clone(),
+                    side: ack.direction.clone(),
+                    price,
+                    remaining: ack.remaining_amount,
+                });
+            }
+            _ => {
+                self.orders.remove(&ack.order_id);
+            }
+        }
+    }
+
+    /// Close the interval since the previous sample of this instrument
+    pub fn on_ticker(&mut self, ticker: &Ticker) {
+        let time = ticker.mark_timestamp;
+        if let Some(prev) = self.last.get(&ticker.instrument_name).copied() {
+            let elapsed = time - prev.time;
+            if elapsed > 0.0 && elapsed <= MAX_SAMPLE_GAP_SEC {
+                let (two_sided, bid_size, ask_size) = self.quoted(&ticker.instrument_name, &prev);
+                let mut start = prev.time;
+                while start < time {
+                    let end = time.min(next_midnight(start));
+                    self.day(&ticker.instrument_name, start).credit(end - start, two_sided, bid_size, ask_size);
+                    start = end;
+                }
+            }
+        }
+        if ticker.mark_price > 0.0 && ticker.best_bid_price > 0.0 && ticker.best_ask_price > 0.0 {
+            self.last.insert(ticker.instrument_name.clone(), Sample {
+                time,
+                mark: ticker.mark_price,
+                best_bid: ticker.best_bid_price,
+                best_ask: ticker.best_ask_price,
+            });
+        } else {
+            // No two-sided market to measure against
+            self.last.remove(&ticker.instrument_name);
+        }
+    }
+
+    /// Count one of our fills
+    pub fn on_trade(&mut self, trade: &Trade) {
+        let day = self.day(&trade.instrument_name, trade.time);
+        match trade.maker_taker {
+            Some(MakerTaker::Maker) => day.maker_volume += trade.amount,
+            Some(MakerTaker::Taker) => day.taker_volume += trade.amount,
+            None => {}
+        }
+        day.notional += trade.amount * trade.price;
+        day.trades += 1;
+    }
+
+    /// Statistics per day and instrument, ordered by date
+    pub fn finish(self) -> Vec<LpDailyStats> {
+        self.days
+            .into_iter()
+            .map(|((date, instrument_name), d)| {
+                let pct = |secs: f64| if d.observed > 0.0 { 100.0 * secs / d.observed } else { 0.0 };
+                let avg = |size_secs: f64| if d.observed > 0.0 { size_secs / d.observed } else { 0.0 };
+                LpDailyStats {
+                    date,
+                    instrument_name,
+                    observed_secs: d.observed,
+                    uptime_pct: pct(d.two_sided),
+                    at_best_bid_pct: pct(d.at_bid),
+                    at_best_ask_pct: pct(d.at_ask),
+                    avg_size_at_best_bid: avg(d.size_at_bid),
+                    avg_size_at_best_ask: avg(d.size_at_ask),
+                    maker_volume: d.maker_volume,
+                    taker_volume: d.taker_volume,
+                    notional: d.notional,
+                    trade_count: d.trades,
+                }
+            })
+            .collect()
+    }
+
+    /// Whether both sides are quoted near the mark, and our size at the best bid/ask
+    fn quoted(&self, instrument: &str, sample: &Sample) -> (bool, f64, f64) {
+        // Unknown tick size: nothing is near enough
+        let max_distance = self.tick_sizes.get(instrument).map_or(-1.0, |tick| self.max_distance_ticks * tick);
+
+        let (mut bid_near, mut ask_near) = (false, false);
+        let (mut bid_size, mut ask_size) = (0.0, 0.0);
+        for order in self.orders.values().filter(|o| o.instrument == instrument) {
+            match order.side {
+                OrderSide::Buy => {
+                    bid_near |= (order.price - sample.mark).abs() <= max_distance;
+                    if order.price >= sample.best_bid {
+                        bid_size += order.remaining;
+                    }
+                }
+                OrderSide::Sell => {
+                    ask_near |= (order.price - sample.mark).abs() <= max_distance;
+                    if order.price <= sample.best_ask {
+                        ask_size += order.remaining;
+                    }
+                }
+            }
+        }
+        (bid_near && ask_near, bid_size, ask_size)
+    }
+
+    fn day(&mut self, instrument: &str, time: f64) -> &mut DayTotals {
+        self.days.entry((day_of(time).to_string(), instrument.to_string())).or_default()
+    }
+}
+
+/// Start of the UTC day after the one `time` falls on
+fn next_midnight(time: f64) -> f64 {
+    day_of(time).succ_opt()
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map_or(f64::INFINITY, |midnight| midnight.and_utc().timestamp() as f64)
+}
@@ -0,0 +1,9 @@
+pub mod eod;
+pub mod export;
+pub mod lp;
+pub mod variants;
+
+pub use export::{render, CsvRecord, ReportFormat};
+pub use eod::{DaySummary, EodExporter, Fill, Journal};
+pub use lp::{LpDailyStats, LpReportBuilder};
+pub use variants::{VariantDailyStats, VariantReportBuilder};
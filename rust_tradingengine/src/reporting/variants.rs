@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::domain::enums::{MakerTaker, OrderSide};
+use crate::domain::model::order::Order;
+use crate::domain::model::trade::Trade;
+use crate::strategies::thalex_market_maker::{day_of, DailyStats};
+use super::export::CsvRecord;
+
+/// One day of an experiment variant's trading on one instrument
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct VariantDailyStats {
+    /// UTC date, YYYY-MM-DD
+    pub date: String,
+    pub variant_id: String,
+    pub instrument_name: String,
+
+    /// Orders placed under the variant
+    pub orders: u64,
+    pub trade_count: u64,
+
+    /// Filled volume by liquidity role
+    pub maker_volume: f64,
+    pub taker_volume: f64,
+    pub notional: f64,
+
+    /// PnL of the variant's fills against its own average entry
+    pub realized_pnl: f64,
+
+    /// Net position built by the variant's fills at the end of the day
+    pub position: f64,
+}
+
+impl CsvRecord for VariantDailyStats {
+    fn header() -> &'static [&'static str] {
+        &[
+            "date", "variant_id", "instrument_name", "orders", "trade_count",
+            "maker_volume", "taker_volume", "notional", "realized_pnl", "position",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.date.clone(),
+            self.variant_id.clone(),
+            self.instrument_name.clone(),
+            self.orders.to_string(),
+            self.trade_count.to_string(),
+            self.maker_volume.to_string(),
+            self.taker_volume.to_string(),
+            format!("{:.2}", self.notional),
+            format!("{:.2}", self.realized_pnl),
+            self.position.to_string(),
+        ]
+    }
+}
+
+/// Replays persisted acks and trades into daily statistics per experiment variant
+///
+/// Each variant keeps its own position and average entry, so its PnL only reflects its
+/// own fills. Events must be fed in time order; records without a variant are left out.
+#[derive(Default)]
+pub struct VariantReportBuilder {
+    /// Variant and side of each tagged order, by exchange order id
+    orders: HashMap<String, (String, OrderSide)>,
+    books: HashMap<(String, String), DailyStats>,
+    days: BTreeMap<(String, String, String), VariantDailyStats>,
+}
+
+impl VariantReportBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply an order update; the first update of an order counts it as placed
+    pub fn on_ack(&mut self, ack: &Order) {
+        let Some(variant_id) = &ack.variant_id else {
+            return;
+        };
+        if self.orders.contains_key(&ack.order_id) {
+            return;
+        }
+        self.orders.insert(ack.order_id.clone(), (variant_id.clone(), ack.direction.clone()));
+        self.day(variant_id, &ack.instrument_name, ack.create_time).orders += 1;
+    }
+
+    /// Count one of our fills against its variant
+    ///
+    /// Trades recorded before their order's ack can't be given a side and only count
+    /// towards volume.
+    pub fn on_trade(&mut self, trade: &Trade) {
+        let order = self.orders.get(&trade.order_id).cloned();
+        let Some(variant_id) = trade.variant_id.clone().or_else(|| order.as_ref().map(|(variant_id, _)| variant_id.clone())) else {
+            return;
+        };
+
+        let mut realized = 0.0;
+        let mut position = None;
+        if let Some((_, side)) = &order {
+            let book = self.books
+                .entry((variant_id.clone(), trade.instrument_name.clone()))
+                .or_insert_with(|| DailyStats::new(day_of(trade.time)));
+            let realized_before = book.realized_pnl;
+            let closed_day = book.record_fill(side, trade.price, trade.amount, trade.time);
+            // A closed day ends at realized_before, so the fill's PnL is all in the new day
+            realized = match closed_day {
+                Some(_) => book.realized_pnl,
+                None => book.realized_pnl - realized_before,
+            };
+            position = Some(book.position);
+        }
+
+        let day = self.day(&variant_id, &trade.instrument_name, trade.time);
+        match trade.maker_taker {
+            Some(MakerTaker::Maker) => day.maker_volume += trade.amount,
+            Some(MakerTaker::Taker) => day.taker_volume += trade.amount,
+            None => {}
+        }
+        day.notional += trade.amount * trade.price;
+        day.trade_count += 1;
+        day.realized_pnl += realized;
+        if let Some(position) = position {
+            day.position = position;
+        }
+    }
+
+    /// Statistics per day, variant and instrument, ordered by date
+    pub fn finish(self) -> Vec<VariantDailyStats> {
+        self.days.into_values().collect()
+    }
+
+    fn day(&mut self, variant_id: &str, instrument: &str, time: f64) -> &mut VariantDailyStats {
+        let date = DateTime::<Utc>::from_timestamp(time as i64, 0)
+            .unwrap_or_default()
+            .format("%Y-%m-%d")
+            .to_string();
+        self.days
+            .entry((date.clone(), variant_id.to_string(), instrument.to_string()))
+            .or_insert_with(|| VariantDailyStats {
+                date,
+                variant_id: variant_id.to_string(),
+                instrument_name: instrument.to_string(),
+                ..Default::default()
+            })
+    }
+}
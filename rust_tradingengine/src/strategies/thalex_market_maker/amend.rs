@@ -0,0 +1,79 @@
+use anyhow::{anyhow, Result};
+
+use crate::config_loader::InstrumentStrategyConfig;
+
+use super::config;
+
+/// The parts of a resting order an amend changes; the others are sent at their current value
+///
+/// Leaving the amount out of a re-price keeps a partially filled order's remaining
+/// size as it is instead of resetting it to the full quote size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Amendment {
+    pub price: Option<f64>,
+    pub amount: Option<f64>,
+}
+
+/// Decides when a resting order is worth amending towards its desired quote
+///
+/// Each level has its own price threshold, so deep levels that rarely fill can be
+/// left alone on small moves while the top of book follows the market closely.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmendPolicy {
+    /// Price move (ticks) per level; the last value applies to deeper levels
+    thresholds: Vec<f64>,
+
+    /// Relative size reduction tolerated before amending
+    size_tolerance: f64,
+}
+
+impl Default for AmendPolicy {
+    fn default() -> Self {
+        Self {
+            thresholds: vec![config::AMEND_THRESHOLD],
+            size_tolerance: 0.0,
+        }
+    }
+}
+
+impl AmendPolicy {
+    pub fn new(thresholds: Vec<f64>, size_tolerance: f64) -> Result<Self> {
+        if thresholds.iter().any(|t| !t.is_finite() || *t < 0.0) {
+            return Err(anyhow!("Amend thresholds must be non-negative: {:?}", thresholds));
+        }
+        if !(0.0..1.0).contains(&size_tolerance) {
+            return Err(anyhow!("Size tolerance must be in [0, 1): {}", size_tolerance));
+        }
+        let thresholds = if thresholds.is_empty() { vec![config::AMEND_THRESHOLD] } else { thresholds };
+        Ok(Self { thresholds, size_tolerance })
+    }
+
+    pub fn from_config(instrument: &InstrumentStrategyConfig) -> Result<Self> {
+        Self::new(instrument.amend_thresholds.clone(), instrument.size_tolerance)
+            .map_err(|e| anyhow!("Invalid amend settings for {}: {}", instrument.instrument, e))
+    }
+
+    /// Price threshold (ticks) for a level
+    pub fn threshold(&self, level: usize) -> f64 {
+        self.thresholds[level.min(self.thresholds.len() - 1)]
+    }
+
+    /// Whether an order at `level` resting at `price`/`amount` should be amended to the
+    /// desired quote
+    ///
+    /// Size is only amended down, when the risk limits shrank the quote by more than
+    /// the tolerance.
+    pub fn needs_amend(&self, level: usize, price: f64, amount: f64, desired_price: f64, desired_amount: f64, tick: f64) -> bool {
+        self.amendment(level, price, amount, desired_price, desired_amount, tick).is_some()
+    }
+
+    /// What to amend on an order at `level` resting at `price`/`amount`: the price when it
+    /// moved past the level's threshold, the amount when it shrank past the tolerance
+    pub fn amendment(&self, level: usize, price: f64, amount: f64, desired_price: f64, desired_amount: f64, tick: f64) -> Option<Amendment> {
+        let amendment = Amendment {
+            price: ((price - desired_price).abs() > self.threshold(level) * tick).then_some(desired_price),
+            amount: (desired_amount < amount * (1.0 - self.size_tolerance)).then_some(desired_amount),
+        };
+        (amendment.price.is_some() || amendment.amount.is_some()).then_some(amendment)
+    }
+}
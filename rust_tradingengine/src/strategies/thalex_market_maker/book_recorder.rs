@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::domain::model::book::{BookLevelUpdate, BookSide, BookUpdateKind};
+use crate::infrastructure::exchange::thalex::channel::Channel;
+
+/// Levels of one side, keyed by price bits
+type Levels = HashMap<u64, (f64, f64)>;
+
+#[derive(Default)]
+struct BookState {
+    sequence: i64,
+    /// Time of the last snapshot, None while the next update must be one
+    last_snapshot: Option<f64>,
+    bids: Levels,
+    asks: Levels,
+}
+
+/// Turns book notifications into periodic full snapshots and level deltas for persistence
+///
+/// Thalex book channels publish the top levels in full on every update, so deltas
+/// are the difference to the previous update; a level leaving the subscribed
+/// depth shows up as a removal. Books are kept per channel, since subscriptions of
+/// one instrument with another depth or grouping publish different levels.
+pub struct BookRecorder {
+    snapshot_interval_sec: f64,
+    books: HashMap<Channel, BookState>,
+}
+
+impl BookRecorder {
+    pub fn new(snapshot_interval_sec: f64) -> Self {
+        Self {
+            snapshot_interval_sec,
+            books: HashMap::new(),
+        }
+    }
+
+    /// Record the next update of every channel as a snapshot; sequences carry on
+    pub fn clear(&mut self) {
+        for state in self.books.values_mut() {
+            state.last_snapshot = None;
+        }
+    }
+
+    /// Record the next update of a channel as a snapshot, after some of its updates were lost
+    pub fn resync(&mut self, channel: &Channel) {
+        if let Some(state) = self.books.get_mut(channel) {
+            state.last_snapshot = None;
+        }
+    }
+
+    /// Record a book notification of `channel` received at `now`
+    pub fn on_book(&mut self, channel: &Channel, notification: &Value, now: f64) -> Result<Vec<BookLevelUpdate>> {
+        let instrument = channel.instrument().ok_or_else(|| anyhow!("Not a book channel: {}", channel))?;
+        let bids = parse_levels(notification, "bids")?;
+        let asks = parse_levels(notification, "asks")?;
+        let exchange_time = notification.get("time").and_then(|v| v.as_f64());
+
+        let state = self.books.entry(channel.clone()).or_default();
+        state.sequence += 1;
+        let record = |kind, side, price, amount| BookLevelUpdate {
+            instrument_name: instrument.to_string(),
+            kind,
+            sequence: state.sequence,
+            side,
+            price,
+            amount,
+            exchange_time,
+            timestamp: now,
+            processing_timestamp: Some(now),
+        };
+
+        let mut updates = Vec::new();
+        if state.last_snapshot.is_none_or(|at| now - at >= self.snapshot_interval_sec) {
+            for (side, levels) in [(BookSide::Bid, &bids), (BookSide::Ask, &asks)] {
+                let mut levels: Vec<(f64, f64)> = levels.values().copied().collect();
+                levels.sort_by(|a, b| match side {
+                    BookSide::Bid => b.0.total_cmp(&a.0),
+                    BookSide::Ask => a.0.total_cmp(&b.0),
+                });
+                updates.extend(levels.into_iter().map(|(price, amount)| record(BookUpdateKind::Snapshot, side, price, amount)));
+            }
+            state.last_snapshot = Some(now);
+        } else {
+            for (side, old, new) in [(BookSide::Bid, &state.bids, &bids), (BookSide::Ask, &state.asks, &asks)] {
+                let mut changes: Vec<(f64, f64)> = new.iter()
+                    .filter(|(key, level)| old.get(key) != Some(level))
+                    .map(|(_, level)| *level)
+                    .chain(old.iter()
+                        .filter(|(key, _)| !new.contains_key(key))
+                        .map(|(_, (price, _))| (*price, 0.0)))
+                    .collect();
+                changes.sort_by(|a, b| a.0.total_cmp(&b.0));
+                updates.extend(changes.into_iter().map(|(price, amount)| record(BookUpdateKind::Delta, side, price, amount)));
+            }
+        }
+
+        state.bids = bids;
+        state.asks = asks;
+        Ok(updates)
+    }
+}
+
+/// Parse `[[price, amount, ...], ...]` levels; a missing side is empty
+fn parse_levels(notification: &Value, side: &str) -> Result<Levels> {
+    let Some(levels) = notification.get(side) else {
+        return Ok(Levels::new());
+    };
+    let levels = levels.as_array().ok_or_else(|| anyhow!("Book {} is not an array", side))?;
+    levels.iter()
+        .map(|level| {
+            let price = level.get(0).and_then(|v| v.as_f64());
+            let amount = level.get(1).and_then(|v| v.as_f64());
+            match (price, amount) {
+                (Some(price), Some(amount)) => Ok((price.to_bits(), (price, amount))),
+                _ => Err(anyhow!("Malformed book {} level: {}", side, level)),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+
+use crate::domain::model::funding_basis::FundingBasis;
+use crate::domain::model::ticker::Ticker;
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+/// Rolling basis of a perpetual against its index, and the funding our position earns
+///
+/// The cumulative funding is what the exchange paid or charged the account, as reported by
+/// its funding notifications, and runs across sessions for as long as the tracker is kept.
+/// A positive rate has longs pay shorts `rate * notional` per funding period.
+#[derive(Debug, Clone)]
+pub struct CarryTracker {
+    window_secs: f64,
+    funding_period_secs: f64,
+
+    /// Basis samples in the window as (time, basis in bps)
+    basis: VecDeque<(f64, f64)>,
+
+    /// Funding received since the tracker started, in quote currency
+    cumulative_funding: f64,
+}
+
+impl CarryTracker {
+    pub fn new(window_secs: f64, funding_period_secs: f64) -> Self {
+        Self {
+            window_secs,
+            funding_period_secs,
+            basis: VecDeque::new(),
+            cumulative_funding: 0.0,
+        }
+    }
+
+    /// Funding received since the tracker started, negative when paid
+    pub fn cumulative_funding(&self) -> f64 {
+        self.cumulative_funding
+    }
+
+    /// Count a funding payment the exchange reported; negative when the account paid it
+    pub fn record_funding(&mut self, amount: f64) {
+        self.cumulative_funding += amount;
+    }
+
+    /// Record the market and our position at `now`; None while the index is unknown
+    pub fn sample(&mut self, now: f64, ticker: &Ticker, position: f64) -> Option<FundingBasis> {
+        if ticker.index_price <= 0.0 {
+            return None;
+        }
+        let basis = ticker.mark_price - ticker.index_price;
+        let basis_bps = 10_000.0 * basis / ticker.index_price;
+        self.basis.push_back((now, basis_bps));
+        while self.basis.front().is_some_and(|(time, _)| *time < now - self.window_secs) {
+            self.basis.pop_front();
+        }
+        let rolling_basis_bps = self.basis.iter().map(|(_, bps)| bps).sum::<f64>() / self.basis.len() as f64;
+
+        let annualized_funding_rate = ticker.funding_rate * SECONDS_PER_YEAR / self.funding_period_secs;
+        // Longs pay a positive rate, shorts receive it
+        let annualized_carry = if position > 0.0 {
+            -annualized_funding_rate
+        } else if position < 0.0 {
+            annualized_funding_rate
+        } else {
+            0.0
+        };
+
+        Some(FundingBasis {
+            instrument_name: ticker.instrument_name.clone(),
+            timestamp: now,
+            window_secs: self.window_secs,
+            mark_price: ticker.mark_price,
+            index_price: ticker.index_price,
+            basis,
+            basis_bps,
+            rolling_basis_bps,
+            funding_rate: ticker.funding_rate,
+            annualized_funding_rate,
+            position,
+            cumulative_funding: self.cumulative_funding,
+            annualized_carry,
+            processing_timestamp: None,
+        })
+    }
+}
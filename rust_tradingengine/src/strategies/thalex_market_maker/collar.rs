@@ -0,0 +1,121 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use std::collections::VecDeque;
+
+use crate::config_loader::CollarConfig;
+use crate::domain::model::quote::SideQuote;
+use crate::infrastructure::metrics;
+
+/// 1 while the collar is tight or moving fast and quote sizes are reduced, 0 otherwise
+pub const METRIC_COLLAR_STRESSED: &str = "quote.collar_stressed";
+
+/// Keeps quotes inside the exchange price collar
+///
+/// Orders priced outside `[collar_low, collar_high]` are rejected by the exchange, so
+/// every level is clamped to `buffer_ticks` inside it. While the collar is narrower than
+/// `tight_bps` of the mark, or its midpoint moved more than `fast_move_bps` within the
+/// window, the quoted sizes are scaled by `size_multiplier`.
+#[derive(Debug, Clone)]
+pub struct CollarGuard {
+    buffer_ticks: f64,
+    tight_bps: f64,
+    fast_move_bps: f64,
+    window_sec: f64,
+    size_multiplier: f64,
+
+    /// Collar midpoints within the window, oldest first
+    history: VecDeque<(f64, f64)>,
+
+    /// Whether the last quotes were reduced, to log transitions only
+    stressed: bool,
+}
+
+impl CollarGuard {
+    pub fn new(buffer_ticks: f64, tight_bps: f64, fast_move_bps: f64, window_sec: f64, size_multiplier: f64) -> Result<Self> {
+        if !buffer_ticks.is_finite() || buffer_ticks < 0.0 {
+            return Err(anyhow!("buffer_ticks must not be negative: {}", buffer_ticks));
+        }
+        if !tight_bps.is_finite() || tight_bps < 0.0 || !fast_move_bps.is_finite() || fast_move_bps <= 0.0 {
+            return Err(anyhow!("Invalid collar thresholds: tight {} bps, fast move {} bps", tight_bps, fast_move_bps));
+        }
+        if !window_sec.is_finite() || window_sec <= 0.0 {
+            return Err(anyhow!("window_sec must be positive: {}", window_sec));
+        }
+        if !(size_multiplier > 0.0 && size_multiplier <= 1.0) {
+            return Err(anyhow!("size_multiplier must be in (0, 1]: {}", size_multiplier));
+        }
+        Ok(Self {
+            buffer_ticks,
+            tight_bps,
+            fast_move_bps,
+            window_sec,
+            size_multiplier,
+            history: VecDeque::new(),
+            stressed: false,
+        })
+    }
+
+    pub fn from_config(collar: &CollarConfig) -> Result<Self> {
+        Self::new(collar.buffer_ticks, collar.tight_bps, collar.fast_move_bps, collar.window_sec, collar.size_multiplier)
+    }
+
+    /// Clamp `quotes` ([bids, asks]) inside the collar observed at `at`
+    ///
+    /// Quotes pass unchanged while the ticker carries no collar. Levels left crossing the
+    /// other side after clamping are dropped, and both sides are pulled when the collar is
+    /// too narrow to hold the buffer.
+    pub fn apply(&mut self, quotes: Vec<Vec<SideQuote>>, low: f64, high: f64, mark: f64, tick: f64, at: f64) -> Vec<Vec<SideQuote>> {
+        if !(low > 0.0 && high > low && mark > 0.0 && tick > 0.0) {
+            return quotes;
+        }
+        self.observe((low + high) / 2.0, at);
+
+        let floor = tick * ((low + self.buffer_ticks * tick) / tick - 1e-9).ceil();
+        let ceiling = tick * ((high - self.buffer_ticks * tick) / tick + 1e-9).floor();
+        if floor > ceiling {
+            warn!("Collar [{}, {}] too narrow for a {} tick buffer, pulling quotes", low, high, self.buffer_ticks);
+            return quotes.iter().map(|_| Vec::new()).collect();
+        }
+
+        let stressed = self.is_stressed(low, high, mark);
+        if stressed != self.stressed {
+            if stressed {
+                warn!("Collar [{}, {}] is tight or moving fast, reducing quote sizes by {}", low, high, self.size_multiplier);
+            } else {
+                info!("Collar [{}, {}] settled, quoting full size", low, high);
+            }
+            self.stressed = stressed;
+        }
+        metrics::global().set_gauge(METRIC_COLLAR_STRESSED, if stressed { 1.0 } else { 0.0 });
+        let multiplier = if stressed { self.size_multiplier } else { 1.0 };
+
+        let mut clamped: Vec<Vec<SideQuote>> = quotes.into_iter()
+            .map(|levels| levels.into_iter()
+                .map(|quote| SideQuote::new(quote.price.clamp(floor, ceiling), quote.amount * multiplier))
+                .collect())
+            .collect();
+        if let [bids, asks] = clamped.as_mut_slice() {
+            let best_ask = asks.iter().map(|quote| quote.price).fold(f64::INFINITY, f64::min);
+            bids.retain(|quote| quote.price < best_ask);
+            let best_bid = bids.iter().map(|quote| quote.price).fold(f64::NEG_INFINITY, f64::max);
+            asks.retain(|quote| quote.price > best_bid);
+        }
+        clamped
+    }
+
+    /// Whether the collar is narrower than `tight_bps` or moved more than `fast_move_bps` within the window
+    pub fn is_stressed(&self, low: f64, high: f64, mark: f64) -> bool {
+        let width_bps = 10_000.0 * (high - low) / mark;
+        let (min, max) = self.history.iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), (_, mid)| (min.min(*mid), max.max(*mid)));
+        let move_bps = if max >= min { 10_000.0 * (max - min) / mark } else { 0.0 };
+        width_bps < self.tight_bps || move_bps > self.fast_move_bps
+    }
+
+    fn observe(&mut self, mid: f64, at: f64) {
+        self.history.push_back((at, mid));
+        while self.history.front().is_some_and(|(observed, _)| at - observed > self.window_sec) {
+            self.history.pop_front();
+        }
+    }
+}
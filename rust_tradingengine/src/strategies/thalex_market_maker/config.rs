@@ -1,6 +1,11 @@
 /// Constants and configuration parameters for the Thalex market maker
+use crate::infrastructure::exchange::thalex::channel::Channel;
+
 pub const PING_INTERVAL_SEC: u64 = 5;
-pub const TIMEOUT_SEC: u64 = 6;
+/// Consecutive unanswered pings after which the connection is considered dead
+pub const MAX_MISSED_PONGS: usize = 3;
+/// How long shutdown waits for Kafka to deliver outstanding messages
+pub const KAFKA_FLUSH_TIMEOUT_SEC: u64 = 5;
 pub const TYPE: &str = "perpetual";
 pub const UNDERLYING: &str = "BTCUSD";
 pub const LABEL: &str = "P";
@@ -10,10 +15,77 @@ pub const BID_STEP: f64 = 5.0;
 pub const BID_SIZES: &[f64] = &[0.2, 0.4];
 pub const ASK_STEP: f64 = 5.0;
 pub const ASK_SIZES: &[f64] = &[0.2, 0.4];
+/// Largest amount allowed on a single order
+pub const MAX_ORDER_AMOUNT: f64 = 1.0;
+/// Amount step used until the instrument's volume tick is known
+pub const AMOUNT_STEP: f64 = 0.001;
+/// Maximum relative index move accepted in one update without confirmation
+pub const INDEX_MAX_JUMP: f64 = 0.01;
+/// How often local clock drift against the exchange is re-checked
+pub const CLOCK_CHECK_INTERVAL_SEC: u64 = 300;
+/// Clock drift above which a warning is logged
+pub const CLOCK_DRIFT_WARN_SEC: f64 = 1.5;
+/// Clock drift above which the bot stops trading
+pub const CLOCK_DRIFT_HALT_SEC: f64 = 5.0;
+/// How often scheduled parameter overrides are re-evaluated
+pub const SCHEDULE_INTERVAL_SEC: u64 = 10;
+/// How often quote presence is sampled for uptime tracking
+pub const UPTIME_SAMPLE_SEC: u64 = 1;
+/// Maximum distance from the mark (in ticks) for a quote to count towards uptime
+pub const UPTIME_MAX_DISTANCE_TICKS: f64 = 50.0;
+/// How often the daily statistics are saved to disk
+pub const STATS_SNAPSHOT_INTERVAL_SEC: u64 = 30;
+/// How often a full order book snapshot is persisted between deltas
+pub const BOOK_SNAPSHOT_INTERVAL_SEC: f64 = 60.0;
+/// Levels of the quoted instrument's book subscribed when `[[thalex.books]]` doesn't list it
+pub const QUOTE_BOOK_DEPTH: u32 = 10;
+/// Levels of the book the quoting features' imbalance is measured over
+pub const BOOK_IMBALANCE_LEVELS: usize = 5;
+/// How often a full ticker snapshot is published between changed-field deltas
+pub const TICKER_SNAPSHOT_INTERVAL_SEC: f64 = 60.0;
+/// How long a broker may take to answer while checking whether journal-only mode can end
+pub const KAFKA_PROBE_TIMEOUT_SEC: u64 = 2;
+/// How often the bot publishes a heartbeat
+pub const HEARTBEAT_INTERVAL_SEC: u64 = 5;
+/// How often the latest ticker of each instrument is published to the compacted topic
+pub const TICKER_SAMPLE_INTERVAL_SEC: u64 = 1;
+pub const FEATURES_INTERVAL_SEC: u64 = 1;
+pub const FEATURES_WINDOW_SEC: f64 = 60.0;
+/// How often basis and funding carry are sampled and published
+pub const CARRY_INTERVAL_SEC: u64 = 10;
+/// Window the published rolling basis is averaged over
+pub const CARRY_BASIS_WINDOW_SEC: f64 = 3600.0;
+/// How often the account's balances and margin are requested
+pub const ACCOUNT_SUMMARY_INTERVAL_SEC: u64 = 30;
+/// Capacity of the order/trade processing queue
+pub const PRIVATE_QUEUE_SIZE: usize = 1024;
+/// Capacity of the market data processing queue; updates are dropped when full
+pub const MARKET_QUEUE_SIZE: usize = 1024;
+/// Batches of book or ticker records queued per channel for Kafka; when full the channel is resent as a snapshot
+pub const PUBLISH_QUEUE_SIZE: usize = 256;
+/// Outbound requests are held back this long after the exchange reports a rate limit
+pub const RATE_LIMIT_BACKOFF_MS: u64 = 1000;
+/// How often the high watermarks of consumed control and signal topics are fetched for their lag
+pub const CONSUMER_WATERMARK_REFRESH_MS: u64 = 1000;
+/// How long an order request waits for the exchange's answer before giving up on it
+pub const CALL_RESPONSE_TIMEOUT_MS: u64 = 5000;
+/// Inserts and amends each quote side may send per budget window
+pub const SIDE_UPDATE_BUDGET: usize = 20;
+pub const SIDE_BUDGET_WINDOW_MS: u64 = 1000;
+/// Notifications on an unknown channel are logged as a warning the first time, then once per this many
+pub const UNKNOWN_CHANNEL_LOG_EVERY: u64 = 1000;
+/// Backoff before a rejected channel subscription is retried, doubling per rejection
+pub const SUBSCRIBE_RETRY_INITIAL_SEC: f64 = 1.0;
+pub const SUBSCRIBE_RETRY_MAX_SEC: f64 = 60.0;
+/// How often failed subscriptions are checked for a due retry
+pub const SUBSCRIBE_RETRY_CHECK_SEC: u64 = 1;
+/// Days back the journal is searched for the fill of a busted or corrected trade
+pub const CORRECTION_LOOKBACK_DAYS: u64 = 7;
 
 /// WebSocket channels to subscribe
-pub const CHANNELS: &[&str] = &[
-    "session.orders",
-    "account.portfolio",
-    "account.trade_history",
+pub const CHANNELS: &[Channel] = &[
+    Channel::Orders,
+    Channel::Portfolio,
+    Channel::Trades,
+    Channel::AccountEvents,
 ];
@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Operator command scoped to one instrument
+///
+/// Parsed from JSON such as `{"command": "pause", "instrument": "BTC-PERPETUAL"}`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Pull the instrument's quotes until resumed
+    Pause { instrument: String },
+    /// Quote the instrument again after a pause or flatten
+    Resume { instrument: String },
+    /// Pull the instrument's quotes and close its position with a market order
+    Flatten { instrument: String },
+    /// Subscribe to the instrument's market data and start quoting it
+    Enable { instrument: String },
+    /// Cancel the instrument's orders and unsubscribe from its market data
+    Disable { instrument: String },
+}
+
+impl ControlCommand {
+    pub fn from_json(value: &Value) -> Result<Self> {
+        serde_json::from_value(value.clone()).map_err(|e| anyhow!("Invalid control command {}: {}", value, e))
+    }
+
+    /// Instrument the command applies to
+    pub fn instrument(&self) -> &str {
+        match self {
+            ControlCommand::Pause { instrument }
+            | ControlCommand::Resume { instrument }
+            | ControlCommand::Flatten { instrument }
+            | ControlCommand::Enable { instrument }
+            | ControlCommand::Disable { instrument } => instrument,
+        }
+    }
+}
+
+/// Command read from the control topic, with an optional deadline
+///
+/// Parsed from JSON such as `{"command": "pause", "instrument": "BTC-PERPETUAL", "expires_at": 1735689600.0}`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ControlMessage {
+    #[serde(flatten)]
+    pub command: ControlCommand,
+
+    /// Unix time (seconds) after which the command must not be applied
+    #[serde(default)]
+    pub expires_at: Option<f64>,
+}
+
+impl ControlMessage {
+    pub fn from_json(value: &Value) -> Result<Self> {
+        serde_json::from_value(value.clone()).map_err(|e| anyhow!("Invalid control message {}: {}", value, e))
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::domain::enums::OrderSide;
+use crate::domain::model::uptime::QuoteUptime;
+use crate::infrastructure::metrics;
+
+/// Our fills today
+pub const METRIC_DAILY_FILLS: &str = "stats.daily_fills";
+
+/// Filled amount today
+pub const METRIC_DAILY_VOLUME: &str = "stats.daily_volume";
+
+/// Realized PnL today, in quote currency
+pub const METRIC_DAILY_REALIZED_PNL: &str = "stats.daily_realized_pnl";
+
+/// Cumulative trading statistics of one UTC day
+///
+/// Saved periodically so a restart continues the day's totals instead of starting
+/// from zero. The position and its average entry price carry over to the next day,
+/// since realized PnL is measured against them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyStats {
+    /// UTC day, YYYY-MM-DD
+    pub date: String,
+    pub fills: u64,
+    pub volume: f64,
+    pub notional: f64,
+    pub realized_pnl: f64,
+    pub position: f64,
+    pub avg_price: f64,
+    pub uptime_period_start: f64,
+    pub uptime_observed_secs: f64,
+    pub uptime_compliant_secs: f64,
+}
+
+impl DailyStats {
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date: date.to_string(),
+            fills: 0,
+            volume: 0.0,
+            notional: 0.0,
+            realized_pnl: 0.0,
+            position: 0.0,
+            avg_price: 0.0,
+            uptime_period_start: 0.0,
+            uptime_observed_secs: 0.0,
+            uptime_compliant_secs: 0.0,
+        }
+    }
+
+    /// Start `date` if it's a new day; returns the completed day
+    pub fn roll(&mut self, date: NaiveDate) -> Option<DailyStats> {
+        if self.date == date.to_string() {
+            return None;
+        }
+        let next = Self {
+            position: self.position,
+            avg_price: self.avg_price,
+            ..Self::new(date)
+        };
+        Some(std::mem::replace(self, next))
+    }
+
+    /// Record one of our fills at `now` (seconds); returns the previous day's totals
+    /// when the fill is the first of a new day
+    pub fn record_fill(&mut self, side: &OrderSide, price: f64, amount: f64, now: f64) -> Option<DailyStats> {
+        let completed = self.roll(day_of(now));
+        self.apply_fill(side, price, amount);
+        self.publish();
+        completed
+    }
+
+    /// Statistics of `date` replayed from the day's opening position and its fills
+    ///
+    /// The uptime of `self` is kept, since fills don't affect it.
+    pub fn rebuilt<'a>(&self, opening: &DailyStats, fills: impl IntoIterator<Item = (&'a OrderSide, f64, f64)>) -> Self {
+        let mut stats = Self {
+            date: self.date.clone(),
+            position: opening.position,
+            avg_price: opening.avg_price,
+            uptime_period_start: self.uptime_period_start,
+            uptime_observed_secs: self.uptime_observed_secs,
+            uptime_compliant_secs: self.uptime_compliant_secs,
+            ..Self::new(NaiveDate::default())
+        };
+        for (side, price, amount) in fills {
+            stats.apply_fill(side, price, amount);
+        }
+        stats
+    }
+
+    /// Take back a fill recorded today, when the fills since the day opened can't be replayed
+    ///
+    /// Counts and position are restored exactly; the average entry price and realized
+    /// PnL are left as they are, since undoing them needs the fills that came after.
+    pub fn reverse_fill(&mut self, side: &OrderSide, price: f64, amount: f64) {
+        self.fills = self.fills.saturating_sub(1);
+        self.volume -= amount;
+        self.notional -= price * amount;
+        self.shift_position(match side {
+            OrderSide::Buy => -amount,
+            OrderSide::Sell => amount,
+        });
+    }
+
+    /// Move the position by `delta`, for a revised fill of a day already closed
+    pub fn shift_position(&mut self, delta: f64) {
+        self.position += delta;
+        if self.position.abs() < 1e-12 {
+            self.position = 0.0;
+            self.avg_price = 0.0;
+        }
+    }
+
+    /// Add a fill to the totals, position and realized PnL of the current day
+    pub fn apply_fill(&mut self, side: &OrderSide, price: f64, amount: f64) {
+        self.fills += 1;
+        self.volume += amount;
+        self.notional += price * amount;
+
+        let signed = match side {
+            OrderSide::Buy => amount,
+            OrderSide::Sell => -amount,
+        };
+        if self.position == 0.0 || self.position.signum() == signed.signum() {
+            // Adding to the position moves the average entry
+            let total = self.position.abs() + amount;
+            self.avg_price = (self.avg_price * self.position.abs() + price * amount) / total;
+        } else {
+            let closed = amount.min(self.position.abs());
+            self.realized_pnl += (price - self.avg_price) * closed * self.position.signum();
+            if amount > self.position.abs() {
+                // Flipped through zero: the remainder opens at this price
+                self.avg_price = price;
+            }
+        }
+        self.position += signed;
+        if self.position.abs() < 1e-12 {
+            self.position = 0.0;
+            self.avg_price = 0.0;
+        }
+    }
+
+    /// Store the uptime accumulated so far today
+    pub fn set_uptime(&mut self, uptime: &QuoteUptime) {
+        if uptime.date != self.date {
+            return;
+        }
+        self.uptime_period_start = uptime.period_start;
+        self.uptime_observed_secs = uptime.observed_secs;
+        self.uptime_compliant_secs = uptime.compliant_secs;
+    }
+
+    /// Publish the day's totals as gauges
+    pub fn publish(&self) {
+        let metrics = metrics::global();
+        metrics.set_gauge(METRIC_DAILY_FILLS, self.fills as f64);
+        metrics.set_gauge(METRIC_DAILY_VOLUME, self.volume);
+        metrics.set_gauge(METRIC_DAILY_REALIZED_PNL, self.realized_pnl);
+    }
+
+    /// Statistics saved at `path`, None when the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let stats = serde_json::from_str(&content).with_context(|| format!("Invalid statistics in {}", path.display()))?;
+        Ok(Some(stats))
+    }
+
+    /// Write the statistics to `path`, replacing the previous snapshot atomically
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// UTC day of a timestamp in seconds
+pub fn day_of(time: f64) -> NaiveDate {
+    DateTime::from_timestamp(time.floor() as i64, 0)
+        .map(|t| t.date_naive())
+        .unwrap_or_default()
+}
@@ -0,0 +1,86 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+use crate::config_loader::{ExperimentAssignment, ExperimentConfig, ParamOverride};
+
+use super::config;
+
+/// One arm of an experiment
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant {
+    pub id: String,
+    pub instruments: Vec<String>,
+    pub params: ParamOverride,
+}
+
+/// Assigns the variant of a running experiment to each instrument over time
+///
+/// Time slices are aligned to the epoch, so every instance running the same experiment
+/// quotes the same variant at the same time.
+#[derive(Debug)]
+pub struct Experiment {
+    pub name: String,
+    assignment: ExperimentAssignment,
+    slice_sec: i64,
+    variants: Vec<Variant>,
+}
+
+impl Experiment {
+    /// The configured experiment, None when disabled
+    pub fn from_config(experiment: &ExperimentConfig) -> Result<Option<Self>> {
+        if !experiment.enabled {
+            return Ok(None);
+        }
+        if experiment.variants.is_empty() {
+            return Err(anyhow!("Experiment '{}' has no variants", experiment.name));
+        }
+
+        let mut ids = HashSet::new();
+        let mut instruments = HashSet::new();
+        for variant in &experiment.variants {
+            if variant.id.is_empty() {
+                return Err(anyhow!("Experiment '{}' has a variant without an id", experiment.name));
+            }
+            if !ids.insert(variant.id.as_str()) {
+                return Err(anyhow!("Experiment '{}' has two variants '{}'", experiment.name, variant.id));
+            }
+            if experiment.assignment == ExperimentAssignment::Instrument {
+                if let Some(instrument) = variant.instruments.iter().find(|i| !instruments.insert(i.as_str())) {
+                    return Err(anyhow!("Instrument {} is assigned to more than one variant", instrument));
+                }
+            }
+        }
+        // Variants are switched by the schedule task, so shorter slices would be skipped
+        if experiment.assignment == ExperimentAssignment::TimeSlice && experiment.slice_sec < config::SCHEDULE_INTERVAL_SEC {
+            return Err(anyhow!("Experiment slices of {}s are shorter than the {}s schedule interval",
+                experiment.slice_sec, config::SCHEDULE_INTERVAL_SEC));
+        }
+
+        Ok(Some(Self {
+            name: experiment.name.clone(),
+            assignment: experiment.assignment,
+            slice_sec: experiment.slice_sec as i64,
+            variants: experiment.variants.iter()
+                .map(|v| Variant {
+                    id: v.id.clone(),
+                    instruments: v.instruments.clone(),
+                    params: v.params.clone(),
+                })
+                .collect(),
+        }))
+    }
+
+    /// Variant `instrument` is quoted with at `now`; None for an instrument no variant lists
+    pub fn variant_at(&self, now: DateTime<Utc>, instrument: &str) -> Option<&Variant> {
+        match self.assignment {
+            ExperimentAssignment::TimeSlice => {
+                let slice = now.timestamp().div_euclid(self.slice_sec);
+                self.variants.get(slice.rem_euclid(self.variants.len() as i64) as usize)
+            }
+            ExperimentAssignment::Instrument => {
+                self.variants.iter().find(|v| v.instruments.iter().any(|i| i == instrument))
+            }
+        }
+    }
+}
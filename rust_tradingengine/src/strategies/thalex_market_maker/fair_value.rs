@@ -0,0 +1,60 @@
+use log::warn;
+use serde_json::Value;
+
+/// Latest fair value from an external feed, such as the mid of another venue
+///
+/// Values older than `max_age_sec` are stale; quoting then falls back to the exchange index
+/// until the feed catches up.
+pub struct ExternalFairValue {
+    /// Maximum age of a value still used for quoting
+    max_age_sec: f64,
+
+    /// Last accepted price and the time it was observed
+    latest: Option<(f64, f64)>,
+}
+
+impl ExternalFairValue {
+    pub fn new(max_age_sec: f64) -> Self {
+        Self {
+            max_age_sec,
+            latest: None,
+        }
+    }
+
+    /// Record a price observed at `at`; invalid prices and out-of-order updates are ignored
+    pub fn update(&mut self, price: f64, at: f64) -> bool {
+        if !price.is_finite() || price <= 0.0 {
+            warn!("Rejecting invalid external fair value: {}", price);
+            return false;
+        }
+        if self.latest.is_some_and(|(_, last)| at < last) {
+            return false;
+        }
+        self.latest = Some((price, at));
+        true
+    }
+
+    /// Latest price if it is no older than the allowed age at `now`
+    pub fn fresh(&self, now: f64) -> Option<f64> {
+        self.latest
+            .filter(|(_, at)| now - at <= self.max_age_sec)
+            .map(|(price, _)| price)
+    }
+
+    /// Latest price and observation time, fresh or not
+    pub fn latest(&self) -> Option<(f64, f64)> {
+        self.latest
+    }
+}
+
+/// Read the price at a dotted `field` path (e.g. `result.mid`) of a JSON message
+///
+/// Prices may be JSON numbers or numeric strings, as many venues quote them as strings.
+pub fn price_at(message: &Value, field: &str) -> Option<f64> {
+    let value = field.split('.').try_fold(message, |value, key| value.get(key))?;
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+
+use crate::domain::model::book::OrderBook;
+use crate::domain::model::features::MarketFeatures;
+use crate::domain::model::ticker::Ticker;
+
+/// Maintains rolling windows of market observations and derives microstructure features
+pub struct FeatureEngine {
+    /// Length of the rolling window (seconds)
+    window_secs: f64,
+
+    /// Observed mid prices as (time, mid)
+    mids: VecDeque<(f64, f64)>,
+
+    /// Times at which trades were observed
+    trade_times: VecDeque<f64>,
+
+    /// Most recent ticker
+    latest: Option<Ticker>,
+
+    /// Spread and imbalance of the instrument's book, preferred over the ticker's top level
+    book: Option<(f64, f64)>,
+
+    /// Levels the book imbalance is measured over
+    book_levels: usize,
+}
+
+impl FeatureEngine {
+    pub fn new(window_secs: f64) -> Self {
+        Self {
+            window_secs,
+            mids: VecDeque::new(),
+            trade_times: VecDeque::new(),
+            latest: None,
+            book: None,
+            book_levels: 1,
+        }
+    }
+
+    /// Measure imbalance over the best `levels` levels of the book
+    pub fn with_book_levels(mut self, levels: usize) -> Self {
+        self.book_levels = levels.max(1);
+        self
+    }
+
+    /// Record an update of the instrument's order book; a book missing a side is ignored
+    pub fn on_book(&mut self, book: &OrderBook) {
+        self.book = book.spread().map(|spread| (spread, book.imbalance(self.book_levels)));
+    }
+
+    /// Record a ticker update observed at `now`
+    pub fn on_ticker(&mut self, ticker: &Ticker, now: f64) {
+        let mid = match (ticker.best_bid(), ticker.best_ask()) {
+            (Some(bid), Some(ask)) => (bid + ask) / 2.0,
+            _ => ticker.mark_price,
+        };
+        if mid > 0.0 {
+            self.mids.push_back((now, mid));
+        }
+        self.latest = Some(ticker.clone());
+        self.evict(now);
+    }
+
+    /// Record an index price update
+    pub fn on_index(&mut self, price: f64) {
+        if let Some(ticker) = &mut self.latest {
+            ticker.index_price = price;
+        }
+    }
+
+    /// Record a trade observed at `now`
+    pub fn on_trade(&mut self, now: f64) {
+        self.trade_times.push_back(now);
+        self.evict(now);
+    }
+
+    /// Compute the current features, or None if no ticker has been seen yet
+    pub fn compute(&mut self, now: f64) -> Option<MarketFeatures> {
+        self.evict(now);
+        let ticker = self.latest.as_ref()?;
+
+        let (spread, imbalance) = match self.book {
+            Some(book) => book,
+            None => {
+                let depth = ticker.best_bid_amount + ticker.best_ask_amount;
+                let imbalance = if depth > 0.0 {
+                    (ticker.best_bid_amount - ticker.best_ask_amount) / depth
+                } else {
+                    0.0
+                };
+                let spread = match (ticker.best_bid(), ticker.best_ask()) {
+                    (Some(bid), Some(ask)) => ask - bid,
+                    _ => 0.0,
+                };
+                (spread, imbalance)
+            }
+        };
+
+        let funding_basis = if ticker.index_price > 0.0 {
+            (ticker.mark_price - ticker.index_price) / ticker.index_price
+        } else {
+            0.0
+        };
+
+        Some(MarketFeatures {
+            instrument_name: ticker.instrument_name.clone(),
+            timestamp: now,
+            window_secs: self.window_secs,
+            imbalance,
+            spread,
+            trade_intensity: self.trade_times.len() as f64 / self.window_secs,
+            realized_vol: self.realized_vol(),
+            funding_basis,
+            funding_rate: ticker.funding_rate,
+            processing_timestamp: Some(now),
+        })
+    }
+
+    /// Square root of the summed squared mid-price log returns in the window
+    pub fn realized_vol(&self) -> f64 {
+        self.mids
+            .iter()
+            .zip(self.mids.iter().skip(1))
+            .map(|((_, prev), (_, next))| (next / prev).ln().powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Drop observations that fell out of the window
+    fn evict(&mut self, now: f64) {
+        let cutoff = now - self.window_secs;
+        while self.mids.front().is_some_and(|(t, _)| *t < cutoff) {
+            self.mids.pop_front();
+        }
+        while self.trade_times.front().is_some_and(|t| *t < cutoff) {
+            self.trade_times.pop_front();
+        }
+    }
+}
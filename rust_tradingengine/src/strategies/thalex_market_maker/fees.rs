@@ -0,0 +1,35 @@
+use crate::config_loader::FeeConfig;
+
+/// Maker/taker fee rates of the account's tier
+///
+/// Rates are fractions of notional; a negative maker rate is a rebate.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeSchedule {
+    pub maker_rate: f64,
+    pub taker_rate: f64,
+}
+
+impl FeeSchedule {
+    pub fn from_config(fees: &FeeConfig) -> Self {
+        Self {
+            maker_rate: fees.maker_fee_bps / 10_000.0,
+            taker_rate: fees.taker_fee_bps / 10_000.0,
+        }
+    }
+
+    /// Expected maker rebate per unit filled at `price` (negative when paying a fee)
+    pub fn maker_rebate(&self, price: f64) -> f64 {
+        -self.maker_rate * price
+    }
+
+    /// Smallest distance from fair value at which a maker fill breaks even
+    pub fn min_profitable_half_spread(&self, price: f64) -> f64 {
+        (self.maker_rate * price).max(0.0)
+    }
+
+    /// Expected edge per unit of a maker fill `half_spread` away from fair value,
+    /// including the rebate
+    pub fn maker_edge(&self, half_spread: f64, price: f64) -> f64 {
+        half_spread + self.maker_rebate(price)
+    }
+}
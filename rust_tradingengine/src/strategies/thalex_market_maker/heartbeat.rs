@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use crate::domain::model::heartbeat::Heartbeat;
+use crate::infrastructure::exchange::thalex::channel::Channel;
+
+/// Tracks when each kind of notification last arrived, for heartbeats
+#[derive(Debug, Default)]
+pub struct HeartbeatTracker {
+    last_ticker: Option<f64>,
+    last_index: Option<f64>,
+    last_order: Option<f64>,
+    last_trade: Option<f64>,
+}
+
+impl HeartbeatTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note a notification received on `channel` at `now`
+    pub fn record(&mut self, channel: &Channel, now: f64) {
+        match channel {
+            Channel::Ticker { .. } => self.last_ticker = Some(now),
+            Channel::Index(_) => self.last_index = Some(now),
+            Channel::Orders => self.last_order = Some(now),
+            Channel::Trades => self.last_trade = Some(now),
+            Channel::Portfolio | Channel::Book { .. } | Channel::RecentTrades { .. } | Channel::AccountEvents => {}
+        }
+    }
+
+    /// Build the heartbeat for `now`
+    pub fn heartbeat(&self, instance_id: &str, sequence: i64, portfolio: &HashMap<String, f64>, now: f64) -> Heartbeat {
+        Heartbeat {
+            instance_id: instance_id.to_string(),
+            sequence,
+            positions_hash: positions_hash(portfolio),
+            last_ticker_time: self.last_ticker,
+            last_index_time: self.last_index,
+            last_order_time: self.last_order,
+            last_trade_time: self.last_trade,
+            timestamp: now,
+            processing_timestamp: Some(now),
+        }
+    }
+}
+
+/// Stable fingerprint of the positions (FNV-1a over the sorted `instrument=amount` pairs)
+///
+/// Independent of map order and of the Rust version, so consumers can compare
+/// hashes across restarts and instances.
+pub fn positions_hash(portfolio: &HashMap<String, f64>) -> String {
+    let mut positions: Vec<(&String, &f64)> = portfolio.iter().collect();
+    positions.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for (instrument, amount) in positions {
+        for byte in format!("{}={};", instrument, amount).bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
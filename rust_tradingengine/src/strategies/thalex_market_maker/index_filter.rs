@@ -0,0 +1,70 @@
+use log::warn;
+
+/// Rate-of-change filter for index prices
+///
+/// A move larger than `max_jump` (relative) in a single update is held back until the
+/// next update confirms it, so a single bad print can't re-price the whole ladder.
+pub struct IndexFilter {
+    /// Maximum accepted relative change between consecutive updates
+    max_jump: f64,
+
+    /// Last accepted price
+    last: Option<f64>,
+
+    /// Suspicious price waiting for confirmation
+    pending: Option<f64>,
+}
+
+impl IndexFilter {
+    pub fn new(max_jump: f64) -> Self {
+        Self {
+            max_jump,
+            last: None,
+            pending: None,
+        }
+    }
+
+    /// Filter an index update, returning the price to use or None if it was rejected
+    pub fn filter(&mut self, price: f64) -> Option<f64> {
+        if !price.is_finite() || price <= 0.0 {
+            warn!("Rejecting invalid index price: {}", price);
+            return None;
+        }
+
+        let last = match self.last {
+            Some(last) => last,
+            None => return self.accept(price),
+        };
+
+        if Self::relative_change(last, price) <= self.max_jump {
+            return self.accept(price);
+        }
+
+        // A large move is only accepted once a second update lands close to it
+        if let Some(pending) = self.pending {
+            if Self::relative_change(pending, price) <= self.max_jump {
+                warn!("Index move {} -> {} confirmed", last, price);
+                return self.accept(price);
+            }
+        }
+
+        warn!("Holding back index spike {} -> {} pending confirmation", last, price);
+        self.pending = Some(price);
+        None
+    }
+
+    /// Last accepted price
+    pub fn last(&self) -> Option<f64> {
+        self.last
+    }
+
+    fn accept(&mut self, price: f64) -> Option<f64> {
+        self.last = Some(price);
+        self.pending = None;
+        Some(price)
+    }
+
+    fn relative_change(from: f64, to: f64) -> f64 {
+        ((to - from) / from).abs()
+    }
+}
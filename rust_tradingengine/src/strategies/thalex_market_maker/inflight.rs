@@ -0,0 +1,236 @@
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::domain::enums::OrderSide;
+use crate::infrastructure::exchange::thalex::models::OrderResult;
+use crate::infrastructure::runtime;
+
+use super::config;
+
+/// A quote insert sent to the exchange whose order hasn't been reported yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InflightInsert {
+    /// Unique tag sent as the order label
+    pub tag: Uuid,
+    pub client_order_id: u64,
+    pub instrument_name: String,
+    pub direction: OrderSide,
+    /// Quote level the order was placed at
+    pub level: usize,
+    pub price: f64,
+    pub amount: f64,
+    /// When the insert was sent (seconds since epoch)
+    pub sent_at: f64,
+    /// Experiment variant the insert was placed under
+    #[serde(default)]
+    pub variant_id: Option<String>,
+}
+
+impl InflightInsert {
+    /// Label the order is inserted with
+    pub fn label(&self) -> String {
+        order_label(&self.tag, self.variant_id.as_deref())
+    }
+}
+
+/// Inserts sent but not yet seen in an order notification
+///
+/// Each insert is recorded before it goes out and, when a path is set, saved by a
+/// writer task, so quoting never waits for the disk: a burst of changes is written as
+/// the latest state only. After a reconnect the inserts still recorded are looked up
+/// among the open orders by their tag, so a level whose insert did reach the exchange
+/// is taken over instead of being submitted twice.
+#[derive(Debug, Default)]
+pub struct InflightOrders {
+    inserts: BTreeMap<u64, InflightInsert>,
+    journal: Option<Journal>,
+}
+
+/// Hands the in-flight inserts to the task writing them to the journal file
+#[derive(Debug)]
+struct Journal {
+    /// Latest state to write, numbered
+    snapshots: watch::Sender<(u64, Vec<InflightInsert>)>,
+    /// Number of the last state written
+    written: watch::Receiver<u64>,
+    version: u64,
+}
+
+impl Journal {
+    /// Start the writer task for `path`; it ends once the journal is dropped and written
+    fn spawn(path: PathBuf) -> Self {
+        let (snapshots, mut pending) = watch::channel((0, Vec::new()));
+        let (done, written) = watch::channel(0);
+        runtime::spawn_io(async move {
+            while pending.changed().await.is_ok() {
+                let (version, inserts) = pending.borrow_and_update().clone();
+                let path = path.clone();
+                match tokio::task::spawn_blocking(move || write_journal(&path, &inserts)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => warn!("Failed to save in-flight orders: {:#}", e),
+                    Err(e) => warn!("In-flight orders writer failed: {}", e),
+                }
+                done.send_replace(version);
+            }
+        });
+        Self { snapshots, written, version: 0 }
+    }
+
+    fn save(&mut self, inserts: &BTreeMap<u64, InflightInsert>) {
+        self.version += 1;
+        self.snapshots.send_replace((self.version, inserts.values().cloned().collect()));
+    }
+}
+
+impl InflightOrders {
+    /// Journal kept in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Journal saved to `path`, continuing with the inserts saved there by a previous session
+    ///
+    /// Must be called within a Tokio runtime, which the writer task is spawned on.
+    pub fn load(path: &Path) -> Result<Self> {
+        let inserts: Vec<InflightInsert> = if path.exists() {
+            let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&content).with_context(|| format!("Invalid in-flight orders in {}", path.display()))?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            inserts: inserts.into_iter().map(|insert| (insert.client_order_id, insert)).collect(),
+            journal: Some(Journal::spawn(path.to_path_buf())),
+        })
+    }
+
+    /// Record an insert about to be sent
+    pub fn record(&mut self, insert: InflightInsert) {
+        self.inserts.insert(insert.client_order_id, insert);
+        self.save();
+    }
+
+    /// Forget the insert of `client_order_id` now that the exchange has answered for it
+    pub fn acknowledge(&mut self, client_order_id: u64) -> Option<InflightInsert> {
+        let insert = self.inserts.remove(&client_order_id)?;
+        self.save();
+        Some(insert)
+    }
+
+    /// Remove and return every recorded insert
+    pub fn take_all(&mut self) -> Vec<InflightInsert> {
+        let inserts = std::mem::take(&mut self.inserts).into_values().collect();
+        self.save();
+        inserts
+    }
+
+    /// Wait until the journal file holds the inserts recorded so far
+    pub async fn flush(&self) {
+        if let Some(journal) = &self.journal {
+            let mut written = journal.written.clone();
+            // The writer only stops once the journal is dropped
+            let _ = written.wait_for(|written| *written >= journal.version).await;
+        }
+    }
+
+    pub fn get(&self, client_order_id: u64) -> Option<&InflightInsert> {
+        self.inserts.get(&client_order_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inserts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inserts.is_empty()
+    }
+
+    /// Lowest client order id that doesn't collide with a recorded insert
+    pub fn next_client_order_id(&self) -> Option<u64> {
+        self.inserts.keys().next_back().map(|client_order_id| client_order_id + 1)
+    }
+
+    /// Hand the current inserts to the journal writer, if there is one
+    fn save(&mut self) {
+        if let Some(journal) = &mut self.journal {
+            journal.save(&self.inserts);
+        }
+    }
+}
+
+/// Write `inserts` to the journal file, replacing the previous one atomically
+fn write_journal(path: &Path, inserts: &[InflightInsert]) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, serde_json::to_vec_pretty(inserts)?)?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Order label carrying `tag` and the experiment variant, prefixed with the bot's label
+///
+/// The exchange echoes the label on every order notification and trade, so the variant
+/// of a fill is known even after its order left the book.
+pub fn order_label(tag: &Uuid, variant: Option<&str>) -> String {
+    match variant {
+        Some(variant) => format!("{}:{}:{}", config::LABEL, tag, variant),
+        None => format!("{}:{}", config::LABEL, tag),
+    }
+}
+
+/// Tag and variant of an order label written by `order_label`
+fn parse_label(label: &str) -> Option<(Uuid, Option<&str>)> {
+    let rest = label.strip_prefix(config::LABEL)?.strip_prefix(':')?;
+    let (tag, variant) = match rest.split_once(':') {
+        Some((tag, variant)) => (tag, Some(variant).filter(|variant| !variant.is_empty())),
+        None => (rest, None),
+    };
+    Some((tag.parse().ok()?, variant))
+}
+
+/// Tag of an order label written by `order_label`
+pub fn tag_of(label: &str) -> Option<Uuid> {
+    parse_label(label).map(|(tag, _)| tag)
+}
+
+/// Experiment variant of an order label written by `order_label`
+pub fn variant_of_label(label: &str) -> Option<&str> {
+    parse_label(label)?.1
+}
+
+/// Whether a label belongs to one of the bot's orders
+pub fn is_own_label(label: &str) -> bool {
+    label == config::LABEL || tag_of(label).is_some()
+}
+
+/// Outcome of looking up in-flight inserts among the open orders
+#[derive(Debug, Default)]
+pub struct Reconciliation {
+    /// Inserts that reached the exchange, with their open order
+    pub adopted: Vec<(InflightInsert, OrderResult)>,
+    /// Inserts with no open order: never arrived, rejected, or already closed
+    pub missing: Vec<InflightInsert>,
+}
+
+/// Match in-flight inserts with open orders by their label tag
+pub fn reconcile(inserts: Vec<InflightInsert>, open_orders: &[OrderResult]) -> Reconciliation {
+    let mut reconciliation = Reconciliation::default();
+    for insert in inserts {
+        let open = open_orders.iter().find(|order| {
+            order.label.as_deref().and_then(tag_of) == Some(insert.tag)
+        });
+        match open {
+            Some(order) => reconciliation.adopted.push((insert, order.clone())),
+            None => reconciliation.missing.push(insert),
+        }
+    }
+    reconciliation
+}
@@ -0,0 +1,189 @@
+use anyhow::{anyhow, Result};
+
+use crate::config_loader::{LadderConfig, LadderShape};
+use crate::domain::model::quote::SideQuote;
+
+use super::config;
+
+/// Offsets (ticks beyond the spread) and sizes of the levels on one side
+#[derive(Debug, Clone, PartialEq)]
+struct SideLadder {
+    offsets: Vec<f64>,
+    sizes: Vec<f64>,
+}
+
+/// Lays out the quote levels on both sides of the book
+///
+/// The first level sits `spread` ticks from the center; deeper levels follow the
+/// configured shape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LadderBuilder {
+    bids: SideLadder,
+    asks: SideLadder,
+}
+
+impl Default for LadderBuilder {
+    fn default() -> Self {
+        let linear = |step: f64, sizes: &[f64]| SideLadder {
+            offsets: (0..sizes.len()).map(|lvl| step * lvl as f64).collect(),
+            sizes: sizes.to_vec(),
+        };
+        Self {
+            bids: linear(config::BID_STEP, config::BID_SIZES),
+            asks: linear(config::ASK_STEP, config::ASK_SIZES),
+        }
+    }
+}
+
+impl LadderBuilder {
+    pub fn new(shape: &LadderShape, bid_sizes: &[f64], ask_sizes: &[f64]) -> Result<Self> {
+        if bid_sizes.iter().chain(ask_sizes).any(|s| !s.is_finite() || *s <= 0.0) {
+            return Err(anyhow!("Ladder sizes must be positive"));
+        }
+        let levels = bid_sizes.len().max(ask_sizes.len());
+        let offsets = offsets(shape, levels)?;
+        let side = |sizes: &[f64]| SideLadder {
+            offsets: offsets[..sizes.len()].to_vec(),
+            sizes: sizes.to_vec(),
+        };
+        Ok(Self {
+            bids: side(bid_sizes),
+            asks: side(ask_sizes),
+        })
+    }
+
+    pub fn from_config(ladder: &LadderConfig) -> Result<Self> {
+        Self::new(&ladder.shape, &ladder.bid_sizes, &ladder.ask_sizes)
+    }
+
+    /// Number of bid and ask levels
+    pub fn levels(&self) -> (usize, usize) {
+        (self.bids.sizes.len(), self.asks.sizes.len())
+    }
+
+    /// Size of the first level, the reference for size scaling
+    pub fn reference_size(&self) -> Option<f64> {
+        self.bids.sizes.first().or(self.asks.sizes.first()).copied()
+    }
+
+    /// Bid and ask quotes around `center`, prices rounded to the tick
+    pub fn build(&self, center: f64, spread: f64, tick: f64, size_multiplier: f64) -> Vec<Vec<SideQuote>> {
+        let round = |price: f64| tick * (price / tick).round();
+        let side = |ladder: &SideLadder, direction: f64| -> Vec<SideQuote> {
+            ladder.offsets.iter().zip(&ladder.sizes)
+                .map(|(offset, size)| SideQuote::new(
+                    round(center + direction * (spread + offset) * tick),
+                    size * size_multiplier,
+                ))
+                .collect()
+        };
+        vec![side(&self.bids, -1.0), side(&self.asks, 1.0)]
+    }
+
+    /// Levels laid out for `spread` and `tick`, to be priced and sized around any center
+    ///
+    /// `version` identifies this ladder, so a cached template can tell it's still current
+    /// without comparing the levels.
+    pub fn template(&self, spread: f64, tick: f64, version: u64) -> QuoteTemplate {
+        let distances = |ladder: &SideLadder, direction: f64| -> Vec<f64> {
+            ladder.offsets.iter().map(|offset| direction * (spread + offset) * tick).collect()
+        };
+        let unpriced = |ladder: &SideLadder| -> Vec<SideQuote> {
+            ladder.sizes.iter().map(|size| SideQuote::new(0.0, *size)).collect()
+        };
+        QuoteTemplate {
+            version,
+            spread,
+            tick,
+            distances: [distances(&self.bids, -1.0), distances(&self.asks, 1.0)],
+            sizes: [self.bids.sizes.clone(), self.asks.sizes.clone()],
+            anchor: None,
+            quotes: vec![unpriced(&self.bids), unpriced(&self.asks)],
+        }
+    }
+}
+
+/// Quote ladder with the level distances worked out once
+///
+/// Pricing is a single add-and-round per level and sizing a single multiply, both
+/// skipped while the center and size multiplier stay where the cached quotes were
+/// priced. Quotes are identical to `LadderBuilder::build` with the same inputs.
+#[derive(Debug, Clone)]
+pub struct QuoteTemplate {
+    /// Version of the ladder the template was laid out from
+    version: u64,
+    spread: f64,
+    tick: f64,
+
+    /// Signed distance of each level from the center, [bids, asks]
+    distances: [Vec<f64>; 2],
+
+    /// Unscaled size of each level, [bids, asks]
+    sizes: [Vec<f64>; 2],
+
+    /// Center and size multiplier the cached quotes were priced at
+    anchor: Option<(f64, f64)>,
+
+    /// Priced and sized levels around `anchor`
+    quotes: Vec<Vec<SideQuote>>,
+}
+
+impl QuoteTemplate {
+    /// Whether the template was laid out from these inputs
+    pub fn matches(&self, version: u64, spread: f64, tick: f64) -> bool {
+        self.version == version && self.spread == spread && self.tick == tick
+    }
+
+    /// Bid and ask quotes around `center` with sizes scaled by `size_multiplier`, prices
+    /// rounded to the tick
+    pub fn quotes(&mut self, center: f64, size_multiplier: f64) -> Vec<Vec<SideQuote>> {
+        self.priced(center, size_multiplier).to_vec()
+    }
+
+    /// The cached quotes, repriced first if `center` or `size_multiplier` changed
+    pub fn priced(&mut self, center: f64, size_multiplier: f64) -> &[Vec<SideQuote>] {
+        if self.anchor != Some((center, size_multiplier)) {
+            let tick = self.tick;
+            for ((quotes, distances), sizes) in self.quotes.iter_mut().zip(&self.distances).zip(&self.sizes) {
+                for ((quote, distance), size) in quotes.iter_mut().zip(distances).zip(sizes) {
+                    quote.price = tick * ((center + distance) / tick).round();
+                    quote.amount = size * size_multiplier;
+                }
+            }
+            self.anchor = Some((center, size_multiplier));
+        }
+        &self.quotes
+    }
+}
+
+/// Offsets of the first `levels` levels for a shape
+fn offsets(shape: &LadderShape, levels: usize) -> Result<Vec<f64>> {
+    let offsets: Vec<f64> = match shape {
+        LadderShape::Linear { step } => {
+            (0..levels).map(|lvl| step * lvl as f64).collect()
+        }
+        LadderShape::Geometric { step, ratio } => {
+            if !ratio.is_finite() || *ratio <= 0.0 {
+                return Err(anyhow!("Geometric ladder ratio must be positive: {}", ratio));
+            }
+            let mut offsets = Vec::with_capacity(levels);
+            let (mut offset, mut gap) = (0.0, *step);
+            for _ in 0..levels {
+                offsets.push(offset);
+                offset += gap;
+                gap *= ratio;
+            }
+            offsets
+        }
+        LadderShape::Custom { offsets } => {
+            if offsets.len() < levels {
+                return Err(anyhow!("Custom ladder has {} offsets for {} levels", offsets.len(), levels));
+            }
+            offsets[..levels].to_vec()
+        }
+    };
+    if offsets.iter().any(|o| !o.is_finite() || *o < 0.0) || offsets.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(anyhow!("Ladder offsets must be non-negative and increasing: {:?}", offsets));
+    }
+    Ok(offsets)
+}
@@ -1,114 +1,413 @@
 use anyhow::{anyhow, Result};
-use log::{debug, error};
+use log::{debug, error, info, warn};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::{Notify, RwLock};
+use tokio::sync::{Mutex, Notify, RwLock};
 
 use crate::infrastructure::kafka::KafkaProducer;
+use crate::domain::model::book::{BookLevelUpdate, OrderBook};
+use crate::domain::model::exchange::Instrument;
+use crate::domain::model::features::MarketFeatures;
 use crate::domain::model::ticker::Ticker;
+use crate::domain::model::ticker_delta::TickerDelta;
+use crate::infrastructure::exchange::thalex::channel::Channel;
+use crate::infrastructure::metrics;
+use crate::domain::clock;
+
+use super::book_recorder::BookRecorder;
+use super::config;
+use super::fair_value::ExternalFairValue;
+use super::features::FeatureEngine;
+use super::index_filter::IndexFilter;
+use super::publish_queue::PublishQueues;
+use super::ticker_delta::TickerDeltaRecorder;
+use super::ticker_sampler::TickerSampler;
+
+/// 1 while quotes are centered on the external fair value, 0 on the exchange index
+pub const METRIC_FAIR_VALUE_EXTERNAL: &str = "quote.fair_value_external";
+
+/// Market state of one tracked instrument
+#[derive(Debug, Clone)]
+pub struct InstrumentData {
+    /// Tick size
+    pub tick: f64,
+    
+    /// Latest ticker, None before the first update
+    pub ticker: Option<Ticker>,
+    
+    /// Signalled on every ticker update of the instrument
+    pub notify: Arc<Notify>,
+}
+
+impl InstrumentData {
+    pub fn new(tick: f64) -> Self {
+        Self { tick, ticker: None, notify: Arc::new(Notify::new()) }
+    }
+}
 
 /// Handles market data updates and processing
+///
+/// Every tracked instrument keeps its own tick, ticker and notification. One of them is
+/// the quoted instrument, whose ticker also drives the index, features and `quote_notify`.
 pub struct MarketDataManager {
-    /// Current ticker data
-    pub ticker: RwLock<Option<Ticker>>,
+    /// Tracked instruments by name
+    pub instruments: RwLock<HashMap<String, InstrumentData>>,
     
     /// Current index price
     pub index_price: RwLock<Option<f64>>,
     
-    /// Tick size for the instrument
-    pub tick: RwLock<Option<f64>>,
-    
-    /// Instrument name
+    /// Quoted instrument name
     pub perp_name: RwLock<Option<String>>,
     
     /// Notification for quoting logic
     pub quote_notify: Arc<Notify>,
     
+    /// Spike filter applied to index prices before they drive quoting
+    pub index_filter: RwLock<IndexFilter>,
+    
+    /// Rolling microstructure feature state
+    pub features: RwLock<FeatureEngine>,
+    
+    /// Configured order book subscriptions
+    pub book_channels: RwLock<Vec<Channel>>,
+    
+    /// Snapshot/delta state of the subscribed books
+    pub book_recorder: RwLock<BookRecorder>,
+    
+    /// Ordered Kafka publishing of each book channel's updates, None without a producer
+    book_publisher: Option<Mutex<PublishQueues<Channel, BookLevelUpdate>>>,
+    
+    /// Live order book of every book channel; channels of one instrument with
+    /// another depth or grouping publish different levels
+    pub order_books: RwLock<HashMap<Channel, OrderBook>>,
+    
+    /// Option instruments selected by `[thalex.options]`, by name
+    pub options: RwLock<HashMap<String, Instrument>>,
+    
+    /// Latest ticker per instrument awaiting the downsampled publish
+    pub ticker_sampler: RwLock<TickerSampler>,
+    
+    /// Changed-field state of every ticker channel; full tickers are published when None
+    pub ticker_deltas: RwLock<Option<TickerDeltaRecorder>>,
+    
+    /// Ordered Kafka publishing of each ticker channel's full tickers, None without a producer
+    ticker_publisher: Option<Mutex<PublishQueues<Channel, Ticker>>>,
+    
+    /// Ordered Kafka publishing of each ticker channel's deltas, None without a producer
+    ticker_delta_publisher: Option<Mutex<PublishQueues<Channel, TickerDelta>>>,
+    
+    /// External fair value quotes are centered on while fresh; the index is used when None
+    pub external_fair_value: RwLock<Option<ExternalFairValue>>,
+    
+    /// Whether the last fair value came from the external feed
+    pub using_external_fair_value: AtomicBool,
+    
     /// Kafka producer for sending market data
     pub kafka_producer: Option<Arc<KafkaProducer>>,
+    
+    /// Hot standby: market data is tracked but neither published nor quoted on
+    pub standby: AtomicBool,
 }
 
 impl MarketDataManager {
     pub fn new(quote_notify: Arc<Notify>, kafka_producer: Option<Arc<KafkaProducer>>) -> Self {
         Self {
-            ticker: RwLock::new(None),
+            instruments: RwLock::new(HashMap::new()),
             index_price: RwLock::new(None),
-            tick: RwLock::new(None),
             perp_name: RwLock::new(None),
+            index_filter: RwLock::new(IndexFilter::new(config::INDEX_MAX_JUMP)),
+            features: RwLock::new(FeatureEngine::new(config::FEATURES_WINDOW_SEC).with_book_levels(config::BOOK_IMBALANCE_LEVELS)),
+            book_channels: RwLock::new(Vec::new()),
+            book_recorder: RwLock::new(BookRecorder::new(config::BOOK_SNAPSHOT_INTERVAL_SEC)),
+            book_publisher: kafka_producer.clone().map(|producer| {
+                Mutex::new(PublishQueues::new("book update", config::PUBLISH_QUEUE_SIZE, move |update: BookLevelUpdate| {
+                    let producer = producer.clone();
+                    async move { producer.send_book_level(&update).await }
+                }))
+            }),
+            order_books: RwLock::new(HashMap::new()),
+            options: RwLock::new(HashMap::new()),
+            ticker_sampler: RwLock::new(TickerSampler::new()),
+            ticker_deltas: RwLock::new(None),
+            ticker_publisher: kafka_producer.clone().map(|producer| {
+                Mutex::new(PublishQueues::new("ticker", config::PUBLISH_QUEUE_SIZE, move |ticker: Ticker| {
+                    let producer = producer.clone();
+                    async move { producer.send_ticker(&ticker).await }
+                }))
+            }),
+            ticker_delta_publisher: kafka_producer.clone().map(|producer| {
+                Mutex::new(PublishQueues::new("ticker delta", config::PUBLISH_QUEUE_SIZE, move |delta: TickerDelta| {
+                    let producer = producer.clone();
+                    async move { producer.send_ticker_delta(&delta).await }
+                }))
+            }),
+            external_fair_value: RwLock::new(None),
+            using_external_fair_value: AtomicBool::new(false),
             quote_notify,
             kafka_producer,
+            standby: AtomicBool::new(false),
         }
     }
 
-    /// Set ticker information
+    pub fn is_standby(&self) -> bool {
+        self.standby.load(Ordering::Relaxed)
+    }
+
+    /// Set the quoted instrument, tracking it if it isn't yet
     pub async fn set_instrument_info(&self, name: String, tick_size: f64) -> Result<()> {
-        let mut tick_guard = self.tick.write().await;
-        let mut perp_name_guard = self.perp_name.write().await;
-        
-        *tick_guard = Some(tick_size);
-        *perp_name_guard = Some(name);
-        
+        self.track_instrument(&name, tick_size).await;
+        *self.perp_name.write().await = Some(name);
         Ok(())
     }
 
+    /// Track an instrument's ticker, returning the notification signalled on its updates
+    ///
+    /// Tracking an instrument again updates its tick size and keeps its state.
+    pub async fn track_instrument(&self, name: &str, tick_size: f64) -> Arc<Notify> {
+        let mut instruments = self.instruments.write().await;
+        let data = instruments.entry(name.to_string()).or_insert_with(|| InstrumentData::new(tick_size));
+        data.tick = tick_size;
+        data.notify.clone()
+    }
+
+    /// Stop tracking an instrument; the quoted instrument stays tracked
+    pub async fn untrack_instrument(&self, name: &str) -> bool {
+        if self.perp_name.read().await.as_deref() == Some(name) {
+            return false;
+        }
+        self.instruments.write().await.remove(name).is_some()
+    }
+
+    /// Names of the tracked instruments, sorted
+    pub async fn tracked_instruments(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.instruments.read().await.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Tick size of a tracked instrument
+    pub async fn tick_of(&self, instrument: &str) -> Option<f64> {
+        self.instruments.read().await.get(instrument).map(|data| data.tick)
+    }
+
+    /// Latest ticker of a tracked instrument, None before its first update
+    pub async fn ticker_of(&self, instrument: &str) -> Option<Ticker> {
+        self.instruments.read().await.get(instrument).and_then(|data| data.ticker.clone())
+    }
+
+    /// Notification signalled on every ticker update of a tracked instrument
+    pub async fn notifier(&self, instrument: &str) -> Option<Arc<Notify>> {
+        self.instruments.read().await.get(instrument).map(|data| data.notify.clone())
+    }
+
+    /// Tick size of the quoted instrument
+    pub async fn tick(&self) -> Option<f64> {
+        let perp_name = self.perp_name.read().await.clone()?;
+        self.tick_of(&perp_name).await
+    }
+
+    /// Latest ticker of the quoted instrument
+    pub async fn ticker(&self) -> Option<Ticker> {
+        let perp_name = self.perp_name.read().await.clone()?;
+        self.ticker_of(&perp_name).await
+    }
+
+    /// Forget an instrument's ticker, e.g. when its market data is unsubscribed
+    pub async fn clear_ticker(&self, instrument: &str) {
+        if let Some(data) = self.instruments.write().await.get_mut(instrument) {
+            data.ticker = None;
+        }
+    }
+
     /// Get the channels to subscribe for market data
-    pub async fn get_public_channels(&self) -> Result<Vec<String>> {
-        let perp_name = self.perp_name.read().await;
-        let name = perp_name
-            .as_ref()
+    pub async fn get_public_channels(&self) -> Result<Vec<Channel>> {
+        let name = self.perp_name.read().await.clone()
             .ok_or_else(|| anyhow!("perp_name not set"))?;
             
-        Ok(vec![
-            format!("ticker.{}.raw", name),
-            format!("price_index.{}", super::config::UNDERLYING),
-        ])
+        let mut channels = vec![
+            Channel::ticker(&name),
+            Channel::Index(config::UNDERLYING.to_string()),
+            Channel::recent_trades(&name),
+        ];
+        channels.extend(self.book_channels.read().await.iter().cloned());
+        // Quoting reads the quoted instrument's book, so it's subscribed even when not configured
+        if let Some(channel) = self.quote_book_channel().await.filter(|channel| !channels.contains(channel)) {
+            channels.push(channel);
+        }
+        channels.extend(self.tracked_instruments().await.iter()
+            .filter(|instrument| **instrument != name)
+            .map(|instrument| Channel::ticker(instrument)));
+        Ok(channels)
+    }
+
+    /// Set the selected option instruments, replacing any previous selection
+    ///
+    /// Selected options are tracked; options dropped from the selection no longer are.
+    pub async fn set_options(&self, options: Vec<Instrument>) {
+        let previous: Vec<String> = self.options.read().await.keys().cloned().collect();
+        for name in previous {
+            if !options.iter().any(|option| option.instrument_name == name) {
+                self.untrack_instrument(&name).await;
+            }
+        }
+        for option in &options {
+            self.track_instrument(&option.instrument_name, option.tick_size).await;
+        }
+        *self.options.write().await = options.into_iter()
+            .map(|option| (option.instrument_name.clone(), option))
+            .collect();
     }
 
-    /// Round a value to the nearest tick
+    /// Specification of a selected option, None for instruments that weren't selected
+    pub async fn option(&self, instrument: &str) -> Option<Instrument> {
+        self.options.read().await.get(instrument).cloned()
+    }
+
+    /// Publish changed-field ticker deltas instead of full tickers
+    pub async fn enable_ticker_deltas(&self) {
+        *self.ticker_deltas.write().await = Some(TickerDeltaRecorder::new(config::TICKER_SNAPSHOT_INTERVAL_SEC));
+    }
+
+    /// Center quotes on an external fair value, falling back to the index once it is older than `max_age_sec`
+    pub async fn enable_external_fair_value(&self, max_age_sec: f64) {
+        *self.external_fair_value.write().await = Some(ExternalFairValue::new(max_age_sec));
+    }
+
+    /// Record a price from the external fair value feed observed at `at`
+    pub async fn set_external_fair_value(&self, price: f64, at: f64) {
+        let accepted = match self.external_fair_value.write().await.as_mut() {
+            Some(fair_value) => fair_value.update(price, at),
+            None => false,
+        };
+        if accepted {
+            self.quote_notify.notify_one();
+        }
+    }
+
+    /// Price quotes are centered on: the external fair value while fresh, the index otherwise
+    pub async fn fair_value(&self) -> Option<f64> {
+        let now = clock::now_secs();
+        self.fair_value_at(now).await
+    }
+
+    /// Fair value as of `now`
+    pub async fn fair_value_at(&self, now: f64) -> Option<f64> {
+        let external = match self.external_fair_value.read().await.as_ref() {
+            Some(fair_value) => fair_value.fresh(now),
+            None => return *self.index_price.read().await,
+        };
+        
+        let using_external = external.is_some();
+        if self.using_external_fair_value.swap(using_external, Ordering::Relaxed) != using_external {
+            if using_external {
+                info!("External fair value is fresh, quoting around it");
+            } else {
+                warn!("External fair value is stale, falling back to the exchange index");
+            }
+        }
+        metrics::global().set_gauge(METRIC_FAIR_VALUE_EXTERNAL, if using_external { 1.0 } else { 0.0 });
+        
+        match external {
+            Some(price) => Some(price),
+            None => *self.index_price.read().await,
+        }
+    }
+
+    /// Set the order book subscriptions
+    pub async fn set_book_channels(&self, channels: Vec<Channel>) {
+        *self.book_channels.write().await = channels;
+    }
+
+    /// Book channel quoting reads the quoted instrument's book from: the first one
+    /// configured for it, else the default book at `QUOTE_BOOK_DEPTH`
+    pub async fn quote_book_channel(&self) -> Option<Channel> {
+        let name = self.perp_name.read().await.clone()?;
+        let configured = self.book_channels.read().await.iter()
+            .find(|channel| matches!(channel, Channel::Book { .. }) && channel.instrument() == Some(name.as_str()))
+            .cloned();
+        Some(configured.unwrap_or_else(|| Channel::book(&name, config::QUOTE_BOOK_DEPTH)))
+    }
+
+    /// Current order book of a book channel, None before its first update
+    pub async fn order_book(&self, channel: &Channel) -> Option<OrderBook> {
+        self.order_books.read().await.get(channel).cloned()
+    }
+
+    /// Round a value to the nearest tick of the quoted instrument
     pub async fn round_to_tick(&self, value: f64) -> Result<f64> {
-        let tick_guard = self.tick.read().await;
-        let tick = tick_guard.ok_or_else(|| anyhow!("Tick size not initialized"))?;
+        let tick = self.tick().await.ok_or_else(|| anyhow!("Tick size not initialized"))?;
         Ok(tick * (value / tick).round())
     }
 
     /// Process ticker updates and send to Kafka
-    pub async fn handle_ticker(&self, notification: &Value) -> Result<()> {
-        // Get the instrument name for the Ticker
-        let instrument_name = self.perp_name.read().await.clone()
-            .unwrap_or_else(|| "BTC-PERPETUAL".to_string());
+    ///
+    /// Tickers for every subscribed instrument are published, but only the quoted
+    /// instrument's ticker drives the trading state.
+    pub async fn handle_ticker(&self, channel: &Channel, notification: &Value) -> Result<()> {
+        let Some(instrument_name) = channel.instrument() else {
+            return Err(anyhow!("Not a ticker channel: {}", channel));
+        };
+        let is_quoted = match &*self.perp_name.read().await {
+            Some(perp_name) => perp_name == instrument_name,
+            None => true,
+        };
         
-        match Ticker::from_json(notification, instrument_name.clone()) {
+        match Ticker::from_json(notification, instrument_name.to_string()) {
             Ok(ticker) => {
                 debug!("Ticker update: mark_price={}, index={}, funding_rate={}", 
                     ticker.mark_price, ticker.index_price, ticker.funding_rate);
                 
-                // Send to Kafka if enabled
-                if let Some(kafka_producer) = &self.kafka_producer {
-                    // Use the ticker directly since we don't have a separate TickerData type
-                    let kafka_ticker_data = ticker.clone();
-                    
-                    // Clone the producer reference and tick_data for use in async task
-                    let kafka_producer_clone = kafka_producer.clone();
-                    let ticker_data_clone = kafka_ticker_data.clone();
-                    
-                    // Spawn a task to handle the Kafka send without blocking
-                    tokio::spawn(async move {
-                        if let Err(e) = kafka_producer_clone.send_ticker(&ticker_data_clone).await {
-                            error!("Failed to send ticker data to Kafka: {:?}", e);
-                        } else {
-                            debug!("Successfully sent ticker data to Kafka");
+                self.ticker_sampler.write().await.record(&ticker);
+                
+                // Publish in order per channel, unless standing by: the primary instance publishes then
+                match &mut *self.ticker_deltas.write().await {
+                    // Start from a fresh snapshot after leaving standby, since nothing was published during it
+                    Some(recorder) if self.is_standby() => recorder.clear(),
+                    Some(recorder) => {
+                        let delta = recorder.on_ticker(channel, &ticker, ticker.processing_timestamp.unwrap_or_default());
+                        if let (Some(publisher), Some(delta)) = (&self.ticker_delta_publisher, delta) {
+                            if !publisher.lock().await.push(channel, vec![delta]) {
+                                warn!("Kafka queue of {} full, resending it as a snapshot", channel);
+                                recorder.resync(channel);
+                            }
+                        }
+                    }
+                    None => {
+                        if let Some(publisher) = self.ticker_publisher.as_ref().filter(|_| !self.is_standby()) {
+                            if !publisher.lock().await.push(channel, vec![ticker.clone()]) {
+                                warn!("Kafka queue of {} full, dropping a ticker", channel);
+                            }
                         }
-                    });
+                    }
                 }
                 
-                // Store the ticker for trading logic
-                let mut ticker_guard = self.ticker.write().await;
-                *ticker_guard = Some(ticker);
+                if !is_quoted {
+                    let mut instruments = self.instruments.write().await;
+                    match instruments.get_mut(instrument_name) {
+                        Some(data) => {
+                            data.ticker = Some(ticker);
+                            data.notify.notify_one();
+                        }
+                        None => debug!("Ticker for untracked instrument {} published only", instrument_name),
+                    }
+                    return Ok(());
+                }
                 
-                // Update index price for easier access
-                let mut index_guard = self.index_price.write().await;
-                if let Some(ticker) = &*ticker_guard {
-                    *index_guard = Some(ticker.index_price);
+                // Feed the rolling feature windows
+                let now = clock::now_secs();
+                self.features.write().await.on_ticker(&ticker, now);
+                
+                // Update index price for easier access, unless it looks like a bad print
+                if let Some(price) = self.index_filter.write().await.filter(ticker.index_price) {
+                    *self.index_price.write().await = Some(price);
+                }
+                
+                // Store the ticker for trading logic
+                if let Some(data) = self.instruments.write().await.get_mut(instrument_name) {
+                    data.ticker = Some(ticker);
+                    data.notify.notify_one();
                 }
                 
                 // Notify the quote task about the new data
@@ -122,21 +421,91 @@ impl MarketDataManager {
         }
     }
 
+    /// Process order book updates, keeping the instrument's book and persisting
+    /// snapshots and deltas to Kafka
+    ///
+    /// Each channel's updates are published in order by one task; when its queue is
+    /// full the batch is dropped and the channel's next update is sent as a snapshot.
+    pub async fn handle_book(&self, channel: &Channel, notification: &Value) -> Result<()> {
+        let Some(instrument_name) = channel.instrument() else {
+            return Err(anyhow!("Not a book channel: {}", channel));
+        };
+        let now = clock::now_secs();
+        let standby = self.is_standby();
+        let updates = {
+            let mut recorder = self.book_recorder.write().await;
+            // Start from a fresh snapshot after leaving standby, since nothing was published during it
+            if standby {
+                recorder.clear();
+            }
+            let updates = recorder.on_book(channel, notification, now)?;
+            // Queue while holding the recorder, so batches of a channel are queued in sequence order
+            if let Some(publisher) = self.book_publisher.as_ref().filter(|_| !standby) {
+                if !publisher.lock().await.push(channel, updates.clone()) {
+                    warn!("Kafka queue of {} full, resending it as a snapshot", channel);
+                    recorder.resync(channel);
+                }
+            }
+            updates
+        };
+        
+        let mut books = self.order_books.write().await;
+        let book = books.entry(channel.clone()).or_insert_with(|| OrderBook::new(instrument_name));
+        for update in &updates {
+            book.apply(update);
+        }
+        if self.quote_book_channel().await.as_ref() == Some(channel) {
+            self.features.write().await.on_book(book);
+        }
+        Ok(())
+    }
+
     /// Process index price updates
     pub async fn handle_index(&self, price: f64) -> Result<()> {
         debug!("Index price update: {}", price);
         
+        let price = match self.index_filter.write().await.filter(price) {
+            Some(price) => price,
+            None => return Ok(()),
+        };
+        
         let mut index_guard = self.index_price.write().await;
         *index_guard = Some(price);
         
-        // Update the ticker's index price if it exists
-        let mut ticker_guard = self.ticker.write().await;
-        if let Some(ticker) = &mut *ticker_guard {
-            ticker.index_price = price;
+        // Update the index price of the tickers on this index
+        for data in self.instruments.write().await.values_mut() {
+            if let Some(ticker) = &mut data.ticker {
+                ticker.index_price = price;
+            }
         }
+        self.features.write().await.on_index(price);
         
         // Notify the quote task about the new data
         self.quote_notify.notify_one();
         Ok(())
     }
+
+    /// Count the market's trades in the quoted instrument for the trade-intensity feature
+    pub async fn handle_recent_trades(&self, channel: &Channel, notification: &Value) -> Result<()> {
+        if channel.instrument() != self.perp_name.read().await.as_deref() {
+            return Ok(());
+        }
+        let trades = notification.as_array().ok_or_else(|| anyhow!("Expected an array of trades on {}", channel))?;
+        for _ in trades {
+            self.record_trade().await;
+        }
+        Ok(())
+    }
+
+    /// Record an observed trade for the trade-intensity feature
+    pub async fn record_trade(&self) {
+        let now = clock::now_secs();
+        self.features.write().await.on_trade(now);
+    }
+
+    /// Compute the current microstructure features
+    pub async fn compute_features(&self) -> Option<MarketFeatures> {
+        let now = clock::now_secs();
+        self.features.write().await.compute(now)
+    }
 }
@@ -0,0 +1,64 @@
+use crate::config_loader::MassQuoteConfig;
+use crate::domain::model::quote::SideQuote;
+
+/// Counter of mass quotes sent
+pub const METRIC_MASS_QUOTES: &str = "orders.mass_quotes";
+
+/// Counter of mass quote levels the exchange refused
+pub const METRIC_MASS_QUOTE_LEVELS_REFUSED: &str = "orders.mass_quote_levels_refused";
+
+/// Quoting the best level of each side through one mass quote per adjustment
+///
+/// The exchange keeps a single mass quote per side and instrument, so only the first
+/// level goes out this way; deeper levels are placed as orders. The quote is only sent
+/// again once a side moved or resized by more than the tolerances of the amend policy
+/// would let an order rest.
+#[derive(Debug, Clone)]
+pub struct MassQuoteStrategy {
+    pub post_only: bool,
+
+    /// Best bid and ask of the last mass quote sent; None until one is sent or after the
+    /// exchange refused one
+    sent: Option<[Option<SideQuote>; 2]>,
+}
+
+impl MassQuoteStrategy {
+    pub fn new(post_only: bool) -> Self {
+        Self { post_only, sent: None }
+    }
+
+    pub fn from_config(config: &MassQuoteConfig) -> Self {
+        Self::new(config.post_only)
+    }
+
+    /// Whether `desired` differs from the quote last sent; `differs` compares the side
+    /// sent with the one wanted
+    pub fn needs_update<F>(&self, desired: &[Option<SideQuote>; 2], mut differs: F) -> bool
+    where
+        F: FnMut(&SideQuote, &SideQuote) -> bool,
+    {
+        let Some(sent) = &self.sent else {
+            return true;
+        };
+        sent.iter().zip(desired).any(|(sent, desired)| match (sent, desired) {
+            (Some(sent), Some(desired)) => differs(sent, desired),
+            (None, None) => false,
+            _ => true,
+        })
+    }
+
+    /// Record the sides of a mass quote handed to the exchange
+    pub fn sent(&mut self, quote: [Option<SideQuote>; 2]) {
+        self.sent = Some(quote);
+    }
+
+    /// The exchange refused the last mass quote; the next adjustment sends it again
+    pub fn rejected(&mut self) {
+        self.sent = None;
+    }
+
+    /// Best bid and ask of the last mass quote sent
+    pub fn quoted(&self) -> Option<&[Option<SideQuote>; 2]> {
+        self.sent.as_ref()
+    }
+}
@@ -3,15 +3,82 @@
 //! This module contains the full strategy logic for market making on Thalex,
 //! including market data handling, order management, quoting, and message routing.
 
+mod amend;
+mod book_recorder;
+mod carry;
+mod collar;
 mod config;
+mod control;
+mod daily_stats;
+mod experiment;
+mod fair_value;
+mod features;
+mod fees;
+mod heartbeat;
+mod index_filter;
+mod inflight;
+mod ladder;
 mod market_data;
+mod mass_quote;
 mod order_manager;
 mod notification_handler;
+mod plugin;
+mod publish_queue;
+mod quote_orders;
+mod risk;
+mod router;
+mod scheduler;
+mod session_summary;
+mod side_budget;
+mod subscriptions;
+mod sizing;
+mod spread;
+mod staleness;
+mod ticker_delta;
+mod ticker_sampler;
+mod unknown_channel;
+mod uptime;
 pub mod quoter; // contains ThalexQuoter runner
 
 // Re-export core strategy components
+pub use amend::{AmendPolicy, Amendment};
+pub use book_recorder::BookRecorder;
+pub use carry::CarryTracker;
+pub use collar::{CollarGuard, METRIC_COLLAR_STRESSED};
 pub use config::*;
-pub use market_data::MarketDataManager;
-pub use order_manager::OrderManager;
+pub use control::{ControlCommand, ControlMessage};
+pub use daily_stats::{day_of, DailyStats};
+pub use experiment::{Experiment, Variant};
+pub use fair_value::{price_at, ExternalFairValue};
+pub use features::FeatureEngine;
+pub use fees::FeeSchedule;
+pub use heartbeat::{positions_hash, HeartbeatTracker};
+pub use index_filter::IndexFilter;
+pub use inflight::{is_own_label, order_label, reconcile, tag_of, variant_of_label, InflightInsert, InflightOrders, Reconciliation};
+pub use ladder::{LadderBuilder, QuoteTemplate};
+pub use market_data::{InstrumentData, MarketDataManager};
+pub use mass_quote::{MassQuoteStrategy, METRIC_MASS_QUOTES, METRIC_MASS_QUOTE_LEVELS_REFUSED};
+pub use order_manager::{
+    OrderManager, METRIC_FILLS, METRIC_FILL_VOLUME, METRIC_MAX_INVENTORY, METRIC_ORDERS_AMENDED,
+    METRIC_ORDERS_CANCELLED, METRIC_ORDERS_INSERTED, METRIC_REALIZED_PNL, METRIC_RISK_REJECTS,
+    METRIC_INFLIGHT_INSERTS, METRIC_INSERTS_ADOPTED, METRIC_SIDE_BUDGET_EXHAUSTED, METRIC_ACCOUNT_EVENTS,
+    METRIC_TRADE_CORRECTIONS, METRIC_SIDE_CANCELS,
+};
 pub use notification_handler::NotificationHandler;
+pub use plugin::NotificationPlugin;
+pub use publish_queue::PublishQueues;
+pub use quote_orders::QuoteOrders;
+pub use risk::{LeverageTier, RiskManager};
+pub use router::{InboundMessage, Priority};
+pub use scheduler::{ParameterScheduler, QuoteParams};
+pub use session_summary::summarize_session;
+pub use side_budget::SideBudget;
+pub use sizing::SizeScaler;
+pub use spread::{HedgeRequest, LinkedOrder, SpreadLegs, HEDGE_LABEL, HEDGE_MAX_ATTEMPTS, METRIC_SPREAD_LEG_RISK};
+pub use staleness::{age_metric, consumer_lag, lag_metric, Staleness, StalenessGuard, WatermarkCache, METRIC_STALE_MESSAGES};
+pub use subscriptions::{SubscriptionState, SubscriptionTracker};
+pub use ticker_delta::TickerDeltaRecorder;
+pub use ticker_sampler::TickerSampler;
+pub use unknown_channel::RawChannelPublisher;
+pub use uptime::UptimeTracker;
 pub use quoter::ThalexQuoter;
@@ -1,82 +1,346 @@
 use anyhow::Result;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
 
-use crate::domain::constants::*;
+use crate::infrastructure::exchange::thalex::calls::{CallRegistry, PendingCall, RpcMethod};
+use crate::infrastructure::exchange::thalex::channel::Channel;
+use crate::infrastructure::exchange::thalex::models::RpcResult;
+use crate::infrastructure::exchange::thalex::outbound::is_rate_limit_error;
+use crate::infrastructure::metrics;
+use crate::domain::clock::now_secs;
 
+use super::config;
+use super::heartbeat::HeartbeatTracker;
 use super::market_data::MarketDataManager;
+use super::mass_quote::METRIC_MASS_QUOTE_LEVELS_REFUSED;
 use super::order_manager::OrderManager;
+use super::plugin::NotificationPlugin;
+use super::router::InboundMessage;
+use super::subscriptions::SubscriptionTracker;
+
+/// Notifications received on channels without typed support
+pub const METRIC_UNKNOWN_CHANNEL: &str = "thalex.unknown_channel_notifications";
+
+/// Channel subscriptions the exchange rejected
+pub const METRIC_SUBSCRIBE_FAILURES: &str = "thalex.subscribe_failures";
 
 /// Handles WebSocket notifications and routes them to appropriate handlers
 pub struct NotificationHandler {
     pub market_data: Arc<MarketDataManager>,
     pub order_manager: Arc<OrderManager>,
+    
+    /// Channels currently subscribed; notifications are only routed for these
+    pub subscriptions: RwLock<HashSet<Channel>>,
+    
+    /// Whether each subscription was confirmed, and when rejected ones are retried
+    pub subscription_states: RwLock<SubscriptionTracker>,
+    
+    /// Additional handlers registered for specific channels/events
+    pub plugins: RwLock<Vec<Arc<dyn NotificationPlugin>>>,
+    
+    /// When each kind of notification last arrived
+    pub heartbeat: RwLock<HeartbeatTracker>,
+    
+    /// Outstanding requests, used to attribute results and errors
+    pub calls: Arc<CallRegistry>,
+    
+    /// Notifications seen per unknown channel name, used to sample warnings
+    pub unknown_channels: RwLock<HashMap<String, u64>>,
 }
 
 impl NotificationHandler {
     pub fn new(market_data: Arc<MarketDataManager>, order_manager: Arc<OrderManager>) -> Self {
         Self {
             market_data,
+            calls: order_manager.calls.clone(),
             order_manager,
+            subscriptions: RwLock::new(HashSet::new()),
+            subscription_states: RwLock::new(SubscriptionTracker::new(
+                config::SUBSCRIBE_RETRY_INITIAL_SEC,
+                config::SUBSCRIBE_RETRY_MAX_SEC,
+            )),
+            plugins: RwLock::new(Vec::new()),
+            heartbeat: RwLock::new(HeartbeatTracker::new()),
+            unknown_channels: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Register an additional handler for notifications and RPC events
+    pub async fn register_plugin(&self, plugin: Arc<dyn NotificationPlugin>) {
+        info!("Registering notification plugin: {}", plugin.name());
+        self.plugins.write().await.push(plugin);
+    }
+
+    /// Remove a previously registered handler by name
+    pub async fn unregister_plugin(&self, name: &str) -> bool {
+        let mut plugins = self.plugins.write().await;
+        let before = plugins.len();
+        plugins.retain(|p| p.name() != name);
+        before != plugins.len()
+    }
+
+    /// Start routing notifications for the given channels; returns those not routed before
+    pub async fn add_subscriptions(&self, channels: &[Channel]) -> Vec<Channel> {
+        let mut subscriptions = self.subscriptions.write().await;
+        let mut added = Vec::new();
+        for channel in channels {
+            if subscriptions.insert(channel.clone()) {
+                info!("Routing notifications for channel: {}", channel);
+                added.push(channel.clone());
+            }
+        }
+        added
+    }
+
+    /// Stop routing notifications for the given channels
+    pub async fn remove_subscriptions(&self, channels: &[Channel]) {
+        let mut subscriptions = self.subscriptions.write().await;
+        for channel in channels {
+            if subscriptions.remove(channel) {
+                info!("Stopped routing notifications for channel: {}", channel);
+            }
+        }
+    }
+
+    /// Whether every channel quoting depends on is confirmed by the exchange
+    pub async fn subscriptions_ready(&self) -> bool {
+        self.subscription_states.read().await.is_ready()
+    }
+
+    /// Mark channels of a rejected subscribe call for a retry
+    async fn subscriptions_failed(&self, channels: &[Channel]) {
+        if channels.is_empty() {
+            return;
+        }
+        metrics::global().incr(METRIC_SUBSCRIBE_FAILURES, channels.len() as u64);
+        self.subscription_states.write().await.failed(channels, now_secs());
+    }
+
+    /// Channels currently being routed
+    pub async fn active_subscriptions(&self) -> Vec<Channel> {
+        let mut channels: Vec<Channel> = self.subscriptions.read().await.iter().cloned().collect();
+        channels.sort_by_key(|c| c.to_string());
+        channels
+    }
+
+    /// Dispatch a routed inbound message to the matching callback
+    pub async fn dispatch(&self, message: InboundMessage) -> Result<()> {
+        match message {
+            InboundMessage::Notification { channel, notification } => {
+                self.handle_notification(&channel, &notification).await
+            }
+            InboundMessage::Result { result, cid } => self.result_callback(&result, cid).await,
+            InboundMessage::Error { error, cid } => self.error_callback(&error, cid).await,
         }
     }
 
     /// Process result callback
+    ///
+    /// The result is deserialized to the type expected for the request it answers;
+    /// results of unknown requests or of an unexpected shape are handled as raw values.
     pub async fn result_callback(&self, result: &Value, cid: u64) -> Result<()> {
-        match cid {
-            CALL_ID_INSTRUMENT => {
-                debug!("Instrument result: {}", result);
+        let call = self.calls.resolve(cid, Ok(result));
+        let typed = match &call {
+            Some(call) => RpcResult::parse_or_raw(call.method, result),
+            None => RpcResult::Other(result.clone()),
+        };
+        match typed {
+            RpcResult::Instruments(instruments) => {
+                debug!("Instruments result: {} instruments", instruments.len());
+            }
+            RpcResult::Instrument(instrument) => {
+                debug!("Instrument result: {:?}", instrument);
+            }
+            RpcResult::Subscribe(channels) => {
+                info!("Sub successful: {}", channels.join(", "));
+                // Channels the call named but the exchange left out were not subscribed
+                let requested = call.as_ref().map(requested_channels).unwrap_or_default();
+                let (confirmed, missing): (Vec<Channel>, Vec<Channel>) = requested.into_iter()
+                    .partition(|channel| channels.contains(&channel.to_string()));
+                self.subscription_states.write().await.confirmed(&confirmed);
+                if !missing.is_empty() {
+                    warn!("Subscription not confirmed, retrying: {}",
+                        missing.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", "));
+                }
+                self.subscriptions_failed(&missing).await;
+            }
+            RpcResult::Login(login) => {
+                info!("Login result: account={}", login.account_number.as_deref().unwrap_or("?"));
             }
-            CALL_ID_SUBSCRIBE => {
-                info!("Sub successful: {}", result);
+            RpcResult::CancelSession => {
+                info!("Cancel session acknowledged");
             }
-            CALL_ID_LOGIN => {
-                info!("Login result: {}", result);
+            RpcResult::CancelOnDisconnect => {
+                info!("Set cancel on disconnect acknowledged");
             }
-            CALL_ID_SET_COD => {
-                info!("Set cancel on disconnect result: {}", result);
+            RpcResult::CancelAll(cancelled) => {
+                match cancelled.n_cancelled {
+                    Some(n) => info!("Cancel all result: {} orders cancelled", n),
+                    None => info!("Cancel all acknowledged"),
+                }
+            }
+            RpcResult::Order(order) => {
+                debug!("Trade request result: client_order_id={:?} status={} remaining={:?}",
+                    order.client_order_id, order.status, order.remaining_amount);
+            }
+            RpcResult::MassQuote(quoted) => {
+                debug!("Mass quote result: {} levels placed, {} refused", quoted.n_success, quoted.n_fail);
+                if quoted.n_fail > 0 {
+                    warn!("Mass quote refused {} levels: {:?}", quoted.n_fail, quoted.errors);
+                    metrics::global().incr(METRIC_MASS_QUOTE_LEVELS_REFUSED, quoted.n_fail);
+                    self.order_manager.mass_quote_rejected().await;
+                }
             }
-            _ if cid > 99 => {
-                debug!("Trade request result: {}", result);
+            RpcResult::OpenOrders(orders) => {
+                info!("Open orders result: {} orders", orders.len());
+                self.order_manager.reconcile_open_orders(&orders).await;
             }
-            _ => {
+            RpcResult::Portfolio(positions) => {
+                info!("Portfolio result: {} positions", positions.len());
+                self.order_manager.reconcile_positions(&positions).await;
+            }
+            RpcResult::AccountSummary(summary) => {
+                self.order_manager.update_account(summary.to_domain(now_secs())).await;
+            }
+            RpcResult::Other(result) => {
                 info!("cid={}: result={}", cid, result);
             }
         }
+        
+        for plugin in self.plugins.read().await.iter() {
+            if let Err(e) = plugin.on_result(result, cid).await {
+                warn!("Plugin {} failed on result cid={}: {}", plugin.name(), cid, e);
+            }
+        }
         Ok(())
     }
 
     /// Process error callback
     pub async fn error_callback(&self, error: &Value, cid: u64) -> Result<()> {
-        error!("cid={}: error={}", cid, error);
+        match self.calls.resolve(cid, Err(error)) {
+            Some(call) => {
+                error!("{} failed (cid={}, {}): error={}",
+                    call.method, cid, call.context.as_deref().unwrap_or("-"), error);
+                let client_order_id = call.context.as_deref().and_then(|c| c.parse().ok());
+                match (call.method, client_order_id) {
+                    (RpcMethod::Cancel, Some(client_order_id)) => self.order_manager.cancel_rejected(client_order_id).await,
+                    (RpcMethod::Insert, Some(client_order_id)) => self.order_manager.insert_rejected(client_order_id).await,
+                    (RpcMethod::OpenOrders, _) => self.order_manager.reconcile_failed().await,
+                    (RpcMethod::Portfolio, _) => self.order_manager.positions_failed().await,
+                    (RpcMethod::MassQuote, _) => self.order_manager.mass_quote_rejected().await,
+                    (RpcMethod::CancelSession, _) => self.order_manager.session_cancel_rejected().await,
+                    (RpcMethod::PublicSubscribe | RpcMethod::PrivateSubscribe, _) => {
+                        self.subscriptions_failed(&requested_channels(&call)).await
+                    }
+                    _ => {}
+                }
+            }
+            None => error!("cid={}: error={}", cid, error),
+        }
+        
+        if is_rate_limit_error(error) {
+            self.order_manager.client.lock().await
+                .throttle(Duration::from_millis(config::RATE_LIMIT_BACKOFF_MS));
+        }
+        
+        for plugin in self.plugins.read().await.iter() {
+            if let Err(e) = plugin.on_error(error, cid).await {
+                warn!("Plugin {} failed on error cid={}: {}", plugin.name(), cid, e);
+            }
+        }
         Ok(())
     }
 
     /// Route notifications to the appropriate handler
-    pub async fn handle_notification(&self, channel: &str, notification: &Value) -> Result<()> {
-        match channel {
-            c if c.starts_with("ticker.") => {
-                self.market_data.handle_ticker(notification).await?;
+    pub async fn handle_notification(&self, channel_name: &str, notification: &Value) -> Result<()> {
+        let channel = match channel_name.parse::<Channel>() {
+            Ok(channel) => channel,
+            Err(_) => return self.handle_unknown_channel(channel_name, notification).await,
+        };
+        
+        // Notifications can still arrive briefly after an unsubscribe
+        if !self.subscriptions.read().await.contains(&channel) {
+            debug!("Ignoring notification for unsubscribed channel: {}", channel);
+            return Ok(());
+        }
+        
+        let now = now_secs();
+        self.heartbeat.write().await.record(&channel, now);
+        
+        match &channel {
+            Channel::Ticker { .. } => {
+                self.market_data.handle_ticker(&channel, notification).await?;
             }
-            c if c.starts_with("price_index.") => {
+            Channel::Index(_) => {
                 if let Some(price) = notification.get("price").and_then(|v| v.as_f64()) {
                     self.market_data.handle_index(price).await?;
                 }
             }
-            "session.orders" => {
+            Channel::Orders => {
                 self.order_manager.handle_orders(notification).await?;
             }
-            "account.portfolio" => {
+            Channel::Portfolio => {
                 self.order_manager.handle_portfolio(notification).await?;
             }
-            "account.trade_history" => {
+            Channel::Trades => {
                 self.order_manager.handle_trades(notification).await?;
             }
-            _ => {
-                error!("Unknown notification channel: {}", channel);
+            Channel::AccountEvents => {
+                self.order_manager.handle_account_events(notification).await?;
+            }
+            Channel::Book { .. } => {
+                self.market_data.handle_book(&channel, notification).await?;
+            }
+            Channel::RecentTrades { .. } => {
+                self.market_data.handle_recent_trades(&channel, notification).await?;
+            }
+        }
+        
+        for plugin in self.plugins.read().await.iter() {
+            if plugin.handles(&channel) {
+                if let Err(e) = plugin.on_notification(&channel, notification).await {
+                    warn!("Plugin {} failed on {}: {}", plugin.name(), channel, e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Hand a notification on a channel without typed support to the plugins
+    ///
+    /// A warning is logged for the first notification of each channel name and then
+    /// once per `UNKNOWN_CHANNEL_LOG_EVERY`, so a new channel is noticed without
+    /// flooding the log.
+    async fn handle_unknown_channel(&self, channel_name: &str, notification: &Value) -> Result<()> {
+        metrics::global().incr(METRIC_UNKNOWN_CHANNEL, 1);
+        let seen = {
+            let mut unknown = self.unknown_channels.write().await;
+            let seen = unknown.entry(channel_name.to_string()).or_default();
+            *seen += 1;
+            *seen
+        };
+        if (seen - 1) % config::UNKNOWN_CHANNEL_LOG_EVERY == 0 {
+            warn!("Notification on unknown channel {} ({} so far), passing to fallback handlers", channel_name, seen);
+        } else {
+            debug!("Notification on unknown channel {}", channel_name);
+        }
+        
+        for plugin in self.plugins.read().await.iter() {
+            if let Err(e) = plugin.on_unknown_channel(channel_name, notification).await {
+                warn!("Plugin {} failed on unknown channel {}: {}", plugin.name(), channel_name, e);
             }
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Channels named by a subscribe call, from the context it was registered with
+fn requested_channels(call: &PendingCall) -> Vec<Channel> {
+    call.context.as_deref()
+        .map(|names| names.split(',').filter_map(|name| name.parse().ok()).collect())
+        .unwrap_or_default()
+}
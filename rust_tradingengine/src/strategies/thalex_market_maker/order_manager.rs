@@ -1,122 +1,1004 @@
 use anyhow::{anyhow, Result};
 use log::{debug, error, info, warn};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{Mutex, RwLock};
 
 use crate::domain::enums::*;
+use crate::domain::model::account_event::AccountEventType;
+use crate::domain::model::account_summary::AccountSummary;
 use crate::domain::model::exchange::*;
-use crate::domain::model::order::{Order, order_from_data, side_to_string};
-use crate::domain::model::quote::SideQuote;
-use crate::infrastructure::exchange::thalex::client::ThalexClient;
+use crate::domain::model::order::{Order, OrderState, side_to_string};
+use crate::domain::model::quote::{MassQuote, SideQuote};
+use crate::domain::model::trade_correction::{CorrectionKind, TradeCorrection};
+use crate::infrastructure::exchange::OrderGateway;
+use crate::infrastructure::exchange::thalex::calls::{CallRegistry, RpcMethod};
+use crate::infrastructure::exchange::thalex::models::{OrderResult, PortfolioEntry};
+use crate::infrastructure::exchange::thalex::parsers::ThaleParser;
 use crate::infrastructure::kafka::producer::KafkaProducer;
+use crate::infrastructure::metrics;
+use crate::reporting::eod::{Fill, Journal};
+use crate::config_loader::{RiskConfig, ScheduleConfig};
+use crate::domain::clock::{self, now_secs};
 
+/// Expected edge of a first-level maker fill after fees, in basis points of the price
+pub const METRIC_MAKER_EDGE_BPS: &str = "quote.maker_edge_bps";
+
+/// Multiplier applied to the ladder sizes by equity/volatility scaling
+pub const METRIC_SIZE_MULTIPLIER: &str = "quote.size_multiplier";
+
+/// Counter of quote orders sent
+pub const METRIC_ORDERS_INSERTED: &str = "orders.inserted";
+
+/// Counter of amends sent
+pub const METRIC_ORDERS_AMENDED: &str = "orders.amended";
+
+/// Counter of cancels sent for individual quote orders
+pub const METRIC_ORDERS_CANCELLED: &str = "orders.cancelled";
+
+/// Counter of cancel-alls sent to pull one side of the quoted instrument
+pub const METRIC_SIDE_CANCELS: &str = "orders.side_cancels";
+/// Counter of session-wide cancels, each pulling every quote at once
+pub const METRIC_SESSION_CANCELS: &str = "orders.session_cancels";
+
+/// Counter of our fills since the process started
+pub const METRIC_FILLS: &str = "orders.fills";
+
+/// Amount filled since the process started
+pub const METRIC_FILL_VOLUME: &str = "orders.fill_volume";
+
+/// Realized PnL since the process started, in quote currency
+pub const METRIC_REALIZED_PNL: &str = "orders.realized_pnl";
+
+/// Largest absolute position reported by the venue since the process started
+pub const METRIC_MAX_INVENTORY: &str = "orders.max_inventory";
+
+/// Counter of inserts and amends held back by the pre-trade risk check
+pub const METRIC_RISK_REJECTS: &str = "orders.risk_rejects";
+
+/// Inserts sent whose order hasn't been reported by the exchange yet
+pub const METRIC_INFLIGHT_INSERTS: &str = "orders.inflight_inserts";
+
+/// Counter of in-flight inserts from a previous session found resting and taken over
+pub const METRIC_INSERTS_ADOPTED: &str = "orders.inserts_adopted";
+
+/// Counter of inserts and amends deferred because their side's update budget was spent
+pub const METRIC_SIDE_BUDGET_EXHAUSTED: &str = "orders.side_budget_exhausted";
+
+/// Counter of account events (deposits, withdrawals, liquidations, ...) received
+pub const METRIC_ACCOUNT_EVENTS: &str = "account.events";
+
+/// Counter of trade busts and corrections reported by the exchange
+pub const METRIC_TRADE_CORRECTIONS: &str = "account.trade_corrections";
+
+/// Funding received by the quoted perpetual since the process started, negative when paid
+pub const METRIC_FUNDING_RECEIVED: &str = "account.funding_received";
+
+/// Counter of instruments whose local position differed from the exchange's when reconciled
+pub const METRIC_POSITION_DRIFT: &str = "account.position_drift";
+
+/// Gauge of the account value margin is measured against, from the latest account summary
+pub const METRIC_ACCOUNT_MARGIN: &str = "account.margin";
+
+/// Gauge of the margin the open positions and orders require
+pub const METRIC_ACCOUNT_REQUIRED_MARGIN: &str = "account.required_margin";
+
+/// Gauge of the margin left for new positions and orders
+pub const METRIC_ACCOUNT_REMAINING_MARGIN: &str = "account.remaining_margin";
+
+use super::amend::AmendPolicy;
+use super::carry::CarryTracker;
+use super::collar::CollarGuard;
 use super::config;
+use super::daily_stats::{day_of, DailyStats};
 use super::market_data::MarketDataManager;
+use super::mass_quote::{MassQuoteStrategy, METRIC_MASS_QUOTES};
+use super::quote_orders::QuoteOrders;
+use super::fees::FeeSchedule;
+use super::inflight::{self, InflightInsert, InflightOrders};
+use super::ladder::{LadderBuilder, QuoteTemplate};
+use super::risk::RiskManager;
+use super::scheduler::QuoteParams;
+use super::side_budget::SideBudget;
+use super::sizing::SizeScaler;
+use super::spread::{HedgeRequest, SpreadLegs, HEDGE_LABEL, METRIC_SPREAD_LEG_RISK};
 
 /// Manages order creation, modification, and cancellation
 pub struct OrderManager {
-    /// Client connection
-    pub client: Arc<Mutex<ThalexClient>>,
+    /// Venue connection used for order entry
+    pub client: Arc<Mutex<dyn OrderGateway>>,
     
     /// Market data manager reference
     pub market_data: Arc<MarketDataManager>,
     
-    /// Orders organized by side [bids, asks]
-    pub orders: RwLock<Vec<Vec<Order>>>,
+    /// Orders organized by side [bids, asks], indexed by client order id
+    pub orders: RwLock<QuoteOrders>,
     
     /// Client order ID counter
     pub client_order_id: RwLock<u64>,
     
+    /// Request ids of order entry calls; shared with the venue connection's other requests
+    pub calls: Arc<CallRegistry>,
+    
+    /// Fills without a known maker/taker role are rejected instead of journaled without one
+    pub strict_parsing: bool,
+    
     /// Portfolio positions
     pub portfolio: RwLock<HashMap<String, f64>>,
     
     /// Kafka producer for messaging
     pub kafka_producer: Option<Arc<KafkaProducer>>,
+    
+    /// Position and order size limits
+    pub risk: RwLock<RiskManager>,
+    
+    /// Quoting parameters currently in force
+    pub params: RwLock<QuoteParams>,
+    
+    /// Experiment variant new orders are tagged with, None outside an experiment
+    pub variant: RwLock<Option<String>>,
+    
+    /// Account fee tier
+    pub fees: RwLock<FeeSchedule>,
+    
+    /// When resting orders are amended towards the desired quotes
+    pub amend_policy: RwLock<AmendPolicy>,
+    
+    /// Spacing and sizes of the quote levels
+    pub ladder: RwLock<LadderBuilder>,
+    
+    /// Bumped on every change of `ladder`, so the template can tell it's out of date
+    pub ladder_version: AtomicU64,
+    
+    /// Ladder laid out for the current spread and tick; rebuilt when either or the ladder changes
+    pub quote_template: RwLock<Option<QuoteTemplate>>,
+    
+    /// Equity/volatility scaling of the ladder sizes; fixed sizes when None
+    pub sizing: RwLock<Option<SizeScaler>>,
+    
+    /// Separate insert/amend allowances of the bid and ask side
+    pub side_budget: RwLock<SideBudget>,
+    
+    /// Clamping of quotes inside the exchange price collar; the collar is ignored when None
+    pub collar: RwLock<Option<CollarGuard>>,
+    
+    /// Hedge leg and linked hedge orders of a spread, None when quoting outright
+    pub spread: RwLock<Option<SpreadLegs>>,
+    
+    /// All levels quoted with one mass quote per adjustment, None when quoting with orders
+    pub mass_quote: RwLock<Option<MassQuoteStrategy>>,
+    
+    /// Instruments whose quotes were pulled by a control command
+    pub paused_instruments: RwLock<HashSet<String>>,
+    
+    /// Instruments taken out of quoting entirely, by configuration or a control command
+    pub disabled_instruments: RwLock<HashSet<String>>,
+    
+    /// Latest balances and margin of the account, None until the first summary arrived
+    pub account: RwLock<Option<AccountSummary>>,
+    
+    /// Today's fills, volume and PnL, persisted across restarts by the quoter
+    pub daily_stats: RwLock<DailyStats>,
+    
+    /// Quote inserts not yet reported by the exchange, tagged for lookup after a reconnect
+    pub inflight: RwLock<InflightOrders>,
+    
+    /// Basis and funding received by the quoted perpetual, kept across sessions by the caller
+    pub carry: RwLock<CarryTracker>,
+    
+    /// Local record of fills and closed days for the end-of-day export, None when disabled
+    journal: RwLock<Option<Journal>>,
+    
+    /// Whether the last economics check found the spread unprofitable
+    unprofitable: AtomicBool,
+    
+    /// Quoting waits while in-flight inserts of a previous session are looked up
+    reconciling: AtomicBool,
+    
+    /// Quoting waits while the exchange's positions are fetched
+    awaiting_positions: AtomicBool,
 }
 
 impl OrderManager {
-    pub fn new(client: Arc<Mutex<ThalexClient>>, market_data: Arc<MarketDataManager>, kafka_producer: Option<Arc<KafkaProducer>>) -> Self {
+    pub fn new(client: Arc<Mutex<dyn OrderGateway>>, market_data: Arc<MarketDataManager>, kafka_producer: Option<Arc<KafkaProducer>>) -> Self {
         Self {
             client,
             market_data,
-            orders: RwLock::new(vec![vec![], vec![]]),  // Initialize empty orders for bids and asks
+            orders: RwLock::new(QuoteOrders::new(2)),  // Initialize empty orders for bids and asks
             client_order_id: RwLock::new(100),          // Start with ID 100
+            calls: Arc::new(CallRegistry::default()),
+            strict_parsing: false,
             portfolio: RwLock::new(HashMap::new()),
             kafka_producer,
+            risk: RwLock::new(RiskManager::from_config(
+                &RiskConfig::default(),
+                config::MAX_ORDER_AMOUNT,
+                config::AMOUNT_STEP,
+            )),
+            params: RwLock::new(QuoteParams::default()),
+            variant: RwLock::new(None),
+            fees: RwLock::new(FeeSchedule::default()),
+            amend_policy: RwLock::new(AmendPolicy::default()),
+            ladder: RwLock::new(LadderBuilder::default()),
+            ladder_version: AtomicU64::new(0),
+            quote_template: RwLock::new(None),
+            sizing: RwLock::new(None),
+            side_budget: RwLock::new(SideBudget::default()),
+            collar: RwLock::new(None),
+            spread: RwLock::new(None),
+            mass_quote: RwLock::new(None),
+            paused_instruments: RwLock::new(HashSet::new()),
+            disabled_instruments: RwLock::new(HashSet::new()),
+            account: RwLock::new(None),
+            daily_stats: RwLock::new(DailyStats::new(day_of(now_secs()))),
+            inflight: RwLock::new(InflightOrders::new()),
+            carry: RwLock::new(CarryTracker::new(
+                config::CARRY_BASIS_WINDOW_SEC,
+                ScheduleConfig::default().funding_interval_sec() as f64,
+            )),
+            journal: RwLock::new(None),
+            unprofitable: AtomicBool::new(false),
+            reconciling: AtomicBool::new(false),
+            awaiting_positions: AtomicBool::new(false),
         }
     }
 
     /// Create quotes based on current market conditions
     pub async fn make_quotes(&self) -> Result<Vec<Vec<SideQuote>>> {
-        let index_guard = self.market_data.index_price.read().await;
-        let index = index_guard.ok_or_else(|| anyhow!("Index price not initialized"))?;
+        let fair_value = self.market_data.fair_value().await
+            .ok_or_else(|| anyhow!("Fair value not initialized"))?;
+        
+        let tick = self.market_data.tick().await.ok_or_else(|| anyhow!("Tick size not initialized"))?;
+
+        let params = self.params.read().await.clone();
+        if self.cancel_only_reason().await.is_some() {
+            return Ok(vec![Vec::new(), Vec::new()]);
+        }
+        let center = fair_value + params.skew * tick;
+        self.check_economics(params.spread * tick, fair_value).await;
+
+        let size_multiplier = params.size_multiplier * self.risk_size_multiplier(fair_value).await;
+        let mut quotes = {
+            let ladder = self.ladder.read().await;
+            let version = self.ladder_version.load(Ordering::Relaxed);
+            let mut template = self.quote_template.write().await;
+            match template.as_mut().filter(|template| template.matches(version, params.spread, tick)) {
+                Some(template) => template.quotes(center, size_multiplier),
+                None => template.insert(ladder.template(params.spread, tick, version)).quotes(center, size_multiplier),
+            }
+        };
+
+        // Keep the quoted size within what margin and the limits support, converted at the mark
+        let (mark_price, delta, collar) = self.market_data.ticker().await
+            .filter(|ticker| ticker.mark_price > 0.0)
+            .map_or((fair_value, 1.0, None), |ticker| (ticker.mark_price, ticker.delta, Some((ticker.collar_low, ticker.collar_high))));
+        if let (Some(guard), Some((low, high))) = (self.collar.write().await.as_mut(), collar) {
+            quotes = guard.apply(quotes, low, high, mark_price, tick, now_secs());
+        }
+        let position = self.position().await;
+        let mut risk = self.risk.write().await;
+        risk.set_delta(delta);
+        Ok(risk.constrain_quotes(quotes, position, mark_price))
+    }
+
+    /// Replace the quote ladder; the cached template is laid out again on the next quote
+    pub async fn set_ladder(&self, ladder: LadderBuilder) {
+        let mut current = self.ladder.write().await;
+        *current = ladder;
+        self.ladder_version.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Multiplier from equity/volatility scaling, 1 when sizing is fixed
+    async fn risk_size_multiplier(&self, price: f64) -> f64 {
+        let Some(scaler) = self.sizing.read().await.clone() else {
+            return 1.0;
+        };
+        let Some(reference_size) = self.ladder.read().await.reference_size() else {
+            return 1.0;
+        };
+        let equity = self.risk.read().await.equity();
+        let vol = self.market_data.features.read().await.realized_vol();
+        let multiplier = scaler.multiplier(equity, price, vol, reference_size);
+        metrics::global().set_gauge(METRIC_SIZE_MULTIPLIER, multiplier);
+        multiplier
+    }
+
+    /// Warn when the first quote level no longer covers the maker fee
+    async fn check_economics(&self, half_spread: f64, price: f64) {
+        let fees = *self.fees.read().await;
+        let edge = fees.maker_edge(half_spread, price);
+        metrics::global().set_gauge(METRIC_MAKER_EDGE_BPS, 10_000.0 * edge / price);
         
-        let tick_guard = self.market_data.tick.read().await;
-        let tick = tick_guard.ok_or_else(|| anyhow!("Tick size not initialized"))?;
+        let unprofitable = edge <= 0.0;
+        if self.unprofitable.swap(unprofitable, Ordering::Relaxed) != unprofitable {
+            if unprofitable {
+                warn!("Configured spread {} is economically negative: maker fee {} per unit needs at least {}",
+                    half_spread, -fees.maker_rebate(price), fees.min_profitable_half_spread(price));
+            } else {
+                info!("Configured spread {} is profitable again after fees (edge {} per unit)", half_spread, edge);
+            }
+        }
+    }
+
+    /// Whether both sides have an open order within `max_distance` of `mark`
+    ///
+    /// When mass quoting, the sides of the last mass quote count as open orders too.
+    pub async fn is_two_sided_within(&self, mark: f64, max_distance: f64) -> bool {
+        let quoted: [Option<SideQuote>; 2] = self.mass_quote.read().await.as_ref()
+            .and_then(|strategy| strategy.quoted().cloned())
+            .unwrap_or_default();
+        let orders = self.orders.read().await;
+        orders.iter().zip(&quoted).all(|(side, best)| {
+            best.as_ref().is_some_and(|level| (level.price - mark).abs() <= max_distance)
+                || side.iter().any(|order| order.is_open() && order.price.is_some_and(|price| (price - mark).abs() <= max_distance))
+        })
+    }
+
+    /// Quote level of the first order level: 1 when mass quoting, whose mass quote holds
+    /// the first level of each side, 0 otherwise
+    async fn first_order_level(&self) -> usize {
+        usize::from(self.mass_quote.read().await.is_some())
+    }
 
-        // Create bid quotes
-        let mut bids = Vec::with_capacity(config::BID_SIZES.len());
-        for (lvl, &amt) in config::BID_SIZES.iter().enumerate() {
-            let price = self.market_data.round_to_tick(index - (config::SPREAD + config::BID_STEP * lvl as f64) * tick).await?;
-            bids.push(SideQuote::new(price, amt));
+    /// Why only cancels may be sent for the quoted instrument, None when it may be quoted
+    pub async fn cancel_only_reason(&self) -> Option<&'static str> {
+        if self.params.read().await.paused {
+            Some("paused by the schedule")
+        } else if self.is_paused().await {
+            Some("paused by a control command")
+        } else if self.is_disabled().await {
+            Some("instrument disabled")
+        } else if self.is_leg_risk_breached().await {
+            Some("spread leg risk breached")
+        } else {
+            None
         }
+    }
 
-        // Create ask quotes
-        let mut asks = Vec::with_capacity(config::ASK_SIZES.len());
-        for (lvl, &amt) in config::ASK_SIZES.iter().enumerate() {
-            let price = self.market_data.round_to_tick(index + (config::SPREAD + config::ASK_STEP * lvl as f64) * tick).await?;
-            asks.push(SideQuote::new(price, amt));
+    /// Whether the quoted instrument was paused by a control command
+    pub async fn is_paused(&self) -> bool {
+        match self.market_data.perp_name.read().await.as_ref() {
+            Some(perp_name) => self.paused_instruments.read().await.contains(perp_name),
+            None => false,
         }
+    }
 
-        Ok(vec![bids, asks])
+    /// Whether the quoted instrument is disabled
+    pub async fn is_disabled(&self) -> bool {
+        match self.market_data.perp_name.read().await.as_ref() {
+            Some(perp_name) => self.is_instrument_disabled(perp_name).await,
+            None => false,
+        }
+    }
+
+    pub async fn is_instrument_disabled(&self, instrument: &str) -> bool {
+        self.disabled_instruments.read().await.contains(instrument)
+    }
+
+    /// Take `instrument` out of quoting; false when it already was
+    pub async fn disable(&self, instrument: &str) -> bool {
+        self.disabled_instruments.write().await.insert(instrument.to_string())
+    }
+
+    /// Put `instrument` back into quoting; false when it wasn't disabled
+    pub async fn enable(&self, instrument: &str) -> bool {
+        self.disabled_instruments.write().await.remove(instrument)
+    }
+
+    /// Tag new orders with `variant`; true when it changed
+    pub async fn set_variant(&self, variant: Option<String>) -> bool {
+        let mut current = self.variant.write().await;
+        if *current == variant {
+            return false;
+        }
+        info!("Experiment variant {} -> {}", current.as_deref().unwrap_or("none"), variant.as_deref().unwrap_or("none"));
+        *current = variant;
+        true
+    }
+
+
+    /// Cancel every quote now rather than on the next adjustment, inserts still on their way included
+    pub async fn cancel_quotes(&self) -> Result<()> {
+        self.cancel_orders(None).await.map(|_| ())
+    }
+
+    /// Cancel our quotes of `instrument`, or of every instrument when None, including the
+    /// inserts the exchange hasn't acknowledged yet
+    ///
+    /// Inserts still waiting to be sent are dropped and their levels freed; those already
+    /// sent are cancelled by client order id, which the exchange handles after the insert.
+    /// The mass quote is pulled when it quotes the instrument. Returns how many orders were
+    /// cancelled or dropped.
+    pub async fn cancel_orders(&self, instrument: Option<&str>) -> Result<usize> {
+        let perp_name = self.market_data.perp_name.read().await.clone();
+        if self.first_order_level().await > 0 && instrument.is_none_or(|instrument| perp_name.as_deref() == Some(instrument)) {
+            self.adjust_mass_quote([None, None]).await?;
+        }
+        
+        let (dropped, cancelled) = {
+            let mut orders_guard = self.orders.write().await;
+            let matching = |order: &&Order| instrument.is_none_or(|instrument| order.instrument_name == instrument);
+            let pending: HashSet<u64> = orders_guard.iter().flatten()
+                .filter(matching)
+                .filter(|order| order.state == OrderState::Pending)
+                .filter_map(|order| order.client_order_id)
+                .collect();
+            let mut client = self.client.lock().await;
+            let dropped = client.drop_queued_inserts(&pending);
+            let cancelled: Vec<u64> = orders_guard.iter().flatten()
+                .filter(matching)
+                .filter(|order| order.is_open() || order.state == OrderState::Pending)
+                .filter_map(|order| order.client_order_id)
+                .filter(|client_order_id| !dropped.contains(client_order_id))
+                .collect();
+            info!("Cancelling {} quotes of {}, {} dropped unsent", cancelled.len(), instrument.unwrap_or("every instrument"), dropped.len());
+            for &client_order_id in &cancelled {
+                let call_id = self.calls.allocate(RpcMethod::Cancel, Some(client_order_id.to_string()));
+                client.cancel(None, Some(client_order_id), Some(call_id)).await?;
+                orders_guard.set_state(client_order_id, OrderState::Cancelling);
+                metrics::global().incr(METRIC_ORDERS_CANCELLED, 1);
+            }
+            (dropped, cancelled.len())
+        };
+        for &client_order_id in &dropped {
+            self.insert_rejected(client_order_id).await;
+        }
+        Ok(dropped.len() + cancelled)
+    }
+
+    /// Pull one side of the quoted instrument's book now, such as on reaching the position limit
+    ///
+    /// Only our own open orders of that side are cancelled, each by its client order id:
+    /// the account's other orders, e.g. a hedge or another session's, are left alone.
+    /// They are cancelling until the exchange reports them closed; returns how many there were.
+    pub async fn cancel_side(&self, side: OrderSide) -> Result<usize> {
+        let side_i = match side {
+            OrderSide::Buy => 0,
+            OrderSide::Sell => 1,
+        };
+        
+        let mut orders_guard = self.orders.write().await;
+        let open: Vec<u64> = orders_guard[side_i].iter()
+            .filter(|order| order.is_open())
+            .filter_map(|order| order.client_order_id)
+            .collect();
+        info!("Cancelling the {} {} orders", open.len(), side_to_string(&side));
+        let mut client = self.client.lock().await;
+        for &client_order_id in &open {
+            let call_id = self.calls.allocate(RpcMethod::Cancel, Some(client_order_id.to_string()));
+            client.cancel(None, Some(client_order_id), Some(call_id)).await?;
+            orders_guard.set_state(client_order_id, OrderState::Cancelling);
+            metrics::global().incr(METRIC_ORDERS_CANCELLED, 1);
+        }
+        metrics::global().incr(METRIC_SIDE_CANCELS, 1);
+        Ok(open.len())
+    }
+    
+    /// Pull the side that would grow the position further once it reached the limit,
+    /// rather than waiting for the next adjustment to cancel it level by level
+    async fn pull_side_at_limit(&self) -> Result<()> {
+        let price = match self.market_data.ticker().await.filter(|ticker| ticker.mark_price > 0.0) {
+            Some(ticker) => ticker.mark_price,
+            None => match self.market_data.fair_value().await {
+                Some(fair_value) => fair_value,
+                None => return Ok(()),
+            },
+        };
+        let position = self.position().await;
+        let max_position = self.risk.read().await.max_position(price);
+        let side = if position > 0.0 && position >= max_position {
+            OrderSide::Buy
+        } else if position < 0.0 && -position >= max_position {
+            OrderSide::Sell
+        } else {
+            return Ok(());
+        };
+        let cancelled = self.cancel_side(side.clone()).await?;
+        if cancelled > 0 {
+            warn!("Position {} reached the limit of {}, pulled {} {} orders", position, max_position, cancelled, side_to_string(&side));
+        }
+        Ok(())
+    }
+
+    /// Pull every quote of the session with a single cancel, such as on halting
+    ///
+    /// Unlike a cancel per level it also reaches inserts not acknowledged yet: those
+    /// still queued are dropped and the exchange cancels the others. Open orders are
+    /// cancelling until the exchange reports them closed; returns how many there were.
+    pub async fn cancel_session(&self) -> Result<usize> {
+        let mut orders_guard = self.orders.write().await;
+        let open: Vec<u64> = orders_guard.iter().flatten()
+            .filter(|order| order.is_open())
+            .filter_map(|order| order.client_order_id)
+            .collect();
+        info!("Cancelling every order of the session ({} open)", open.len());
+        let call_id = self.calls.allocate(RpcMethod::CancelSession, None);
+        self.client.lock().await.cancel_session(Some(call_id)).await?;
+        for &client_order_id in &open {
+            orders_guard.set_state(client_order_id, OrderState::Cancelling);
+        }
+        metrics::global().incr(METRIC_SESSION_CANCELS, 1);
+        Ok(open.len())
+    }
+
+    /// The exchange refused the session cancel: its cancelling orders are resting again
+    pub async fn session_cancel_rejected(&self) {
+        let mut orders_guard = self.orders.write().await;
+        let cancelling: Vec<u64> = orders_guard.iter().flatten()
+            .filter(|order| order.state == OrderState::Cancelling)
+            .filter_map(|order| order.client_order_id)
+            .collect();
+        for &client_order_id in &cancelling {
+            orders_guard.set_state(client_order_id, OrderState::Acknowledged);
+        }
+        error!("Session cancel rejected, {} orders left resting", cancelling.len());
+    }
+
+    /// Stop quoting `instrument`; open quotes are cancelled on the next adjustment
+    pub async fn pause(&self, instrument: &str) {
+        if self.paused_instruments.write().await.insert(instrument.to_string()) {
+            warn!("Quoting paused for {}", instrument);
+        }
+    }
+
+    /// Quote `instrument` again
+    pub async fn resume(&self, instrument: &str) {
+        if self.paused_instruments.write().await.remove(instrument) {
+            info!("Quoting resumed for {}", instrument);
+        }
+    }
+
+    /// Close the position in `instrument` with an immediate-or-cancel market order
+    ///
+    /// Returns the amount sent, or None when there was no position to close.
+    pub async fn flatten(&self, instrument: &str) -> Result<Option<f64>> {
+        let position = self.portfolio.read().await.get(instrument).copied().unwrap_or(0.0);
+        if position.abs() < config::AMOUNT_STEP {
+            info!("No position to flatten in {}", instrument);
+            return Ok(None);
+        }
+        
+        let client_order_id = {
+            let mut id_guard = self.client_order_id.write().await;
+            let client_order_id = *id_guard;
+            *id_guard += 1;
+            client_order_id
+        };
+        let side = if position > 0.0 { OrderSide::Sell } else { OrderSide::Buy };
+        warn!("Flattening {} position of {} with {} {} at market", instrument, position, side_to_string(&side), position.abs());
+        
+        let order_request = OrderRequest {
+            symbol: instrument.to_string(),
+            side,
+            order_type: OrderType::Market,
+            quantity: position.abs(),
+            price: None,
+            client_order_id: Some(client_order_id),
+            time_in_force: Some(TimeInForce::IOC),
+            label: None,
+        };
+        let call_id = self.calls.allocate(RpcMethod::Insert, Some(client_order_id.to_string()));
+        self.client.lock().await.insert(order_request, Some(call_id)).await?;
+        Ok(Some(position.abs()))
+    }
+
+    /// Whether a spread's unhedged leg is beyond its limit
+    async fn is_leg_risk_breached(&self) -> bool {
+        match self.spread.write().await.as_mut() {
+            Some(spread) => spread.check_leg_risk(),
+            None => false,
+        }
+    }
+
+    /// Send a hedge order on the spread's hedge leg at market
+    ///
+    /// Retries come back from the spread when the order can't be sent, up to its attempt limit.
+    async fn send_hedge(&self, mut hedge: HedgeRequest) -> Result<()> {
+        loop {
+            let Some(instrument) = self.spread.read().await.as_ref().map(|spread| spread.hedge_instrument.clone()) else {
+                return Ok(());
+            };
+            let client_order_id = {
+                let mut id_guard = self.client_order_id.write().await;
+                let client_order_id = *id_guard;
+                *id_guard += 1;
+                client_order_id
+            };
+            info!("Hedging on {}: {} {} (attempt {})", instrument, side_to_string(&hedge.side), hedge.amount, hedge.attempt);
+            if let Some(spread) = self.spread.write().await.as_mut() {
+                spread.hedge_sent(client_order_id, &hedge);
+            }
+            
+            let order_request = OrderRequest {
+                symbol: instrument.clone(),
+                side: hedge.side.clone(),
+                order_type: OrderType::Market,
+                quantity: hedge.amount,
+                price: None,
+                client_order_id: Some(client_order_id),
+                time_in_force: Some(TimeInForce::IOC),
+                label: Some(HEDGE_LABEL.to_string()),
+            };
+            let call_id = self.calls.allocate(RpcMethod::Insert, Some(client_order_id.to_string()));
+            let sent = self.client.lock().await.insert(order_request, Some(call_id)).await;
+            self.publish_leg_risk().await;
+            let Err(e) = sent else {
+                return Ok(());
+            };
+            error!("Failed to send hedge on {}: {}", instrument, e);
+            let retry = self.spread.write().await.as_mut().and_then(|spread| spread.hedge_failed(client_order_id));
+            match retry {
+                Some(retry) => hedge = retry,
+                None => return Err(e),
+            }
+        }
+    }
+
+    /// Update the leg risk gauge
+    async fn publish_leg_risk(&self) {
+        if let Some(spread) = self.spread.read().await.as_ref() {
+            metrics::global().set_gauge(METRIC_SPREAD_LEG_RISK, spread.leg_risk());
+        }
+    }
+
+    /// The exchange rejected the hedge insert `client_order_id`; its remainder is retried
+    async fn hedge_rejected(&self, client_order_id: u64) -> Result<bool> {
+        let retry = match self.spread.write().await.as_mut() {
+            Some(spread) if spread.is_hedge(client_order_id) => spread.hedge_failed(client_order_id),
+            _ => return Ok(false),
+        };
+        if let Some(retry) = retry {
+            self.send_hedge(retry).await?;
+        }
+        self.publish_leg_risk().await;
+        self.market_data.quote_notify.notify_one();
+        Ok(true)
+    }
+
+    /// Insert a limit order on one of the selected options
+    ///
+    /// The price and amount are moved onto the option's ticks first. Returns the
+    /// client order id once the exchange accepted the order, or the exchange's error.
+    pub async fn insert_option_order(&self, instrument: &str, side: OrderSide, price: f64, amount: f64) -> Result<u64> {
+        let option = self.market_data.option(instrument).await
+            .ok_or_else(|| anyhow!("{} is not a selected option", instrument))?;
+        if self.is_instrument_disabled(instrument).await {
+            return Err(anyhow!("{} is disabled", instrument));
+        }
+        let (price, amount) = option.conform(price, amount, now_secs())?;
+
+        let client_order_id = {
+            let mut id_guard = self.client_order_id.write().await;
+            let client_order_id = *id_guard;
+            *id_guard += 1;
+            client_order_id
+        };
+        info!("Inserting {} {} {} at {}", instrument, side_to_string(&side), amount, price);
+
+        let order_request = OrderRequest {
+            symbol: instrument.to_string(),
+            side,
+            order_type: OrderType::Limit,
+            quantity: amount,
+            price: Some(price),
+            client_order_id: Some(client_order_id),
+            time_in_force: Some(TimeInForce::GTC),
+            label: None,
+        };
+        let waiter = self.calls.allocate_awaited(RpcMethod::Insert, Some(client_order_id.to_string()));
+        self.client.lock().await.insert(order_request, Some(waiter.id())).await?;
+        metrics::global().incr(METRIC_ORDERS_INSERTED, 1);
+        waiter.response(Duration::from_millis(config::CALL_RESPONSE_TIMEOUT_MS)).await?;
+        Ok(client_order_id)
+    }
+
+    /// Cancel the order `client_order_id` and wait for the exchange to confirm it
+    ///
+    /// Unlike the cancels of `adjust_quotes`, which go out without holding up the quote
+    /// cycle, this returns the exchange's error when the cancel is refused.
+    pub async fn cancel_order(&self, client_order_id: u64) -> Result<()> {
+        let waiter = self.calls.allocate_awaited(RpcMethod::Cancel, Some(client_order_id.to_string()));
+        {
+            // Held across the send so the answer can't be handled before the order is marked
+            let mut orders_guard = self.orders.write().await;
+            self.client.lock().await.cancel(None, Some(client_order_id), Some(waiter.id())).await?;
+            if orders_guard.get(client_order_id).is_some_and(|order| order.is_open()) {
+                orders_guard.set_state(client_order_id, OrderState::Cancelling);
+            }
+        }
+        metrics::global().incr(METRIC_ORDERS_CANCELLED, 1);
+        waiter.response(Duration::from_millis(config::CALL_RESPONSE_TIMEOUT_MS)).await?;
+        Ok(())
+    }
+
+    /// Current position in the quoted instrument
+    pub async fn position(&self) -> f64 {
+        let Some(perp_name) = self.market_data.perp_name.read().await.clone() else {
+            return 0.0;
+        };
+        self.portfolio.read().await.get(&perp_name).copied().unwrap_or(0.0)
+    }
+
+    /// Journal fills and closed days for the end-of-day export
+    pub async fn set_journal(&self, journal: Journal) {
+        *self.journal.write().await = Some(journal);
+    }
+    
+    /// Log a trading day that just closed and keep its final statistics in the journal
+    pub async fn close_day(&self, day: &DailyStats) {
+        info!("Trading day {} closed: {} fills, volume {}, realized PnL {:.2}",
+            day.date, day.fills, day.volume, day.realized_pnl);
+        if let Some(journal) = &*self.journal.read().await {
+            if let Err(e) = journal.close_day(day) {
+                warn!("Failed to journal the statistics of {}: {:#}", day.date, e);
+            }
+        }
+    }
+    
+    /// Continue with the in-flight inserts of a previous session
+    ///
+    /// Client order ids continue past the recorded ones so new inserts can't be mistaken
+    /// for them. Call `request_reconcile` once the private channels are subscribed.
+    pub async fn set_inflight(&self, inflight: InflightOrders) {
+        if let Some(next) = inflight.next_client_order_id() {
+            let mut id_guard = self.client_order_id.write().await;
+            *id_guard = (*id_guard).max(next);
+        }
+        metrics::global().set_gauge(METRIC_INFLIGHT_INSERTS, inflight.len() as f64);
+        *self.inflight.write().await = inflight;
+    }
+    
+    /// Ask for the open orders if inserts of a previous session are unaccounted for
+    ///
+    /// Quoting is held until the answer arrives, so a level whose insert reached the
+    /// exchange isn't submitted again. Returns whether a request was sent.
+    pub async fn request_reconcile(&self) -> Result<bool> {
+        let inflight = self.inflight.read().await.len();
+        if inflight == 0 {
+            return Ok(false);
+        }
+        info!("Looking up {} in-flight inserts among the open orders", inflight);
+        self.reconciling.store(true, Ordering::Relaxed);
+        let call_id = self.calls.allocate(RpcMethod::OpenOrders, None);
+        if let Err(e) = self.client.lock().await.open_orders(Some(call_id)).await {
+            self.reconciling.store(false, Ordering::Relaxed);
+            return Err(e);
+        }
+        Ok(true)
+    }
+    
+    /// Whether quoting waits for in-flight inserts to be looked up
+    pub fn is_reconciling(&self) -> bool {
+        self.reconciling.load(Ordering::Relaxed)
+    }
+    
+    /// Take over the in-flight inserts found among `open_orders` and resume quoting
+    ///
+    /// An adopted order goes back to the level it was inserted for, so the next
+    /// adjustment amends or cancels it instead of inserting another one. Inserts without
+    /// an open order are forgotten and their levels quoted afresh.
+    pub async fn reconcile_open_orders(&self, open_orders: &[OrderResult]) {
+        let inserts = self.inflight.write().await.take_all();
+        let reconciliation = inflight::reconcile(inserts, open_orders);
+        let first_level = self.first_order_level().await;
+        {
+            let mut orders_guard = self.orders.write().await;
+            for (insert, open) in &reconciliation.adopted {
+                info!("Insert {} ({}) reached the exchange, taking over its order", insert.client_order_id, insert.tag);
+                let side_i = match insert.direction {
+                    OrderSide::Buy => 0,
+                    OrderSide::Sell => 1,
+                };
+                let remaining = open.remaining_amount.unwrap_or(insert.amount);
+                let mut order = Order::pending(
+                    open.client_order_id.unwrap_or(insert.client_order_id),
+                    insert.instrument_name.clone(),
+                    insert.direction.clone(),
+                    open.price.unwrap_or(insert.price),
+                    remaining,
+                );
+                order.order_id = open.order_id.clone().unwrap_or_default();
+                order.amount = open.amount.unwrap_or(insert.amount);
+                order.filled_amount = open.filled_amount.unwrap_or_default();
+                order.status = open.status.parse().unwrap_or(OrderStatus::Open);
+                order.variant_id = insert.variant_id.clone();
+                order.state = OrderState::Acknowledged;
+                orders_guard.place(side_i, insert.level.saturating_sub(first_level), order);
+                metrics::global().incr(METRIC_INSERTS_ADOPTED, 1);
+            }
+        }
+        for insert in &reconciliation.missing {
+            info!("Insert {} ({}) has no open order, its level is free", insert.client_order_id, insert.tag);
+        }
+        metrics::global().set_gauge(METRIC_INFLIGHT_INSERTS, 0.0);
+        self.reconciling.store(false, Ordering::Relaxed);
+        self.market_data.quote_notify.notify_one();
+    }
+    
+    /// The open orders couldn't be fetched: resume quoting without the in-flight inserts
+    pub async fn reconcile_failed(&self) {
+        let inserts = self.inflight.write().await.take_all();
+        warn!("Open orders unavailable, quoting without looking up {} in-flight inserts", inserts.len());
+        metrics::global().set_gauge(METRIC_INFLIGHT_INSERTS, 0.0);
+        self.reconciling.store(false, Ordering::Relaxed);
+        self.market_data.quote_notify.notify_one();
+    }
+    
+    /// Ask for the account's positions, holding quoting until they are reconciled
+    pub async fn request_positions(&self) -> Result<()> {
+        self.awaiting_positions.store(true, Ordering::Relaxed);
+        let call_id = self.calls.allocate(RpcMethod::Portfolio, None);
+        if let Err(e) = self.client.lock().await.portfolio(Some(call_id)).await {
+            self.awaiting_positions.store(false, Ordering::Relaxed);
+            return Err(e);
+        }
+        Ok(())
+    }
+    
+    /// Whether quoting waits for the exchange's positions
+    pub fn is_awaiting_positions(&self) -> bool {
+        self.awaiting_positions.load(Ordering::Relaxed)
+    }
+    
+    /// Correct the local positions to those the exchange reports and resume quoting
+    ///
+    /// Instruments held locally but left out of the report are flat on the exchange.
+    /// Returns the instruments whose local position had drifted, sorted.
+    pub async fn reconcile_positions(&self, positions: &[PortfolioEntry]) -> Vec<String> {
+        let reported: HashMap<&str, f64> = positions.iter()
+            .map(|entry| (entry.instrument_name.as_str(), entry.position))
+            .collect();
+        let mut drifted = Vec::new();
+        {
+            let mut portfolio = self.portfolio.write().await;
+            let instruments: HashSet<String> = portfolio.keys().cloned()
+                .chain(reported.keys().map(|instrument| instrument.to_string()))
+                .collect();
+            for instrument in instruments {
+                let local = portfolio.get(&instrument).copied().unwrap_or(0.0);
+                let exchange = reported.get(instrument.as_str()).copied().unwrap_or(0.0);
+                if (local - exchange).abs() > 1e-9 {
+                    warn!("Position of {} drifted: {} locally, {} on the exchange; correcting", instrument, local, exchange);
+                    drifted.push(instrument.clone());
+                }
+                metrics::global().max_gauge(METRIC_MAX_INVENTORY, exchange.abs());
+                portfolio.insert(instrument, exchange);
+            }
+        }
+        drifted.sort();
+        metrics::global().incr(METRIC_POSITION_DRIFT, drifted.len() as u64);
+        info!("Positions reconciled: {} reported by the exchange, {} corrected", reported.len(), drifted.len());
+        self.awaiting_positions.store(false, Ordering::Relaxed);
+        self.market_data.quote_notify.notify_one();
+        drifted
+    }
+    
+    /// The positions couldn't be fetched: resume quoting with the local ones
+    pub async fn positions_failed(&self) {
+        warn!("Positions unavailable, quoting without reconciling them");
+        self.awaiting_positions.store(false, Ordering::Relaxed);
+        self.market_data.quote_notify.notify_one();
+    }
+    
+    /// The exchange rejected the insert of `client_order_id`, or it was dropped unsent, so
+    /// it is no longer in flight and its level is free for the next quote
+    pub async fn insert_rejected(&self, client_order_id: u64) {
+        let mut inflight = self.inflight.write().await;
+        if inflight.acknowledge(client_order_id).is_some() {
+            metrics::global().set_gauge(METRIC_INFLIGHT_INSERTS, inflight.len() as f64);
+        }
+        drop(inflight);
+        if self.orders.write().await.reject(client_order_id) {
+            self.market_data.quote_notify.notify_one();
+        }
+        if let Err(e) = self.hedge_rejected(client_order_id).await {
+            error!("Failed to retry rejected hedge {}: {}", client_order_id, e);
+        }
     }
 
     /// Adjust quotes to match the desired state
     pub async fn adjust_quotes(&self, desired: Vec<Vec<SideQuote>>) -> Result<()> {
+        if self.is_reconciling() {
+            debug!("Holding quotes until in-flight inserts are looked up");
+            return Ok(());
+        }
+        if self.is_awaiting_positions() {
+            debug!("Holding quotes until the positions are reconciled");
+            return Ok(());
+        }
+        let mut desired = desired;
+        let first_level = self.first_order_level().await;
+        if first_level > 0 {
+            // The mass quote takes the best level of each side, orders the rest
+            let best = [0, 1].map(|side_i| (!desired[side_i].is_empty()).then(|| desired[side_i].remove(0)));
+            self.adjust_mass_quote(best).await?;
+        }
+        
         let sides = [OrderSide::Buy, OrderSide::Sell];
+        let amend_policy = self.amend_policy.read().await.clone();
+        let position = self.position().await;
+        let risk = self.risk.read().await;
+        let mut budget = self.side_budget.write().await;
+        let now = clock::instant();
+        let variant = self.variant.read().await.clone();
         let mut orders_guard = self.orders.write().await;
         
         for (side_i, side) in sides.iter().enumerate() {
-            let side_orders = &mut orders_guard[side_i];
             let side_quotes = &desired[side_i];
             
-            // Cancel excess orders
-            for i in side_quotes.len()..side_orders.len() {
-                if side_orders[i].is_open() {
-                    info!("Cancelling {}-{} {}", side_to_string(side), i, side_orders[i].id);
-                    let mut client = self.client.lock().await;
-                    client.cancel(
-                        None, 
-                        Some(side_orders[i].id), 
-                        Some(side_orders[i].id)
-                    ).await?;
-                }
+            // Cancel excess orders; each level is freed once the exchange confirms its cancel
+            let excess: Vec<(usize, u64)> = orders_guard[side_i].iter().enumerate()
+                .skip(side_quotes.len())
+                .filter(|(_, order)| order.is_open())
+                .filter_map(|(i, order)| order.client_order_id.map(|client_order_id| (i, client_order_id)))
+                .collect();
+            for (i, client_order_id) in excess {
+                info!("Cancelling {}-{} {}", side_to_string(side), first_level + i, client_order_id);
+                let call_id = self.calls.allocate(RpcMethod::Cancel, Some(client_order_id.to_string()));
+                let mut client = self.client.lock().await;
+                client.cancel(
+                    None, 
+                    Some(client_order_id), 
+                    Some(call_id)
+                ).await?;
+                orders_guard.set_state(client_order_id, OrderState::Cancelling);
+                metrics::global().incr(METRIC_ORDERS_CANCELLED, 1);
             }
             
             // Adjust orders for each level
-            for (q_lvl, q) in side_quotes.iter().enumerate() {
-                let needs_new_order = q_lvl >= side_orders.len() || 
-                    (side_orders[q_lvl].status.is_some() && !side_orders[q_lvl].is_open());
+            for (slot, q) in side_quotes.iter().enumerate() {
+                let q_lvl = first_level + slot;
+                let needs_new_order = slot >= orders_guard[side_i].len() || orders_guard[side_i][slot].is_closed();
                 
                 if needs_new_order {
+                    if let Err(e) = risk.check_order(side, q.amount, q.price, position) {
+                        warn!("Not inserting {}-{}: {}", side_to_string(side), q_lvl, e);
+                        metrics::global().incr(METRIC_RISK_REJECTS, 1);
+                        continue;
+                    }
+                    if !budget.try_acquire(side_i, now) {
+                        debug!("{} update budget spent, deferring insert at level {}", side_to_string(side), q_lvl);
+                        metrics::global().incr(METRIC_SIDE_BUDGET_EXHAUSTED, 1);
+                        continue;
+                    }
+                    
                     // Create a new order for this level
                     let mut id_guard = self.client_order_id.write().await;
                     let client_order_id = *id_guard;
                     *id_guard += 1;
                     
-                    // Update the order list
-                    if q_lvl >= side_orders.len() {
-                        side_orders.push(Order::new(client_order_id, q.price, q.amount, None));
-                    } else {
-                        side_orders[q_lvl] = Order::new(client_order_id, q.price, q.amount, None);
+                    let perp_name = self.market_data.perp_name.read().await.clone()
+                        .ok_or_else(|| anyhow!("Perpetual name not initialized"))?;
+                    
+                    // Record the insert under a fresh tag before it can reach the exchange
+                    let insert = InflightInsert {
+                        tag: uuid::Uuid::new_v4(),
+                        client_order_id,
+                        instrument_name: perp_name.clone(),
+                        direction: side.clone(),
+                        level: q_lvl,
+                        price: q.price,
+                        amount: q.amount,
+                        sent_at: now_secs(),
+                        variant_id: variant.clone(),
+                    };
+                    let label = insert.label();
+                    {
+                        let mut inflight = self.inflight.write().await;
+                        inflight.record(insert);
+                        metrics::global().set_gauge(METRIC_INFLIGHT_INSERTS, inflight.len() as f64);
                     }
                     
+                    // Update the order list
+                    let mut order = Order::pending(client_order_id, perp_name.clone(), side.clone(), q.price, q.amount);
+                    order.variant_id = variant.clone();
+                    orders_guard.place(side_i, slot, order);
+                    
                     // Send the order to the exchange
                     info!("Inserting {} {}-{} {}@{}", client_order_id, side_to_string(side), q_lvl, q.amount, q.price);
-                    let perp_name = self.market_data.perp_name.read().await.clone()
-                        .ok_or_else(|| anyhow!("Perpetual name not initialized"))?;
                     
                     let order_request = OrderRequest {
                         symbol: perp_name,
@@ -126,32 +1008,55 @@ impl OrderManager {
                         price: Some(q.price),
                         client_order_id: Some(client_order_id),
                         time_in_force: Some(TimeInForce::GTC),
+                        label: Some(label),
                     };
                     
+                    let call_id = self.calls.allocate(RpcMethod::Insert, Some(client_order_id.to_string()));
                     let mut client = self.client.lock().await;
-                    client.insert(order_request, Some(client_order_id)).await?;
-                } else if side_orders[q_lvl].is_open() {
+                    client.insert(order_request, Some(call_id)).await?;
+                    metrics::global().incr(METRIC_ORDERS_INSERTED, 1);
+                } else if orders_guard[side_i][slot].is_open() {
                     // Check if we need to amend the order
-                    let tick_guard = self.market_data.tick.read().await;
-                    let tick = tick_guard.ok_or_else(|| anyhow!("Tick size not initialized"))?;
+                    let tick = self.market_data.tick().await.ok_or_else(|| anyhow!("Tick size not initialized"))?;
                     
-                    if (side_orders[q_lvl].price - q.price).abs() > config::AMEND_THRESHOLD * tick {
-                        info!("Amending {} {}-{} {} -> {}", 
-                            side_orders[q_lvl].id, 
+                    // Amend on price moves past the level's threshold, and when the risk limits shrank the size
+                    let order = &orders_guard[side_i][slot];
+                    let price = order.price.unwrap_or_default();
+                    if let Some(amendment) = amend_policy.amendment(q_lvl, price, order.remaining_amount, q.price, q.amount, tick) {
+                        let client_order_id = order.client_order_id.unwrap_or_default();
+                        let amended_amount = amendment.amount.unwrap_or(order.remaining_amount);
+                        let amended_price = amendment.price.unwrap_or(price);
+                        if let Err(e) = risk.check_order(side, amended_amount, amended_price, position) {
+                            warn!("Not amending {} {}-{}: {}", client_order_id, side_to_string(side), q_lvl, e);
+                            metrics::global().incr(METRIC_RISK_REJECTS, 1);
+                            continue;
+                        }
+                        if !budget.try_acquire(side_i, now) {
+                            debug!("{} update budget spent, deferring amend of {}", side_to_string(side), client_order_id);
+                            metrics::global().incr(METRIC_SIDE_BUDGET_EXHAUSTED, 1);
+                            continue;
+                        }
+                        info!("Amending {} {}-{} {} -> {:?}, amount {} -> {:?}", 
+                            client_order_id, 
                             side_to_string(side), 
                             q_lvl, 
-                            side_orders[q_lvl].price, 
-                            q.price
+                            price, 
+                            amendment.price,
+                            order.remaining_amount,
+                            amendment.amount
                         );
                         
+                        let call_id = self.calls.allocate(RpcMethod::Amend, Some(client_order_id.to_string()));
                         let mut client = self.client.lock().await;
+                        // Venues take the amended amount as the order's new total, filled part included
                         client.amend(
-                            q.amount,
-                            q.price,
+                            order.filled_amount + amended_amount,
+                            amended_price,
                             None,
-                            Some(side_orders[q_lvl].id),
-                            Some(side_orders[q_lvl].id)
+                            Some(client_order_id),
+                            Some(call_id)
                         ).await?;
+                        metrics::global().incr(METRIC_ORDERS_AMENDED, 1);
                     }
                 }
             }
@@ -160,48 +1065,373 @@ impl OrderManager {
         Ok(())
     }
 
+    /// Send the best bid and ask as one mass quote once they moved away from those resting
+    ///
+    /// The amend policy decides which moves are worth a new mass quote. A side the risk
+    /// limits refuse, or no longer wanted, is pulled with a zero amount at its last price.
+    async fn adjust_mass_quote(&self, best: [Option<SideQuote>; 2]) -> Result<()> {
+        let sides = [OrderSide::Buy, OrderSide::Sell];
+        let position = self.position().await;
+        let mut wanted: [Option<SideQuote>; 2] = [None, None];
+        {
+            let risk = self.risk.read().await;
+            for (side_i, side) in sides.iter().enumerate() {
+                let Some(q) = &best[side_i] else {
+                    continue;
+                };
+                if let Err(e) = risk.check_order(side, q.amount, q.price, position) {
+                    warn!("Not quoting {}-0: {}", side_to_string(side), e);
+                    metrics::global().incr(METRIC_RISK_REJECTS, 1);
+                    continue;
+                }
+                wanted[side_i] = Some(q.clone());
+            }
+        }
+        
+        let amend_policy = self.amend_policy.read().await.clone();
+        let mut strategy_guard = self.mass_quote.write().await;
+        let Some(strategy) = strategy_guard.as_mut() else {
+            return Ok(());
+        };
+        let tick = self.market_data.tick().await.ok_or_else(|| anyhow!("Tick size not initialized"))?;
+        let changed = strategy.needs_update(&wanted, |sent, wanted| {
+            amend_policy.needs_amend(0, sent.price, sent.amount, wanted.price, wanted.amount, tick)
+        });
+        if !changed {
+            return Ok(());
+        }
+        
+        let pulled = |side_i: usize| strategy.quoted()
+            .and_then(|quoted| quoted[side_i].as_ref())
+            .map(|sent| SideQuote::new(sent.price, 0.0));
+        let [bid, ask] = [0, 1].map(|side_i| wanted[side_i].clone().or_else(|| pulled(side_i)));
+        if bid.is_none() && ask.is_none() {
+            strategy.sent(wanted);
+            return Ok(());
+        }
+        let perp_name = self.market_data.perp_name.read().await.clone()
+            .ok_or_else(|| anyhow!("Perpetual name not initialized"))?;
+        info!("Mass quoting {}: bid {:?}, ask {:?}", perp_name, bid, ask);
+        let quote = MassQuote { instrument_name: perp_name.clone(), bid, ask };
+        let call_id = self.calls.allocate(RpcMethod::MassQuote, Some(perp_name));
+        self.client.lock().await
+            .mass_quote(vec![quote], Some(config::LABEL.to_string()), strategy.post_only, Some(call_id))
+            .await?;
+        strategy.sent(wanted);
+        metrics::global().incr(METRIC_MASS_QUOTES, 1);
+        Ok(())
+    }
+    
+    /// The exchange refused some or all of the last mass quote: send the ladder again on
+    /// the next adjustment
+    pub async fn mass_quote_rejected(&self) {
+        if let Some(strategy) = self.mass_quote.write().await.as_mut() {
+            strategy.rejected();
+        }
+    }
+
     /// Process order updates
+    ///
+    /// A confirmed cancel frees its level at once and asks for a re-quote, so levels
+    /// dropped by one parameter change are reused as soon as the next one wants them.
     pub async fn handle_orders(&self, notification: &Value) -> Result<()> {
         if let Some(orders_array) = notification.as_array() {
             let mut orders_guard = self.orders.write().await;
+            let mut cancels_confirmed = 0;
+            let mut reported = Vec::new();
+            let mut hedges = Vec::new();
             
             for order_data in orders_array {
-                match order_from_data(order_data) {
-                    Ok(order) => {
+                match ThaleParser::parse_order_json(order_data) {
+                    Ok(mut order) => {
+                        // The variant travels in the label the order was inserted with
+                        order.variant_id = order_data.get("label").and_then(|v| v.as_str())
+                            .and_then(inflight::variant_of_label)
+                            .map(str::to_string);
+                        
                         // Publish to Kafka if producer exists
-                        if let Some(kafka_producer) = &self.kafka_producer {
-                            if let Err(e) = kafka_producer.publish_order_notification(order_data).await {
-                                // println!("\n \n THIS GOES WRONG: !{:?} \n \n \n", order_data);
+                        if let Some(kafka_producer) = self.account_producer() {
+                            if let Err(e) = kafka_producer.publish_order(&order, order_data).await {
                                 warn!("Failed to publish to Kafka: {}", e);
                             }
                         }
                         
-                        if !self.update_order(&order, &mut orders_guard) {
+                        // Hedge orders of a spread are tracked by the spread, not the quote book
+                        if let Some(client_order_id) = order.client_order_id {
+                            if let Some(spread) = self.spread.write().await.as_mut().filter(|spread| spread.is_hedge(client_order_id)) {
+                                hedges.extend(spread.on_hedge_update(client_order_id, order.filled_amount, order.is_open()));
+                                continue;
+                            }
+                        }
+                        
+                        // An update while our cancel is in flight, such as a partial fill, leaves it in flight
+                        let cancelling = order.client_order_id
+                            .and_then(|client_order_id| orders_guard.get(client_order_id))
+                            .is_some_and(|known| known.state == OrderState::Cancelling);
+                        if cancelling && order.is_open() {
+                            order.state = OrderState::Cancelling;
+                        } else if cancelling {
+                            cancels_confirmed += 1;
+                        }
+                        
+                        if !orders_guard.update(&order) {
                             error!("Didn't find order: {:?}", order);
                         }
+                        reported.extend(order.client_order_id);
                     },
                     Err(e) => {
                         error!("Failed to parse order data: {}", e);
                     }
                 }
             }
+            
+            // Reported orders are known to the exchange, their inserts no longer in flight
+            if !reported.is_empty() {
+                let mut inflight = self.inflight.write().await;
+                if reported.into_iter().filter(|&client_order_id| inflight.acknowledge(client_order_id).is_some()).count() > 0 {
+                    metrics::global().set_gauge(METRIC_INFLIGHT_INSERTS, inflight.len() as f64);
+                }
+            }
+            
+            if cancels_confirmed > 0 {
+                let released: usize = (0..orders_guard.len()).map(|side| orders_guard.release_closed_tail(side)).sum();
+                debug!("{} cancels confirmed, {} levels released", cancels_confirmed, released);
+                self.market_data.quote_notify.notify_one();
+            }
+            drop(orders_guard);
+            
+            if self.spread.read().await.is_some() {
+                for hedge in hedges {
+                    self.send_hedge(hedge).await?;
+                }
+                self.publish_leg_risk().await;
+                // Quotes pulled for leg risk come back once the hedge filled
+                self.market_data.quote_notify.notify_one();
+            }
         }
         Ok(())
     }
 
-    /// Update order in collection if it exists
-    fn update_order(&self, order: &Order, orders: &mut Vec<Vec<Order>>) -> bool {
-        for side in 0..2 {
-            for i in 0..orders[side].len() {
-                if orders[side][i].id == order.id {
-                    orders[side][i] = order.clone();
-                    return true;
+    /// The exchange rejected the cancel of `client_order_id`: treat the order as resting
+    /// again so the next adjustment decides what to do with it
+    pub async fn cancel_rejected(&self, client_order_id: u64) {
+        let mut orders_guard = self.orders.write().await;
+        if orders_guard.get(client_order_id).is_some_and(|order| order.state == OrderState::Cancelling) {
+            orders_guard.set_state(client_order_id, OrderState::Acknowledged);
+            warn!("Cancel of {} rejected, order left resting", client_order_id);
+        }
+    }
+
+    /// Ask the exchange for the account's balances and margin
+    pub async fn request_account_summary(&self) -> Result<()> {
+        let call_id = self.calls.allocate(RpcMethod::AccountSummary, None);
+        self.client.lock().await.account_summary(Some(call_id)).await
+    }
+    
+    /// Producer for the account's orders, trades and summaries, None in hot standby,
+    /// where the primary instance publishes them
+    fn account_producer(&self) -> Option<&Arc<KafkaProducer>> {
+        self.kafka_producer.as_ref().filter(|_| !self.market_data.is_standby())
+    }
+    
+    /// Take in a fresh account summary: quote sizes are kept within its remaining margin
+    /// from the next adjustment on, and it is published to Kafka
+    pub async fn update_account(&self, summary: AccountSummary) {
+        debug!("Account summary: margin={} required={} remaining={}",
+            summary.margin, summary.required_margin, summary.remaining_margin);
+        self.risk.write().await.set_available_margin(summary.remaining_margin);
+        metrics::global().set_gauge(METRIC_ACCOUNT_MARGIN, summary.margin);
+        metrics::global().set_gauge(METRIC_ACCOUNT_REQUIRED_MARGIN, summary.required_margin);
+        metrics::global().set_gauge(METRIC_ACCOUNT_REMAINING_MARGIN, summary.remaining_margin);
+        if let Some(kafka_producer) = self.account_producer() {
+            if let Err(e) = kafka_producer.send_account_summary(&summary).await {
+                warn!("Failed to publish account summary to Kafka: {}", e);
+            }
+        }
+        *self.account.write().await = Some(summary);
+    }
+    
+    /// Process account events
+    ///
+    /// Every event is published; a liquidation halts trading and pulls every quote of the
+    /// session, since the position and margin are no longer what the strategy assumed.
+    /// The session cancel goes out even while quotes are held, e.g. during a reconcile,
+    /// and reaches inserts not acknowledged yet.
+    pub async fn handle_account_events(&self, notification: &Value) -> Result<()> {
+        let events = match notification {
+            Value::Array(events) => events.as_slice(),
+            event => std::slice::from_ref(event),
+        };
+        for event_data in events {
+            match ThaleParser::parse_trade_correction_json(event_data) {
+                Ok(Some(correction)) => {
+                    if let Err(e) = self.handle_trade_correction(correction).await {
+                        error!("Failed to handle trade correction: {:#}", e);
+                    }
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    error!("Failed to parse trade correction: {}", e);
+                    continue;
+                }
+            }
+            let event = match ThaleParser::parse_account_event_json(event_data) {
+                Ok(event) => event,
+                Err(e) => {
+                    error!("Failed to parse account event: {}", e);
+                    continue;
+                }
+            };
+            metrics::global().incr(METRIC_ACCOUNT_EVENTS, 1);
+            if let Some(kafka_producer) = self.account_producer() {
+                if let Err(e) = kafka_producer.send_account_event(&event).await {
+                    warn!("Failed to publish account event to Kafka: {}", e);
                 }
             }
+            
+            match ThaleParser::parse_funding_payment_json(event_data) {
+                Ok(Some(amount)) => self.record_funding(event.instrument_name.as_deref(), amount).await,
+                Ok(None) => {}
+                Err(e) => error!("Failed to parse funding payment: {}", e),
+            }
+            
+            let message = event.message.as_deref().unwrap_or("-");
+            match event.event_type {
+                AccountEventType::Liquidation => {
+                    let reason = format!("Liquidation reported by the exchange: {}", message);
+                    error!("{}, halting trading", reason);
+                    self.risk.write().await.halt(&reason);
+                    self.cancel_session().await?;
+                }
+                AccountEventType::MarginCall => warn!("Margin call: {}", message),
+                AccountEventType::Deposit | AccountEventType::Withdrawal => {
+                    info!("Account {}: {:?} {}", event.event_type.as_str(), event.amount, event.currency.as_deref().unwrap_or("?"));
+                }
+                AccountEventType::Other => debug!("Account notification: {}", message),
+            }
         }
-        false
+        Ok(())
     }
 
+    /// Count a funding payment of the quoted perpetual into the carry analytics
+    ///
+    /// Payments naming another instrument, such as a spread's hedge leg, are left out.
+    async fn record_funding(&self, instrument: Option<&str>, amount: f64) {
+        let perp_name = self.market_data.perp_name.read().await.clone();
+        if instrument.is_some_and(|instrument| perp_name.as_deref() != Some(instrument)) {
+            debug!("Funding of {:.4} for {} is not for the quoted perpetual", amount, instrument.unwrap_or("?"));
+            return;
+        }
+        let mut carry = self.carry.write().await;
+        carry.record_funding(amount);
+        metrics::global().set_gauge(METRIC_FUNDING_RECEIVED, carry.cumulative_funding());
+        info!("Funding {} {:.4}, {:.4} since start", if amount < 0.0 { "paid" } else { "received" }, amount.abs(), carry.cumulative_funding());
+    }
+    
+    /// Take a bust or correction of one of our trades into the journal and today's statistics
+    ///
+    /// Fields the notification leaves out are taken from the journaled fill. When today's
+    /// journaled fills replay to the current statistics they're rebuilt from the corrected
+    /// fills, restating realized PnL; otherwise the fill is reversed and its corrected
+    /// version applied, which restores counts and position but not realized PnL.
+    pub async fn handle_trade_correction(&self, mut correction: TradeCorrection) -> Result<()> {
+        metrics::global().incr(METRIC_TRADE_CORRECTIONS, 1);
+        let journal = self.journal.read().await;
+        let today = day_of(now_secs());
+        let fill = match &*journal {
+            Some(journal) => journal.find_fill(&correction.trade_id, today, config::CORRECTION_LOOKBACK_DAYS)?,
+            None => None,
+        };
+        if let Some(fill) = &fill {
+            correction.order_id.get_or_insert_with(|| fill.order_id.clone());
+            correction.instrument_name.get_or_insert_with(|| fill.instrument_name.clone());
+            correction.direction.get_or_insert_with(|| fill.direction.clone());
+            correction.original_price.get_or_insert(fill.price);
+            correction.original_amount.get_or_insert(fill.amount);
+        }
+        
+        let side = correction.direction.as_ref().map(side_to_string).unwrap_or("?");
+        let instrument = correction.instrument_name.as_deref().unwrap_or("?");
+        let message = correction.message.as_deref().unwrap_or("-");
+        match correction.kind {
+            CorrectionKind::Bust => error!("ALERT: trade {} busted by the exchange: {} {:?} {} @ {:?} ({})",
+                correction.trade_id, side, correction.original_amount, instrument, correction.original_price, message),
+            CorrectionKind::Correction => error!("ALERT: trade {} corrected by the exchange: {} {:?} {} @ {:?} is now {:?} @ {:?} ({})",
+                correction.trade_id, side, correction.original_amount, instrument, correction.original_price,
+                correction.corrected_amount, correction.corrected_price, message),
+        }
+        
+        let fill_day = fill.as_ref().map(|fill| day_of(fill.time)).unwrap_or(today);
+        let mut daily_stats = self.daily_stats.write().await;
+        let realized_before = daily_stats.realized_pnl;
+        let revised = match (&correction.direction, correction.original_price, correction.original_amount) {
+            // A journal without the fill has either seen this revision already or never had the trade
+            _ if journal.is_some() && fill.is_none() => None,
+            (Some(side), Some(price), Some(amount)) => Some((side.clone(), price, amount)),
+            _ => None,
+        };
+        let corrected = match correction.kind {
+            CorrectionKind::Bust => None,
+            CorrectionKind::Correction => revised.as_ref().map(|(_, price, amount)| {
+                (correction.corrected_price.unwrap_or(*price), correction.corrected_amount.unwrap_or(*amount))
+            }),
+        };
+        
+        // Today's statistics as replayed from the journal, if that reproduces them
+        let replayable = match &*journal {
+            Some(journal) if fill_day.to_string() == daily_stats.date => {
+                let opening = match fill_day.pred_opt() {
+                    Some(previous) => journal.day_stats(previous)?,
+                    None => None,
+                }.unwrap_or_else(|| DailyStats::new(fill_day));
+                let fills = journal.fills(fill_day)?;
+                let replayed = daily_stats.rebuilt(&opening, fills.iter().map(|fill| (&fill.direction, fill.price, fill.amount)));
+                let matches = replayed.fills == daily_stats.fills
+                    && (replayed.position - daily_stats.position).abs() < 1e-9
+                    && (replayed.realized_pnl - daily_stats.realized_pnl).abs() < 1e-6;
+                matches.then_some(opening)
+            }
+            _ => None,
+        };
+        if let Some(journal) = &*journal {
+            journal.append_correction(fill_day, &correction)?;
+        }
+        
+        match (&revised, replayable, &*journal) {
+            (None, _, _) => warn!("Trade {} isn't a journaled fill, statistics left as they are", correction.trade_id),
+            (Some(_), Some(opening), Some(journal)) => {
+                let fills = journal.fills(fill_day)?;
+                *daily_stats = daily_stats.rebuilt(&opening, fills.iter().map(|fill| (&fill.direction, fill.price, fill.amount)));
+            }
+            (Some((side, price, amount)), _, _) if fill_day.to_string() == daily_stats.date => {
+                warn!("Today's fills don't replay to its statistics, realized PnL isn't restated for trade {}", correction.trade_id);
+                daily_stats.reverse_fill(side, *price, *amount);
+                if let Some((price, amount)) = corrected {
+                    daily_stats.apply_fill(side, price, amount);
+                }
+            }
+            (Some((side, _, amount)), _, _) => {
+                warn!("Trade {} was filled on {}, only the position carried into today is revised", correction.trade_id, fill_day);
+                let signed = |amount: f64| match side {
+                    OrderSide::Buy => amount,
+                    OrderSide::Sell => -amount,
+                };
+                daily_stats.shift_position(signed(corrected.map(|(_, amount)| amount).unwrap_or(0.0)) - signed(*amount));
+            }
+        }
+        daily_stats.publish();
+        metrics::global().add_gauge(METRIC_REALIZED_PNL, daily_stats.realized_pnl - realized_before);
+        drop(daily_stats);
+        drop(journal);
+        
+        if let Some(kafka_producer) = self.account_producer() {
+            if let Err(e) = kafka_producer.send_trade_correction(&correction).await {
+                warn!("Failed to publish trade correction to Kafka: {}", e);
+            }
+        }
+        Ok(())
+    }
 
     /// Process portfolio updates
     pub async fn handle_portfolio(&self, notification: &Value) -> Result<()> {
@@ -214,9 +1444,12 @@ impl OrderManager {
                     position.get("position").and_then(|v| v.as_f64())
                 ) {
                     portfolio_guard.insert(instrument.to_string(), position_amount);
+                    metrics::global().max_gauge(METRIC_MAX_INVENTORY, position_amount.abs());
                     debug!("Portfolio update: {}={}", instrument, position_amount);
                 }
             }
+            drop(portfolio_guard);
+            self.pull_side_at_limit().await?;
         }
         Ok(())
     }
@@ -227,16 +1460,59 @@ impl OrderManager {
             for trade in trades_array {
                 // Look for trades with our label
                 if let Some(label) = trade.get("label").and_then(|v| v.as_str()) {
-                    if label == config::LABEL {
+                    if inflight::is_own_label(label) {
                         let direction = trade.get("direction").and_then(|v| v.as_str()).unwrap_or("unknown");
                         let amount = trade.get("amount").and_then(|v| v.as_f64()).unwrap_or(0.0);
                         let price = trade.get("price").and_then(|v| v.as_f64()).unwrap_or(0.0);
                         
                         info!("Trade executed: {} {} @ {}", direction, amount, price);
+                        
+                        let side = match direction {
+                            "buy" => OrderSide::Buy,
+                            "sell" => OrderSide::Sell,
+                            _ => continue,
+                        };
+                        let time = trade.get("time").and_then(|v| v.as_f64()).unwrap_or_else(now_secs);
+                        if let Some(journal) = &*self.journal.read().await {
+                            let journaled = match ThaleParser::parse_trade_json(trade, self.strict_parsing) {
+                                Ok(mut parsed) => {
+                                    parsed.variant_id = inflight::variant_of_label(label).map(str::to_string);
+                                    journal.append_fill(&Fill::new(&parsed, side.clone()))
+                                }
+                                Err(e) => Err(e),
+                            };
+                            if let Err(e) = journaled {
+                                warn!("Failed to journal fill: {:#}", e);
+                            }
+                        }
+                        let mut daily_stats = self.daily_stats.write().await;
+                        let realized_before = daily_stats.realized_pnl;
+                        let closed_day = daily_stats.record_fill(&side, price, amount, time);
+                        // A closed day ends at realized_before, so the fill's PnL is all in the new day
+                        let realized = match &closed_day {
+                            Some(_) => daily_stats.realized_pnl,
+                            None => daily_stats.realized_pnl - realized_before,
+                        };
+                        let metrics = metrics::global();
+                        metrics.incr(METRIC_FILLS, 1);
+                        metrics.add_gauge(METRIC_FILL_VOLUME, amount);
+                        metrics.add_gauge(METRIC_REALIZED_PNL, realized);
+                        drop(daily_stats);
+                        if let Some(day) = closed_day {
+                            self.close_day(&day).await;
+                        }
+                        
+                        // Offset the fill on the spread's other leg right away
+                        let client_order_id = trade.get("client_order_id").and_then(|v| v.as_u64());
+                        let hedge = self.spread.write().await.as_mut()
+                            .and_then(|spread| spread.on_quote_fill(client_order_id, &side, amount));
+                        if let Some(hedge) = hedge {
+                            self.send_hedge(hedge).await?;
+                        }
                     }
                 }
             }
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
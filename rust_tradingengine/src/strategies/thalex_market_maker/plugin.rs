@@ -0,0 +1,36 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::infrastructure::exchange::thalex::channel::Channel;
+
+/// Additional consumer of exchange notifications (risk, metrics, persistence, ...)
+///
+/// Plugins run after the built-in market data and order handling. Errors returned
+/// by a plugin are logged and never interrupt the core routing.
+#[async_trait]
+pub trait NotificationPlugin: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &str;
+
+    /// Whether the plugin wants notifications from this channel
+    fn handles(&self, channel: &Channel) -> bool;
+
+    /// Called for every notification on a channel the plugin handles
+    async fn on_notification(&self, channel: &Channel, notification: &Value) -> Result<()>;
+
+    /// Called for every RPC result
+    async fn on_result(&self, _result: &Value, _cid: u64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for every RPC error
+    async fn on_error(&self, _error: &Value, _cid: u64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for notifications on channels without typed support, with the raw channel name
+    async fn on_unknown_channel(&self, _channel_name: &str, _notification: &Value) -> Result<()> {
+        Ok(())
+    }
+}
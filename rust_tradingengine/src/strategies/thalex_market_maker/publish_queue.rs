@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use log::error;
+use tokio::sync::mpsc;
+
+use crate::infrastructure::runtime;
+
+type SendFn<T> = Arc<dyn Fn(T) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// Publishes the records of each key in order, on one task per key
+///
+/// A delta only makes sense after the one it follows, so the records of a key are sent
+/// one at a time in the order they were queued. Each key's queue holds at most `capacity`
+/// batches: `push` refuses a batch while it is full, and the caller resynchronises the
+/// key, typically by publishing a fresh snapshot next.
+pub struct PublishQueues<K, T> {
+    name: &'static str,
+    capacity: usize,
+    send: SendFn<T>,
+    queues: HashMap<K, mpsc::Sender<Vec<T>>>,
+}
+
+impl<K: Eq + Hash + Clone, T: Send + 'static> PublishQueues<K, T> {
+    /// Queues publishing records with `send`; `name` identifies them in error logs
+    pub fn new<F, Fut>(name: &'static str, capacity: usize, send: F) -> Self
+    where
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        Self {
+            name,
+            capacity: capacity.max(1),
+            send: Arc::new(move |record| Box::pin(send(record))),
+            queues: HashMap::new(),
+        }
+    }
+
+    /// Queue a batch of records for `key`, starting its task on first use
+    ///
+    /// Returns false if the key's queue is full and the batch was dropped.
+    pub fn push(&mut self, key: &K, batch: Vec<T>) -> bool {
+        if batch.is_empty() {
+            return true;
+        }
+        let queue = match self.queues.get(key) {
+            Some(queue) if !queue.is_closed() => queue,
+            _ => {
+                let queue = self.spawn();
+                self.queues.entry(key.clone()).insert_entry(queue).into_mut()
+            }
+        };
+        queue.try_send(batch).is_ok()
+    }
+
+    /// Number of keys with a publishing task
+    pub fn len(&self) -> usize {
+        self.queues.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.is_empty()
+    }
+
+    fn spawn(&self) -> mpsc::Sender<Vec<T>> {
+        let (tx, mut rx) = mpsc::channel::<Vec<T>>(self.capacity);
+        let send = self.send.clone();
+        let name = self.name;
+        runtime::spawn_io(async move {
+            while let Some(batch) = rx.recv().await {
+                for record in batch {
+                    if let Err(e) = send(record).await {
+                        error!("Failed to send {} to Kafka: {:?}", name, e);
+                    }
+                }
+            }
+        });
+        tx
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use crate::domain::enums::OrderStatus;
+use crate::domain::model::order::{Order, OrderState};
+
+/// Our quote orders by side and level, indexed by client order id
+///
+/// Reads go through the per-side vectors; every change goes through `place` or `update`
+/// so the index always points at the order's current side and level.
+#[derive(Debug, Default)]
+pub struct QuoteOrders {
+    /// Orders organized by side [bids, asks]
+    sides: Vec<Vec<Order>>,
+
+    /// Side and level of each order by client order id
+    index: HashMap<u64, (usize, usize)>,
+}
+
+impl QuoteOrders {
+    pub fn new(sides: usize) -> Self {
+        Self {
+            sides: vec![Vec::new(); sides],
+            index: HashMap::new(),
+        }
+    }
+
+    /// Put `order` at `level` of `side`, replacing the order there; a level past the end
+    /// is appended
+    pub fn place(&mut self, side: usize, level: usize, order: Order) {
+        let side_orders = &mut self.sides[side];
+        let level = level.min(side_orders.len());
+        if let Some(client_order_id) = order.client_order_id {
+            self.index.insert(client_order_id, (side, level));
+        }
+        if level == side_orders.len() {
+            side_orders.push(order);
+        } else {
+            let replaced = std::mem::replace(&mut side_orders[level], order);
+            if let Some(client_order_id) = replaced.client_order_id {
+                if self.index.get(&client_order_id) == Some(&(side, level)) && replaced.client_order_id != side_orders[level].client_order_id {
+                    self.index.remove(&client_order_id);
+                }
+            }
+        }
+    }
+
+    /// Replace the order with the same client order id; false when there is none
+    pub fn update(&mut self, order: &Order) -> bool {
+        match order.client_order_id.and_then(|client_order_id| self.index.get(&client_order_id)) {
+            Some(&(side, level)) => {
+                self.sides[side][level] = order.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the local lifecycle state of the order with `client_order_id`; false when there is none
+    pub fn set_state(&mut self, client_order_id: u64, state: OrderState) -> bool {
+        match self.index.get(&client_order_id) {
+            Some(&(side, level)) => {
+                self.sides[side][level].state = state;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Close the pending order with `client_order_id` whose insert was rejected, so its
+    /// level is quoted again; false when there is no such order
+    pub fn reject(&mut self, client_order_id: u64) -> bool {
+        match self.index.get(&client_order_id) {
+            Some(&(side, level)) if self.sides[side][level].state == OrderState::Pending => {
+                let order = &mut self.sides[side][level];
+                order.status = OrderStatus::Cancelled;
+                order.state = OrderState::Acknowledged;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drop closed orders from the end of `side` so those levels are free again; returns
+    /// how many were dropped
+    pub fn release_closed_tail(&mut self, side: usize) -> usize {
+        let mut released = 0;
+        while let Some(order) = self.sides[side].last().filter(|order| order.is_closed()) {
+            if let Some(client_order_id) = order.client_order_id {
+                self.index.remove(&client_order_id);
+            }
+            self.sides[side].pop();
+            released += 1;
+        }
+        released
+    }
+
+    /// Side and level of the order with `client_order_id`
+    pub fn locate(&self, client_order_id: u64) -> Option<(usize, usize)> {
+        self.index.get(&client_order_id).copied()
+    }
+
+    pub fn get(&self, client_order_id: u64) -> Option<&Order> {
+        self.locate(client_order_id).map(|(side, level)| &self.sides[side][level])
+    }
+}
+
+impl Deref for QuoteOrders {
+    type Target = [Vec<Order>];
+
+    fn deref(&self) -> &[Vec<Order>] {
+        &self.sides
+    }
+}
@@ -1,35 +1,75 @@
 // Standard library imports
+use chrono::{NaiveTime, Utc};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 // External crate imports
 use anyhow::{anyhow, Result};
-use futures_util::SinkExt;
 use log::{debug, error, info, warn};
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::{ClientConfig, Message};
 use serde_json::Value;
-use tokio::sync::{broadcast, Mutex, Notify};
+use tokio::sync::{broadcast, mpsc, Mutex, Notify};
 use tokio::time::{Duration, Instant};
-use tungstenite::Message;
 
 // Internal crate imports 
-use crate::infrastructure::exchange::thalex::client::ThalexClient;
+use crate::infrastructure::exchange::thalex::channel::Channel;
+use crate::infrastructure::exchange::thalex::client::Network;
+use crate::infrastructure::exchange::thalex::clock::{self, ClockStatus};
+use crate::infrastructure::exchange::thalex::calls::RpcMethod;
 use crate::infrastructure::exchange::thalex::models::InstrumentResponse;
-use crate::infrastructure::kafka::KafkaProducer;
-use crate::config_loader::AppConfig;
-use crate::domain::constants::*;
+use crate::infrastructure::exchange::thalex::subscriptions::SubscriptionManager;
+use crate::infrastructure::exchange::thalex::session::SessionClient;
+use crate::infrastructure::bot_state::BotStateMachine;
+use crate::infrastructure::kafka::watchdog::METRIC_PRODUCER_STALLS;
+use crate::infrastructure::kafka::{KafkaProducer, ProducerWatchdog, WatchdogAction};
+use crate::infrastructure::lifecycle::LifecyclePublisher;
+use crate::infrastructure::metrics;
+#[cfg(feature = "chaos")]
+use crate::infrastructure::chaos::ChaosLayer;
+use crate::config_loader::{AppConfig, ChaosConfig, ControlConfig, FairValueConfig, FairValueSource, OptionsConfig, ProducerWatchdogConfig, SpillConfig, StrategyConfig};
+use crate::domain::clock::{instant, now_secs, now_utc};
+use crate::domain::model::bot_state::BotState;
+use crate::domain::model::lifecycle::LifecycleEventType;
+use crate::reporting::eod::{self, EodExporter};
 
 // Import our modular components
 use crate::strategies::thalex_market_maker::{
     config,
+    day_of,
+    price_at,
+    AmendPolicy,
+    CarryTracker,
+    CollarGuard,
+    ControlCommand,
+    ControlMessage,
+    DailyStats,
+    Experiment,
+    InflightOrders,
+    LadderBuilder,
+    SideBudget,
+    SizeScaler,
+    SpreadLegs,
     MarketDataManager,
+    MassQuoteStrategy,
     OrderManager,
+    RawChannelPublisher,
     NotificationHandler,
+    ParameterScheduler,
+    UptimeTracker,
+    FeeSchedule,
+    RiskManager,
+    InboundMessage,
+    Priority,
+    StalenessGuard,
+    WatermarkCache,
 };
 
 
 /// Main Thalex market maker implementation
 pub struct ThalexQuoter {
     /// Client connection
-    pub client: Arc<Mutex<ThalexClient>>,
+    pub client: Arc<Mutex<dyn SessionClient>>,
     
     /// Condition variable for quotation
     pub quote_notify: Arc<Notify>,
@@ -42,26 +82,123 @@ pub struct ThalexQuoter {
     
     /// Notification handler
     pub notification_handler: Arc<NotificationHandler>,
+    
+    /// Cancel-on-disconnect timeout (seconds), None when disabled
+    pub cancel_on_disconnect: Option<u64>,
+    
+    /// Subscriptions and cancel-on-disconnect setting replayed on the next session,
+    /// shared across sessions by the caller
+    pub subscriptions: Arc<SubscriptionManager>,
+    
+    /// Time-of-day and event parameter overrides
+    pub scheduler: ParameterScheduler,
+    
+    /// Parameter A/B test applied on top of the schedule, None when disabled
+    pub experiment: Option<Experiment>,
+    
+    /// Per-instrument quoting settings, applied once the quoted instrument is known
+    pub strategy: StrategyConfig,
+    
+    /// Fault injection on inbound messages (`chaos` feature builds only)
+    pub chaos: ChaosConfig,
+    
+    /// Session lifecycle event publisher, shared across sessions by the caller
+    pub lifecycle: Arc<LifecyclePublisher>,
+    
+    /// What the tasks may do, shared across sessions by the caller
+    pub state: Arc<BotStateMachine>,
+    
+    /// Where the daily statistics are saved, None when persistence is disabled
+    pub stats_path: Option<PathBuf>,
+    
+    /// Daily export of the previous day's report files, None when disabled
+    pub eod_export: Option<(NaiveTime, EodExporter)>,
+    
+    /// Where the external fair value comes from, if quotes aren't centered on the index
+    pub fair_value: FairValueConfig,
+    
+    /// Brokers the fair value and control topics are consumed from
+    pub kafka_bootstrap_servers: Option<String>,
+    
+    /// Operator commands read from Kafka
+    pub control: ControlConfig,
+    
+    /// Stall detection of the Kafka publishing pipeline
+    pub producer_watchdog: ProducerWatchdogConfig,
+    
+    /// Retry of Kafka deliveries that failed
+    pub spill: SpillConfig,
+    
+    /// Option instruments to select from the listed ones, None to trade the perpetual only
+    pub options: Option<OptionsConfig>,
 }
 
 impl ThalexQuoter {
-    pub async fn new(client: Arc<Mutex<ThalexClient>>, config: Option<Arc<AppConfig>>) -> Self {
+    pub async fn new<C: SessionClient + 'static>(client: Arc<Mutex<C>>, config: Option<Arc<AppConfig>>) -> Self {
+        let cancel_on_disconnect = config.as_ref()
+            .map(|config| config.thalex.clone())
+            .unwrap_or_default()
+            .cancel_on_disconnect_timeout();
+        
+        let chaos = config.as_ref()
+            .map(|config| config.chaos.clone())
+            .unwrap_or_default();
+        if chaos.enabled && !cfg!(feature = "chaos") {
+            warn!("Chaos injection configured but this build lacks the chaos feature, ignoring");
+        }
+        
+        let strategy = config.as_ref()
+            .map(|config| config.strategy.clone())
+            .unwrap_or_default();
+        
+        let lifecycle = Arc::new(LifecyclePublisher::new(
+            config.as_ref().map(|config| config.app.network.clone()).unwrap_or_default()
+        ));
+        let state = Arc::new(BotStateMachine::new(lifecycle.instance_id(), None));
+        
+        let scheduler = match config.as_ref().map(|config| ParameterScheduler::from_config(&config.schedule)) {
+            Some(Ok(scheduler)) => scheduler,
+            Some(Err(e)) => {
+                error!("Ignoring parameter schedule: {}", e);
+                ParameterScheduler::default()
+            }
+            None => ParameterScheduler::default(),
+        };
+        
+        let experiment = match config.as_ref().map(|config| Experiment::from_config(&config.experiment)) {
+            Some(Ok(experiment)) => experiment,
+            Some(Err(e)) => {
+                error!("Ignoring experiment: {}", e);
+                None
+            }
+            None => None,
+        };
+        
         // Initialize Kafka producer using the provided config
         let kafka_producer = if let Some(config) = config.clone() {
             debug!("AppConfig provided, initializing Kafka producer");
             
-            match KafkaProducer::new(
+            match KafkaProducer::with_registry_retry(
                 config.kafka_bootstrap_servers(),
                 config.kafka_schema_registry_url(),
-                std::collections::HashMap::from([
-                    ("ticker".to_string(), config.topics.ticker.clone()),
-                    ("ack".to_string(), config.topics.ack.clone()),
-                    ("trade".to_string(), config.topics.trade.clone()),
-                    ("index".to_string(), config.topics.index.clone()),
-                ]),
-                "../schemas".to_string()
+                config.topics.topic_types(),
+                config.kafka.schema_dir.clone(),
+                config.kafka.registry_retry.clone(),
             ).await {
-                Ok(producer) => {
+                Ok(mut producer) => {
+                    #[cfg(feature = "chaos")]
+                    producer.set_faults(&config.chaos);
+                    if config.kafka.watchdog.journal_fallback {
+                        producer.set_journal(&config.kafka.watchdog.journal_path);
+                    }
+                    producer.set_json_mirror(&config.kafka.json_mirror);
+                    producer.set_spill(&config.kafka.spill);
+                    producer.set_strict_parsing(config.thalex.strict_parsing);
+                    match producer.reconcile_topics(&config.kafka.topic_admin).await {
+                        Ok(drift) if !drift.is_empty() => warn!("{} topic settings differ from the configuration", drift.len()),
+                        Ok(_) => {}
+                        Err(e) => warn!("Failed to reconcile topic settings: {}", e),
+                    }
                     info!("Kafka producer initialized successfully");
                     Some(Arc::new(producer))
                 }
@@ -84,15 +221,113 @@ impl ThalexQuoter {
             quote_notify.clone(),
             kafka_producer.clone()
         ));
-        let order_manager = Arc::new(OrderManager::new(
+        if let Some(config) = &config {
+            match config.thalex.book_channels() {
+                Ok(channels) => market_data.set_book_channels(channels).await,
+                Err(e) => error!("Ignoring book subscriptions: {}", e),
+            }
+            if config.topics.ticker_delta_mode {
+                market_data.enable_ticker_deltas().await;
+            }
+        }
+        let mut order_manager = OrderManager::new(
             client.clone(),
             market_data.clone(),
             kafka_producer
-        ));
+        );
+        // Order requests draw their ids from the same allocator as the client's other requests
+        order_manager.calls = client.lock().await.calls();
+        order_manager.strict_parsing = config.as_ref().is_some_and(|config| config.thalex.strict_parsing);
+        let order_manager = Arc::new(order_manager);
+        if let Some(config) = &config {
+            *order_manager.fees.write().await = FeeSchedule::from_config(&config.fees);
+            *order_manager.risk.write().await = RiskManager::from_config(
+                &config.risk,
+                config::MAX_ORDER_AMOUNT,
+                config::AMOUNT_STEP,
+            );
+            *order_manager.carry.write().await = CarryTracker::new(
+                config::CARRY_BASIS_WINDOW_SEC,
+                config.schedule.funding_interval_sec() as f64,
+            );
+        }
+        if let Some(spread) = config.as_ref().map(|config| &config.spread).filter(|spread| spread.enabled) {
+            match SpreadLegs::from_config(spread) {
+                Ok(legs) => {
+                    info!("Hedging every fill on {} at a ratio of {}", spread.hedge_instrument, spread.hedge_ratio);
+                    *order_manager.spread.write().await = Some(legs);
+                }
+                Err(e) => error!("Spread disabled: {}", e),
+            }
+        }
+        for instrument in strategy.disabled_instruments() {
+            info!("{} is disabled by configuration", instrument);
+            order_manager.disable(instrument).await;
+        }
+        let stats_path = config.as_ref()
+            .filter(|config| config.stats.enabled)
+            .map(|config| PathBuf::from(&config.stats.path));
+        if let Some(path) = &stats_path {
+            match DailyStats::load(path) {
+                Ok(Some(mut stats)) => {
+                    if let Some(day) = stats.roll(day_of(now_secs())) {
+                        info!("Saved statistics are from {}, starting a new day", day.date);
+                    } else {
+                        info!("Continuing statistics of {}: {} fills, realized PnL {:.2}",
+                            stats.date, stats.fills, stats.realized_pnl);
+                    }
+                    stats.publish();
+                    *order_manager.daily_stats.write().await = stats;
+                }
+                Ok(None) => info!("No saved statistics at {}, starting from zero", path.display()),
+                Err(e) => error!("Ignoring saved statistics: {:#}", e),
+            }
+        }
+        if let Some(config) = config.as_ref().filter(|config| config.inflight.enabled) {
+            match InflightOrders::load(Path::new(&config.inflight.path)) {
+                Ok(inflight) => {
+                    if !inflight.is_empty() {
+                        info!("{} inserts of the previous session are unaccounted for", inflight.len());
+                    }
+                    order_manager.set_inflight(inflight).await;
+                }
+                Err(e) => error!("Keeping in-flight orders in memory only: {:#}", e),
+            }
+        }
+        let eod_export = match config.as_ref().filter(|config| config.eod_export.enabled) {
+            Some(config) => match eod::parse_time(&config.eod_export.time)
+                .and_then(|time| Ok((time, EodExporter::from_config(&config.eod_export)?)))
+            {
+                Ok((time, exporter)) => {
+                    info!("Exporting the previous day every day at {} UTC", time);
+                    order_manager.set_journal(exporter.journal.clone()).await;
+                    Some((time, exporter))
+                }
+                Err(e) => {
+                    error!("End-of-day export disabled: {:#}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let fair_value = config.as_ref()
+            .map(|config| config.fair_value.clone())
+            .unwrap_or_default();
+        if fair_value.source != FairValueSource::Index {
+            info!("Quoting around the external fair value from {:?}, index fallback after {}ms",
+                fair_value.source, fair_value.max_age_ms);
+            market_data.enable_external_fair_value(fair_value.max_age_ms as f64 / 1000.0).await;
+        }
         let notification_handler = Arc::new(NotificationHandler::new(
             market_data.clone(),
             order_manager.clone()
         ));
+        if let (Some(config), Some(producer)) = (&config, &order_manager.kafka_producer) {
+            notification_handler.register_plugin(Arc::new(RawChannelPublisher::new(
+                producer.clone(),
+                config.topics.unknown_channel.clone(),
+            ))).await;
+        }
 
         Self {
             client,
@@ -100,6 +335,22 @@ impl ThalexQuoter {
             market_data,
             order_manager,
             notification_handler,
+            cancel_on_disconnect,
+            subscriptions: Arc::new(SubscriptionManager::new()),
+            scheduler,
+            experiment,
+            strategy,
+            chaos,
+            lifecycle,
+            state,
+            stats_path,
+            eod_export,
+            fair_value,
+            kafka_bootstrap_servers: config.as_ref().map(|config| config.kafka_bootstrap_servers().to_string()),
+            control: config.as_ref().map(|config| config.control.clone()).unwrap_or_default(),
+            producer_watchdog: config.as_ref().map(|config| config.kafka.watchdog.clone()).unwrap_or_default(),
+            spill: config.as_ref().map(|config| config.kafka.spill.clone()).unwrap_or_default(),
+            options: config.as_ref().and_then(|config| config.thalex.options.clone()),
         }
     }
 
@@ -111,17 +362,21 @@ impl ThalexQuoter {
             tokio::select! {
                 _ = interval.tick() => {
                     let mut client = self.client.lock().await;
-                    if let Some(socket) = &mut client.socket {
-                        if let Err(e) = socket.send(Message::Ping(vec![])).await {
-                            error!("Ping failed: {}", e);
-                            return Err(anyhow!("Ping failed"));
-                        } else {
-                            debug!("Ping sent");
-                        }
-                    } else {
+                    if !client.connected() {
                         warn!("No active socket in ping task");
                         return Err(anyhow!("No active socket"));
                     }
+                    let missed = client.liveness().missed_pongs();
+                    if missed >= config::MAX_MISSED_PONGS {
+                        error!("{} pings unanswered, connection is dead", missed);
+                        return Err(anyhow!("Connection dead: {} missed pongs", missed));
+                    }
+                    if let Err(e) = client.ping().await {
+                        error!("Ping failed: {}", e);
+                        return Err(anyhow!("Ping failed"));
+                    } else {
+                        debug!("Ping sent (last rtt {:?})", client.liveness().last_rtt());
+                    }
                 }
                 _ = shutdown.recv() => {
                     info!("Ping task received shutdown signal");
@@ -134,20 +389,34 @@ impl ThalexQuoter {
     /// Task to update quotes based on market data
     pub async fn quote_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
         info!("Quote task started");
-        let mut last_update = Instant::now();
+        let mut last_update = instant();
+        let mut waiting_logged = false;
     
         loop {
             tokio::select! {
                 _ = self.quote_notify.notified() => {
-                    let has_ticker = self.market_data.ticker.read().await.is_some();
-                    let has_index = self.market_data.index_price.read().await.is_some();
+                    let has_ticker = self.market_data.ticker().await.is_some();
+                    let has_fair_value = self.market_data.fair_value().await.is_some();
+                    let subscribed = self.notification_handler.subscriptions_ready().await;
+                    if !subscribed && self.state.current() == BotState::Warmup && !waiting_logged {
+                        let unconfirmed = self.notification_handler.subscription_states.read().await.unconfirmed();
+                        info!("Waiting for subscriptions before quoting: {}",
+                            unconfirmed.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", "));
+                        waiting_logged = true;
+                    }
         
-                    if has_ticker && has_index {
+                    if has_ticker && has_fair_value && subscribed && !self.market_data.is_standby() {
                         // Throttle: e.g., 1 update per 100ms
-                        if last_update.elapsed() >= Duration::from_millis(100) {
-                            let quotes = self.order_manager.make_quotes().await?;
+                        if instant().saturating_duration_since(last_update) >= Duration::from_millis(100) && self.update_quoting_state().await {
+                            // Requests held back by a rate limit go out ahead of the new quotes
+                            self.client.lock().await.flush().await?;
+                            let quotes = match self.state.may_quote() {
+                                true => self.order_manager.make_quotes().await?,
+                                // Adjusting to no quotes cancels the open ones
+                                false => vec![Vec::new(), Vec::new()],
+                            };
                             self.order_manager.adjust_quotes(quotes).await?;
-                            last_update = Instant::now();
+                            last_update = instant();
                         }
                     }
                 }
@@ -159,96 +428,1103 @@ impl ThalexQuoter {
         }
     }
 
-    /// Fetch and set instrument information
-    pub async fn await_instruments(&self, client: &mut ThalexClient) -> Result<()> {
-        client.instruments(Some(CALL_ID_INSTRUMENTS)).await?;
+    /// Move the state on to quoting, or to cancel-only or halted when the order manager
+    /// says so; false when the quotes may not be adjusted at all
+    pub async fn update_quoting_state(&self) -> bool {
+        let halted = self.order_manager.risk.read().await.halt_reason().map(str::to_string);
+        let (to, reason) = match (&halted, self.order_manager.cancel_only_reason().await) {
+            (Some(reason), _) => (BotState::Halted, reason.as_str()),
+            (None, Some(reason)) => (BotState::CancelOnly, reason),
+            (None, None) => (BotState::Quoting, "market data and subscriptions ready"),
+        };
+        match self.state.transition(to, reason) {
+            Ok(Some(_)) if to == BotState::Halted => self.pull_quotes().await,
+            Ok(Some(BotState::Warmup)) => {
+                self.lifecycle.emit(LifecycleEventType::QuotingStarted, None).await;
+            }
+            Ok(_) => {}
+            Err(e) => debug!("Quotes left as they are: {:#}", e),
+        }
+        to != BotState::Halted && self.state.current() == to
+    }
+
+    /// Pull every resting quote at once; quotes are no longer adjusted once halted
+    async fn pull_quotes(&self) {
+        match self.order_manager.cancel_session().await {
+            Ok(open) => warn!("Halted, cancelling every quote ({} open)", open),
+            Err(e) => error!("Failed to cancel the quotes on halting: {:#}", e),
+        }
+    }
 
-        match client.receive().await? {
-            Some(msg) => {
-                let parsed: InstrumentResponse = serde_json::from_str(&msg)?;
-                for instr in parsed.result {
-                    if instr.type_field == config::TYPE && instr.underlying == config::UNDERLYING {
-                        self.market_data.set_instrument_info(instr.instrument_name, instr.tick_size).await?;
-                        return Ok(());
+    /// Task to subscribe again to channels whose subscription the exchange rejected
+    pub async fn subscription_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(config::SUBSCRIBE_RETRY_CHECK_SEC));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let due = self.notification_handler.subscription_states.read().await.due(now_secs());
+                    if due.is_empty() {
+                        continue;
+                    }
+                    info!("Retrying subscriptions: {}", due.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", "));
+                    if let Err(e) = self.subscribe_channels(due.clone()).await {
+                        warn!("Failed to retry subscriptions: {:#}", e);
+                        self.notification_handler.subscription_states.write().await.failed(&due, now_secs());
                     }
                 }
-                Err(anyhow!("Perpetual BTCUSD not found"))
-            },
-            None => Err(anyhow!("No message received")),
+                _ = shutdown.recv() => {
+                    info!("Subscription task received shutdown signal");
+                    return Ok(());
+                }
+            }
         }
     }
 
-    /// Task to listen for WebSocket messages
-    pub async fn listen_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
-        {
-            let mut client = self.client.lock().await;
+    /// Task to apply scheduled parameter overrides as they start and end
+    pub async fn schedule_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(config::SCHEDULE_INTERVAL_SEC));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let now = now_utc();
+                    let (mut params, mut active) = self.scheduler.params_at(now);
+                    let mut variant_id = None;
+                    if let Some(experiment) = &self.experiment {
+                        let instrument = self.market_data.perp_name.read().await.clone().unwrap_or_default();
+                        if let Some(variant) = experiment.variant_at(now, &instrument) {
+                            params.apply(&variant.params);
+                            active.push(format!("{}/{}", experiment.name, variant.id));
+                            variant_id = Some(variant.id.clone());
+                        }
+                    }
+                    let switched = self.order_manager.set_variant(variant_id).await;
+                    
+                    let mut current = self.order_manager.params.write().await;
+                    if *current != params {
+                        info!("Parameter change: spread {} -> {}, skew {} -> {}, size x{} -> x{}, paused {} -> {} (active overrides: {:?})",
+                            current.spread, params.spread, current.skew, params.skew,
+                            current.size_multiplier, params.size_multiplier, current.paused, params.paused, active);
+                        *current = params;
+                        self.quote_notify.notify_one();
+                    }
+                    drop(current);
+                    
+                    // Quotes of the previous variant are pulled, so no order outlives its variant's slice
+                    if switched {
+                        if let Err(e) = self.order_manager.cancel_quotes().await {
+                            warn!("Failed to pull quotes of the previous variant: {:#}", e);
+                        }
+                        self.quote_notify.notify_one();
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Schedule task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
 
-            // Initialize instrument data
-            self.await_instruments(&mut client).await?;
+    /// Publish the latest ticker of each updated instrument to the compacted ticker topic
+    pub async fn ticker_sample_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(config::TICKER_SAMPLE_INTERVAL_SEC));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let tickers = self.market_data.ticker_sampler.write().await.take();
+                    // The primary instance already publishes in standby
+                    if self.market_data.is_standby() {
+                        continue;
+                    }
+                    let Some(kafka_producer) = &self.market_data.kafka_producer else {
+                        continue;
+                    };
+                    for ticker in &tickers {
+                        if let Err(e) = kafka_producer.send_ticker_latest(ticker).await {
+                            warn!("Failed to send latest ticker to Kafka: {}", e);
+                        }
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Ticker sample task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+    
+    /// Task to sample quote presence and publish daily uptime statistics
+    pub async fn uptime_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(config::UPTIME_SAMPLE_SEC));
+        let mut tracker: Option<UptimeTracker> = None;
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    // Uptime only covers time this instance was responsible for quoting
+                    if self.market_data.is_standby() {
+                        continue;
+                    }
+                    let (Some(instrument), Some(tick)) = (
+                        self.market_data.perp_name.read().await.clone(),
+                        self.market_data.tick().await,
+                    ) else {
+                        continue;
+                    };
+                    let mark = self.market_data.ticker().await.map(|t| t.mark_price);
+                    let compliant = match mark {
+                        Some(mark) => self.order_manager
+                            .is_two_sided_within(mark, config::UPTIME_MAX_DISTANCE_TICKS * tick).await,
+                        None => false,
+                    };
+                    
+                    let now = now_secs();
+                    if tracker.is_none() {
+                        // Pick up the day's uptime from before a restart
+                        let mut resumed = UptimeTracker::new(&instrument, config::UPTIME_MAX_DISTANCE_TICKS);
+                        resumed.resume(&*self.order_manager.daily_stats.read().await);
+                        tracker = Some(resumed);
+                    }
+                    let Some(tracker) = tracker.as_mut() else {
+                        continue;
+                    };
+                    if let Some(mut daily) = tracker.sample(now, compliant) {
+                        info!("Quote uptime for {} on {}: {:.2}% of {:.0}s",
+                            daily.instrument_name, daily.date, daily.uptime_pct, daily.observed_secs);
+                        daily.processing_timestamp = Some(now);
+                        if let Some(kafka_producer) = &self.market_data.kafka_producer {
+                            if let Err(e) = kafka_producer.send_uptime(&daily).await {
+                                warn!("Failed to send uptime to Kafka: {}", e);
+                            }
+                        }
+                    }
+                    let current = tracker.current(now);
+                    metrics::global().set_gauge(&format!("quote.uptime_pct.{}", instrument), current.uptime_pct);
+                    self.order_manager.daily_stats.write().await.set_uptime(&current);
+                }
+                _ = shutdown.recv() => {
+                    info!("Uptime task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Task to save the daily statistics periodically and once more on shutdown
+    pub async fn stats_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let Some(path) = &self.stats_path else {
+            // Nothing to save; finishing early would end the session
+            let _ = shutdown.recv().await;
+            return Ok(());
+        };
+        let mut interval = tokio::time::interval(Duration::from_secs(config::STATS_SNAPSHOT_INTERVAL_SEC));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let (snapshot, closed) = {
+                        let mut stats = self.order_manager.daily_stats.write().await;
+                        let closed = stats.roll(day_of(now_secs()));
+                        if closed.is_some() {
+                            stats.publish();
+                        }
+                        (stats.clone(), closed)
+                    };
+                    if let Some(day) = closed {
+                        self.order_manager.close_day(&day).await;
+                    }
+                    if let Err(e) = snapshot.save(path) {
+                        warn!("Failed to save daily statistics: {:#}", e);
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Stats task received shutdown signal");
+                    let snapshot = self.order_manager.daily_stats.read().await.clone();
+                    snapshot.save(path)?;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Task to export the previous day's fills and summary at the configured time of day
+    ///
+    /// Days whose export was missed, e.g. while disconnected over the export time, are
+    /// exported on start before waiting for the next run.
+    pub async fn eod_export_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let Some((time, exporter)) = &self.eod_export else {
+            // Nothing to export; finishing early would end the session
+            let _ = shutdown.recv().await;
+            return Ok(());
+        };
+        
+        loop {
+            // Close the day first when no fill has rolled it over yet
+            let closed = self.order_manager.daily_stats.write().await.roll(day_of(now_secs()));
+            if let Some(day) = closed {
+                self.order_manager.close_day(&day).await;
+            }
+            if let Err(e) = exporter.catch_up(Utc::now(), *time).await {
+                error!("{:#}", e);
+            }
+
+            let wait = (eod::next_run(Utc::now(), *time) - Utc::now()).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = shutdown.recv() => {
+                    info!("End-of-day export task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Task to feed the external fair value from the configured Kafka topic or HTTP endpoint
+    pub async fn fair_value_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        match self.fair_value.source {
+            FairValueSource::Kafka => self.consume_fair_value(shutdown).await,
+            FairValueSource::Http => self.poll_fair_value(shutdown).await,
+            FairValueSource::Index => {
+                // Quotes follow the index; finishing early would end the session
+                let _ = shutdown.recv().await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Consumer of `topic` reading only records produced from now on
+    fn latest_consumer(&self, group: &str, topic: &str) -> Result<Arc<StreamConsumer>> {
+        let brokers = self.kafka_bootstrap_servers.as_deref()
+            .ok_or_else(|| anyhow!("Consuming {} needs a Kafka configuration", topic))?;
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("group.id", format!("cryptics-{}-{}", group, self.lifecycle.instance_id()))
+            .set("enable.auto.commit", "false")
+            .set("auto.offset.reset", "latest")
+            .create()?;
+        consumer.subscribe(&[topic])?;
+        Ok(Arc::new(consumer))
+    }
+
+    /// Read JSON fair values from the Kafka topic, dated by their record timestamps
+    ///
+    /// Values read too late, or with newer ones already waiting behind them, are skipped.
+    async fn consume_fair_value(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let consumer = self.latest_consumer("fair-value", &self.fair_value.topic)?;
+        let guard = StalenessGuard::new(&self.fair_value.topic, self.fair_value.max_age_ms as f64 / 1000.0, self.fair_value.max_lag);
+        let mut watermarks = WatermarkCache::new(Duration::from_millis(config::CONSUMER_WATERMARK_REFRESH_MS));
+        info!("Consuming external fair value from {}", self.fair_value.topic);
+        
+        loop {
+            tokio::select! {
+                message = consumer.recv() => {
+                    let message = match message {
+                        Ok(message) => message,
+                        Err(e) => {
+                            warn!("Fair value consumer error: {}", e);
+                            continue;
+                        }
+                    };
+                    let produced_at = message.timestamp().to_millis().map(|millis| millis as f64 / 1000.0);
+                    let lag = watermarks.lag(&consumer, message.topic(), message.partition(), message.offset()).await;
+                    let staleness = guard.check(produced_at, None, lag, now_secs());
+                    if !staleness.is_fresh() {
+                        debug!("Skipping fair value at offset {}: {:?}", message.offset(), staleness);
+                        continue;
+                    }
+                    let at = produced_at.unwrap_or_else(now_secs);
+                    let price = message.payload()
+                        .and_then(|payload| serde_json::from_slice::<Value>(payload).ok())
+                        .and_then(|value| price_at(&value, &self.fair_value.field));
+                    match price {
+                        Some(price) => self.market_data.set_external_fair_value(price, at).await,
+                        None => debug!("Ignoring fair value message without a numeric {}", self.fair_value.field),
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Fair value task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Task to apply operator commands from the control topic
+    ///
+    /// Commands read too late, past their deadline or behind a backlog are ignored.
+    pub async fn control_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        if !self.control.enabled {
+            // No control topic; finishing early would end the session
+            let _ = shutdown.recv().await;
+            return Ok(());
+        }
+        let consumer = self.latest_consumer("control", &self.control.topic)?;
+        let guard = StalenessGuard::new(&self.control.topic, self.control.max_age_ms as f64 / 1000.0, self.control.max_lag);
+        let mut watermarks = WatermarkCache::new(Duration::from_millis(config::CONSUMER_WATERMARK_REFRESH_MS));
+        info!("Consuming control commands from {}", self.control.topic);
+        
+        loop {
+            tokio::select! {
+                message = consumer.recv() => {
+                    let message = match message {
+                        Ok(message) => message,
+                        Err(e) => {
+                            warn!("Control consumer error: {}", e);
+                            continue;
+                        }
+                    };
+                    let control = message.payload()
+                        .ok_or_else(|| anyhow!("Empty control message"))
+                        .and_then(|payload| Ok(serde_json::from_slice::<Value>(payload)?))
+                        .and_then(|value| ControlMessage::from_json(&value));
+                    let control = match control {
+                        Ok(control) => control,
+                        Err(e) => {
+                            warn!("Ignoring control message at offset {}: {}", message.offset(), e);
+                            continue;
+                        }
+                    };
+                    let produced_at = message.timestamp().to_millis().map(|millis| millis as f64 / 1000.0);
+                    let lag = watermarks.lag(&consumer, message.topic(), message.partition(), message.offset()).await;
+                    let staleness = guard.check(produced_at, control.expires_at, lag, now_secs());
+                    if !staleness.is_fresh() {
+                        warn!("Ignoring stale control command {:?}: {:?}", control.command, staleness);
+                        continue;
+                    }
+                    if let Err(e) = self.handle_command(control.command).await {
+                        warn!("Control command failed: {}", e);
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Control task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Poll the HTTP endpoint for the fair value, dated by when each response arrived
+    async fn poll_fair_value(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        if self.fair_value.url.is_empty() {
+            return Err(anyhow!("HTTP fair value source needs a url"));
+        }
+        let period = Duration::from_millis(self.fair_value.poll_interval_ms.max(1));
+        let client = reqwest::Client::builder().timeout(period).build()?;
+        let mut interval = tokio::time::interval(period);
+        info!("Polling external fair value from {}", self.fair_value.url);
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let response = async {
+                        client.get(&self.fair_value.url).send().await?.error_for_status()?.json::<Value>().await
+                    }.await;
+                    match response.map(|value| price_at(&value, &self.fair_value.field)) {
+                        Ok(Some(price)) => self.market_data.set_external_fair_value(price, now_secs()).await,
+                        Ok(None) => debug!("Ignoring fair value response without a numeric {}", self.fair_value.field),
+                        // A failing endpoint shows up as a stale fair value
+                        Err(e) => debug!("Fair value request failed: {}", e),
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Fair value task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Task to restart the Kafka producer when deliveries stall, and to fall back to the
+    /// journal when a restart doesn't help
+    pub async fn producer_watchdog_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let producer = match &self.order_manager.kafka_producer {
+            Some(producer) if self.producer_watchdog.enabled => producer.clone(),
+            _ => {
+                // Nothing to watch; finishing early would end the session
+                let _ = shutdown.recv().await;
+                return Ok(());
+            }
+        };
+        let stall_after = Duration::from_millis(self.producer_watchdog.stall_after_ms);
+        let mut watchdog = ProducerWatchdog::new(stall_after, self.producer_watchdog.journal_fallback);
+        let mut interval = tokio::time::interval(Duration::from_millis(self.producer_watchdog.check_interval_ms.max(1)));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if producer.is_journal_only() {
+                        // Back to Kafka as soon as a broker answers again
+                        if producer.probe(Duration::from_secs(config::KAFKA_PROBE_TIMEOUT_SEC)).await.is_ok() {
+                            info!("Kafka reachable again, leaving journal-only mode");
+                            producer.set_journal_only(false)?;
+                            watchdog.reset();
+                        }
+                        continue;
+                    }
+                    match watchdog.check(producer.backlog(), producer.last_delivery(), instant()) {
+                        WatchdogAction::None => {}
+                        WatchdogAction::Restart => {
+                            metrics::global().incr(METRIC_PRODUCER_STALLS, 1);
+                            error!("ALERT: no Kafka delivery for {}ms with {} records waiting, restarting the producer",
+                                self.producer_watchdog.stall_after_ms, producer.backlog());
+                            if let Err(e) = producer.restart_client() {
+                                error!("Failed to restart the Kafka producer: {:#}", e);
+                            }
+                        }
+                        WatchdogAction::DegradeToJournal => {
+                            error!("ALERT: Kafka deliveries still stalled after a restart, writing records to {} until Kafka recovers",
+                                self.producer_watchdog.journal_path);
+                            producer.set_journal_only(true)?;
+                            watchdog.reset();
+                        }
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Producer watchdog task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
 
-            // Set cancel on disconnect
-            client
-                .set_cancel_on_disconnect(config::TIMEOUT_SEC, Some(CALL_ID_SET_COD))
-                .await?;
+    /// Task to retry the Kafka deliveries that failed, once the broker is back
+    pub async fn spill_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let producer = match &self.order_manager.kafka_producer {
+            Some(producer) if self.spill.enabled => producer.clone(),
+            _ => {
+                // Nothing to retry; finishing early would end the session
+                let _ = shutdown.recv().await;
+                return Ok(());
+            }
+        };
+        let mut interval = tokio::time::interval(Duration::from_millis(self.spill.retry_interval_ms.max(1)));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if producer.spilled() > 0 {
+                        producer.retry_spilled().await;
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Spill task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
 
-            // Subscribe to private channels
-            let private_channels = config::CHANNELS.iter().map(|x| x.to_string()).collect();
-            client
-                .private_subscribe(private_channels, Some(CALL_ID_SUBSCRIBE))
-                .await?;
+    /// Task to periodically compute microstructure features and publish them to Kafka
+    pub async fn features_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(config::FEATURES_INTERVAL_SEC));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Some(features) = self.market_data.compute_features().await {
+                        debug!("Features: imbalance={:.3}, spread={}, intensity={:.3}, vol={:.6}, basis={:.6}",
+                            features.imbalance, features.spread, features.trade_intensity,
+                            features.realized_vol, features.funding_basis);
+                        
+                        if let Some(kafka_producer) = self.market_data.kafka_producer.as_ref().filter(|_| !self.market_data.is_standby()) {
+                            if let Err(e) = kafka_producer.send_features(&features).await {
+                                warn!("Failed to send features to Kafka: {}", e);
+                            }
+                        }
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Features task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Task to sample the basis and funding carry of the quoted perpetual and publish them to Kafka
+    pub async fn carry_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(config::CARRY_INTERVAL_SEC));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let Some(ticker) = self.market_data.ticker().await else {
+                        continue;
+                    };
+                    let position = self.order_manager.position().await;
+                    let now = now_secs();
+                    let Some(mut sample) = self.order_manager.carry.write().await.sample(now, &ticker, position) else {
+                        continue;
+                    };
+                    debug!("Carry: basis={:.2}bps (rolling {:.2}bps), funding={:.2}, carry={:.4}/yr",
+                        sample.basis_bps, sample.rolling_basis_bps, sample.cumulative_funding, sample.annualized_carry);
+                    
+                    sample.processing_timestamp = Some(now);
+                    if let Some(kafka_producer) = self.market_data.kafka_producer.as_ref().filter(|_| !self.market_data.is_standby()) {
+                        if let Err(e) = kafka_producer.send_funding_basis(&sample).await {
+                            warn!("Failed to send funding and basis to Kafka: {}", e);
+                        }
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Carry task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
 
-            // Subscribe to public channels
-            let public_channels = self.market_data.get_public_channels().await?;
-            client
-                .public_subscribe(public_channels, Some(CALL_ID_SUBSCRIBE))
-                .await?;
+    /// Task to request the account summary periodically; quote sizes follow its remaining margin
+    pub async fn account_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(config::ACCOUNT_SUMMARY_INTERVAL_SEC));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = self.order_manager.request_account_summary().await {
+                        warn!("Failed to request the account summary: {}", e);
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Account task received shutdown signal");
+                    return Ok(());
+                }
+            }
         }
+    }
 
+    /// Task to write queued requests once throttling or the request budget allows it
+    ///
+    /// Sends and quote cycles flush the queue too, but a quiet market may bring neither
+    /// for a while: without this, requests held back would wait for them, and stale order
+    /// requests would only be expired, and their levels freed, on the next one.
+    pub async fn outbound_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let outbound_ready = self.client.lock().await.outbound_ready();
+        
         loop {
+            // Sleep until the throttle ends, a budget refills or a request expires; a
+            // request queued meanwhile may be due sooner, so it wakes the task too
+            let wake = self.client.lock().await.next_flush();
+            let due = wake.map(|wake| wake.saturating_duration_since(instant()));
             tokio::select! {
-                // Get the next message
-                msg_result = async {
+                _ = tokio::time::sleep(due.unwrap_or_default()), if due.is_some() => {
                     let mut client = self.client.lock().await;
-                    client.receive().await
-                } => {
-                    match msg_result {
-                        Ok(Some(msg)) => {
-                            debug!("Raw Message Thalex:{}", msg);
-                            match serde_json::from_str::<Value>(&msg) {
-                                Ok(parsed) => {
-                                    if let Some(channel) = parsed.get("channel_name").and_then(|v| v.as_str()) {
-                                        if let Some(notification) = parsed.get("notification") {
-                                            self.notification_handler.handle_notification(channel, notification).await?;
-                                        }
-                                    } else if let Some(result) = parsed.get("result") {
-                                        let cid = parsed.get("id").and_then(|v| v.as_u64()).unwrap_or_default();
-                                        self.notification_handler.result_callback(result, cid).await?;
-                                    } else if let Some(error) = parsed.get("error") {
-                                        let cid = parsed.get("id").and_then(|v| v.as_u64()).unwrap_or_default();
-                                        self.notification_handler.error_callback(error, cid).await?;
-                                    } else {
-                                        warn!("Unhandled message: {}", msg);
-                                    }
-                                },
-                                Err(e) => {
-                                    error!("Failed to parse JSON: {}", e);
-                                }
+                    if client.outbound_depth() > 0 {
+                        client.flush().await?;
+                    }
+                }
+                _ = outbound_ready.notified() => {}
+                _ = shutdown.recv() => {
+                    info!("Outbound task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Leave hot standby: cancel whatever the previous primary left on the account,
+    /// reconcile and start quoting
+    ///
+    /// Quoting is held until the exchange reports the positions, which fills the
+    /// portfolio subscription hasn't delivered yet may have moved, and until in-flight
+    /// inserts, if any, are looked up among the open orders left after the cancel.
+    pub async fn take_over(&self) -> Result<()> {
+        info!("Taking over quoting from the previous primary");
+        {
+            let mut client = self.client.lock().await;
+            let id = client.calls().allocate(RpcMethod::CancelAll, None);
+            client.cancel_all(Some(id)).await?;
+        }
+        self.order_manager.request_positions().await?;
+        self.order_manager.request_reconcile().await?;
+        self.market_data.standby.store(false, std::sync::atomic::Ordering::Relaxed);
+        self.quote_notify.notify_one();
+        Ok(())
+    }
+
+    /// Task to publish a heartbeat so monitoring notices when the bot stops
+    pub async fn heartbeat_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(config::HEARTBEAT_INTERVAL_SEC));
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let now = now_secs();
+                    let heartbeat = {
+                        let portfolio = self.order_manager.portfolio.read().await;
+                        self.notification_handler.heartbeat.read().await.heartbeat(
+                            self.lifecycle.instance_id(),
+                            self.lifecycle.next_heartbeat_sequence(),
+                            &portfolio,
+                            now,
+                        )
+                    };
+                    debug!("Heartbeat {} (positions {})", heartbeat.sequence, heartbeat.positions_hash);
+                    if let Some(kafka_producer) = &self.market_data.kafka_producer {
+                        if let Err(e) = kafka_producer.send_heartbeat(&heartbeat).await {
+                            warn!("Failed to send heartbeat to Kafka: {}", e);
+                        }
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Heartbeat task received shutdown signal");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Measure local clock drift against the exchange and log the result
+    pub async fn check_clock(network: &Network) -> Result<ClockStatus> {
+        let drift = clock::measure_drift(network).await?;
+        let status = ClockStatus::from_drift(drift, config::CLOCK_DRIFT_WARN_SEC, config::CLOCK_DRIFT_HALT_SEC);
+        match status {
+            ClockStatus::InSync => debug!("Local clock in sync with exchange (drift {:.3}s)", drift),
+            ClockStatus::Drifted => warn!("Local clock drifted {:.3}s from exchange time", drift),
+            ClockStatus::Halt => error!("Local clock drifted {:.3}s from exchange time, halting", drift),
+        }
+        Ok(status)
+    }
+
+    /// Task to periodically re-check clock drift; returns an error when trading must halt
+    pub async fn clock_task(&self, network: Network, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let period = Duration::from_secs(config::CLOCK_CHECK_INTERVAL_SEC);
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    match Self::check_clock(&network).await {
+                        Ok(ClockStatus::Halt) => {
+                            let reason = format!("Clock drift exceeds {}s", config::CLOCK_DRIFT_HALT_SEC);
+                            match self.state.transition(BotState::Halted, &reason) {
+                                Ok(Some(_)) => self.pull_quotes().await,
+                                Ok(None) => {}
+                                Err(e) => warn!("{:#}", e),
                             }
-                        },
-                        Ok(None) => {
-                            debug!("No message received");
-                        },
-                        Err(e) => {
-                            error!("Error receiving message: {}", e);
-                            return Err(e);
+                            self.lifecycle.emit(LifecycleEventType::KillSwitch, Some(reason.clone())).await;
+                            return Err(anyhow!(reason));
                         }
+                        Ok(_) => {}
+                        Err(e) => warn!("Clock check failed: {}", e),
                     }
                 }
                 _ = shutdown.recv() => {
-                    info!("Listen task received shutdown signal");
+                    info!("Clock task received shutdown signal");
                     return Ok(());
                 }
             }
         }
     }
+
+    /// Apply the configured quoting settings of the quoted instrument
+    async fn apply_strategy_config(&self, instrument: &str) {
+        let Some(settings) = self.strategy.instrument(instrument) else {
+            return;
+        };
+        match AmendPolicy::from_config(settings) {
+            Ok(policy) => *self.order_manager.amend_policy.write().await = policy,
+            Err(e) => error!("Ignoring amend settings: {}", e),
+        }
+        if let Some(ladder) = &settings.ladder {
+            match LadderBuilder::from_config(ladder) {
+                Ok(ladder) => self.order_manager.set_ladder(ladder).await,
+                Err(e) => error!("Ignoring ladder for {}: {}", instrument, e),
+            }
+        }
+        if let Some(limits) = &settings.limits {
+            if let Err(e) = self.order_manager.risk.write().await.set_limits(limits) {
+                error!("Ignoring risk limits for {}: {}", instrument, e);
+            }
+        }
+        if let Some(budget) = &settings.side_budget {
+            match SideBudget::from_config(budget) {
+                Ok(budget) => *self.order_manager.side_budget.write().await = budget,
+                Err(e) => error!("Ignoring side budget for {}: {}", instrument, e),
+            }
+        }
+        if let Some(sizing) = &settings.sizing {
+            match SizeScaler::from_config(sizing) {
+                Ok(scaler) => *self.order_manager.sizing.write().await = Some(scaler),
+                Err(e) => error!("Ignoring size scaling for {}: {}", instrument, e),
+            }
+        }
+        if let Some(collar) = &settings.collar {
+            match CollarGuard::from_config(collar) {
+                Ok(guard) => *self.order_manager.collar.write().await = Some(guard),
+                Err(e) => error!("Ignoring collar settings for {}: {}", instrument, e),
+            }
+        }
+        if let Some(mass_quote) = &settings.mass_quote {
+            info!("Quoting {} with mass quotes", instrument);
+            *self.order_manager.mass_quote.write().await = Some(MassQuoteStrategy::from_config(mass_quote));
+        }
+    }
+
+    /// Apply an operator command to one of the quoted instruments
+    ///
+    /// Commands for instruments this instance doesn't quote are rejected.
+    pub async fn handle_command(&self, command: ControlCommand) -> Result<()> {
+        let instrument = command.instrument();
+        let quoted = self.market_data.perp_name.read().await.clone();
+        if quoted.as_deref() != Some(instrument) {
+            return Err(anyhow!("{} is not quoted by this instance (quoting {:?})", instrument, quoted));
+        }
+        
+        info!("Control command: {:?}", command);
+        match &command {
+            ControlCommand::Pause { instrument } => self.order_manager.pause(instrument).await,
+            ControlCommand::Resume { instrument } => self.order_manager.resume(instrument).await,
+            ControlCommand::Flatten { instrument } => {
+                self.order_manager.pause(instrument).await;
+                self.order_manager.flatten(instrument).await?;
+            }
+            ControlCommand::Enable { instrument } => self.enable_instrument(instrument).await?,
+            ControlCommand::Disable { instrument } => self.disable_instrument(instrument).await?,
+        }
+        // The quote task pulls or restores the quotes
+        self.quote_notify.notify_one();
+        Ok(())
+    }
+
+    /// Start quoting a disabled instrument: its market data is subscribed again and quoting
+    /// resumes with fresh orders once a new ticker arrives
+    pub async fn enable_instrument(&self, instrument: &str) -> Result<()> {
+        if !self.order_manager.enable(instrument).await {
+            debug!("{} is already enabled", instrument);
+            return Ok(());
+        }
+        info!("Enabling {}", instrument);
+        // The ticker from before the instrument was disabled is stale
+        self.market_data.clear_ticker(instrument).await;
+        self.apply_strategy_config(instrument).await;
+        let channels: Vec<Channel> = self.market_data.get_public_channels().await?
+            .into_iter()
+            .filter(|channel| channel.instrument() == Some(instrument))
+            .collect();
+        if let Err(e) = self.subscribe_channels(channels).await {
+            self.order_manager.disable(instrument).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Stop quoting an instrument: its open orders are cancelled right away and its
+    /// market data unsubscribed; the order levels are released as the cancels are confirmed
+    pub async fn disable_instrument(&self, instrument: &str) -> Result<()> {
+        if !self.order_manager.disable(instrument).await {
+            debug!("{} is already disabled", instrument);
+            return Ok(());
+        }
+        info!("Disabling {}", instrument);
+        self.order_manager.cancel_orders(Some(instrument)).await?;
+        let channels: Vec<Channel> = self.active_channels().await
+            .into_iter()
+            .filter(|channel| channel.instrument() == Some(instrument))
+            .collect();
+        if !channels.is_empty() {
+            self.unsubscribe_channels(channels).await?;
+        }
+        Ok(())
+    }
+
+    /// Fetch and set instrument information
+    ///
+    /// The perpetual is required; options matching `[thalex.options]` are selected
+    /// alongside it, and their tickers subscribed with the other public channels.
+    pub async fn await_instruments(&self, client: &mut dyn SessionClient) -> Result<()> {
+        let id = client.calls().allocate(RpcMethod::Instruments, None);
+        client.instruments(Some(id)).await?;
+
+        let Some(msg) = client.receive().await? else {
+            return Err(anyhow!("No message received"));
+        };
+        let parsed: InstrumentResponse = serde_json::from_str(&msg)?;
+        client.calls().complete(parsed.id);
+        
+        let perp = parsed.result.iter()
+            .find(|instr| instr.type_field == config::TYPE && instr.underlying == config::UNDERLYING)
+            .ok_or_else(|| anyhow!("Perpetual BTCUSD not found"))?;
+        self.apply_strategy_config(&perp.instrument_name).await;
+        self.market_data.set_instrument_info(perp.instrument_name.clone(), perp.tick_size).await?;
+        {
+            let mut risk = self.order_manager.risk.write().await;
+            if let Some(step) = perp.volume_tick_size {
+                risk.set_amount_step(step);
+            }
+            if let Some(contract_size) = perp.contract_size {
+                risk.set_contract_size(contract_size);
+            }
+        }
+        
+        // The hedge leg of a spread is tracked so its ticker is subscribed alongside the quoted one
+        let hedge_instrument = self.order_manager.spread.read().await.as_ref().map(|spread| spread.hedge_instrument.clone());
+        if let Some(hedge_instrument) = hedge_instrument {
+            let hedge = parsed.result.iter()
+                .find(|instr| instr.instrument_name == hedge_instrument)
+                .ok_or_else(|| anyhow!("Spread hedge instrument {} not found", hedge_instrument))?;
+            self.market_data.track_instrument(&hedge.instrument_name, hedge.tick_size).await;
+            if let (Some(step), Some(spread)) = (hedge.volume_tick_size, self.order_manager.spread.write().await.as_mut()) {
+                spread.set_hedge_step(step);
+            }
+        }
+        
+        if let Some(options) = &self.options {
+            let listed: Vec<_> = parsed.result.iter()
+                .filter(|instr| instr.is_option())
+                .filter_map(|instr| match instr.clone().with_name_details() {
+                    Ok(instr) => Some(instr),
+                    Err(e) => {
+                        warn!("Skipping option: {}", e);
+                        None
+                    }
+                })
+                .collect();
+            let selected = options.select(&listed, now_secs());
+            if selected.is_empty() {
+                warn!("No listed {} option matches the options selection", options.underlying);
+            } else {
+                info!("Selected {} of {} listed options: {}", selected.len(), listed.len(),
+                    selected.iter().map(|option| option.instrument_name.as_str()).collect::<Vec<_>>().join(", "));
+            }
+            self.market_data.set_options(selected).await;
+        }
+        Ok(())
+    }
+
+    /// Enable cancel-on-disconnect and wait for the exchange to accept it
+    ///
+    /// Quoting without the exchange-side safety net is not allowed, so a rejection is fatal.
+    pub async fn await_cancel_on_disconnect(client: &mut dyn SessionClient, timeout_secs: u64) -> Result<()> {
+        let id = client.calls().allocate(RpcMethod::SetCancelOnDisconnect, Some(timeout_secs.to_string()));
+        client.set_cancel_on_disconnect(timeout_secs, Some(id)).await?;
+
+        loop {
+            let Some(msg) = client.receive().await? else {
+                continue;
+            };
+            let parsed: Value = serde_json::from_str(&msg)?;
+            if parsed.get("id").and_then(|v| v.as_u64()) != Some(id) {
+                debug!("Ignoring message while awaiting cancel on disconnect: {}", msg);
+                continue;
+            }
+            client.calls().complete(id);
+            if let Some(error) = parsed.get("error") {
+                return Err(anyhow!("Exchange rejected cancel on disconnect: {}", error));
+            }
+            info!("Cancel on disconnect set to {}s", timeout_secs);
+            return Ok(());
+        }
+    }
+
+    /// Subscribe to channels at runtime and start routing their notifications
+    ///
+    /// Private and public channels are split into the matching subscribe calls.
+    pub async fn subscribe_channels(&self, channels: Vec<Channel>) -> Result<()> {
+        // Register first so notifications arriving right after the subscribe are routed
+        let added = self.notification_handler.add_subscriptions(&channels).await;
+        
+        let (private, public): (Vec<Channel>, Vec<Channel>) = channels.iter().cloned().partition(|c| c.is_private());
+        for (group, is_private) in [(&private, true), (&public, false)] {
+            if group.is_empty() {
+                continue;
+            }
+            if let Err(e) = self.send_subscribe(group, is_private).await {
+                // Stop routing only the channels this call added and didn't send: all of them when
+                // the private call failed, the public ones when the private call already went out
+                let unsent: Vec<Channel> = added.iter()
+                    .filter(|channel| is_private || !channel.is_private())
+                    .cloned()
+                    .collect();
+                self.notification_handler.remove_subscriptions(&unsent).await;
+                if !is_private {
+                    self.subscriptions.subscribed(&private);
+                }
+                return Err(e);
+            }
+        }
+        self.subscriptions.subscribed(&channels);
+        Ok(())
+    }
+
+    /// Send one subscribe call for `channels`, all private or all public
+    async fn send_subscribe(&self, channels: &[Channel], is_private: bool) -> Result<()> {
+        let method = if is_private { RpcMethod::PrivateSubscribe } else { RpcMethod::PublicSubscribe };
+        let names: Vec<String> = channels.iter().map(|c| c.to_string()).collect();
+        let mut client = self.client.lock().await;
+        let id = client.calls().allocate(method, Some(names.join(",")));
+        client.subscribe(names, is_private, Some(id)).await?;
+        self.notification_handler.subscription_states.write().await.requested(channels);
+        Ok(())
+    }
+
+    /// Unsubscribe from channels at runtime and stop routing their notifications
+    pub async fn unsubscribe_channels(&self, channels: Vec<Channel>) -> Result<()> {
+        {
+            let mut client = self.client.lock().await;
+            let names: Vec<String> = channels.iter().map(|c| c.to_string()).collect();
+            let id = client.calls().allocate(RpcMethod::Unsubscribe, Some(names.join(",")));
+            client.unsubscribe(names, Some(id)).await?;
+        }
+        self.notification_handler.remove_subscriptions(&channels).await;
+        self.notification_handler.subscription_states.write().await.removed(&channels);
+        self.subscriptions.unsubscribed(&channels);
+        Ok(())
+    }
+
+    /// Channels the quoter is currently subscribed to
+    pub async fn active_channels(&self) -> Vec<Channel> {
+        self.notification_handler.active_subscriptions().await
+    }
+
+    /// Task to listen for WebSocket messages
+    pub async fn listen_task(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        // Requests the client drops unsent are rejected like the venue would reject them
+        let (rejection_tx, mut rejection_rx) = mpsc::unbounded_channel::<String>();
+
+        let mut reader = {
+            let mut client = self.client.lock().await;
+            client.set_rejections(rejection_tx);
+
+            // Initialize instrument data
+            self.await_instruments(&mut *client).await?;
+
+            // Set cancel on disconnect, as the previous session had it if there was one
+            match self.subscriptions.cancel_on_disconnect().or(self.cancel_on_disconnect) {
+                Some(timeout_secs) => {
+                    Self::await_cancel_on_disconnect(&mut *client, timeout_secs).await?;
+                    self.subscriptions.cancel_on_disconnect_set(timeout_secs);
+                }
+                None => warn!("Cancel on disconnect disabled, orders will survive a dropped session"),
+            }
+
+            // Detach the read half so receiving never holds the client lock
+            client.take_reader().ok_or_else(|| anyhow!("WebSocket reader unavailable"))?
+        };
+
+        // Quoting waits for the account channels and the quoted instrument's prices
+        let mut required = config::CHANNELS.to_vec();
+        if let Some(perp_name) = self.market_data.perp_name.read().await.as_deref() {
+            required.push(Channel::ticker(perp_name));
+        }
+        required.push(Channel::Index(config::UNDERLYING.to_string()));
+        self.notification_handler.subscription_states.write().await.require(&required);
+
+        // Subscribe to private channels, with those the previous session added
+        let restored = self.subscriptions.channels().len();
+        self.subscribe_channels(self.subscriptions.restore(config::CHANNELS.to_vec(), true)).await?;
+        
+        // Find out which inserts of the previous session reached the exchange, and correct
+        // the positions to the exchange's, before quoting
+        self.order_manager.request_reconcile().await?;
+        self.order_manager.request_positions().await?;
+
+        // Subscribe to public channels, with those the previous session added, except
+        // those of disabled instruments
+        let mut public_channels = self.subscriptions.restore(self.market_data.get_public_channels().await?, false);
+        let disabled = self.order_manager.disabled_instruments.read().await.clone();
+        public_channels.retain(|channel| channel.instrument().is_none_or(|instrument| !disabled.contains(instrument)));
+        self.subscribe_channels(public_channels).await?;
+        if restored > 0 {
+            info!("Restored the subscriptions of the previous session ({} channels)", restored);
+        }
+        self.lifecycle.emit(LifecycleEventType::Subscribed, Some(format!("{} channels", self.active_channels().await.len()))).await;
+
+        // Private order/trade traffic and market data are processed on separate
+        // paths so a burst of market data can't delay ack handling
+        let (private_tx, private_rx) = mpsc::channel::<InboundMessage>(config::PRIVATE_QUEUE_SIZE);
+        let (market_tx, market_rx) = mpsc::channel::<InboundMessage>(config::MARKET_QUEUE_SIZE);
+
+        #[cfg(feature = "chaos")]
+        let mut chaos = self.chaos.enabled.then(|| {
+            warn!("Chaos injection enabled: {:?}", self.chaos);
+            ChaosLayer::new(self.chaos.clone())
+        });
+
+        let receive_loop = async {
+            loop {
+                match reader.receive().await {
+                    Ok(Some(msg)) => {
+                        #[cfg(feature = "chaos")]
+                        if let Some(chaos) = chaos.as_mut() {
+                            for msg in chaos.inject(msg).await {
+                                Self::route(&msg, &private_tx, &market_tx).await?;
+                            }
+                            continue;
+                        }
+                        Self::route(&msg, &private_tx, &market_tx).await?;
+                    },
+                    Ok(None) => {
+                        debug!("No message received");
+                    },
+                    Err(e) => {
+                        error!("Error receiving message: {}", e);
+                        return Err(e);
+                    }
+                }
+            }
+        };
+
+        let rejection_loop = async {
+            while let Some(msg) = rejection_rx.recv().await {
+                Self::route(&msg, &private_tx, &market_tx).await?;
+            }
+            Ok(())
+        };
+
+        tokio::select! {
+            biased;
+            _ = shutdown.recv() => {
+                info!("Listen task received shutdown signal");
+                Ok(())
+            }
+            res = self.process_queue(private_rx) => res,
+            res = self.process_queue(market_rx) => res,
+            res = rejection_loop => res,
+            res = receive_loop => res,
+        }
+    }
+
+    /// Parse a raw frame and queue it on the processing path for its priority
+    pub async fn route(
+        msg: &str,
+        private_tx: &mpsc::Sender<InboundMessage>,
+        market_tx: &mpsc::Sender<InboundMessage>,
+    ) -> Result<()> {
+        debug!("Raw Message Thalex:{}", msg);
+        let message = match InboundMessage::from_slice(msg.as_bytes()) {
+            Ok(Some(message)) => message,
+            Ok(None) => {
+                warn!("Unhandled message: {}", msg);
+                return Ok(());
+            }
+            Err(e) => {
+                error!("Failed to parse JSON: {}", e);
+                return Ok(());
+            }
+        };
+        match message.priority() {
+            Priority::High => {
+                private_tx.send(message).await
+                    .map_err(|_| anyhow!("Private processing queue closed"))?;
+            }
+            Priority::Normal => {
+                if let Err(mpsc::error::TrySendError::Full(_)) = market_tx.try_send(message) {
+                    warn!("Market data queue full, dropping update");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Drain a processing queue, dispatching each message to the notification handler
+    async fn process_queue(&self, mut queue: mpsc::Receiver<InboundMessage>) -> Result<()> {
+        while let Some(message) = queue.recv().await {
+            self.notification_handler.dispatch(message).await?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,259 @@
+use anyhow::{anyhow, Result};
+
+pub use crate::config_loader::LeverageTier;
+use crate::config_loader::{RiskConfig, RiskLimitsConfig};
+use crate::domain::enums::OrderSide;
+use crate::domain::model::quote::SideQuote;
+
+/// Constrains quote sizes so the position never exceeds what margin supports
+pub struct RiskManager {
+    /// Equity backing the strategy
+    equity: f64,
+
+    /// Leverage tiers, ascending by notional
+    tiers: Vec<LeverageTier>,
+
+    /// Largest amount allowed on a single order
+    max_order_amount: f64,
+
+    /// Amounts are rounded down to a multiple of this step
+    amount_step: f64,
+
+    /// Underlying units per contract, from the instrument registry
+    contract_size: f64,
+
+    /// Delta of one unit of the underlying, from the latest ticker
+    delta: f64,
+
+    /// Notional and delta limits, on top of the tiers
+    limits: RiskLimitsConfig,
+
+    /// Why trading was halted; no orders are allowed until the process restarts
+    halted: Option<String>,
+
+    /// Margin the account has left for new positions, from the latest account summary
+    available_margin: Option<f64>,
+}
+
+impl RiskManager {
+    pub fn new(equity: f64, tiers: &[LeverageTier], max_order_amount: f64, amount_step: f64) -> Self {
+        let mut tiers = tiers.to_vec();
+        tiers.sort_by(|a, b| a.max_notional.total_cmp(&b.max_notional));
+        Self {
+            equity,
+            tiers,
+            max_order_amount,
+            amount_step,
+            contract_size: 1.0,
+            delta: 1.0,
+            limits: RiskLimitsConfig::default(),
+            halted: None,
+            available_margin: None,
+        }
+    }
+
+    /// Risk manager with the configured equity and leverage tiers
+    pub fn from_config(config: &RiskConfig, max_order_amount: f64, amount_step: f64) -> Self {
+        Self::new(config.equity, &config.leverage_tiers, max_order_amount, amount_step)
+    }
+
+    /// Stop all trading, e.g. after the exchange liquidated the account
+    pub fn halt(&mut self, reason: &str) {
+        if self.halted.is_none() {
+            self.halted = Some(reason.to_string());
+        }
+    }
+
+    /// Why trading is halted, if it is
+    pub fn halt_reason(&self) -> Option<&str> {
+        self.halted.as_deref()
+    }
+
+    /// Equity backing the strategy
+    pub fn equity(&self) -> f64 {
+        self.equity
+    }
+
+    /// Update the equity from account data
+    pub fn set_equity(&mut self, equity: f64) {
+        if equity.is_finite() && equity >= 0.0 {
+            self.equity = equity;
+        }
+    }
+
+    /// Margin the account has left for new positions, None until an account summary arrived
+    pub fn available_margin(&self) -> Option<f64> {
+        self.available_margin
+    }
+
+    /// Update the margin left for new positions from account data
+    pub fn set_available_margin(&mut self, margin: f64) {
+        if margin.is_finite() {
+            self.available_margin = Some(margin.max(0.0));
+        }
+    }
+
+    /// Use the instrument's volume tick as the amount step
+    pub fn set_amount_step(&mut self, amount_step: f64) {
+        if amount_step > 0.0 {
+            self.amount_step = amount_step;
+        }
+    }
+
+    /// Use the instrument's contract size when converting notional and delta limits
+    pub fn set_contract_size(&mut self, contract_size: f64) {
+        if contract_size.is_finite() && contract_size > 0.0 {
+            self.contract_size = contract_size;
+        }
+    }
+
+    /// Update the instrument's delta from the ticker
+    pub fn set_delta(&mut self, delta: f64) {
+        if delta.is_finite() {
+            self.delta = delta;
+        }
+    }
+
+    /// Apply notional and delta limits; every configured limit must be positive
+    pub fn set_limits(&mut self, limits: &RiskLimitsConfig) -> Result<()> {
+        for (name, limit) in [
+            ("max_position_notional", limits.max_position_notional),
+            ("max_order_notional", limits.max_order_notional),
+            ("max_delta", limits.max_delta),
+        ] {
+            if let Some(limit) = limit.filter(|limit| !(limit.is_finite() && *limit > 0.0)) {
+                return Err(anyhow!("{} must be positive: {}", name, limit));
+            }
+        }
+        self.limits = limits.clone();
+        Ok(())
+    }
+
+    /// Largest position notional allowed by the tiers for the current equity
+    ///
+    /// A notional is allowed when it sits inside a tier and equity times that tier's
+    /// leverage covers it; the answer is the largest such notional across all tiers.
+    pub fn max_notional(&self) -> f64 {
+        self.tiers
+            .iter()
+            .map(|tier| tier.max_notional.min(self.equity * tier.max_leverage))
+            .fold(0.0, f64::max)
+    }
+
+    /// Leverage of the tier `notional` sits in; beyond the last tier, that tier's leverage
+    fn leverage_at(&self, notional: f64) -> f64 {
+        self.tiers.iter()
+            .find(|tier| notional <= tier.max_notional)
+            .or(self.tiers.last())
+            .map_or(0.0, |tier| tier.max_leverage)
+    }
+
+    /// Contracts the available margin can carry on top of `position` at `price`, None
+    /// while the available margin is unknown
+    pub fn margin_capacity(&self, position: f64, price: f64) -> Option<f64> {
+        let available_margin = self.available_margin?;
+        let contract_notional = price * self.contract_size;
+        if contract_notional <= 0.0 {
+            return Some(0.0);
+        }
+        let leverage = self.leverage_at(position.abs() * contract_notional);
+        Some(available_margin * leverage / contract_notional)
+    }
+
+    /// Largest absolute position (in contracts) allowed at `price`, by the tiers and
+    /// the notional and delta limits
+    pub fn max_position(&self, price: f64) -> f64 {
+        let contract_notional = price * self.contract_size;
+        if contract_notional <= 0.0 {
+            return 0.0;
+        }
+        let mut max_position = self.max_notional() / contract_notional;
+        if let Some(max_notional) = self.limits.max_position_notional {
+            max_position = max_position.min(max_notional / contract_notional);
+        }
+        let contract_delta = (self.delta * self.contract_size).abs();
+        if let (Some(max_delta), true) = (self.limits.max_delta, contract_delta > 0.0) {
+            max_position = max_position.min(max_delta / contract_delta);
+        }
+        max_position
+    }
+
+    /// Largest amount allowed on a single order at `price`
+    pub fn max_order(&self, price: f64) -> f64 {
+        match self.limits.max_order_notional {
+            Some(max_notional) if price > 0.0 => self.max_order_amount.min(max_notional / (price * self.contract_size)),
+            _ => self.max_order_amount,
+        }
+    }
+
+    /// Pre-trade check of a single order: its size, and the position should it fill completely
+    ///
+    /// A fill that reduces the absolute position is always allowed.
+    pub fn check_order(&self, side: &OrderSide, amount: f64, price: f64, position: f64) -> Result<()> {
+        if let Some(reason) = &self.halted {
+            return Err(anyhow!("Trading halted: {}", reason));
+        }
+        // Tolerance for amounts that were rounded to the step
+        let tolerance = self.amount_step * 1e-6;
+        let max_order = self.max_order(price);
+        if amount > max_order + tolerance {
+            return Err(anyhow!("Order of {} at {} exceeds the order limit of {}", amount, price, max_order));
+        }
+        let after = match side {
+            OrderSide::Buy => position + amount,
+            OrderSide::Sell => position - amount,
+        };
+        let max_position = self.max_position(price);
+        if after.abs() > max_position + tolerance && after.abs() > position.abs() {
+            return Err(anyhow!("Order of {} at {} would take the position to {}, beyond the limit of {}",
+                amount, price, after, max_position));
+        }
+        Ok(())
+    }
+
+    /// Clip quote amounts so no combination of fills takes the position beyond the limit
+    ///
+    /// `quotes` is `[bids, asks]` ordered from the best level outwards; levels that
+    /// end up below one amount step are dropped. Nothing is quoted while halted.
+    ///
+    /// Once the available margin is known, fills that grow the position are also kept
+    /// within what it can carry. The exchange counts the strategy's own open quotes
+    /// against that margin, so the cap is conservative while they rest.
+    pub fn constrain_quotes(&self, quotes: Vec<Vec<SideQuote>>, position: f64, price: f64) -> Vec<Vec<SideQuote>> {
+        if self.halted.is_some() {
+            return quotes.iter().map(|_| Vec::new()).collect();
+        }
+        let max_position = self.max_position(price);
+        let max_order = self.max_order(price);
+        let mut capacities = [max_position - position, max_position + position];
+        if let Some(margin_capacity) = self.margin_capacity(position, price) {
+            // Reducing the position frees margin, so only the part beyond flat needs it
+            capacities[0] = capacities[0].min((-position).max(0.0) + margin_capacity);
+            capacities[1] = capacities[1].min(position.max(0.0) + margin_capacity);
+        }
+
+        quotes
+            .into_iter()
+            .zip(capacities)
+            .map(|(levels, capacity)| {
+                let mut remaining = capacity.max(0.0);
+                levels
+                    .into_iter()
+                    .filter_map(|quote| {
+                        let amount = self.round_down(quote.amount.min(max_order).min(remaining));
+                        if amount < self.amount_step {
+                            return None;
+                        }
+                        remaining -= amount;
+                        Some(SideQuote::new(quote.price, amount))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn round_down(&self, amount: f64) -> f64 {
+        // Small epsilon so exact multiples aren't lost to float error
+        (amount / self.amount_step + 1e-9).floor() * self.amount_step
+    }
+}
@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::infrastructure::exchange::thalex::channel::Channel;
+
+/// Processing class of an inbound message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Priority {
+    /// Order/trade/portfolio notifications and RPC results/errors
+    High,
+    /// Market data (ticker, index, book)
+    Normal,
+}
+
+/// Inbound exchange message after JSON parsing, ready to be routed
+#[derive(Clone, Debug)]
+pub enum InboundMessage {
+    Notification { channel: String, notification: Value },
+    Result { result: Value, cid: u64 },
+    Error { error: Value, cid: u64 },
+}
+
+/// Top-level fields of a WebSocket frame
+///
+/// The channel name is borrowed from the frame and the payload is deserialized straight
+/// into its `Value`, without building a map for the envelope around it.
+#[derive(Deserialize)]
+struct Frame<'a> {
+    #[serde(borrow)]
+    channel_name: Option<Cow<'a, str>>,
+    id: Option<u64>,
+    #[serde(default, deserialize_with = "present")]
+    notification: Option<Value>,
+    #[serde(default, deserialize_with = "present")]
+    result: Option<Value>,
+    #[serde(default, deserialize_with = "present")]
+    error: Option<Value>,
+}
+
+/// A field that is present, even as `null`, which plain `Option` would read as absent
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+impl InboundMessage {
+    /// Parse a raw WebSocket frame into a routable message in a single pass
+    ///
+    /// `Ok(None)` is a well-formed frame that isn't routable.
+    pub fn from_slice(frame: &[u8]) -> anyhow::Result<Option<Self>> {
+        let frame: Frame = serde_json::from_slice(frame)?;
+        let cid = frame.id.unwrap_or_default();
+
+        let message = if let Some(channel) = frame.channel_name {
+            frame.notification.map(|notification| InboundMessage::Notification {
+                channel: channel.into_owned(),
+                notification,
+            })
+        } else if let Some(result) = frame.result {
+            Some(InboundMessage::Result { result, cid })
+        } else {
+            frame.error.map(|error| InboundMessage::Error { error, cid })
+        };
+        Ok(message)
+    }
+
+    /// Extract a routable message from a parsed WebSocket frame
+    pub fn from_json(mut parsed: Value) -> Option<Self> {
+        let cid = parsed.get("id").and_then(|v| v.as_u64()).unwrap_or_default();
+
+        if let Some(channel) = parsed.get("channel_name").and_then(|v| v.as_str()).map(str::to_string) {
+            let notification = parsed.get_mut("notification")?.take();
+            Some(InboundMessage::Notification { channel, notification })
+        } else if let Some(result) = parsed.get_mut("result") {
+            Some(InboundMessage::Result { result: result.take(), cid })
+        } else {
+            parsed.get_mut("error").map(|error| InboundMessage::Error { error: error.take(), cid })
+        }
+    }
+
+    /// Which processing path the message belongs on
+    pub fn priority(&self) -> Priority {
+        match self {
+            InboundMessage::Notification { channel, .. } if Channel::is_private_name(channel) => Priority::High,
+            InboundMessage::Notification { .. } => Priority::Normal,
+            InboundMessage::Result { .. } | InboundMessage::Error { .. } => Priority::High,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::config_loader::{ParamOverride, Schedule, ScheduleConfig};
+
+use super::config;
+
+/// Quoting parameters that can change over the day
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuoteParams {
+    /// Distance of the first level from the index, in ticks
+    pub spread: f64,
+
+    /// Shift applied to both sides, in ticks
+    pub skew: f64,
+
+    /// Factor applied to every quote size
+    pub size_multiplier: f64,
+
+    /// No quotes are placed, and open ones are cancelled
+    pub paused: bool,
+}
+
+impl Default for QuoteParams {
+    fn default() -> Self {
+        Self {
+            spread: config::SPREAD,
+            skew: 0.0,
+            size_multiplier: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl QuoteParams {
+    /// Replace the parameters `params` sets
+    pub fn apply(&mut self, params: &ParamOverride) {
+        if let Some(spread) = params.spread {
+            self.spread = spread;
+        }
+        if let Some(skew) = params.skew {
+            self.skew = skew;
+        }
+        if let Some(size_multiplier) = params.size_multiplier {
+            self.size_multiplier = size_multiplier;
+        }
+        if let Some(pause) = params.pause {
+            self.paused = pause;
+        }
+    }
+}
+
+/// Resolves the quoting parameters in force at a given time
+///
+/// Daily windows are applied in config order, then the funding window, then events,
+/// so a later entry wins for any field both set.
+#[derive(Default)]
+pub struct ParameterScheduler {
+    base: QuoteParams,
+    schedule: Schedule,
+}
+
+impl ParameterScheduler {
+    pub fn from_config(schedule: &ScheduleConfig) -> Result<Self> {
+        Ok(Self { base: QuoteParams::default(), schedule: schedule.parse()? })
+    }
+
+    /// Parameters in force at `now`, with the names of the active overrides
+    pub fn params_at(&self, now: DateTime<Utc>) -> (QuoteParams, Vec<String>) {
+        let mut params = self.base.clone();
+        let mut active = Vec::new();
+        for (name, overrides) in self.schedule.active_at(now) {
+            params.apply(overrides);
+            active.push(name.to_string());
+        }
+        (params, active)
+    }
+}
@@ -0,0 +1,31 @@
+use crate::domain::model::session_summary::SessionSummary;
+use crate::infrastructure::metrics::Metrics;
+use crate::infrastructure::reconnect::METRIC_RECONNECTS;
+
+use super::order_manager::{
+    METRIC_FILLS, METRIC_FILL_VOLUME, METRIC_MAX_INVENTORY, METRIC_ORDERS_AMENDED, METRIC_ORDERS_CANCELLED,
+    METRIC_ORDERS_INSERTED, METRIC_REALIZED_PNL,
+};
+
+/// Assemble the summary of the run that started at `started_at` from the process metrics
+///
+/// The order and fill metrics count from process start across every session, so they
+/// cover the whole run including reconnects.
+pub fn summarize_session(metrics: &Metrics, instance_id: &str, started_at: f64, now: f64) -> SessionSummary {
+    let count = |name| metrics.counter_value(name) as i64;
+    let total = |name| metrics.gauge_value(name).unwrap_or(0.0);
+    SessionSummary {
+        instance_id: instance_id.to_string(),
+        started_at,
+        ended_at: now,
+        quotes_sent: count(METRIC_ORDERS_INSERTED),
+        amends: count(METRIC_ORDERS_AMENDED),
+        cancels: count(METRIC_ORDERS_CANCELLED),
+        fills: count(METRIC_FILLS),
+        volume: total(METRIC_FILL_VOLUME),
+        realized_pnl: total(METRIC_REALIZED_PNL),
+        max_inventory: total(METRIC_MAX_INVENTORY),
+        reconnects: count(METRIC_RECONNECTS),
+        processing_timestamp: Some(now),
+    }
+}
@@ -0,0 +1,64 @@
+use anyhow::{anyhow, Result};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::config_loader::SideBudgetConfig;
+
+use super::config;
+
+/// Separate allowances of order updates (inserts and amends) for the bid and ask side
+///
+/// Each side may send at most `max_updates` within any `window`. During a fast
+/// one-directional move the chasing side spends only its own allowance, so the other
+/// side's updates still go out. Cancels don't draw from either budget.
+#[derive(Debug, Clone)]
+pub struct SideBudget {
+    max_updates: usize,
+    window: Duration,
+    /// Send times of the updates still inside the window, per side
+    sent: [VecDeque<Instant>; 2],
+}
+
+impl Default for SideBudget {
+    fn default() -> Self {
+        Self {
+            max_updates: config::SIDE_UPDATE_BUDGET,
+            window: Duration::from_millis(config::SIDE_BUDGET_WINDOW_MS),
+            sent: Default::default(),
+        }
+    }
+}
+
+impl SideBudget {
+    pub fn new(max_updates: usize, window: Duration) -> Result<Self> {
+        if max_updates == 0 {
+            return Err(anyhow!("max_updates must be positive"));
+        }
+        if window.is_zero() {
+            return Err(anyhow!("window_ms must be positive"));
+        }
+        Ok(Self { max_updates, window, sent: Default::default() })
+    }
+
+    pub fn from_config(budget: &SideBudgetConfig) -> Result<Self> {
+        Self::new(budget.max_updates, Duration::from_millis(budget.window_ms))
+    }
+
+    /// Spend one update of `side` (0 bids, 1 asks) at `now`; false when its budget is used up
+    pub fn try_acquire(&mut self, side: usize, now: Instant) -> bool {
+        if self.available(side, now) == 0 {
+            return false;
+        }
+        self.sent[side].push_back(now);
+        true
+    }
+
+    /// Updates `side` may still send at `now`
+    pub fn available(&mut self, side: usize, now: Instant) -> usize {
+        let sent = &mut self.sent[side];
+        while sent.front().is_some_and(|&at| now.duration_since(at) >= self.window) {
+            sent.pop_front();
+        }
+        self.max_updates.saturating_sub(sent.len())
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::{anyhow, Result};
+
+use crate::config_loader::SizingConfig;
+
+/// Scales quote sizes so each level carries a constant share of equity at risk
+///
+/// The multiplier is recomputed every quoting cycle from the current equity,
+/// price and realized volatility, and capped to the configured bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeScaler {
+    risk_per_level: f64,
+    min_vol: f64,
+    min_multiplier: f64,
+    max_multiplier: f64,
+}
+
+impl SizeScaler {
+    pub fn new(risk_per_level: f64, min_vol: f64, min_multiplier: f64, max_multiplier: f64) -> Result<Self> {
+        if !(risk_per_level > 0.0 && risk_per_level < 1.0) {
+            return Err(anyhow!("risk_per_level must be in (0, 1): {}", risk_per_level));
+        }
+        if !min_vol.is_finite() || min_vol <= 0.0 {
+            return Err(anyhow!("min_vol must be positive: {}", min_vol));
+        }
+        if !(min_multiplier >= 0.0 && min_multiplier <= max_multiplier) {
+            return Err(anyhow!("Invalid multiplier bounds [{}, {}]", min_multiplier, max_multiplier));
+        }
+        Ok(Self { risk_per_level, min_vol, min_multiplier, max_multiplier })
+    }
+
+    pub fn from_config(sizing: &SizingConfig) -> Result<Self> {
+        Self::new(sizing.risk_per_level, sizing.min_vol, sizing.min_multiplier, sizing.max_multiplier)
+    }
+
+    /// Multiplier for ladder sizes whose first level is `reference_size`
+    ///
+    /// `vol` is the relative volatility over the feature window; without usable
+    /// inputs the smallest multiplier is used.
+    pub fn multiplier(&self, equity: f64, price: f64, vol: f64, reference_size: f64) -> f64 {
+        if !(equity > 0.0 && price > 0.0 && reference_size > 0.0 && vol.is_finite()) {
+            return self.min_multiplier;
+        }
+        let target_size = equity * self.risk_per_level / (price * vol.max(self.min_vol));
+        (target_size / reference_size).clamp(self.min_multiplier, self.max_multiplier)
+    }
+}
@@ -0,0 +1,231 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use std::collections::BTreeMap;
+
+use crate::config_loader::SpreadConfig;
+use crate::domain::enums::OrderSide;
+
+/// Quoted-leg amount not yet offset on the hedge leg; positive when net long
+pub const METRIC_SPREAD_LEG_RISK: &str = "spread.leg_risk";
+
+/// Label of hedge orders, so their fills aren't counted as quote fills
+pub const HEDGE_LABEL: &str = "H";
+
+/// Hedge attempts per quote fill before the remainder is left as leg risk
+pub const HEDGE_MAX_ATTEMPTS: u32 = 3;
+
+/// Hedge order sent for a fill on the quoted leg
+#[derive(Debug, Clone)]
+pub struct LinkedOrder {
+    /// Quote order whose fill is being hedged, when known
+    pub quote_order_id: Option<u64>,
+    pub side: OrderSide,
+    pub amount: f64,
+    pub filled: f64,
+    /// Attempt number for this quote fill, starting at 1
+    pub attempt: u32,
+}
+
+impl LinkedOrder {
+    fn signed_filled(&self) -> f64 {
+        match self.side {
+            OrderSide::Buy => self.filled,
+            OrderSide::Sell => -self.filled,
+        }
+    }
+}
+
+/// Hedge to send on the hedge leg
+#[derive(Debug, Clone)]
+pub struct HedgeRequest {
+    pub quote_order_id: Option<u64>,
+    pub side: OrderSide,
+    pub amount: f64,
+    pub attempt: u32,
+}
+
+/// Linked orders of a two-leg spread
+///
+/// Every fill on the quoted leg is hedged right away with an opposite order of
+/// `hedge_ratio` times its size on the hedge leg, rounded to the hedge instrument's
+/// amount step. What the rounding leaves over is carried to the next fill's hedge, so
+/// fills too small for a hedge of their own add up until they make one. The quoted-leg
+/// amount not offset by hedge fills is the leg risk; while it exceeds `max_leg_risk`
+/// quoting is pulled.
+#[derive(Debug, Clone)]
+pub struct SpreadLegs {
+    pub hedge_instrument: String,
+    hedge_ratio: f64,
+    max_leg_risk: f64,
+
+    /// Amount step of the hedge instrument, 0 until its instrument info is known
+    hedge_step: f64,
+
+    /// Hedge-leg amount owed but left out of the hedges sent so far, positive to buy
+    carried: f64,
+
+    /// Net quoted-leg fills since the spread started, positive when bought
+    quoted: f64,
+
+    /// Net hedge fills of closed hedge orders, positive when bought
+    hedged: f64,
+
+    /// Hedge orders still working, by client order id
+    linked: BTreeMap<u64, LinkedOrder>,
+
+    /// Whether the leg risk was over the limit at the last check, to log transitions only
+    breached: bool,
+}
+
+impl SpreadLegs {
+    pub fn new(hedge_instrument: &str, hedge_ratio: f64, max_leg_risk: f64) -> Result<Self> {
+        if hedge_instrument.is_empty() {
+            return Err(anyhow!("Spread needs a hedge instrument"));
+        }
+        if !hedge_ratio.is_finite() || hedge_ratio <= 0.0 {
+            return Err(anyhow!("hedge_ratio must be positive: {}", hedge_ratio));
+        }
+        if !max_leg_risk.is_finite() || max_leg_risk < 0.0 {
+            return Err(anyhow!("max_leg_risk must not be negative: {}", max_leg_risk));
+        }
+        Ok(Self {
+            hedge_instrument: hedge_instrument.to_string(),
+            hedge_ratio,
+            max_leg_risk,
+            hedge_step: 0.0,
+            carried: 0.0,
+            quoted: 0.0,
+            hedged: 0.0,
+            linked: BTreeMap::new(),
+            breached: false,
+        })
+    }
+
+    pub fn from_config(spread: &SpreadConfig) -> Result<Self> {
+        Self::new(&spread.hedge_instrument, spread.hedge_ratio, spread.max_leg_risk)
+    }
+
+    /// Round hedges to the hedge instrument's amount step; hedges below one step aren't sent
+    pub fn set_hedge_step(&mut self, step: f64) {
+        if step.is_finite() && step > 0.0 {
+            self.hedge_step = step;
+        }
+    }
+
+    /// `amount` rounded to the hedge instrument's amount step
+    fn round_to_step(&self, amount: f64) -> f64 {
+        match self.hedge_step {
+            step if step > 0.0 => step * (amount / step).round(),
+            _ => amount,
+        }
+    }
+
+    /// Record a fill on the quoted leg, returning the hedge it calls for
+    ///
+    /// None when the fill, with what earlier fills left over, rounds to less than one
+    /// amount step of the hedge instrument.
+    pub fn on_quote_fill(&mut self, quote_order_id: Option<u64>, side: &OrderSide, amount: f64) -> Option<HedgeRequest> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return None;
+        }
+        let quoted = match side {
+            OrderSide::Buy => amount,
+            OrderSide::Sell => -amount,
+        };
+        self.quoted += quoted;
+        let owed = self.carried - quoted * self.hedge_ratio;
+        let hedge = self.round_to_step(owed.abs());
+        if hedge <= 1e-9 || hedge < self.hedge_step - 1e-9 {
+            self.carried = owed;
+            return None;
+        }
+        let hedge_side = if owed > 0.0 { OrderSide::Buy } else { OrderSide::Sell };
+        self.carried = owed - hedge.copysign(owed);
+        Some(HedgeRequest { quote_order_id, side: hedge_side, amount: hedge, attempt: 1 })
+    }
+
+    /// Record a hedge order sent with `client_order_id`
+    pub fn hedge_sent(&mut self, client_order_id: u64, hedge: &HedgeRequest) {
+        self.linked.insert(client_order_id, LinkedOrder {
+            quote_order_id: hedge.quote_order_id,
+            side: hedge.side.clone(),
+            amount: hedge.amount,
+            filled: 0.0,
+            attempt: hedge.attempt,
+        });
+    }
+
+    /// Whether `client_order_id` is one of the working hedge orders
+    pub fn is_hedge(&self, client_order_id: u64) -> bool {
+        self.linked.contains_key(&client_order_id)
+    }
+
+    /// Record the exchange's report of a hedge order
+    ///
+    /// When the order closed short of its amount, the retry for the remainder is returned
+    /// until `HEDGE_MAX_ATTEMPTS` is reached.
+    pub fn on_hedge_update(&mut self, client_order_id: u64, filled: f64, open: bool) -> Option<HedgeRequest> {
+        let order = self.linked.get_mut(&client_order_id)?;
+        order.filled = filled;
+        if open {
+            return None;
+        }
+        self.close(client_order_id)
+    }
+
+    /// The hedge order couldn't be sent or was rejected; returns the retry if attempts remain
+    pub fn hedge_failed(&mut self, client_order_id: u64) -> Option<HedgeRequest> {
+        self.close(client_order_id)
+    }
+
+    /// Fold a closed hedge's fills into the hedged amount and retry its remainder
+    fn close(&mut self, client_order_id: u64) -> Option<HedgeRequest> {
+        let order = self.linked.remove(&client_order_id)?;
+        self.hedged += order.signed_filled();
+        let remaining = self.round_to_step(order.amount - order.filled);
+        if remaining <= 1e-9 || remaining < self.hedge_step - 1e-9 {
+            return None;
+        }
+        if order.attempt >= HEDGE_MAX_ATTEMPTS {
+            warn!("Hedge {} on {} left {} unfilled after {} attempts", client_order_id, self.hedge_instrument, remaining, order.attempt);
+            return None;
+        }
+        Some(HedgeRequest {
+            quote_order_id: order.quote_order_id,
+            side: order.side.clone(),
+            amount: remaining,
+            attempt: order.attempt + 1,
+        })
+    }
+
+    /// Quoted-leg amount not offset by hedge fills, positive when net long
+    pub fn leg_risk(&self) -> f64 {
+        let working: f64 = self.linked.values().map(LinkedOrder::signed_filled).sum();
+        self.quoted + (self.hedged + working) / self.hedge_ratio
+    }
+
+    /// Whether the leg risk exceeds the limit, logging when that changes
+    pub fn check_leg_risk(&mut self) -> bool {
+        let leg_risk = self.leg_risk();
+        let breached = leg_risk.abs() > self.max_leg_risk + 1e-9;
+        if breached != self.breached {
+            if breached {
+                warn!("Leg risk {} beyond {}, pulling quotes until {} is hedged", leg_risk, self.max_leg_risk, self.hedge_instrument);
+            } else {
+                info!("Leg risk {} back within {}", leg_risk, self.max_leg_risk);
+            }
+            self.breached = breached;
+        }
+        breached
+    }
+
+    /// Hedge orders still working at the exchange
+    pub fn open_hedges(&self) -> impl Iterator<Item = (&u64, &LinkedOrder)> {
+        self.linked.iter()
+    }
+
+    /// Working hedge order sent with `client_order_id`
+    pub fn linked(&self, client_order_id: u64) -> Option<&LinkedOrder> {
+        self.linked.get(&client_order_id)
+    }
+}
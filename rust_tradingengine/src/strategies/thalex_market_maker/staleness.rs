@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::debug;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+
+use crate::domain::clock;
+use crate::infrastructure::metrics;
+
+/// Consumed control and signal messages skipped as stale
+pub const METRIC_STALE_MESSAGES: &str = "consumer.stale_messages";
+
+/// Gauge of the records behind the last one read from `topic`
+pub fn lag_metric(topic: &str) -> String {
+    format!("consumer.lag.{}", topic)
+}
+
+/// Gauge of the age of the last record read from `topic` (milliseconds)
+pub fn age_metric(topic: &str) -> String {
+    format!("consumer.age_ms.{}", topic)
+}
+
+/// Why a consumed message is or isn't acted on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Staleness {
+    Fresh,
+    /// Produced longer ago than the topic's maximum age
+    TooOld { age_sec: f64 },
+    /// Its own deadline passed
+    Expired { expires_at: f64 },
+    /// More records than the topic's maximum lag were produced after it
+    Behind { lag: u64 },
+}
+
+impl Staleness {
+    pub fn is_fresh(&self) -> bool {
+        matches!(self, Staleness::Fresh)
+    }
+}
+
+/// Decides whether a message consumed from a control or signal topic is still current
+///
+/// A delayed consumer must not act on instructions meant for an earlier moment, so
+/// messages older than `max_age_sec`, past their own deadline, or with more than
+/// `max_lag` records behind them are skipped. A `max_lag` of 0 leaves lag unchecked.
+#[derive(Debug, Clone)]
+pub struct StalenessGuard {
+    topic: String,
+    max_age_sec: f64,
+    max_lag: u64,
+}
+
+impl StalenessGuard {
+    pub fn new(topic: &str, max_age_sec: f64, max_lag: u64) -> Self {
+        Self { topic: topic.to_string(), max_age_sec, max_lag }
+    }
+
+    /// Judge a message produced at `produced_at` with `lag` records behind it, at `now`
+    ///
+    /// Messages without a timestamp or a known lag pass those checks. The age and lag
+    /// are published as gauges and stale messages counted.
+    pub fn check(&self, produced_at: Option<f64>, expires_at: Option<f64>, lag: Option<u64>, now: f64) -> Staleness {
+        if let Some(lag) = lag {
+            metrics::global().set_gauge(&lag_metric(&self.topic), lag as f64);
+        }
+        let age_sec = produced_at.map(|produced_at| (now - produced_at).max(0.0));
+        if let Some(age_sec) = age_sec {
+            metrics::global().set_gauge(&age_metric(&self.topic), age_sec * 1000.0);
+        }
+
+        let staleness = match (age_sec, expires_at, lag) {
+            (_, Some(expires_at), _) if now > expires_at => Staleness::Expired { expires_at },
+            (Some(age_sec), _, _) if age_sec > self.max_age_sec => Staleness::TooOld { age_sec },
+            (_, _, Some(lag)) if self.max_lag > 0 && lag > self.max_lag => Staleness::Behind { lag },
+            _ => Staleness::Fresh,
+        };
+        if !staleness.is_fresh() {
+            metrics::global().incr(METRIC_STALE_MESSAGES, 1);
+        }
+        staleness
+    }
+}
+
+/// Records behind `offset` in a partition whose next offset to be written is `high_watermark`
+pub fn consumer_lag(high_watermark: i64, offset: i64) -> u64 {
+    (high_watermark - offset - 1).max(0) as u64
+}
+
+/// High watermarks of consumed partitions, fetched from the broker at most once per `refresh`
+///
+/// Fetching is a broker round trip, so a watermark may be up to `refresh` old and the lag
+/// derived from it an underestimate.
+pub struct WatermarkCache {
+    refresh: Duration,
+    high: HashMap<(String, i32), (i64, Instant)>,
+}
+
+impl WatermarkCache {
+    pub fn new(refresh: Duration) -> Self {
+        Self { refresh, high: HashMap::new() }
+    }
+
+    /// Lag behind the record at `offset` of `topic`/`partition`; None while the watermark can't be fetched
+    pub async fn lag(&mut self, consumer: &Arc<StreamConsumer>, topic: &str, partition: i32, offset: i64) -> Option<u64> {
+        let key = (topic.to_string(), partition);
+        let cached = self.high.get(&key).filter(|(_, fetched)| clock::instant().saturating_duration_since(*fetched) < self.refresh).map(|(high, _)| *high);
+        let high = match cached {
+            Some(high) => high,
+            None => {
+                let consumer = consumer.clone();
+                let (topic, refresh) = (topic.to_string(), self.refresh);
+                let fetched = tokio::task::spawn_blocking(move || consumer.fetch_watermarks(&topic, partition, refresh)).await;
+                match fetched {
+                    Ok(Ok((_, high))) => {
+                        self.high.insert(key, (high, clock::instant()));
+                        high
+                    }
+                    Ok(Err(e)) => {
+                        debug!("Failed to fetch the watermarks of {}/{}: {}", key.0, partition, e);
+                        return None;
+                    }
+                    Err(_) => return None,
+                }
+            }
+        };
+        // The cached watermark may predate the record
+        Some(consumer_lag(high.max(offset + 1), offset))
+    }
+}
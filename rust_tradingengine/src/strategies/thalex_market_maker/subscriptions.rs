@@ -0,0 +1,109 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::infrastructure::exchange::thalex::channel::Channel;
+
+/// Where a channel's subscription stands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubscriptionState {
+    /// Subscribe call sent, no answer yet; `attempts` earlier calls were rejected
+    Pending { attempts: u32 },
+    Confirmed,
+    /// Rejected `attempts` times in a row; subscribed again from `retry_at`
+    Failed { attempts: u32, retry_at: f64 },
+}
+
+/// Tracks the subscription of each channel so rejected ones can be retried
+///
+/// A subscribe call can fail as a whole or leave out some of the channels it named.
+/// Failed channels are retried with exponential backoff, and quoting waits until the
+/// required channels are confirmed.
+#[derive(Debug)]
+pub struct SubscriptionTracker {
+    states: HashMap<Channel, SubscriptionState>,
+    required: HashSet<Channel>,
+    initial_backoff_sec: f64,
+    max_backoff_sec: f64,
+}
+
+impl SubscriptionTracker {
+    pub fn new(initial_backoff_sec: f64, max_backoff_sec: f64) -> Self {
+        Self {
+            states: HashMap::new(),
+            required: HashSet::new(),
+            initial_backoff_sec,
+            max_backoff_sec,
+        }
+    }
+
+    /// Note that a subscribe call for `channels` was sent
+    pub fn requested(&mut self, channels: &[Channel]) {
+        for channel in channels {
+            let attempts = match self.states.get(channel) {
+                Some(SubscriptionState::Failed { attempts, .. }) => *attempts,
+                _ => 0,
+            };
+            self.states.insert(channel.clone(), SubscriptionState::Pending { attempts });
+        }
+    }
+
+    pub fn confirmed(&mut self, channels: &[Channel]) {
+        for channel in channels {
+            self.states.insert(channel.clone(), SubscriptionState::Confirmed);
+        }
+    }
+
+    /// Schedule a retry of channels whose subscription was rejected at `now`
+    ///
+    /// The backoff doubles with each consecutive rejection, up to the maximum.
+    pub fn failed(&mut self, channels: &[Channel], now: f64) {
+        for channel in channels {
+            let attempts = match self.states.get(channel) {
+                Some(SubscriptionState::Pending { attempts }) | Some(SubscriptionState::Failed { attempts, .. }) => attempts + 1,
+                _ => 1,
+            };
+            let backoff = (self.initial_backoff_sec * 2f64.powi(attempts as i32 - 1)).min(self.max_backoff_sec);
+            self.states.insert(channel.clone(), SubscriptionState::Failed { attempts, retry_at: now + backoff });
+        }
+    }
+
+    /// Forget channels that were unsubscribed
+    pub fn removed(&mut self, channels: &[Channel]) {
+        for channel in channels {
+            self.states.remove(channel);
+        }
+    }
+
+    /// Channels quoting waits for
+    pub fn require(&mut self, channels: &[Channel]) {
+        self.required.extend(channels.iter().cloned());
+    }
+
+    pub fn state(&self, channel: &Channel) -> Option<SubscriptionState> {
+        self.states.get(channel).copied()
+    }
+
+    /// Failed channels whose retry is due at `now`
+    pub fn due(&self, now: f64) -> Vec<Channel> {
+        let mut due: Vec<Channel> = self.states.iter()
+            .filter(|(_, state)| matches!(state, SubscriptionState::Failed { retry_at, .. } if *retry_at <= now))
+            .map(|(channel, _)| channel.clone())
+            .collect();
+        due.sort_by_key(|c| c.to_string());
+        due
+    }
+
+    /// Required channels not confirmed yet
+    pub fn unconfirmed(&self) -> Vec<Channel> {
+        let mut channels: Vec<Channel> = self.required.iter()
+            .filter(|channel| self.state(channel) != Some(SubscriptionState::Confirmed))
+            .cloned()
+            .collect();
+        channels.sort_by_key(|c| c.to_string());
+        channels
+    }
+
+    /// Whether every required channel is confirmed
+    pub fn is_ready(&self) -> bool {
+        self.required.iter().all(|channel| self.state(channel) == Some(SubscriptionState::Confirmed))
+    }
+}
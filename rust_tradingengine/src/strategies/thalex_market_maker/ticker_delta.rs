@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use crate::domain::model::ticker::Ticker;
+use crate::domain::model::ticker_delta::{TickerDelta, TickerDeltaKind};
+use crate::infrastructure::exchange::thalex::channel::Channel;
+
+struct TickerState {
+    sequence: i64,
+    /// Time of the last snapshot, None while the next record must be one
+    last_snapshot: Option<f64>,
+    values: [f64; Ticker::NUMERIC_FIELDS.len()],
+}
+
+/// Turns ticker notifications into periodic full snapshots and changed-field deltas
+///
+/// Slow-moving fields such as the 24h statistics are only republished when they change,
+/// or with the next snapshot. State is kept per ticker channel, so each channel's
+/// records form one sequence.
+pub struct TickerDeltaRecorder {
+    snapshot_interval_sec: f64,
+    tickers: HashMap<Channel, TickerState>,
+}
+
+impl TickerDeltaRecorder {
+    pub fn new(snapshot_interval_sec: f64) -> Self {
+        Self {
+            snapshot_interval_sec,
+            tickers: HashMap::new(),
+        }
+    }
+
+    /// Record the next update of every channel as a snapshot; sequences carry on
+    pub fn clear(&mut self) {
+        for state in self.tickers.values_mut() {
+            state.last_snapshot = None;
+        }
+    }
+
+    /// Record the next update of a channel as a snapshot, after some of its records were lost
+    pub fn resync(&mut self, channel: &Channel) {
+        if let Some(state) = self.tickers.get_mut(channel) {
+            state.last_snapshot = None;
+        }
+    }
+
+    /// Record a ticker of `channel` received at `now`; None when no field changed
+    pub fn on_ticker(&mut self, channel: &Channel, ticker: &Ticker, now: f64) -> Option<TickerDelta> {
+        let values = ticker.numeric_values();
+        let (kind, changes) = match self.tickers.get(channel) {
+            Some(TickerState { last_snapshot: Some(at), values: previous, .. }) if now - at < self.snapshot_interval_sec => {
+                let changes: Vec<(String, f64)> = Ticker::NUMERIC_FIELDS.iter()
+                    .zip(values.iter().zip(previous))
+                    .filter(|(_, (new, old))| new.to_bits() != old.to_bits())
+                    .map(|(name, (new, _))| (name.to_string(), *new))
+                    .collect();
+                if changes.is_empty() {
+                    return None;
+                }
+                (TickerDeltaKind::Delta, changes)
+            }
+            _ => {
+                let changes = Ticker::NUMERIC_FIELDS.iter()
+                    .zip(values)
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect();
+                (TickerDeltaKind::Snapshot, changes)
+            }
+        };
+
+        let state = self.tickers.entry(channel.clone()).or_insert(TickerState {
+            sequence: 0,
+            last_snapshot: None,
+            values,
+        });
+        state.sequence += 1;
+        state.values = values;
+        if kind == TickerDeltaKind::Snapshot {
+            state.last_snapshot = Some(now);
+        }
+
+        Some(TickerDelta {
+            instrument_name: ticker.instrument_name.clone(),
+            kind,
+            sequence: state.sequence,
+            changes,
+            timestamp: now,
+            processing_timestamp: ticker.processing_timestamp,
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use crate::domain::model::ticker::Ticker;
+
+/// Keeps the latest ticker per instrument for the downsampled ticker topic
+///
+/// Every update overwrites the previous one; `take` hands out the instruments
+/// that changed since the last call, so a quiet instrument is not republished.
+#[derive(Default)]
+pub struct TickerSampler {
+    latest: HashMap<String, Ticker>,
+}
+
+impl TickerSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a ticker update, replacing any unsent one for the same instrument
+    pub fn record(&mut self, ticker: &Ticker) {
+        self.latest.insert(ticker.instrument_name.clone(), ticker.clone());
+    }
+
+    /// Latest tickers recorded since the previous call, ordered by instrument name
+    pub fn take(&mut self) -> Vec<Ticker> {
+        let mut tickers: Vec<Ticker> = self.latest.drain().map(|(_, ticker)| ticker).collect();
+        tickers.sort_by(|a, b| a.instrument_name.cmp(&b.instrument_name));
+        tickers
+    }
+
+    /// Drop unsent updates
+    pub fn clear(&mut self) {
+        self.latest.clear();
+    }
+}
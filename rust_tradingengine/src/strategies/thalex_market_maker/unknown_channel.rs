@@ -0,0 +1,66 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use log::error;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::infrastructure::exchange::thalex::channel::Channel;
+use crate::infrastructure::kafka::KafkaProducer;
+use crate::infrastructure::runtime;
+use crate::domain::clock;
+
+use super::plugin::NotificationPlugin;
+
+/// Publishes notifications on channels without typed support to a raw JSON topic
+///
+/// Keeps the data of channels the exchange adds before the bot learns to parse them.
+pub struct RawChannelPublisher {
+    producer: Arc<KafkaProducer>,
+    topic: String,
+}
+
+impl RawChannelPublisher {
+    pub fn new(producer: Arc<KafkaProducer>, topic: String) -> Self {
+        Self { producer, topic }
+    }
+
+    /// Record published for a notification received at `received_at` (seconds)
+    pub fn record(channel_name: &str, notification: &Value, received_at: f64) -> Value {
+        json!({
+            "channel_name": channel_name,
+            "received_at": received_at,
+            "notification": notification,
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationPlugin for RawChannelPublisher {
+    fn name(&self) -> &str {
+        "raw_channel_publisher"
+    }
+
+    fn handles(&self, _channel: &Channel) -> bool {
+        false
+    }
+
+    async fn on_notification(&self, _channel: &Channel, _notification: &Value) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_unknown_channel(&self, channel_name: &str, notification: &Value) -> Result<()> {
+        let now = clock::now_secs();
+        let record = Self::record(channel_name, notification, now);
+        
+        // Spawn the send so routing doesn't wait for delivery
+        let producer = self.producer.clone();
+        let topic = self.topic.clone();
+        let key = channel_name.to_string();
+        runtime::spawn_io(async move {
+            if let Err(e) = producer.send_json(&topic, &key, &record).await {
+                error!("Failed to send unknown channel notification to Kafka: {:?}", e);
+            }
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::NaiveDate;
+
+use crate::domain::model::uptime::QuoteUptime;
+
+use super::daily_stats::{day_of, DailyStats};
+
+/// Integrates quote presence over time and reports it per UTC day
+///
+/// Each sample says whether the bot was compliant (two-sided within the allowed
+/// distance of the mark) at that moment; the time until the next sample is credited
+/// to that state. Time before the first sample of a session is not counted.
+pub struct UptimeTracker {
+    instrument_name: String,
+    max_distance_ticks: f64,
+
+    /// Day currently being accumulated
+    day: Option<NaiveDate>,
+    period_start: f64,
+    observed_secs: f64,
+    compliant_secs: f64,
+
+    /// Previous sample as (time, compliant)
+    last: Option<(f64, bool)>,
+}
+
+impl UptimeTracker {
+    pub fn new(instrument_name: &str, max_distance_ticks: f64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            max_distance_ticks,
+            day: None,
+            period_start: 0.0,
+            observed_secs: 0.0,
+            compliant_secs: 0.0,
+            last: None,
+        }
+    }
+
+    /// Record the compliance state at `now`; returns the previous day's statistics
+    /// when `now` falls on a new UTC day
+    pub fn sample(&mut self, now: f64, compliant: bool) -> Option<QuoteUptime> {
+        let today = day_of(now);
+        let mut completed = None;
+
+        if let Some((last_time, last_compliant)) = self.last {
+            if self.day != Some(today) {
+                // Credit time up to midnight to the old day, then start the new one
+                let midnight = today.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp() as f64;
+                self.accumulate(midnight - last_time, last_compliant);
+                completed = Some(self.stats(midnight));
+                self.reset(today, midnight);
+                self.accumulate(now - midnight, last_compliant);
+            } else {
+                self.accumulate(now - last_time, last_compliant);
+            }
+        } else if self.day != Some(today) {
+            self.reset(today, now);
+        }
+
+        self.last = Some((now, compliant));
+        completed
+    }
+
+    /// Continue accumulating a day saved before a restart
+    ///
+    /// Must be called before the first sample; the downtime itself isn't counted.
+    /// Statistics of another day than the first sample's are discarded.
+    pub fn resume(&mut self, saved: &DailyStats) {
+        let Ok(day) = saved.date.parse::<NaiveDate>() else {
+            return;
+        };
+        if saved.uptime_observed_secs <= 0.0 {
+            return;
+        }
+        self.day = Some(day);
+        self.period_start = saved.uptime_period_start;
+        self.observed_secs = saved.uptime_observed_secs;
+        self.compliant_secs = saved.uptime_compliant_secs;
+    }
+
+    /// Statistics of the current day so far
+    pub fn current(&self, now: f64) -> QuoteUptime {
+        self.stats(now)
+    }
+
+    fn accumulate(&mut self, secs: f64, compliant: bool) {
+        let secs = secs.max(0.0);
+        self.observed_secs += secs;
+        if compliant {
+            self.compliant_secs += secs;
+        }
+    }
+
+    fn reset(&mut self, day: NaiveDate, start: f64) {
+        self.day = Some(day);
+        self.period_start = start;
+        self.observed_secs = 0.0;
+        self.compliant_secs = 0.0;
+    }
+
+    fn stats(&self, period_end: f64) -> QuoteUptime {
+        let uptime_pct = if self.observed_secs > 0.0 {
+            100.0 * self.compliant_secs / self.observed_secs
+        } else {
+            0.0
+        };
+        QuoteUptime {
+            instrument_name: self.instrument_name.clone(),
+            date: self.day.map(|d| d.to_string()).unwrap_or_default(),
+            period_start: self.period_start,
+            period_end,
+            observed_secs: self.observed_secs,
+            compliant_secs: self.compliant_secs,
+            uptime_pct,
+            max_distance_ticks: self.max_distance_ticks,
+            processing_timestamp: None,
+        }
+    }
+}
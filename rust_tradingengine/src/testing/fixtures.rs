@@ -0,0 +1,336 @@
+//! Realistic domain records and exchange frames for tests and benchmarks
+//!
+//! The plain functions return one fixed record, which callers adjust with struct update
+//! syntax (`Ticker { mark_price: 1.0, ..fixtures::ticker("BTC-PERPETUAL") }`). `Fixtures`
+//! draws varied but reproducible records from a seed.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+
+use crate::domain::enums::{MakerTaker, OrderSide, OrderStatus, OrderType, TimeInForce};
+use crate::domain::model::account_event::{AccountEvent, AccountEventType};
+use crate::domain::model::book::{BookLevelUpdate, BookSide, BookUpdateKind};
+use crate::domain::model::order::{Order, OrderState};
+use crate::domain::model::ticker::Ticker;
+use crate::domain::model::trade::Trade;
+use crate::domain::model::trade_correction::{CorrectionKind, TradeCorrection};
+
+/// Timestamp the fixtures are stamped with: 2026-10-15 00:00 UTC
+pub const T0: f64 = 1792022400.0;
+
+/// Instrument the fixtures trade unless told otherwise
+pub const INSTRUMENT: &str = "BTC-PERPETUAL";
+
+/// Mark price the fixtures are built around
+pub const MARK: f64 = 65000.0;
+
+/// Ticker one tick wide around `MARK`
+pub fn ticker(instrument_name: &str) -> Ticker {
+    Ticker {
+        instrument_name: instrument_name.to_string(),
+        mark_price: MARK,
+        mark_timestamp: T0,
+        best_bid_price: MARK - 1.0,
+        best_bid_amount: 1.5,
+        best_ask_price: MARK + 1.0,
+        best_ask_amount: 2.5,
+        last_price: MARK,
+        delta: 1.0,
+        volume_24h: 1234.5,
+        value_24h: 80_000_000.0,
+        low_price_24h: MARK - 1000.0,
+        high_price_24h: MARK + 1000.0,
+        change_24h: 0.01,
+        index_price: MARK,
+        forward: 0.0,
+        funding_mark: 0.0001,
+        funding_rate: 0.0001,
+        collar_low: MARK - 1000.0,
+        collar_high: MARK + 1000.0,
+        realised_funding_24h: 0.0,
+        average_funding_rate_24h: 0.0,
+        open_interest: 100.0,
+        processing_timestamp: Some(T0),
+    }
+}
+
+/// Limit order resting in the book, as the exchange reports it
+pub fn order(client_order_id: u64, direction: OrderSide, price: f64, amount: f64) -> Order {
+    Order {
+        order_id: format!("{:08}", client_order_id),
+        client_order_id: Some(client_order_id),
+        instrument_name: INSTRUMENT.to_string(),
+        direction,
+        price: Some(price),
+        amount,
+        filled_amount: 0.0,
+        remaining_amount: amount,
+        status: OrderStatus::Open,
+        order_type: OrderType::Limit,
+        time_in_force: TimeInForce::GTC,
+        change_reason: "insert".to_string(),
+        delete_reason: None,
+        insert_reason: Some("client_request".to_string()),
+        create_time: T0,
+        persistent: false,
+        processing_timestamp: Some(T0),
+        variant_id: None,
+        state: OrderState::Acknowledged,
+    }
+}
+
+/// Order update published on the `ack` topic: a sell half filled
+pub fn ack() -> Order {
+    Order {
+        filled_amount: 0.05,
+        remaining_amount: 0.05,
+        status: OrderStatus::PartiallyFilled,
+        change_reason: "fill".to_string(),
+        ..order(67890, OrderSide::Sell, MARK, 0.1)
+    }
+}
+
+/// Maker fill of one of the bot's orders
+pub fn trade(client_order_id: u64, price: f64, amount: f64) -> Trade {
+    Trade {
+        trade_id: format!("T{:08}", client_order_id),
+        order_id: format!("{:08}", client_order_id),
+        client_order_id: Some(client_order_id),
+        instrument_name: INSTRUMENT.to_string(),
+        price,
+        amount,
+        maker_taker: Some(MakerTaker::Maker),
+        time: T0,
+        processing_timestamp: Some(T0),
+        variant_id: None,
+    }
+}
+
+/// Account notification of `event_type` moving 0.25 BTC
+pub fn account_event(event_type: AccountEventType) -> AccountEvent {
+    AccountEvent {
+        event_type,
+        timestamp: T0,
+        currency: Some("BTC".to_string()),
+        amount: Some(0.25),
+        instrument_name: None,
+        message: Some(format!("{} of 0.25 BTC", event_type.as_str())),
+        processing_timestamp: Some(T0),
+    }
+}
+
+/// Bust of the fill `trade(client_order_id, ..)` of a buy
+pub fn trade_bust(client_order_id: u64, price: f64, amount: f64) -> TradeCorrection {
+    TradeCorrection {
+        kind: CorrectionKind::Bust,
+        trade_id: format!("T{:08}", client_order_id),
+        order_id: Some(format!("{:08}", client_order_id)),
+        instrument_name: Some(INSTRUMENT.to_string()),
+        direction: Some(OrderSide::Buy),
+        original_price: Some(price),
+        original_amount: Some(amount),
+        corrected_price: None,
+        corrected_amount: None,
+        message: None,
+        timestamp: T0,
+        processing_timestamp: Some(T0),
+    }
+}
+
+/// Book snapshot with `depth` levels a side, one tick apart around `mid`
+pub fn book_snapshot(instrument_name: &str, mid: f64, depth: usize, sequence: i64) -> Vec<BookLevelUpdate> {
+    let level = |side: BookSide, price: f64, i: usize| BookLevelUpdate {
+        instrument_name: instrument_name.to_string(),
+        kind: BookUpdateKind::Snapshot,
+        sequence,
+        side,
+        price,
+        amount: 0.5 * (i + 1) as f64,
+        exchange_time: Some(T0),
+        timestamp: T0,
+        processing_timestamp: None,
+    };
+    let bids = (0..depth).map(|i| level(BookSide::Bid, mid - 1.0 - i as f64, i));
+    let asks = (0..depth).map(|i| level(BookSide::Ask, mid + 1.0 + i as f64, i));
+    bids.chain(asks).collect()
+}
+
+/// `ticker.<instrument>.raw` notification carrying `ticker`
+pub fn ticker_notification(ticker: &Ticker) -> Value {
+    json!({
+        "channel_name": format!("ticker.{}.raw", ticker.instrument_name),
+        "notification": {
+            "mark_price": ticker.mark_price, "mark_timestamp": ticker.mark_timestamp,
+            "best_bid_price": ticker.best_bid_price, "best_bid_amount": ticker.best_bid_amount,
+            "best_ask_price": ticker.best_ask_price, "best_ask_amount": ticker.best_ask_amount,
+            "last_price": ticker.last_price, "delta": ticker.delta, "volume_24h": ticker.volume_24h,
+            "value_24h": ticker.value_24h, "low_price_24h": ticker.low_price_24h,
+            "high_price_24h": ticker.high_price_24h, "change_24h": ticker.change_24h,
+            "index": ticker.index_price, "forward": ticker.forward, "funding_mark": ticker.funding_mark,
+            "funding_rate": ticker.funding_rate, "collar_low": ticker.collar_low,
+            "collar_high": ticker.collar_high, "realised_funding_24h": ticker.realised_funding_24h,
+            "average_funding_rate_24h": ticker.average_funding_rate_24h,
+            "open_interest": ticker.open_interest,
+        },
+    })
+}
+
+/// Order status as the exchange reports it, in `session.orders` and RPC results
+pub fn order_json(order: &Order) -> Value {
+    json!({
+        "order_id": order.order_id, "client_order_id": order.client_order_id,
+        "instrument_name": order.instrument_name, "direction": order.direction,
+        "price": order.price, "amount": order.amount, "filled_amount": order.filled_amount,
+        "remaining_amount": order.remaining_amount, "status": order.status,
+        "order_type": order.order_type, "time_in_force": order.time_in_force,
+        "change_reason": order.change_reason, "delete_reason": order.delete_reason,
+        "insert_reason": order.insert_reason, "create_time": order.create_time,
+        "persistent": order.persistent,
+    })
+}
+
+/// Order status listing `fills`, each a fill of the order
+pub fn order_with_fills_json(order: &Order, fills: &[Trade]) -> Value {
+    let mut status = order_json(order);
+    status["fills"] = fills.iter().map(|fill| json!({
+        "trade_id": fill.trade_id, "price": fill.price, "amount": fill.amount,
+        "maker_taker": fill.maker_taker, "time": fill.time,
+    })).collect();
+    status
+}
+
+/// Trade as the exchange reports it in `account.trade_history`
+pub fn trade_json(trade: &Trade) -> Value {
+    json!({
+        "trade_id": trade.trade_id, "order_id": trade.order_id,
+        "client_order_id": trade.client_order_id, "instrument_name": trade.instrument_name,
+        "price": trade.price, "amount": trade.amount, "maker_taker": trade.maker_taker,
+        "time": trade.time,
+    })
+}
+
+/// Account notification reporting `event`
+pub fn account_event_json(event: &AccountEvent) -> Value {
+    json!({
+        "category": event.event_type, "create_time": event.timestamp, "currency": event.currency,
+        "amount": event.amount, "instrument_name": event.instrument_name, "message": event.message,
+    })
+}
+
+/// Account notification busting or correcting a trade
+pub fn trade_correction_json(correction: &TradeCorrection) -> Value {
+    let category = match correction.kind {
+        CorrectionKind::Bust => "trade_bust",
+        CorrectionKind::Correction => "trade_correction",
+    };
+    json!({
+        "category": category, "create_time": correction.timestamp, "trade_id": correction.trade_id,
+        "order_id": correction.order_id, "instrument_name": correction.instrument_name,
+        "direction": correction.direction, "price": correction.original_price,
+        "amount": correction.original_amount, "new_price": correction.corrected_price,
+        "new_amount": correction.corrected_amount, "message": correction.message,
+    })
+}
+
+/// Book notification with `bids` and `asks` as (price, amount) levels, best first
+pub fn book_json(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Value {
+    let levels = |levels: &[(f64, f64)]| levels.iter().map(|(price, amount)| json!([price, amount])).collect::<Vec<_>>();
+    json!({"bids": levels(bids), "asks": levels(asks)})
+}
+
+/// Notification frame of `channel_name`
+pub fn notification(channel_name: &str, notification: Value) -> Value {
+    json!({"channel_name": channel_name, "notification": notification})
+}
+
+/// `session.orders` notification reporting `orders`
+pub fn orders_notification(orders: &[Order]) -> Value {
+    notification("session.orders", orders.iter().map(order_json).collect())
+}
+
+/// `account.trade_history` notification reporting `trades`
+pub fn trades_notification(trades: &[Trade]) -> Value {
+    notification("account.trade_history", trades.iter().map(trade_json).collect())
+}
+
+/// `recent_trades.<instrument>.all` notification reporting `trades` as the market saw them
+pub fn recent_trades_notification(instrument_name: &str, trades: &[Trade]) -> Value {
+    let trades: Vec<Value> = trades.iter()
+        .map(|trade| json!({
+            "trade_id": trade.trade_id, "instrument_name": trade.instrument_name,
+            "price": trade.price, "amount": trade.amount, "time": trade.time,
+        }))
+        .collect();
+    notification(&format!("recent_trades.{}.all", instrument_name), trades.into())
+}
+
+/// Successful RPC response to request `id`
+pub fn rpc_result(id: u64, result: Value) -> Value {
+    json!({"id": id, "result": result})
+}
+
+/// Error response to request `id`
+pub fn rpc_error(id: u64, code: i64, message: &str) -> Value {
+    json!({"id": id, "error": {"code": code, "message": message}})
+}
+
+/// Seeded source of varied records; the same seed gives the same sequence
+pub struct Fixtures {
+    rng: StdRng,
+    next_id: u64,
+}
+
+impl Fixtures {
+    pub fn seeded(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed), next_id: 1 }
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn side(&mut self) -> OrderSide {
+        if self.rng.gen_bool(0.5) { OrderSide::Buy } else { OrderSide::Sell }
+    }
+
+    /// Ticker with the mark within 1% of `MARK` and a spread of one to five ticks
+    pub fn ticker(&mut self, instrument_name: &str) -> Ticker {
+        let mark = (MARK * self.rng.gen_range(0.99..1.01)).round();
+        let half_spread = self.rng.gen_range(1..=5) as f64;
+        Ticker {
+            mark_price: mark,
+            best_bid_price: mark - half_spread,
+            best_bid_amount: self.rng.gen_range(0.1..5.0),
+            best_ask_price: mark + half_spread,
+            best_ask_amount: self.rng.gen_range(0.1..5.0),
+            last_price: mark,
+            index_price: mark + self.rng.gen_range(-10.0..10.0),
+            funding_rate: self.rng.gen_range(-0.0005..0.0005),
+            ..ticker(instrument_name)
+        }
+    }
+
+    /// Open order on a random side, up to ten ticks away from `MARK`
+    pub fn order(&mut self) -> Order {
+        let direction = self.side();
+        let offset = self.rng.gen_range(1..=10) as f64;
+        let price = match direction {
+            OrderSide::Buy => MARK - offset,
+            OrderSide::Sell => MARK + offset,
+        };
+        let amount = self.rng.gen_range(1..=10) as f64 / 10.0;
+        let client_order_id = self.next_id();
+        order(client_order_id, direction, price, amount)
+    }
+
+    /// Fill of part of a fresh order
+    pub fn trade(&mut self) -> Trade {
+        let order = self.order();
+        let amount = order.amount * self.rng.gen_range(1..=4) as f64 / 4.0;
+        let client_order_id = order.client_order_id.unwrap_or_default();
+        trade(client_order_id, order.price.unwrap_or(MARK), amount)
+    }
+}
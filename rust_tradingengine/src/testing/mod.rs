@@ -0,0 +1,3 @@
+//! Test data shared by the unit tests, the benchmarks and the sample publishers
+
+pub mod fixtures;
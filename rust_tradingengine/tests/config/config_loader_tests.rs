@@ -0,0 +1,388 @@
+use anyhow::Result;
+use serde_json::json;
+
+use cryptics_lab_bot::config_loader::{AppConfig, ConfigFormat, FairValueSource, LadderShape, LeverageTier};
+use cryptics_lab_bot::domain::model::exchange::Instrument;
+
+fn base_config() -> serde_json::Value {
+    json!({
+        "kafka": {
+            "bootstrap_servers": "localhost:9092",
+            "bootstrap_servers_internal": "broker0:29092",
+            "schema_registry_url": "http://localhost:8081",
+            "schema_registry_url_internal": "http://schema-registry:8081",
+            "connect_url": "http://localhost:8083",
+            "connect_url_internal": "http://kafka-connect:8083"
+        },
+        "topics": {
+            "ticker": "base.ticker",
+            "ack": "base.ack",
+            "trade": "base.trade",
+            "index": "base.index",
+            "base_name": "base"
+        },
+        "app": {
+            "rust_running_in_docker": false
+        },
+        "profiles": {
+            "prod": {
+                "app": { "network": "prod", "log_level": "warn" },
+                "topics": { "ticker": "prod.ticker" }
+            }
+        }
+    })
+}
+
+#[test]
+fn test_config_without_profile_uses_base() -> Result<()> {
+    let config = AppConfig::from_value(base_config(), None)?;
+    
+    assert_eq!(config.app.profile, None);
+    assert_eq!(config.app.network, "test");
+    assert_eq!(config.log_level(), log::LevelFilter::Info);
+    assert_eq!(config.topics.ticker, "base.ticker");
+    Ok(())
+}
+
+#[test]
+fn test_profile_layers_over_base() -> Result<()> {
+    let config = AppConfig::from_value(base_config(), Some("prod"))?;
+    
+    assert_eq!(config.app.profile.as_deref(), Some("prod"));
+    assert_eq!(config.app.network, "prod");
+    assert_eq!(config.log_level(), log::LevelFilter::Warn);
+    // Overridden key
+    assert_eq!(config.topics.ticker, "prod.ticker");
+    // Keys not in the profile keep their base values
+    assert_eq!(config.topics.ack, "base.ack");
+    assert!(!config.app.rust_running_in_docker);
+    Ok(())
+}
+
+#[test]
+fn test_profile_selected_in_document() -> Result<()> {
+    let mut raw = base_config();
+    raw["app"]["profile"] = json!("prod");
+    
+    let config = AppConfig::from_value(raw, None)?;
+    assert_eq!(config.app.network, "prod");
+    Ok(())
+}
+
+#[test]
+fn test_unknown_profile_is_an_error() {
+    assert!(AppConfig::from_value(base_config(), Some("staging")).is_err());
+}
+
+#[test]
+fn test_repository_config_loads() -> Result<()> {
+    let config = AppConfig::from_file("../config.toml")?;
+    assert!(config.app.profile.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_config_from_json_str() -> Result<()> {
+    let config = AppConfig::from_str(&base_config().to_string(), ConfigFormat::Json)?;
+    assert_eq!(config.topics.ticker, "base.ticker");
+    Ok(())
+}
+
+#[test]
+fn test_config_from_yaml_str() -> Result<()> {
+    let yaml = serde_yaml::to_string(&base_config())?;
+    let config = AppConfig::from_str(&yaml, ConfigFormat::Yaml)?;
+    assert_eq!(config.kafka.bootstrap_servers, "localhost:9092");
+    assert_eq!(config.topics.index, "base.index");
+    Ok(())
+}
+
+#[test]
+fn test_format_detected_from_extension() -> Result<()> {
+    assert_eq!(ConfigFormat::from_path("config.yml".as_ref()), Some(ConfigFormat::Yaml));
+    assert_eq!(ConfigFormat::from_path("config.JSON".as_ref()), Some(ConfigFormat::Json));
+    assert_eq!(ConfigFormat::from_path("config.toml".as_ref()), Some(ConfigFormat::Toml));
+    assert_eq!(ConfigFormat::from_path("config".as_ref()), None);
+    
+    let path = std::env::temp_dir().join(format!("cryptics_config_{}.yaml", std::process::id()));
+    std::fs::write(&path, serde_yaml::to_string(&base_config())?)?;
+    let config = AppConfig::from_file(&path);
+    std::fs::remove_file(&path)?;
+    assert_eq!(config?.topics.trade, "base.trade");
+    Ok(())
+}
+
+#[test]
+fn test_env_vars_build_nested_document() -> Result<()> {
+    let vars = vec![
+        ("CRYPTICS__KAFKA__BOOTSTRAP_SERVERS".to_string(), "localhost:9092".to_string()),
+        ("CRYPTICS__APP__RUST_RUNNING_IN_DOCKER".to_string(), "true".to_string()),
+        ("CRYPTICS__TOPICS__TICKER".to_string(), "env.ticker".to_string()),
+        ("UNRELATED".to_string(), "ignored".to_string()),
+    ];
+    let raw = AppConfig::env_to_value(vars)?;
+    
+    assert_eq!(raw, json!({
+        "kafka": { "bootstrap_servers": "localhost:9092" },
+        "app": { "rust_running_in_docker": "true" },
+        "topics": { "ticker": "env.ticker" }
+    }));
+    
+    // A variable can't nest below one that set a plain value
+    let clash = vec![
+        ("CRYPTICS__KAFKA".to_string(), "localhost".to_string()),
+        ("CRYPTICS__KAFKA__TIMEOUT_MS".to_string(), "5000".to_string()),
+    ];
+    assert!(AppConfig::env_to_value(clash).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_env_values_read_as_the_type_of_their_setting() -> Result<()> {
+    let mut vars: Vec<(String, String)> = Vec::new();
+    for (section, table) in base_config().as_object().unwrap() {
+        for (key, value) in table.as_object().unwrap() {
+            if let Some(text) = value.as_str() {
+                vars.push((format!("CRYPTICS__{}__{}", section, key).to_uppercase(), text.to_string()));
+            }
+        }
+    }
+    vars.extend([
+        ("CRYPTICS__APP__RUST_RUNNING_IN_DOCKER", "true"),
+        ("CRYPTICS__KAFKA__TIMEOUT_MS", "2500"),
+        ("CRYPTICS__TOPICS__BASE_NAME", "1234"),
+        ("CRYPTICS__DATABASE__HOST", "db"),
+        ("CRYPTICS__DATABASE__HOST_INTERNAL", "db"),
+        ("CRYPTICS__DATABASE__PORT", "5433"),
+        ("CRYPTICS__DATABASE__NAME", "cryptics"),
+        ("CRYPTICS__DATABASE__USER", "bot"),
+        ("CRYPTICS__DATABASE__PASSWORD", "00123"),
+        ("CRYPTICS__DATABASE__SCHEMA", "public"),
+    ].map(|(key, value)| (key.to_string(), value.to_string())));
+    
+    let config = AppConfig::from_env_vars(vars, None)?;
+    assert!(config.app.rust_running_in_docker);
+    assert_eq!(config.kafka.timeout_ms, 2500);
+    assert_eq!(config.database.port, 5433);
+    // Numeric-looking strings stay strings where a string is expected
+    assert_eq!(config.topics.base_name, "1234");
+    assert_eq!(config.database.password, "00123");
+    Ok(())
+}
+
+#[test]
+fn test_database_password_not_defaulted() -> Result<()> {
+    let config = AppConfig::from_value(base_config(), None)?;
+    assert!(config.database.password.is_empty());
+    assert!(!config.database_url().contains("password"));
+    Ok(())
+}
+
+#[test]
+fn test_cancel_on_disconnect_defaults_and_disable() -> Result<()> {
+    let config = AppConfig::from_value(base_config(), None)?;
+    assert_eq!(config.thalex.cancel_on_disconnect_timeout(), Some(6));
+    
+    let mut raw = base_config();
+    raw["thalex"] = json!({ "cancel_on_disconnect": false });
+    let config = AppConfig::from_value(raw, None)?;
+    assert_eq!(config.thalex.cancel_on_disconnect_timeout(), None);
+    Ok(())
+}
+
+#[test]
+fn test_funding_interval_read_from_the_schedule() -> Result<()> {
+    let config = AppConfig::from_value(base_config(), None)?;
+    assert_eq!(config.schedule.funding_interval_sec(), 8 * 3600);
+    
+    let mut raw = base_config();
+    raw["schedule"] = json!({ "funding": { "interval_sec": 3600, "before_sec": 60, "spread": 40.0 } });
+    let config = AppConfig::from_value(raw, None)?;
+    assert_eq!(config.schedule.funding_interval_sec(), 3600);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_schedule_rejected_on_load() {
+    let mut raw = base_config();
+    raw["schedule"] = json!({ "windows": [{ "name": "asia", "start": "25:00", "end": "02:00" }] });
+    let err = AppConfig::from_value(raw, None).unwrap_err();
+    assert!(err.to_string().contains("Invalid time of day"));
+
+    let mut raw = base_config();
+    raw["schedule"] = json!({ "funding": { "interval_sec": 3600, "before_sec": 1800, "after_sec": 1800 } });
+    assert!(AppConfig::from_value(raw, None).is_err());
+}
+
+#[test]
+fn test_risk_tiers_read_from_config() -> Result<()> {
+    let config = AppConfig::from_value(base_config(), None)?;
+    assert_eq!(config.risk.equity, 10_000.0);
+    assert_eq!(config.risk.leverage_tiers.len(), 3);
+    
+    let mut raw = base_config();
+    raw["risk"] = json!({ "equity": 25000.0, "leverage_tiers": [{ "max_notional": 100000.0, "max_leverage": 4.0 }] });
+    let config = AppConfig::from_value(raw, None)?;
+    assert_eq!(config.risk.equity, 25_000.0);
+    assert_eq!(config.risk.leverage_tiers, vec![LeverageTier { max_notional: 100_000.0, max_leverage: 4.0 }]);
+    Ok(())
+}
+
+#[test]
+fn test_fair_value_source_defaults_to_index() -> Result<()> {
+    let config = AppConfig::from_value(base_config(), None)?;
+    assert_eq!(config.fair_value.source, FairValueSource::Index);
+    
+    let mut raw = base_config();
+    raw["fair_value"] = json!({ "source": "http", "url": "http://localhost:9000/mid", "field": "result.mid" });
+    let config = AppConfig::from_value(raw, None)?;
+    assert_eq!(config.fair_value.source, FairValueSource::Http);
+    assert_eq!(config.fair_value.field, "result.mid");
+    assert_eq!(config.fair_value.max_age_ms, 3000);
+    Ok(())
+}
+
+#[test]
+fn test_book_subscriptions_validated() -> Result<()> {
+    let mut raw = base_config();
+    raw["thalex"] = json!({ "books": [{ "instrument": "BTC-PERPETUAL", "depth": 10 }] });
+    let config = AppConfig::from_value(raw, None)?;
+    assert_eq!(config.thalex.book_channels()?[0].to_string(), "book.BTC-PERPETUAL.none.10.raw");
+    
+    let mut raw = base_config();
+    raw["thalex"] = json!({ "books": [{ "instrument": "BTC-PERPETUAL", "depth": 3 }] });
+    assert!(AppConfig::from_value(raw, None).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_options_selected_by_expiry_strike_and_type() -> Result<()> {
+    let mut raw = base_config();
+    raw["thalex"] = json!({ "options": {
+        "underlying": "BTCUSD", "max_days_to_expiry": 10, "min_strike": 60000, "max_strike": 80000, "option_type": "call",
+    } });
+    let config = AppConfig::from_value(raw, None)?;
+    let options = config.thalex.options.unwrap();
+    
+    let listed: Vec<Instrument> = [
+        "BTC-27DEC24-70000-C", "BTC-27DEC24-65000-C", "BTC-27DEC24-70000-P",
+        "BTC-27DEC24-90000-C", "BTC-28MAR25-70000-C", "BTC-20DEC24-70000-C",
+    ].iter()
+        .map(|name| serde_json::from_value::<Instrument>(json!({
+            "instrument_name": name, "type": "option", "underlying": "BTCUSD", "tick_size": 5.0,
+        })).unwrap().with_name_details().unwrap())
+        .collect();
+    // 2024-12-21, a day after the 20DEC24 expiry
+    let selected: Vec<String> = options.select(&listed, 1734739200.0)
+        .into_iter()
+        .map(|option| option.instrument_name)
+        .collect();
+    assert_eq!(selected, vec!["BTC-27DEC24-65000-C", "BTC-27DEC24-70000-C"]);
+    
+    let mut raw = base_config();
+    raw["thalex"] = json!({ "options": { "min_strike": 80000, "max_strike": 60000 } });
+    assert!(AppConfig::from_value(raw, None).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_strategy_settings_per_instrument() -> Result<()> {
+    let mut raw = base_config();
+    raw["strategy"] = json!({ "instruments": [
+        { "instrument": "BTC-PERPETUAL", "amend_thresholds": [5.0, 15.0], "size_tolerance": 0.1 },
+        { "instrument": "ETH-PERPETUAL" },
+    ] });
+    let config = AppConfig::from_value(raw, None)?;
+    
+    let btc = config.strategy.instrument("BTC-PERPETUAL").unwrap();
+    assert_eq!(btc.amend_thresholds, vec![5.0, 15.0]);
+    assert_eq!(btc.size_tolerance, 0.1);
+    assert!(config.strategy.instrument("ETH-PERPETUAL").unwrap().amend_thresholds.is_empty());
+    assert!(config.strategy.instrument("SOL-PERPETUAL").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_ladder_shapes_parsed() -> Result<()> {
+    let mut raw = base_config();
+    raw["strategy"] = json!({ "instruments": [
+        { "instrument": "BTC-PERPETUAL", "ladder": { "shape": "geometric", "step": 5.0, "ratio": 1.5, "bid_sizes": [0.1], "ask_sizes": [0.1] } },
+        { "instrument": "ETH-PERPETUAL", "ladder": { "shape": "custom", "offsets": [0.0, 2.0], "bid_sizes": [0.1, 0.2], "ask_sizes": [0.1, 0.2] } },
+    ] });
+    let config = AppConfig::from_value(raw, None)?;
+    
+    let btc = config.strategy.instrument("BTC-PERPETUAL").unwrap().ladder.clone().unwrap();
+    assert_eq!(btc.shape, LadderShape::Geometric { step: 5.0, ratio: 1.5 });
+    let eth = config.strategy.instrument("ETH-PERPETUAL").unwrap().ladder.clone().unwrap();
+    assert_eq!(eth.shape, LadderShape::Custom { offsets: vec![0.0, 2.0] });
+    assert_eq!(eth.ask_sizes, vec![0.1, 0.2]);
+    
+    let mut raw = base_config();
+    raw["strategy"] = json!({ "instruments": [
+        { "instrument": "BTC-PERPETUAL", "ladder": { "shape": "spiral", "bid_sizes": [], "ask_sizes": [] } },
+    ] });
+    assert!(AppConfig::from_value(raw, None).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_topic_types_from_config() -> Result<()> {
+    let mut raw = base_config();
+    raw["topics"]["types"] = json!({
+        "funding": { "topic": "base.funding", "subject": "funding-rate-value" },
+        "ticker": { "topic": "base.ticker.v2", "schema": "ticker" }
+    });
+    let config = AppConfig::from_value(raw, None)?;
+    let types = config.topics.topic_types();
+    
+    // Built-in types keep their schema and compaction settings
+    assert_eq!(types["ack"].topic, "base.ack");
+    assert_eq!(types["ticker_latest"].schema_name("ticker_latest"), "ticker");
+    assert!(types["ticker_latest"].compacted);
+    
+    // New types are added and entries under `types` override built-in ones
+    assert_eq!(types["funding"].schema_name("funding"), "funding");
+    assert_eq!(types["funding"].subject(), "funding-rate-value");
+    assert_eq!(types["ticker"].topic, "base.ticker.v2");
+    assert_eq!(types["ticker"].subject(), "base.ticker.v2-value");
+    assert_eq!(config.kafka.schema_dir, "../schemas");
+    Ok(())
+}
+
+#[test]
+fn test_topic_retention_from_config() -> Result<()> {
+    let mut raw = base_config();
+    raw["topics"]["retention"] = json!({
+        "ticker": { "retention_ms": 86_400_000 },
+        "book": { "retention_bytes": 1_073_741_824 },
+        "unknown": { "retention_ms": 1 }
+    });
+    let config = AppConfig::from_value(raw, None)?;
+    let types = config.topics.topic_types();
+    
+    assert_eq!(types["ticker"].retention.retention_ms, Some(86_400_000));
+    assert_eq!(types["book"].retention.retention_bytes, Some(1_073_741_824));
+    assert!(!types["trade"].retention.is_set());
+    assert!(!types.contains_key("unknown"));
+    
+    // Reported but left alone by default
+    assert!(config.kafka.topic_admin.enabled);
+    assert!(!config.kafka.topic_admin.alter);
+    Ok(())
+}
+
+#[test]
+fn test_control_consumer_defaults() -> Result<()> {
+    let config = AppConfig::from_value(base_config(), None)?;
+    assert!(!config.control.enabled);
+    assert_eq!(config.control.max_age_ms, 5000);
+    assert_eq!(config.fair_value.max_lag, 100);
+    
+    let mut raw = base_config();
+    raw["control"] = json!({ "enabled": true, "topic": "ops.control", "max_lag": 0 });
+    let config = AppConfig::from_value(raw, None)?;
+    assert!(config.control.enabled);
+    assert_eq!(config.control.topic, "ops.control");
+    assert_eq!(config.control.max_lag, 0);
+    Ok(())
+}
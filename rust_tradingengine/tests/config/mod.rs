@@ -0,0 +1,4 @@
+//! Tests for configuration loading
+
+// Import test modules
+pub mod config_loader_tests;
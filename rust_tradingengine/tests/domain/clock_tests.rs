@@ -0,0 +1,73 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::json;
+use cryptics_lab_bot::domain::clock::{self, Clock, ManualClock};
+use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
+
+#[test]
+fn test_manual_clock_moves_only_when_advanced() {
+    let manual = ManualClock::new(1792022400.0);
+    let start = manual.instant();
+    assert_eq!(manual.now_secs(), 1792022400.0);
+    assert_eq!(manual.instant(), start);
+    
+    manual.advance(Duration::from_millis(1500));
+    assert_eq!(manual.now_secs(), 1792022401.5);
+    assert_eq!(manual.instant() - start, Duration::from_millis(1500));
+    
+    manual.advance_to(1792022410.0);
+    assert_eq!(manual.now_secs(), 1792022410.0);
+    assert_eq!(manual.instant() - start, Duration::from_secs(10));
+    
+    // Time never goes backwards
+    manual.advance_to(1792022405.0);
+    assert_eq!(manual.now_secs(), 1792022410.0);
+}
+
+#[test]
+fn test_scoped_clock_is_restored_when_dropped() {
+    let outer = Arc::new(ManualClock::new(1000.0));
+    let inner = Arc::new(ManualClock::new(2000.0));
+    
+    let outer_guard = clock::scoped(outer.clone());
+    assert_eq!(clock::now_secs(), 1000.0);
+    {
+        let _inner_guard = clock::scoped(inner.clone());
+        assert_eq!(clock::now_secs(), 2000.0);
+        assert_eq!(clock::now_millis(), 2000000);
+        assert_eq!(clock::now_utc().timestamp(), 2000);
+    }
+    assert_eq!(clock::now_secs(), 1000.0);
+    outer.advance(Duration::from_secs(5));
+    assert_eq!(clock::instant(), outer.instant());
+    drop(outer_guard);
+    
+    // Back on the system clock
+    assert!(clock::now_secs() > 1005.0);
+}
+
+#[test]
+fn test_parsers_stamp_virtual_time() -> Result<()> {
+    let manual = Arc::new(ManualClock::new(1792022400.25));
+    let _guard = clock::scoped(manual.clone());
+    let order = json!({
+        "order_id": "ord-1",
+        "instrument_name": "BTC-PERPETUAL",
+        "direction": "buy",
+        "price": 50000.0,
+        "amount": 0.1,
+        "status": "open",
+    });
+    
+    let first = ThaleParser::parse_order_json(&order)?;
+    assert_eq!(first.processing_timestamp, Some(1792022400.25));
+    
+    // The same input at the same virtual time gives the same output
+    assert_eq!(ThaleParser::parse_order_json(&order)?.processing_timestamp, first.processing_timestamp);
+    
+    manual.advance(Duration::from_millis(250));
+    assert_eq!(ThaleParser::parse_order_json(&order)?.processing_timestamp, Some(1792022400.5));
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use cryptics_lab_bot::domain::clock::{self, ManualClock};
+use cryptics_lab_bot::domain::model::bot_state::BotState;
+use cryptics_lab_bot::infrastructure::bot_state::{BotStateMachine, METRIC_BOT_STATE, METRIC_STATE_TRANSITIONS};
+use cryptics_lab_bot::infrastructure::metrics;
+
+fn journal_path() -> PathBuf {
+    std::env::temp_dir().join(format!("bot_state_tests_{}", uuid::Uuid::new_v4())).join("bot_state.jsonl")
+}
+
+#[test]
+fn test_allowed_transitions() {
+    use BotState::*;
+    assert!(Connecting.can_transition_to(Warmup));
+    assert!(Warmup.can_transition_to(Quoting));
+    assert!(Quoting.can_transition_to(CancelOnly));
+    assert!(CancelOnly.can_transition_to(Quoting));
+    assert!(Quoting.can_transition_to(Halted));
+    assert!(Halted.can_transition_to(Draining));
+    assert!(Draining.can_transition_to(Connecting));
+    
+    // Quoting needs a session, and a halt is only left by tearing the session down
+    assert!(!Connecting.can_transition_to(Quoting));
+    assert!(!Halted.can_transition_to(Quoting));
+    assert!(!Halted.can_transition_to(CancelOnly));
+    assert!(!Draining.can_transition_to(Quoting));
+    assert!(BotState::ALL.iter().all(|state| !Stopped.can_transition_to(*state)));
+}
+
+#[test]
+fn test_permissions_of_each_state() {
+    let quoting: Vec<BotState> = BotState::ALL.into_iter().filter(BotState::may_quote).collect();
+    assert_eq!(quoting, vec![BotState::Quoting]);
+    let cancelling: Vec<BotState> = BotState::ALL.into_iter().filter(BotState::may_cancel).collect();
+    assert_eq!(cancelling, vec![BotState::Warmup, BotState::Quoting, BotState::CancelOnly, BotState::Halted, BotState::Draining]);
+}
+
+#[test]
+fn test_transitions_are_applied_and_published() -> Result<()> {
+    let machine = BotStateMachine::new("instance-1", None);
+    assert_eq!(machine.current(), BotState::Connecting);
+    
+    assert_eq!(machine.transition(BotState::Warmup, "logged in")?, Some(BotState::Connecting));
+    assert_eq!(machine.transition(BotState::Warmup, "logged in")?, None);
+    assert_eq!(machine.transition(BotState::Quoting, "ready")?, Some(BotState::Warmup));
+    assert!(machine.may_quote());
+    assert_eq!(metrics::global().gauge_value(METRIC_BOT_STATE), Some(BotState::Quoting.index() as f64));
+    assert!(metrics::global().counter_value(&format!("{}.quoting", METRIC_STATE_TRANSITIONS)) >= 1);
+    
+    // Refused transitions leave the state as it was
+    machine.transition(BotState::Halted, "liquidation")?;
+    let err = machine.transition(BotState::Quoting, "ready").unwrap_err();
+    assert!(err.to_string().contains("halted to quoting"));
+    assert_eq!(machine.current(), BotState::Halted);
+    assert!(!machine.may_quote());
+    assert!(machine.may_cancel());
+    Ok(())
+}
+
+#[test]
+fn test_transitions_are_journaled_with_reasons() -> Result<()> {
+    let _clock = clock::scoped(Arc::new(ManualClock::new(1792022400.0)));
+    let path = journal_path();
+    let machine = BotStateMachine::new("instance-1", Some(path.clone()));
+    machine.transition(BotState::Warmup, "logged in")?;
+    machine.transition(BotState::Quoting, "ready")?;
+    machine.transition(BotState::CancelOnly, "paused by a control command")?;
+    
+    // Refused and repeated transitions aren't journaled
+    assert!(machine.transition(BotState::Connecting, "reconnect").is_err());
+    machine.transition(BotState::CancelOnly, "paused by a control command")?;
+    
+    let history = BotStateMachine::history(&path)?;
+    let moves: Vec<(BotState, BotState, &str)> = history.iter()
+        .map(|transition| (transition.from, transition.to, transition.reason.as_str()))
+        .collect();
+    assert_eq!(moves, vec![
+        (BotState::Connecting, BotState::Warmup, "logged in"),
+        (BotState::Warmup, BotState::Quoting, "ready"),
+        (BotState::Quoting, BotState::CancelOnly, "paused by a control command"),
+    ]);
+    assert!(history.iter().all(|transition| transition.instance_id == "instance-1" && transition.timestamp == 1792022400.0));
+    assert_eq!(BotStateMachine::last_transition(&path)?.map(|last| last.to), Some(BotState::CancelOnly));
+    
+    // A journal that doesn't exist yet has no history
+    assert!(BotStateMachine::history(&journal_path())?.is_empty());
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
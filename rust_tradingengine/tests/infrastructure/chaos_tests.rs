@@ -0,0 +1,97 @@
+use cryptics_lab_bot::config_loader::{ChaosConfig, FaultConfig};
+use cryptics_lab_bot::infrastructure::chaos::{ChaosLayer, FaultInjector, FaultTarget};
+use std::time::Duration;
+
+fn config(drop_rate: f64, reorder_rate: f64) -> ChaosConfig {
+    ChaosConfig {
+        enabled: true,
+        latency_ms: 10,
+        jitter_ms: 5,
+        drop_rate,
+        reorder_rate,
+        seed: Some(7),
+        ..Default::default()
+    }
+}
+
+fn deliver(chaos: &mut ChaosLayer, count: usize) -> Vec<String> {
+    (0..count).flat_map(|i| chaos.plan(i.to_string())).collect()
+}
+
+#[test]
+fn test_passthrough_without_faults() {
+    let mut chaos = ChaosLayer::new(config(0.0, 0.0));
+    let expected: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+    assert_eq!(deliver(&mut chaos, 20), expected);
+}
+
+#[test]
+fn test_drop_everything() {
+    let mut chaos = ChaosLayer::new(config(1.0, 0.0));
+    assert!(deliver(&mut chaos, 20).is_empty());
+}
+
+#[test]
+fn test_reorder_swaps_with_next_message() {
+    let mut chaos = ChaosLayer::new(config(0.0, 1.0));
+    assert!(chaos.plan("a".to_string()).is_empty());
+    assert_eq!(chaos.plan("b".to_string()), vec!["b", "a"]);
+}
+
+#[test]
+fn test_reorder_keeps_every_message() {
+    let mut chaos = ChaosLayer::new(config(0.0, 0.3));
+    let mut delivered = deliver(&mut chaos, 200);
+    delivered.extend(chaos.plan("last".to_string()));
+    assert!(delivered.len() >= 200);
+    delivered.sort();
+    delivered.dedup();
+    assert!(delivered.len() >= 200, "no message may be duplicated");
+}
+
+#[test]
+fn test_delay_within_jitter_bounds() {
+    let mut chaos = ChaosLayer::new(config(0.0, 0.0));
+    for _ in 0..50 {
+        let delay = chaos.delay();
+        assert!(delay >= Duration::from_millis(10) && delay <= Duration::from_millis(15));
+    }
+}
+
+#[test]
+fn test_seed_is_reproducible() {
+    let mut a = ChaosLayer::new(config(0.2, 0.2));
+    let mut b = ChaosLayer::new(config(0.2, 0.2));
+    assert_eq!(deliver(&mut a, 100), deliver(&mut b, 100));
+}
+
+fn faults(error_rate: f64, outage_sec: u64, outage_every_sec: u64) -> FaultConfig {
+    FaultConfig { error_rate, delay_ms: 0, outage_sec, outage_every_sec }
+}
+
+#[tokio::test]
+async fn test_fault_error_rate_extremes() {
+    let healthy = FaultInjector::new(FaultTarget::Kafka, faults(0.0, 0, 0), Some(1));
+    let broken = FaultInjector::new(FaultTarget::Kafka, faults(1.0, 0, 0), Some(1));
+    for _ in 0..20 {
+        assert!(healthy.check().await.is_ok());
+        assert!(broken.check().await.is_err());
+    }
+}
+
+#[test]
+fn test_outage_windows() {
+    let fault = FaultInjector::new(FaultTarget::Registry, faults(0.0, 10, 60), None);
+    assert!(fault.is_unavailable(Duration::from_secs(0)));
+    assert!(fault.is_unavailable(Duration::from_secs(9)));
+    assert!(!fault.is_unavailable(Duration::from_secs(10)));
+    assert!(!fault.is_unavailable(Duration::from_secs(59)));
+    assert!(fault.is_unavailable(Duration::from_secs(65)));
+}
+
+#[tokio::test]
+async fn test_outage_fails_calls() {
+    let fault = FaultInjector::new(FaultTarget::Registry, faults(0.0, 3600, 7200), None);
+    let err = fault.check().await.unwrap_err();
+    assert!(err.to_string().contains("outage"));
+}
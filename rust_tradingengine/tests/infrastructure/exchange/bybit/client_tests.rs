@@ -0,0 +1,104 @@
+use serde_json::json;
+
+use cryptics_lab_bot::domain::enums::{OrderSide, OrderType, TimeInForce};
+use cryptics_lab_bot::domain::model::exchange::OrderRequest;
+use cryptics_lab_bot::infrastructure::exchange::bybit::client::is_private_topic;
+use cryptics_lab_bot::infrastructure::exchange::bybit::{BybitClient, BybitKeys, BybitNetwork, BybitRestClient};
+use cryptics_lab_bot::infrastructure::exchange::{ExchangeClient, OrderGateway, Venue};
+
+fn order(client_order_id: u64) -> OrderRequest {
+    OrderRequest {
+        symbol: "BTCUSDT".to_string(),
+        side: OrderSide::Buy,
+        order_type: OrderType::Limit,
+        quantity: 0.001,
+        price: Some(65000.5),
+        client_order_id: Some(client_order_id),
+        time_in_force: Some(TimeInForce::GTC),
+        label: None,
+    }
+}
+
+#[test]
+fn test_signature_is_hex_hmac_sha256() {
+    let keys = BybitKeys::new("key", "key");
+    assert_eq!(
+        keys.sign("The quick brown fox jumps over the lazy dog"),
+        "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+}
+
+#[test]
+fn test_auth_token_signs_the_expiry() {
+    let keys = BybitKeys::new("api-key", "secret");
+    let token: serde_json::Value = serde_json::from_str(&keys.make_auth_token(1792022400000)).unwrap();
+    assert_eq!(token[0], "api-key");
+    assert_eq!(token[1], 1792022400000i64);
+    assert_eq!(token[2], keys.sign("GET/realtime1792022400000"));
+    assert!(!format!("{:?}", keys).contains("secret"));
+}
+
+#[test]
+fn test_rest_signature_covers_timestamp_key_window_and_payload() {
+    let keys = BybitKeys::new("api-key", "secret");
+    let rest = BybitRestClient::new(BybitNetwork::Testnet.rest_url(), keys.clone());
+    let headers = rest.auth_headers(1792022400000, "category=linear");
+    let sign = headers.iter().find(|(name, _)| *name == "X-BAPI-SIGN").unwrap();
+    assert_eq!(sign.1, keys.sign("1792022400000api-key5000category=linear"));
+
+    assert_eq!(BybitRestClient::result(json!({"retCode": 0, "result": {"list": []}})).unwrap(), json!({"list": []}));
+    let err = BybitRestClient::result(json!({"retCode": 10001, "retMsg": "params error"})).unwrap_err();
+    assert_eq!(err.to_string(), "Bybit error 10001: params error");
+}
+
+#[test]
+fn test_order_requests() {
+    let client = BybitClient::new();
+    let create = client.create_request(&order(7), Some(12));
+    assert_eq!(create["op"], "order.create");
+    assert_eq!(create["reqId"], "12");
+    assert_eq!(create["header"]["X-BAPI-RECV-WINDOW"], "5000");
+    assert_eq!(create["args"][0], json!({
+        "category": "linear", "symbol": "BTCUSDT", "side": "Buy", "orderType": "Limit",
+        "qty": "0.001", "price": "65000.5", "timeInForce": "GTC", "orderLinkId": "7",
+    }));
+
+    let amend = client.amend_request("BTCUSDT", None, Some(65001.0), None, Some(7), None);
+    assert_eq!(amend["op"], "order.amend");
+    assert_eq!(amend["args"][0], json!({"category": "linear", "symbol": "BTCUSDT", "orderLinkId": "7", "price": "65001"}));
+    assert!(amend.get("reqId").is_none());
+
+    let cancel = client.cancel_request("BTCUSDT", Some("abc".to_string()), None, Some(3));
+    assert_eq!(cancel["args"][0], json!({"category": "linear", "symbol": "BTCUSDT", "orderId": "abc"}));
+}
+
+#[tokio::test]
+async fn test_unconnected_client() {
+    let mut client = BybitClient::new();
+    assert_eq!(client.venue(), Venue::Bybit);
+    assert!(!client.connected());
+
+    let err = client.insert(order(7), Some(1)).await.unwrap_err();
+    assert!(err.to_string().contains("trade socket not connected"));
+    assert!(client.symbol_of(7).is_none());
+
+    // Amends and cancels need the symbol remembered from the insert
+    let err = client.cancel(None, Some(7), None).await.unwrap_err();
+    assert!(err.to_string().contains("Unknown symbol"));
+    let err = client.amend(1.0, 65001.0, None, Some(7), None).await.unwrap_err();
+    assert!(err.to_string().contains("Unknown symbol"));
+
+    assert!(client.receive().await.is_err());
+    assert!(client.open_orders(Some(2)).await.unwrap_err().to_string().contains("REST client not configured"));
+}
+
+#[test]
+fn test_private_topics() {
+    assert!(is_private_topic("order"));
+    assert!(is_private_topic("position.linear"));
+    assert!(is_private_topic("execution"));
+    assert!(!is_private_topic("tickers.BTCUSDT"));
+    assert!(!is_private_topic("orderbook.50.BTCUSDT"));
+    assert_eq!("mainnet".parse::<BybitNetwork>().unwrap(), BybitNetwork::Mainnet);
+    assert!("devnet".parse::<BybitNetwork>().is_err());
+}
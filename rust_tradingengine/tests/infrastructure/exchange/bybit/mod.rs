@@ -0,0 +1,4 @@
+//! Tests for the Bybit connector
+
+pub mod client_tests;
+pub mod parsers_tests;
@@ -0,0 +1,93 @@
+use serde_json::json;
+
+use cryptics_lab_bot::domain::enums::{MakerTaker, OrderSide, OrderStatus, TimeInForce};
+use cryptics_lab_bot::infrastructure::exchange::bybit::BybitParser;
+
+#[test]
+fn test_ticker_snapshot_then_delta() {
+    let snapshot = json!({
+        "symbol": "BTCUSDT", "lastPrice": "65000.5", "markPrice": "65001.2", "indexPrice": "64998.7",
+        "bid1Price": "65000.0", "bid1Size": "1.2", "ask1Price": "65000.5", "ask1Size": "0.8",
+        "volume24h": "12345.6", "turnover24h": "800000000", "highPrice24h": "66000", "lowPrice24h": "64000",
+        "price24hPcnt": "0.0123", "fundingRate": "0.0001", "openInterest": "50000",
+    });
+    let mut ticker = BybitParser::parse_ticker(&snapshot, Some(1792022400000.0)).unwrap();
+    assert_eq!(ticker.instrument_name, "BTCUSDT");
+    assert_eq!(ticker.mark_price, 65001.2);
+    assert_eq!(ticker.best_bid_price, 65000.0);
+    assert_eq!(ticker.best_ask_amount, 0.8);
+    assert_eq!(ticker.value_24h, 800000000.0);
+    assert_eq!(ticker.mark_timestamp, 1792022400.0);
+    assert_eq!(ticker.delta, 1.0);
+
+    // A delta only carries what changed
+    BybitParser::apply_ticker(&json!({"symbol": "BTCUSDT", "bid1Price": "65000.1", "markPrice": ""}), Some(1792022400100.0), &mut ticker);
+    assert_eq!(ticker.best_bid_price, 65000.1);
+    assert_eq!(ticker.mark_price, 65001.2);
+    assert_eq!(ticker.funding_rate, 0.0001);
+    assert_eq!(ticker.mark_timestamp, 1792022400.1);
+}
+
+#[test]
+fn test_order_maps_to_ack() {
+    let data = json!({
+        "orderId": "fd4300ae-7847-404e-b947-b46980a4d140", "orderLinkId": "42", "symbol": "BTCUSDT",
+        "side": "Sell", "orderType": "Limit", "price": "65010", "qty": "0.01", "cumExecQty": "0.004",
+        "leavesQty": "0.006", "orderStatus": "PartiallyFilled", "timeInForce": "PostOnly",
+        "rejectReason": "EC_NoError", "createdTime": "1792022400123",
+    });
+    let order = BybitParser::parse_order(&data).unwrap();
+    assert_eq!(order.client_order_id, Some(42));
+    assert!(matches!(order.direction, OrderSide::Sell));
+    assert!(matches!(order.status, OrderStatus::PartiallyFilled));
+    assert!(matches!(order.time_in_force, TimeInForce::GTC));
+    assert_eq!(order.price, Some(65010.0));
+    assert_eq!(order.remaining_amount, 0.006);
+    assert_eq!(order.create_time, 1792022400.123);
+    assert!(order.delete_reason.is_none());
+    assert!(order.is_open());
+}
+
+#[test]
+fn test_order_statuses() {
+    let status = |status: &str, filled: &str| {
+        let data = json!({"orderId": "1", "symbol": "BTCUSDT", "side": "Buy", "qty": "1", "cumExecQty": filled, "orderStatus": status});
+        BybitParser::parse_order(&data).map(|order| order.status)
+    };
+    assert!(matches!(status("New", "0").unwrap(), OrderStatus::Open));
+    assert!(matches!(status("Filled", "1").unwrap(), OrderStatus::Filled));
+    assert!(matches!(status("Cancelled", "0").unwrap(), OrderStatus::Cancelled));
+    assert!(matches!(status("Cancelled", "0.5").unwrap(), OrderStatus::CancelledPartiallyFilled));
+    assert!(matches!(status("PartiallyFilledCanceled", "0.5").unwrap(), OrderStatus::CancelledPartiallyFilled));
+    assert!(status("Bogus", "0").is_err());
+
+    // Label-less orders placed elsewhere have no client order id
+    let data = json!({"orderId": "1", "orderLinkId": "", "symbol": "BTCUSDT", "side": "Buy", "qty": "1", "orderStatus": "New"});
+    assert_eq!(BybitParser::parse_order(&data).unwrap().client_order_id, None);
+}
+
+#[test]
+fn test_execution_maps_to_trade() {
+    let data = json!({
+        "execId": "e-1", "orderId": "o-1", "orderLinkId": "42", "symbol": "BTCUSDT", "side": "Buy",
+        "execPrice": "65000.5", "execQty": "0.002", "isMaker": true, "execTime": "1792022400500",
+    });
+    let trade = BybitParser::parse_execution(&data).unwrap();
+    assert_eq!(trade.trade_id, "e-1");
+    assert_eq!(trade.client_order_id, Some(42));
+    assert_eq!(trade.amount, 0.002);
+    assert_eq!(trade.maker_taker, Some(MakerTaker::Maker));
+    assert_eq!(trade.time, 1792022400.5);
+    assert!(matches!(BybitParser::execution_side(&data).unwrap(), OrderSide::Buy));
+    assert!(BybitParser::parse_execution(&json!({"execId": "e-2"})).is_err());
+}
+
+#[test]
+fn test_position_is_signed() {
+    let long = BybitParser::parse_position(&json!({"symbol": "BTCUSDT", "side": "Buy", "size": "0.5"})).unwrap();
+    assert_eq!(long, ("BTCUSDT".to_string(), 0.5));
+    let short = BybitParser::parse_position(&json!({"symbol": "BTCUSDT", "side": "Sell", "size": "0.25"})).unwrap();
+    assert_eq!(short.1, -0.25);
+    let flat = BybitParser::parse_position(&json!({"symbol": "BTCUSDT", "side": "", "size": "0"})).unwrap();
+    assert_eq!(flat.1, 0.0);
+}
@@ -0,0 +1,47 @@
+use cryptics_lab_bot::domain::enums::{OrderSide, OrderType, TimeInForce};
+use cryptics_lab_bot::domain::model::exchange::OrderRequest;
+use cryptics_lab_bot::infrastructure::exchange::thalex::client::ThalexClient;
+use cryptics_lab_bot::infrastructure::exchange::thalex::error::ClientError;
+use cryptics_lab_bot::infrastructure::exchange::{ExchangeClient, Venue};
+
+/// A session driven only through the trait, as a strategy would
+async fn quote_once(client: &mut dyn ExchangeClient) -> anyhow::Result<()> {
+    client.subscribe(vec!["session.orders".to_string()], true, Some(1)).await?;
+    client.subscribe(vec!["ticker.BTC-PERPETUAL.1000ms".to_string()], false, Some(2)).await?;
+    client.insert(OrderRequest {
+        symbol: "BTC-PERPETUAL".to_string(),
+        side: OrderSide::Buy,
+        order_type: OrderType::Limit,
+        quantity: 0.1,
+        price: Some(50_000.0),
+        client_order_id: Some(1),
+        time_in_force: Some(TimeInForce::GTC),
+        label: None,
+    }, Some(3)).await?;
+    client.amend(0.1, 50_010.0, None, Some(1), Some(4)).await?;
+    client.cancel(None, Some(1), Some(5)).await?;
+    client.unsubscribe(vec!["ticker.BTC-PERPETUAL.1000ms".to_string()], Some(6)).await
+}
+
+#[tokio::test]
+async fn test_thalex_client_behind_trait() {
+    let mut client = ThalexClient::new();
+    assert_eq!(ExchangeClient::venue(&client), Venue::Thalex);
+    assert!(!ExchangeClient::connected(&client));
+
+    // Requests wait for the socket, whichever way they're made
+    quote_once(&mut client).await.unwrap();
+    assert_eq!(client.outbound_depth(), 6);
+}
+
+#[tokio::test]
+async fn test_trait_session_calls_need_a_connection() {
+    let mut client = ThalexClient::new();
+    let exchange: &mut dyn ExchangeClient = &mut client;
+
+    let error = exchange.receive().await.unwrap_err();
+    assert!(matches!(error.downcast_ref::<ClientError>(), Some(ClientError::NotConnected)));
+    let error = exchange.disconnect().await.unwrap_err();
+    assert!(matches!(error.downcast_ref::<ClientError>(), Some(ClientError::NotConnected)));
+    assert!(exchange.connect("not a url").await.is_err());
+}
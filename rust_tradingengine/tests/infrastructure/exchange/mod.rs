@@ -1,4 +1,8 @@
 //! Tests for exchange-related components
 
 // Import test modules
+pub mod bybit;
 pub mod thalex;
+pub mod client_tests;
+pub mod okx;
+pub mod symbology_tests;
@@ -0,0 +1,143 @@
+use serde_json::{json, Value};
+
+use cryptics_lab_bot::domain::enums::{OrderSide, OrderType, TimeInForce};
+use cryptics_lab_bot::domain::model::exchange::OrderRequest;
+use cryptics_lab_bot::infrastructure::exchange::okx::client::{channel_arg, is_private_channel};
+use cryptics_lab_bot::infrastructure::exchange::okx::{OkxClient, OkxKeys, OkxNetwork, OkxRestClient};
+use cryptics_lab_bot::infrastructure::exchange::{ExchangeClient, OrderGateway, Venue};
+
+fn order(client_order_id: u64) -> OrderRequest {
+    OrderRequest {
+        symbol: "BTC-USDT-SWAP".to_string(),
+        side: OrderSide::Buy,
+        order_type: OrderType::Limit,
+        quantity: 1.0,
+        price: Some(65000.5),
+        client_order_id: Some(client_order_id),
+        time_in_force: Some(TimeInForce::GTC),
+        label: None,
+    }
+}
+
+#[test]
+fn test_signature_is_base64_hmac_sha256() {
+    let keys = OkxKeys::new("key", "key", "pass");
+    assert_eq!(keys.sign("The quick brown fox jumps over the lazy dog"), "97yD9DBThCSxMpjmqm+xQ+9NWaFJRhdZl0edvC0aPNg=");
+}
+
+#[test]
+fn test_auth_token_signs_the_verify_path() {
+    let keys = OkxKeys::new("api-key", "secret", "passphrase");
+    let token: Value = serde_json::from_str(&keys.make_auth_token(1792022400)).unwrap();
+    assert_eq!(token["apiKey"], "api-key");
+    assert_eq!(token["passphrase"], "passphrase");
+    assert_eq!(token["timestamp"], "1792022400");
+    assert_eq!(token["sign"], keys.sign("1792022400GET/users/self/verify"));
+    assert!(!format!("{:?}", keys).contains("secret"));
+}
+
+#[test]
+fn test_rest_signature_and_demo_flag() {
+    let keys = OkxKeys::new("api-key", "secret", "passphrase");
+    let timestamp = OkxRestClient::timestamp(chrono::DateTime::from_timestamp_millis(1792022400123).unwrap());
+    assert_eq!(timestamp, "2026-10-15T00:00:00.123Z");
+
+    let path = "/api/v5/trade/orders-pending?instType=SWAP";
+    let demo = OkxRestClient::new(OkxNetwork::Demo.rest_url(), keys.clone(), OkxNetwork::Demo.simulated());
+    let headers = demo.auth_headers(&timestamp, "GET", path, "");
+    let header = |name: &str| headers.iter().find(|(n, _)| *n == name).map(|(_, v)| v.clone());
+    assert_eq!(header("OK-ACCESS-SIGN"), Some(keys.sign(&format!("{}GET{}", timestamp, path))));
+    assert_eq!(header("OK-ACCESS-PASSPHRASE").as_deref(), Some("passphrase"));
+    assert_eq!(header("x-simulated-trading").as_deref(), Some("1"));
+
+    let live = OkxRestClient::new(OkxNetwork::Live.rest_url(), keys, OkxNetwork::Live.simulated());
+    assert!(!live.auth_headers(&timestamp, "GET", path, "").iter().any(|(n, _)| *n == "x-simulated-trading"));
+
+    assert_eq!(OkxRestClient::result(json!({"code": "0", "msg": "", "data": []})).unwrap(), json!([]));
+    let err = OkxRestClient::result(json!({"code": "50113", "msg": "Invalid Sign", "data": []})).unwrap_err();
+    assert_eq!(err.to_string(), "OKX error 50113: Invalid Sign");
+}
+
+#[test]
+fn test_order_requests() {
+    let client = OkxClient::new();
+    let create = client.create_request(&order(7), Some(12));
+    assert_eq!(create["op"], "order");
+    assert_eq!(create["id"], "12");
+    assert_eq!(create["args"][0], json!({
+        "instId": "BTC-USDT-SWAP", "tdMode": "cross", "side": "buy", "ordType": "limit",
+        "sz": "1", "px": "65000.5", "clOrdId": "7",
+    }));
+
+    let mut ioc = order(8);
+    ioc.time_in_force = Some(TimeInForce::IOC);
+    assert_eq!(client.create_request(&ioc, None)["args"][0]["ordType"], "ioc");
+
+    let amend = client.amend_request("BTC-USDT-SWAP", None, Some(65001.0), None, Some(7), None);
+    assert_eq!(amend["op"], "amend-order");
+    assert_eq!(amend["args"][0], json!({"instId": "BTC-USDT-SWAP", "clOrdId": "7", "newPx": "65001"}));
+    assert!(amend.get("id").is_none());
+
+    let cancel = client.cancel_request("BTC-USDT-SWAP", Some("123".to_string()), None, Some(3));
+    assert_eq!(cancel["op"], "cancel-order");
+    assert_eq!(cancel["args"][0], json!({"instId": "BTC-USDT-SWAP", "ordId": "123"}));
+}
+
+#[tokio::test]
+async fn test_unconnected_client() {
+    let mut client = OkxClient::new();
+    assert_eq!(client.venue(), Venue::Okx);
+    assert!(!client.connected());
+
+    let err = client.insert(order(7), Some(1)).await.unwrap_err();
+    assert!(err.to_string().contains("private socket not connected"));
+    assert!(client.symbol_of(7).is_none());
+
+    // Amends and cancels need the instrument remembered from the insert
+    let err = client.cancel(None, Some(7), None).await.unwrap_err();
+    assert!(err.to_string().contains("Unknown instrument"));
+    let err = client.amend(1.0, 65001.0, None, Some(7), None).await.unwrap_err();
+    assert!(err.to_string().contains("Unknown instrument"));
+
+    assert!(client.receive().await.is_err());
+    assert!(client.open_orders(Some(2)).await.unwrap_err().to_string().contains("REST client not configured"));
+}
+
+#[test]
+fn test_channels() {
+    assert!(is_private_channel("orders"));
+    assert!(is_private_channel("positions"));
+    assert!(!is_private_channel("tickers:BTC-USDT-SWAP"));
+    assert_eq!(channel_arg("tickers:BTC-USDT-SWAP", "SWAP"), json!({"channel": "tickers", "instId": "BTC-USDT-SWAP"}));
+    assert_eq!(channel_arg("orders", "SWAP"), json!({"channel": "orders", "instType": "SWAP"}));
+    assert_eq!(channel_arg("account", "SWAP"), json!({"channel": "account"}));
+
+    assert_eq!("demo".parse::<OkxNetwork>().unwrap(), OkxNetwork::Demo);
+    assert_eq!("prod".parse::<OkxNetwork>().unwrap(), OkxNetwork::Live);
+    assert!("devnet".parse::<OkxNetwork>().is_err());
+    assert!(OkxNetwork::Demo.ws_url().contains("wspap"));
+    assert!(!OkxNetwork::Live.simulated());
+}
+
+#[tokio::test]
+#[ignore] // This test needs demo-trading keys in OKX_API_KEY_TEST, OKX_API_SECRET_TEST and OKX_PASSPHRASE_TEST
+async fn test_demo_login_and_subscribe() {
+    let network = OkxNetwork::Demo;
+    let keys = OkxKeys::load(&network).unwrap();
+    let mut client = OkxClient::new();
+    client.set_rest(OkxRestClient::new(network.rest_url(), keys.clone(), network.simulated()));
+    client.connect(network.ws_url()).await.unwrap();
+
+    client.login(keys.make_auth_token(chrono::Utc::now().timestamp()), None, Some(1)).await.unwrap();
+    let reply: Value = loop {
+        if let Some(text) = client.receive().await.unwrap() {
+            break serde_json::from_str(&text).unwrap();
+        }
+    };
+    assert_eq!(reply["event"], "login", "{}", reply);
+    assert_eq!(reply["code"], "0", "{}", reply);
+
+    client.subscribe(vec!["orders".to_string()], true, Some(2)).await.unwrap();
+    client.open_orders(Some(3)).await.unwrap();
+    client.disconnect().await.unwrap();
+}
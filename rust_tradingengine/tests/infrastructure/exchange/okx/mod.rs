@@ -0,0 +1,4 @@
+//! Tests for the OKX connector
+
+pub mod client_tests;
+pub mod parsers_tests;
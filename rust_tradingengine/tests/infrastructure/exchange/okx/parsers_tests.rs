@@ -0,0 +1,92 @@
+use serde_json::json;
+
+use cryptics_lab_bot::domain::enums::{MakerTaker, OrderSide, OrderStatus, OrderType, TimeInForce};
+use cryptics_lab_bot::infrastructure::exchange::okx::OkxParser;
+
+#[test]
+fn test_ticker_then_mark_price() {
+    let data = json!({
+        "instType": "SWAP", "instId": "BTC-USDT-SWAP", "last": "65000.5", "lastSz": "2",
+        "askPx": "65000.5", "askSz": "80", "bidPx": "65000.0", "bidSz": "120",
+        "open24h": "64000", "high24h": "66000", "low24h": "63500",
+        "volCcy24h": "12345.6", "vol24h": "1234560", "ts": "1792022400000",
+    });
+    let mut ticker = OkxParser::parse_ticker(&data).unwrap();
+    assert_eq!(ticker.instrument_name, "BTC-USDT-SWAP");
+    assert_eq!(ticker.best_bid_price, 65000.0);
+    assert_eq!(ticker.best_ask_amount, 80.0);
+    assert_eq!(ticker.volume_24h, 1234560.0);
+    assert!((ticker.change_24h - 1000.5 / 64000.0).abs() < 1e-12);
+    assert_eq!(ticker.mark_timestamp, 1792022400.0);
+    assert_eq!(ticker.delta, 1.0);
+
+    // The mark-price channel only carries the mark
+    OkxParser::apply_ticker(&json!({"instId": "BTC-USDT-SWAP", "markPx": "65001.2", "ts": "1792022400100"}), &mut ticker);
+    assert_eq!(ticker.mark_price, 65001.2);
+    assert_eq!(ticker.best_bid_price, 65000.0);
+    assert_eq!(ticker.mark_timestamp, 1792022400.1);
+}
+
+#[test]
+fn test_order_maps_to_ack() {
+    let data = json!({
+        "instId": "BTC-USDT-SWAP", "ordId": "312269865356374016", "clOrdId": "42", "px": "65010",
+        "sz": "10", "ordType": "post_only", "side": "sell", "accFillSz": "4", "state": "partially_filled",
+        "cancelSource": "", "cTime": "1792022400123",
+    });
+    let order = OkxParser::parse_order(&data).unwrap();
+    assert_eq!(order.order_id, "312269865356374016");
+    assert_eq!(order.client_order_id, Some(42));
+    assert!(matches!(order.direction, OrderSide::Sell));
+    assert!(matches!(order.status, OrderStatus::PartiallyFilled));
+    assert!(matches!(order.order_type, OrderType::Limit));
+    assert!(matches!(order.time_in_force, TimeInForce::GTC));
+    assert_eq!(order.remaining_amount, 6.0);
+    assert_eq!(order.delete_reason, None);
+    assert_eq!(order.create_time, 1792022400.123);
+
+    let mut cancelled = data.clone();
+    cancelled["state"] = json!("canceled");
+    cancelled["cancelSourceReason"] = json!("Order canceled by user");
+    let order = OkxParser::parse_order(&cancelled).unwrap();
+    assert!(matches!(order.status, OrderStatus::CancelledPartiallyFilled));
+    assert_eq!(order.delete_reason.as_deref(), Some("Order canceled by user"));
+
+    cancelled["accFillSz"] = json!("0");
+    cancelled["clOrdId"] = json!("");
+    let order = OkxParser::parse_order(&cancelled).unwrap();
+    assert!(matches!(order.status, OrderStatus::Cancelled));
+    assert_eq!(order.client_order_id, None);
+
+    cancelled["state"] = json!("expired");
+    assert!(OkxParser::parse_order(&cancelled).is_err());
+}
+
+#[test]
+fn test_fill_from_order_update() {
+    let data = json!({
+        "instId": "BTC-USDT-SWAP", "ordId": "312269865356374016", "clOrdId": "42", "side": "buy",
+        "tradeId": "9876", "fillPx": "65000.5", "fillSz": "2", "execType": "M", "fillTime": "1792022400500",
+    });
+    let trade = OkxParser::parse_fill(&data).unwrap().unwrap();
+    assert_eq!(trade.trade_id, "9876");
+    assert_eq!(trade.client_order_id, Some(42));
+    assert_eq!(trade.price, 65000.5);
+    assert_eq!(trade.amount, 2.0);
+    assert_eq!(trade.maker_taker, Some(MakerTaker::Maker));
+    assert_eq!(trade.time, 1792022400.5);
+    assert!(matches!(OkxParser::side(&data).unwrap(), OrderSide::Buy));
+
+    // Updates without a fill, such as the ack of a new order
+    assert!(OkxParser::parse_fill(&json!({"instId": "BTC-USDT-SWAP", "tradeId": ""})).unwrap().is_none());
+}
+
+#[test]
+fn test_position_sign() {
+    let net = OkxParser::parse_position(&json!({"instId": "BTC-USDT-SWAP", "pos": "-3", "posSide": "net"})).unwrap();
+    assert_eq!(net, ("BTC-USDT-SWAP".to_string(), -3.0));
+    let short = OkxParser::parse_position(&json!({"instId": "BTC-USDT-SWAP", "pos": "3", "posSide": "short"})).unwrap();
+    assert_eq!(short.1, -3.0);
+    let long = OkxParser::parse_position(&json!({"instId": "BTC-USDT-SWAP", "pos": "3", "posSide": "long"})).unwrap();
+    assert_eq!(long.1, 3.0);
+}
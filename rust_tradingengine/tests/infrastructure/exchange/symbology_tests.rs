@@ -0,0 +1,94 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use cryptics_lab_bot::config_loader::{SymbolOverride, SymbologyConfig};
+use cryptics_lab_bot::infrastructure::exchange::symbology::{ContractKind, OptionRight};
+use cryptics_lab_bot::infrastructure::exchange::{CanonicalInstrument, Symbology, Venue};
+
+fn expiry() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 12, 26).unwrap()
+}
+
+#[test]
+fn test_canonical_ids_round_trip() -> Result<()> {
+    for id in ["BTC-USD-PERP", "ETH-USDT-20251226-FUT", "BTC-USD-20251226-100000-C", "SOL-USDC-20251226-152.5-P"] {
+        let instrument: CanonicalInstrument = id.parse()?;
+        assert_eq!(instrument.to_string(), id);
+    }
+    let option: CanonicalInstrument = "BTC-USD-20251226-100000-C".parse()?;
+    assert_eq!(option.kind, ContractKind::Option { expiry: expiry(), strike: 100_000.0, right: OptionRight::Call });
+
+    for invalid in ["BTC-PERP", "-USD-PERP", "BTC-USD-2025-FUT", "BTC-USD-20251226-0-C", "BTC-USD-20251226-100000-X"] {
+        assert!(invalid.parse::<CanonicalInstrument>().is_err(), "{}", invalid);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_thalex_naming() -> Result<()> {
+    let symbology = Symbology::default();
+    let perp = CanonicalInstrument::perpetual("BTC", "USD");
+    assert_eq!(symbology.to_venue(&perp, Venue::Thalex)?, "BTC-PERPETUAL");
+    assert_eq!(symbology.from_venue(Venue::Thalex, "BTC-PERPETUAL")?, perp);
+
+    let future: CanonicalInstrument = "BTC-USD-20251226-FUT".parse()?;
+    assert_eq!(symbology.to_venue(&future, Venue::Thalex)?, "BTC-26DEC25");
+    assert_eq!(symbology.from_venue(Venue::Thalex, "BTC-26DEC25")?, future);
+
+    let option: CanonicalInstrument = "ETH-USD-20251226-3500-P".parse()?;
+    assert_eq!(symbology.to_venue(&option, Venue::Thalex)?, "ETH-26DEC25-3500-P");
+    assert_eq!(symbology.from_venue(Venue::Thalex, "ETH-26DEC25-3500-P")?, option);
+
+    assert!(symbology.to_venue(&CanonicalInstrument::perpetual("BTC", "USDT"), Venue::Thalex).is_err());
+    assert!(symbology.from_venue(Venue::Thalex, "BTCUSD").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_bybit_and_okx_perpetuals() -> Result<()> {
+    let symbology = Symbology::default();
+    let linear = CanonicalInstrument::perpetual("BTC", "USDT");
+    assert_eq!(symbology.to_venue(&linear, Venue::Bybit)?, "BTCUSDT");
+    assert_eq!(symbology.to_venue(&linear, Venue::Okx)?, "BTC-USDT-SWAP");
+    assert_eq!(symbology.from_venue(Venue::Bybit, "BTCUSDT")?, linear);
+    assert_eq!(symbology.from_venue(Venue::Okx, "BTC-USDT-SWAP")?, linear);
+
+    assert_eq!(symbology.from_venue(Venue::Bybit, "BTCUSD")?, CanonicalInstrument::perpetual("BTC", "USD"));
+    assert_eq!(symbology.from_venue(Venue::Bybit, "ETHPERP")?, CanonicalInstrument::perpetual("ETH", "USDC"));
+    assert_eq!(symbology.to_venue(&CanonicalInstrument::perpetual("ETH", "USDC"), Venue::Bybit)?, "ETHPERP");
+
+    let future: CanonicalInstrument = "BTC-USD-20251226-FUT".parse()?;
+    assert_eq!(symbology.to_venue(&future, Venue::Okx)?, "BTC-USD-251226");
+    assert_eq!(symbology.from_venue(Venue::Okx, "BTC-USD-251226-100000-C")?.to_string(), "BTC-USD-20251226-100000-C");
+    assert!(symbology.to_venue(&future, Venue::Bybit).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_translate_between_venues() -> Result<()> {
+    let symbology = Symbology::default();
+    assert_eq!(symbology.translate(Venue::Thalex, "BTC-26DEC25", Venue::Okx)?, "BTC-USD-251226");
+    assert_eq!(symbology.translate(Venue::Okx, "BTC-USD-SWAP", Venue::Thalex)?, "BTC-PERPETUAL");
+    Ok(())
+}
+
+#[test]
+fn test_overrides_take_precedence() -> Result<()> {
+    let symbology = Symbology::from_config(&SymbologyConfig {
+        overrides: vec![SymbolOverride {
+            canonical: "BTC-USDT-20251226-FUT".to_string(),
+            venue: Venue::Bybit,
+            symbol: "BTCUSDT-26DEC25".to_string(),
+        }],
+    })?;
+    let future: CanonicalInstrument = "BTC-USDT-20251226-FUT".parse()?;
+    assert_eq!(symbology.to_venue(&future, Venue::Bybit)?, "BTCUSDT-26DEC25");
+    assert_eq!(symbology.from_venue(Venue::Bybit, "BTCUSDT-26DEC25")?, future);
+    assert_eq!(symbology.to_venue(&future, Venue::Okx)?, "BTC-USDT-251226");
+
+    let invalid = SymbologyConfig {
+        overrides: vec![SymbolOverride { canonical: "BTCUSDT".to_string(), venue: Venue::Bybit, symbol: "BTCUSDT".to_string() }],
+    };
+    assert!(Symbology::from_config(&invalid).is_err());
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde_json::json;
+
+use cryptics_lab_bot::config_loader::TradeBackfillConfig;
+use cryptics_lab_bot::infrastructure::exchange::thalex::models::TradeHistoryResult;
+use cryptics_lab_bot::infrastructure::exchange::thalex::TradeBackfill;
+
+fn watermark_path() -> PathBuf {
+    std::env::temp_dir().join(format!("backfill_tests_{}", uuid::Uuid::new_v4())).join("trade_backfill.json")
+}
+
+fn backfill(path: &Path) -> TradeBackfill {
+    TradeBackfill::new(&TradeBackfillConfig {
+        enabled: true,
+        path: path.to_string_lossy().to_string(),
+        lookback_sec: 3600,
+        overlap_sec: 60,
+        page_size: 2,
+    }, false)
+}
+
+fn trade(trade_id: &str, time: f64) -> serde_json::Value {
+    json!({"trade_id": trade_id, "order_id": "o1", "instrument_name": "BTC-PERPETUAL",
+        "price": 65000.0, "amount": 0.1, "maker_taker": "maker", "time": time})
+}
+
+#[test]
+fn test_window_starts_at_watermark_within_lookback() {
+    let backfill = backfill(&watermark_path());
+    let now = 1792022400.0;
+    
+    // Without a watermark the whole lookback is fetched
+    assert_eq!(backfill.window_start(None, now), now - 3600.0);
+    // Recent trades are fetched again from a little before the watermark
+    assert_eq!(backfill.window_start(Some(now - 600.0), now), now - 660.0);
+    // A watermark older than the lookback is cut off
+    assert_eq!(backfill.window_start(Some(now - 86_400.0), now), now - 3600.0);
+}
+
+#[test]
+fn test_watermark_persisted_once_caught_up() -> Result<()> {
+    let path = watermark_path();
+    let backfill = backfill(&path);
+    assert_eq!(backfill.watermark()?, None);
+    
+    backfill.mark(1792022400.0)?;
+    assert_eq!(backfill.watermark()?, Some(1792022400.0));
+    
+    // A session whose backfill didn't complete leaves the watermark for the next one
+    backfill.session_ended(1792026000.0);
+    assert_eq!(backfill.watermark()?, Some(1792022400.0));
+    Ok(())
+}
+
+#[test]
+fn test_ordered_deduplicates_and_sorts() -> Result<()> {
+    let pages = vec![
+        TradeHistoryResult { trades: vec![trade("t3", 300.0), trade("t2", 200.0)], bookmark: Some("b1".to_string()) },
+        TradeHistoryResult { trades: vec![trade("t2", 200.0), trade("t1", 100.0), trade("t0", 50.0)], bookmark: None },
+    ];
+    let trades = TradeBackfill::ordered(&pages, 100.0, false)?;
+    
+    let ids: Vec<&str> = trades.iter().map(|trade| trade.trade_id.as_str()).collect();
+    assert_eq!(ids, vec!["t1", "t2", "t3"]);
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use serde_json::json;
+
+use cryptics_lab_bot::infrastructure::exchange::thalex::calls::{CallRegistry, PendingCall, RpcMethod, MAX_PENDING_CALLS};
+
+#[test]
+fn test_ids_are_unique_per_request() {
+    let calls = CallRegistry::default();
+    let first = calls.allocate(RpcMethod::Amend, Some("100".to_string()));
+    let second = calls.allocate(RpcMethod::Amend, Some("100".to_string()));
+    let third = calls.allocate(RpcMethod::Cancel, Some("100".to_string()));
+    
+    assert!(first < second && second < third);
+    assert_eq!(calls.outstanding(), 3);
+}
+
+#[test]
+fn test_complete_attributes_response() {
+    let calls = CallRegistry::default();
+    let insert = calls.allocate(RpcMethod::Insert, Some("101".to_string()));
+    let subscribe = calls.allocate(RpcMethod::PublicSubscribe, None);
+    
+    assert_eq!(calls.complete(insert), Some(PendingCall {
+        method: RpcMethod::Insert,
+        context: Some("101".to_string()),
+    }));
+    // Each response completes its request once
+    assert_eq!(calls.complete(insert), None);
+    assert_eq!(calls.get(subscribe).map(|c| c.method), Some(RpcMethod::PublicSubscribe));
+    assert_eq!(calls.outstanding(), 1);
+}
+
+#[test]
+fn test_unknown_id_is_not_attributed() {
+    let calls = CallRegistry::default();
+    assert_eq!(calls.complete(42), None);
+}
+
+#[test]
+fn test_oldest_unanswered_requests_are_forgotten() {
+    let calls = CallRegistry::default();
+    let first = calls.allocate(RpcMethod::Insert, None);
+    for _ in 0..MAX_PENDING_CALLS {
+        calls.allocate(RpcMethod::Insert, None);
+    }
+    
+    assert_eq!(calls.outstanding(), MAX_PENDING_CALLS);
+    assert_eq!(calls.get(first), None);
+}
+
+#[test]
+fn test_method_names() {
+    assert_eq!(RpcMethod::CancelAll.to_string(), "private/cancel_all");
+    assert_eq!(RpcMethod::Login.as_str(), "public/login");
+}
+
+#[tokio::test]
+async fn test_waiter_receives_its_response() {
+    let calls = CallRegistry::default();
+    let insert = calls.allocate_awaited(RpcMethod::Insert, Some("101".to_string()));
+    let cancel = calls.allocate_awaited(RpcMethod::Cancel, Some("100".to_string()));
+
+    // Responses are matched by id, whatever order they arrive in
+    let cancel_id = cancel.id();
+    assert_eq!(calls.resolve(cancel_id, Err(&json!({"message": "order not found"}))).map(|c| c.method), Some(RpcMethod::Cancel));
+    assert_eq!(calls.resolve(insert.id(), Ok(&json!({"status": "open"}))).map(|c| c.method), Some(RpcMethod::Insert));
+
+    assert_eq!(insert.response(Duration::from_secs(1)).await.unwrap(), json!({"status": "open"}));
+    let error = cancel.response(Duration::from_secs(1)).await.unwrap_err();
+    assert!(error.to_string().contains("order not found"), "{}", error);
+    assert_eq!(calls.outstanding(), 0);
+}
+
+#[tokio::test]
+async fn test_waiter_fails_when_the_call_is_dropped() {
+    let calls = CallRegistry::default();
+    let insert = calls.allocate_awaited(RpcMethod::Insert, None);
+    calls.complete(insert.id());
+
+    let error = insert.response(Duration::from_secs(1)).await.unwrap_err();
+    assert!(error.to_string().contains("dropped"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_waiter_times_out_without_a_response() {
+    let calls = CallRegistry::default();
+    let insert = calls.allocate_awaited(RpcMethod::Insert, None);
+    let id = insert.id();
+
+    assert!(insert.response(Duration::from_millis(10)).await.is_err());
+    // A late answer still completes the request
+    assert!(calls.resolve(id, Ok(&json!({}))).is_some());
+}
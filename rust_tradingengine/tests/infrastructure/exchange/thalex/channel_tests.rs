@@ -0,0 +1,99 @@
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+
+#[test]
+fn test_parse_channels() {
+    assert_eq!("session.orders".parse::<Channel>().unwrap(), Channel::Orders);
+    assert_eq!("account.trade_history".parse::<Channel>().unwrap(), Channel::Trades);
+    assert_eq!("account.portfolio".parse::<Channel>().unwrap(), Channel::Portfolio);
+    assert_eq!("user.inbox_notifications".parse::<Channel>().unwrap(), Channel::AccountEvents);
+    assert_eq!("price_index.BTCUSD".parse::<Channel>().unwrap(), Channel::Index("BTCUSD".to_string()));
+    assert_eq!("ticker.BTC-PERPETUAL.raw".parse::<Channel>().unwrap(), Channel::ticker("BTC-PERPETUAL"));
+    assert_eq!("recent_trades.BTC-PERPETUAL.all".parse::<Channel>().unwrap(), Channel::recent_trades("BTC-PERPETUAL"));
+    assert_eq!(
+        "book.BTC-PERPETUAL.none.10.100ms".parse::<Channel>().unwrap(),
+        Channel::Book {
+            instrument: "BTC-PERPETUAL".to_string(),
+            grouping: "none".to_string(),
+            depth: 10,
+            delay: "100ms".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_channel_round_trip() {
+    let names = [
+        "session.orders",
+        "account.trade_history",
+        "account.portfolio",
+        "user.inbox_notifications",
+        "price_index.BTCUSD",
+        "ticker.BTC-PERPETUAL.raw",
+        "ticker.ETH-PERPETUAL.1000ms",
+        "recent_trades.BTC-PERPETUAL.all",
+        "recent_trades.BTC-PERPETUAL.block",
+        "book.BTC-PERPETUAL.1.5.raw",
+        "book.BTC-PERPETUAL.0.5.10.100ms",
+    ];
+    
+    for name in names {
+        let channel: Channel = name.parse().unwrap();
+        assert_eq!(channel.to_string(), name);
+    }
+}
+
+#[test]
+fn test_parse_invalid_channels() {
+    assert!("".parse::<Channel>().is_err());
+    assert!("ticker".parse::<Channel>().is_err());
+    assert!("ticker..raw".parse::<Channel>().is_err());
+    assert!("book.BTC-PERPETUAL.none.ten.raw".parse::<Channel>().is_err());
+    assert!("book.BTC-PERPETUAL.10.raw".parse::<Channel>().is_err());
+    assert!("book..none.10.raw".parse::<Channel>().is_err());
+    assert!("account.unknown".parse::<Channel>().is_err());
+    assert!("recent_trades.BTC-PERPETUAL".parse::<Channel>().is_err());
+}
+
+#[test]
+fn test_channel_privacy_and_instrument() {
+    assert!(Channel::Orders.is_private());
+    assert!(Channel::Trades.is_private());
+    assert!(Channel::Portfolio.is_private());
+    assert!(Channel::AccountEvents.is_private());
+    assert!(!Channel::ticker("BTC-PERPETUAL").is_private());
+    assert!(!Channel::Index("BTCUSD".to_string()).is_private());
+    assert!(!Channel::recent_trades("BTC-PERPETUAL").is_private());
+    for channel in [Channel::Orders, Channel::Trades, Channel::Portfolio, Channel::AccountEvents, Channel::ticker("BTC-PERPETUAL"), Channel::recent_trades("BTC-PERPETUAL")] {
+        assert_eq!(Channel::is_private_name(&channel.to_string()), channel.is_private(), "{}", channel);
+    }
+    
+    assert_eq!(Channel::book("BTC-PERPETUAL", 10).instrument(), Some("BTC-PERPETUAL"));
+    assert_eq!(Channel::recent_trades("BTC-PERPETUAL").instrument(), Some("BTC-PERPETUAL"));
+    assert_eq!(Channel::Orders.instrument(), None);
+}
+
+#[test]
+fn test_book_validation() {
+    assert!(Channel::book_with("BTC-PERPETUAL", "none", 10, "raw").is_ok());
+    assert!(Channel::book_with("BTC-PERPETUAL", "5", 50, "100ms").is_ok());
+    assert!(Channel::book_with("BTC-PERPETUAL", "0.5", 10, "raw").is_ok());
+    
+    assert!(Channel::book_with("BTC-PERPETUAL", "none", 7, "raw").is_err());
+    assert!(Channel::book_with("BTC-PERPETUAL", "wide", 10, "raw").is_err());
+    assert!(Channel::book_with("BTC-PERPETUAL", "none", 10, "3ms").is_err());
+}
+
+#[test]
+fn test_fractional_grouping_round_trips() {
+    let channel = Channel::book_with("BTC-PERPETUAL", "0.5", 10, "raw").unwrap();
+    let parsed: Channel = channel.to_string().parse().unwrap();
+    assert_eq!(parsed, channel);
+    assert_eq!(parsed.instrument(), Some("BTC-PERPETUAL"));
+}
+
+#[test]
+fn test_parsed_channel_validation() {
+    let channel: Channel = "ticker.BTC-PERPETUAL.slow".parse().unwrap();
+    assert!(channel.validate().is_err());
+    assert!(Channel::Orders.validate().is_ok());
+}
@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+use std::time::Duration;
+use serde_json::json;
+
+use cryptics_lab_bot::domain::enums::{OrderSide, OrderType, TimeInForce};
+use cryptics_lab_bot::domain::model::exchange::OrderRequest;
+use cryptics_lab_bot::domain::model::quote::{MassQuote, SideQuote};
+use cryptics_lab_bot::infrastructure::exchange::thalex::calls::RpcMethod;
+use cryptics_lab_bot::infrastructure::exchange::thalex::client::ThalexClient;
+use cryptics_lab_bot::infrastructure::exchange::thalex::error::ClientError;
+use cryptics_lab_bot::infrastructure::exchange::thalex::session::SessionClient;
+
+#[test]
+fn test_timeout_detected_through_anyhow() {
+    let error: anyhow::Error = ClientError::Timeout {
+        operation: "private/insert".to_string(),
+        after: Duration::from_secs(5),
+    }.into();
+    
+    assert!(ClientError::is_timeout(&error));
+    assert_eq!(error.to_string(), "private/insert timed out after 5s");
+    assert!(!ClientError::is_timeout(&ClientError::NotConnected.into()));
+    assert!(!ClientError::is_timeout(&anyhow::anyhow!("other")));
+}
+
+#[tokio::test]
+async fn test_unconnected_client_fails_fast() {
+    let mut client = ThalexClient::new();
+    client.set_request_timeout(Duration::from_millis(10));
+    
+    let error = client.ping().await.unwrap_err();
+    assert!(matches!(error.downcast_ref::<ClientError>(), Some(ClientError::NotConnected)));
+    
+    let error = client.receive().await.unwrap_err();
+    assert!(matches!(error.downcast_ref::<ClientError>(), Some(ClientError::NotConnected)));
+}
+
+#[tokio::test]
+async fn test_requests_queue_while_disconnected() {
+    let mut client = ThalexClient::new();
+    let insert_id = client.calls().allocate(RpcMethod::Insert, Some("1".to_string()));
+    client.insert(OrderRequest {
+        symbol: "BTC-PERPETUAL".to_string(),
+        side: OrderSide::Buy,
+        order_type: OrderType::Limit,
+        quantity: 0.1,
+        price: Some(50_000.0),
+        client_order_id: Some(1),
+        time_in_force: Some(TimeInForce::GTC),
+        label: None,
+    }, Some(insert_id)).await.unwrap();
+    client.cancel(None, Some(1), None).await.unwrap();
+    
+    // Nothing can be written yet, so both wait for the socket
+    assert_eq!(client.outbound_depth(), 2);
+    assert_eq!(client.flush().await.unwrap(), 0);
+    assert_eq!(client.calls().outstanding(), 1);
+}
+
+#[tokio::test]
+async fn test_queued_inserts_dropped_by_client_order_id() {
+    let mut client = ThalexClient::new();
+    for client_order_id in [1, 2] {
+        let id = client.calls().allocate(RpcMethod::Insert, Some(client_order_id.to_string()));
+        client.insert(OrderRequest {
+            symbol: "BTC-PERPETUAL".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: 0.1,
+            price: Some(50_000.0),
+            client_order_id: Some(client_order_id),
+            time_in_force: Some(TimeInForce::GTC),
+            label: None,
+        }, Some(id)).await.unwrap();
+    }
+    
+    assert_eq!(client.drop_queued_inserts(&HashSet::from([2, 3])), vec![2]);
+    assert_eq!(client.outbound_depth(), 1);
+    // Without a rejection channel the dropped insert's call is just completed
+    assert_eq!(client.calls().outstanding(), 1);
+}
+
+#[test]
+fn test_amend_params() {
+    // `private/amend` takes both parts; the amount is the order's new total, not what remains
+    let params = ThalexClient::amend_params(0.175, 50_010.0, None, Some(1)).unwrap();
+    assert_eq!(params, json!({"client_order_id": 1, "price": 50_010.0, "amount": 0.175}));
+
+    let params = ThalexClient::amend_params(0.2, 50_010.0, Some("abc".to_string()), None).unwrap();
+    assert_eq!(params, json!({"order_id": "abc", "price": 50_010.0, "amount": 0.2}));
+}
+
+#[tokio::test]
+async fn test_amend_needs_exactly_one_id() {
+    let mut client = ThalexClient::new();
+    for (order_id, client_order_id) in [(None, None), (Some("abc".to_string()), Some(1))] {
+        let err = client.amend(0.05, 50_010.0, order_id, client_order_id, None).await.unwrap_err();
+        assert!(err.to_string().contains("Exactly one"));
+    }
+    assert_eq!(client.outbound_depth(), 0);
+
+    client.amend(0.05, 50_010.0, None, Some(1), None).await.unwrap();
+    assert_eq!(client.outbound_depth(), 1);
+}
+
+#[tokio::test]
+async fn test_mass_quote_needs_an_instrument() {
+    let mut client = ThalexClient::new();
+    assert!(client.mass_quote(Vec::new(), None, true, None).await.is_err());
+    assert_eq!(client.outbound_depth(), 0);
+
+    let quote = MassQuote {
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        bid: Some(SideQuote::new(49_975.0, 0.2)),
+        ask: None,
+    };
+    client.mass_quote(vec![quote], Some("P".to_string()), true, None).await.unwrap();
+    assert_eq!(client.outbound_depth(), 1);
+}
+
+#[test]
+fn test_mass_quote_params_match_the_venue() {
+    // Request shape of `private/mass_quote` in the Thalex API docs: one `{p, a}` per
+    // side and instrument, a side left out stays as it is and a zero amount pulls it
+    let expected: serde_json::Value = serde_json::from_str(include_str!("fixtures/mass_quote_params.json")).unwrap();
+    let quotes = vec![
+        MassQuote {
+            instrument_name: "BTC-PERPETUAL".to_string(),
+            bid: Some(SideQuote::new(49_975.0, 0.2)),
+            ask: Some(SideQuote::new(50_025.0, 0.2)),
+        },
+        MassQuote { instrument_name: "ETH-PERPETUAL".to_string(), bid: Some(SideQuote::new(2_499.5, 0.0)), ask: None },
+    ];
+    assert_eq!(ThalexClient::mass_quote_params(&quotes, Some("P".to_string()), true), expected);
+}
+
+#[test]
+fn test_throttle_expires() {
+    let mut client = ThalexClient::new();
+    assert!(!client.is_throttled());
+    client.throttle(Duration::from_secs(60));
+    assert!(client.is_throttled());
+    client.throttle(Duration::ZERO);
+    assert!(!client.is_throttled());
+}
+
+#[tokio::test]
+async fn test_client_behind_the_session_trait() {
+    let mut client = ThalexClient::new();
+    let session: &mut dyn SessionClient = &mut client;
+    assert!(session.take_reader().is_none());
+    
+    let id = session.calls().allocate(RpcMethod::Instruments, None);
+    session.instruments(Some(id)).await.unwrap();
+    assert_eq!(session.outbound_depth(), 1);
+    // Nothing is written while disconnected, so only the expiry of order requests could wake a drain
+    assert_eq!(session.next_flush(), None);
+    assert_eq!(session.discard_outbound(), 1);
+    assert_eq!(session.calls().outstanding(), 0);
+}
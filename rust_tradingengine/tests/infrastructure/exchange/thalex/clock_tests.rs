@@ -0,0 +1,14 @@
+use cryptics_lab_bot::infrastructure::exchange::thalex::clock::ClockStatus;
+
+#[test]
+fn test_drift_classification() {
+    assert_eq!(ClockStatus::from_drift(0.2, 1.5, 5.0), ClockStatus::InSync);
+    assert_eq!(ClockStatus::from_drift(2.0, 1.5, 5.0), ClockStatus::Drifted);
+    assert_eq!(ClockStatus::from_drift(5.0, 1.5, 5.0), ClockStatus::Halt);
+}
+
+#[test]
+fn test_negative_drift_uses_magnitude() {
+    assert_eq!(ClockStatus::from_drift(-2.0, 1.5, 5.0), ClockStatus::Drifted);
+    assert_eq!(ClockStatus::from_drift(-10.0, 1.5, 5.0), ClockStatus::Halt);
+}
@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+use cryptics_lab_bot::infrastructure::exchange::thalex::liveness::Liveness;
+
+#[test]
+fn test_pong_measures_rtt() {
+    let liveness = Liveness::default();
+    let sent = Instant::now();
+    let payload = liveness.ping_sent(sent);
+    assert_eq!(liveness.missed_pongs(), 1);
+    
+    let rtt = liveness.pong_received(&payload, sent + Duration::from_millis(40));
+    assert_eq!(rtt, Some(Duration::from_millis(40)));
+    assert_eq!(liveness.last_rtt(), Some(Duration::from_millis(40)));
+    assert_eq!(liveness.missed_pongs(), 0);
+}
+
+#[test]
+fn test_unanswered_pings_accumulate() {
+    let liveness = Liveness::default();
+    let now = Instant::now();
+    for _ in 0..3 {
+        liveness.ping_sent(now);
+    }
+    assert_eq!(liveness.missed_pongs(), 3);
+}
+
+#[test]
+fn test_late_pong_clears_older_pings() {
+    let liveness = Liveness::default();
+    let now = Instant::now();
+    liveness.ping_sent(now);
+    let second = liveness.ping_sent(now);
+    liveness.ping_sent(now);
+    
+    assert!(liveness.pong_received(&second, now).is_some());
+    assert_eq!(liveness.missed_pongs(), 1);
+}
+
+#[test]
+fn test_unknown_pong_ignored() {
+    let liveness = Liveness::default();
+    liveness.ping_sent(Instant::now());
+    
+    assert_eq!(liveness.pong_received(&[], Instant::now()), None);
+    assert_eq!(liveness.pong_received(&42u64.to_be_bytes(), Instant::now()), None);
+    assert_eq!(liveness.missed_pongs(), 1);
+}
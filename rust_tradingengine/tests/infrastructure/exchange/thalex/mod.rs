@@ -1,4 +1,17 @@
 //! Tests for Thalex exchange components
 
 // Import test modules
+pub mod backfill_tests;
+pub mod calls_tests;
+pub mod channel_tests;
+pub mod client_tests;
+pub mod clock_tests;
+pub mod keys_tests;
+pub mod liveness_tests;
+pub mod models_tests;
+pub mod outbound_tests;
+pub mod parsers_proptest_tests;
 pub mod parsers_tests;
+pub mod rate_limit_tests;
+pub mod rest_tests;
+pub mod subscriptions_tests;
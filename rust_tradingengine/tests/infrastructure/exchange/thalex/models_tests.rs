@@ -0,0 +1,184 @@
+use serde_json::json;
+
+use cryptics_lab_bot::domain::enums::OptionType;
+use cryptics_lab_bot::infrastructure::exchange::thalex::calls::RpcMethod;
+use cryptics_lab_bot::infrastructure::exchange::thalex::models::RpcResult;
+
+#[test]
+fn test_instruments_result() {
+    let result = json!([
+        {"instrument_name": "BTC-PERPETUAL", "type": "perpetual", "underlying": "BTCUSD", "tick_size": 1.0, "volume_tick_size": 0.001},
+        {"instrument_name": "ETH-PERPETUAL", "type": "perpetual", "underlying": "ETHUSD", "tick_size": 0.1},
+    ]);
+    
+    let RpcResult::Instruments(instruments) = RpcResult::parse(RpcMethod::Instruments, &result).unwrap() else {
+        panic!("expected instruments");
+    };
+    assert_eq!(instruments.len(), 2);
+    assert_eq!(instruments[0].volume_tick_size, Some(0.001));
+    assert_eq!(instruments[1].volume_tick_size, None);
+}
+
+#[test]
+fn test_order_result() {
+    let result = json!({
+        "order_id": "0012AB",
+        "client_order_id": 1042,
+        "instrument_name": "BTC-PERPETUAL",
+        "direction": "buy",
+        "price": 50000.0,
+        "amount": 0.2,
+        "filled_amount": 0.05,
+        "remaining_amount": 0.15,
+        "status": "partially_filled",
+    });
+    
+    let RpcResult::Order(order) = RpcResult::parse(RpcMethod::Insert, &result).unwrap() else {
+        panic!("expected order result");
+    };
+    assert_eq!(order.client_order_id, Some(1042));
+    assert_eq!(order.status, "partially_filled");
+    assert_eq!(order.remaining_amount, Some(0.15));
+}
+
+#[test]
+fn test_acknowledgement_results() {
+    assert!(matches!(RpcResult::parse(RpcMethod::SetCancelOnDisconnect, &json!(null)).unwrap(), RpcResult::CancelOnDisconnect));
+    assert!(matches!(RpcResult::parse(RpcMethod::CancelSession, &json!(null)).unwrap(), RpcResult::CancelSession));
+    
+    let RpcResult::CancelAll(cancelled) = RpcResult::parse(RpcMethod::CancelAll, &json!({"n_cancelled": 3})).unwrap() else {
+        panic!("expected cancel all result");
+    };
+    assert_eq!(cancelled.n_cancelled, Some(3));
+    
+    let RpcResult::Subscribe(channels) = RpcResult::parse(RpcMethod::PrivateSubscribe, &json!(["session.orders"])).unwrap() else {
+        panic!("expected subscribe result");
+    };
+    assert_eq!(channels, vec!["session.orders".to_string()]);
+}
+
+#[test]
+fn test_mass_quote_result() {
+    let result = json!({"n_success": 3, "n_fail": 1, "errors": [{"code": 3, "message": "price out of collar"}]});
+    let RpcResult::MassQuote(quoted) = RpcResult::parse(RpcMethod::MassQuote, &result).unwrap() else {
+        panic!("expected mass quote result");
+    };
+    assert_eq!((quoted.n_success, quoted.n_fail), (3, 1));
+    assert_eq!(quoted.errors.len(), 1);
+}
+
+#[test]
+fn test_open_orders_result() {
+    let result = json!([
+        {"order_id": "o1", "client_order_id": 100, "label": "P:tag", "status": "open"},
+        {"order_id": "o2", "status": "partially_filled"},
+    ]);
+    let RpcResult::OpenOrders(orders) = RpcResult::parse(RpcMethod::OpenOrders, &result).unwrap() else {
+        panic!("expected open orders result");
+    };
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders[0].label.as_deref(), Some("P:tag"));
+    assert_eq!(orders[1].label, None);
+}
+
+#[test]
+fn test_portfolio_result() {
+    let result = json!([
+        {"instrument_name": "BTC-PERPETUAL", "position": -0.4, "mark_price": 65013.0, "average_price": 64900.0},
+        {"instrument_name": "BTC-27DEC24", "position": 0.1},
+    ]);
+    let RpcResult::Portfolio(positions) = RpcResult::parse(RpcMethod::Portfolio, &result).unwrap() else {
+        panic!("expected portfolio result");
+    };
+    assert_eq!(positions.len(), 2);
+    assert_eq!((positions[0].instrument_name.as_str(), positions[0].position), ("BTC-PERPETUAL", -0.4));
+    assert_eq!(positions[1].mark_price, None);
+}
+
+#[test]
+fn test_account_summary_result() {
+    let result = json!({
+        "cash": [
+            {"currency": "USDC", "balance": 25000.0, "collateral_factor": 1.0},
+            {"currency": "BTC", "balance": 0.5},
+        ],
+        "unrealised_pnl": -120.0,
+        "cash_collateral": 57000.0,
+        "margin": 56880.0,
+        "required_margin": 4300.0,
+        "remaining_margin": 52580.0,
+        "session_realised_pnl": 85.0,
+    });
+    let RpcResult::AccountSummary(summary) = RpcResult::parse(RpcMethod::AccountSummary, &result).unwrap() else {
+        panic!("expected account summary result");
+    };
+    let summary = summary.to_domain(1792022400.0);
+    assert_eq!(summary.balances.len(), 2);
+    assert_eq!(summary.balance("BTC"), Some(0.5));
+    assert_eq!(summary.balances[1].collateral_factor, None);
+    assert_eq!(summary.remaining_margin, 52580.0);
+    assert!((summary.margin_utilisation() - 4300.0 / 56880.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_unexpected_shape_falls_back_to_raw() {
+    let result = json!({"unexpected": true});
+    assert!(RpcResult::parse(RpcMethod::Instruments, &result).is_err());
+    assert!(matches!(RpcResult::parse_or_raw(RpcMethod::Instruments, &result), RpcResult::Other(v) if v == result));
+}
+
+#[test]
+fn test_option_details_from_name() {
+    let result = json!([
+        {"instrument_name": "BTC-27DEC24-100000-C", "type": "option", "underlying": "BTCUSD", "tick_size": 5.0, "volume_tick_size": 0.01},
+        {"instrument_name": "BTC-27DEC24-90000-P", "type": "option", "underlying": "BTCUSD", "tick_size": 5.0,
+            "strike_price": 90000.0, "option_type": "put", "expiration_timestamp": 1735286400.0},
+    ]);
+    
+    let RpcResult::Instruments(instruments) = RpcResult::parse(RpcMethod::Instruments, &result).unwrap() else {
+        panic!("expected instruments");
+    };
+    let call = instruments[0].clone().with_name_details().unwrap();
+    assert!(call.is_option());
+    assert_eq!(call.strike_price, Some(100_000.0));
+    assert_eq!(call.option_type, Some(OptionType::Call));
+    // 2024-12-27 08:00 UTC
+    assert_eq!(call.expiration_timestamp, Some(1735286400.0));
+    
+    let put = instruments[1].clone().with_name_details().unwrap();
+    assert_eq!(put.option_type, Some(OptionType::Put));
+    assert_eq!(put.days_to_expiry(1735286400.0 - 86_400.0), Some(1.0));
+}
+
+#[test]
+fn test_option_name_without_details_is_rejected() {
+    let result = json!([
+        {"instrument_name": "BTC-27DEC24-C", "type": "option", "underlying": "BTCUSD", "tick_size": 5.0},
+        {"instrument_name": "BTC-27XYZ24-100000-C", "type": "option", "underlying": "BTCUSD", "tick_size": 5.0},
+    ]);
+    
+    let RpcResult::Instruments(instruments) = RpcResult::parse(RpcMethod::Instruments, &result).unwrap() else {
+        panic!("expected instruments");
+    };
+    assert!(instruments[0].clone().with_name_details().is_err());
+    assert!(instruments[1].clone().with_name_details().is_err());
+}
+
+#[test]
+fn test_conform_moves_orders_onto_ticks() {
+    let result = json!([
+        {"instrument_name": "BTC-27DEC24-100000-C", "type": "option", "underlying": "BTCUSD", "tick_size": 5.0, "volume_tick_size": 0.01},
+    ]);
+    
+    let RpcResult::Instruments(instruments) = RpcResult::parse(RpcMethod::Instruments, &result).unwrap() else {
+        panic!("expected instruments");
+    };
+    let option = instruments[0].clone().with_name_details().unwrap();
+    let before_expiry = 1735286400.0 - 3600.0;
+    let (price, amount) = option.conform(1_233.0, 0.057, before_expiry).unwrap();
+    assert_eq!(price, 1_235.0);
+    assert!((amount - 0.05).abs() < 1e-12);
+    
+    assert!(option.conform(1_233.0, 0.005, before_expiry).is_err());
+    assert!(option.conform(1_233.0, 0.05, 1735286400.0).is_err());
+}
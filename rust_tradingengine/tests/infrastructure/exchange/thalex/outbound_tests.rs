@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use cryptics_lab_bot::infrastructure::exchange::thalex::outbound::{
+    is_rate_limit_error, OutboundQueue, OutboundRequest, RequestPriority,
+};
+
+fn request(method: &str, id: u64, queued_at: Instant) -> OutboundRequest {
+    OutboundRequest { method: method.to_string(), id: Some(id), text: String::new(), queued_at }
+}
+
+#[test]
+fn test_priority_of_method() {
+    assert_eq!(RequestPriority::of("public/login"), RequestPriority::Session);
+    assert_eq!(RequestPriority::of("private/subscribe"), RequestPriority::Session);
+    assert_eq!(RequestPriority::of("private/cancel_all"), RequestPriority::Cancel);
+    assert_eq!(RequestPriority::of("private/cancel"), RequestPriority::Cancel);
+    assert_eq!(RequestPriority::of("private/amend"), RequestPriority::Amend);
+    assert_eq!(RequestPriority::of("private/mass_quote"), RequestPriority::Amend);
+    assert_eq!(RequestPriority::of("private/insert"), RequestPriority::Insert);
+}
+
+#[test]
+fn test_cancels_preempt_amends_preempt_inserts() {
+    let now = Instant::now();
+    let mut queue = OutboundQueue::default();
+    queue.push(request("private/insert", 1, now));
+    queue.push(request("private/amend", 2, now));
+    queue.push(request("private/insert", 3, now));
+    queue.push(request("private/cancel", 4, now));
+    queue.push(request("public/login", 5, now));
+    queue.push(request("private/cancel", 6, now));
+    
+    assert_eq!(queue.len(), 6);
+    assert_eq!(queue.depth(RequestPriority::Insert), 2);
+    
+    let order: Vec<u64> = std::iter::from_fn(|| queue.pop()).filter_map(|r| r.id).collect();
+    assert_eq!(order, vec![5, 4, 6, 2, 1, 3]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn test_stale_amends_and_inserts_expire() {
+    let now = Instant::now();
+    let old = now - Duration::from_secs(3);
+    let mut queue = OutboundQueue::new(Duration::from_secs(2));
+    queue.push(request("private/insert", 1, old));
+    queue.push(request("private/amend", 2, old));
+    queue.push(request("private/cancel", 3, old));
+    queue.push(request("private/insert", 4, now));
+    
+    let expired: Vec<u64> = queue.expire(now).into_iter().filter_map(|r| r.id).collect();
+    assert_eq!(expired, vec![2, 1]);
+    
+    // Cancels never go stale
+    assert_eq!(queue.depth(RequestPriority::Cancel), 1);
+    assert_eq!(queue.depth(RequestPriority::Insert), 1);
+}
+
+#[test]
+fn test_next_expiry_is_the_oldest_amend_or_insert() {
+    let now = Instant::now();
+    let mut queue = OutboundQueue::new(Duration::from_secs(2));
+    queue.push(request("private/cancel", 1, now - Duration::from_secs(5)));
+    assert_eq!(queue.next_expiry(), None);
+    
+    queue.push(request("private/insert", 2, now - Duration::from_secs(1)));
+    queue.push(request("private/amend", 3, now));
+    let expiry = queue.next_expiry().unwrap();
+    assert!(queue.expire(expiry - Duration::from_millis(1)).is_empty());
+    let expired: Vec<u64> = queue.expire(expiry).into_iter().filter_map(|r| r.id).collect();
+    assert_eq!(expired, vec![2]);
+}
+
+#[test]
+fn test_rate_limit_error_detection() {
+    assert!(is_rate_limit_error(&json!({"code": 1, "message": "Rate limit exceeded"})));
+    assert!(is_rate_limit_error(&json!({"message": "request throttled"})));
+    assert!(!is_rate_limit_error(&json!({"code": 2, "message": "insufficient margin"})));
+    assert!(!is_rate_limit_error(&json!("oops")));
+}
+
+#[test]
+fn test_refused_priorities_are_skipped_in_order() {
+    let now = Instant::now();
+    let mut queue = OutboundQueue::default();
+    queue.push(request("private/amend", 1, now));
+    queue.push(request("private/amend", 2, now));
+    queue.push(request("private/insert", 3, now));
+    
+    // With the amend budget spent, inserts still go out and amends keep their order
+    let popped = queue.pop_admitted(|r| r.method != "private/amend");
+    assert_eq!(popped.and_then(|r| r.id), Some(3));
+    assert!(queue.pop_admitted(|r| r.method != "private/amend").is_none());
+    assert_eq!(queue.pop_admitted(|_| true).and_then(|r| r.id), Some(1));
+}
+
+#[test]
+fn test_only_amends_and_inserts_are_dropped() {
+    let now = Instant::now();
+    let mut queue = OutboundQueue::default();
+    queue.push(request("private/cancel", 1, now));
+    queue.push(request("private/amend", 2, now));
+    queue.push(request("private/insert", 3, now));
+    queue.push(request("private/insert", 4, now));
+    
+    let dropped: Vec<u64> = queue.drop_expiring(|r| r.id != Some(4)).into_iter().filter_map(|r| r.id).collect();
+    assert_eq!(dropped, vec![2, 3]);
+    let left: Vec<u64> = std::iter::from_fn(|| queue.pop()).filter_map(|r| r.id).collect();
+    assert_eq!(left, vec![1, 4]);
+}
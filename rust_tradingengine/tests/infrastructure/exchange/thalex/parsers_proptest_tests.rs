@@ -0,0 +1,188 @@
+//! Property-based tests: the parsers either produce a faithful struct or a descriptive
+//! error for any shape of exchange JSON, and never panic
+
+use proptest::prelude::*;
+use serde_json::{json, Map, Value};
+
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
+
+/// Any JSON value, nested a few levels deep
+fn any_json() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(|n| json!(n)),
+        any::<f64>().prop_map(|n| json!(n)),
+        ".{0,12}".prop_map(Value::String),
+    ];
+    leaf.prop_recursive(3, 16, 4, |inner| prop_oneof![
+        prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+        prop::collection::hash_map(".{0,6}", inner, 0..4)
+            .prop_map(|m| Value::Object(m.into_iter().collect())),
+    ])
+}
+
+/// A field that is usually well-formed but may be missing, null or of the wrong type
+fn field(valid: impl Strategy<Value = Value> + 'static) -> impl Strategy<Value = Option<Value>> {
+    prop_oneof![
+        6 => valid.prop_map(Some),
+        1 => Just(None),
+        1 => Just(Some(Value::Null)),
+        2 => any_json().prop_map(Some),
+    ]
+}
+
+fn one_of(values: &'static [&'static str]) -> impl Strategy<Value = Value> {
+    prop_oneof![
+        4 => prop::sample::select(values).prop_map(|s| json!(s)),
+        1 => "[a-z_]{0,20}".prop_map(Value::String),
+    ]
+}
+
+fn price() -> impl Strategy<Value = Value> {
+    (0.0f64..1_000_000.0).prop_map(|p| json!(p))
+}
+
+/// Build an object from optional fields, leaving missing ones out
+fn object(fields: Vec<(&'static str, Option<Value>)>) -> Value {
+    let map: Map<String, Value> = fields
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|v| (name.to_string(), v)))
+        .collect();
+    Value::Object(map)
+}
+
+prop_compose! {
+    fn order_json()(
+        direction in field(one_of(&["buy", "sell"])),
+        status in field(one_of(&["open", "partially_filled", "cancelled", "cancelled_partially_filled", "filled"])),
+        order_type in field(one_of(&["limit", "market"])),
+        time_in_force in field(one_of(&["good_till_cancelled", "immediate_or_cancel"])),
+        price in field(price()),
+        amount in field(price()),
+        order_id in field("[a-z0-9-]{1,12}".prop_map(Value::String)),
+        client_order_id in field(any::<u64>().prop_map(|n| json!(n))),
+        delete_reason in field("[a-z_]{1,12}".prop_map(Value::String)),
+    ) -> Value {
+        object(vec![
+            ("direction", direction),
+            ("status", status),
+            ("order_type", order_type),
+            ("time_in_force", time_in_force),
+            ("price", price),
+            ("amount", amount),
+            ("order_id", order_id),
+            ("client_order_id", client_order_id),
+            ("delete_reason", delete_reason),
+            ("instrument_name", Some(json!("BTC-PERPETUAL"))),
+        ])
+    }
+}
+
+prop_compose! {
+    fn trade_json()(
+        instrument_name in field("[A-Z-]{1,16}".prop_map(Value::String)),
+        price in field(price()),
+        amount in field(price()),
+        maker_taker in field(one_of(&["maker", "taker"])),
+        time in field(price()),
+    ) -> Value {
+        object(vec![
+            ("instrument_name", instrument_name),
+            ("price", price),
+            ("amount", amount),
+            ("maker_taker", maker_taker),
+            ("time", time),
+        ])
+    }
+}
+
+prop_compose! {
+    fn ticker_json()(
+        mark_price in field(price()),
+        mark_timestamp in field(price()),
+        index in field(price()),
+        funding_rate in field((-0.01f64..0.01).prop_map(|r| json!(r))),
+        best_bid_price in field(price()),
+    ) -> Value {
+        object(vec![
+            ("mark_price", mark_price),
+            ("mark_timestamp", mark_timestamp),
+            ("index", index),
+            ("funding_rate", funding_rate),
+            ("best_bid_price", best_bid_price),
+        ])
+    }
+}
+
+/// The serialized name of an enum, as the exchange spells it
+fn wire_name<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn test_ack_enums_match_input_or_error(data in order_json()) {
+        match ThaleParser::parse_order_json(&data) {
+            Ok(ack) => {
+                prop_assert_eq!(wire_name(&ack.direction), data["direction"].clone());
+                prop_assert_eq!(wire_name(&ack.status), data["status"].clone());
+                if data["order_type"].is_string() {
+                    prop_assert_eq!(wire_name(&ack.order_type), data["order_type"].clone());
+                }
+                if data["time_in_force"].is_string() {
+                    prop_assert_eq!(wire_name(&ack.time_in_force), data["time_in_force"].clone());
+                }
+                prop_assert_eq!(ack.price, data["price"].as_f64());
+            }
+            Err(e) => prop_assert!(!e.to_string().is_empty()),
+        }
+    }
+
+    #[test]
+    fn test_ack_accepts_every_known_value(
+        direction in prop::sample::select(&["buy", "sell"][..]),
+        status in prop::sample::select(&["open", "partially_filled", "cancelled", "cancelled_partially_filled", "filled"][..]),
+    ) {
+        let ack = ThaleParser::parse_order_json(&json!({"direction": direction, "status": status}));
+        prop_assert!(ack.is_ok(), "{:?}", ack.err());
+    }
+
+    #[test]
+    fn test_trade_fields_match_input_or_error(data in trade_json()) {
+        match ThaleParser::parse_trade_json(&data, false) {
+            Ok(trade) => {
+                prop_assert_eq!(Some(trade.price), data["price"].as_f64());
+                prop_assert_eq!(Some(trade.amount), data["amount"].as_f64());
+                prop_assert_eq!(Some(trade.instrument_name.as_str()), data["instrument_name"].as_str());
+            }
+            Err(e) => prop_assert!(e.to_string().starts_with("Missing")),
+        }
+    }
+
+    #[test]
+    fn test_order_fills_never_panic(mut data in order_json(), fills in prop::collection::vec(trade_json(), 0..4)) {
+        data["fills"] = Value::Array(fills);
+        let _ = ThaleParser::extract_trades_from_order(&data, false);
+    }
+
+    #[test]
+    fn test_ticker_fields_match_input_or_error(data in ticker_json()) {
+        match Ticker::from_json(&data, "BTC-PERPETUAL".to_string()) {
+            Ok(ticker) => {
+                prop_assert_eq!(Some(ticker.mark_price), data["mark_price"].as_f64());
+                prop_assert_eq!(Some(ticker.index_price), data["index"].as_f64());
+            }
+            Err(e) => prop_assert!(e.to_string().starts_with("Missing")),
+        }
+    }
+
+    #[test]
+    fn test_parsers_never_panic_on_arbitrary_json(data in any_json()) {
+        let _ = ThaleParser::parse_order_json(&data);
+        let _ = ThaleParser::parse_trade_json(&data, false);
+        let _ = ThaleParser::extract_trades_from_order(&data, false);
+        let _ = Ticker::from_json(&data, "BTC-PERPETUAL".to_string());
+    }
+}
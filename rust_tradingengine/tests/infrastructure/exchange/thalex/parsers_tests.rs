@@ -1,118 +1,57 @@
 use anyhow::Result;
 use serde_json::json;
-use cryptics_lab_bot::domain::enums::{OrderSide, OrderStatus, OrderType, TimeInForce};
+use cryptics_lab_bot::domain::enums::{MakerTaker, OrderSide, OrderStatus, OrderType, TimeInForce};
+use cryptics_lab_bot::domain::model::account_event::AccountEventType;
+use cryptics_lab_bot::domain::model::trade_correction::CorrectionKind;
+use cryptics_lab_bot::domain::model::order::Order;
 use cryptics_lab_bot::domain::model::trade::Trade;
 use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
+use cryptics_lab_bot::testing::fixtures;
 
 #[test]
-fn test_parse_ack_json() -> Result<()> {
-    // Create sample JSON data
-    let json_data = json!({
-        "order_id": "ord-123456",
-        "client_order_id": 42,
-        "instrument_name": "BTC-PERPETUAL",
-        "direction": "buy",
-        "price": 50000.0,
-        "amount": 0.1,
-        "filled_amount": 0.05,
-        "remaining_amount": 0.05,
-        "status": "partially_filled",
-        "order_type": "limit",
-        "time_in_force": "good_till_cancelled",
-        "change_reason": "update",
-        "delete_reason": null,
-        "insert_reason": "new_order",
-        "create_time": 1645543210.123,
-        "persistent": true
-    });
-    
-    // Parse the JSON into an Ack
-    let ack = ThaleParser::parse_ack_json(&json_data)?;
+fn test_parse_order_json() -> Result<()> {
+    let order = fixtures::ack();
+    let ack = ThaleParser::parse_order_json(&fixtures::order_json(&order))?;
     
     // Verify the fields
-    assert_eq!(ack.order_id, "ord-123456");
-    assert_eq!(ack.client_order_id, Some(42));
-    assert_eq!(ack.instrument_name, "BTC-PERPETUAL");
-    
-    match ack.direction {
-        OrderSide::Buy => {}, // Expected
-        _ => panic!("Expected Buy direction"),
-    }
-    
-    assert_eq!(ack.price, Some(50000.0));
+    assert_eq!(ack.order_id, order.order_id);
+    assert_eq!(ack.client_order_id, Some(67890));
+    assert_eq!(ack.instrument_name, fixtures::INSTRUMENT);
+    assert!(matches!(ack.direction, OrderSide::Sell));
+    assert_eq!(ack.price, Some(fixtures::MARK));
     assert_eq!(ack.amount, 0.1);
     assert_eq!(ack.filled_amount, 0.05);
     assert_eq!(ack.remaining_amount, 0.05);
-    
-    match ack.status {
-        OrderStatus::PartiallyFilled => {}, // Expected
-        _ => panic!("Expected PartiallyFilled status"),
-    }
-    
-    match ack.order_type {
-        OrderType::Limit => {}, // Expected
-        _ => panic!("Expected Limit order type"),
-    }
-    
-    match ack.time_in_force {
-        TimeInForce::GTC => {}, // Expected
-        _ => panic!("Expected GTC time in force"),
-    }
-    
-    assert_eq!(ack.change_reason, "update");
+    assert!(matches!(ack.status, OrderStatus::PartiallyFilled));
+    assert!(matches!(ack.order_type, OrderType::Limit));
+    assert!(matches!(ack.time_in_force, TimeInForce::GTC));
+    assert_eq!(ack.change_reason, "fill");
     assert_eq!(ack.delete_reason, None);
-    assert_eq!(ack.insert_reason, Some("new_order".to_string()));
-    assert_eq!(ack.create_time, 1645543210.123);
-    assert_eq!(ack.persistent, true);
+    assert_eq!(ack.insert_reason, Some("client_request".to_string()));
+    assert_eq!(ack.create_time, fixtures::T0);
+    assert!(!ack.persistent);
     
     // Test with missing fields or different values
-    let json_data_with_nulls = json!({
-        "order_id": "ord-789012",
-        "client_order_id": null,
-        "instrument_name": "ETH-PERPETUAL",
-        "direction": "sell",
-        "price": null,
-        "amount": 0.5,
-        "filled_amount": 0.0,
-        "remaining_amount": 0.5,
-        "status": "open",
-        "order_type": "market",
-        "time_in_force": "immediate_or_cancel",
-        "change_reason": "new",
-        "delete_reason": "user_requested",
-        "insert_reason": null,
-        "create_time": 1645543220.456,
-        "persistent": false
-    });
-    
-    let ack_with_nulls = ThaleParser::parse_ack_json(&json_data_with_nulls)?;
-    
-    assert_eq!(ack_with_nulls.order_id, "ord-789012");
+    let cancelled = Order {
+        client_order_id: None,
+        instrument_name: "ETH-PERPETUAL".to_string(),
+        price: None,
+        order_type: OrderType::Market,
+        time_in_force: TimeInForce::IOC,
+        delete_reason: Some("user_requested".to_string()),
+        insert_reason: None,
+        ..fixtures::order(2, OrderSide::Buy, 3000.0, 0.5)
+    };
+    let ack_with_nulls = ThaleParser::parse_order_json(&fixtures::order_json(&cancelled))?;
+    
+    assert_eq!(ack_with_nulls.order_id, cancelled.order_id);
     assert_eq!(ack_with_nulls.client_order_id, None);
     assert_eq!(ack_with_nulls.instrument_name, "ETH-PERPETUAL");
-    
-    match ack_with_nulls.direction {
-        OrderSide::Sell => {}, // Expected
-        _ => panic!("Expected Sell direction"),
-    }
-    
+    assert!(matches!(ack_with_nulls.direction, OrderSide::Buy));
     assert_eq!(ack_with_nulls.price, None);
-    
-    match ack_with_nulls.status {
-        OrderStatus::Open => {}, // Expected
-        _ => panic!("Expected Open status"),
-    }
-    
-    match ack_with_nulls.order_type {
-        OrderType::Market => {}, // Expected
-        _ => panic!("Expected Market order type"),
-    }
-    
-    match ack_with_nulls.time_in_force {
-        TimeInForce::IOC => {}, // Expected
-        _ => panic!("Expected IOC time in force"),
-    }
-    
+    assert!(matches!(ack_with_nulls.status, OrderStatus::Open));
+    assert!(matches!(ack_with_nulls.order_type, OrderType::Market));
+    assert!(matches!(ack_with_nulls.time_in_force, TimeInForce::IOC));
     assert_eq!(ack_with_nulls.delete_reason, Some("user_requested".to_string()));
     assert_eq!(ack_with_nulls.insert_reason, None);
     
@@ -172,152 +111,82 @@ fn test_parse_ticker_json() -> Result<()> {
 
 #[test]
 fn test_parse_trade_json() -> Result<()> {
-    // Create sample JSON data
-    let json_data = json!({
-        "trade_id": "trd-123456",
-        "order_id": "ord-789012",
-        "client_order_id": 42,
-        "instrument_name": "BTC-PERPETUAL",
-        "price": 50000.0,
-        "amount": 0.1,
-        "maker_taker": "maker",
-        "timestamp": 1645543210.123
-    });
-    
-    // Parse the JSON into a Trade
-    let trade = ThaleParser::parse_trade_json(&json_data)?;
+    let fill = fixtures::trade(42, 50000.0, 0.1);
+    let trade = ThaleParser::parse_trade_json(&fixtures::trade_json(&fill), false)?;
     
     // Verify the fields
-    assert_eq!(trade.trade_id, "trd-123456");
-    assert_eq!(trade.order_id, "ord-789012");
+    assert_eq!(trade.trade_id, fill.trade_id);
+    assert_eq!(trade.order_id, fill.order_id);
     assert_eq!(trade.client_order_id, Some(42));
-    assert_eq!(trade.instrument_name, "BTC-PERPETUAL");
+    assert_eq!(trade.instrument_name, fixtures::INSTRUMENT);
     assert_eq!(trade.price, 50000.0);
     assert_eq!(trade.amount, 0.1);
-    assert_eq!(trade.maker_taker, "maker");
-    assert_eq!(trade.time, 1645543210.123);
+    assert_eq!(trade.maker_taker, Some(MakerTaker::Maker));
+    assert_eq!(trade.time, fixtures::T0);
+    
+    // The time may come as `timestamp`
+    let mut timestamped = fixtures::trade_json(&fill);
+    timestamped["timestamp"] = timestamped["time"].take();
+    assert_eq!(ThaleParser::parse_trade_json(&timestamped, false)?.time, fixtures::T0);
     
     // Test with missing trade_id (should generate a UUID)
-    let json_data_without_trade_id = json!({
-        "order_id": "ord-345678",
-        "client_order_id": null,
-        "instrument_name": "ETH-PERPETUAL",
-        "price": 3000.0,
-        "amount": 0.5,
-        "maker_taker": "taker",
-        "timestamp": 1645543220.456
-    });
-    
-    let trade_without_id = ThaleParser::parse_trade_json(&json_data_without_trade_id)?;
+    let taker = Trade {
+        client_order_id: None,
+        maker_taker: Some(MakerTaker::Taker),
+        ..fixtures::trade(43, 3000.0, 0.5)
+    };
+    let mut without_trade_id = fixtures::trade_json(&taker);
+    without_trade_id.as_object_mut().unwrap().remove("trade_id");
+    
+    let trade_without_id = ThaleParser::parse_trade_json(&without_trade_id, false)?;
     
     // Verify that a trade_id was generated (it should start with "trade-")
     assert!(trade_without_id.trade_id.starts_with("trade-"));
-    assert_eq!(trade_without_id.order_id, "ord-345678");
+    assert_eq!(trade_without_id.order_id, taker.order_id);
     assert_eq!(trade_without_id.client_order_id, None);
-    assert_eq!(trade_without_id.instrument_name, "ETH-PERPETUAL");
     assert_eq!(trade_without_id.price, 3000.0);
     assert_eq!(trade_without_id.amount, 0.5);
-    assert_eq!(trade_without_id.maker_taker, "taker");
-    assert_eq!(trade_without_id.time, 1645543220.456);
+    assert_eq!(trade_without_id.maker_taker, Some(MakerTaker::Taker));
     
     Ok(())
 }
 
 #[test]
 fn test_extract_trades_from_order() -> Result<()> {
-    // Create sample order data with fills
-    let order_data_with_fills = json!({
-        "order_id": "ord-123456",
-        "client_order_id": 42,
-        "instrument_name": "BTC-PERPETUAL",
-        "direction": "buy",
-        "price": 50000.0,
-        "amount": 0.2,
-        "filled_amount": 0.2,
-        "remaining_amount": 0.0,
-        "status": "filled",
-        "order_type": "limit",
-        "time_in_force": "good_till_cancelled",
-        "change_reason": "fill",
-        "create_time": 1645543210.123,
-        "persistent": true,
-        "fills": [
-            {
-                "trade_id": "trd-111111",
-                "price": 50000.0,
-                "amount": 0.1,
-                "maker_taker": "maker",
-                "time": 1645543220.456
-            },
-            {
-                "trade_id": "trd-222222",
-                "price": 50000.0,
-                "amount": 0.1,
-                "maker_taker": "maker",
-                "time": 1645543230.789
-            }
-        ]
-    });
+    let order = Order {
+        filled_amount: 0.2,
+        remaining_amount: 0.0,
+        status: OrderStatus::Filled,
+        ..fixtures::order(42, OrderSide::Buy, 50000.0, 0.2)
+    };
+    let fills = [
+        Trade { trade_id: "trd-111111".to_string(), time: fixtures::T0 + 10.0, ..fixtures::trade(42, 50000.0, 0.1) },
+        Trade { trade_id: "trd-222222".to_string(), time: fixtures::T0 + 20.0, ..fixtures::trade(42, 50000.0, 0.1) },
+    ];
     
     // Extract trades from the order data
-    let trades = ThaleParser::extract_trades_from_order(&order_data_with_fills)?;
+    let trades = ThaleParser::extract_trades_from_order(&fixtures::order_with_fills_json(&order, &fills), false)?;
     
-    // Verify the extracted trades
+    // Each fill becomes a trade of the order
     assert_eq!(trades.len(), 2);
-    
-    // Check first trade
-    assert_eq!(trades[0].trade_id, "trd-111111");
-    assert_eq!(trades[0].order_id, "ord-123456");
-    assert_eq!(trades[0].client_order_id, Some(42));
-    assert_eq!(trades[0].instrument_name, "BTC-PERPETUAL");
-    assert_eq!(trades[0].price, 50000.0);
-    assert_eq!(trades[0].amount, 0.1);
-    assert_eq!(trades[0].maker_taker, "maker");
-    assert_eq!(trades[0].time, 1645543220.456);
-    
-    // Check second trade
-    assert_eq!(trades[1].trade_id, "trd-222222");
-    assert_eq!(trades[1].order_id, "ord-123456");
-    assert_eq!(trades[1].client_order_id, Some(42));
-    assert_eq!(trades[1].instrument_name, "BTC-PERPETUAL");
-    assert_eq!(trades[1].price, 50000.0);
-    assert_eq!(trades[1].amount, 0.1);
-    assert_eq!(trades[1].maker_taker, "maker");
-    assert_eq!(trades[1].time, 1645543230.789);
+    for (trade, fill) in trades.iter().zip(&fills) {
+        assert_eq!(trade.trade_id, fill.trade_id);
+        assert_eq!(trade.order_id, order.order_id);
+        assert_eq!(trade.client_order_id, Some(42));
+        assert_eq!(trade.instrument_name, fixtures::INSTRUMENT);
+        assert_eq!(trade.price, 50000.0);
+        assert_eq!(trade.amount, 0.1);
+        assert_eq!(trade.maker_taker, Some(MakerTaker::Maker));
+        assert_eq!(trade.time, fill.time);
+    }
     
     // Test with missing trade_id (should generate a UUID)
-    let order_data_without_trade_ids = json!({
-        "order_id": "ord-345678",
-        "client_order_id": null,
-        "instrument_name": "ETH-PERPETUAL",
-        "direction": "sell",
-        "price": 3000.0,
-        "amount": 0.5,
-        "filled_amount": 0.5,
-        "remaining_amount": 0.0,
-        "status": "filled",
-        "order_type": "limit",
-        "time_in_force": "good_till_cancelled",
-        "change_reason": "fill",
-        "create_time": 1645543240.123,
-        "persistent": true,
-        "fills": [
-            {
-                "price": 3000.0,
-                "amount": 0.3,
-                "maker_taker": "taker",
-                "time": 1645543250.456
-            },
-            {
-                "price": 3000.0,
-                "amount": 0.2,
-                "maker_taker": "taker",
-                "time": 1645543260.789
-            }
-        ]
-    });
-    
-    let trades_without_ids = ThaleParser::extract_trades_from_order(&order_data_without_trade_ids)?;
+    let mut without_trade_ids = fixtures::order_with_fills_json(&order, &fills);
+    for fill in without_trade_ids["fills"].as_array_mut().unwrap() {
+        fill.as_object_mut().unwrap().remove("trade_id");
+    }
+    
+    let trades_without_ids = ThaleParser::extract_trades_from_order(&without_trade_ids, false)?;
     
     // Verify trades were extracted
     assert_eq!(trades_without_ids.len(), 2);
@@ -327,24 +196,8 @@ fn test_extract_trades_from_order() -> Result<()> {
     assert!(trades_without_ids[1].trade_id.starts_with("trade-"));
     
     // Test with an order that has no fills
-    let order_data_without_fills = json!({
-        "order_id": "ord-999999",
-        "client_order_id": 99,
-        "instrument_name": "BTC-PERPETUAL",
-        "direction": "buy",
-        "price": 50000.0,
-        "amount": 0.1,
-        "filled_amount": 0.0,
-        "remaining_amount": 0.1,
-        "status": "open",
-        "order_type": "limit",
-        "time_in_force": "good_till_cancelled",
-        "change_reason": "new",
-        "create_time": 1645543270.123,
-        "persistent": true
-    });
-    
-    let trades_without_fills = ThaleParser::extract_trades_from_order(&order_data_without_fills)?;
+    let open = fixtures::order(99, OrderSide::Buy, 50000.0, 0.1);
+    let trades_without_fills = ThaleParser::extract_trades_from_order(&fixtures::order_json(&open), false)?;
     
     // There should be no trades
     assert_eq!(trades_without_fills.len(), 0);
@@ -352,6 +205,38 @@ fn test_extract_trades_from_order() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_maker_taker_strict_and_lenient() -> Result<()> {
+    let trade_with = |maker_taker: Option<&str>| {
+        let mut data = fixtures::trade_json(&fixtures::trade(42, 50000.0, 0.1));
+        match maker_taker {
+            Some(role) => data["maker_taker"] = json!(role),
+            None => { data.as_object_mut().unwrap().remove("maker_taker"); }
+        }
+        data
+    };
+    
+    // Lenient: unknown or missing roles are recorded as None
+    assert_eq!(ThaleParser::parse_trade_json(&trade_with(Some("liquidation")), false)?.maker_taker, None);
+    assert_eq!(ThaleParser::parse_trade_json(&trade_with(None), false)?.maker_taker, None);
+    
+    // Strict: both are rejected, known roles still parse
+    let unknown = ThaleParser::parse_trade_json(&trade_with(Some("liquidation")), true);
+    let missing = ThaleParser::parse_trade_json(&trade_with(None), true);
+    let known = ThaleParser::parse_trade_json(&trade_with(Some("taker")), true);
+    let order = fixtures::order(42, OrderSide::Buy, 50000.0, 0.1);
+    let mut both = fixtures::order_with_fills_json(&order, &[fixtures::trade(42, 50000.0, 0.1)]);
+    both["fills"][0]["maker_taker"] = json!("both");
+    let fill = ThaleParser::extract_trades_from_order(&both, true);
+    
+    assert!(unknown.is_err());
+    assert!(missing.is_err());
+    assert!(fill.is_err());
+    assert_eq!(known?.maker_taker, Some(MakerTaker::Taker));
+    
+    Ok(())
+}
+
 #[test]
 fn test_trade_to_json() {
     // Create a test Trade object
@@ -362,9 +247,10 @@ fn test_trade_to_json() {
         instrument_name: "BTC-PERPETUAL".to_string(),
         price: 50000.0,
         amount: 0.1,
-        maker_taker: "maker".to_string(),
+        maker_taker: Some(MakerTaker::Maker),
         time: 1645543210.123,
         processing_timestamp: Some(1645543210.456),
+        variant_id: None,
     };
     
     // Convert to JSON
@@ -388,13 +274,95 @@ fn test_trade_to_json() {
         instrument_name: "ETH-PERPETUAL".to_string(),
         price: 3000.0,
         amount: 0.5,
-        maker_taker: "taker".to_string(),
+        maker_taker: Some(MakerTaker::Taker),
         time: 1645543220.456,
         processing_timestamp: Some(1645543220.789),
+        variant_id: None,
     };
     
     let json_without_client_id = ThaleParser::trade_to_json(&trade_without_client_id);
     
     // Verify client_order_id is null
     assert!(json_without_client_id["client_order_id"].is_null());
+    
+    // Trades without a role keep the "unknown" placeholder on the wire
+    let trade_without_role = Trade { maker_taker: None, ..trade_without_client_id };
+    assert_eq!(ThaleParser::trade_to_json(&trade_without_role)["maker_taker"], "unknown");
+}
+
+#[test]
+fn test_parse_account_event_json() -> Result<()> {
+    let event = fixtures::account_event(AccountEventType::Deposit);
+    let deposit = ThaleParser::parse_account_event_json(&fixtures::account_event_json(&event))?;
+    assert_eq!(deposit.event_type, AccountEventType::Deposit);
+    assert_eq!(deposit.timestamp, fixtures::T0);
+    assert_eq!(deposit.currency.as_deref(), Some("BTC"));
+    assert_eq!(deposit.amount, Some(0.25));
+    assert_eq!(deposit.message, event.message);
+    
+    // Without a category the type decides; the title stands in for a missing message
+    let liquidation = ThaleParser::parse_account_event_json(&json!({
+        "type": "liquidation",
+        "title": "Position liquidated",
+        "instrument_name": "BTC-PERPETUAL",
+    }))?;
+    assert_eq!(liquidation.event_type, AccountEventType::Liquidation);
+    assert_eq!(liquidation.instrument_name.as_deref(), Some("BTC-PERPETUAL"));
+    assert_eq!(liquidation.message.as_deref(), Some("Position liquidated"));
+    
+    let other = ThaleParser::parse_account_event_json(&json!({"type": "maintenance"}))?;
+    assert_eq!(other.event_type, AccountEventType::Other);
+    let margin_call = fixtures::account_event(AccountEventType::MarginCall);
+    assert_eq!(ThaleParser::parse_account_event_json(&fixtures::account_event_json(&margin_call))?.event_type, AccountEventType::MarginCall);
+    
+    // Free text never classifies an event, however alarming it reads
+    for text in [json!({"title": "Position liquidated"}), json!({"category": "news", "message": "Avoid liquidation: add margin"})] {
+        assert_eq!(ThaleParser::parse_account_event_json(&text)?.event_type, AccountEventType::Other);
+    }
+    assert!(ThaleParser::parse_account_event_json(&json!({"amount": "lots"})).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_funding_payment_json() -> Result<()> {
+    let mut funding = fixtures::account_event_json(&fixtures::account_event(AccountEventType::Other));
+    funding["category"] = json!("funding");
+    funding["amount"] = json!(-1.25);
+    assert_eq!(ThaleParser::parse_funding_payment_json(&funding)?, Some(-1.25));
+    assert_eq!(ThaleParser::parse_funding_payment_json(&json!({"type": "Funding_Payment", "amount": 2.0}))?, Some(2.0));
+    
+    // Only the category or type identifies a payment
+    let deposit = fixtures::account_event_json(&fixtures::account_event(AccountEventType::Deposit));
+    assert_eq!(ThaleParser::parse_funding_payment_json(&deposit)?, None);
+    assert_eq!(ThaleParser::parse_funding_payment_json(&json!({"title": "Funding paid", "amount": 2.0}))?, None);
+    assert!(ThaleParser::parse_funding_payment_json(&json!({"category": "funding"})).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_trade_correction_json() -> Result<()> {
+    let notification = fixtures::trade_correction_json(&fixtures::trade_bust(1, fixtures::MARK, 0.2));
+    let bust = ThaleParser::parse_trade_correction_json(&notification)?.expect("a bust");
+    assert_eq!(bust.kind, CorrectionKind::Bust);
+    assert_eq!(bust.trade_id, "T00000001");
+    assert!(matches!(bust.direction, Some(OrderSide::Buy)));
+    assert_eq!((bust.original_price, bust.original_amount), (Some(fixtures::MARK), Some(0.2)));
+    assert_eq!(bust.timestamp, fixtures::T0);
+    
+    // The exchange may only title a correction and name the new price
+    let correction = ThaleParser::parse_trade_correction_json(&json!({
+        "title": "Trade corrected",
+        "trade_id": "T00000002",
+        "new_price": 65010.0,
+    }))?.expect("a correction");
+    assert_eq!(correction.kind, CorrectionKind::Correction);
+    assert_eq!(correction.corrected_price, Some(65010.0));
+    assert_eq!(correction.corrected_amount, None);
+    assert_eq!(correction.message.as_deref(), Some("Trade corrected"));
+    
+    // Other notifications are left to the account event parser
+    let deposit = fixtures::account_event_json(&fixtures::account_event(AccountEventType::Deposit));
+    assert!(ThaleParser::parse_trade_correction_json(&deposit)?.is_none());
+    assert!(ThaleParser::parse_trade_correction_json(&json!({"title": "Trade cancelled"})).is_err());
+    Ok(())
 }
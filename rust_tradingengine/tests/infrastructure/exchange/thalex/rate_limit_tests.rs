@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use cryptics_lab_bot::config_loader::{RateLimitConfig, RateLimitOverflow, RequestBudget};
+use cryptics_lab_bot::infrastructure::exchange::thalex::rate_limit::{RateLimiter, TokenBucket};
+
+fn config() -> RateLimitConfig {
+    RateLimitConfig {
+        enabled: true,
+        per_sec: 10.0,
+        burst: 4.0,
+        methods: BTreeMap::from([
+            ("private/amend".to_string(), RequestBudget { per_sec: 2.0, burst: 2.0 }),
+        ]),
+        overflow: RateLimitOverflow::Queue,
+    }
+}
+
+#[test]
+fn test_bucket_refills_at_its_rate() {
+    let start = Instant::now();
+    let mut bucket = TokenBucket::new(2.0, 2.0, start);
+    assert!(bucket.take(start));
+    assert!(bucket.take(start));
+    assert!(!bucket.take(start));
+    
+    assert!(!bucket.take(start + Duration::from_millis(400)));
+    assert!(bucket.take(start + Duration::from_millis(500)));
+    // Idle time refills no further than the burst
+    let later = start + Duration::from_secs(60);
+    assert!(bucket.take(later) && bucket.take(later));
+    assert!(!bucket.take(later));
+}
+
+#[test]
+fn test_ready_when_the_budget_refills() {
+    let start = Instant::now();
+    let mut limiter = RateLimiter::from_config(&config());
+    assert_eq!(limiter.ready_at("private/amend", start), start);
+    assert!(limiter.try_acquire("private/amend", start));
+    assert!(limiter.try_acquire("private/amend", start));
+    
+    // The amend budget refills a request every 500ms, the global one has room left
+    let ready = limiter.ready_at("private/amend", start);
+    assert_eq!(ready, start + Duration::from_millis(500));
+    assert!(!limiter.try_acquire("private/amend", ready - Duration::from_millis(1)));
+    assert!(limiter.try_acquire("private/amend", ready));
+    assert_eq!(limiter.ready_at("public/ticker", start), start);
+}
+
+#[test]
+fn test_method_budget_limits_its_method_only() {
+    let now = Instant::now();
+    let mut limiter = RateLimiter::from_config(&config());
+    assert!(limiter.try_acquire("private/amend", now));
+    assert!(limiter.try_acquire("private/amend", now));
+    assert!(!limiter.try_acquire("private/amend", now));
+    
+    // Cancels only draw on the global budget, of which two requests are left
+    assert!(limiter.try_acquire("private/cancel", now));
+    assert!(limiter.admits("private/cancel", now));
+    assert!(limiter.try_acquire("private/cancel", now));
+    assert!(!limiter.try_acquire("private/cancel", now));
+}
+
+#[test]
+fn test_refused_request_spends_nothing() {
+    let now = Instant::now();
+    let mut limiter = RateLimiter::from_config(&RateLimitConfig { burst: 1.0, ..config() });
+    assert!(limiter.try_acquire("private/insert", now));
+    // Over the global budget: the amend budget stays untouched
+    assert!(!limiter.try_acquire("private/amend", now));
+    let later = now + Duration::from_millis(100);
+    assert!(limiter.try_acquire("private/amend", later));
+}
+
+#[test]
+fn test_disabled_limiter_admits_everything() {
+    let now = Instant::now();
+    let mut limiter = RateLimiter::from_config(&RateLimitConfig { enabled: false, ..config() });
+    assert!((0..1000).all(|_| limiter.try_acquire("private/amend", now)));
+    assert!(!limiter.drops_overflow());
+    assert!(RateLimiter::from_config(&RateLimitConfig { overflow: RateLimitOverflow::Drop, ..config() }).drops_overflow());
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use serde_json::json;
+
+use cryptics_lab_bot::infrastructure::exchange::thalex::client::{Network, ThalexKeys};
+use cryptics_lab_bot::infrastructure::exchange::thalex::keys::decrypt_pem;
+use cryptics_lab_bot::infrastructure::exchange::thalex::models::{AccountSummary, TradeHistoryResult};
+use cryptics_lab_bot::infrastructure::exchange::thalex::{ThalexRestClient, TradeHistoryQuery};
+
+/// Throwaway RSA key encrypted with PBES2/AES-256-CBC, passphrase "test-passphrase"
+const ENCRYPTED_KEY: &str = include_str!("fixtures/test_key_encrypted.pem");
+
+#[test]
+fn test_private_calls_use_the_login_token() -> Result<()> {
+    let keys = ThalexKeys { kid: "test-kid".to_string(), private_key: decrypt_pem(ENCRYPTED_KEY, "test-passphrase")? };
+    let rest = ThalexRestClient::for_network(&Network::TEST, Some(keys));
+    let auth = rest.auth_header()?;
+    let token = auth.strip_prefix("Bearer ").expect("bearer token");
+    let header = jsonwebtoken::decode_header(token)?;
+    assert_eq!(header.kid.as_deref(), Some("test-kid"));
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::RS512);
+
+    let public_only = ThalexRestClient::for_network(&Network::TEST, None);
+    let err = public_only.auth_header().unwrap_err();
+    assert!(err.to_string().contains("no keys"), "{}", err);
+    Ok(())
+}
+
+#[test]
+fn test_response_envelope() {
+    assert_eq!(ThalexRestClient::result(json!({"result": {"margin": 1.0}})).unwrap(), json!({"margin": 1.0}));
+    let err = ThalexRestClient::result(json!({"error": {"code": 3, "message": "not authorized"}})).unwrap_err();
+    assert_eq!(err.to_string(), "Thalex error 3: not authorized");
+    assert!(ThalexRestClient::result(json!({})).is_err());
+}
+
+#[test]
+fn test_trade_history_query_params() {
+    assert!(TradeHistoryQuery::default().params().is_empty());
+    let query = TradeHistoryQuery {
+        time_low: Some(1792022400.0),
+        limit: Some(100),
+        bookmark: Some("abc".to_string()),
+        instrument_names: vec!["BTC-PERPETUAL".to_string(), "ETH-PERPETUAL".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(query.params(), vec![
+        ("time_low", "1792022400".to_string()),
+        ("limit", "100".to_string()),
+        ("bookmark", "abc".to_string()),
+        ("instrument_names", "BTC-PERPETUAL,ETH-PERPETUAL".to_string()),
+    ]);
+}
+
+#[test]
+fn test_typed_results() -> Result<()> {
+    let summary: AccountSummary = serde_json::from_value(json!({
+        "cash": [{"currency": "USD", "balance": 10000.0, "collateral_factor": 1.0}],
+        "unrealised_pnl": -12.5, "cash_collateral": 10000.0, "margin": 9987.5,
+        "required_margin": 250.0, "remaining_margin": 9737.5, "session_realised_pnl": 3.0,
+    }))?;
+    assert_eq!(summary.cash[0].currency, "USD");
+    assert_eq!(summary.margin, 9987.5);
+    assert_eq!(summary.remaining_margin, 9737.5);
+
+    let page: TradeHistoryResult = serde_json::from_value(json!({
+        "trades": [{
+            "trade_id": "T1", "order_id": "O1", "client_order_id": 42, "instrument_name": "BTC-PERPETUAL",
+            "direction": "buy", "price": 65000.0, "amount": 0.1, "time": 1792022400.5, "maker_taker": "maker",
+        }],
+        "bookmark": "next",
+    }))?;
+    assert_eq!(page.bookmark.as_deref(), Some("next"));
+    let trades = page.parsed(false)?;
+    assert_eq!(trades[0].trade_id, "T1");
+    assert_eq!(trades[0].client_order_id, Some(42));
+    assert_eq!(trades[0].time, 1792022400.5);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+use cryptics_lab_bot::infrastructure::exchange::thalex::subscriptions::SubscriptionManager;
+
+#[test]
+fn test_records_subscribed_channels_once_in_order() {
+    let subscriptions = SubscriptionManager::new();
+    subscriptions.subscribed(&[Channel::Orders, Channel::ticker("BTC-PERPETUAL")]);
+    subscriptions.subscribed(&[Channel::ticker("BTC-PERPETUAL"), Channel::Index("BTCUSD".to_string())]);
+    assert_eq!(subscriptions.channels(), vec![
+        Channel::Orders,
+        Channel::ticker("BTC-PERPETUAL"),
+        Channel::Index("BTCUSD".to_string()),
+    ]);
+    
+    subscriptions.unsubscribed(&[Channel::ticker("BTC-PERPETUAL")]);
+    assert_eq!(subscriptions.channels(), vec![Channel::Orders, Channel::Index("BTCUSD".to_string())]);
+}
+
+#[test]
+fn test_restore_adds_recorded_channels_to_the_defaults() {
+    let subscriptions = SubscriptionManager::new();
+    
+    // Nothing recorded before the first session
+    assert_eq!(subscriptions.restore(vec![Channel::Orders], true), vec![Channel::Orders]);
+    assert_eq!(subscriptions.cancel_on_disconnect(), None);
+    
+    subscriptions.subscribed(&[Channel::Orders, Channel::Portfolio, Channel::ticker("BTC-PERPETUAL"), Channel::ticker("ETH-PERPETUAL")]);
+    subscriptions.cancel_on_disconnect_set(6);
+    
+    // Each kind only brings back channels of that kind, after the defaults
+    assert_eq!(subscriptions.restore(vec![Channel::Orders, Channel::Trades], true), vec![
+        Channel::Orders,
+        Channel::Trades,
+        Channel::Portfolio,
+    ]);
+    assert_eq!(subscriptions.restore(vec![Channel::ticker("BTC-PERPETUAL")], false), vec![
+        Channel::ticker("BTC-PERPETUAL"),
+        Channel::ticker("ETH-PERPETUAL"),
+    ]);
+    assert_eq!(subscriptions.cancel_on_disconnect(), Some(6));
+}
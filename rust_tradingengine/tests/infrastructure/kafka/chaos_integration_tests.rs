@@ -0,0 +1,139 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use cryptics_lab_bot::config_loader::{ChaosConfig, FaultConfig, SpillConfig};
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::infrastructure::kafka::KafkaProducer;
+use cryptics_lab_bot::infrastructure::metrics;
+
+fn ticker() -> Ticker {
+    Ticker {
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        mark_price: 50000.0,
+        mark_timestamp: 1645543210.123,
+        best_bid_price: 49950.0,
+        best_bid_amount: 0.5,
+        best_ask_price: 50050.0,
+        best_ask_amount: 0.3,
+        last_price: 49975.0,
+        delta: 0.1,
+        volume_24h: 1000.0,
+        value_24h: 50000000.0,
+        low_price_24h: 48000.0,
+        high_price_24h: 51000.0,
+        change_24h: 2.5,
+        index_price: 50010.0,
+        forward: 0.0,
+        funding_mark: 0.0001,
+        funding_rate: 0.0002,
+        collar_low: 48000.0,
+        collar_high: 52000.0,
+        realised_funding_24h: 0.0003,
+        average_funding_rate_24h: 0.0002,
+        open_interest: 500.0,
+        processing_timestamp: Some(1645543210.456),
+    }
+}
+
+fn chaos(kafka: FaultConfig, registry: FaultConfig) -> ChaosConfig {
+    ChaosConfig {
+        enabled: true,
+        seed: Some(11),
+        kafka,
+        registry,
+        ..Default::default()
+    }
+}
+
+/// Registry failures surface as send errors without leaking pending sends
+#[tokio::test]
+async fn test_registry_fault_fails_send() -> Result<()> {
+    // Nothing listens on port 1, so preloading fails fast and sends go to the registry
+    let mut producer = KafkaProducer::new(
+        "localhost:1",
+        "http://localhost:1",
+        HashMap::from([("ticker".to_string(), "test.chaos.ticker".to_string())]),
+        "../schemas".to_string(),
+    ).await?;
+    producer.set_faults(&chaos(
+        FaultConfig::default(),
+        FaultConfig { error_rate: 1.0, ..Default::default() },
+    ));
+
+    let before = metrics::global().counter_value("chaos.registry.errors");
+    let err = producer.send_ticker(&ticker()).await.unwrap_err();
+    assert!(err.to_string().contains("Injected Registry error"), "{}", err);
+    assert!(metrics::global().counter_value("chaos.registry.errors") > before);
+    assert_eq!(producer.pending_sends(), 0);
+    Ok(())
+}
+
+/// Kafka produce failures are reported per send and the producer keeps working
+///
+/// Note: This test requires a running Kafka and Schema Registry
+#[tokio::test]
+#[ignore] // This test requires a running Kafka cluster, so it's ignored by default
+async fn test_kafka_fault_fails_some_sends() -> Result<()> {
+    let mut producer = KafkaProducer::new(
+        "localhost:9092",
+        "http://localhost:8081",
+        HashMap::from([("ticker".to_string(), "test.chaos.ticker".to_string())]),
+        "../schemas".to_string(),
+    ).await?;
+    producer.set_faults(&chaos(
+        FaultConfig { error_rate: 0.5, ..Default::default() },
+        FaultConfig::default(),
+    ));
+
+    let before = metrics::global().counter_value("chaos.kafka.errors");
+    let mut failed = 0;
+    for _ in 0..20 {
+        if producer.send_ticker(&ticker()).await.is_err() {
+            failed += 1;
+        }
+    }
+    assert!(failed > 0 && failed < 20);
+    assert_eq!(metrics::global().counter_value("chaos.kafka.errors") - before, failed);
+
+    producer.flush(Duration::from_secs(5)).await?;
+    assert_eq!(producer.pending_sends(), 0);
+    Ok(())
+}
+
+/// With a spill buffer, sends failed by an outage are kept and delivered once it ends
+///
+/// Note: This test requires a running Kafka and Schema Registry
+#[tokio::test]
+#[ignore] // This test requires a running Kafka cluster, so it's ignored by default
+async fn test_kafka_outage_spills_and_delivers_after_recovery() -> Result<()> {
+    let mut producer = KafkaProducer::new(
+        "localhost:9092",
+        "http://localhost:8081",
+        HashMap::from([("ticker".to_string(), "test.chaos.ticker".to_string())]),
+        "../schemas".to_string(),
+    ).await?;
+    let dead_letter = std::env::temp_dir()
+        .join(format!("kafka_spill_{}", uuid::Uuid::new_v4()))
+        .join("dead_letter.jsonl");
+    producer.set_spill(&SpillConfig { dead_letter_path: dead_letter.to_string_lossy().into_owned(), ..Default::default() });
+    producer.set_faults(&chaos(
+        FaultConfig { error_rate: 1.0, ..Default::default() },
+        FaultConfig::default(),
+    ));
+
+    for _ in 0..5 {
+        producer.send_ticker(&ticker()).await?;
+    }
+    assert_eq!(producer.spilled(), 5);
+    assert_eq!(producer.retry_spilled().await, 0);
+
+    // The outage is over
+    producer.set_faults(&ChaosConfig::default());
+    assert_eq!(producer.retry_spilled().await, 5);
+    assert_eq!(producer.spilled(), 0);
+
+    producer.flush(Duration::from_secs(5)).await?;
+    assert!(!dead_letter.exists());
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use anyhow::Result;
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{to_avro_datum, Schema};
+
+use cryptics_lab_bot::domain::enums::{MakerTaker, OrderSide, OrderStatus};
+use cryptics_lab_bot::domain::model::book::{BookLevelUpdate, BookSide, BookUpdateKind};
+use cryptics_lab_bot::domain::model::order::Order;
+use cryptics_lab_bot::domain::model::ticker_delta::{TickerDelta, TickerDeltaKind};
+use cryptics_lab_bot::domain::model::trade::Trade;
+use cryptics_lab_bot::infrastructure::kafka::helper::AvroConverter;
+use cryptics_lab_bot::infrastructure::kafka::{event_id, frame_confluent_payload, split_confluent_payload, ConfluentDecoder, TypedRecord};
+
+const SCHEMA_DIR: &str = "../schemas";
+
+fn schema(path: &str) -> Result<Schema> {
+    Ok(Schema::parse_str(&std::fs::read_to_string(format!("{}/{}", SCHEMA_DIR, path))?)?)
+}
+
+/// Frame a record the way the producer's encoder does
+fn frame(schema_id: i32, schema: &Schema, fields: Vec<(String, AvroValue)>) -> Result<Vec<u8>> {
+    let value = AvroValue::Record(fields).resolve(schema)?;
+    Ok(frame_confluent_payload(schema_id, &to_avro_datum(schema, value)?))
+}
+
+fn trade() -> Trade {
+    Trade {
+        trade_id: "TRD12345".to_string(),
+        order_id: "ORD67890".to_string(),
+        client_order_id: Some(42),
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        price: 65000.0,
+        amount: 0.25,
+        maker_taker: Some(MakerTaker::Taker),
+        time: 1792022400.5,
+        processing_timestamp: Some(1792022400.6),
+        variant_id: None,
+    }
+}
+
+/// Nothing listens on port 1, so any registry call would fail
+fn offline_decoder() -> ConfluentDecoder {
+    ConfluentDecoder::new("http://localhost:1")
+}
+
+#[test]
+fn test_split_confluent_payload() -> Result<()> {
+    let (schema_id, body) = split_confluent_payload(&[0, 0, 0, 1, 2, 7, 8])?;
+    assert_eq!(schema_id, 258);
+    assert_eq!(body, &[7, 8]);
+    
+    assert!(split_confluent_payload(&[0, 0, 0]).is_err());
+    let err = split_confluent_payload(&[1, 0, 0, 0, 1]).unwrap_err();
+    assert!(err.to_string().contains("magic byte"), "{}", err);
+    Ok(())
+}
+
+#[test]
+fn test_frame_confluent_payload() -> Result<()> {
+    let payload = frame_confluent_payload(258, &[7, 8]);
+    assert_eq!(payload, vec![0, 0, 0, 1, 2, 7, 8]);
+    assert_eq!(split_confluent_payload(&payload)?, (258, &[7u8, 8][..]));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_decodes_trade_into_domain_type() -> Result<()> {
+    let decoder = offline_decoder();
+    let latest = schema("trade/v4.avsc")?;
+    decoder.insert_schema(7, latest.clone());
+    
+    let mut fields = AvroConverter::trade_to_avro_value(&trade())?;
+    fields.push(("event_id".to_string(), AvroValue::Union(1, Box::new(AvroValue::Long(1001)))));
+    let payload = frame(7, &latest, fields)?;
+    
+    let TypedRecord::Trade(decoded) = decoder.decode_confluent_payload(&payload).await? else {
+        panic!("not a trade");
+    };
+    let expected = trade();
+    assert_eq!(decoded.trade_id, expected.trade_id);
+    assert_eq!(decoded.client_order_id, Some(42));
+    assert_eq!(decoded.price, expected.price);
+    assert_eq!(decoded.maker_taker, Some(MakerTaker::Taker));
+    assert_eq!(decoded.processing_timestamp, expected.processing_timestamp);
+    
+    let (schema_id, value) = decoder.decode_value(&payload).await?;
+    assert_eq!(schema_id, 7);
+    assert_eq!(event_id(&value), Some(1001));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_decodes_experiment_variant() -> Result<()> {
+    let decoder = offline_decoder();
+    let latest = schema("trade/v5.avsc")?;
+    decoder.insert_schema(8, latest.clone());
+    
+    let mut fields = AvroConverter::trade_to_avro_value(&trade())?;
+    fields.push(("variant_id".to_string(), AvroValue::Union(1, Box::new(AvroValue::String("wide".to_string())))));
+    let TypedRecord::Trade(decoded) = decoder.decode_confluent_payload(&frame(8, &latest, fields)?).await? else {
+        panic!("not a trade");
+    };
+    assert_eq!(decoded.variant_id.as_deref(), Some("wide"));
+    
+    // Records outside an experiment, and of older versions, have none
+    let TypedRecord::Trade(decoded) = decoder.decode_confluent_payload(&frame(8, &latest, AvroConverter::trade_to_avro_value(&trade())?)?).await? else {
+        panic!("not a trade");
+    };
+    assert_eq!(decoded.variant_id, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_decodes_records_of_older_versions() -> Result<()> {
+    let decoder = offline_decoder();
+    let v2 = schema("trade/v2.avsc")?;
+    decoder.insert_schema(3, v2.clone());
+    
+    // v2 has no typed role; the string field is mapped instead
+    let mut fields = AvroConverter::trade_to_avro_value(&trade())?;
+    fields.retain(|(name, _)| name != "maker_taker_role");
+    let payload = frame(3, &v2, fields)?;
+    
+    let TypedRecord::Trade(decoded) = decoder.decode_confluent_payload(&payload).await? else {
+        panic!("not a trade");
+    };
+    assert_eq!(decoded.maker_taker, Some(MakerTaker::Taker));
+    
+    let (_, value) = decoder.decode_value(&payload).await?;
+    assert_eq!(event_id(&value), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_decodes_ack_and_book() -> Result<()> {
+    let decoder = offline_decoder();
+    let ack_schema = schema("ack/v3.avsc")?;
+    let book_schema = schema("book/v2.avsc")?;
+    decoder.insert_schema(1, ack_schema.clone());
+    decoder.insert_schema(2, book_schema.clone());
+    
+    let mut order = Order::pending(77, "BTC-PERPETUAL".to_string(), OrderSide::Sell, 65010.0, 0.1);
+    order.order_id = "ORD1".to_string();
+    let payload = frame(1, &ack_schema, AvroConverter::ack_to_avro_value(&order))?;
+    let TypedRecord::Ack(decoded) = decoder.decode_confluent_payload(&payload).await? else {
+        panic!("not an ack");
+    };
+    assert_eq!(decoded.order_id, "ORD1");
+    assert_eq!(decoded.client_order_id, Some(77));
+    assert!(matches!(decoded.direction, OrderSide::Sell));
+    assert!(matches!(decoded.status, OrderStatus::Open));
+    assert_eq!(decoded.price, Some(65010.0));
+    
+    let update = BookLevelUpdate {
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        kind: BookUpdateKind::Snapshot,
+        sequence: 9,
+        side: BookSide::Bid,
+        price: 64990.0,
+        amount: 1.5,
+        exchange_time: None,
+        timestamp: 1792022400.0,
+        processing_timestamp: None,
+    };
+    let payload = frame(2, &book_schema, AvroConverter::book_level_to_avro_value(&update)?)?;
+    let TypedRecord::Book(decoded) = decoder.decode_confluent_payload(&payload).await? else {
+        panic!("not a book level");
+    };
+    assert_eq!(decoded, update);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_decodes_ticker_delta_with_only_changed_fields() -> Result<()> {
+    let decoder = offline_decoder();
+    let delta_schema = schema("ticker_delta/v1.avsc")?;
+    decoder.insert_schema(4, delta_schema.clone());
+    
+    let delta = TickerDelta {
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        kind: TickerDeltaKind::Delta,
+        sequence: 3,
+        changes: vec![("best_bid_price".to_string(), 64995.0), ("funding_rate".to_string(), 0.0001)],
+        timestamp: 1792022400.0,
+        processing_timestamp: Some(1792022400.0),
+    };
+    let payload = frame(4, &delta_schema, AvroConverter::ticker_delta_to_avro_value(&delta)?)?;
+    let TypedRecord::TickerDelta(decoded) = decoder.decode_confluent_payload(&payload).await? else {
+        panic!("not a ticker delta");
+    };
+    assert_eq!(decoded, delta);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_schema_without_domain_type_decodes_to_fields() -> Result<()> {
+    let decoder = offline_decoder();
+    let index = schema("index/v2.avsc")?;
+    decoder.insert_schema(5, index.clone());
+    
+    let payload = frame(5, &index, vec![
+        ("index_name".to_string(), AvroValue::String("BTCUSD".to_string())),
+        ("price".to_string(), AvroValue::Double(65000.0)),
+        ("timestamp".to_string(), AvroValue::Double(1792022400.0)),
+    ])?;
+    let TypedRecord::Other { name, fields } = decoder.decode_confluent_payload(&payload).await? else {
+        panic!("index has no domain type");
+    };
+    assert_eq!(name, "Index");
+    assert_eq!(fields[0], ("index_name".to_string(), AvroValue::String("BTCUSD".to_string())));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_corrupt_body_names_the_schema() -> Result<()> {
+    let decoder = offline_decoder();
+    decoder.insert_schema(7, schema("trade/v4.avsc")?);
+    
+    let err = decoder.decode_confluent_payload(&[0, 0, 0, 0, 7, 0xff]).await.unwrap_err();
+    assert!(err.to_string().contains("schema 7"), "{}", err);
+    Ok(())
+}
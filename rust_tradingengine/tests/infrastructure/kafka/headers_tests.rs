@@ -0,0 +1,41 @@
+use rdkafka::message::Headers;
+
+use cryptics_lab_bot::infrastructure::kafka::headers::{timestamp_millis, to_owned_headers, RecordMeta, HEADER_EVENT_TYPE, HEADER_INSTRUMENT, HEADER_PRODUCED_AT, HEADER_SCHEMA_ID};
+
+#[test]
+fn test_event_time_becomes_the_record_timestamp() {
+    let meta = RecordMeta::new("ticker").instrument(Some("BTC-PERPETUAL")).at(1792022400.1234);
+    assert_eq!(meta.timestamp_millis(), Some(1792022400123));
+    
+    // Unset exchange times leave the timestamp to the producer
+    assert_eq!(RecordMeta::new("ticker").timestamp_millis(), None);
+    assert_eq!(RecordMeta::new("ack").at(0.0).timestamp_millis(), None);
+    assert_eq!(timestamp_millis(f64::NAN), None);
+}
+
+#[test]
+fn test_record_headers() {
+    let meta = RecordMeta::new("trade").instrument(Some("BTC-PERPETUAL")).at(1792022400.0);
+    assert_eq!(meta.headers(Some(7), 1792022400500), vec![
+        (HEADER_EVENT_TYPE, "trade".to_string()),
+        (HEADER_SCHEMA_ID, "7".to_string()),
+        (HEADER_INSTRUMENT, "BTC-PERPETUAL".to_string()),
+        (HEADER_PRODUCED_AT, "1792022400500".to_string()),
+    ]);
+    
+    // Records not about an instrument, or without a framed payload, leave those headers out
+    let headers = RecordMeta::new("heartbeat").instrument(Some("")).headers(None, 1);
+    assert_eq!(headers, vec![(HEADER_EVENT_TYPE, "heartbeat".to_string()), (HEADER_PRODUCED_AT, "1".to_string())]);
+}
+
+#[test]
+fn test_owned_headers_keep_order_and_values() {
+    let owned = to_owned_headers(&RecordMeta::new("book").instrument(Some("ETH-PERPETUAL")).headers(Some(3), 42));
+    let pairs: Vec<(&str, Option<&[u8]>)> = owned.iter().map(|header| (header.key, header.value)).collect();
+    assert_eq!(pairs, vec![
+        (HEADER_EVENT_TYPE, Some(&b"book"[..])),
+        (HEADER_SCHEMA_ID, Some(&b"3"[..])),
+        (HEADER_INSTRUMENT, Some(&b"ETH-PERPETUAL"[..])),
+        (HEADER_PRODUCED_AT, Some(&b"42"[..])),
+    ]);
+}
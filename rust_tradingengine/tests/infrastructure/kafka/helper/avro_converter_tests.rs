@@ -1,6 +1,11 @@
 use apache_avro::types::Value as AvroValue;
-use cryptics_lab_bot::domain::enums::{OrderSide, OrderStatus, OrderType, TimeInForce};
-use cryptics_lab_bot::domain::model::ack::Ack;
+use cryptics_lab_bot::domain::enums::{MakerTaker, OrderSide, OrderStatus, OrderType, TimeInForce};
+use cryptics_lab_bot::domain::model::order::{Order, OrderState};
+use cryptics_lab_bot::domain::model::features::MarketFeatures;
+use cryptics_lab_bot::domain::model::uptime::QuoteUptime;
+use cryptics_lab_bot::domain::model::lifecycle::{LifecycleEvent, LifecycleEventType};
+use cryptics_lab_bot::domain::model::heartbeat::Heartbeat;
+use cryptics_lab_bot::domain::model::book::{BookLevelUpdate, BookSide, BookUpdateKind};
 use cryptics_lab_bot::domain::model::trade::Trade;
 use cryptics_lab_bot::domain::model::ticker::Ticker;
 use cryptics_lab_bot::infrastructure::kafka::helper::AvroConverter;
@@ -113,7 +118,7 @@ fn test_order_status_to_avro() {
 #[test]
 fn test_ack_to_avro_value() {
     // Create a test Ack object
-    let ack = Ack {
+    let ack = Order {
         order_id: "ORD12345".to_string(),
         client_order_id: Some(67890),
         instrument_name: "BTC-PERPETUAL".to_string(),
@@ -131,6 +136,8 @@ fn test_ack_to_avro_value() {
         create_time: 1645543210.123,
         persistent: true,
         processing_timestamp: Some(1645543210.456),
+        variant_id: None,
+        state: OrderState::Acknowledged,
     };
     
     // Convert to Avro value
@@ -210,16 +217,17 @@ fn test_trade_to_avro_value() {
         instrument_name: "ETH-PERPETUAL".to_string(),
         price: 3000.0,
         amount: 0.25,
-        maker_taker: "maker".to_string(),
+        maker_taker: Some(MakerTaker::Maker),
         time: 1645543210.123,
         processing_timestamp: Some(1645543210.456),
+        variant_id: None,
     };
     
     // Convert to Avro value
     let avro_fields = AvroConverter::trade_to_avro_value(&trade).unwrap();
     
     // Verify all fields are present
-    assert_eq!(avro_fields.len(), 9); // Ensure all fields are included (including processing_timestamp)
+    assert_eq!(avro_fields.len(), 10); // Ensure all fields are included (including processing_timestamp and maker_taker_role)
     
     // Verify specific fields and their values
     for (field_name, field_value) in &avro_fields {
@@ -259,6 +267,20 @@ fn test_trade_to_avro_value() {
                     _ => panic!("Expected Double for amount"),
                 }
             },
+            "maker_taker" => {
+                match field_value {
+                    AvroValue::String(s) => assert_eq!(s, "maker"),
+                    _ => panic!("Expected String for maker_taker"),
+                }
+            },
+            "maker_taker_role" => {
+                match field_value {
+                    AvroValue::Union(1, box_value) => {
+                        assert_eq!(**box_value, AvroValue::Enum(0, "maker".to_string()));
+                    },
+                    _ => panic!("Expected Union with Enum for maker_taker_role"),
+                }
+            },
             // Add more field checks as needed
             _ => {} // Skip other fields for brevity
         }
@@ -272,9 +294,10 @@ fn test_trade_to_avro_value() {
         instrument_name: "BTC-PERPETUAL".to_string(),
         price: 50000.0,
         amount: 0.1,
-        maker_taker: "taker".to_string(),
+        maker_taker: Some(MakerTaker::Taker),
         time: 1645543210.123,
         processing_timestamp: Some(1645543210.789),
+        variant_id: None,
     };
     
     let avro_fields = AvroConverter::trade_to_avro_value(&trade_without_client_id).unwrap();
@@ -293,6 +316,13 @@ fn test_trade_to_avro_value() {
             }
         }
     }
+    
+    // Without a reported role the string falls back to "unknown" and the typed field is null
+    let trade_without_role = Trade { maker_taker: None, ..trade_without_client_id };
+    let avro_fields = AvroConverter::trade_to_avro_value(&trade_without_role).unwrap();
+    let field = |name: &str| avro_fields.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone());
+    assert_eq!(field("maker_taker"), Some(AvroValue::String("unknown".to_string())));
+    assert_eq!(field("maker_taker_role"), Some(AvroValue::Union(0, Box::new(AvroValue::Null))));
 }
 
 #[test]
@@ -380,4 +410,161 @@ fn test_ticker_to_avro_value() {
             _ => {} // Skip other fields for brevity
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_features_to_avro_value() {
+    let features = MarketFeatures {
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        timestamp: 1645543210.0,
+        window_secs: 60.0,
+        imbalance: 0.25,
+        spread: 5.0,
+        trade_intensity: 0.5,
+        realized_vol: 0.001,
+        funding_basis: 0.0002,
+        funding_rate: 0.0001,
+        processing_timestamp: None,
+    };
+    
+    let avro_fields = AvroConverter::features_to_avro_value(&features).unwrap();
+    assert_eq!(avro_fields.len(), 10);
+    
+    let names: Vec<&str> = avro_fields.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec![
+        "instrument_name", "timestamp", "window_secs", "imbalance", "spread",
+        "trade_intensity", "realized_vol", "funding_basis", "funding_rate", "processing_timestamp",
+    ]);
+    
+    match &avro_fields[3].1 {
+        AvroValue::Double(v) => assert_eq!(*v, 0.25),
+        other => panic!("Expected Double for imbalance, got {:?}", other),
+    }
+    match &avro_fields[9].1 {
+        AvroValue::Union(0, inner) => assert_eq!(**inner, AvroValue::Null),
+        other => panic!("Expected null union for processing_timestamp, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_uptime_to_avro_value() {
+    let uptime = QuoteUptime {
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        date: "2026-10-15".to_string(),
+        period_start: 1760486400.0,
+        period_end: 1760572800.0,
+        observed_secs: 86400.0,
+        compliant_secs: 82080.0,
+        uptime_pct: 95.0,
+        max_distance_ticks: 50.0,
+        processing_timestamp: Some(1760572801.0),
+    };
+    
+    let avro_fields = AvroConverter::uptime_to_avro_value(&uptime).unwrap();
+    let names: Vec<&str> = avro_fields.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec![
+        "instrument_name", "date", "period_start", "period_end", "observed_secs",
+        "compliant_secs", "uptime_pct", "max_distance_ticks", "processing_timestamp",
+    ]);
+    
+    match &avro_fields[6].1 {
+        AvroValue::Double(v) => assert_eq!(*v, 95.0),
+        other => panic!("Expected Double for uptime_pct, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_lifecycle_to_avro_value() {
+    let event = LifecycleEvent {
+        instance_id: "5f0c6a9e-2d1b-4c3e-9a8f-0b1c2d3e4f50".to_string(),
+        event_type: LifecycleEventType::KillSwitch,
+        reason: Some("Clock drift exceeds 2s".to_string()),
+        network: "test".to_string(),
+        timestamp: 1792022400.0,
+        processing_timestamp: None,
+    };
+    
+    let avro_fields = AvroConverter::lifecycle_to_avro_value(&event).unwrap();
+    let names: Vec<&str> = avro_fields.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec![
+        "instance_id", "event_type", "reason", "network", "timestamp", "processing_timestamp",
+    ]);
+    
+    match &avro_fields[1].1 {
+        AvroValue::Enum(i, s) => {
+            assert_eq!(*i, 5);
+            assert_eq!(s, "kill_switch");
+        },
+        other => panic!("Expected Enum, got {:?}", other),
+    }
+    match &avro_fields[2].1 {
+        AvroValue::Union(1, inner) => assert_eq!(**inner, AvroValue::String("Clock drift exceeds 2s".to_string())),
+        other => panic!("Expected Union(1, String), got {:?}", other),
+    }
+    match &avro_fields[5].1 {
+        AvroValue::Union(0, inner) => assert_eq!(**inner, AvroValue::Null),
+        other => panic!("Expected Union(0, Null), got {:?}", other),
+    }
+}
+
+#[test]
+fn test_heartbeat_to_avro_value() {
+    let heartbeat = Heartbeat {
+        instance_id: "5f0c6a9e-2d1b-4c3e-9a8f-0b1c2d3e4f50".to_string(),
+        sequence: 42,
+        positions_hash: "af63bd4c8601b7df".to_string(),
+        last_ticker_time: Some(1792022399.5),
+        last_index_time: None,
+        last_order_time: None,
+        last_trade_time: None,
+        timestamp: 1792022400.0,
+        processing_timestamp: Some(1792022400.0),
+    };
+    
+    let avro_fields = AvroConverter::heartbeat_to_avro_value(&heartbeat).unwrap();
+    let names: Vec<&str> = avro_fields.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec![
+        "instance_id", "sequence", "positions_hash", "last_ticker_time", "last_index_time",
+        "last_order_time", "last_trade_time", "timestamp", "processing_timestamp",
+    ]);
+    
+    assert_eq!(avro_fields[1].1, AvroValue::Long(42));
+    match &avro_fields[3].1 {
+        AvroValue::Union(1, inner) => assert_eq!(**inner, AvroValue::Double(1792022399.5)),
+        other => panic!("Expected Union(1, Double), got {:?}", other),
+    }
+    match &avro_fields[4].1 {
+        AvroValue::Union(0, inner) => assert_eq!(**inner, AvroValue::Null),
+        other => panic!("Expected Union(0, Null), got {:?}", other),
+    }
+}
+
+#[test]
+fn test_book_level_to_avro_value() {
+    let update = BookLevelUpdate {
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        kind: BookUpdateKind::Snapshot,
+        sequence: 1,
+        side: BookSide::Ask,
+        price: 65010.0,
+        amount: 0.25,
+        exchange_time: None,
+        timestamp: 1792022400.0,
+        processing_timestamp: Some(1792022400.0),
+    };
+    
+    let avro_fields = AvroConverter::book_level_to_avro_value(&update).unwrap();
+    let names: Vec<&str> = avro_fields.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec![
+        "instrument_name", "kind", "sequence", "side", "price", "amount",
+        "exchange_time", "timestamp", "processing_timestamp",
+    ]);
+    
+    assert_eq!(avro_fields[1].1, AvroValue::Enum(0, "snapshot".to_string()));
+    assert_eq!(avro_fields[3].1, AvroValue::Enum(1, "ask".to_string()));
+    assert_eq!(avro_fields[2].1, AvroValue::Long(1));
+    match &avro_fields[6].1 {
+        AvroValue::Union(0, inner) => assert_eq!(**inner, AvroValue::Null),
+        other => panic!("Expected Union(0, Null), got {:?}", other),
+    }
+}
@@ -2,3 +2,5 @@
 
 // Import test modules
 pub mod avro_converter_tests;
+pub mod schema_helper_tests;
+pub mod schema_validator_tests;
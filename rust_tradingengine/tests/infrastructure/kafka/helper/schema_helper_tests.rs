@@ -0,0 +1,57 @@
+use anyhow::Result;
+
+use cryptics_lab_bot::infrastructure::kafka::helper::{compare_schemas, SchemaChange, SchemaHelper};
+
+const SCHEMA_DIR: &str = "../schemas";
+
+fn record(fields: &str) -> String {
+    format!(r#"{{"type": "record", "name": "Sample", "fields": [{}]}}"#, fields)
+}
+
+#[test]
+fn test_latest_schema_is_highest_version() -> Result<()> {
+    let helper = SchemaHelper::new(SCHEMA_DIR.to_string());
+    let path = helper.find_latest_schema("trade")?;
+    assert_eq!(path.file_name().unwrap(), "v5.avsc");
+    Ok(())
+}
+
+#[test]
+fn test_added_optional_fields_upgrade() -> Result<()> {
+    let helper = SchemaHelper::new(SCHEMA_DIR.to_string());
+    let v2 = std::fs::read_to_string(format!("{}/trade/v2.avsc", SCHEMA_DIR))?;
+    let v3 = std::fs::read_to_string(format!("{}/trade/v3.avsc", SCHEMA_DIR))?;
+    let v4 = std::fs::read_to_string(format!("{}/trade/v4.avsc", SCHEMA_DIR))?;
+    let latest = helper.get_schema_content("trade")?;
+    
+    assert_eq!(compare_schemas(&v2, &v3)?, SchemaChange::AddedOptionalFields(vec!["maker_taker_role".to_string()]));
+    assert_eq!(compare_schemas(&v3, &v4)?, SchemaChange::AddedOptionalFields(vec!["event_id".to_string()]));
+    assert_eq!(compare_schemas(&v4, &latest)?, SchemaChange::AddedOptionalFields(vec!["variant_id".to_string()]));
+    assert_eq!(compare_schemas(&v2, &latest)?,
+        SchemaChange::AddedOptionalFields(vec!["event_id".to_string(), "maker_taker_role".to_string(), "variant_id".to_string()]));
+    assert_eq!(compare_schemas(&latest, &latest)?, SchemaChange::Unchanged);
+    Ok(())
+}
+
+#[test]
+fn test_docs_are_ignored() -> Result<()> {
+    let registered = record(r#"{"name": "a", "type": "string", "doc": "old"}"#);
+    let local = record(r#"{"name": "a", "type": "string", "doc": "new"}"#);
+    assert_eq!(compare_schemas(&registered, &local)?, SchemaChange::Unchanged);
+    Ok(())
+}
+
+#[test]
+fn test_other_changes_are_incompatible() -> Result<()> {
+    let registered = record(r#"{"name": "a", "type": "string"}, {"name": "b", "type": "double"}"#);
+    
+    let removed = record(r#"{"name": "a", "type": "string"}"#);
+    let retyped = record(r#"{"name": "a", "type": "string"}, {"name": "b", "type": "long"}"#);
+    let required = record(r#"{"name": "a", "type": "string"}, {"name": "b", "type": "double"}, {"name": "c", "type": "int"}"#);
+    
+    for local in [removed, retyped, required] {
+        assert!(matches!(compare_schemas(&registered, &local)?, SchemaChange::Incompatible(_)), "{}", local);
+    }
+    assert!(compare_schemas(&registered, "not a schema").is_err());
+    Ok(())
+}
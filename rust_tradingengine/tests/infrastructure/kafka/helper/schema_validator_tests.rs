@@ -0,0 +1,76 @@
+use apache_avro::types::Value as AvroValue;
+use apache_avro::Schema;
+
+use cryptics_lab_bot::infrastructure::kafka::helper::validate_record;
+
+fn schema() -> Schema {
+    Schema::parse_str(r#"{
+        "type": "record",
+        "name": "Order",
+        "fields": [
+            {"name": "order_id", "type": "string"},
+            {"name": "amount", "type": "double"},
+            {"name": "side", "type": {"type": "enum", "name": "Side", "symbols": ["buy", "sell"]}},
+            {"name": "price", "type": ["null", "double"], "default": null}
+        ]
+    }"#).unwrap()
+}
+
+fn record() -> Vec<(String, AvroValue)> {
+    vec![
+        ("order_id".to_string(), AvroValue::String("ORD1".to_string())),
+        ("amount".to_string(), AvroValue::Double(0.1)),
+        ("side".to_string(), AvroValue::Enum(1, "sell".to_string())),
+        ("price".to_string(), AvroValue::Union(1, Box::new(AvroValue::Double(50000.0)))),
+    ]
+}
+
+fn with(name: &str, value: AvroValue) -> Vec<(String, AvroValue)> {
+    record().into_iter()
+        .map(|(field, v)| if field == name { (field, value.clone()) } else { (field, v) })
+        .collect()
+}
+
+fn error(fields: &[(String, AvroValue)]) -> String {
+    validate_record(&schema(), fields).unwrap_err().to_string()
+}
+
+#[test]
+fn test_valid_record_passes() {
+    assert!(validate_record(&schema(), &record()).is_ok());
+    
+    // Fields with a default may be left out
+    let without_price: Vec<_> = record().into_iter().filter(|(name, _)| name != "price").collect();
+    assert!(validate_record(&schema(), &without_price).is_ok());
+}
+
+#[test]
+fn test_missing_and_unknown_fields_are_named() {
+    let without_amount: Vec<_> = record().into_iter().filter(|(name, _)| name != "amount").collect();
+    assert_eq!(error(&without_amount), "Field 'amount' is missing and has no default");
+    
+    let mut extra = record();
+    extra.push(("venue".to_string(), AvroValue::String("thalex".to_string())));
+    assert_eq!(error(&extra), "Field 'venue' is not in schema Order");
+}
+
+#[test]
+fn test_type_mismatch_names_field_and_types() {
+    let message = error(&with("amount", AvroValue::String("0.1".to_string())));
+    assert!(message.starts_with("Field 'amount': expected double"), "{}", message);
+    
+    let message = error(&with("price", AvroValue::Union(1, Box::new(AvroValue::String("x".to_string())))));
+    assert!(message.starts_with("Field 'price': expected double"), "{}", message);
+}
+
+#[test]
+fn test_enum_symbols_checked() {
+    assert_eq!(
+        error(&with("side", AvroValue::Enum(2, "hold".to_string()))),
+        "Field 'side': enum symbol 'hold' not in [buy, sell]"
+    );
+    assert_eq!(
+        error(&with("side", AvroValue::Enum(0, "sell".to_string()))),
+        "Field 'side': enum symbol 'sell' has index 0, expected 1"
+    );
+}
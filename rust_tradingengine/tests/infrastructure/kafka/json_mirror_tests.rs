@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use apache_avro::types::Value as AvroValue;
+use cryptics_lab_bot::config_loader::{JsonMirrorConfig, TopicType};
+use cryptics_lab_bot::domain::model::heartbeat::Heartbeat;
+use cryptics_lab_bot::infrastructure::kafka::helper::AvroConverter;
+use cryptics_lab_bot::infrastructure::kafka::json_mirror::{mirror_topic, mirror_topics, record_to_json};
+use serde_json::{json, Value};
+
+fn topics() -> HashMap<String, TopicType> {
+    HashMap::from([
+        ("ticker".to_string(), TopicType::new("thalex.ticker.avro")),
+        ("trade".to_string(), TopicType::new("thalex.trade.avro")),
+        ("heartbeat".to_string(), TopicType::new("heartbeat")),
+    ])
+}
+
+#[test]
+fn test_mirror_topic_replaces_the_avro_suffix() {
+    assert_eq!(mirror_topic("thalex.ticker.avro"), "thalex.ticker.json");
+    assert_eq!(mirror_topic("heartbeat"), "heartbeat.json");
+}
+
+#[test]
+fn test_mirror_topics_follow_the_config() {
+    // Nothing is mirrored unless enabled
+    assert!(mirror_topics(&JsonMirrorConfig::default(), &topics()).is_empty());
+    
+    let all = mirror_topics(&JsonMirrorConfig { enabled: true, topic_types: Vec::new() }, &topics());
+    assert_eq!(all.len(), 3);
+    assert_eq!(all["thalex.trade.avro"], "thalex.trade.json");
+    
+    let some = mirror_topics(&JsonMirrorConfig {
+        enabled: true,
+        topic_types: vec!["ticker".to_string(), "unknown".to_string()],
+    }, &topics());
+    assert_eq!(some, HashMap::from([("thalex.ticker.avro".to_string(), "thalex.ticker.json".to_string())]));
+}
+
+#[test]
+fn test_record_to_json_keeps_every_field() {
+    let heartbeat = Heartbeat {
+        instance_id: "bot-1".to_string(),
+        sequence: 7,
+        positions_hash: "abc".to_string(),
+        last_ticker_time: Some(1_700_000_000.5),
+        last_index_time: None,
+        last_order_time: None,
+        last_trade_time: None,
+        timestamp: 1_700_000_001.0,
+        processing_timestamp: None,
+    };
+    let mut fields = AvroConverter::heartbeat_to_avro_value(&heartbeat).unwrap();
+    fields.push(("event_id".to_string(), AvroValue::Long(42)));
+    
+    let json: Value = serde_json::from_slice(&record_to_json(&fields).unwrap()).unwrap();
+    
+    // Unions are written as their plain value
+    assert_eq!(json, json!({
+        "instance_id": "bot-1",
+        "sequence": 7,
+        "positions_hash": "abc",
+        "last_ticker_time": 1_700_000_000.5,
+        "last_index_time": null,
+        "last_order_time": null,
+        "last_trade_time": null,
+        "timestamp": 1_700_000_001.0,
+        "processing_timestamp": null,
+        "event_id": 42,
+    }));
+}
+
+#[test]
+fn test_record_to_json_rejects_non_finite_numbers() {
+    let fields = vec![("mark_price".to_string(), AvroValue::Double(f64::NAN))];
+    assert!(record_to_json(&fields).is_err());
+}
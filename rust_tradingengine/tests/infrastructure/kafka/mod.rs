@@ -1,7 +1,17 @@
 //! Tests for Kafka-related components
 
 // Import test modules
+#[cfg(feature = "chaos")]
+pub mod chaos_integration_tests;
+pub mod decoder_tests;
+pub mod headers_tests;
 pub mod helper;
+pub mod json_mirror_tests;
 pub mod producer_tests;
+pub mod registry_tests;
+pub mod schema_compatibility_tests;
+pub mod spill_tests;
 pub mod ticker_integration_tests;
+pub mod topic_admin_tests;
 pub mod trade_integration_tests;
+pub mod watchdog_tests;
@@ -2,9 +2,12 @@ use std::collections::HashMap;
 use anyhow::Result;
 use serde_json::json;
 
+use cryptics_lab_bot::domain::enums::MakerTaker;
 use cryptics_lab_bot::domain::model::ticker::Ticker;
 use cryptics_lab_bot::infrastructure::kafka::helper::AvroConverter;
+use cryptics_lab_bot::infrastructure::kafka::{decode_avro, event_id, split_confluent_payload, KafkaProducer, TypedRecord};
 use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
+use cryptics_lab_bot::testing::fixtures;
 
 // Test for the AvroConverter and the Ticker model
 #[test]
@@ -85,7 +88,7 @@ fn test_trade_from_json() -> Result<()> {
     });
     
     // Parse the JSON into a Trade
-    let trade = ThaleParser::parse_trade_json(&json_data)?;
+    let trade = ThaleParser::parse_trade_json(&json_data, false)?;
     
     // Verify the fields
     assert_eq!(trade.trade_id, "trd-123456");
@@ -94,14 +97,14 @@ fn test_trade_from_json() -> Result<()> {
     assert_eq!(trade.instrument_name, "BTC-PERPETUAL");
     assert_eq!(trade.price, 50000.0);
     assert_eq!(trade.amount, 0.1);
-    assert_eq!(trade.maker_taker, "maker");
+    assert_eq!(trade.maker_taker, Some(MakerTaker::Maker));
     assert_eq!(trade.time, 1645543210.123);
     
     // Now test converting the Trade to Avro
     let avro_fields = AvroConverter::trade_to_avro_value(&trade)?;
     
     // Verify all fields are present
-    assert_eq!(avro_fields.len(), 9); // Ensure all fields are included (including processing_timestamp)
+    assert_eq!(avro_fields.len(), 10); // Ensure all fields are included (including processing_timestamp and maker_taker_role)
     
     // Check some key fields
     let field_map: HashMap<_, _> = avro_fields.iter().cloned().collect();
@@ -174,8 +177,8 @@ fn test_extract_trades_from_order() -> Result<()> {
     });
     
     // Parse the Ack and extract trades
-    let ack = ThaleParser::parse_ack_json(&order_data)?;
-    let trades = ThaleParser::extract_trades_from_order(&order_data)?;
+    let ack = ThaleParser::parse_order_json(&order_data)?;
+    let trades = ThaleParser::extract_trades_from_order(&order_data, false)?;
     
     // Verify the Ack
     assert_eq!(ack.order_id, "ord-123456");
@@ -194,3 +197,136 @@ fn test_extract_trades_from_order() -> Result<()> {
     
     Ok(())
 }
+
+#[tokio::test]
+async fn test_event_ids_increase_per_topic() -> Result<()> {
+    // No topic types, so nothing is preloaded from the unreachable registry
+    let producer = KafkaProducer::new("localhost:1", "http://localhost:1", HashMap::new(), "../schemas".to_string()).await?;
+    
+    let first = producer.next_event_id("test.trades");
+    assert_eq!(producer.next_event_id("test.trades"), first + 1);
+    assert_eq!(producer.next_event_id("test.trades"), first + 2);
+    
+    // Each topic has its own sequence, starting from the same base
+    assert_eq!(producer.next_event_id("test.tickers"), first);
+    
+    // A restarted instance continues above the previous one
+    let restarted = KafkaProducer::new("localhost:1", "http://localhost:1", HashMap::new(), "../schemas".to_string()).await?;
+    assert!(restarted.next_event_id("test.trades") > first + 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_journal_only_mode_writes_records_to_journal() -> Result<()> {
+    let mut producer = KafkaProducer::new("localhost:1", "http://localhost:1", HashMap::new(), "../schemas".to_string()).await?;
+    assert!(producer.set_journal_only(true).is_err());
+    
+    let path = std::env::temp_dir()
+        .join(format!("kafka_journal_{}", uuid::Uuid::new_v4()))
+        .join("journal.jsonl");
+    producer.set_journal(&path);
+    producer.set_journal_only(true)?;
+    
+    producer.send_json("test.raw", "key-1", &json!({"a": 1})).await?;
+    producer.send_json("test.raw", "key-2", &json!({"b": 2})).await?;
+    
+    let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["topic"], "test.raw");
+    assert_eq!(lines[1]["key"], "key-2");
+    // {"a":1}, hex encoded
+    assert_eq!(lines[0]["payload"], "7b2261223a317d");
+    assert_eq!(producer.backlog(), 0);
+    
+    producer.set_journal_only(false)?;
+    assert!(!producer.is_journal_only());
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_records_carry_event_time_and_headers() -> Result<()> {
+    let mut producer = KafkaProducer::new("localhost:1", "http://localhost:1", HashMap::new(), "../schemas".to_string()).await?;
+    let schema = apache_avro::Schema::parse_str(&std::fs::read_to_string("../schemas/ticker/v3.avsc")?)?;
+    producer.cache_schema(&producer.get_topic("ticker"), 7, schema);
+    
+    let path = std::env::temp_dir()
+        .join(format!("kafka_journal_{}", uuid::Uuid::new_v4()))
+        .join("journal.jsonl");
+    producer.set_journal(&path);
+    producer.set_journal_only(true)?;
+    
+    let mut ticker = Ticker::new("BTC-PERPETUAL".to_string());
+    ticker.mark_timestamp = 1792022400.25;
+    producer.send_ticker(&ticker).await?;
+    
+    let line: serde_json::Value = serde_json::from_str(std::fs::read_to_string(&path)?.trim())?;
+    assert_eq!(line["timestamp"], 1792022400250i64);
+    assert_eq!(line["headers"]["event_type"], "ticker");
+    assert_eq!(line["headers"]["schema_id"], "7");
+    assert_eq!(line["headers"]["instrument"], "BTC-PERPETUAL");
+    let produced_at: i64 = line["headers"]["produced_at"].as_str().unwrap().parse()?;
+    assert!(produced_at > 1_700_000_000_000);
+    
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ack_timestamp_is_when_the_notification_arrived() -> Result<()> {
+    let mut producer = KafkaProducer::new("localhost:1", "http://localhost:1", HashMap::new(), "../schemas".to_string()).await?;
+    let schema = apache_avro::Schema::parse_str(&std::fs::read_to_string("../schemas/ack/v4.avsc")?)?;
+    producer.cache_schema(&producer.get_topic("ack"), 4, schema);
+    
+    let path = std::env::temp_dir()
+        .join(format!("kafka_journal_{}", uuid::Uuid::new_v4()))
+        .join("journal.jsonl");
+    producer.set_journal(&path);
+    producer.set_journal_only(true)?;
+    
+    // A fill notification for an order created an hour earlier
+    let mut ack = fixtures::ack();
+    ack.create_time = 1792018800.0;
+    ack.processing_timestamp = Some(1792022400.5);
+    producer.send_ack(&ack).await?;
+    
+    let line: serde_json::Value = serde_json::from_str(std::fs::read_to_string(&path)?.trim())?;
+    assert_eq!(line["timestamp"], 1792022400500i64);
+    assert_eq!(line["headers"]["schema_id"], "4");
+    
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cached_schema_encodes_locally_into_reused_buffers() -> Result<()> {
+    let producer = KafkaProducer::new("localhost:1", "http://localhost:1", HashMap::new(), "../schemas".to_string()).await?;
+    let schema = apache_avro::Schema::parse_str(&std::fs::read_to_string("../schemas/ticker/v3.avsc")?)?;
+    producer.cache_schema("test.ticker", 7, schema.clone());
+    
+    let mut ticker = Ticker::new("BTC-PERPETUAL".to_string());
+    ticker.mark_price = 65010.0;
+    ticker.processing_timestamp = Some(1792022400.0);
+    let fields = AvroConverter::ticker_to_avro_value(&ticker)?;
+    
+    let payload = producer.encode_confluent_format("ticker", fields.clone(), "test.ticker").await?;
+    let (schema_id, body) = split_confluent_payload(&payload)?;
+    assert_eq!(schema_id, 7);
+    let value = decode_avro(&schema, body)?;
+    assert!(event_id(&value).is_some());
+    let TypedRecord::Ticker(decoded) = TypedRecord::from_avro("ThalexTicker", value)? else {
+        panic!("not a ticker");
+    };
+    assert_eq!(decoded.numeric_values(), ticker.numeric_values());
+    assert_eq!(decoded.processing_timestamp, ticker.processing_timestamp);
+    
+    // Once sent, the buffer is handed to the next record
+    let first = payload.as_ptr();
+    drop(payload);
+    let payload = producer.encode_confluent_format("ticker", fields, "test.ticker").await?;
+    assert_eq!(payload.as_ptr(), first);
+    Ok(())
+}
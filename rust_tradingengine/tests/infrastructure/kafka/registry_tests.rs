@@ -0,0 +1,121 @@
+use anyhow::anyhow;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use cryptics_lab_bot::config_loader::RegistryRetryConfig;
+use cryptics_lab_bot::infrastructure::kafka::registry::{CircuitBreaker, Failure, RegistryClient};
+
+fn fast_config(max_retries: u32, failure_threshold: u32) -> RegistryRetryConfig {
+    RegistryRetryConfig {
+        max_retries,
+        initial_backoff_ms: 1,
+        max_backoff_ms: 2,
+        failure_threshold,
+        open_cooldown_ms: 60_000,
+        request_timeout_ms: 5000,
+    }
+}
+
+#[tokio::test]
+async fn test_transient_failures_are_retried() {
+    let registry = RegistryClient::new(fast_config(3, 10));
+    let attempts = AtomicU32::new(0);
+    
+    let result = registry.call("lookup", || async {
+        match attempts.fetch_add(1, Ordering::SeqCst) {
+            0 | 1 => Err(Failure::Transient(anyhow!("503"))),
+            _ => Ok(42),
+        }
+    }).await;
+    
+    assert_eq!(result.unwrap(), 42);
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_retries_are_bounded_and_permanent_failures_are_not_retried() {
+    let registry = RegistryClient::new(fast_config(2, 10));
+    let attempts = AtomicU32::new(0);
+    let result: anyhow::Result<()> = registry.call("lookup", || async {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        Err(Failure::Transient(anyhow!("timeout")))
+    }).await;
+    assert!(result.unwrap_err().to_string().contains("lookup failed after 2 retries"));
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    
+    attempts.store(0, Ordering::SeqCst);
+    let result: anyhow::Result<()> = registry.call("register", || async {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        Err(Failure::Permanent(anyhow!("422 invalid schema")))
+    }).await;
+    assert_eq!(result.unwrap_err().to_string(), "422 invalid schema");
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_open_circuit_short_circuits_calls() {
+    let registry = RegistryClient::new(fast_config(5, 2));
+    let attempts = AtomicU32::new(0);
+    let result: anyhow::Result<()> = registry.call("lookup", || async {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        Err(Failure::Transient(anyhow!("connection refused")))
+    }).await;
+    
+    // The second failure opens the circuit before the retries run out
+    assert!(result.unwrap_err().to_string().contains("circuit open"));
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert!(registry.is_open());
+    
+    let result = registry.call("lookup", || async { Ok(1) }).await;
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_breaker_half_opens_after_cooldown() {
+    let now = Instant::now();
+    let mut breaker = CircuitBreaker::new(2, Duration::from_secs(10));
+    breaker.on_failure(now);
+    assert!(!breaker.is_open(now));
+    breaker.on_failure(now);
+    assert!(breaker.is_open(now + Duration::from_secs(9)));
+    
+    // One trial after the cooldown; failing it reopens the circuit right away
+    let later = now + Duration::from_secs(11);
+    assert!(!breaker.is_open(later));
+    breaker.on_failure(later);
+    assert!(breaker.is_open(later));
+    
+    breaker.on_success();
+    assert!(!breaker.is_open(later));
+}
+
+#[test]
+fn test_backoff_is_capped_and_jittered() {
+    let registry = RegistryClient::new(RegistryRetryConfig {
+        initial_backoff_ms: 100,
+        max_backoff_ms: 300,
+        ..Default::default()
+    });
+    for _ in 0..20 {
+        let first = registry.backoff(1);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100), "{:?}", first);
+        assert!(registry.backoff(10) <= Duration::from_millis(300));
+    }
+}
+
+#[tokio::test]
+async fn test_unanswered_request_times_out() {
+    // Accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/subjects", listener.local_addr().unwrap());
+    let registry = RegistryClient::new(RegistryRetryConfig {
+        request_timeout_ms: 50,
+        ..fast_config(1, 10)
+    });
+    
+    let started = Instant::now();
+    let err = registry.send("lookup", |http| http.get(&url)).await.unwrap_err();
+    assert!(format!("{:#}", err).contains("failed after 1 retries"), "{:#}", err);
+    assert!(started.elapsed() < Duration::from_secs(2), "{:?}", started.elapsed());
+    drop(listener);
+}
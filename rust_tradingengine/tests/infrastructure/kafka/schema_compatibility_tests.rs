@@ -0,0 +1,321 @@
+//! Golden-file checks: every schema under `schemas/` must round-trip a record produced
+//! by `AvroConverter`, so struct/schema drift fails here instead of at the registry
+
+use anyhow::{anyhow, Context, Result};
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{from_avro_datum, to_avro_datum, Schema};
+use std::path::{Path, PathBuf};
+
+use cryptics_lab_bot::domain::enums::{MakerTaker, OrderSide};
+use cryptics_lab_bot::domain::model::features::MarketFeatures;
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::domain::model::ticker_delta::{TickerDelta, TickerDeltaKind};
+use cryptics_lab_bot::domain::model::trade::Trade;
+use cryptics_lab_bot::domain::model::uptime::QuoteUptime;
+use cryptics_lab_bot::domain::model::lifecycle::{LifecycleEvent, LifecycleEventType};
+use cryptics_lab_bot::domain::model::heartbeat::Heartbeat;
+use cryptics_lab_bot::domain::model::session_summary::SessionSummary;
+use cryptics_lab_bot::domain::model::funding_basis::FundingBasis;
+use cryptics_lab_bot::domain::model::account_event::{AccountEvent, AccountEventType};
+use cryptics_lab_bot::domain::model::trade_correction::{CorrectionKind, TradeCorrection};
+use cryptics_lab_bot::domain::model::account_summary::{AccountSummary, Balance};
+use cryptics_lab_bot::domain::model::book::{BookLevelUpdate, BookUpdateKind};
+use cryptics_lab_bot::infrastructure::kafka::helper::{validate_record, AvroConverter};
+use cryptics_lab_bot::testing::fixtures;
+
+const SCHEMA_DIR: &str = "../schemas";
+
+/// Schema types published without going through `AvroConverter`
+const WITHOUT_CONVERTER: &[&str] = &["index"];
+
+/// Sample record for a schema type, as the producer would build it
+fn sample(schema_type: &str) -> Result<Option<Vec<(String, AvroValue)>>> {
+    let fields = match schema_type {
+        "ack" => AvroConverter::ack_to_avro_value(&fixtures::ack()),
+        "trade" => AvroConverter::trade_to_avro_value(&Trade {
+            client_order_id: None,
+            maker_taker: Some(MakerTaker::Taker),
+            ..fixtures::trade(67890, 3000.0, 0.25)
+        })?,
+        "ticker" => AvroConverter::ticker_to_avro_value(&Ticker {
+            processing_timestamp: None,
+            ..fixtures::ticker(fixtures::INSTRUMENT)
+        })?,
+        "ticker_delta" => AvroConverter::ticker_delta_to_avro_value(&TickerDelta {
+            instrument_name: "BTC-PERPETUAL".to_string(),
+            kind: TickerDeltaKind::Delta,
+            sequence: 12,
+            changes: vec![("mark_price".to_string(), 50001.0), ("open_interest".to_string(), 501.0)],
+            timestamp: 1645543210.123,
+            processing_timestamp: Some(1645543210.456),
+        })?,
+        "features" => AvroConverter::features_to_avro_value(&MarketFeatures {
+            instrument_name: "BTC-PERPETUAL".to_string(),
+            timestamp: 1645543210.0,
+            window_secs: 60.0,
+            imbalance: -0.25,
+            spread: 5.0,
+            trade_intensity: 0.5,
+            realized_vol: 0.001,
+            funding_basis: 0.0002,
+            funding_rate: 0.0001,
+            processing_timestamp: Some(1645543210.5),
+        })?,
+        "uptime" => AvroConverter::uptime_to_avro_value(&QuoteUptime {
+            instrument_name: "BTC-PERPETUAL".to_string(),
+            date: "2026-10-15".to_string(),
+            period_start: 1792022400.0,
+            period_end: 1792108800.0,
+            observed_secs: 86400.0,
+            compliant_secs: 82080.0,
+            uptime_pct: 95.0,
+            max_distance_ticks: 50.0,
+            processing_timestamp: None,
+        })?,
+        "lifecycle" => AvroConverter::lifecycle_to_avro_value(&LifecycleEvent {
+            instance_id: "5f0c6a9e-2d1b-4c3e-9a8f-0b1c2d3e4f50".to_string(),
+            event_type: LifecycleEventType::Reconnect,
+            reason: Some("session timed out".to_string()),
+            network: "test".to_string(),
+            timestamp: 1792022400.0,
+            processing_timestamp: None,
+        })?,
+        "heartbeat" => AvroConverter::heartbeat_to_avro_value(&Heartbeat {
+            instance_id: "5f0c6a9e-2d1b-4c3e-9a8f-0b1c2d3e4f50".to_string(),
+            sequence: 42,
+            positions_hash: "af63bd4c8601b7df".to_string(),
+            last_ticker_time: Some(1792022399.5),
+            last_index_time: Some(1792022399.0),
+            last_order_time: None,
+            last_trade_time: None,
+            timestamp: 1792022400.0,
+            processing_timestamp: None,
+        })?,
+        "session_summary" => AvroConverter::session_summary_to_avro_value(&SessionSummary {
+            instance_id: "5f0c6a9e-2d1b-4c3e-9a8f-0b1c2d3e4f50".to_string(),
+            started_at: 1792000000.0,
+            ended_at: 1792022400.0,
+            quotes_sent: 1250,
+            amends: 8400,
+            cancels: 310,
+            fills: 42,
+            volume: 3.7,
+            realized_pnl: 128.5,
+            max_inventory: 0.6,
+            reconnects: 2,
+            processing_timestamp: None,
+        })?,
+        "funding_basis" => AvroConverter::funding_basis_to_avro_value(&FundingBasis {
+            instrument_name: "BTC-PERPETUAL".to_string(),
+            timestamp: 1792022400.0,
+            window_secs: 3600.0,
+            mark_price: 65_013.0,
+            index_price: 65_000.0,
+            basis: 13.0,
+            basis_bps: 2.0,
+            rolling_basis_bps: 1.6,
+            funding_rate: 0.0001,
+            annualized_funding_rate: 0.1095,
+            position: -0.4,
+            cumulative_funding: 3.2,
+            annualized_carry: 0.1095,
+            processing_timestamp: Some(1792022400.1),
+        })?,
+        "account_event" => AvroConverter::account_event_to_avro_value(&AccountEvent {
+            event_type: AccountEventType::Liquidation,
+            timestamp: 1792022400.0,
+            currency: None,
+            amount: Some(0.4),
+            instrument_name: Some("BTC-PERPETUAL".to_string()),
+            message: Some("Position liquidated".to_string()),
+            processing_timestamp: Some(1792022400.1),
+        })?,
+        "trade_correction" => AvroConverter::trade_correction_to_avro_value(&TradeCorrection {
+            kind: CorrectionKind::Correction,
+            trade_id: "T00000042".to_string(),
+            order_id: Some("00000042".to_string()),
+            instrument_name: Some("BTC-PERPETUAL".to_string()),
+            direction: Some(OrderSide::Buy),
+            original_price: Some(65_000.0),
+            original_amount: Some(0.2),
+            corrected_price: Some(65_010.0),
+            corrected_amount: None,
+            message: Some("Trade corrected".to_string()),
+            timestamp: 1792022400.0,
+            processing_timestamp: Some(1792022400.1),
+        })?,
+        "account_summary" => AvroConverter::account_summary_to_avro_value(&AccountSummary {
+            timestamp: 1792022400.0,
+            balances: vec![
+                Balance { currency: "USDC".to_string(), balance: 25_000.0, collateral_factor: Some(1.0) },
+                Balance { currency: "BTC".to_string(), balance: 0.5, collateral_factor: None },
+            ],
+            cash_collateral: 57_000.0,
+            unrealised_pnl: -120.0,
+            margin: 56_880.0,
+            required_margin: 4_300.0,
+            remaining_margin: 52_580.0,
+            session_realised_pnl: 85.0,
+            processing_timestamp: Some(1792022400.1),
+        })?,
+        "book" => AvroConverter::book_level_to_avro_value(&BookLevelUpdate {
+            kind: BookUpdateKind::Delta,
+            amount: 0.0,
+            ..fixtures::book_snapshot(fixtures::INSTRUMENT, fixtures::MARK, 1, 17)[1].clone()
+        })?,
+        _ => return Ok(None),
+    };
+    Ok(Some(fields))
+}
+
+/// Version number of a `v<N>.avsc` file
+fn version(path: &Path) -> Result<u32> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.strip_prefix('v'))
+        .and_then(|number| number.parse().ok())
+        .ok_or_else(|| anyhow!("{} is not named v<N>.avsc", path.display()))
+}
+
+/// Every `<type>/<version>.avsc` under the schema directory, oldest version first
+fn schema_files() -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    for dir in std::fs::read_dir(SCHEMA_DIR)? {
+        let dir = dir?.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let schema_type = dir.file_name().unwrap().to_string_lossy().to_string();
+        for file in std::fs::read_dir(&dir)? {
+            let file = file?.path();
+            if file.extension().is_some_and(|ext| ext == "avsc") {
+                files.push((schema_type.clone(), version(&file)?, file));
+            }
+        }
+    }
+    files.sort();
+    Ok(files.into_iter().map(|(schema_type, _, file)| (schema_type, file)).collect())
+}
+
+fn parse(path: &Path) -> Result<Schema> {
+    let content = std::fs::read_to_string(path)?;
+    Schema::parse_str(&content).with_context(|| format!("Invalid schema {}", path.display()))
+}
+
+/// Encode the sample with `schema`, decode it again and compare with the converter output
+///
+/// Decoded fields must equal the converter's fields as they were built, not as resolved
+/// against the schema, so a value the schema only accepts after a promotion fails here.
+/// Fields the converter doesn't set must have a default; fields the schema doesn't know
+/// are only allowed for older versions (`latest` false).
+fn round_trip(path: &Path, fields: Vec<(String, AvroValue)>, latest: bool) -> Result<()> {
+    let schema = parse(path)?;
+    let Schema::Record(record_schema) = &schema else {
+        return Err(anyhow!("{} is not a record schema", path.display()));
+    };
+    if latest {
+        for (name, _) in &fields {
+            assert!(record_schema.lookup.contains_key(name), "{}: field '{}' is not in the schema", path.display(), name);
+        }
+    }
+
+    let resolved = AvroValue::Record(fields.clone())
+        .resolve(&schema)
+        .with_context(|| format!("{} does not match the converter output", path.display()))?;
+    let datum = to_avro_datum(&schema, resolved)?;
+    let AvroValue::Record(decoded) = from_avro_datum(&schema, &mut datum.as_slice(), None)? else {
+        return Err(anyhow!("{} did not decode to a record", path.display()));
+    };
+
+    assert_eq!(decoded.len(), record_schema.fields.len(), "{}: field count", path.display());
+    for (field, (name, got)) in record_schema.fields.iter().zip(&decoded) {
+        assert_eq!(&field.name, name, "{}: field order", path.display());
+        match fields.iter().find(|(raw_name, _)| raw_name == name) {
+            Some((_, want)) => assert_eq!(want, got, "{}: field '{}'", path.display(), name),
+            None => assert!(field.default.is_some(), "{}: field '{}' is neither set nor defaulted", path.display(), name),
+        }
+    }
+    Ok(())
+}
+
+/// Latest schema file of each type
+fn is_latest(files: &[(String, PathBuf)], index: usize) -> bool {
+    files.get(index + 1).is_none_or(|(next_type, _)| next_type != &files[index].0)
+}
+
+#[test]
+fn test_every_schema_round_trips_converter_output() -> Result<()> {
+    let files = schema_files()?;
+    assert!(!files.is_empty(), "no schemas found under {}", SCHEMA_DIR);
+
+    for (index, (schema_type, path)) in files.iter().enumerate() {
+        match sample(schema_type)? {
+            Some(fields) => round_trip(path, fields, is_latest(&files, index))?,
+            None => {
+                assert!(
+                    WITHOUT_CONVERTER.contains(&schema_type.as_str()),
+                    "schema type '{}' has no sample record in this test", schema_type
+                );
+                parse(path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_older_versions_read_latest_records() -> Result<()> {
+    let files = schema_files()?;
+    let mut types: Vec<&str> = files.iter().map(|(t, _)| t.as_str()).collect();
+    types.dedup();
+
+    for schema_type in types {
+        let Some(fields) = sample(schema_type)? else { continue };
+        let versions: Vec<&PathBuf> = files.iter()
+            .filter(|(t, _)| t == schema_type)
+            .map(|(_, path)| path)
+            .collect();
+        let latest = parse(versions.last().unwrap())?;
+        let datum = to_avro_datum(&latest, AvroValue::Record(fields).resolve(&latest)?)?;
+
+        for path in &versions[..versions.len() - 1] {
+            let reader = parse(path)?;
+            from_avro_datum(&latest, &mut datum.as_slice(), Some(&reader))
+                .with_context(|| format!("{} cannot read records written with the latest {} schema",
+                    path.display(), schema_type))?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_converter_output_passes_validation() -> Result<()> {
+    let files = schema_files()?;
+    let mut types: Vec<&str> = files.iter().map(|(t, _)| t.as_str()).collect();
+    types.dedup();
+
+    for schema_type in types {
+        let Some(fields) = sample(schema_type)? else { continue };
+        let latest = files.iter().rev().find(|(t, _)| t == schema_type).map(|(_, path)| path).unwrap();
+        validate_record(&parse(latest)?, &fields)
+            .with_context(|| format!("{} rejects the converter output", latest.display()))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_latest_schemas_carry_event_id() -> Result<()> {
+    let files = schema_files()?;
+    let mut types: Vec<&str> = files.iter().map(|(t, _)| t.as_str()).collect();
+    types.dedup();
+
+    for schema_type in types {
+        let Some(mut fields) = sample(schema_type)? else { continue };
+        let latest = files.iter().rev().find(|(t, _)| t == schema_type).map(|(_, path)| path).unwrap();
+        fields.push(("event_id".to_string(), AvroValue::Union(1, Box::new(AvroValue::Long(1_792_022_400_000_001)))));
+        validate_record(&parse(latest)?, &fields)
+            .with_context(|| format!("{} has no event_id", latest.display()))?;
+        round_trip(latest, fields, true)?;
+    }
+    Ok(())
+}
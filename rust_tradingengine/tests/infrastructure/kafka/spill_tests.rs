@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::FutureRecord;
+use serde_json::json;
+
+use cryptics_lab_bot::config_loader::SpillConfig;
+use cryptics_lab_bot::infrastructure::kafka::{KafkaProducer, SpillBuffer, SpilledRecord};
+
+fn dead_letter_path() -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("kafka_spill_{}", uuid::Uuid::new_v4()))
+        .join("dead_letter.jsonl")
+}
+
+fn spill_config(path: &Path, max_records: usize, max_attempts: u32) -> SpillConfig {
+    SpillConfig {
+        enabled: true,
+        max_records,
+        max_attempts,
+        retry_interval_ms: 10,
+        dead_letter_path: path.to_string_lossy().into_owned(),
+    }
+}
+
+fn record(key: &str) -> SpilledRecord {
+    SpilledRecord::from_record(&FutureRecord::to("test.spill").key(key).payload(key))
+}
+
+fn dead_lettered(path: &Path) -> Result<Vec<serde_json::Value>> {
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?)
+}
+
+/// Stand-in for a broker that is down until `up` is set
+struct Broker {
+    up: AtomicBool,
+    delivered: Mutex<Vec<String>>,
+}
+
+impl Broker {
+    fn new() -> Self {
+        Self { up: AtomicBool::new(false), delivered: Mutex::new(Vec::new()) }
+    }
+
+    async fn deliver(&self, record: SpilledRecord) -> Result<()> {
+        if !self.up.load(Ordering::SeqCst) {
+            return Err(anyhow!("broker down"));
+        }
+        self.delivered.lock().unwrap().push(String::from_utf8(record.key.unwrap())?);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_spilled_record_keeps_headers_and_timestamp() {
+    let headers = OwnedHeaders::new()
+        .insert(Header { key: "event_type", value: Some("ticker") })
+        .insert(Header { key: "instrument", value: None::<&str> });
+    let spilled = SpilledRecord::from_record(&FutureRecord::to("test.spill")
+        .key("k")
+        .payload("p")
+        .timestamp(1792022400250)
+        .headers(headers));
+
+    assert_eq!(spilled.attempts, 1);
+    assert_eq!(spilled.timestamp, Some(1792022400250));
+    assert_eq!(spilled.headers, vec![
+        ("event_type".to_string(), Some(b"ticker".to_vec())),
+        ("instrument".to_string(), None),
+    ]);
+
+    // Handing it back to the client rebuilds the same record
+    assert_eq!(SpilledRecord::from_record(&spilled.to_record()), spilled);
+}
+
+#[tokio::test]
+async fn test_spilled_records_delivered_in_order_after_recovery() {
+    let path = dead_letter_path();
+    let spill = SpillBuffer::new(&spill_config(&path, 100, 10));
+    let broker = Broker::new();
+    for key in ["a", "b", "c"] {
+        spill.spill(record(key));
+    }
+
+    // The broker is down: the retry stops at the oldest record and keeps everything
+    assert_eq!(spill.retry(|record| broker.deliver(record)).await, 0);
+    assert_eq!(spill.retry(|record| broker.deliver(record)).await, 0);
+    assert_eq!(spill.len(), 3);
+
+    broker.up.store(true, Ordering::SeqCst);
+    assert_eq!(spill.retry(|record| broker.deliver(record)).await, 3);
+    assert!(spill.is_empty());
+    assert_eq!(*broker.delivered.lock().unwrap(), vec!["a", "b", "c"]);
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn test_record_dead_lettered_after_max_attempts() -> Result<()> {
+    let path = dead_letter_path();
+    let spill = SpillBuffer::new(&spill_config(&path, 100, 3));
+    let broker = Broker::new();
+    spill.spill(record("a"));
+
+    // Spilling was the first failed attempt, the second retry is the third
+    assert_eq!(spill.retry(|record| broker.deliver(record)).await, 0);
+    assert_eq!(spill.len(), 1);
+    assert_eq!(spill.retry(|record| broker.deliver(record)).await, 0);
+    assert!(spill.is_empty());
+
+    let lines = dead_lettered(&path)?;
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["topic"], "test.spill");
+    assert_eq!(lines[0]["key"], "a");
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_buffer_dead_letters_the_oldest() -> Result<()> {
+    let path = dead_letter_path();
+    let spill = SpillBuffer::new(&spill_config(&path, 2, 10));
+    for key in ["a", "b", "c"] {
+        spill.spill(record(key));
+    }
+    assert_eq!(spill.len(), 2);
+    assert_eq!(dead_lettered(&path)?[0]["key"], "a");
+
+    let broker = Broker::new();
+    broker.up.store(true, Ordering::SeqCst);
+    assert_eq!(spill.retry(|record| broker.deliver(record)).await, 2);
+    assert_eq!(*broker.delivered.lock().unwrap(), vec!["b", "c"]);
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[test]
+fn test_dead_letter_all_empties_the_buffer() -> Result<()> {
+    let path = dead_letter_path();
+    let spill = SpillBuffer::new(&spill_config(&path, 100, 10));
+    spill.spill(record("a"));
+    spill.spill(record("b"));
+
+    assert_eq!(spill.dead_letter_all(), 2);
+    assert!(spill.is_empty());
+    let keys: Vec<_> = dead_lettered(&path)?.iter().map(|line| line["key"].clone()).collect();
+    assert_eq!(keys, vec!["a", "b"]);
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+/// Sends to an unreachable broker are spilled, queue behind each other and are delivered
+/// once a destination answers again, here the journal
+#[tokio::test]
+async fn test_producer_spills_failed_sends_until_recovery() -> Result<()> {
+    // Nothing listens on port 1, so the first delivery times out
+    let mut producer = KafkaProducer::new("localhost:1", "http://localhost:1", HashMap::new(), "../schemas".to_string()).await?;
+    let path = dead_letter_path();
+    producer.set_spill(&spill_config(&path, 100, 10));
+    let journal = path.with_file_name("journal.jsonl");
+    producer.set_journal(&journal);
+
+    producer.send_json("test.raw", "key-1", &json!({"a": 1})).await?;
+    producer.send_json("test.raw", "key-2", &json!({"b": 2})).await?;
+    assert_eq!(producer.spilled(), 2);
+    // The watchdog still sees them waiting
+    assert!(producer.backlog() >= 2);
+
+    producer.set_journal_only(true)?;
+    assert_eq!(producer.retry_spilled().await, 2);
+    assert_eq!(producer.spilled(), 0);
+
+    let keys: Vec<_> = dead_lettered(&journal)?.iter().map(|line| line["key"].clone()).collect();
+    assert_eq!(keys, vec!["key-1", "key-2"]);
+    assert!(!path.exists());
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
@@ -8,7 +8,7 @@ use rdkafka::{ClientConfig, Message};
 use rdkafka::config::RDKafkaLogLevel;
 use tokio;
 
-use cryptics_lab_bot::infrastructure::kafka::KafkaProducer;
+use cryptics_lab_bot::infrastructure::kafka::{split_confluent_payload, KafkaProducer};
 use cryptics_lab_bot::infrastructure::kafka::helper::SchemaHelper;
 use cryptics_lab_bot::domain::model::ticker::Ticker;
 
@@ -96,15 +96,10 @@ async fn test_kafka_ticker_integration() -> Result<()> {
                 Ok(message) => {
                     if let Some(payload) = message.payload() {
                         // Validate that the payload follows the expected format
-                        if payload.len() >= 5 && payload[0] == 0 {
-                            let received_schema_id = i32::from_be_bytes([
-                                payload[1], payload[2], payload[3], payload[4]
-                            ]);
+                        if let Ok((received_schema_id, avro_data)) = split_confluent_payload(payload) {
                             
                             assert_eq!(received_schema_id, schema_id, "Schema ID mismatch");
                             
-                            // Decode the message
-                            let avro_data = &payload[5..];
                             
                             // Get the schema from the registry
                             let schema_url = format!("{}/schemas/ids/{}", schema_registry_url, received_schema_id);
@@ -119,7 +114,7 @@ async fn test_kafka_ticker_integration() -> Result<()> {
                                 let schema = Schema::parse_str(schema_str)?;
                                 
                                 // Decode the data
-                                match Reader::with_schema(&schema, &avro_data[..]) {
+                                match Reader::with_schema(&schema, avro_data) {
                                     Ok(mut reader) => {
                                         if let Some(Ok(value)) = reader.next() {
                                             // Verify specific fields to ensure proper serialization
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use cryptics_lab_bot::config_loader::{TopicRetention, TopicType};
+use cryptics_lab_bot::infrastructure::kafka::topic_admin::{config_drift, expected_configs, ConfigDrift};
+
+fn ticker() -> TopicType {
+    TopicType {
+        retention: TopicRetention { retention_ms: Some(86_400_000), retention_bytes: None },
+        ..TopicType::new("thalex.ticker.avro")
+    }
+}
+
+fn broker(settings: &[(&str, &str)]) -> HashMap<String, String> {
+    settings.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+#[test]
+fn test_topics_without_expectations_are_left_alone() {
+    assert!(expected_configs(&TopicType::new("thalex.trade.avro")).is_empty());
+}
+
+#[test]
+fn test_expected_configs() {
+    assert_eq!(expected_configs(&ticker()), vec![
+        ("cleanup.policy", "delete".to_string()),
+        ("retention.ms", "86400000".to_string()),
+    ]);
+    
+    let latest = TopicType::builtin("ticker_latest", "thalex.ticker_latest.avro");
+    assert_eq!(expected_configs(&latest), vec![("cleanup.policy", "compact".to_string())]);
+}
+
+#[test]
+fn test_drift_reports_differing_settings_only() {
+    let expected = expected_configs(&ticker());
+    
+    let matching = broker(&[("cleanup.policy", "delete"), ("retention.ms", "86400000"), ("segment.ms", "3600000")]);
+    assert!(config_drift("thalex.ticker.avro", &expected, &matching).is_empty());
+    
+    // A week of ticks kept on the broker's default retention
+    let drifted = broker(&[("cleanup.policy", "delete"), ("retention.ms", "604800000")]);
+    assert_eq!(config_drift("thalex.ticker.avro", &expected, &drifted), vec![ConfigDrift {
+        topic: "thalex.ticker.avro".to_string(),
+        name: "retention.ms".to_string(),
+        expected: "86400000".to_string(),
+        actual: Some("604800000".to_string()),
+    }]);
+    
+    let unreported = config_drift("thalex.ticker.avro", &expected, &HashMap::new());
+    assert_eq!(unreported.len(), 2);
+    assert!(unreported.iter().all(|d| d.actual.is_none()));
+}
@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use cryptics_lab_bot::infrastructure::kafka::{ProducerWatchdog, WatchdogAction};
+
+const STALL_AFTER: Duration = Duration::from_secs(10);
+
+fn secs(start: Instant, secs: u64) -> Instant {
+    start + Duration::from_secs(secs)
+}
+
+#[test]
+fn test_idle_pipeline_is_not_stalled() {
+    let start = Instant::now();
+    let mut watchdog = ProducerWatchdog::new(STALL_AFTER, true);
+    
+    // Nothing waiting, however long ago the last delivery was
+    assert_eq!(watchdog.check(0, start, secs(start, 60)), WatchdogAction::None);
+    
+    // A record that has just started waiting isn't a stall either
+    assert_eq!(watchdog.check(1, start, secs(start, 61)), WatchdogAction::None);
+    assert_eq!(watchdog.check(1, start, secs(start, 70)), WatchdogAction::None);
+    assert_eq!(watchdog.check(1, start, secs(start, 72)), WatchdogAction::Restart);
+}
+
+#[test]
+fn test_deliveries_keep_a_busy_pipeline_healthy() {
+    let start = Instant::now();
+    let mut watchdog = ProducerWatchdog::new(STALL_AFTER, false);
+    
+    for t in 0..30 {
+        assert_eq!(watchdog.check(5, secs(start, t), secs(start, t + 1)), WatchdogAction::None);
+    }
+}
+
+#[test]
+fn test_stall_restarts_then_degrades_to_journal() {
+    let start = Instant::now();
+    let mut watchdog = ProducerWatchdog::new(STALL_AFTER, true);
+    
+    assert_eq!(watchdog.check(3, start, secs(start, 1)), WatchdogAction::None);
+    assert_eq!(watchdog.check(3, start, secs(start, 11)), WatchdogAction::Restart);
+    
+    // The restarted client gets the full stall period to deliver
+    assert_eq!(watchdog.check(3, start, secs(start, 15)), WatchdogAction::None);
+    assert_eq!(watchdog.check(3, start, secs(start, 21)), WatchdogAction::DegradeToJournal);
+}
+
+#[test]
+fn test_recovery_after_restart_clears_the_stall() {
+    let start = Instant::now();
+    let mut watchdog = ProducerWatchdog::new(STALL_AFTER, true);
+    
+    assert_eq!(watchdog.check(3, start, secs(start, 1)), WatchdogAction::None);
+    assert_eq!(watchdog.check(3, start, secs(start, 11)), WatchdogAction::Restart);
+    
+    // Deliveries resumed after the restart; a later stall restarts again
+    let delivered = secs(start, 12);
+    assert_eq!(watchdog.check(3, delivered, secs(start, 13)), WatchdogAction::None);
+    assert_eq!(watchdog.check(3, delivered, secs(start, 23)), WatchdogAction::Restart);
+}
+
+#[test]
+fn test_without_journal_fallback_keeps_restarting() {
+    let start = Instant::now();
+    let mut watchdog = ProducerWatchdog::new(STALL_AFTER, false);
+    
+    assert_eq!(watchdog.check(1, start, secs(start, 1)), WatchdogAction::None);
+    assert_eq!(watchdog.check(1, start, secs(start, 11)), WatchdogAction::Restart);
+    assert_eq!(watchdog.check(1, start, secs(start, 21)), WatchdogAction::Restart);
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use cryptics_lab_bot::config_loader::LeaseConfig;
+use cryptics_lab_bot::infrastructure::lease::{FileLeaseStore, LeaseManager, LeaseStore};
+use tokio::sync::broadcast;
+
+/// 2026-10-15T00:00:00Z
+const NOW: f64 = 1_792_022_400.0;
+
+fn lease_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("lease_tests_{}", uuid::Uuid::new_v4()))
+}
+
+#[tokio::test]
+async fn test_second_holder_sees_first_until_expiry() {
+    let store = FileLeaseStore::new(lease_dir()).unwrap();
+    
+    let a = store.acquire("thalex.test.BTCUSD", "a", NOW + 15.0, NOW).await.unwrap();
+    assert_eq!(a.holder, "a");
+    
+    let b = store.acquire("thalex.test.BTCUSD", "b", NOW + 20.0, NOW + 5.0).await.unwrap();
+    assert_eq!(b.holder, "a");
+    assert_eq!(b.expires_at, NOW + 15.0);
+    
+    // a stopped renewing
+    let b = store.acquire("thalex.test.BTCUSD", "b", NOW + 31.0, NOW + 16.0).await.unwrap();
+    assert_eq!(b.holder, "b");
+    
+    let a = store.acquire("thalex.test.BTCUSD", "a", NOW + 32.0, NOW + 17.0).await.unwrap();
+    assert_eq!(a.holder, "b");
+}
+
+#[tokio::test]
+async fn test_holder_renews_own_lease() {
+    let store = FileLeaseStore::new(lease_dir()).unwrap();
+    store.acquire("key", "a", NOW + 15.0, NOW).await.unwrap();
+    
+    let renewed = store.acquire("key", "a", NOW + 20.0, NOW + 5.0).await.unwrap();
+    assert_eq!(renewed.holder, "a");
+    assert_eq!(renewed.expires_at, NOW + 20.0);
+}
+
+#[tokio::test]
+async fn test_release_only_by_holder() {
+    let store = FileLeaseStore::new(lease_dir()).unwrap();
+    store.acquire("key", "a", NOW + 15.0, NOW).await.unwrap();
+    
+    store.release("key", "b").await.unwrap();
+    assert_eq!(store.acquire("key", "b", NOW + 15.0, NOW).await.unwrap().holder, "a");
+    
+    store.release("key", "a").await.unwrap();
+    assert_eq!(store.acquire("key", "b", NOW + 15.0, NOW).await.unwrap().holder, "b");
+}
+
+#[tokio::test]
+async fn test_keys_are_independent() {
+    let store = FileLeaseStore::new(lease_dir()).unwrap();
+    store.acquire("thalex.test.BTCUSD", "a", NOW + 15.0, NOW).await.unwrap();
+    
+    let other = store.acquire("thalex.test.ETHUSD", "b", NOW + 15.0, NOW).await.unwrap();
+    assert_eq!(other.holder, "b");
+}
+
+#[tokio::test]
+async fn test_hold_fails_when_lease_taken_over() {
+    let store = Arc::new(FileLeaseStore::new(lease_dir()).unwrap());
+    let far_future = f64::MAX;
+    store.acquire("key", "other", far_future, 0.0).await.unwrap();
+    
+    let manager = LeaseManager::new(store, "key", "me", &LeaseConfig::default());
+    let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let err = manager.hold(shutdown_rx).await.unwrap_err();
+    assert!(err.to_string().contains("taken over by other"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_manager_acquires_free_lease() {
+    let store = Arc::new(FileLeaseStore::new(lease_dir()).unwrap());
+    let manager = LeaseManager::new(store, "key", "me", &LeaseConfig::default());
+    
+    let record = manager.try_acquire(NOW).await.unwrap();
+    assert!(manager.is_mine(&record));
+    assert_eq!(record.expires_at, NOW + 15.0);
+}
+
+#[tokio::test]
+async fn test_standby_takes_expired_lease_immediately() {
+    let store = Arc::new(FileLeaseStore::new(lease_dir()).unwrap());
+    store.acquire("key", "dead_primary", 1.0, 0.0).await.unwrap();
+    
+    let manager = LeaseManager::new(store, "key", "standby", &LeaseConfig::default());
+    tokio::time::timeout(std::time::Duration::from_secs(1), manager.wait_until_held()).await
+        .expect("expired lease should be taken without waiting");
+}
+
+#[tokio::test]
+async fn test_standby_waits_for_release() {
+    let store = Arc::new(FileLeaseStore::new(lease_dir()).unwrap());
+    store.acquire("key", "primary", f64::MAX, 0.0).await.unwrap();
+    
+    let config = LeaseConfig { renew_sec: 1, ..LeaseConfig::default() };
+    let manager = LeaseManager::new(store.clone(), "key", "standby", &config);
+    let waiting = tokio::spawn(async move {
+        manager.wait_until_held().await;
+        manager
+    });
+    
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(!waiting.is_finished());
+    
+    store.release("key", "primary").await.unwrap();
+    let manager = tokio::time::timeout(std::time::Duration::from_secs(3), waiting).await
+        .expect("standby should take over after release")
+        .unwrap();
+    assert!(manager.is_mine(&store.acquire("key", "probe", 0.0, 0.0).await.unwrap()));
+}
@@ -0,0 +1,68 @@
+use cryptics_lab_bot::domain::model::lifecycle::LifecycleEventType;
+use cryptics_lab_bot::infrastructure::lifecycle::{LifecyclePublisher, MAX_PENDING_EVENTS};
+
+#[test]
+fn test_event_type_names() {
+    let names: Vec<&str> = LifecycleEventType::ALL.iter().map(|t| t.as_str()).collect();
+    assert_eq!(names, vec![
+        "connected", "logged_in", "subscribed", "quoting_started", "reconnect", "kill_switch", "shutdown",
+    ]);
+    assert_eq!(serde_json::to_string(&LifecycleEventType::QuotingStarted).unwrap(), "\"quoting_started\"");
+}
+
+#[test]
+fn test_event_carries_instance_and_network() {
+    let publisher = LifecyclePublisher::new("test");
+    let event = publisher.event(LifecycleEventType::Reconnect, Some("session timed out".to_string()));
+    
+    assert_eq!(event.instance_id, publisher.instance_id());
+    assert_eq!(event.network, "test");
+    assert_eq!(event.reason.as_deref(), Some("session timed out"));
+    assert!(event.timestamp > 0.0);
+}
+
+#[test]
+fn test_instance_ids_are_unique() {
+    let a = LifecyclePublisher::new("test");
+    let b = LifecyclePublisher::new("test");
+    assert_ne!(a.instance_id(), b.instance_id());
+}
+
+#[tokio::test]
+async fn test_events_buffered_without_producer() {
+    let publisher = LifecyclePublisher::new("test");
+    publisher.emit(LifecycleEventType::Connected, None).await;
+    publisher.emit(LifecycleEventType::LoggedIn, None).await;
+    
+    let pending: Vec<LifecycleEventType> = publisher.pending().await.iter().map(|e| e.event_type).collect();
+    assert_eq!(pending, vec![LifecycleEventType::Connected, LifecycleEventType::LoggedIn]);
+}
+
+#[tokio::test]
+async fn test_buffer_drops_oldest_when_full() {
+    let publisher = LifecyclePublisher::new("test");
+    publisher.emit(LifecycleEventType::Connected, None).await;
+    for _ in 0..MAX_PENDING_EVENTS {
+        publisher.emit(LifecycleEventType::Reconnect, None).await;
+    }
+    
+    let pending = publisher.pending().await;
+    assert_eq!(pending.len(), MAX_PENDING_EVENTS);
+    assert!(pending.iter().all(|e| e.event_type == LifecycleEventType::Reconnect));
+}
+
+#[tokio::test]
+async fn test_attach_without_producer_keeps_buffer() {
+    let publisher = LifecyclePublisher::new("test");
+    publisher.emit(LifecycleEventType::Connected, None).await;
+    publisher.attach(None).await;
+    
+    assert_eq!(publisher.pending().await.len(), 1);
+}
+
+#[test]
+fn test_heartbeat_sequence_counts_up() {
+    let publisher = LifecyclePublisher::new("test");
+    assert_eq!(publisher.next_heartbeat_sequence(), 1);
+    assert_eq!(publisher.next_heartbeat_sequence(), 2);
+}
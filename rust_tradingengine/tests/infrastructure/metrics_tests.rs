@@ -0,0 +1,46 @@
+use cryptics_lab_bot::infrastructure::metrics::Metrics;
+
+#[test]
+fn test_counters_accumulate() {
+    let metrics = Metrics::default();
+    metrics.incr("test.counter", 2);
+    metrics.incr("test.counter", 3);
+    
+    assert_eq!(metrics.counter_value("test.counter"), 5);
+    assert_eq!(metrics.counter_value("test.missing"), 0);
+}
+
+#[test]
+fn test_gauges_hold_last_value() {
+    let metrics = Metrics::default();
+    assert_eq!(metrics.gauge_value("test.gauge"), None);
+    
+    metrics.set_gauge("test.gauge", 4.0);
+    metrics.set_gauge("test.gauge", 1.5);
+    assert_eq!(metrics.gauge_value("test.gauge"), Some(1.5));
+}
+
+#[test]
+fn test_gauges_accumulate_and_track_maximum() {
+    let metrics = Metrics::default();
+    metrics.add_gauge("test.total", 0.25);
+    metrics.add_gauge("test.total", -1.0);
+    assert_eq!(metrics.gauge_value("test.total"), Some(-0.75));
+    
+    metrics.max_gauge("test.max", 2.0);
+    metrics.max_gauge("test.max", 1.0);
+    assert_eq!(metrics.gauge_value("test.max"), Some(2.0));
+    metrics.max_gauge("test.max", 3.5);
+    assert_eq!(metrics.gauge_value("test.max"), Some(3.5));
+}
+
+#[test]
+fn test_snapshot_contains_all_metrics() {
+    let metrics = Metrics::default();
+    metrics.incr("a.counter", 1);
+    metrics.set_gauge("b.gauge", 7.0);
+    
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.get("a.counter"), Some(&1.0));
+    assert_eq!(snapshot.get("b.gauge"), Some(&7.0));
+}
@@ -2,4 +2,13 @@
 
 // Import test modules
 pub mod kafka;
+pub mod bot_state_tests;
 pub mod exchange;
+#[cfg(feature = "chaos")]
+pub mod chaos_tests;
+pub mod lease_tests;
+pub mod lifecycle_tests;
+pub mod metrics_tests;
+pub mod persistence;
+pub mod reconnect_tests;
+pub mod runtime_tests;
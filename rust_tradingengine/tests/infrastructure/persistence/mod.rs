@@ -0,0 +1,3 @@
+//! Tests for persistence targets
+
+pub mod object_store_tests;
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ::object_store::memory::InMemory;
+use ::object_store::path::Path as ObjectPath;
+use ::object_store::ObjectStore as _;
+
+use cryptics_lab_bot::config_loader::{ObjectStoreConfig, ObjectStoreProvider};
+use cryptics_lab_bot::infrastructure::persistence::object_store::{self, secret};
+use cryptics_lab_bot::infrastructure::persistence::{CloudStore, LocalStore, ObjectStore};
+
+fn cloud(bucket: &Arc<InMemory>, prefix: &str) -> CloudStore {
+    CloudStore::new(bucket.clone(), prefix, "memory://reports")
+}
+
+/// Content of the object at `key` of the in-memory bucket
+async fn object(bucket: &InMemory, key: &str) -> Vec<u8> {
+    bucket.get(&ObjectPath::from(key)).await.unwrap().bytes().await.unwrap().to_vec()
+}
+
+#[test]
+fn test_keys_are_placed_under_the_prefix() {
+    let bucket = Arc::new(InMemory::new());
+    assert_eq!(cloud(&bucket, "/eod/").key("2026-10-15/fills.csv"), "eod/2026-10-15/fills.csv");
+    assert_eq!(cloud(&bucket, "").key("2026-10-15/fills.csv"), "2026-10-15/fills.csv");
+}
+
+#[tokio::test]
+async fn test_cloud_store_puts_under_the_prefix() {
+    let bucket = Arc::new(InMemory::new());
+    let store = cloud(&bucket, "eod");
+    store.put("2026-10-15/summary.csv", b"date\n".to_vec()).await.unwrap();
+    store.put("2026-10-15/summary.csv", b"date,fills\n".to_vec()).await.unwrap();
+    assert_eq!(object(&bucket, "eod/2026-10-15/summary.csv").await, b"date,fills\n");
+    assert_eq!(store.location(), "memory://reports");
+}
+
+#[tokio::test]
+async fn test_large_files_are_uploaded_in_parts() {
+    let dir = temp_dir();
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("fills.csv");
+    let content: Vec<u8> = (0..10_000u32).flat_map(|i| format!("{},65000\n", i).into_bytes()).collect();
+    std::fs::write(&source, &content).unwrap();
+
+    // Small and large files alike end up whole
+    let bucket = Arc::new(InMemory::new());
+    cloud(&bucket, "eod").put_file(&source, "whole.csv").await.unwrap();
+    cloud(&bucket, "eod").with_part_size(4096).put_file(&source, "parts.csv").await.unwrap();
+    assert_eq!(object(&bucket, "eod/whole.csv").await, content);
+    assert_eq!(object(&bucket, "eod/parts.csv").await, content);
+
+    let err = cloud(&bucket, "eod").put_file(&dir.join("missing.csv"), "missing.csv").await.unwrap_err();
+    assert!(err.to_string().contains("Failed to read"));
+    std::fs::remove_dir_all(dir).ok();
+}
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("object_store_tests_{}", uuid::Uuid::new_v4()))
+}
+
+fn config(provider: ObjectStoreProvider, bucket: &str) -> ObjectStoreConfig {
+    ObjectStoreConfig {
+        provider,
+        endpoint: None,
+        bucket: bucket.to_string(),
+        region: "eu-west-1".to_string(),
+        prefix: "eod".to_string(),
+        access_key: "env:OBJECT_STORE_TESTS_ACCESS_KEY".to_string(),
+        secret_key: "env:OBJECT_STORE_TESTS_SECRET_KEY".to_string(),
+        service_account: None,
+    }
+}
+
+#[tokio::test]
+async fn test_local_store_writes_under_its_root() {
+    let dir = temp_dir();
+    let store = LocalStore::new(&dir);
+    store.put("2026-10-15/summary.csv", b"date\n".to_vec()).await.unwrap();
+    store.put("2026-10-15/summary.csv", b"date,fills\n".to_vec()).await.unwrap();
+    assert_eq!(std::fs::read_to_string(dir.join("2026-10-15/summary.csv")).unwrap(), "date,fills\n");
+
+    let source = dir.join("source.csv");
+    std::fs::write(&source, "a,b\n").unwrap();
+    store.put_file(&source, "copy/source.csv").await.unwrap();
+    assert_eq!(std::fs::read_to_string(dir.join("copy/source.csv")).unwrap(), "a,b\n");
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn test_local_provider_applies_the_prefix() {
+    let dir = temp_dir();
+    let store = object_store::from_config(&config(ObjectStoreProvider::Local, dir.to_str().unwrap())).unwrap();
+    store.put("2026-10-15/fills.csv", Vec::new()).await.unwrap();
+    assert!(dir.join("eod/2026-10-15/fills.csv").exists());
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_remote_providers_need_credentials_and_endpoints() {
+    // Neither credential variable is set
+    let err = object_store::from_config(&config(ObjectStoreProvider::S3, "reports")).err().unwrap();
+    assert!(format!("{:#}", err).contains("OBJECT_STORE_TESTS_ACCESS_KEY"));
+
+    let err = object_store::from_config(&config(ObjectStoreProvider::Minio, "reports")).err().unwrap();
+    assert!(err.to_string().contains("needs an endpoint"));
+
+    let gcs = ObjectStoreConfig {
+        service_account: Some("env:OBJECT_STORE_TESTS_SERVICE_ACCOUNT".to_string()),
+        ..config(ObjectStoreProvider::Gcs, "reports")
+    };
+    let err = object_store::from_config(&gcs).err().unwrap();
+    assert!(format!("{:#}", err).contains("OBJECT_STORE_TESTS_SERVICE_ACCOUNT"));
+}
+
+#[test]
+fn test_secret_references() {
+    let dir = temp_dir();
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("secret");
+    std::fs::write(&path, "s3cr3t\n").unwrap();
+    assert_eq!(secret(&format!("file:{}", path.display())).unwrap(), "s3cr3t");
+
+    assert!(secret("env:OBJECT_STORE_TESTS_UNSET").unwrap_err().to_string().contains("Missing"));
+    assert!(secret("AKIDEXAMPLE").is_err());
+    assert!(secret("vault:path").is_err());
+    std::fs::remove_dir_all(dir).ok();
+}
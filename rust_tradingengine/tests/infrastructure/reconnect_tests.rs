@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+
+use cryptics_lab_bot::config_loader::ReconnectConfig;
+use cryptics_lab_bot::infrastructure::reconnect::ReconnectPolicy;
+
+fn config() -> ReconnectConfig {
+    ReconnectConfig {
+        initial_delay_ms: 100,
+        max_delay_ms: 1000,
+        multiplier: 2.0,
+        max_attempts: 6,
+        reset_after_sec: 60,
+        churn_window_sec: 600,
+        churn_alert_threshold: 3,
+    }
+}
+
+#[test]
+fn test_delay_grows_exponentially_up_to_cap() {
+    let mut policy = ReconnectPolicy::new(config());
+    let now = Instant::now();
+    let short = Duration::from_secs(1);
+    
+    let delays: Vec<u64> = (0..6)
+        .map(|_| policy.next_delay(short, now).unwrap().as_millis() as u64)
+        .collect();
+    assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+}
+
+#[test]
+fn test_gives_up_after_max_attempts() {
+    let mut policy = ReconnectPolicy::new(config());
+    let now = Instant::now();
+    for _ in 0..6 {
+        assert!(policy.next_delay(Duration::ZERO, now).is_some());
+    }
+    assert!(policy.next_delay(Duration::ZERO, now).is_none());
+}
+
+#[test]
+fn test_stable_session_resets_backoff() {
+    let mut policy = ReconnectPolicy::new(config());
+    let now = Instant::now();
+    policy.next_delay(Duration::ZERO, now);
+    policy.next_delay(Duration::ZERO, now);
+    
+    let delay = policy.next_delay(Duration::from_secs(120), now).unwrap();
+    assert_eq!(delay, Duration::from_millis(100));
+    assert_eq!(policy.attempts(), 1);
+}
+
+#[test]
+fn test_churn_counts_reconnects_in_window() {
+    let mut policy = ReconnectPolicy::new(config());
+    let start = Instant::now();
+    policy.next_delay(Duration::from_secs(120), start);
+    policy.next_delay(Duration::from_secs(120), start + Duration::from_secs(10));
+    assert!(!policy.is_churning());
+    
+    policy.next_delay(Duration::from_secs(120), start + Duration::from_secs(20));
+    assert!(policy.is_churning());
+    
+    // Older reconnects fall out of the window
+    policy.next_delay(Duration::from_secs(120), start + Duration::from_secs(615));
+    assert_eq!(policy.churn(), 2);
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use cryptics_lab_bot::config_loader::RuntimeConfig;
+use cryptics_lab_bot::infrastructure::runtime::{pin_current_thread, spawn_io, RuntimeTopology};
+
+fn thread_name() -> String {
+    std::thread::current().name().unwrap_or_default().to_string()
+}
+
+#[test]
+fn test_default_topology_shares_one_runtime() -> Result<()> {
+    let topology = RuntimeTopology::build(&RuntimeConfig::default())?;
+    assert!(topology.io.is_none());
+    
+    // Without an installed IO runtime, IO work stays on the current runtime
+    let name = topology.main.block_on(async { spawn_io(async { thread_name() }).await })?;
+    assert_eq!(name, "quote-worker");
+    topology.shutdown(Duration::from_secs(1));
+    Ok(())
+}
+
+#[test]
+fn test_dedicated_io_runtime_has_its_own_workers() -> Result<()> {
+    let config = RuntimeConfig {
+        worker_threads: Some(1),
+        io_runtime: true,
+        io_worker_threads: 1,
+        ..RuntimeConfig::default()
+    };
+    let topology = RuntimeTopology::build(&config)?;
+    let io = topology.io.as_ref().expect("IO runtime");
+    
+    let name = io.block_on(async { tokio::spawn(async { thread_name() }).await })?;
+    assert_eq!(name, "io-worker");
+    topology.shutdown(Duration::from_secs(1));
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn allowed_cores() -> Result<String> {
+    let status = std::fs::read_to_string("/proc/thread-self/status")?;
+    Ok(status.lines()
+        .find_map(|line| line.strip_prefix("Cpus_allowed_list:"))
+        .unwrap_or_default()
+        .trim()
+        .to_string())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_workers_pinned_to_configured_cores() -> Result<()> {
+    // Any core this process may run on, e.g. "2" from "2-7,9"
+    let core: usize = allowed_cores()?
+        .split(|c: char| !c.is_ascii_digit())
+        .next()
+        .unwrap_or_default()
+        .parse()?;
+    let config = RuntimeConfig {
+        worker_threads: Some(1),
+        quote_cores: vec![core],
+        ..RuntimeConfig::default()
+    };
+    let topology = RuntimeTopology::build(&config)?;
+    let allowed = topology.main.block_on(async { tokio::spawn(async { allowed_cores() }).await })??;
+    assert_eq!(allowed, core.to_string());
+    topology.shutdown(Duration::from_secs(1));
+    Ok(())
+}
+
+#[test]
+fn test_pinning_rejects_invalid_cores() {
+    let result = std::thread::spawn(|| {
+        (pin_current_thread(&[]).is_err(), pin_current_thread(&[1 << 20]).is_err())
+    }).join().unwrap();
+    assert_eq!(result, (true, true));
+}
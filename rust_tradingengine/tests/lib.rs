@@ -1,4 +1,9 @@
 //! Test suite for the cryptics_lab_bot infrastructure
 
 // Import test modules
+mod config;
+mod domain;
 mod infrastructure;
+mod reporting;
+mod strategies;
+mod testing;
@@ -0,0 +1,228 @@
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use cryptics_lab_bot::domain::enums::OrderSide;
+use cryptics_lab_bot::domain::model::trade::Trade;
+use cryptics_lab_bot::domain::model::trade_correction::{CorrectionKind, TradeCorrection};
+use cryptics_lab_bot::infrastructure::persistence::LocalStore;
+use cryptics_lab_bot::reporting::eod::{due_exports, export_date, next_run, parse_time};
+use cryptics_lab_bot::reporting::{DaySummary, EodExporter, Fill, Journal, ReportFormat};
+use cryptics_lab_bot::strategies::thalex_market_maker::{day_of, DailyStats};
+use cryptics_lab_bot::testing::fixtures;
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("eod_tests_{}", uuid::Uuid::new_v4()))
+}
+
+fn fill(id: u64, time: f64, direction: OrderSide, price: f64, amount: f64) -> Fill {
+    Fill::new(&Trade { time, ..fixtures::trade(id, price, amount) }, direction)
+}
+
+#[test]
+fn test_journal_keeps_fills_per_day() {
+    let dir = temp_dir();
+    let journal = Journal::new(&dir);
+    journal.append_fill(&fill(2, fixtures::T0 + 60.0, OrderSide::Sell, 65010.0, 0.1)).unwrap();
+    journal.append_fill(&fill(1, fixtures::T0 + 30.0, OrderSide::Buy, 65000.0, 0.2)).unwrap();
+    journal.append_fill(&fill(3, fixtures::T0 + 86400.0, OrderSide::Buy, 65000.0, 0.1)).unwrap();
+
+    let fills = journal.fills(day_of(fixtures::T0)).unwrap();
+    assert_eq!(fills.iter().map(|f| f.client_order_id).collect::<Vec<_>>(), vec![Some(1), Some(2)]);
+    assert_eq!(journal.fills(day_of(fixtures::T0 + 86400.0)).unwrap().len(), 1);
+    assert!(journal.fills(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()).unwrap().is_empty());
+    std::fs::remove_dir_all(dir).ok();
+}
+
+fn correction(kind: CorrectionKind, id: u64, price: Option<f64>, amount: Option<f64>) -> TradeCorrection {
+    TradeCorrection {
+        kind,
+        trade_id: format!("T{:08}", id),
+        order_id: None,
+        instrument_name: None,
+        direction: None,
+        original_price: None,
+        original_amount: None,
+        corrected_price: price,
+        corrected_amount: amount,
+        message: None,
+        timestamp: fixtures::T0 + 3600.0,
+        processing_timestamp: None,
+    }
+}
+
+#[test]
+fn test_corrections_revise_journaled_fills() {
+    let dir = temp_dir();
+    let journal = Journal::new(&dir);
+    let day = day_of(fixtures::T0);
+    journal.append_fill(&fill(1, fixtures::T0 + 30.0, OrderSide::Buy, 65000.0, 0.2)).unwrap();
+    journal.append_fill(&fill(2, fixtures::T0 + 60.0, OrderSide::Sell, 65010.0, 0.1)).unwrap();
+    journal.append_fill(&fill(3, fixtures::T0 + 90.0, OrderSide::Sell, 65020.0, 0.1)).unwrap();
+    
+    journal.append_correction(day, &correction(CorrectionKind::Bust, 2, None, None)).unwrap();
+    journal.append_correction(day, &correction(CorrectionKind::Correction, 3, Some(65015.0), None)).unwrap();
+    assert_eq!(journal.corrections(day).unwrap().len(), 2);
+    
+    let fills = journal.fills(day).unwrap();
+    assert_eq!(fills.iter().map(|f| f.client_order_id).collect::<Vec<_>>(), vec![Some(1), Some(3)]);
+    assert_eq!((fills[1].price, fills[1].amount), (65015.0, 0.1));
+    
+    // The busted fill is gone; fills of earlier days are found within the lookback
+    let next_day = day_of(fixtures::T0 + 86400.0);
+    assert!(journal.find_fill("T00000002", next_day, 7).unwrap().is_none());
+    assert_eq!(journal.find_fill("T00000003", next_day, 7).unwrap().map(|f| f.price), Some(65015.0));
+    assert!(journal.find_fill("T00000003", next_day, 0).unwrap().is_none());
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_torn_journal_line_is_skipped() {
+    let dir = temp_dir();
+    let journal = Journal::new(&dir);
+    journal.append_fill(&fill(1, fixtures::T0, OrderSide::Buy, 65000.0, 0.2)).unwrap();
+    let path = dir.join("2026-10-15").join("fills.jsonl");
+    let mut content = std::fs::read_to_string(&path).unwrap();
+    content.push_str("{\"time\": 17920");
+    std::fs::write(&path, content).unwrap();
+
+    assert_eq!(journal.fills(day_of(fixtures::T0)).unwrap().len(), 1);
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_export_writes_fills_and_closing_stats() {
+    let dir = temp_dir();
+    let journal = Journal::new(dir.join("journal"));
+    let date = day_of(fixtures::T0);
+    journal.append_fill(&fill(1, fixtures::T0 + 30.0, OrderSide::Buy, 100.0, 2.0)).unwrap();
+    journal.append_fill(&fill(2, fixtures::T0 + 60.0, OrderSide::Sell, 110.0, 1.0)).unwrap();
+    let mut stats = DailyStats::new(date);
+    stats.record_fill(&OrderSide::Buy, 100.0, 2.0, fixtures::T0 + 30.0);
+    stats.record_fill(&OrderSide::Sell, 110.0, 1.0, fixtures::T0 + 60.0);
+    stats.uptime_observed_secs = 80000.0;
+    stats.uptime_compliant_secs = 76000.0;
+    journal.close_day(&stats).unwrap();
+
+    let exporter = EodExporter::new(journal, dir.join("out"), vec![ReportFormat::Csv, ReportFormat::Json], None);
+    let written = exporter.write(date).unwrap();
+    assert_eq!(written.len(), 4);
+
+    let fills = std::fs::read_to_string(dir.join("out/2026-10-15/fills.csv")).unwrap();
+    let lines: Vec<&str> = fills.lines().collect();
+    assert_eq!(lines[0], "time,trade_id,order_id,client_order_id,instrument_name,direction,price,amount,maker_taker");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("2026-10-15T00:00:30"));
+    assert!(lines[1].contains(",buy,100,2,maker"));
+
+    let summary = std::fs::read_to_string(dir.join("out/2026-10-15/summary.csv")).unwrap();
+    assert_eq!(summary.lines().nth(1), Some("2026-10-15,2,3,310.00,10.00,1,100.00,80000,95.00"));
+    let json: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(dir.join("out/2026-10-15/summary.json")).unwrap()
+    ).unwrap();
+    assert_eq!(json[0]["realized_pnl"], 10.0);
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_unclosed_day_is_summarized_from_fills() {
+    let dir = temp_dir();
+    let journal = Journal::new(dir.join("journal"));
+    journal.append_fill(&fill(1, fixtures::T0 + 30.0, OrderSide::Buy, 100.0, 2.0)).unwrap();
+
+    let exporter = EodExporter::new(journal, dir.join("out"), vec![ReportFormat::Csv], None);
+    exporter.write(day_of(fixtures::T0)).unwrap();
+    let summary = std::fs::read_to_string(dir.join("out/2026-10-15/summary.csv")).unwrap();
+    assert!(summary.lines().nth(1).unwrap().starts_with("2026-10-15,1,2,200.00,0.00,"));
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn test_export_uploads_to_the_object_store() {
+    let dir = temp_dir();
+    let journal = Journal::new(dir.join("journal"));
+    journal.append_fill(&fill(1, fixtures::T0 + 30.0, OrderSide::Buy, 100.0, 2.0)).unwrap();
+
+    let store = Arc::new(LocalStore::new(dir.join("bucket")));
+    let exporter = EodExporter::new(journal, dir.join("out"), vec![ReportFormat::Csv], Some(store));
+    exporter.export(day_of(fixtures::T0)).await.unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("bucket/2026-10-15/fills.csv")).unwrap(),
+        std::fs::read_to_string(dir.join("out/2026-10-15/fills.csv")).unwrap(),
+    );
+    assert!(dir.join("bucket/2026-10-15/summary.csv").exists());
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_uptime_pct_of_empty_day() {
+    let summary = DaySummary::from(&DailyStats::new(day_of(fixtures::T0)));
+    assert_eq!(summary.uptime_pct, 0.0);
+}
+
+#[test]
+fn test_next_run_and_exported_day() {
+    let at = parse_time("00:05").unwrap();
+    let before = Utc.with_ymd_and_hms(2026, 10, 15, 0, 1, 0).unwrap();
+    let run = next_run(before, at);
+    assert_eq!(run, Utc.with_ymd_and_hms(2026, 10, 15, 0, 5, 0).unwrap());
+    assert_eq!(export_date(run), NaiveDate::from_ymd_opt(2026, 10, 14).unwrap());
+
+    // At or after the time, the next run is tomorrow
+    assert_eq!(next_run(run, at), Utc.with_ymd_and_hms(2026, 10, 16, 0, 5, 0).unwrap());
+    assert_eq!(next_run(before, NaiveTime::from_hms_opt(23, 0, 0).unwrap()),
+        Utc.with_ymd_and_hms(2026, 10, 15, 23, 0, 0).unwrap());
+    assert!(parse_time("25:00").is_err());
+}
+
+#[test]
+fn test_due_exports_after_the_last_one() {
+    let at = parse_time("00:05").unwrap();
+    let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 0, 10, 0).unwrap();
+    assert_eq!(due_exports(now, at, Some(day(15))), Vec::<NaiveDate>::new());
+    assert_eq!(due_exports(now, at, Some(day(12))), vec![day(13), day(14), day(15)]);
+
+    // Before today's run the 15th isn't due yet
+    let early = Utc.with_ymd_and_hms(2026, 10, 16, 0, 1, 0).unwrap();
+    assert_eq!(due_exports(early, at, Some(day(12))), vec![day(13), day(14)]);
+
+    // Without an export on record only the latest due day is
+    assert_eq!(due_exports(now, at, None), vec![day(15)]);
+}
+
+#[tokio::test]
+async fn test_catch_up_exports_missed_runs() {
+    let dir = temp_dir();
+    let journal = Journal::new(dir.join("journal"));
+    let day = day_of(fixtures::T0);
+    journal.append_fill(&fill(1, fixtures::T0 + 30.0, OrderSide::Buy, 100.0, 2.0)).unwrap();
+    journal.append_fill(&fill(2, fixtures::T0 + 86400.0 + 30.0, OrderSide::Sell, 101.0, 1.0)).unwrap();
+    journal.mark_exported(day.pred_opt().unwrap()).unwrap();
+    let exporter = EodExporter::new(journal, dir.join("out"), vec![ReportFormat::Csv], None);
+
+    // Down over the runs of both days: the restart makes up for them, oldest first
+    let at = parse_time("00:05").unwrap();
+    let now = (day + chrono::Days::new(2)).and_hms_opt(6, 0, 0).unwrap().and_utc();
+    let exported = exporter.catch_up(now, at).await.unwrap();
+    assert_eq!(exported, vec![day, day.succ_opt().unwrap()]);
+    assert!(dir.join(format!("out/{}/fills.csv", day)).exists());
+    assert!(dir.join(format!("out/{}/fills.csv", day.succ_opt().unwrap())).exists());
+    assert_eq!(exporter.journal.last_exported().unwrap(), day.succ_opt());
+
+    // Nothing is exported twice
+    assert!(exporter.catch_up(now, at).await.unwrap().is_empty());
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_export_record_only_moves_forward() {
+    let dir = temp_dir();
+    let journal = Journal::new(&dir);
+    let day = day_of(fixtures::T0);
+    assert_eq!(journal.last_exported().unwrap(), None);
+    journal.mark_exported(day).unwrap();
+    journal.mark_exported(day.pred_opt().unwrap()).unwrap();
+    assert_eq!(journal.last_exported().unwrap(), Some(day));
+    std::fs::remove_dir_all(dir).ok();
+}
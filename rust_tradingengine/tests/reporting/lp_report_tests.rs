@@ -0,0 +1,171 @@
+use anyhow::Result;
+use serde_json::json;
+
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::domain::model::trade::Trade;
+use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
+use cryptics_lab_bot::reporting::{render, LpReportBuilder, ReportFormat};
+
+/// 2026-10-15 00:00:00 UTC
+const DAY_START: f64 = 1_792_022_400.0;
+
+fn ticker(time: f64, bid: f64, ask: f64) -> Ticker {
+    let mut ticker = Ticker::new("BTC-PERPETUAL".to_string());
+    ticker.mark_timestamp = time;
+    ticker.mark_price = (bid + ask) / 2.0;
+    ticker.best_bid_price = bid;
+    ticker.best_ask_price = ask;
+    ticker
+}
+
+/// Builder measuring uptime within 10 ticks of 5 (50) of the mark
+fn builder() -> LpReportBuilder {
+    let mut builder = LpReportBuilder::new(10.0);
+    builder.set_tick_size("BTC-PERPETUAL", 5.0);
+    builder
+}
+
+fn ack(builder: &mut LpReportBuilder, order_id: &str, direction: &str, price: f64, remaining: f64, status: &str) {
+    let ack = ThaleParser::parse_order_json(&json!({
+        "order_id": order_id,
+        "instrument_name": "BTC-PERPETUAL",
+        "direction": direction,
+        "price": price,
+        "remaining_amount": remaining,
+        "status": status,
+    })).unwrap();
+    builder.on_ack(&ack);
+}
+
+fn trade(time: f64, amount: f64, maker_taker: &str) -> Trade {
+    Trade {
+        trade_id: format!("t-{}", time),
+        order_id: "b1".to_string(),
+        client_order_id: None,
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        price: 50_000.0,
+        amount,
+        maker_taker: maker_taker.parse().ok(),
+        time,
+        processing_timestamp: None,
+        variant_id: None,
+    }
+}
+
+#[test]
+fn test_uptime_and_size_at_bbo() {
+    let mut builder = builder();
+    builder.on_ticker(&ticker(DAY_START, 49_990.0, 50_010.0));
+
+    // Two-sided at the touch for 30s
+    ack(&mut builder, "b1", "buy", 49_990.0, 0.2, "open");
+    ack(&mut builder, "a1", "sell", 50_010.0, 0.3, "open");
+    builder.on_ticker(&ticker(DAY_START + 30.0, 49_990.0, 50_010.0));
+
+    // Ask cancelled: one-sided for 10s
+    ack(&mut builder, "a1", "sell", 50_010.0, 0.3, "cancelled");
+    builder.on_ticker(&ticker(DAY_START + 40.0, 49_990.0, 50_010.0));
+
+    let rows = builder.finish();
+    assert_eq!(rows.len(), 1);
+    let row = &rows[0];
+    assert_eq!(row.date, "2026-10-15");
+    assert_eq!(row.observed_secs, 40.0);
+    assert!((row.uptime_pct - 75.0).abs() < 1e-9);
+    assert!((row.at_best_bid_pct - 100.0).abs() < 1e-9);
+    assert!((row.at_best_ask_pct - 75.0).abs() < 1e-9);
+    assert!((row.avg_size_at_best_bid - 0.2).abs() < 1e-9);
+    assert!((row.avg_size_at_best_ask - 0.225).abs() < 1e-9);
+}
+
+#[test]
+fn test_quotes_away_from_mid_are_not_up() {
+    // The bid 100 below the mark is 20 ticks away and doesn't count
+    let mut builder = builder();
+    builder.on_ticker(&ticker(DAY_START, 49_990.0, 50_010.0));
+    ack(&mut builder, "b1", "buy", 49_900.0, 0.2, "open");
+    ack(&mut builder, "a1", "sell", 50_020.0, 0.2, "open");
+    builder.on_ticker(&ticker(DAY_START + 10.0, 49_990.0, 50_010.0));
+
+    let row = &builder.finish()[0];
+    assert_eq!(row.uptime_pct, 0.0);
+    assert_eq!(row.at_best_bid_pct, 0.0);
+}
+
+#[test]
+fn test_data_gaps_are_not_observed() {
+    let mut builder = builder();
+    builder.on_ticker(&ticker(DAY_START, 49_990.0, 50_010.0));
+    builder.on_ticker(&ticker(DAY_START + 10.0, 49_990.0, 50_010.0));
+    builder.on_ticker(&ticker(DAY_START + 3_600.0, 49_990.0, 50_010.0));
+
+    assert_eq!(builder.finish()[0].observed_secs, 10.0);
+}
+
+#[test]
+fn test_volume_split_by_day_and_role() {
+    let mut builder = builder();
+    builder.on_trade(&trade(DAY_START - 1.0, 0.5, "maker"));
+    builder.on_trade(&trade(DAY_START + 1.0, 0.2, "maker"));
+    builder.on_trade(&trade(DAY_START + 2.0, 0.1, "taker"));
+
+    let rows = builder.finish();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].date, "2026-10-14");
+    assert_eq!(rows[0].maker_volume, 0.5);
+    assert_eq!(rows[1].maker_volume, 0.2);
+    assert_eq!(rows[1].taker_volume, 0.1);
+    assert_eq!(rows[1].trade_count, 2);
+    assert!((rows[1].notional - 15_000.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_render_csv_and_json() -> Result<()> {
+    let mut builder = builder();
+    builder.on_trade(&trade(DAY_START, 0.2, "maker"));
+    let rows = builder.finish();
+
+    let csv = render(&rows, ReportFormat::Csv)?;
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("date,instrument_name,observed_secs,uptime_pct"));
+    assert!(lines[1].starts_with("2026-10-15,BTC-PERPETUAL,0,0.00"));
+
+    let json: serde_json::Value = serde_json::from_str(&render(&rows, ReportFormat::Json)?)?;
+    assert_eq!(json[0]["maker_volume"], 0.2);
+    assert_eq!(json[0]["instrument_name"], "BTC-PERPETUAL");
+
+    assert_eq!("JSON".parse::<ReportFormat>()?, ReportFormat::Json);
+    assert_eq!(ReportFormat::from_path(std::path::Path::new("out.csv")), Some(ReportFormat::Csv));
+    Ok(())
+}
+
+#[test]
+fn test_uptime_without_tick_size_is_zero() {
+    let mut builder = LpReportBuilder::new(10.0);
+    builder.on_ticker(&ticker(DAY_START, 49_990.0, 50_010.0));
+    ack(&mut builder, "b1", "buy", 49_990.0, 0.2, "open");
+    ack(&mut builder, "a1", "sell", 50_010.0, 0.2, "open");
+    builder.on_ticker(&ticker(DAY_START + 10.0, 49_990.0, 50_010.0));
+
+    let row = &builder.finish()[0];
+    assert_eq!(row.observed_secs, 10.0);
+    assert_eq!(row.uptime_pct, 0.0);
+}
+
+#[test]
+fn test_interval_across_midnight_split_between_days() {
+    let mut builder = builder();
+    ack(&mut builder, "b1", "buy", 49_990.0, 0.2, "open");
+    ack(&mut builder, "a1", "sell", 50_010.0, 0.2, "open");
+    builder.on_ticker(&ticker(DAY_START - 20.0, 49_990.0, 50_010.0));
+    builder.on_ticker(&ticker(DAY_START + 10.0, 49_990.0, 50_010.0));
+
+    let rows = builder.finish();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].date, "2026-10-14");
+    assert_eq!(rows[0].observed_secs, 20.0);
+    assert_eq!(rows[1].date, "2026-10-15");
+    assert_eq!(rows[1].observed_secs, 10.0);
+    assert!((rows[1].uptime_pct - 100.0).abs() < 1e-9);
+}
@@ -0,0 +1,6 @@
+//! Tests for reporting
+
+// Import test modules
+pub mod eod_tests;
+pub mod lp_report_tests;
+pub mod variants_tests;
@@ -0,0 +1,79 @@
+use cryptics_lab_bot::domain::enums::{MakerTaker, OrderSide};
+use cryptics_lab_bot::domain::model::order::Order;
+use cryptics_lab_bot::domain::model::trade::Trade;
+use cryptics_lab_bot::reporting::{render, ReportFormat, VariantReportBuilder};
+
+const T0: f64 = 1792022400.0;
+
+fn ack(order_id: &str, side: OrderSide, variant_id: Option<&str>) -> Order {
+    let mut order = Order::pending(1, "BTC-PERPETUAL".to_string(), side, 65000.0, 1.0);
+    order.order_id = order_id.to_string();
+    order.create_time = T0;
+    order.variant_id = variant_id.map(str::to_string);
+    order
+}
+
+fn trade(order_id: &str, price: f64, amount: f64, time: f64) -> Trade {
+    Trade {
+        trade_id: format!("{}-{}", order_id, time),
+        order_id: order_id.to_string(),
+        client_order_id: None,
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        price,
+        amount,
+        maker_taker: Some(MakerTaker::Maker),
+        time,
+        processing_timestamp: None,
+        variant_id: None,
+    }
+}
+
+#[test]
+fn test_variants_keep_separate_books() {
+    let mut builder = VariantReportBuilder::new();
+    builder.on_ack(&ack("a-buy", OrderSide::Buy, Some("tight")));
+    builder.on_ack(&ack("a-sell", OrderSide::Sell, Some("tight")));
+    builder.on_ack(&ack("b-buy", OrderSide::Buy, Some("wide")));
+    builder.on_ack(&ack("untagged", OrderSide::Sell, None));
+    // Later updates of an order don't count it again
+    builder.on_ack(&ack("a-buy", OrderSide::Buy, Some("tight")));
+    
+    builder.on_trade(&trade("a-buy", 65000.0, 1.0, T0 + 1.0));
+    builder.on_trade(&trade("b-buy", 64990.0, 1.0, T0 + 2.0));
+    // Closes the tight position only; the wide one stays open
+    builder.on_trade(&trade("a-sell", 65010.0, 1.0, T0 + 3.0));
+    builder.on_trade(&trade("untagged", 65020.0, 1.0, T0 + 4.0));
+    
+    let rows = builder.finish();
+    assert_eq!(rows.len(), 2);
+    let (tight, wide) = (&rows[0], &rows[1]);
+    assert_eq!((tight.variant_id.as_str(), tight.date.as_str()), ("tight", "2026-10-15"));
+    assert_eq!(tight.orders, 2);
+    assert_eq!(tight.trade_count, 2);
+    assert_eq!(tight.maker_volume, 2.0);
+    assert_eq!(tight.realized_pnl, 10.0);
+    assert_eq!(tight.position, 0.0);
+    
+    assert_eq!(wide.variant_id, "wide");
+    assert_eq!(wide.orders, 1);
+    assert_eq!(wide.realized_pnl, 0.0);
+    assert_eq!(wide.position, 1.0);
+}
+
+#[test]
+fn test_trade_variant_without_ack_counts_volume() {
+    let mut builder = VariantReportBuilder::new();
+    let mut fill = trade("unknown", 65000.0, 0.5, T0);
+    fill.variant_id = Some("tight".to_string());
+    builder.on_trade(&fill);
+    
+    let rows = builder.finish();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].orders, 0);
+    assert_eq!(rows[0].notional, 32500.0);
+    assert_eq!(rows[0].realized_pnl, 0.0);
+    
+    let csv = render(&rows, ReportFormat::Csv).unwrap();
+    assert!(csv.starts_with("date,variant_id,instrument_name,orders,trade_count"));
+    assert!(csv.contains("2026-10-15,tight,BTC-PERPETUAL,0,1,0.5,0,32500.00,0.00,0"));
+}
@@ -0,0 +1,4 @@
+//! Tests for the strategy layer
+
+// Import test modules
+pub mod thalex_market_maker;
@@ -0,0 +1,54 @@
+use cryptics_lab_bot::config_loader::InstrumentStrategyConfig;
+use cryptics_lab_bot::strategies::thalex_market_maker::{AmendPolicy, Amendment, AMEND_THRESHOLD};
+
+#[test]
+fn test_last_threshold_applies_to_deeper_levels() {
+    let policy = AmendPolicy::new(vec![2.0, 5.0, 10.0], 0.0).unwrap();
+    assert_eq!(policy.threshold(0), 2.0);
+    assert_eq!(policy.threshold(2), 10.0);
+    assert_eq!(policy.threshold(7), 10.0);
+}
+
+#[test]
+fn test_defaults_to_uniform_threshold() {
+    let policy = AmendPolicy::default();
+    assert_eq!(policy.threshold(0), AMEND_THRESHOLD);
+    assert_eq!(policy.threshold(3), AMEND_THRESHOLD);
+    assert_eq!(AmendPolicy::new(vec![], 0.0).unwrap(), policy);
+}
+
+#[test]
+fn test_needs_amend() {
+    let policy = AmendPolicy::new(vec![5.0, 20.0], 0.1).unwrap();
+    // Price moves are compared in ticks against the level's threshold
+    assert!(!policy.needs_amend(0, 100.0, 1.0, 102.5, 1.0, 0.5));
+    assert!(policy.needs_amend(0, 100.0, 1.0, 103.0, 1.0, 0.5));
+    assert!(!policy.needs_amend(1, 100.0, 1.0, 109.0, 1.0, 0.5));
+    // Size is only amended down, beyond the tolerance
+    assert!(!policy.needs_amend(0, 100.0, 1.0, 100.0, 0.95, 0.5));
+    assert!(policy.needs_amend(0, 100.0, 1.0, 100.0, 0.8, 0.5));
+    assert!(!policy.needs_amend(0, 100.0, 1.0, 100.0, 2.0, 0.5));
+}
+
+#[test]
+fn test_amendment_carries_only_changed_parts() {
+    let policy = AmendPolicy::new(vec![5.0], 0.1).unwrap();
+    assert_eq!(policy.amendment(0, 100.0, 1.0, 100.5, 1.0, 0.5), None);
+    assert_eq!(policy.amendment(0, 100.0, 1.0, 103.0, 1.0, 0.5), Some(Amendment { price: Some(103.0), amount: None }));
+    assert_eq!(policy.amendment(0, 100.0, 1.0, 100.0, 0.5, 0.5), Some(Amendment { price: None, amount: Some(0.5) }));
+    assert_eq!(policy.amendment(0, 100.0, 1.0, 97.0, 0.5, 0.5), Some(Amendment { price: Some(97.0), amount: Some(0.5) }));
+}
+
+#[test]
+fn test_invalid_settings_rejected() {
+    assert!(AmendPolicy::new(vec![-1.0], 0.0).is_err());
+    assert!(AmendPolicy::new(vec![5.0], 1.0).is_err());
+    
+    let settings = InstrumentStrategyConfig {
+        instrument: "BTC-PERPETUAL".to_string(),
+        amend_thresholds: vec![f64::NAN],
+        ..Default::default()
+    };
+    let err = AmendPolicy::from_config(&settings).unwrap_err();
+    assert!(err.to_string().contains("BTC-PERPETUAL"));
+}
@@ -0,0 +1,181 @@
+use serde_json::json;
+
+use cryptics_lab_bot::domain::model::book::{BookLevel, BookSide, BookUpdateKind, OrderBook};
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+use cryptics_lab_bot::strategies::thalex_market_maker::BookRecorder;
+
+/// 2026-10-15T00:00:00Z
+const DAY_START: f64 = 1_792_022_400.0;
+
+fn channel(instrument: &str) -> Channel {
+    Channel::book(instrument, 5)
+}
+
+#[test]
+fn test_first_update_is_snapshot() {
+    let mut recorder = BookRecorder::new(60.0);
+    let book = json!({
+        "bids": [[99.0, 1.0, 0.0], [100.0, 2.0, 0.0]],
+        "asks": [[102.0, 1.5, 0.0], [101.0, 0.5, 0.0]],
+        "time": DAY_START - 0.1,
+    });
+    
+    let updates = recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START).unwrap();
+    assert!(updates.iter().all(|u| u.kind == BookUpdateKind::Snapshot && u.sequence == 1));
+    let levels: Vec<(BookSide, f64, f64)> = updates.iter().map(|u| (u.side, u.price, u.amount)).collect();
+    assert_eq!(levels, vec![
+        (BookSide::Bid, 100.0, 2.0),
+        (BookSide::Bid, 99.0, 1.0),
+        (BookSide::Ask, 101.0, 0.5),
+        (BookSide::Ask, 102.0, 1.5),
+    ]);
+    assert_eq!(updates[0].exchange_time, Some(DAY_START - 0.1));
+}
+
+#[test]
+fn test_deltas_between_snapshots() {
+    let mut recorder = BookRecorder::new(60.0);
+    recorder.on_book(&channel("BTC-PERPETUAL"), &json!({
+        "bids": [[100.0, 2.0], [99.0, 1.0]],
+        "asks": [[101.0, 0.5]],
+    }), DAY_START).unwrap();
+    
+    let updates = recorder.on_book(&channel("BTC-PERPETUAL"), &json!({
+        "bids": [[100.0, 3.0], [98.0, 4.0]],
+        "asks": [[101.0, 0.5]],
+    }), DAY_START + 1.0).unwrap();
+    
+    assert!(updates.iter().all(|u| u.kind == BookUpdateKind::Delta && u.sequence == 2));
+    let changes: Vec<(BookSide, f64, f64)> = updates.iter().map(|u| (u.side, u.price, u.amount)).collect();
+    assert_eq!(changes, vec![
+        (BookSide::Bid, 98.0, 4.0),
+        (BookSide::Bid, 99.0, 0.0),
+        (BookSide::Bid, 100.0, 3.0),
+    ]);
+}
+
+#[test]
+fn test_unchanged_book_yields_no_deltas() {
+    let mut recorder = BookRecorder::new(60.0);
+    let book = json!({"bids": [[100.0, 2.0]], "asks": [[101.0, 0.5]]});
+    recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START).unwrap();
+    
+    assert!(recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START + 1.0).unwrap().is_empty());
+}
+
+#[test]
+fn test_periodic_snapshot() {
+    let mut recorder = BookRecorder::new(60.0);
+    let book = json!({"bids": [[100.0, 2.0]], "asks": [[101.0, 0.5]]});
+    recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START).unwrap();
+    recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START + 30.0).unwrap();
+    
+    let updates = recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START + 60.0).unwrap();
+    assert_eq!(updates.len(), 2);
+    assert!(updates.iter().all(|u| u.kind == BookUpdateKind::Snapshot && u.sequence == 3));
+}
+
+#[test]
+fn test_clear_forces_snapshot() {
+    let mut recorder = BookRecorder::new(60.0);
+    let book = json!({"bids": [[100.0, 2.0]], "asks": []});
+    recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START).unwrap();
+    recorder.clear();
+    
+    // The sequence carries on, so consumers never see a number twice
+    let updates = recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START + 1.0).unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].kind, BookUpdateKind::Snapshot);
+    assert_eq!(updates[0].sequence, 2);
+}
+
+#[test]
+fn test_resync_forces_snapshot_of_one_channel() {
+    let mut recorder = BookRecorder::new(60.0);
+    let book = json!({"bids": [[100.0, 2.0]], "asks": []});
+    recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START).unwrap();
+    recorder.on_book(&channel("ETH-PERPETUAL"), &book, DAY_START).unwrap();
+    recorder.resync(&channel("BTC-PERPETUAL"));
+    
+    let updates = recorder.on_book(&channel("BTC-PERPETUAL"), &book, DAY_START + 1.0).unwrap();
+    assert_eq!(updates[0].kind, BookUpdateKind::Snapshot);
+    assert!(recorder.on_book(&channel("ETH-PERPETUAL"), &book, DAY_START + 1.0).unwrap().is_empty());
+}
+
+#[test]
+fn test_channels_of_one_instrument_are_independent() {
+    let mut recorder = BookRecorder::new(60.0);
+    let top = Channel::book("BTC-PERPETUAL", 1);
+    let deep = Channel::book("BTC-PERPETUAL", 5);
+    recorder.on_book(&top, &json!({"bids": [[100.0, 2.0]]}), DAY_START).unwrap();
+    recorder.on_book(&deep, &json!({"bids": [[100.0, 2.0], [99.0, 1.0]]}), DAY_START).unwrap();
+    
+    // The top-of-book update doesn't remove the deeper channel's second level
+    assert!(recorder.on_book(&top, &json!({"bids": [[100.0, 2.0]]}), DAY_START + 1.0).unwrap().is_empty());
+    let updates = recorder.on_book(&deep, &json!({"bids": [[100.0, 2.0], [99.0, 1.0]]}), DAY_START + 1.0).unwrap();
+    assert!(updates.is_empty());
+}
+
+#[test]
+fn test_instruments_are_independent() {
+    let mut recorder = BookRecorder::new(60.0);
+    recorder.on_book(&channel("BTC-PERPETUAL"), &json!({"bids": [[100.0, 2.0]]}), DAY_START).unwrap();
+    
+    let updates = recorder.on_book(&channel("ETH-PERPETUAL"), &json!({"bids": [[10.0, 1.0]]}), DAY_START + 1.0).unwrap();
+    assert_eq!(updates[0].kind, BookUpdateKind::Snapshot);
+    assert_eq!(updates[0].instrument_name, "ETH-PERPETUAL");
+}
+
+#[test]
+fn test_malformed_level_rejected() {
+    let mut recorder = BookRecorder::new(60.0);
+    assert!(recorder.on_book(&channel("BTC-PERPETUAL"), &json!({"bids": [[100.0]]}), DAY_START).is_err());
+    assert!(recorder.on_book(&channel("BTC-PERPETUAL"), &json!({"bids": "none"}), DAY_START).is_err());
+}
+
+#[test]
+fn test_order_book_follows_snapshots_and_deltas() {
+    let mut recorder = BookRecorder::new(60.0);
+    let mut book = OrderBook::new("BTC-PERPETUAL");
+    let mut apply = |notification, now| {
+        for update in recorder.on_book(&channel("BTC-PERPETUAL"), &notification, now).unwrap() {
+            book.apply(&update);
+        }
+        book.clone()
+    };
+    
+    let first = apply(json!({"bids": [[99.0, 1.0], [100.0, 2.0]], "asks": [[101.0, 0.5], [102.0, 1.5]]}), DAY_START);
+    assert_eq!(first.best_bid(), Some(BookLevel { price: 100.0, amount: 2.0 }));
+    assert_eq!(first.asks.iter().map(|level| level.price).collect::<Vec<_>>(), vec![101.0, 102.0]);
+    
+    // Deltas change, add and remove levels in place
+    let updated = apply(json!({"bids": [[100.0, 3.0], [98.0, 4.0]], "asks": [[100.5, 0.2], [101.0, 0.5]]}), DAY_START + 1.0);
+    assert_eq!(updated.bids, vec![BookLevel { price: 100.0, amount: 3.0 }, BookLevel { price: 98.0, amount: 4.0 }]);
+    assert_eq!(updated.asks.iter().map(|level| level.price).collect::<Vec<_>>(), vec![100.5, 101.0]);
+    assert_eq!(updated.sequence, 2);
+    
+    // A periodic snapshot replaces the book
+    let snapshot = apply(json!({"bids": [[97.0, 1.0]], "asks": [[103.0, 1.0]]}), DAY_START + 61.0);
+    assert_eq!((snapshot.bids.len(), snapshot.asks.len()), (1, 1));
+    assert_eq!(snapshot.spread(), Some(6.0));
+}
+
+#[test]
+fn test_order_book_microprice_and_imbalance() {
+    let mut book = OrderBook::new("BTC-PERPETUAL");
+    assert_eq!(book.mid(), None);
+    let mut recorder = BookRecorder::new(60.0);
+    for update in recorder.on_book(&channel("BTC-PERPETUAL"), &json!({
+        "bids": [[100.0, 3.0], [99.0, 1.0]],
+        "asks": [[102.0, 1.0], [103.0, 3.0]],
+    }), DAY_START).unwrap() {
+        book.apply(&update);
+    }
+    
+    assert_eq!(book.mid(), Some(101.0));
+    // Thin asks pull the microprice towards them
+    assert_eq!(book.microprice(), Some(101.5));
+    assert_eq!(book.imbalance(1), 0.5);
+    assert_eq!(book.imbalance(2), 0.0);
+    assert_eq!(book.depth(BookSide::Ask, 5), 4.0);
+}
@@ -0,0 +1,62 @@
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::strategies::thalex_market_maker::CarryTracker;
+use cryptics_lab_bot::testing::fixtures;
+
+const PERIOD: f64 = 8.0 * 3600.0;
+
+fn ticker(mark_price: f64, index_price: f64, funding_rate: f64) -> Ticker {
+    Ticker { mark_price, index_price, funding_rate, ..fixtures::ticker(fixtures::INSTRUMENT) }
+}
+
+#[test]
+fn test_basis_and_rolling_average() {
+    let mut tracker = CarryTracker::new(60.0, PERIOD);
+    assert!(tracker.sample(0.0, &ticker(50_010.0, 0.0, 0.0), 0.0).is_none());
+
+    let sample = tracker.sample(0.0, &ticker(50_010.0, 50_000.0, 0.0), 0.0).unwrap();
+    assert_eq!(sample.instrument_name, fixtures::INSTRUMENT);
+    assert_eq!(sample.basis, 10.0);
+    assert!((sample.basis_bps - 2.0).abs() < 1e-9);
+
+    let sample = tracker.sample(30.0, &ticker(50_030.0, 50_000.0, 0.0), 0.0).unwrap();
+    assert!((sample.rolling_basis_bps - 4.0).abs() < 1e-9);
+
+    // The first sample falls out of the window
+    let sample = tracker.sample(90.0, &ticker(50_030.0, 50_000.0, 0.0), 0.0).unwrap();
+    assert!((sample.rolling_basis_bps - 6.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_cumulative_funding_is_what_was_paid() {
+    let mut tracker = CarryTracker::new(60.0, PERIOD);
+    // Holding a position at a funding rate accrues nothing by itself
+    tracker.sample(0.0, &ticker(50_000.0, 50_000.0, 0.0001), 2.0);
+    let sample = tracker.sample(PERIOD, &ticker(50_000.0, 50_000.0, 0.0001), 2.0).unwrap();
+    assert_eq!(sample.cumulative_funding, 0.0);
+
+    tracker.record_funding(-10.0);
+    tracker.record_funding(4.0);
+    let sample = tracker.sample(PERIOD + 1.0, &ticker(50_000.0, 50_000.0, 0.0001), 2.0).unwrap();
+    assert_eq!(sample.cumulative_funding, -6.0);
+
+    // A copy kept across sessions carries on from the same total
+    let mut next_session = tracker.clone();
+    next_session.record_funding(1.0);
+    assert_eq!(next_session.cumulative_funding(), -5.0);
+}
+
+#[test]
+fn test_annualized_carry_follows_position_sign() {
+    let mut tracker = CarryTracker::new(60.0, PERIOD);
+    let long = tracker.sample(0.0, &ticker(50_000.0, 50_000.0, 0.0001), 1.0).unwrap();
+    assert!((long.annualized_funding_rate - 0.1095).abs() < 1e-9);
+    assert_eq!(long.annualized_carry, -long.annualized_funding_rate);
+
+    let short = tracker.sample(1.0, &ticker(50_000.0, 50_000.0, 0.0001), -1.0).unwrap();
+    assert_eq!(short.annualized_carry, short.annualized_funding_rate);
+    assert_eq!(tracker.sample(2.0, &ticker(50_000.0, 50_000.0, 0.0001), 0.0).unwrap().annualized_carry, 0.0);
+
+    // Funding settled hourly annualizes eight times the rate
+    let hourly = CarryTracker::new(60.0, 3600.0).sample(0.0, &ticker(50_000.0, 50_000.0, 0.0001), 1.0).unwrap();
+    assert!((hourly.annualized_funding_rate - 0.876).abs() < 1e-9);
+}
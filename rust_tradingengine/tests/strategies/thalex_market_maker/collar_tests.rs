@@ -0,0 +1,91 @@
+use cryptics_lab_bot::domain::model::quote::SideQuote;
+use cryptics_lab_bot::strategies::thalex_market_maker::CollarGuard;
+
+const MARK: f64 = 50_000.0;
+const TICK: f64 = 1.0;
+
+fn guard() -> CollarGuard {
+    CollarGuard::new(2.0, 50.0, 25.0, 10.0, 0.5).unwrap()
+}
+
+fn quotes(bids: &[f64], asks: &[f64]) -> Vec<Vec<SideQuote>> {
+    vec![
+        bids.iter().map(|price| SideQuote::new(*price, 0.2)).collect(),
+        asks.iter().map(|price| SideQuote::new(*price, 0.2)).collect(),
+    ]
+}
+
+fn prices(quotes: &[SideQuote]) -> Vec<f64> {
+    quotes.iter().map(|quote| quote.price).collect()
+}
+
+#[test]
+fn test_wide_collar_leaves_quotes_alone() {
+    let mut guard = guard();
+    let result = guard.apply(quotes(&[49_975.0, 49_970.0], &[50_025.0, 50_030.0]), 49_000.0, 51_000.0, MARK, TICK, 0.0);
+    assert_eq!(prices(&result[0]), vec![49_975.0, 49_970.0]);
+    assert_eq!(prices(&result[1]), vec![50_025.0, 50_030.0]);
+    assert_eq!(result[0][0].amount, 0.2);
+}
+
+#[test]
+fn test_quotes_clamped_inside_collar_with_buffer() {
+    let mut guard = guard();
+    // Fair value above the collar: asks pushed down to the buffer below collar_high
+    let result = guard.apply(quotes(&[50_400.0, 50_395.0], &[50_450.0, 50_455.0]), 49_800.0, 50_420.0, MARK, TICK, 0.0);
+    assert_eq!(prices(&result[1]), vec![50_418.0, 50_418.0]);
+    assert_eq!(prices(&result[0]), vec![50_400.0, 50_395.0]);
+    
+    // Both sides collapse onto the ceiling: the bids would cross and are dropped
+    let result = guard.apply(quotes(&[50_600.0], &[50_650.0]), 49_800.0, 50_420.0, MARK, TICK, 1.0);
+    assert!(result[0].is_empty());
+    assert_eq!(prices(&result[1]), vec![50_418.0]);
+}
+
+#[test]
+fn test_tight_collar_reduces_size() {
+    let mut guard = guard();
+    // 40 bps wide
+    let result = guard.apply(quotes(&[49_990.0], &[50_010.0]), 49_900.0, 50_100.0, MARK, TICK, 0.0);
+    assert_eq!(result[0][0].amount, 0.1);
+    assert_eq!(result[1][0].amount, 0.1);
+}
+
+#[test]
+fn test_fast_moving_collar_reduces_size_until_window_passes() {
+    let mut guard = guard();
+    let result = guard.apply(quotes(&[49_975.0], &[50_025.0]), 49_000.0, 51_000.0, MARK, TICK, 0.0);
+    assert_eq!(result[0][0].amount, 0.2);
+    
+    // Midpoint up 200 (40 bps) within the window
+    let result = guard.apply(quotes(&[49_975.0], &[50_025.0]), 49_200.0, 51_200.0, MARK, TICK, 5.0);
+    assert_eq!(result[0][0].amount, 0.1);
+    assert!(guard.is_stressed(49_200.0, 51_200.0, MARK));
+    
+    // The earlier collar has left the window
+    let result = guard.apply(quotes(&[49_975.0], &[50_025.0]), 49_200.0, 51_200.0, MARK, TICK, 16.0);
+    assert_eq!(result[0][0].amount, 0.2);
+}
+
+#[test]
+fn test_collar_narrower_than_buffer_pulls_quotes() {
+    let mut guard = guard();
+    let result = guard.apply(quotes(&[49_999.0], &[50_001.0]), 49_999.0, 50_002.0, MARK, TICK, 0.0);
+    assert!(result.iter().all(|side| side.is_empty()));
+}
+
+#[test]
+fn test_missing_collar_passes_quotes() {
+    let mut guard = guard();
+    let result = guard.apply(quotes(&[49_975.0], &[50_025.0]), 0.0, 0.0, MARK, TICK, 0.0);
+    assert_eq!(prices(&result[0]), vec![49_975.0]);
+    assert_eq!(result[1][0].amount, 0.2);
+}
+
+#[test]
+fn test_invalid_settings_rejected() {
+    assert!(CollarGuard::new(-1.0, 50.0, 25.0, 10.0, 0.5).is_err());
+    assert!(CollarGuard::new(2.0, 50.0, 0.0, 10.0, 0.5).is_err());
+    assert!(CollarGuard::new(2.0, 50.0, 25.0, 0.0, 0.5).is_err());
+    assert!(CollarGuard::new(2.0, 50.0, 25.0, 10.0, 1.5).is_err());
+}
@@ -0,0 +1,34 @@
+use serde_json::json;
+
+use cryptics_lab_bot::strategies::thalex_market_maker::{ControlCommand, ControlMessage};
+
+#[test]
+fn test_commands_parsed_with_instrument() {
+    let command = ControlCommand::from_json(&json!({ "command": "flatten", "instrument": "ETH-PERPETUAL" })).unwrap();
+    assert_eq!(command, ControlCommand::Flatten { instrument: "ETH-PERPETUAL".to_string() });
+    assert_eq!(command.instrument(), "ETH-PERPETUAL");
+    
+    let command = ControlCommand::from_json(&json!({ "command": "pause", "instrument": "BTC-PERPETUAL" })).unwrap();
+    assert_eq!(command, ControlCommand::Pause { instrument: "BTC-PERPETUAL".to_string() });
+    
+    let command = ControlCommand::from_json(&json!({ "command": "disable", "instrument": "BTC-PERPETUAL" })).unwrap();
+    assert_eq!(command, ControlCommand::Disable { instrument: "BTC-PERPETUAL".to_string() });
+}
+
+#[test]
+fn test_invalid_commands_rejected() {
+    assert!(ControlCommand::from_json(&json!({ "command": "liquidate", "instrument": "BTC-PERPETUAL" })).is_err());
+    // Commands are always instrument-scoped
+    assert!(ControlCommand::from_json(&json!({ "command": "pause" })).is_err());
+}
+
+#[test]
+fn test_control_messages_carry_an_optional_deadline() {
+    let message = ControlMessage::from_json(&json!({ "command": "pause", "instrument": "BTC-PERPETUAL", "expires_at": 1_700_000_000.0 })).unwrap();
+    assert_eq!(message.command, ControlCommand::Pause { instrument: "BTC-PERPETUAL".to_string() });
+    assert_eq!(message.expires_at, Some(1_700_000_000.0));
+    
+    let message = ControlMessage::from_json(&json!({ "command": "resume", "instrument": "BTC-PERPETUAL" })).unwrap();
+    assert_eq!(message.expires_at, None);
+    assert!(ControlMessage::from_json(&json!({ "command": "liquidate", "instrument": "BTC-PERPETUAL" })).is_err());
+}
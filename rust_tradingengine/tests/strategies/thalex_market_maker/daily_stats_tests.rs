@@ -0,0 +1,132 @@
+use cryptics_lab_bot::domain::enums::OrderSide;
+use cryptics_lab_bot::strategies::thalex_market_maker::{day_of, DailyStats, UptimeTracker};
+
+/// 2026-10-15T00:00:00Z
+const DAY_START: f64 = 1_792_022_400.0;
+
+#[test]
+fn test_fills_accumulate_realized_pnl() {
+    let mut stats = DailyStats::new(day_of(DAY_START));
+    let t0 = DAY_START + 3600.0;
+    
+    assert!(stats.record_fill(&OrderSide::Buy, 100.0, 2.0, t0).is_none());
+    stats.record_fill(&OrderSide::Buy, 110.0, 2.0, t0 + 1.0);
+    assert_eq!(stats.avg_price, 105.0);
+    
+    stats.record_fill(&OrderSide::Sell, 120.0, 1.0, t0 + 2.0);
+    assert_eq!(stats.realized_pnl, 15.0);
+    assert_eq!(stats.position, 3.0);
+    assert_eq!(stats.avg_price, 105.0);
+    
+    // Selling through zero closes 3 and opens a short of 2 at the fill price
+    stats.record_fill(&OrderSide::Sell, 100.0, 5.0, t0 + 3.0);
+    assert_eq!(stats.realized_pnl, 0.0);
+    assert_eq!(stats.position, -2.0);
+    assert_eq!(stats.avg_price, 100.0);
+    
+    assert_eq!(stats.fills, 4);
+    assert_eq!(stats.volume, 10.0);
+}
+
+#[test]
+fn test_rebuilt_replays_fills_from_the_opening_position() {
+    let mut opening = DailyStats::new(day_of(DAY_START - 86_400.0));
+    opening.record_fill(&OrderSide::Buy, 100.0, 2.0, DAY_START - 10.0);
+    let mut stats = opening.clone();
+    stats.record_fill(&OrderSide::Sell, 110.0, 1.0, DAY_START + 10.0);
+    stats.record_fill(&OrderSide::Sell, 120.0, 1.0, DAY_START + 20.0);
+    stats.uptime_observed_secs = 60.0;
+    
+    let fills = [(OrderSide::Sell, 110.0, 1.0), (OrderSide::Sell, 120.0, 1.0)];
+    assert_eq!(stats.rebuilt(&opening, fills.iter().map(|(side, price, amount)| (side, *price, *amount))), stats);
+    
+    // Without the second sale the day realized only the first and the uptime is kept
+    let busted = stats.rebuilt(&opening, fills[..1].iter().map(|(side, price, amount)| (side, *price, *amount)));
+    assert_eq!(busted.date, "2026-10-15");
+    assert_eq!((busted.fills, busted.volume, busted.realized_pnl), (1, 1.0, 10.0));
+    assert_eq!((busted.position, busted.avg_price), (1.0, 100.0));
+    assert_eq!(busted.uptime_observed_secs, 60.0);
+}
+
+#[test]
+fn test_reverse_fill_restores_counts_and_position() {
+    let mut stats = DailyStats::new(day_of(DAY_START));
+    stats.record_fill(&OrderSide::Buy, 100.0, 2.0, DAY_START + 10.0);
+    stats.record_fill(&OrderSide::Sell, 110.0, 1.0, DAY_START + 20.0);
+    
+    stats.reverse_fill(&OrderSide::Sell, 110.0, 1.0);
+    assert_eq!((stats.fills, stats.volume, stats.notional), (1, 2.0, 200.0));
+    assert_eq!(stats.position, 2.0);
+    // Realized PnL can't be taken back without replaying the day
+    assert_eq!(stats.realized_pnl, 10.0);
+    
+    stats.reverse_fill(&OrderSide::Buy, 100.0, 2.0);
+    assert_eq!((stats.position, stats.avg_price), (0.0, 0.0));
+}
+
+#[test]
+fn test_new_day_carries_position_over() {
+    let mut stats = DailyStats::new(day_of(DAY_START));
+    stats.record_fill(&OrderSide::Buy, 100.0, 1.0, DAY_START + 10.0);
+    
+    let completed = stats.record_fill(&OrderSide::Sell, 90.0, 0.5, DAY_START + 86_400.0 + 10.0)
+        .expect("day completed");
+    assert_eq!(completed.date, "2026-10-15");
+    assert_eq!(completed.fills, 1);
+    
+    assert_eq!(stats.date, "2026-10-16");
+    assert_eq!(stats.fills, 1);
+    assert_eq!(stats.realized_pnl, -5.0);
+    assert_eq!(stats.position, 0.5);
+    assert_eq!(stats.avg_price, 100.0);
+}
+
+#[test]
+fn test_save_and_load_round_trip() {
+    let path = std::env::temp_dir()
+        .join(format!("daily_stats_tests_{}", uuid::Uuid::new_v4()))
+        .join("daily_stats.json");
+    assert!(DailyStats::load(&path).unwrap().is_none());
+    
+    let mut stats = DailyStats::new(day_of(DAY_START));
+    stats.record_fill(&OrderSide::Sell, 100.0, 0.25, DAY_START + 60.0);
+    stats.save(&path).unwrap();
+    
+    assert_eq!(DailyStats::load(&path).unwrap(), Some(stats));
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_uptime_resumes_same_day() {
+    let mut tracker = UptimeTracker::new("BTC-PERPETUAL", 50.0);
+    tracker.sample(DAY_START + 100.0, true);
+    tracker.sample(DAY_START + 160.0, true);
+    let mut stats = DailyStats::new(day_of(DAY_START));
+    stats.set_uptime(&tracker.current(DAY_START + 160.0));
+    
+    // Restarted later the same day: the downtime isn't observed
+    let mut restarted = UptimeTracker::new("BTC-PERPETUAL", 50.0);
+    restarted.resume(&stats);
+    restarted.sample(DAY_START + 1000.0, false);
+    restarted.sample(DAY_START + 1040.0, false);
+    
+    let current = restarted.current(DAY_START + 1040.0);
+    assert_eq!(current.period_start, DAY_START + 100.0);
+    assert_eq!(current.observed_secs, 100.0);
+    assert_eq!(current.compliant_secs, 60.0);
+}
+
+#[test]
+fn test_uptime_of_previous_day_is_not_resumed() {
+    let mut stats = DailyStats::new(day_of(DAY_START));
+    stats.uptime_observed_secs = 500.0;
+    stats.uptime_compliant_secs = 500.0;
+    
+    let mut tracker = UptimeTracker::new("BTC-PERPETUAL", 50.0);
+    tracker.resume(&stats);
+    tracker.sample(DAY_START + 86_400.0 + 10.0, true);
+    
+    let current = tracker.current(DAY_START + 86_400.0 + 10.0);
+    assert_eq!(current.date, "2026-10-16");
+    assert_eq!(current.observed_secs, 0.0);
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use cryptics_lab_bot::config_loader::ExperimentConfig;
+use cryptics_lab_bot::strategies::thalex_market_maker::{Experiment, QuoteParams};
+
+fn experiment(config: serde_json::Value) -> Result<Option<Experiment>> {
+    let config: ExperimentConfig = serde_json::from_value(config)?;
+    Experiment::from_config(&config)
+}
+
+fn at(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+}
+
+fn variant_id(experiment: &Experiment, now: &str, instrument: &str) -> Option<String> {
+    experiment.variant_at(at(now), instrument).map(|variant| variant.id.clone())
+}
+
+#[test]
+fn test_disabled_experiment_is_none() -> Result<()> {
+    assert!(experiment(json!({}))?.is_none());
+    assert!(experiment(json!({ "variants": [{ "id": "a", "spread": 20.0 }] }))?.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_time_slices_alternate_in_config_order() -> Result<()> {
+    let experiment = experiment(json!({
+        "enabled": true, "name": "spread", "slice_sec": 3600,
+        "variants": [{ "id": "tight", "spread": 20.0 }, { "id": "wide", "spread": 30.0 }]
+    }))?.unwrap();
+    
+    // Slices count from the epoch, so every instance agrees on the variant
+    assert_eq!(variant_id(&experiment, "2026-10-15T00:00:00Z", "BTC-PERPETUAL").as_deref(), Some("tight"));
+    assert_eq!(variant_id(&experiment, "2026-10-15T00:59:59Z", "BTC-PERPETUAL").as_deref(), Some("tight"));
+    assert_eq!(variant_id(&experiment, "2026-10-15T01:00:00Z", "BTC-PERPETUAL").as_deref(), Some("wide"));
+    assert_eq!(variant_id(&experiment, "2026-10-15T02:30:00Z", "ETH-PERPETUAL").as_deref(), Some("tight"));
+    
+    // A variant only replaces what it sets
+    let mut params = QuoteParams { skew: 2.0, ..QuoteParams::default() };
+    params.apply(&experiment.variant_at(at("2026-10-15T01:00:00Z"), "BTC-PERPETUAL").unwrap().params);
+    assert_eq!(params.spread, 30.0);
+    assert_eq!(params.skew, 2.0);
+    Ok(())
+}
+
+#[test]
+fn test_instrument_assignment() -> Result<()> {
+    let experiment = experiment(json!({
+        "enabled": true, "assignment": "instrument",
+        "variants": [
+            { "id": "tight", "spread": 20.0, "instruments": ["BTC-PERPETUAL"] },
+            { "id": "wide", "spread": 30.0, "instruments": ["ETH-PERPETUAL"] }
+        ]
+    }))?.unwrap();
+    assert_eq!(variant_id(&experiment, "2026-10-15T00:00:00Z", "BTC-PERPETUAL").as_deref(), Some("tight"));
+    assert_eq!(variant_id(&experiment, "2026-10-15T05:00:00Z", "ETH-PERPETUAL").as_deref(), Some("wide"));
+    assert_eq!(variant_id(&experiment, "2026-10-15T00:00:00Z", "SOL-PERPETUAL"), None);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_experiments_are_rejected() {
+    let invalid = [
+        json!({ "enabled": true }),
+        json!({ "enabled": true, "variants": [{ "id": "" }] }),
+        json!({ "enabled": true, "variants": [{ "id": "a" }, { "id": "a", "spread": 30.0 }] }),
+        json!({ "enabled": true, "slice_sec": 1, "variants": [{ "id": "a" }, { "id": "b" }] }),
+        json!({ "enabled": true, "assignment": "instrument", "variants": [
+            { "id": "a", "instruments": ["BTC-PERPETUAL"] }, { "id": "b", "instruments": ["BTC-PERPETUAL"] }
+        ] }),
+    ];
+    for config in invalid {
+        assert!(experiment(config.clone()).is_err(), "accepted {}", config);
+    }
+}
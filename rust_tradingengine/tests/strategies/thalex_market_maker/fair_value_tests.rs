@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use tokio::sync::Notify;
+
+use cryptics_lab_bot::strategies::thalex_market_maker::{price_at, ExternalFairValue, MarketDataManager};
+
+#[test]
+fn test_external_fair_value_goes_stale() {
+    let mut fair_value = ExternalFairValue::new(3.0);
+    assert_eq!(fair_value.fresh(100.0), None);
+    
+    assert!(fair_value.update(65010.0, 100.0));
+    assert_eq!(fair_value.fresh(102.5), Some(65010.0));
+    assert_eq!(fair_value.fresh(103.5), None);
+    assert_eq!(fair_value.latest(), Some((65010.0, 100.0)));
+}
+
+#[test]
+fn test_invalid_and_out_of_order_values_ignored() {
+    let mut fair_value = ExternalFairValue::new(3.0);
+    assert!(fair_value.update(65010.0, 100.0));
+    
+    assert!(!fair_value.update(f64::NAN, 101.0));
+    assert!(!fair_value.update(-1.0, 101.0));
+    assert!(!fair_value.update(64000.0, 99.0));
+    assert_eq!(fair_value.fresh(101.0), Some(65010.0));
+}
+
+#[test]
+fn test_price_read_from_dotted_path() {
+    let message = json!({"result": {"mid": "65012.5", "bid": 65012.0}, "price": 1.0, "venue": "x"});
+    
+    assert_eq!(price_at(&message, "price"), Some(1.0));
+    assert_eq!(price_at(&message, "result.mid"), Some(65012.5));
+    assert_eq!(price_at(&message, "result.bid"), Some(65012.0));
+    assert_eq!(price_at(&message, "result.ask"), None);
+    assert_eq!(price_at(&message, "venue"), None);
+}
+
+#[tokio::test]
+async fn test_quotes_fall_back_to_index_when_feed_is_stale() {
+    let market_data = MarketDataManager::new(Arc::new(Notify::new()), None);
+    market_data.handle_index(65000.0).await.unwrap();
+    
+    // Without an external feed the index is the fair value
+    assert_eq!(market_data.fair_value_at(100.0).await, Some(65000.0));
+    
+    market_data.enable_external_fair_value(3.0).await;
+    assert_eq!(market_data.fair_value_at(100.0).await, Some(65000.0));
+    
+    market_data.set_external_fair_value(65020.0, 100.0).await;
+    assert_eq!(market_data.fair_value_at(101.0).await, Some(65020.0));
+    assert_eq!(market_data.fair_value_at(104.0).await, Some(65000.0));
+    
+    market_data.set_external_fair_value(65030.0, 104.0).await;
+    assert_eq!(market_data.fair_value_at(104.5).await, Some(65030.0));
+}
@@ -0,0 +1,81 @@
+use cryptics_lab_bot::domain::model::book::{BookLevelUpdate, BookSide, BookUpdateKind, OrderBook};
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::strategies::thalex_market_maker::FeatureEngine;
+
+fn make_ticker(bid: f64, bid_amount: f64, ask: f64, ask_amount: f64) -> Ticker {
+    let mut ticker = Ticker::new("BTC-PERPETUAL".to_string());
+    ticker.best_bid_price = bid;
+    ticker.best_bid_amount = bid_amount;
+    ticker.best_ask_price = ask;
+    ticker.best_ask_amount = ask_amount;
+    ticker.mark_price = 50100.0;
+    ticker.index_price = 50000.0;
+    ticker.funding_rate = 0.0001;
+    ticker
+}
+
+#[test]
+fn test_features_none_without_ticker() {
+    let mut engine = FeatureEngine::new(60.0);
+    assert!(engine.compute(1000.0).is_none());
+}
+
+#[test]
+fn test_features_from_ticker() {
+    let mut engine = FeatureEngine::new(60.0);
+    engine.on_ticker(&make_ticker(49990.0, 3.0, 50010.0, 1.0), 1000.0);
+    
+    let features = engine.compute(1000.0).expect("features should be available");
+    
+    assert_eq!(features.instrument_name, "BTC-PERPETUAL");
+    assert_eq!(features.imbalance, 0.5);
+    assert_eq!(features.spread, 20.0);
+    assert!((features.funding_basis - 0.002).abs() < 1e-12);
+    assert_eq!(features.funding_rate, 0.0001);
+    assert_eq!(features.realized_vol, 0.0);
+    assert_eq!(features.trade_intensity, 0.0);
+}
+
+#[test]
+fn test_book_replaces_ticker_top_level() {
+    let mut engine = FeatureEngine::new(60.0).with_book_levels(2);
+    engine.on_ticker(&make_ticker(49990.0, 3.0, 50010.0, 1.0), 1000.0);
+    
+    let mut book = OrderBook::new("BTC-PERPETUAL");
+    for (side, price, amount) in [(BookSide::Bid, 49995.0, 1.0), (BookSide::Bid, 49994.0, 1.0), (BookSide::Ask, 50005.0, 2.0), (BookSide::Ask, 50006.0, 2.0)] {
+        book.apply(&BookLevelUpdate {
+            instrument_name: "BTC-PERPETUAL".to_string(),
+            kind: BookUpdateKind::Snapshot,
+            sequence: 1,
+            side,
+            price,
+            amount,
+            exchange_time: None,
+            timestamp: 1000.0,
+            processing_timestamp: None,
+        });
+    }
+    engine.on_book(&book);
+    
+    let features = engine.compute(1000.0).expect("features should be available");
+    assert_eq!(features.spread, 10.0);
+    assert!((features.imbalance + 1.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_features_rolling_window() {
+    let mut engine = FeatureEngine::new(10.0);
+    engine.on_ticker(&make_ticker(99.0, 1.0, 101.0, 1.0), 0.0);
+    engine.on_ticker(&make_ticker(109.0, 1.0, 111.0, 1.0), 1.0);
+    engine.on_trade(1.0);
+    engine.on_trade(2.0);
+    
+    let features = engine.compute(2.0).unwrap();
+    assert!((features.realized_vol - (110.0f64 / 100.0).ln()).abs() < 1e-12);
+    assert_eq!(features.trade_intensity, 0.2);
+    
+    // Everything falls out of the window after it elapses
+    let features = engine.compute(20.0).unwrap();
+    assert_eq!(features.realized_vol, 0.0);
+    assert_eq!(features.trade_intensity, 0.0);
+}
@@ -0,0 +1,25 @@
+use cryptics_lab_bot::config_loader::FeeConfig;
+use cryptics_lab_bot::strategies::thalex_market_maker::FeeSchedule;
+
+#[test]
+fn test_from_config_converts_bps() {
+    let fees = FeeSchedule::from_config(&FeeConfig { maker_fee_bps: -1.0, taker_fee_bps: 5.0 });
+    assert!((fees.maker_rate + 0.0001).abs() < 1e-12);
+    assert!((fees.taker_rate - 0.0005).abs() < 1e-12);
+}
+
+#[test]
+fn test_rebate_makes_any_spread_profitable() {
+    let fees = FeeSchedule { maker_rate: -0.0001, taker_rate: 0.0005 };
+    assert_eq!(fees.min_profitable_half_spread(50_000.0), 0.0);
+    assert!((fees.maker_rebate(50_000.0) - 5.0).abs() < 1e-9);
+    assert!((fees.maker_edge(2.0, 50_000.0) - 7.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_maker_fee_sets_minimum_spread() {
+    let fees = FeeSchedule { maker_rate: 0.0002, taker_rate: 0.0005 };
+    assert!((fees.min_profitable_half_spread(50_000.0) - 10.0).abs() < 1e-9);
+    assert!(fees.maker_edge(5.0, 50_000.0) < 0.0);
+    assert!(fees.maker_edge(15.0, 50_000.0) > 0.0);
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+use cryptics_lab_bot::strategies::thalex_market_maker::{positions_hash, HeartbeatTracker};
+
+/// 2026-10-15T00:00:00Z
+const DAY_START: f64 = 1_792_022_400.0;
+
+#[test]
+fn test_heartbeat_reports_last_notification_times() {
+    let mut tracker = HeartbeatTracker::new();
+    tracker.record(&"ticker.BTC-PERPETUAL.1000ms".parse::<Channel>().unwrap(), DAY_START + 1.0);
+    tracker.record(&Channel::Orders, DAY_START + 2.0);
+    tracker.record(&"ticker.BTC-PERPETUAL.1000ms".parse::<Channel>().unwrap(), DAY_START + 3.0);
+    tracker.record(&Channel::Portfolio, DAY_START + 4.0);
+    
+    let heartbeat = tracker.heartbeat("instance", 7, &HashMap::new(), DAY_START + 5.0);
+    assert_eq!(heartbeat.instance_id, "instance");
+    assert_eq!(heartbeat.sequence, 7);
+    assert_eq!(heartbeat.last_ticker_time, Some(DAY_START + 3.0));
+    assert_eq!(heartbeat.last_order_time, Some(DAY_START + 2.0));
+    assert_eq!(heartbeat.last_index_time, None);
+    assert_eq!(heartbeat.last_trade_time, None);
+    assert_eq!(heartbeat.timestamp, DAY_START + 5.0);
+}
+
+#[test]
+fn test_positions_hash_ignores_map_order() {
+    let a = HashMap::from([
+        ("BTC-PERPETUAL".to_string(), 0.4),
+        ("ETH-PERPETUAL".to_string(), -2.0),
+    ]);
+    let b = HashMap::from([
+        ("ETH-PERPETUAL".to_string(), -2.0),
+        ("BTC-PERPETUAL".to_string(), 0.4),
+    ]);
+    assert_eq!(positions_hash(&a), positions_hash(&b));
+    assert_eq!(positions_hash(&a).len(), 16);
+}
+
+#[test]
+fn test_positions_hash_changes_with_positions() {
+    let flat = HashMap::from([("BTC-PERPETUAL".to_string(), 0.0)]);
+    let long = HashMap::from([("BTC-PERPETUAL".to_string(), 0.2)]);
+    assert_ne!(positions_hash(&flat), positions_hash(&long));
+    assert_ne!(positions_hash(&flat), positions_hash(&HashMap::new()));
+}
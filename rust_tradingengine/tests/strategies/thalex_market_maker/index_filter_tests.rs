@@ -0,0 +1,41 @@
+use cryptics_lab_bot::strategies::thalex_market_maker::IndexFilter;
+
+#[test]
+fn test_first_price_and_small_moves_accepted() {
+    let mut filter = IndexFilter::new(0.01);
+    assert_eq!(filter.filter(50000.0), Some(50000.0));
+    assert_eq!(filter.filter(50200.0), Some(50200.0));
+    assert_eq!(filter.last(), Some(50200.0));
+}
+
+#[test]
+fn test_single_spike_rejected() {
+    let mut filter = IndexFilter::new(0.01);
+    filter.filter(50000.0);
+    
+    // Bad print followed by a return to normal
+    assert_eq!(filter.filter(60000.0), None);
+    assert_eq!(filter.filter(50010.0), Some(50010.0));
+    assert_eq!(filter.last(), Some(50010.0));
+}
+
+#[test]
+fn test_confirmed_jump_accepted() {
+    let mut filter = IndexFilter::new(0.01);
+    filter.filter(50000.0);
+    
+    assert_eq!(filter.filter(55000.0), None);
+    assert_eq!(filter.filter(55100.0), Some(55100.0));
+    
+    // The new level is the reference from now on
+    assert_eq!(filter.filter(55200.0), Some(55200.0));
+}
+
+#[test]
+fn test_invalid_prices_rejected() {
+    let mut filter = IndexFilter::new(0.01);
+    assert_eq!(filter.filter(0.0), None);
+    assert_eq!(filter.filter(-1.0), None);
+    assert_eq!(filter.filter(f64::NAN), None);
+    assert_eq!(filter.last(), None);
+}
@@ -0,0 +1,116 @@
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use cryptics_lab_bot::domain::enums::OrderSide;
+use cryptics_lab_bot::infrastructure::exchange::thalex::models::OrderResult;
+use cryptics_lab_bot::strategies::thalex_market_maker::{
+    is_own_label, order_label, reconcile, tag_of, variant_of_label, InflightInsert, InflightOrders,
+};
+
+fn insert(client_order_id: u64, level: usize) -> InflightInsert {
+    InflightInsert {
+        tag: Uuid::new_v4(),
+        client_order_id,
+        instrument_name: "BTC-PERPETUAL".to_string(),
+        direction: OrderSide::Buy,
+        level,
+        price: 49_975.0,
+        amount: 0.2,
+        sent_at: 1_792_022_400.0,
+        variant_id: None,
+    }
+}
+
+fn open_order(client_order_id: u64, label: Value) -> OrderResult {
+    serde_json::from_value(json!({
+        "order_id": format!("o{}", client_order_id),
+        "client_order_id": client_order_id,
+        "instrument_name": "BTC-PERPETUAL",
+        "direction": "buy",
+        "price": 49_975.0,
+        "amount": 0.2,
+        "filled_amount": 0.0,
+        "remaining_amount": 0.2,
+        "label": label,
+        "status": "open",
+    })).unwrap()
+}
+
+#[test]
+fn test_labels_carry_the_tag() {
+    let tag = Uuid::new_v4();
+    let label = order_label(&tag, None);
+    assert!(label.starts_with("P:"));
+    assert_eq!(tag_of(&label), Some(tag));
+    
+    // The plain label is still ours, but carries no tag
+    assert!(is_own_label(&label) && is_own_label("P"));
+    assert_eq!(tag_of("P"), None);
+    assert!(!is_own_label("manual") && !is_own_label("P:not-a-uuid"));
+}
+
+#[test]
+fn test_labels_carry_the_variant() {
+    let tag = Uuid::new_v4();
+    let label = order_label(&tag, Some("tight"));
+    assert_eq!(tag_of(&label), Some(tag));
+    assert_eq!(variant_of_label(&label), Some("tight"));
+    assert!(is_own_label(&label));
+    
+    assert_eq!(variant_of_label(&order_label(&tag, None)), None);
+    assert_eq!(variant_of_label("P"), None);
+    assert_eq!(variant_of_label("P:not-a-uuid:tight"), None);
+}
+
+#[test]
+fn test_acknowledged_inserts_leave_the_journal() {
+    let mut inflight = InflightOrders::new();
+    inflight.record(insert(100, 0));
+    inflight.record(insert(101, 1));
+    assert_eq!(inflight.next_client_order_id(), Some(102));
+    
+    assert_eq!(inflight.acknowledge(100).map(|i| i.level), Some(0));
+    assert!(inflight.acknowledge(100).is_none());
+    assert!(inflight.get(101).is_some());
+    assert_eq!(inflight.take_all().len(), 1);
+    assert!(inflight.is_empty() && inflight.next_client_order_id().is_none());
+}
+
+#[tokio::test]
+async fn test_journal_survives_a_restart() {
+    let path = std::env::temp_dir()
+        .join(format!("inflight_tests_{}", Uuid::new_v4()))
+        .join("inflight_orders.json");
+    let mut inflight = InflightOrders::load(&path).unwrap();
+    assert!(inflight.is_empty());
+    
+    // Saved in the background, the latest state once written
+    let first = insert(100, 0);
+    inflight.record(first.clone());
+    inflight.record(insert(101, 1));
+    inflight.acknowledge(101);
+    inflight.flush().await;
+    
+    let reloaded = InflightOrders::load(&path).unwrap();
+    assert_eq!(reloaded.len(), 1);
+    assert_eq!(reloaded.get(100).map(|i| i.tag), Some(first.tag));
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_reconcile_matches_open_orders_by_tag() {
+    let arrived = insert(100, 0);
+    let lost = insert(101, 1);
+    let open = vec![
+        open_order(7, json!("P")),
+        open_order(100, json!(arrived.label())),
+        open_order(8, Value::Null),
+    ];
+    
+    let reconciliation = reconcile(vec![arrived.clone(), lost.clone()], &open);
+    assert_eq!(reconciliation.adopted.len(), 1);
+    assert_eq!(reconciliation.adopted[0].0.tag, arrived.tag);
+    assert_eq!(reconciliation.adopted[0].1.order_id.as_deref(), Some("o100"));
+    assert_eq!(reconciliation.missing.len(), 1);
+    assert_eq!(reconciliation.missing[0].tag, lost.tag);
+}
@@ -0,0 +1,92 @@
+use cryptics_lab_bot::config_loader::LadderShape;
+use cryptics_lab_bot::strategies::thalex_market_maker::LadderBuilder;
+
+fn prices(quotes: &[cryptics_lab_bot::domain::model::quote::SideQuote]) -> Vec<f64> {
+    quotes.iter().map(|q| q.price).collect()
+}
+
+#[test]
+fn test_default_matches_linear_constants() {
+    let quotes = LadderBuilder::default().build(50_000.0, 25.0, 1.0, 1.0);
+    assert_eq!(prices(&quotes[0]), vec![49_975.0, 49_970.0]);
+    assert_eq!(prices(&quotes[1]), vec![50_025.0, 50_030.0]);
+    assert_eq!(quotes[1][1].amount, 0.4);
+}
+
+#[test]
+fn test_geometric_spacing() {
+    let ladder = LadderBuilder::new(
+        &LadderShape::Geometric { step: 4.0, ratio: 2.0 },
+        &[0.1, 0.2, 0.3, 0.4],
+        &[0.1, 0.2],
+    ).unwrap();
+    assert_eq!(ladder.levels(), (4, 2));
+    
+    let quotes = ladder.build(1_000.0, 10.0, 0.5, 1.0);
+    // Gaps of 4, 8 and 16 ticks
+    assert_eq!(prices(&quotes[0]), vec![995.0, 993.0, 989.0, 981.0]);
+    assert_eq!(prices(&quotes[1]), vec![1_005.0, 1_007.0]);
+}
+
+#[test]
+fn test_custom_offsets_and_size_multiplier() {
+    let ladder = LadderBuilder::new(
+        &LadderShape::Custom { offsets: vec![0.0, 3.0, 10.0] },
+        &[0.2, 0.5, 1.0],
+        &[0.2, 0.5, 1.0],
+    ).unwrap();
+    
+    let quotes = ladder.build(100.0, 2.0, 1.0, 0.5);
+    assert_eq!(prices(&quotes[0]), vec![98.0, 95.0, 88.0]);
+    assert_eq!(prices(&quotes[1]), vec![102.0, 105.0, 112.0]);
+    assert_eq!(quotes[0].iter().map(|q| q.amount).collect::<Vec<_>>(), vec![0.1, 0.25, 0.5]);
+}
+
+#[test]
+fn test_prices_rounded_to_tick() {
+    let ladder = LadderBuilder::new(&LadderShape::Linear { step: 1.5 }, &[0.1, 0.1], &[0.1]).unwrap();
+    let quotes = ladder.build(100.2, 1.0, 1.0, 1.0);
+    assert_eq!(prices(&quotes[0]), vec![99.0, 98.0]);
+    assert_eq!(prices(&quotes[1]), vec![101.0]);
+}
+
+#[test]
+fn test_invalid_ladders_rejected() {
+    // Fewer offsets than levels
+    assert!(LadderBuilder::new(&LadderShape::Custom { offsets: vec![0.0] }, &[0.1, 0.1], &[0.1]).is_err());
+    // Offsets must increase
+    assert!(LadderBuilder::new(&LadderShape::Custom { offsets: vec![0.0, 5.0, 5.0] }, &[0.1; 3], &[0.1]).is_err());
+    assert!(LadderBuilder::new(&LadderShape::Linear { step: 0.0 }, &[0.1, 0.1], &[0.1]).is_err());
+    assert!(LadderBuilder::new(&LadderShape::Geometric { step: 5.0, ratio: -1.0 }, &[0.1], &[0.1]).is_err());
+    assert!(LadderBuilder::new(&LadderShape::Linear { step: 5.0 }, &[0.0], &[0.1]).is_err());
+}
+
+#[test]
+fn test_template_matches_build() {
+    let ladder = LadderBuilder::new(
+        &LadderShape::Geometric { step: 4.0, ratio: 1.5 },
+        &[0.1, 0.2, 0.3],
+        &[0.1, 0.2],
+    ).unwrap();
+    let mut template = ladder.template(10.0, 0.5, 0);
+    for (center, multiplier) in [(1_000.0, 1.5), (1_000.0, 1.5), (1_000.0, 0.75), (1_000.3, 0.75), (999.75, 1.5), (1_234.56, 2.0)] {
+        let built = ladder.build(center, 10.0, 0.5, multiplier);
+        let templated = template.quotes(center, multiplier);
+        for (built, templated) in built.iter().zip(&templated) {
+            assert_eq!(prices(built), prices(templated));
+            let sizes = |quotes: &[cryptics_lab_bot::domain::model::quote::SideQuote]| quotes.iter().map(|q| q.amount).collect::<Vec<_>>();
+            assert_eq!(sizes(built), sizes(templated));
+        }
+    }
+}
+
+#[test]
+fn test_template_matches_its_inputs_only() {
+    let ladder = LadderBuilder::default();
+    let template = ladder.template(25.0, 1.0, 3);
+    assert!(template.matches(3, 25.0, 1.0));
+    assert!(!template.matches(3, 20.0, 1.0));
+    assert!(!template.matches(3, 25.0, 0.5));
+    // A replaced ladder comes with the next version
+    assert!(!template.matches(4, 25.0, 1.0));
+}
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+use cryptics_lab_bot::strategies::thalex_market_maker::MarketDataManager;
+use cryptics_lab_bot::testing::fixtures;
+
+const PERP: &str = "BTC-PERPETUAL";
+const FUTURE: &str = "BTC-27DEC24";
+
+async fn setup() -> MarketDataManager {
+    let market_data = MarketDataManager::new(Arc::new(Notify::new()), None);
+    market_data.set_instrument_info(PERP.to_string(), 1.0).await.unwrap();
+    market_data
+}
+
+async fn send_ticker(market_data: &MarketDataManager, ticker: &Ticker) -> Result<()> {
+    let frame = fixtures::ticker_notification(ticker);
+    market_data.handle_ticker(&Channel::ticker(&ticker.instrument_name), &frame["notification"]).await
+}
+
+#[tokio::test]
+async fn test_tickers_kept_per_instrument() -> Result<()> {
+    let market_data = setup().await;
+    market_data.track_instrument(FUTURE, 5.0).await;
+    
+    send_ticker(&market_data, &fixtures::ticker(PERP)).await?;
+    send_ticker(&market_data, &Ticker { mark_price: 66_000.0, ..fixtures::ticker(FUTURE) }).await?;
+    
+    assert_eq!(market_data.ticker().await.unwrap().mark_price, fixtures::MARK);
+    assert_eq!(market_data.ticker_of(FUTURE).await.unwrap().mark_price, 66_000.0);
+    assert_eq!(market_data.tick().await, Some(1.0));
+    assert_eq!(market_data.tick_of(FUTURE).await, Some(5.0));
+    assert_eq!(market_data.tracked_instruments().await, vec![FUTURE.to_string(), PERP.to_string()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_each_instrument_notifies_separately() -> Result<()> {
+    let market_data = setup().await;
+    let future_notify = market_data.track_instrument(FUTURE, 5.0).await;
+    
+    send_ticker(&market_data, &fixtures::ticker(FUTURE)).await?;
+    tokio::time::timeout(Duration::from_millis(100), future_notify.notified()).await?;
+    // Another instrument's ticker doesn't wake the quoter
+    assert!(tokio::time::timeout(Duration::from_millis(20), market_data.quote_notify.notified()).await.is_err());
+    
+    send_ticker(&market_data, &fixtures::ticker(PERP)).await?;
+    tokio::time::timeout(Duration::from_millis(100), market_data.quote_notify.notified()).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_untracked_tickers_not_kept() -> Result<()> {
+    let market_data = setup().await;
+    send_ticker(&market_data, &fixtures::ticker(FUTURE)).await?;
+    assert!(market_data.ticker_of(FUTURE).await.is_none());
+    
+    market_data.track_instrument(FUTURE, 5.0).await;
+    send_ticker(&market_data, &fixtures::ticker(FUTURE)).await?;
+    assert!(market_data.untrack_instrument(FUTURE).await);
+    assert!(market_data.ticker_of(FUTURE).await.is_none());
+    // The quoted instrument can't be untracked
+    assert!(!market_data.untrack_instrument(PERP).await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tracked_instruments_subscribed() -> Result<()> {
+    let market_data = setup().await;
+    market_data.track_instrument(FUTURE, 5.0).await;
+    let channels = market_data.get_public_channels().await?;
+    assert_eq!(channels[0], Channel::ticker(PERP));
+    assert!(channels.contains(&Channel::ticker(FUTURE)));
+    assert_eq!(channels.iter().filter(|channel| **channel == Channel::ticker(PERP)).count(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_books_kept_per_channel() -> Result<()> {
+    let market_data = setup().await;
+    let deep = Channel::book(PERP, 5);
+    let top = Channel::book(PERP, 1);
+    market_data.set_book_channels(vec![deep.clone(), top.clone()]).await;
+    assert_eq!(market_data.quote_book_channel().await, Some(deep.clone()));
+    send_ticker(&market_data, &fixtures::ticker(PERP)).await?;
+    
+    market_data.handle_book(&deep, &fixtures::book_json(&[(100.0, 1.0), (99.0, 2.0)], &[(101.0, 1.0), (102.0, 3.0)])).await?;
+    market_data.handle_book(&top, &fixtures::book_json(&[(100.0, 1.0)], &[(104.0, 1.0)])).await?;
+    
+    // The top-of-book channel neither truncates the deeper book nor drives the features
+    assert_eq!(market_data.order_book(&deep).await.unwrap().bids.len(), 2);
+    assert_eq!(market_data.order_book(&top).await.unwrap().bids.len(), 1);
+    assert_eq!(market_data.compute_features().await.unwrap().spread, 1.0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quote_book_subscribed_once() -> Result<()> {
+    let market_data = setup().await;
+    let books = |channels: &[Channel]| channels.iter().filter(|channel| matches!(channel, Channel::Book { .. })).count();
+    assert_eq!(books(&market_data.get_public_channels().await?), 1);
+    
+    market_data.set_book_channels(vec![Channel::book(PERP, 10)]).await;
+    let channels = market_data.get_public_channels().await?;
+    assert_eq!(books(&channels), 1);
+    assert!(channels.contains(&Channel::book(PERP, 10)));
+    Ok(())
+}
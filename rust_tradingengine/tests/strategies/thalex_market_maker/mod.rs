@@ -0,0 +1,35 @@
+//! Tests for Thalex market maker components
+
+// Import test modules
+pub mod amend_tests;
+pub mod book_recorder_tests;
+pub mod carry_tests;
+pub mod collar_tests;
+pub mod control_tests;
+pub mod daily_stats_tests;
+pub mod experiment_tests;
+pub mod fair_value_tests;
+pub mod features_tests;
+pub mod fees_tests;
+pub mod heartbeat_tests;
+pub mod index_filter_tests;
+pub mod inflight_tests;
+pub mod ladder_tests;
+pub mod market_data_tests;
+pub mod notification_handler_tests;
+pub mod order_manager_tests;
+pub mod publish_queue_tests;
+pub mod quote_orders_tests;
+pub mod quoter_tests;
+pub mod risk_tests;
+pub mod router_tests;
+pub mod scheduler_tests;
+pub mod session_summary_tests;
+pub mod side_budget_tests;
+pub mod sizing_tests;
+pub mod spread_tests;
+pub mod staleness_tests;
+pub mod subscriptions_tests;
+pub mod ticker_delta_tests;
+pub mod ticker_sampler_tests;
+pub mod uptime_tests;
@@ -0,0 +1,252 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex, Notify};
+
+use cryptics_lab_bot::domain::clock::{self, ManualClock};
+use cryptics_lab_bot::domain::model::quote::SideQuote;
+use cryptics_lab_bot::infrastructure::exchange::thalex::calls::RpcMethod;
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+use cryptics_lab_bot::infrastructure::exchange::thalex::client::ThalexClient;
+use cryptics_lab_bot::infrastructure::exchange::thalex::outbound::DEFAULT_OUTBOUND_MAX_AGE;
+use cryptics_lab_bot::strategies::thalex_market_maker::{
+    MarketDataManager, NotificationHandler, NotificationPlugin, OrderManager, RawChannelPublisher, SubscriptionState,
+    FEATURES_WINDOW_SEC,
+};
+use cryptics_lab_bot::testing::fixtures;
+
+/// Plugin that counts the notifications it receives for a single channel
+struct CountingPlugin {
+    channel: Channel,
+    count: AtomicUsize,
+    fail: bool,
+}
+
+#[async_trait]
+impl NotificationPlugin for CountingPlugin {
+    fn name(&self) -> &str {
+        "counting"
+    }
+
+    fn handles(&self, channel: &Channel) -> bool {
+        *channel == self.channel
+    }
+
+    async fn on_notification(&self, _channel: &Channel, _notification: &Value) -> Result<()> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        if self.fail {
+            return Err(anyhow!("plugin failure"));
+        }
+        Ok(())
+    }
+}
+
+fn make_handler() -> NotificationHandler {
+    let client = Arc::new(Mutex::new(ThalexClient::new()));
+    let market_data = Arc::new(MarketDataManager::new(Arc::new(Notify::new()), None));
+    let order_manager = Arc::new(OrderManager::new(client, market_data.clone(), None));
+    NotificationHandler::new(market_data, order_manager)
+}
+
+/// Trade history notification without trades
+fn no_trades() -> Value {
+    fixtures::trades_notification(&[])["notification"].clone()
+}
+
+#[tokio::test]
+async fn test_plugin_receives_matching_channel_only() -> Result<()> {
+    let handler = make_handler();
+    let plugin = Arc::new(CountingPlugin { channel: Channel::Trades, count: AtomicUsize::new(0), fail: false });
+    handler.register_plugin(plugin.clone()).await;
+    handler.add_subscriptions(&[Channel::Trades, Channel::Portfolio]).await;
+    
+    handler.handle_notification("account.trade_history", &no_trades()).await?;
+    handler.handle_notification("account.portfolio", &json!([])).await?;
+    
+    assert_eq!(plugin.count.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_plugin_skipped_for_unsubscribed_channel() -> Result<()> {
+    let handler = make_handler();
+    let plugin = Arc::new(CountingPlugin { channel: Channel::Trades, count: AtomicUsize::new(0), fail: false });
+    handler.register_plugin(plugin.clone()).await;
+    
+    handler.handle_notification("account.trade_history", &no_trades()).await?;
+    
+    assert_eq!(plugin.count.load(Ordering::SeqCst), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_plugin_errors_do_not_propagate() -> Result<()> {
+    let handler = make_handler();
+    let plugin = Arc::new(CountingPlugin { channel: Channel::Trades, count: AtomicUsize::new(0), fail: true });
+    handler.register_plugin(plugin.clone()).await;
+    handler.add_subscriptions(&[Channel::Trades]).await;
+    
+    handler.handle_notification("account.trade_history", &no_trades()).await?;
+    assert_eq!(plugin.count.load(Ordering::SeqCst), 1);
+    
+    assert!(handler.unregister_plugin("counting").await);
+    handler.handle_notification("account.trade_history", &no_trades()).await?;
+    assert_eq!(plugin.count.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_channels_left_out_of_a_subscribe_result_are_retried() -> Result<()> {
+    let handler = make_handler();
+    let ticker: Channel = "ticker.BTC-PERPETUAL.1000ms".parse()?;
+    let index = Channel::Index("BTCUSD".to_string());
+    handler.subscription_states.write().await.require(&[ticker.clone(), index.clone()]);
+    handler.subscription_states.write().await.requested(&[ticker.clone(), index.clone()]);
+    
+    let cid = handler.calls.allocate(RpcMethod::PublicSubscribe, Some(format!("{},{}", ticker, index)));
+    handler.result_callback(&json!([index.to_string()]), cid).await?;
+    
+    let states = handler.subscription_states.read().await;
+    assert_eq!(states.state(&index), Some(SubscriptionState::Confirmed));
+    assert!(matches!(states.state(&ticker), Some(SubscriptionState::Failed { attempts: 1, .. })));
+    drop(states);
+    assert!(!handler.subscriptions_ready().await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rejected_subscribe_call_fails_all_its_channels() -> Result<()> {
+    let handler = make_handler();
+    handler.subscription_states.write().await.require(&[Channel::Orders]);
+    handler.subscription_states.write().await.requested(&[Channel::Orders, Channel::Trades]);
+    
+    let cid = handler.calls.allocate(RpcMethod::PrivateSubscribe, Some("session.orders,account.trade_history".to_string()));
+    handler.error_callback(&fixtures::rpc_error(cid, 1, "not logged in")["error"], cid).await?;
+    
+    let states = handler.subscription_states.read().await;
+    assert!(matches!(states.state(&Channel::Orders), Some(SubscriptionState::Failed { .. })));
+    assert!(matches!(states.state(&Channel::Trades), Some(SubscriptionState::Failed { .. })));
+    assert_eq!(states.unconfirmed(), vec![Channel::Orders]);
+    Ok(())
+}
+
+/// Plugin that collects the notifications of unknown channels
+#[derive(Default)]
+struct FallbackPlugin {
+    received: std::sync::Mutex<Vec<(String, Value)>>,
+}
+
+#[async_trait]
+impl NotificationPlugin for FallbackPlugin {
+    fn name(&self) -> &str {
+        "fallback"
+    }
+
+    fn handles(&self, _channel: &Channel) -> bool {
+        false
+    }
+
+    async fn on_notification(&self, _channel: &Channel, _notification: &Value) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_unknown_channel(&self, channel_name: &str, notification: &Value) -> Result<()> {
+        self.received.lock().unwrap().push((channel_name.to_string(), notification.clone()));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_public_trades_feed_trade_intensity() -> Result<()> {
+    let handler = make_handler();
+    handler.market_data.set_instrument_info("BTC-PERPETUAL".to_string(), 1.0).await?;
+    let channels = handler.market_data.get_public_channels().await?;
+    assert!(channels.contains(&Channel::recent_trades("BTC-PERPETUAL")));
+    handler.add_subscriptions(&channels).await;
+    handler.add_subscriptions(&[Channel::recent_trades("ETH-PERPETUAL")]).await;
+
+    let ticker = fixtures::ticker("BTC-PERPETUAL");
+    handler.handle_notification("ticker.BTC-PERPETUAL.raw", &fixtures::ticker_notification(&ticker)["notification"]).await?;
+    let trades = [fixtures::trade(1, 49_990.0, 0.2), fixtures::trade(2, 50_010.0, 0.1)];
+    let frame = fixtures::recent_trades_notification("BTC-PERPETUAL", &trades);
+    handler.handle_notification(frame["channel_name"].as_str().unwrap(), &frame["notification"]).await?;
+    // Other instruments' trades don't count
+    let frame = fixtures::recent_trades_notification("ETH-PERPETUAL", &trades);
+    handler.handle_notification(frame["channel_name"].as_str().unwrap(), &frame["notification"]).await?;
+    // Nor do the account's own trades
+    handler.add_subscriptions(&[Channel::Trades]).await;
+    handler.handle_notification("account.trade_history", &fixtures::trades_notification(&trades)["notification"]).await?;
+
+    let features = handler.market_data.compute_features().await.expect("features once a ticker arrived");
+    assert_eq!(features.trade_intensity, 2.0 / FEATURES_WINDOW_SEC);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unknown_channel_routed_to_fallback() -> Result<()> {
+    let handler = make_handler();
+    let fallback = Arc::new(FallbackPlugin::default());
+    handler.register_plugin(fallback.clone()).await;
+    handler.add_subscriptions(&[Channel::Trades]).await;
+    
+    handler.handle_notification("account.new_channel", &json!({"x": 1})).await?;
+    handler.handle_notification("account.new_channel", &json!({"x": 2})).await?;
+    handler.handle_notification("account.trade_history", &no_trades()).await?;
+    
+    let received = fallback.received.lock().unwrap().clone();
+    assert_eq!(received, vec![
+        ("account.new_channel".to_string(), json!({"x": 1})),
+        ("account.new_channel".to_string(), json!({"x": 2})),
+    ]);
+    assert_eq!(handler.unknown_channels.read().await["account.new_channel"], 2);
+    Ok(())
+}
+
+#[test]
+fn test_raw_channel_record_keeps_notification() {
+    let record = RawChannelPublisher::record("account.new_channel", &json!({"x": 1}), 1700000000.5);
+    assert_eq!(record, json!({
+        "channel_name": "account.new_channel",
+        "received_at": 1700000000.5,
+        "notification": {"x": 1},
+    }));
+}
+
+#[tokio::test]
+async fn test_expired_insert_is_rejected_and_its_level_quoted_again() -> Result<()> {
+    let manual = Arc::new(ManualClock::new(1792022400.0));
+    let _clock = clock::scoped(manual.clone());
+    let (rejection_tx, mut rejection_rx) = mpsc::unbounded_channel();
+    let mut client = ThalexClient::new();
+    client.set_rejections(rejection_tx);
+    let calls = client.calls();
+    let client = Arc::new(Mutex::new(client));
+    let market_data = Arc::new(MarketDataManager::new(Arc::new(Notify::new()), None));
+    market_data.set_instrument_info("BTC-PERPETUAL".to_string(), 1.0).await?;
+    *market_data.index_price.write().await = Some(50_000.0);
+    let mut order_manager = OrderManager::new(client.clone(), market_data.clone(), None);
+    order_manager.calls = calls;
+    let order_manager = Arc::new(order_manager);
+    let handler = NotificationHandler::new(market_data, order_manager.clone());
+    let desired = vec![vec![SideQuote::new(49_975.0, 0.2)], vec![]];
+
+    // Disconnected: the insert waits in the queue until it goes stale
+    order_manager.adjust_quotes(desired.clone()).await?;
+    assert_eq!(client.lock().await.outbound_depth(), 1);
+    manual.advance(DEFAULT_OUTBOUND_MAX_AGE + Duration::from_millis(1));
+    client.lock().await.flush().await?;
+    assert_eq!(client.lock().await.outbound_depth(), 0);
+
+    // Its rejection reaches the response handler like the venue's would
+    let rejection: Value = serde_json::from_str(&rejection_rx.try_recv()?)?;
+    handler.error_callback(&rejection["error"], rejection["id"].as_u64().unwrap()).await?;
+    assert_eq!(handler.calls.outstanding(), 0);
+
+    // The level is free again, so the next cycle quotes it anew
+    order_manager.adjust_quotes(desired).await?;
+    assert_eq!(client.lock().await.outbound_depth(), 1);
+    Ok(())
+}
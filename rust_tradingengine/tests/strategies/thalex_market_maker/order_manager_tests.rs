@@ -0,0 +1,1074 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+
+use cryptics_lab_bot::config_loader::{LadderShape, RiskLimitsConfig};
+use cryptics_lab_bot::domain::enums::OrderSide;
+use cryptics_lab_bot::domain::model::account_summary::{AccountSummary, Balance};
+use cryptics_lab_bot::domain::model::exchange::{Instrument, OrderRequest};
+use cryptics_lab_bot::domain::model::order::OrderState;
+use cryptics_lab_bot::domain::model::quote::{MassQuote, SideQuote};
+use cryptics_lab_bot::infrastructure::exchange::OrderGateway;
+use cryptics_lab_bot::infrastructure::exchange::thalex::calls::CallRegistry;
+use cryptics_lab_bot::infrastructure::exchange::thalex::models::{OrderResult, PortfolioEntry};
+use cryptics_lab_bot::reporting::Journal;
+use cryptics_lab_bot::strategies::thalex_market_maker::{
+    day_of, tag_of, AmendPolicy, InflightOrders, LadderBuilder, MarketDataManager, MassQuoteStrategy, OrderManager, SideBudget, SizeScaler, SpreadLegs, LABEL,
+};
+
+const INDEX: f64 = 50_000.0;
+const TICK: f64 = 1.0;
+
+/// Order request the strategy sent to the fake venue
+#[derive(Clone, Debug, PartialEq)]
+enum Call {
+    Insert { cid: u64, side: &'static str, price: f64, amount: f64 },
+    Amend { cid: u64, price: f64, amount: f64 },
+    Cancel { cid: u64 },
+    CancelSession,
+    /// Sides of a mass quote as (price, amount)
+    MassQuote { bid: Option<(f64, f64)>, ask: Option<(f64, f64)>, post_only: bool },
+    OpenOrders,
+    AccountSummary,
+    Portfolio,
+}
+
+/// Venue-side view of one order
+#[derive(Clone, Debug)]
+struct VenueOrder {
+    side: &'static str,
+    price: f64,
+    amount: f64,
+    filled: f64,
+    status: &'static str,
+    label: Option<String>,
+}
+
+/// Deterministic venue: records requests and scripts the order notifications they produce
+#[derive(Default)]
+struct ScriptedExchange {
+    calls: Vec<Call>,
+    orders: BTreeMap<u64, VenueOrder>,
+    /// Orders changed since the last notification
+    changed: Vec<u64>,
+    reject_inserts: bool,
+    /// Answers every request right away through the registry when set
+    answers: Option<Arc<CallRegistry>>,
+    /// Answer requests with an error instead of a result
+    refuse: bool,
+}
+
+impl ScriptedExchange {
+    /// Notification acknowledging everything requested since the last one
+    fn ack(&mut self) -> Value {
+        let changed: Vec<u64> = std::mem::take(&mut self.changed);
+        Value::Array(changed.into_iter().map(|cid| self.notification(cid)).collect())
+    }
+
+    /// Notification for a fill of `amount` on order `cid`
+    fn fill(&mut self, cid: u64, amount: f64) -> Value {
+        let order = self.orders.get_mut(&cid).expect("unknown order");
+        order.filled += amount;
+        order.status = if order.filled >= order.amount { "filled" } else { "partially_filled" };
+        json!([self.notification(cid)])
+    }
+
+    fn notification(&self, cid: u64) -> Value {
+        let order = &self.orders[&cid];
+        json!({
+            "client_order_id": cid,
+            "instrument_name": "BTC-PERPETUAL",
+            "direction": order.side,
+            "price": order.price,
+            "amount": order.amount,
+            "filled_amount": order.filled,
+            "remaining_amount": order.amount - order.filled,
+            "label": order.label,
+            "status": order.status,
+        })
+    }
+
+    fn take_calls(&mut self) -> Vec<Call> {
+        std::mem::take(&mut self.calls)
+    }
+
+    /// Answer request `id` as the response handler would
+    fn answer(&self, id: Option<u64>) {
+        if let (Some(calls), Some(id)) = (&self.answers, id) {
+            if self.refuse {
+                calls.resolve(id, Err(&json!({"code": 1, "message": "refused"})));
+            } else {
+                calls.resolve(id, Ok(&json!({})));
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl OrderGateway for ScriptedExchange {
+    async fn insert(&mut self, order: OrderRequest, id: Option<u64>) -> Result<()> {
+        if self.reject_inserts {
+            return Err(anyhow!("insert rejected"));
+        }
+        let cid = order.client_order_id.unwrap();
+        // Market orders carry no price
+        let price = order.price.unwrap_or_default();
+        let side = match order.side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        };
+        self.calls.push(Call::Insert { cid, side, price, amount: order.quantity });
+        self.orders.insert(cid, VenueOrder { side, price, amount: order.quantity, filled: 0.0, status: "open", label: order.label });
+        self.changed.push(cid);
+        self.answer(id);
+        Ok(())
+    }
+
+    async fn amend(
+        &mut self,
+        amount: f64,
+        price: f64,
+        _order_id: Option<String>,
+        client_order_id: Option<u64>,
+        _id: Option<u64>,
+    ) -> Result<()> {
+        let cid = client_order_id.unwrap();
+        self.calls.push(Call::Amend { cid, price, amount });
+        let order = self.orders.get_mut(&cid).expect("amend of unknown order");
+        // Like the venue, the amount is the new total and the filled part counts towards it
+        order.price = price;
+        order.amount = amount;
+        self.changed.push(cid);
+        Ok(())
+    }
+
+    async fn cancel(&mut self, _order_id: Option<String>, client_order_id: Option<u64>, id: Option<u64>) -> Result<()> {
+        let cid = client_order_id.unwrap();
+        self.calls.push(Call::Cancel { cid });
+        self.answer(id);
+        if self.refuse {
+            return Ok(());
+        }
+        self.orders.get_mut(&cid).expect("cancel of unknown order").status = "cancelled";
+        self.changed.push(cid);
+        Ok(())
+    }
+
+    async fn open_orders(&mut self, _id: Option<u64>) -> Result<()> {
+        self.calls.push(Call::OpenOrders);
+        Ok(())
+    }
+
+    async fn account_summary(&mut self, _id: Option<u64>) -> Result<()> {
+        self.calls.push(Call::AccountSummary);
+        Ok(())
+    }
+
+    async fn portfolio(&mut self, _id: Option<u64>) -> Result<()> {
+        self.calls.push(Call::Portfolio);
+        Ok(())
+    }
+
+    async fn cancel_session(&mut self, id: Option<u64>) -> Result<()> {
+        self.calls.push(Call::CancelSession);
+        self.answer(id);
+        let cancelled: Vec<u64> = self.orders.iter_mut()
+            .filter(|(_, order)| order.status == "open")
+            .map(|(cid, order)| {
+                order.status = "cancelled";
+                *cid
+            })
+            .collect();
+        self.changed.extend(cancelled);
+        Ok(())
+    }
+
+    async fn mass_quote(&mut self, quotes: Vec<MassQuote>, label: Option<String>, post_only: bool, id: Option<u64>) -> Result<()> {
+        assert_eq!(label.as_deref(), Some(LABEL));
+        let level = |side: &Option<SideQuote>| side.as_ref().map(|level| (level.price, level.amount));
+        for quote in quotes {
+            self.calls.push(Call::MassQuote { bid: level(&quote.bid), ask: level(&quote.ask), post_only });
+        }
+        self.answer(id);
+        Ok(())
+    }
+}
+
+async fn setup() -> (Arc<Mutex<ScriptedExchange>>, OrderManager) {
+    let exchange = Arc::new(Mutex::new(ScriptedExchange::default()));
+    let market_data = Arc::new(MarketDataManager::new(Arc::new(Notify::new()), None));
+    market_data.set_instrument_info("BTC-PERPETUAL".to_string(), TICK).await.unwrap();
+    *market_data.index_price.write().await = Some(INDEX);
+    let order_manager = OrderManager::new(exchange.clone(), market_data, None);
+    (exchange, order_manager)
+}
+
+fn quotes(bid: f64, ask: f64, amount: f64) -> Vec<Vec<SideQuote>> {
+    vec![
+        vec![SideQuote::new(bid, amount), SideQuote::new(bid - 5.0, amount)],
+        vec![SideQuote::new(ask, amount), SideQuote::new(ask + 5.0, amount)],
+    ]
+}
+
+/// Quote, then feed the venue's acks back
+async fn quote_and_ack(exchange: &Mutex<ScriptedExchange>, om: &OrderManager, desired: Vec<Vec<SideQuote>>) -> Result<Vec<Call>> {
+    om.adjust_quotes(desired).await?;
+    let ack = exchange.lock().await.ack();
+    om.handle_orders(&ack).await?;
+    Ok(exchange.lock().await.take_calls())
+}
+
+#[tokio::test]
+async fn test_make_quotes_around_index() -> Result<()> {
+    let (_, om) = setup().await;
+    let quotes = om.make_quotes().await?;
+    assert_eq!(quotes[0][0].price, INDEX - 25.0);
+    assert_eq!(quotes[1][0].price, INDEX + 25.0);
+    assert_eq!(quotes[0][1].price, INDEX - 30.0);
+    assert_eq!(quotes[1][1].amount, 0.4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_make_quotes_scaled_by_equity() -> Result<()> {
+    let (_, om) = setup().await;
+    // No volatility observed yet: the floor applies
+    *om.sizing.write().await = Some(SizeScaler::new(0.001, 0.001, 0.1, 2.0)?);
+    om.risk.write().await.set_equity(5_000.0);
+    
+    // 5k * 0.1% / (50k * 0.1%) = 0.1 contracts on the first level, half the ladder size
+    let quotes = om.make_quotes().await?;
+    assert_eq!(quotes[0][0].amount, 0.1);
+    assert_eq!(quotes[1][1].amount, 0.2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_make_quotes_follow_size_and_ladder_changes() -> Result<()> {
+    let (_, om) = setup().await;
+    om.make_quotes().await?;
+
+    // A new size multiplier reuses the cached layout
+    om.params.write().await.size_multiplier = 0.5;
+    assert_eq!(om.make_quotes().await?[1][1].amount, 0.2);
+
+    // A replaced ladder is laid out again
+    om.set_ladder(LadderBuilder::new(&LadderShape::Linear { step: 10.0 }, &[1.0], &[1.0])?).await;
+    let quotes = om.make_quotes().await?;
+    assert_eq!(quotes[1].len(), 1);
+    assert_eq!(quotes[1][0].amount, 0.5);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_paused_params_pull_all_quotes() -> Result<()> {
+    let (_, om) = setup().await;
+    om.params.write().await.paused = true;
+    let quotes = om.make_quotes().await?;
+    assert!(quotes.iter().all(Vec::is_empty));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_paused_instrument_pulls_quotes_until_resumed() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, om.make_quotes().await?).await?;
+    
+    // Pausing another instrument leaves this one quoting
+    om.pause("ETH-PERPETUAL").await;
+    assert!(!om.make_quotes().await?[0].is_empty());
+    
+    om.pause("BTC-PERPETUAL").await;
+    let calls = quote_and_ack(&exchange, &om, om.make_quotes().await?).await?;
+    assert_eq!(calls.len(), 4);
+    assert!(calls.iter().all(|call| matches!(call, Call::Cancel { .. })));
+    
+    om.resume("BTC-PERPETUAL").await;
+    assert_eq!(om.make_quotes().await?[1].len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_disabled_instrument_cancels_quotes_until_enabled() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, om.make_quotes().await?).await?;
+
+    assert!(om.disable("BTC-PERPETUAL").await);
+    assert!(!om.disable("BTC-PERPETUAL").await);
+    assert!(om.make_quotes().await?.iter().all(Vec::is_empty));
+    om.cancel_quotes().await?;
+    let calls = exchange.lock().await.take_calls();
+    assert_eq!(calls.len(), 4);
+    assert!(calls.iter().all(|call| matches!(call, Call::Cancel { .. })));
+
+    // Confirmed cancels release every level, so enabling starts from fresh orders
+    let ack = exchange.lock().await.ack();
+    om.handle_orders(&ack).await?;
+    assert!(om.orders.read().await.iter().all(Vec::is_empty));
+    assert!(om.enable("BTC-PERPETUAL").await);
+    let calls = quote_and_ack(&exchange, &om, om.make_quotes().await?).await?;
+    assert_eq!(calls.len(), 4);
+    assert!(calls.iter().all(|call| matches!(call, Call::Insert { .. })));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pre_trade_check_holds_back_orders_beyond_limits() -> Result<()> {
+    let (exchange, om) = setup().await;
+    om.risk.write().await.set_limits(&RiskLimitsConfig { max_order_notional: Some(15_000.0), ..Default::default() })?;
+
+    // 0.4 at 50k is 20k notional: only the 0.2 levels go out
+    let desired = vec![
+        vec![SideQuote::new(49_975.0, 0.2), SideQuote::new(49_970.0, 0.4)],
+        vec![SideQuote::new(50_025.0, 0.2), SideQuote::new(50_030.0, 0.4)],
+    ];
+    let calls = quote_and_ack(&exchange, &om, desired).await?;
+    assert_eq!(calls, vec![
+        Call::Insert { cid: 100, side: "buy", price: 49_975.0, amount: 0.2 },
+        Call::Insert { cid: 101, side: "sell", price: 50_025.0, amount: 0.2 },
+    ]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancelling_an_instrument_cancels_its_unacknowledged_inserts() -> Result<()> {
+    let (exchange, om) = setup().await;
+    om.adjust_quotes(om.make_quotes().await?).await?;
+    exchange.lock().await.take_calls();
+    
+    // Other instruments' quotes are left alone
+    assert_eq!(om.cancel_orders(Some("ETH-PERPETUAL")).await?, 0);
+    assert!(exchange.lock().await.take_calls().is_empty());
+    
+    // The inserts were sent but not acknowledged yet: each is cancelled by its client order id
+    assert_eq!(om.cancel_orders(Some("BTC-PERPETUAL")).await?, 4);
+    let calls = exchange.lock().await.take_calls();
+    assert_eq!(calls.len(), 4);
+    assert!(calls.iter().all(|call| matches!(call, Call::Cancel { .. })));
+    assert!(om.orders.read().await.iter().flatten().all(|order| order.state == OrderState::Cancelling));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flatten_closes_position_at_market() -> Result<()> {
+    let (exchange, om) = setup().await;
+    assert_eq!(om.flatten("BTC-PERPETUAL").await?, None);
+    
+    om.handle_portfolio(&json!([{ "instrument_name": "BTC-PERPETUAL", "position": 0.3 }])).await?;
+    assert_eq!(om.flatten("BTC-PERPETUAL").await?, Some(0.3));
+    assert_eq!(exchange.lock().await.take_calls(), vec![
+        Call::Insert { cid: 100, side: "sell", price: 0.0, amount: 0.3 },
+    ]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_option_orders_conformed_to_ticks() -> Result<()> {
+    let (exchange, om) = setup().await;
+    let option: Instrument = serde_json::from_value(json!({
+        "instrument_name": "BTC-25DEC37-100000-C", "type": "option", "underlying": "BTCUSD",
+        "tick_size": 5.0, "volume_tick_size": 0.01,
+    }))?;
+    om.market_data.set_options(vec![option.with_name_details()?]).await;
+    exchange.lock().await.answers = Some(om.calls.clone());
+    
+    assert_eq!(om.insert_option_order("BTC-25DEC37-100000-C", OrderSide::Buy, 1_233.0, 0.057).await?, 100);
+    assert!(om.insert_option_order("BTC-25DEC37-90000-C", OrderSide::Buy, 1_233.0, 0.05).await.is_err());
+    assert!(om.insert_option_order("BTC-25DEC37-100000-C", OrderSide::Sell, 1_233.0, 0.001).await.is_err());
+    
+    let calls = exchange.lock().await.take_calls();
+    assert_eq!(calls.len(), 1);
+    let Call::Insert { cid, side, price, amount } = calls[0] else {
+        panic!("expected an insert");
+    };
+    assert_eq!((cid, side, price), (100, "buy", 1_235.0));
+    assert!((amount - 0.05).abs() < 1e-12);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_option_order_waits_for_the_exchange_answer() -> Result<()> {
+    let (exchange, om) = setup().await;
+    let option: Instrument = serde_json::from_value(json!({
+        "instrument_name": "BTC-25DEC37-100000-C", "type": "option", "underlying": "BTCUSD",
+        "tick_size": 5.0, "volume_tick_size": 0.01,
+    }))?;
+    om.market_data.set_options(vec![option.with_name_details()?]).await;
+    {
+        let mut exchange = exchange.lock().await;
+        exchange.answers = Some(om.calls.clone());
+        exchange.refuse = true;
+    }
+
+    let error = om.insert_option_order("BTC-25DEC37-100000-C", OrderSide::Buy, 1_235.0, 0.05).await.unwrap_err();
+    assert!(error.to_string().contains("refused"), "{}", error);
+    assert_eq!(om.calls.outstanding(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancel_order_returns_the_exchange_answer() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    exchange.lock().await.answers = Some(om.calls.clone());
+
+    om.cancel_order(100).await?;
+    assert_eq!(om.orders.read().await.get(100).map(|order| order.state), Some(OrderState::Cancelling));
+
+    exchange.lock().await.refuse = true;
+    assert!(om.cancel_order(101).await.is_err());
+    assert_eq!(exchange.lock().await.take_calls(), vec![Call::Cancel { cid: 100 }, Call::Cancel { cid: 101 }]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancel_side_pulls_only_our_orders_of_one_side() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    
+    assert_eq!(om.cancel_side(OrderSide::Buy).await?, 2);
+    assert_eq!(om.orders.read().await.get(100).map(|order| order.state), Some(OrderState::Cancelling));
+    assert_eq!(om.orders.read().await.get(102).map(|order| order.state), Some(OrderState::Acknowledged));
+    assert_eq!(exchange.lock().await.take_calls(), vec![Call::Cancel { cid: 100 }, Call::Cancel { cid: 101 }]);
+    
+    // Confirmed cancels free the bid levels; the asks keep resting
+    let ack = exchange.lock().await.ack();
+    om.handle_orders(&ack).await?;
+    assert!(om.orders.read().await[0].is_empty());
+    assert_eq!(om.orders.read().await[1].len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rejected_side_cancel_leaves_orders_resting() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    
+    om.cancel_side(OrderSide::Sell).await?;
+    om.cancel_rejected(102).await;
+    assert_eq!(om.orders.read().await.get(102).map(|order| order.state), Some(OrderState::Acknowledged));
+    assert_eq!(om.orders.read().await.get(103).map(|order| order.state), Some(OrderState::Cancelling));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_position_at_the_limit_pulls_the_growing_side() -> Result<()> {
+    let (exchange, om) = setup().await;
+    om.risk.write().await.set_limits(&RiskLimitsConfig { max_position_notional: Some(25_000.0), ..Default::default() })?;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    
+    // Below the limit of 0.5 nothing is pulled
+    om.handle_portfolio(&json!([{"instrument_name": "BTC-PERPETUAL", "position": 0.3}])).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+    
+    om.handle_portfolio(&json!([{"instrument_name": "BTC-PERPETUAL", "position": 0.5}])).await?;
+    assert_eq!(exchange.lock().await.take_calls(), vec![Call::Cancel { cid: 100 }, Call::Cancel { cid: 101 }]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_initial_quotes_insert_every_level() -> Result<()> {
+    let (exchange, om) = setup().await;
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert_eq!(calls, vec![
+        Call::Insert { cid: 100, side: "buy", price: 49_975.0, amount: 0.2 },
+        Call::Insert { cid: 101, side: "buy", price: 49_970.0, amount: 0.2 },
+        Call::Insert { cid: 102, side: "sell", price: 50_025.0, amount: 0.2 },
+        Call::Insert { cid: 103, side: "sell", price: 50_030.0, amount: 0.2 },
+    ]);
+    assert!(om.is_two_sided_within(INDEX, 30.0).await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mass_quote_sends_the_best_level_and_orders_the_rest() -> Result<()> {
+    let (exchange, om) = setup().await;
+    *om.mass_quote.write().await = Some(MassQuoteStrategy::new(true));
+    
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert_eq!(calls, vec![
+        Call::MassQuote { bid: Some((49_975.0, 0.2)), ask: Some((50_025.0, 0.2)), post_only: true },
+        Call::Insert { cid: 100, side: "buy", price: 49_970.0, amount: 0.2 },
+        Call::Insert { cid: 101, side: "sell", price: 50_030.0, amount: 0.2 },
+    ]);
+    assert!(om.orders.read().await.iter().all(|side| side.len() == 1));
+    assert!(om.is_two_sided_within(INDEX, 30.0).await);
+    
+    // Moves within the amend thresholds leave the resting quotes alone
+    om.adjust_quotes(quotes(49_973.0, 50_027.0, 0.2)).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+    
+    om.adjust_quotes(quotes(49_950.0, 50_050.0, 0.2)).await?;
+    let calls = exchange.lock().await.take_calls();
+    assert_eq!(calls[0], Call::MassQuote { bid: Some((49_950.0, 0.2)), ask: Some((50_050.0, 0.2)), post_only: true });
+    assert!(calls[1..].iter().all(|call| matches!(call, Call::Amend { .. })));
+    
+    // Pulling the quotes zeroes both sides of the mass quote and cancels the orders
+    om.cancel_quotes().await?;
+    assert_eq!(exchange.lock().await.take_calls(), vec![
+        Call::MassQuote { bid: Some((49_950.0, 0.0)), ask: Some((50_050.0, 0.0)), post_only: true },
+        Call::Cancel { cid: 100 },
+        Call::Cancel { cid: 101 },
+    ]);
+    assert!(!om.is_two_sided_within(INDEX, 30.0).await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_refused_mass_quote_is_sent_again() -> Result<()> {
+    let (exchange, om) = setup().await;
+    *om.mass_quote.write().await = Some(MassQuoteStrategy::new(false));
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    let calls = exchange.lock().await.take_calls();
+    assert_eq!(calls.iter().filter(|call| matches!(call, Call::MassQuote { .. })).count(), 1);
+    
+    om.mass_quote_rejected().await;
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert!(matches!(exchange.lock().await.take_calls()[..], [Call::MassQuote { post_only: false, .. }]));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unacked_orders_are_left_alone() -> Result<()> {
+    let (exchange, om) = setup().await;
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    exchange.lock().await.take_calls();
+
+    // No ack yet: a big move must neither duplicate nor amend in-flight orders
+    om.adjust_quotes(quotes(49_900.0, 50_100.0, 0.2)).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_amend_threshold() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+
+    // Within AMEND_THRESHOLD ticks: nothing to do
+    let calls = quote_and_ack(&exchange, &om, quotes(49_980.0, 50_030.0, 0.2)).await?;
+    assert!(calls.is_empty(), "{:?}", calls);
+
+    // Beyond the threshold: every level is amended in place
+    let calls = quote_and_ack(&exchange, &om, quotes(49_990.0, 50_040.0, 0.2)).await?;
+    assert_eq!(calls, vec![
+        Call::Amend { cid: 100, price: 49_990.0, amount: 0.2 },
+        Call::Amend { cid: 101, price: 49_985.0, amount: 0.2 },
+        Call::Amend { cid: 102, price: 50_040.0, amount: 0.2 },
+        Call::Amend { cid: 103, price: 50_045.0, amount: 0.2 },
+    ]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_smaller_size_amends_without_price_move() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.1)).await?;
+    assert_eq!(calls.len(), 4);
+    assert!(calls.iter().all(|c| matches!(c, Call::Amend { amount, .. } if *amount == 0.1)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_price_amend_keeps_partially_filled_size() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    let partial = exchange.lock().await.fill(100, 0.075);
+    om.handle_orders(&partial).await?;
+
+    // Re-pricing leaves the remaining 0.125 working instead of resetting it to 0.2: the
+    // amount sent is the total, the filled 0.075 included
+    let calls = quote_and_ack(&exchange, &om, quotes(49_990.0, 50_025.0, 0.2)).await?;
+    assert_eq!(calls, vec![
+        Call::Amend { cid: 100, price: 49_990.0, amount: 0.2 },
+        Call::Amend { cid: 101, price: 49_985.0, amount: 0.2 },
+    ]);
+    let orders = om.orders.read().await;
+    assert_eq!(orders[0][0].price, Some(49_990.0));
+    assert!((orders[0][0].remaining_amount - 0.125).abs() < 1e-9);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_size_amend_of_partially_filled_order_counts_the_fill() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    let partial = exchange.lock().await.fill(100, 0.075);
+    om.handle_orders(&partial).await?;
+
+    // Shrinking the remaining 0.125 to 0.1 sends the new total of 0.175
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.1)).await?;
+    assert_eq!(calls[0], Call::Amend { cid: 100, price: 49_975.0, amount: 0.175 });
+    let orders = om.orders.read().await;
+    assert!((orders[0][0].filled_amount - 0.075).abs() < 1e-9);
+    assert!((orders[0][0].remaining_amount - 0.1).abs() < 1e-9);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_per_level_amend_thresholds() -> Result<()> {
+    let (exchange, om) = setup().await;
+    *om.amend_policy.write().await = AmendPolicy::new(vec![5.0, 20.0], 0.0)?;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+
+    // A 10 tick move re-prices the top of book only
+    let calls = quote_and_ack(&exchange, &om, quotes(49_985.0, 50_035.0, 0.2)).await?;
+    assert_eq!(calls, vec![
+        Call::Amend { cid: 100, price: 49_985.0, amount: 0.2 },
+        Call::Amend { cid: 102, price: 50_035.0, amount: 0.2 },
+    ]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_size_tolerance() -> Result<()> {
+    let (exchange, om) = setup().await;
+    *om.amend_policy.write().await = AmendPolicy::new(vec![5.0], 0.25)?;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+
+    // 10% smaller: within tolerance
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.18)).await?;
+    assert!(calls.is_empty(), "{:?}", calls);
+
+    // 50% smaller: amended
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.1)).await?;
+    assert_eq!(calls.len(), 4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_excess_levels_are_cancelled() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+
+    let one_level = vec![vec![SideQuote::new(49_975.0, 0.2)], vec![SideQuote::new(50_025.0, 0.2)]];
+    let calls = quote_and_ack(&exchange, &om, one_level.clone()).await?;
+    assert_eq!(calls, vec![Call::Cancel { cid: 101 }, Call::Cancel { cid: 103 }]);
+
+    // Once the cancels are acked, nothing is cancelled twice
+    let calls = quote_and_ack(&exchange, &om, one_level).await?;
+    assert!(calls.is_empty(), "{:?}", calls);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancel_ack_frees_level_for_requote() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+
+    let one_level = vec![vec![SideQuote::new(49_975.0, 0.2)], vec![SideQuote::new(50_025.0, 0.2)]];
+    om.adjust_quotes(one_level.clone()).await?;
+    assert_eq!(exchange.lock().await.take_calls(), vec![Call::Cancel { cid: 101 }, Call::Cancel { cid: 103 }]);
+
+    // While the cancels are in flight the levels are neither cancelled again nor reused
+    om.adjust_quotes(one_level).await?;
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+
+    // The acks free the levels and ask for a re-quote, which fills them straight away
+    let ack = exchange.lock().await.ack();
+    om.handle_orders(&ack).await?;
+    assert!(om.orders.read().await.iter().all(|side| side.len() == 1));
+    tokio::time::timeout(Duration::from_millis(100), om.market_data.quote_notify.notified()).await?;
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert_eq!(calls, vec![
+        Call::Insert { cid: 104, side: "buy", price: 49_970.0, amount: 0.2 },
+        Call::Insert { cid: 105, side: "sell", price: 50_030.0, amount: 0.2 },
+    ]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rejected_cancel_leaves_order_resting() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+
+    let one_level = vec![vec![SideQuote::new(49_975.0, 0.2)], vec![SideQuote::new(50_025.0, 0.2)]];
+    om.adjust_quotes(one_level.clone()).await?;
+    exchange.lock().await.take_calls();
+    om.cancel_rejected(101).await;
+    assert!(om.orders.read().await[0][1].is_open());
+
+    // The next adjustment tries again
+    om.adjust_quotes(one_level).await?;
+    assert_eq!(exchange.lock().await.take_calls(), vec![Call::Cancel { cid: 101 }]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_filled_level_is_replaced() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+
+    // A partial fill keeps the order working
+    let partial = exchange.lock().await.fill(100, 0.1);
+    om.handle_orders(&partial).await?;
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert!(calls.is_empty(), "{:?}", calls);
+
+    // A full fill frees the level for a fresh order
+    let filled = exchange.lock().await.fill(100, 0.1);
+    om.handle_orders(&filled).await?;
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert_eq!(calls, vec![Call::Insert { cid: 104, side: "buy", price: 49_975.0, amount: 0.2 }]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_order_ignores_unknown_and_malformed() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+
+    om.handle_orders(&json!([
+        {"client_order_id": 999, "price": 1.0, "remaining_amount": 1.0, "status": "filled"},
+        {"client_order_id": 100, "status": "filled"},
+        "not an order",
+    ])).await?;
+    om.handle_orders(&json!({"unexpected": "shape"})).await?;
+
+    let orders = om.orders.read().await;
+    assert!(orders.iter().flatten().all(|o| o.is_open()));
+    assert_eq!(orders[0][0].price, Some(49_975.0));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_insert_failure_propagates() -> Result<()> {
+    let (exchange, om) = setup().await;
+    exchange.lock().await.reject_inserts = true;
+    let err = om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await.unwrap_err();
+    assert!(err.to_string().contains("insert rejected"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pending_orders_wait_for_ack() -> Result<()> {
+    let (exchange, om) = setup().await;
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    exchange.lock().await.take_calls();
+
+    // Not acknowledged yet: neither re-inserted nor amended, and not counted as quoting
+    om.adjust_quotes(quotes(49_900.0, 50_100.0, 0.2)).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+    assert!(!om.is_two_sided_within(INDEX, 30.0).await);
+
+    let ack = exchange.lock().await.ack();
+    om.handle_orders(&ack).await?;
+    let orders = om.orders.read().await;
+    assert!(orders.iter().flatten().all(|o| o.state == OrderState::Acknowledged && o.is_open()));
+    assert!(matches!(orders[1][0].direction, OrderSide::Sell));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_inserts_are_tagged_until_acknowledged() -> Result<()> {
+    let (exchange, om) = setup().await;
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    
+    // Every insert is journaled under the tag the venue sees in its label
+    {
+        let inflight = om.inflight.read().await;
+        assert_eq!(inflight.len(), 4);
+        let venue = exchange.lock().await;
+        for (cid, order) in &venue.orders {
+            let tag = order.label.as_deref().and_then(tag_of).expect("untagged insert");
+            assert_eq!(inflight.get(*cid).map(|insert| insert.tag), Some(tag));
+        }
+    }
+    
+    let ack = exchange.lock().await.ack();
+    om.handle_orders(&ack).await?;
+    assert!(om.inflight.read().await.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_orders_carry_the_variant_they_were_placed_under() -> Result<()> {
+    let (exchange, om) = setup().await;
+    assert!(om.set_variant(Some("tight".to_string())).await);
+    assert!(!om.set_variant(Some("tight".to_string())).await);
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert!(om.inflight.read().await.get(100).is_some_and(|insert| insert.variant_id.as_deref() == Some("tight")));
+    
+    // Acks keep the tag although the venue doesn't report it
+    let ack = exchange.lock().await.ack();
+    om.handle_orders(&ack).await?;
+    assert!(om.orders.read().await.iter().flatten().all(|order| order.variant_id.as_deref() == Some("tight")));
+    
+    // Later inserts get the new variant, resting orders keep theirs
+    assert!(om.set_variant(Some("wide".to_string())).await);
+    om.adjust_quotes(vec![vec![SideQuote::new(49_975.0, 0.2)], vec![]]).await?;
+    let ack = exchange.lock().await.ack();
+    om.handle_orders(&ack).await?;
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    let orders = om.orders.read().await;
+    assert_eq!(orders.get(100).and_then(|order| order.variant_id.as_deref()), Some("tight"));
+    assert!(orders[1].iter().all(|order| order.variant_id.as_deref() == Some("wide")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_account_summary_sizes_quotes_by_remaining_margin() -> Result<()> {
+    let (exchange, om) = setup().await;
+    om.request_account_summary().await?;
+    assert_eq!(exchange.lock().await.take_calls(), vec![Call::AccountSummary]);
+    
+    // With no margin left nothing that grows the position is quoted
+    om.update_account(AccountSummary {
+        timestamp: 1792022400.0,
+        balances: vec![Balance { currency: "USDC".to_string(), balance: 1_000.0, collateral_factor: Some(1.0) }],
+        cash_collateral: 1_000.0,
+        unrealised_pnl: 0.0,
+        margin: 1_000.0,
+        required_margin: 1_000.0,
+        remaining_margin: 0.0,
+        session_realised_pnl: 0.0,
+        processing_timestamp: None,
+    }).await;
+    assert_eq!(om.risk.read().await.available_margin(), Some(0.0));
+    assert_eq!(om.account.read().await.as_ref().map(|account| account.margin_utilisation()), Some(1.0));
+    let constrained = om.risk.read().await.constrain_quotes(quotes(49_975.0, 50_025.0, 0.2), 0.0, INDEX);
+    assert!(constrained.iter().all(Vec::is_empty));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_positions_reconciled_before_quoting() -> Result<()> {
+    let (exchange, om) = setup().await;
+    om.portfolio.write().await.extend([("BTC-PERPETUAL".to_string(), 0.3), ("ETH-PERPETUAL".to_string(), 1.0)]);
+    om.request_positions().await?;
+    assert!(om.is_awaiting_positions());
+    assert_eq!(exchange.lock().await.take_calls(), vec![Call::Portfolio]);
+    
+    // Nothing is quoted until the positions are known
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+    
+    // The perpetual drifted and the ETH position was closed while the bot was away
+    let reported: Vec<PortfolioEntry> = serde_json::from_value(json!([
+        {"instrument_name": "BTC-PERPETUAL", "position": -0.1},
+        {"instrument_name": "BTC-27DEC24", "position": 0.5},
+    ]))?;
+    let drifted = om.reconcile_positions(&reported).await;
+    assert_eq!(drifted, vec!["BTC-27DEC24", "BTC-PERPETUAL", "ETH-PERPETUAL"]);
+    assert!(!om.is_awaiting_positions());
+    {
+        let portfolio = om.portfolio.read().await;
+        assert_eq!(portfolio.get("BTC-PERPETUAL"), Some(&-0.1));
+        assert_eq!(portfolio.get("ETH-PERPETUAL"), Some(&0.0));
+    }
+    
+    // Positions that agree aren't reported again
+    assert!(om.reconcile_positions(&reported).await.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reconnect_adopts_inserts_that_arrived() -> Result<()> {
+    let (exchange, om) = setup().await;
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    exchange.lock().await.take_calls();
+    
+    // The session drops before any ack; only the bid inserts made it to the venue
+    let mut journal = InflightOrders::new();
+    for insert in om.inflight.write().await.take_all() {
+        journal.record(insert);
+    }
+    let arrived: Vec<OrderResult> = {
+        let venue = exchange.lock().await;
+        venue.orders.iter()
+            .filter(|(_, order)| order.side == "buy")
+            .map(|(cid, _)| serde_json::from_value(venue.notification(*cid)).unwrap())
+            .collect()
+    };
+    
+    let market_data = om.market_data.clone();
+    let om = OrderManager::new(exchange.clone(), market_data, None);
+    om.set_inflight(journal).await;
+    assert!(om.request_reconcile().await?);
+    assert_eq!(exchange.lock().await.take_calls(), vec![Call::OpenOrders]);
+    
+    // Nothing is quoted until the open orders are known
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+    
+    om.reconcile_open_orders(&arrived).await;
+    assert!(!om.is_reconciling() && om.inflight.read().await.is_empty());
+    
+    // The adopted bids rest where they were; only the asks are inserted, under fresh ids
+    let calls = quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    assert_eq!(calls, vec![
+        Call::Insert { cid: 104, side: "sell", price: 50_025.0, amount: 0.2 },
+        Call::Insert { cid: 105, side: "sell", price: 50_030.0, amount: 0.2 },
+    ]);
+    let orders = om.orders.read().await;
+    assert_eq!(orders[0].iter().map(|o| o.client_order_id).collect::<Vec<_>>(), vec![Some(100), Some(101)]);
+    assert!(orders[0].iter().all(|o| o.is_open()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_lookup_resumes_quoting() -> Result<()> {
+    let (exchange, om) = setup().await;
+    assert!(!om.request_reconcile().await?);
+    
+    let mut journal = InflightOrders::new();
+    om.adjust_quotes(quotes(49_975.0, 50_025.0, 0.2)).await?;
+    for insert in om.inflight.write().await.take_all() {
+        journal.record(insert);
+    }
+    om.set_inflight(journal).await;
+    assert!(om.request_reconcile().await?);
+    om.reconcile_failed().await;
+    assert!(!om.is_reconciling() && om.inflight.read().await.is_empty());
+    exchange.lock().await.take_calls();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_one_sided_burst_leaves_other_side_updating() -> Result<()> {
+    let (exchange, om) = setup().await;
+    *om.side_budget.write().await = SideBudget::new(4, Duration::from_secs(60))?;
+    quote_and_ack(&exchange, &om, quotes(49_975.0, 50_025.0, 0.2)).await?;
+    
+    // Bids chase the market down until their allowance is spent
+    let calls = quote_and_ack(&exchange, &om, quotes(49_950.0, 50_025.0, 0.2)).await?;
+    assert_eq!(calls.len(), 2);
+    let calls = quote_and_ack(&exchange, &om, quotes(49_925.0, 50_025.0, 0.2)).await?;
+    assert!(calls.is_empty());
+    
+    // The asks' budget is untouched, so they follow while the bids wait
+    let calls = quote_and_ack(&exchange, &om, quotes(49_925.0, 50_050.0, 0.2)).await?;
+    assert_eq!(calls, vec![
+        Call::Amend { cid: 102, price: 50_050.0, amount: 0.2 },
+        Call::Amend { cid: 103, price: 50_055.0, amount: 0.2 },
+    ]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_liquidation_halts_and_pulls_quotes() -> Result<()> {
+    let (exchange, om) = setup().await;
+    quote_and_ack(&exchange, &om, om.make_quotes().await?).await?;
+    
+    // Deposits are only recorded
+    om.handle_account_events(&json!({"category": "deposit", "amount": 1.0, "currency": "BTC"})).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+    
+    // A message mentioning liquidation is only informational
+    om.handle_account_events(&json!({"category": "announcement", "message": "Liquidation engine upgrade"})).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+    assert!(om.risk.read().await.halt_reason().is_none());
+    
+    // Held quotes don't hold back the cancel: the whole session is cancelled at once
+    om.request_positions().await?;
+    exchange.lock().await.take_calls();
+    om.handle_account_events(&json!([{"category": "liquidation", "message": "Account liquidated"}])).await?;
+    assert_eq!(exchange.lock().await.take_calls(), vec![Call::CancelSession]);
+    assert!(om.risk.read().await.halt_reason().is_some_and(|reason| reason.contains("Account liquidated")));
+    let ack = exchange.lock().await.ack();
+    om.handle_orders(&ack).await?;
+    assert!(om.orders.read().await.iter().flatten().all(|order| order.is_closed()));
+    assert!(om.make_quotes().await?.iter().all(Vec::is_empty));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_busts_and_corrections_restate_the_day() -> Result<()> {
+    let (_exchange, om) = setup().await;
+    let dir = std::env::temp_dir().join(format!("om_corrections_{}", uuid::Uuid::new_v4()));
+    om.set_journal(Journal::new(&dir)).await;
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs_f64();
+    let trade = |id: &str, direction: &str, price: f64, amount: f64| json!({
+        "trade_id": id, "order_id": "O1", "instrument_name": "BTC-PERPETUAL", "label": LABEL,
+        "direction": direction, "price": price, "amount": amount, "time": now,
+    });
+    om.handle_trades(&json!([trade("T1", "buy", 50_000.0, 0.2), trade("T2", "sell", 50_010.0, 0.1)])).await?;
+    assert!((om.daily_stats.read().await.realized_pnl - 1.0).abs() < 1e-9);
+    
+    // The bust takes the sale out of the day, PnL included
+    om.handle_account_events(&json!({"category": "trade_bust", "trade_id": "T2"})).await?;
+    {
+        let stats = om.daily_stats.read().await;
+        assert_eq!((stats.fills, stats.position, stats.realized_pnl), (1, 0.2, 0.0));
+    }
+    
+    om.handle_account_events(&json!({"title": "Trade corrected", "trade_id": "T1", "new_price": 49_990.0})).await?;
+    let stats = om.daily_stats.read().await.clone();
+    assert_eq!((stats.fills, stats.position, stats.avg_price), (1, 0.2, 49_990.0));
+    
+    // A repeated bust finds no fill and changes nothing
+    om.handle_account_events(&json!({"category": "trade_bust", "trade_id": "T2", "direction": "sell", "price": 50_010.0, "amount": 0.1})).await?;
+    assert_eq!(*om.daily_stats.read().await, stats);
+    
+    let journal = Journal::new(&dir);
+    let corrections = journal.corrections(day_of(now))?;
+    assert_eq!(corrections.len(), 3);
+    assert!(matches!(corrections[0].direction, Some(OrderSide::Sell)));
+    assert_eq!(corrections[0].original_price, Some(50_010.0));
+    std::fs::remove_dir_all(dir).ok();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_funding_payments_add_up_in_the_carry() -> Result<()> {
+    let (exchange, om) = setup().await;
+    
+    om.handle_account_events(&json!({"category": "funding", "amount": 1.5, "instrument_name": "BTC-PERPETUAL"})).await?;
+    om.handle_account_events(&json!([{"type": "funding_payment", "amount": -0.5}])).await?;
+    // Another perpetual's funding isn't the quoted one's carry
+    om.handle_account_events(&json!({"category": "funding", "amount": 7.0, "instrument_name": "ETH-PERPETUAL"})).await?;
+    // Nor is a message about funding
+    om.handle_account_events(&json!({"category": "announcement", "message": "Funding of 3.0 paid", "amount": 3.0})).await?;
+    
+    assert!((om.carry.read().await.cumulative_funding() - 1.0).abs() < 1e-9);
+    assert!(exchange.lock().await.take_calls().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_spread_fill_hedged_on_other_leg() -> Result<()> {
+    let (exchange, om) = setup().await;
+    *om.spread.write().await = Some(SpreadLegs::new("BTC-27DEC24", 1.0, 0.05)?);
+    
+    om.handle_trades(&json!([{
+        "trade_id": "T1", "instrument_name": "BTC-PERPETUAL", "label": LABEL, "client_order_id": 7,
+        "direction": "buy", "price": 49_990.0, "amount": 0.2,
+    }])).await?;
+    assert_eq!(exchange.lock().await.take_calls(), vec![
+        Call::Insert { cid: 100, side: "sell", price: 0.0, amount: 0.2 },
+    ]);
+    {
+        let spread = om.spread.read().await;
+        let spread = spread.as_ref().unwrap();
+        assert_eq!(spread.linked(100).unwrap().quote_order_id, Some(7));
+        assert!((spread.leg_risk() - 0.2).abs() < 1e-12);
+    }
+    // Unhedged beyond the limit: no quotes
+    assert!(om.make_quotes().await?.iter().all(Vec::is_empty));
+    
+    // The IOC hedge fills partially; the remainder goes out again
+    let hedge = |cid: u64, amount: f64, filled: f64| json!([{
+        "client_order_id": cid, "instrument_name": "BTC-27DEC24", "direction": "sell", "amount": amount,
+        "filled_amount": filled, "remaining_amount": 0.0, "status": "cancelled", "order_type": "market",
+    }]);
+    om.handle_orders(&hedge(100, 0.2, 0.15)).await?;
+    let calls = exchange.lock().await.take_calls();
+    let [Call::Insert { cid: 101, side: "sell", amount, .. }] = calls.as_slice() else {
+        panic!("expected a retry, got {:?}", calls);
+    };
+    assert!((amount - 0.05).abs() < 1e-12);
+    
+    om.handle_orders(&hedge(101, 0.05, 0.05)).await?;
+    assert!(exchange.lock().await.take_calls().is_empty());
+    assert!(om.spread.read().await.as_ref().unwrap().leg_risk().abs() < 1e-12);
+    assert!(om.make_quotes().await?.iter().all(|side| !side.is_empty()));
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::Semaphore;
+
+use cryptics_lab_bot::strategies::thalex_market_maker::PublishQueues;
+
+type Record = (&'static str, u32);
+type Sent = Arc<Mutex<Vec<Record>>>;
+
+/// Queues recording what they send as `(key, record)`, after waiting a turn of `gate`
+fn recording(capacity: usize, gate: Arc<Semaphore>) -> (PublishQueues<&'static str, Record>, Sent) {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let log = sent.clone();
+    let queues = PublishQueues::new("test record", capacity, move |record: Record| {
+        let gate = gate.clone();
+        let log = log.clone();
+        async move {
+            gate.acquire().await?.forget();
+            log.lock().unwrap().push(record);
+            Ok::<(), anyhow::Error>(())
+        }
+    });
+    (queues, sent)
+}
+
+async fn wait_for(sent: &Mutex<Vec<Record>>, count: usize) -> Result<()> {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while sent.lock().unwrap().len() < count {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_records_of_a_key_sent_in_order() -> Result<()> {
+    let (mut queues, sent) = recording(16, Arc::new(Semaphore::new(Semaphore::MAX_PERMITS)));
+    for batch in 0..5 {
+        assert!(queues.push(&"BTC", vec![("BTC", batch * 2), ("BTC", batch * 2 + 1)]));
+        assert!(queues.push(&"ETH", vec![("ETH", batch)]));
+    }
+    assert_eq!(queues.len(), 2);
+    
+    wait_for(&sent, 15).await?;
+    let sent = sent.lock().unwrap();
+    let of = |key| sent.iter().filter(|(k, _)| *k == key).map(|(_, n)| *n).collect::<Vec<_>>();
+    assert_eq!(of("BTC"), (0..10).collect::<Vec<_>>());
+    assert_eq!(of("ETH"), (0..5).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_queue_refuses_batches() -> Result<()> {
+    let gate = Arc::new(Semaphore::new(0));
+    let (mut queues, sent) = recording(1, gate.clone());
+    
+    // The key's task hasn't run yet, so its single slot is taken by the first batch
+    assert!(queues.push(&"BTC", vec![("BTC", 1)]));
+    assert!(!queues.push(&"BTC", vec![("BTC", 2)]));
+    // Other keys have their own queue
+    assert!(queues.push(&"ETH", vec![("ETH", 1)]));
+    
+    gate.add_permits(2);
+    wait_for(&sent, 2).await?;
+    assert!(queues.push(&"BTC", vec![("BTC", 3)]));
+    gate.add_permits(1);
+    wait_for(&sent, 3).await?;
+    
+    let btc: Vec<u32> = sent.lock().unwrap().iter().filter(|(k, _)| *k == "BTC").map(|(_, n)| *n).collect();
+    assert_eq!(btc, vec![1, 3]);
+    Ok(())
+}
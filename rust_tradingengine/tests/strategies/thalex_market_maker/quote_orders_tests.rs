@@ -0,0 +1,85 @@
+use cryptics_lab_bot::domain::enums::{OrderSide, OrderStatus};
+use cryptics_lab_bot::domain::model::order::{Order, OrderState};
+use cryptics_lab_bot::strategies::thalex_market_maker::QuoteOrders;
+
+fn order(client_order_id: u64, direction: OrderSide, price: f64) -> Order {
+    Order::pending(client_order_id, "BTC-PERPETUAL".to_string(), direction, price, 0.1)
+}
+
+#[test]
+fn test_placed_orders_are_found_by_client_order_id() {
+    let mut orders = QuoteOrders::new(2);
+    orders.place(0, 0, order(100, OrderSide::Buy, 49_975.0));
+    orders.place(0, 1, order(101, OrderSide::Buy, 49_950.0));
+    orders.place(1, 0, order(102, OrderSide::Sell, 50_025.0));
+
+    assert_eq!(orders.locate(100), Some((0, 0)));
+    assert_eq!(orders.locate(101), Some((0, 1)));
+    assert_eq!(orders.locate(102), Some((1, 0)));
+    assert_eq!(orders.get(101).and_then(|o| o.price), Some(49_950.0));
+    assert_eq!(orders[1][0].client_order_id, Some(102));
+    assert!(orders.locate(103).is_none());
+}
+
+#[test]
+fn test_replacing_a_level_drops_the_old_order_from_the_index() {
+    let mut orders = QuoteOrders::new(2);
+    orders.place(0, 0, order(100, OrderSide::Buy, 49_975.0));
+    orders.place(0, 0, order(110, OrderSide::Buy, 49_970.0));
+
+    assert_eq!(orders[0].len(), 1);
+    assert!(orders.locate(100).is_none());
+    assert_eq!(orders.locate(110), Some((0, 0)));
+    assert!(!orders.update(&order(100, OrderSide::Buy, 1.0)));
+}
+
+#[test]
+fn test_levels_past_the_end_are_appended() {
+    let mut orders = QuoteOrders::new(2);
+    orders.place(1, 3, order(100, OrderSide::Sell, 50_025.0));
+    assert_eq!(orders[1].len(), 1);
+    assert_eq!(orders.locate(100), Some((1, 0)));
+}
+
+#[test]
+fn test_update_replaces_the_indexed_order() {
+    let mut orders = QuoteOrders::new(2);
+    orders.place(0, 0, order(100, OrderSide::Buy, 49_975.0));
+    orders.place(1, 0, order(101, OrderSide::Sell, 50_025.0));
+
+    let mut filled = order(101, OrderSide::Sell, 50_025.0);
+    filled.remaining_amount = 0.0;
+    assert!(orders.update(&filled));
+    assert_eq!(orders[1][0].remaining_amount, 0.0);
+    assert_eq!(orders[0][0].remaining_amount, 0.1);
+
+    let mut unknown = order(999, OrderSide::Buy, 1.0);
+    assert!(!orders.update(&unknown));
+    unknown.client_order_id = None;
+    assert!(!orders.update(&unknown));
+}
+
+#[test]
+fn test_closed_tail_is_released() {
+    let mut orders = QuoteOrders::new(2);
+    for (level, client_order_id) in [100, 101, 102].into_iter().enumerate() {
+        orders.place(0, level, order(client_order_id, OrderSide::Buy, 49_975.0 - level as f64));
+    }
+    for client_order_id in [100, 102] {
+        let mut closed = order(client_order_id, OrderSide::Buy, 1.0);
+        closed.state = OrderState::Acknowledged;
+        closed.status = OrderStatus::Cancelled;
+        assert!(orders.update(&closed));
+    }
+
+    // Only the trailing level goes; the closed top level stays for the next insert
+    assert_eq!(orders.release_closed_tail(0), 1);
+    assert_eq!(orders[0].len(), 2);
+    assert!(orders.locate(102).is_none());
+    assert_eq!(orders.locate(100), Some((0, 0)));
+    assert_eq!(orders.release_closed_tail(1), 0);
+
+    assert!(orders.set_state(101, OrderState::Cancelling));
+    assert!(!orders[0][1].is_open() && !orders[0][1].is_closed());
+    assert!(!orders.set_state(102, OrderState::Cancelling));
+}
@@ -0,0 +1,330 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use tokio::sync::{mpsc, Mutex, Notify};
+
+use cryptics_lab_bot::domain::model::bot_state::BotState;
+use cryptics_lab_bot::domain::model::exchange::OrderRequest;
+use cryptics_lab_bot::domain::model::quote::SideQuote;
+use cryptics_lab_bot::infrastructure::exchange::thalex::calls::{CallRegistry, RpcMethod};
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+use cryptics_lab_bot::infrastructure::exchange::thalex::client::ThalexClient;
+use cryptics_lab_bot::infrastructure::exchange::thalex::liveness::Liveness;
+use cryptics_lab_bot::infrastructure::exchange::thalex::session::{MessageReader, SessionClient};
+use cryptics_lab_bot::infrastructure::exchange::{ExchangeClient, OrderGateway, Venue};
+use cryptics_lab_bot::strategies::thalex_market_maker::{ControlCommand, SubscriptionState, ThalexQuoter};
+
+/// Quoter on a client that isn't connected; requests wait in its outbound queue
+async fn quoter() -> ThalexQuoter {
+    let quoter = ThalexQuoter::new(Arc::new(Mutex::new(ThalexClient::new())), None).await;
+    quoter.market_data.set_instrument_info("BTC-PERPETUAL".to_string(), 1.0).await.unwrap();
+    quoter
+}
+
+/// Client that refuses subscribe calls of the private or public channels once told to
+#[derive(Default)]
+struct RefusingClient {
+    inner: ThalexClient,
+    refuse_private: bool,
+    refuse_public: bool,
+}
+
+#[async_trait]
+impl OrderGateway for RefusingClient {
+    async fn insert(&mut self, order: OrderRequest, id: Option<u64>) -> Result<()> {
+        self.inner.insert(order, id).await
+    }
+
+    async fn amend(&mut self, amount: f64, price: f64, order_id: Option<String>, client_order_id: Option<u64>, id: Option<u64>) -> Result<()> {
+        OrderGateway::amend(&mut self.inner, amount, price, order_id, client_order_id, id).await
+    }
+
+    async fn cancel(&mut self, order_id: Option<String>, client_order_id: Option<u64>, id: Option<u64>) -> Result<()> {
+        OrderGateway::cancel(&mut self.inner, order_id, client_order_id, id).await
+    }
+
+    async fn open_orders(&mut self, id: Option<u64>) -> Result<()> {
+        OrderGateway::open_orders(&mut self.inner, id).await
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for RefusingClient {
+    fn venue(&self) -> Venue {
+        Venue::Thalex
+    }
+
+    async fn connect(&mut self, url: &str) -> Result<()> {
+        ExchangeClient::connect(&mut self.inner, url).await
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        ExchangeClient::disconnect(&mut self.inner).await
+    }
+
+    fn connected(&self) -> bool {
+        ExchangeClient::connected(&self.inner)
+    }
+
+    async fn login(&mut self, token: String, account: Option<String>, id: Option<u64>) -> Result<()> {
+        ExchangeClient::login(&mut self.inner, token, account, id).await
+    }
+
+    async fn subscribe(&mut self, channels: Vec<String>, private: bool, id: Option<u64>) -> Result<()> {
+        if (private && self.refuse_private) || (!private && self.refuse_public) {
+            return Err(anyhow!("subscribe refused"));
+        }
+        ExchangeClient::subscribe(&mut self.inner, channels, private, id).await
+    }
+
+    async fn unsubscribe(&mut self, channels: Vec<String>, id: Option<u64>) -> Result<()> {
+        ExchangeClient::unsubscribe(&mut self.inner, channels, id).await
+    }
+
+    async fn receive(&mut self) -> Result<Option<String>> {
+        ExchangeClient::receive(&mut self.inner).await
+    }
+
+    async fn cancel_all(&mut self, id: Option<u64>) -> Result<()> {
+        ExchangeClient::cancel_all(&mut self.inner, id).await
+    }
+}
+
+#[async_trait]
+impl SessionClient for RefusingClient {
+    fn calls(&self) -> Arc<CallRegistry> {
+        SessionClient::calls(&self.inner)
+    }
+
+    fn liveness(&self) -> Arc<Liveness> {
+        SessionClient::liveness(&self.inner)
+    }
+
+    async fn ping(&mut self) -> Result<()> {
+        SessionClient::ping(&mut self.inner).await
+    }
+
+    async fn instruments(&mut self, id: Option<u64>) -> Result<()> {
+        SessionClient::instruments(&mut self.inner, id).await
+    }
+
+    async fn set_cancel_on_disconnect(&mut self, timeout_secs: u64, id: Option<u64>) -> Result<()> {
+        SessionClient::set_cancel_on_disconnect(&mut self.inner, timeout_secs, id).await
+    }
+
+    fn set_rejections(&mut self, rejections: mpsc::UnboundedSender<String>) {
+        SessionClient::set_rejections(&mut self.inner, rejections)
+    }
+
+    fn take_reader(&mut self) -> Option<Box<dyn MessageReader>> {
+        SessionClient::take_reader(&mut self.inner)
+    }
+
+    async fn flush(&mut self) -> Result<usize> {
+        SessionClient::flush(&mut self.inner).await
+    }
+
+    fn outbound_depth(&self) -> usize {
+        SessionClient::outbound_depth(&self.inner)
+    }
+
+    fn outbound_ready(&self) -> Arc<Notify> {
+        SessionClient::outbound_ready(&self.inner)
+    }
+
+    fn next_flush(&mut self) -> Option<Instant> {
+        SessionClient::next_flush(&mut self.inner)
+    }
+
+    fn discard_outbound(&mut self) -> usize {
+        SessionClient::discard_outbound(&mut self.inner)
+    }
+}
+
+#[tokio::test]
+async fn test_failed_subscribe_keeps_active_channels_routed() -> Result<()> {
+    let client = Arc::new(Mutex::new(RefusingClient::default()));
+    let quoter = ThalexQuoter::new(client.clone(), None).await;
+    let btc = Channel::ticker("BTC-PERPETUAL");
+    let eth = Channel::ticker("ETH-PERPETUAL");
+    quoter.subscribe_channels(vec![btc.clone()]).await?;
+
+    // A retry of the active channel fails: it stays routed, the new one isn't
+    client.lock().await.refuse_public = true;
+    assert!(quoter.subscribe_channels(vec![btc.clone(), eth.clone()]).await.is_err());
+    assert_eq!(quoter.active_channels().await, vec![btc.clone()]);
+
+    // The private subscribe went out before the public one failed, so its channel stays routed
+    assert!(quoter.subscribe_channels(vec![Channel::Orders, eth.clone()]).await.is_err());
+    assert_eq!(quoter.active_channels().await, vec![Channel::Orders, btc.clone()]);
+    assert_eq!(quoter.subscriptions.channels(), vec![btc, Channel::Orders]);
+
+    // A refused private subscribe leaves the public half unsent too
+    let mut client = client.lock().await;
+    client.refuse_public = false;
+    client.refuse_private = true;
+    drop(client);
+    assert!(quoter.subscribe_channels(vec![Channel::Portfolio, eth]).await.is_err());
+    assert_eq!(quoter.active_channels().await, vec![Channel::Orders, Channel::ticker("BTC-PERPETUAL")]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_channels_at_runtime() -> Result<()> {
+    let quoter = quoter().await;
+    let ticker = Channel::ticker("ETH-PERPETUAL");
+    quoter.subscribe_channels(vec![Channel::Orders, ticker.clone()]).await?;
+    assert_eq!(quoter.active_channels().await, vec![Channel::Orders, ticker.clone()]);
+
+    // Private and public channels go in separate calls
+    let client = quoter.client.lock().await;
+    assert_eq!(client.outbound_depth(), 2);
+    assert_eq!(client.calls().get(1).map(|call| call.method), Some(RpcMethod::PrivateSubscribe));
+    assert_eq!(client.calls().get(2).map(|call| call.method), Some(RpcMethod::PublicSubscribe));
+    drop(client);
+
+    let states = quoter.notification_handler.subscription_states.read().await;
+    assert_eq!(states.state(&Channel::Orders), Some(SubscriptionState::Pending { attempts: 0 }));
+    assert_eq!(states.state(&ticker), Some(SubscriptionState::Pending { attempts: 0 }));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_duplicate_subscribe_keeps_one_channel() -> Result<()> {
+    let quoter = quoter().await;
+    let ticker = Channel::ticker("ETH-PERPETUAL");
+    quoter.subscribe_channels(vec![ticker.clone()]).await?;
+    quoter.subscribe_channels(vec![ticker.clone()]).await?;
+    assert_eq!(quoter.active_channels().await, vec![ticker.clone()]);
+    assert_eq!(quoter.subscriptions.channels(), vec![ticker.clone()]);
+
+    // A single unsubscribe removes it
+    quoter.unsubscribe_channels(vec![ticker]).await?;
+    assert!(quoter.active_channels().await.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unsubscribe_channels_at_runtime() -> Result<()> {
+    let quoter = quoter().await;
+    let eth = Channel::ticker("ETH-PERPETUAL");
+    let btc = Channel::ticker("BTC-PERPETUAL");
+    quoter.subscribe_channels(vec![eth.clone(), btc.clone()]).await?;
+
+    quoter.unsubscribe_channels(vec![eth.clone()]).await?;
+    assert_eq!(quoter.active_channels().await, vec![btc.clone()]);
+    let client = quoter.client.lock().await;
+    assert_eq!(client.outbound_depth(), 2);
+    assert_eq!(client.calls().get(2).map(|call| call.method), Some(RpcMethod::Unsubscribe));
+    drop(client);
+
+    // The channel's subscription is forgotten, the other one is still awaited
+    let states = quoter.notification_handler.subscription_states.read().await;
+    assert_eq!(states.state(&eth), None);
+    assert_eq!(states.state(&btc), Some(SubscriptionState::Pending { attempts: 0 }));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_disable_and_enable_instrument_channels() -> Result<()> {
+    let quoter = quoter().await;
+    quoter.subscribe_channels(quoter.market_data.get_public_channels().await?).await?;
+    let ticker = Channel::ticker("BTC-PERPETUAL");
+    assert!(quoter.active_channels().await.contains(&ticker));
+
+    quoter.handle_command(ControlCommand::Disable { instrument: "BTC-PERPETUAL".to_string() }).await?;
+    let channels = quoter.active_channels().await;
+    assert!(!channels.contains(&ticker));
+    // Channels of the underlying stay, other instruments may still need them
+    assert!(channels.iter().any(|channel| matches!(channel, Channel::Index(_))));
+    assert!(quoter.order_manager.is_disabled().await);
+
+    quoter.handle_command(ControlCommand::Enable { instrument: "BTC-PERPETUAL".to_string() }).await?;
+    assert!(quoter.active_channels().await.contains(&ticker));
+    assert!(!quoter.order_manager.is_disabled().await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_commands_for_other_instruments_rejected() -> Result<()> {
+    let quoter = quoter().await;
+    let err = quoter.handle_command(ControlCommand::Disable { instrument: "ETH-PERPETUAL".to_string() }).await.unwrap_err();
+    assert!(err.to_string().contains("not quoted"));
+    assert!(!quoter.order_manager.is_instrument_disabled("ETH-PERPETUAL").await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quoted_book_subscribed_by_default() -> Result<()> {
+    let quoter = quoter().await;
+    let channels = quoter.market_data.get_public_channels().await?;
+    assert!(channels.contains(&Channel::book("BTC-PERPETUAL", 10)));
+    
+    // A configured subscription of the instrument's book is used as is
+    quoter.market_data.set_book_channels(vec![Channel::book("BTC-PERPETUAL", 50)]).await;
+    let books: Vec<Channel> = quoter.market_data.get_public_channels().await?.into_iter()
+        .filter(|channel| matches!(channel, Channel::Book { .. }))
+        .collect();
+    assert_eq!(books, vec![Channel::book("BTC-PERPETUAL", 50)]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscriptions_recorded_for_the_next_session() -> Result<()> {
+    let quoter = quoter().await;
+    let eth = Channel::ticker("ETH-PERPETUAL");
+    quoter.subscribe_channels(vec![Channel::Orders, eth.clone(), Channel::ticker("BTC-PERPETUAL")]).await?;
+    quoter.unsubscribe_channels(vec![Channel::ticker("BTC-PERPETUAL")]).await?;
+    assert_eq!(quoter.subscriptions.channels(), vec![Channel::Orders, eth.clone()]);
+    
+    // The next session's quoter is handed the same recording and adds the runtime channels to its defaults
+    let mut next = self::quoter().await;
+    next.subscriptions = quoter.subscriptions.clone();
+    let public = next.subscriptions.restore(next.market_data.get_public_channels().await?, false);
+    assert!(public.contains(&eth));
+    assert!(public.contains(&Channel::ticker("BTC-PERPETUAL")));
+    assert_eq!(next.subscriptions.restore(vec![Channel::Orders], true), vec![Channel::Orders]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_halting_cancels_the_session() -> Result<()> {
+    let quoter = quoter().await;
+    *quoter.market_data.index_price.write().await = Some(50_000.0);
+    quoter.order_manager.adjust_quotes(vec![vec![SideQuote::new(49_975.0, 0.2)], vec![]]).await?;
+    assert_eq!(quoter.client.lock().await.outbound_depth(), 1);
+
+    quoter.order_manager.risk.write().await.halt("loss limit");
+    assert!(!quoter.update_quoting_state().await);
+    assert_eq!(quoter.state.current(), BotState::Halted);
+
+    // The queued insert gives way to a single session cancel
+    let client = quoter.client.lock().await;
+    assert_eq!(client.outbound_depth(), 1);
+    assert_eq!(client.calls().outstanding(), 1);
+    drop(client);
+
+    // Only entering the state cancels
+    assert!(!quoter.update_quoting_state().await);
+    assert_eq!(quoter.client.lock().await.outbound_depth(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_take_over_reconciles_positions_before_quoting() -> Result<()> {
+    let quoter = quoter().await;
+    quoter.market_data.standby.store(true, Ordering::Relaxed);
+    
+    quoter.take_over().await?;
+    assert!(!quoter.market_data.is_standby());
+    assert!(quoter.order_manager.is_awaiting_positions());
+    // The cancel and the portfolio request wait for the connection
+    assert_eq!(quoter.client.lock().await.outbound_depth(), 2);
+    
+    quoter.order_manager.reconcile_positions(&[]).await;
+    assert!(!quoter.order_manager.is_awaiting_positions());
+    Ok(())
+}
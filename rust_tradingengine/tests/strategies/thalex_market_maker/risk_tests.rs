@@ -0,0 +1,161 @@
+use cryptics_lab_bot::config_loader::{RiskConfig, RiskLimitsConfig};
+use cryptics_lab_bot::domain::enums::OrderSide;
+use cryptics_lab_bot::domain::model::quote::SideQuote;
+use cryptics_lab_bot::strategies::thalex_market_maker::{LeverageTier, RiskManager};
+
+const TIERS: &[LeverageTier] = &[
+    LeverageTier { max_notional: 250_000.0, max_leverage: 3.0 },
+    LeverageTier { max_notional: 50_000.0, max_leverage: 5.0 },
+];
+
+fn quotes() -> Vec<Vec<SideQuote>> {
+    vec![
+        vec![SideQuote::new(99.0, 0.2), SideQuote::new(98.0, 0.4)],
+        vec![SideQuote::new(101.0, 0.2), SideQuote::new(102.0, 0.4)],
+    ]
+}
+
+#[test]
+fn test_max_notional_uses_best_tier() {
+    // Small equity: the high-leverage first tier allows the most
+    let risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    assert_eq!(risk.max_notional(), 50_000.0);
+    
+    // Larger equity: the second tier's leverage covers more notional
+    let risk = RiskManager::new(50_000.0, TIERS, 1.0, 0.001);
+    assert_eq!(risk.max_notional(), 150_000.0);
+}
+
+#[test]
+fn test_tiers_and_equity_from_config() {
+    let config = RiskConfig { equity: 50_000.0, leverage_tiers: TIERS.to_vec() };
+    assert_eq!(RiskManager::from_config(&config, 1.0, 0.001).max_notional(), 150_000.0);
+    
+    // Defaults to the previous built-in tiers
+    assert_eq!(RiskManager::from_config(&RiskConfig::default(), 1.0, 0.001).max_notional(), 50_000.0);
+}
+
+#[test]
+fn test_quotes_unchanged_within_limits() {
+    let risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    let constrained = risk.constrain_quotes(quotes(), 0.0, 100.0);
+    
+    assert_eq!(constrained[0].len(), 2);
+    assert_eq!(constrained[1][1].amount, 0.4);
+}
+
+#[test]
+fn test_long_position_limits_bids_only() {
+    // Max position at price 100 is 500 contracts with 10k equity
+    let risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    let constrained = risk.constrain_quotes(quotes(), 499.7, 100.0);
+    
+    let bids = &constrained[0];
+    assert_eq!(bids.len(), 2);
+    assert!((bids[0].amount - 0.2).abs() < 1e-9);
+    assert!((bids[1].amount - 0.1).abs() < 1e-9);
+    assert_eq!(constrained[1].len(), 2);
+}
+
+#[test]
+fn test_levels_dropped_at_limit() {
+    let risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    let constrained = risk.constrain_quotes(quotes(), -500.0, 100.0);
+    
+    assert_eq!(constrained[0].len(), 2);
+    assert!(constrained[1].is_empty());
+}
+
+#[test]
+fn test_available_margin_caps_growing_fills() {
+    let mut risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    assert_eq!(risk.margin_capacity(0.0, 100.0), None);
+    
+    // 2 of margin at the first tier's 5x leverage carries 0.1 contracts at 100
+    risk.set_available_margin(2.0);
+    assert!((risk.margin_capacity(0.0, 100.0).unwrap() - 0.1).abs() < 1e-9);
+    let constrained = risk.constrain_quotes(quotes(), 0.0, 100.0);
+    assert_eq!(constrained[0].len(), 1);
+    assert!((constrained[0][0].amount - 0.1).abs() < 1e-9);
+    assert!((constrained[1][0].amount - 0.1).abs() < 1e-9);
+    
+    // Selling down a long frees margin, so the asks may cover the position on top
+    let constrained = risk.constrain_quotes(quotes(), 0.3, 100.0);
+    assert!((constrained[0][0].amount - 0.1).abs() < 1e-9);
+    assert_eq!(constrained[1].len(), 2);
+    assert!((constrained[1][1].amount - 0.2).abs() < 1e-9);
+    
+    // Negative remaining margin only allows reducing fills
+    risk.set_available_margin(-50.0);
+    assert_eq!(risk.available_margin(), Some(0.0));
+    assert!(risk.constrain_quotes(quotes(), 0.0, 100.0).iter().all(Vec::is_empty));
+}
+
+#[test]
+fn test_order_amount_capped() {
+    let risk = RiskManager::new(10_000.0, TIERS, 0.3, 0.1);
+    let constrained = risk.constrain_quotes(quotes(), 0.0, 100.0);
+    
+    assert!((constrained[0][1].amount - 0.3).abs() < 1e-9);
+}
+
+#[test]
+fn test_notional_limits_convert_at_price_and_contract_size() {
+    let mut risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    risk.set_limits(&RiskLimitsConfig {
+        max_position_notional: Some(20_000.0),
+        max_order_notional: Some(20.0),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(risk.max_position(100.0), 200.0);
+    assert_eq!(risk.max_order(100.0), 0.2);
+
+    // Ten units per contract: a tenth of the contracts
+    risk.set_contract_size(10.0);
+    assert_eq!(risk.max_position(100.0), 20.0);
+    assert!((risk.max_order(100.0) - 0.02).abs() < 1e-12);
+}
+
+#[test]
+fn test_delta_limit() {
+    let mut risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    risk.set_limits(&RiskLimitsConfig { max_delta: Some(50.0), ..Default::default() }).unwrap();
+    assert_eq!(risk.max_position(100.0), 50.0);
+    risk.set_delta(0.5);
+    assert_eq!(risk.max_position(100.0), 100.0);
+    // The tiers still apply when they are tighter
+    risk.set_delta(0.01);
+    assert_eq!(risk.max_position(100.0), 500.0);
+}
+
+#[test]
+fn test_check_order() {
+    let mut risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    risk.set_limits(&RiskLimitsConfig { max_position_notional: Some(100.0), ..Default::default() }).unwrap();
+    assert!(risk.check_order(&OrderSide::Buy, 0.5, 100.0, 0.4).is_ok());
+    assert!(risk.check_order(&OrderSide::Buy, 0.7, 100.0, 0.4).is_err());
+    // Reducing an oversized position is allowed
+    assert!(risk.check_order(&OrderSide::Sell, 0.5, 100.0, 2.0).is_ok());
+    assert!(risk.check_order(&OrderSide::Sell, 1.5, 100.0, 0.0).is_err());
+}
+
+#[test]
+fn test_invalid_limits_rejected() {
+    let mut risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    let err = risk.set_limits(&RiskLimitsConfig { max_delta: Some(-1.0), ..Default::default() }).unwrap_err();
+    assert!(err.to_string().contains("max_delta"));
+    assert_eq!(risk.max_position(100.0), 500.0);
+}
+
+#[test]
+fn test_halt_blocks_all_orders() {
+    let mut risk = RiskManager::new(10_000.0, TIERS, 1.0, 0.001);
+    risk.halt("liquidation");
+    risk.halt("later reason");
+    assert_eq!(risk.halt_reason(), Some("liquidation"));
+    
+    // Not even position-reducing orders go out, and nothing is quoted
+    let err = risk.check_order(&OrderSide::Sell, 0.1, 100.0, 1.0).unwrap_err();
+    assert!(err.to_string().contains("liquidation"));
+    assert!(risk.constrain_quotes(quotes(), 0.0, 100.0).iter().all(Vec::is_empty));
+}
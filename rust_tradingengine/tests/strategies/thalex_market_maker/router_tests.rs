@@ -0,0 +1,84 @@
+use serde_json::json;
+
+use cryptics_lab_bot::domain::enums::OrderSide;
+use cryptics_lab_bot::strategies::thalex_market_maker::{InboundMessage, Priority};
+use cryptics_lab_bot::testing::fixtures;
+
+#[test]
+fn test_private_notifications_are_high_priority() {
+    for channel in ["session.orders", "account.trade_history", "account.portfolio"] {
+        let message = InboundMessage::from_json(fixtures::notification(channel, json!([]))).unwrap();
+        assert_eq!(message.priority(), Priority::High, "channel {}", channel);
+    }
+}
+
+#[test]
+fn test_market_data_is_normal_priority() {
+    for channel in ["ticker.BTC-PERPETUAL.raw", "price_index.BTCUSD", "some.unknown.channel"] {
+        let message = InboundMessage::from_json(fixtures::notification(channel, json!({}))).unwrap();
+        assert_eq!(message.priority(), Priority::Normal, "channel {}", channel);
+    }
+}
+
+#[test]
+fn test_results_and_errors_are_high_priority() {
+    let order = fixtures::order(1, OrderSide::Buy, fixtures::MARK, 0.1);
+    let result = InboundMessage::from_json(fixtures::rpc_result(101, fixtures::order_json(&order))).unwrap();
+    match &result {
+        InboundMessage::Result { cid, result } => {
+            assert_eq!(*cid, 101);
+            assert_eq!(result["order_id"], order.order_id);
+        }
+        other => panic!("Expected Result, got {:?}", other),
+    }
+    assert_eq!(result.priority(), Priority::High);
+    
+    let error = InboundMessage::from_json(fixtures::rpc_error(7, 1, "rate limited")).unwrap();
+    assert!(matches!(error, InboundMessage::Error { cid: 7, .. }));
+    assert_eq!(error.priority(), Priority::High);
+}
+
+#[test]
+fn test_unroutable_messages() {
+    assert!(InboundMessage::from_json(json!({"foo": "bar"})).is_none());
+    let mut without_notification = fixtures::orders_notification(&[]);
+    without_notification.as_object_mut().unwrap().remove("notification");
+    assert!(InboundMessage::from_json(without_notification).is_none());
+}
+
+#[test]
+fn test_from_slice_matches_from_json() {
+    let order = fixtures::order(1, OrderSide::Buy, fixtures::MARK, 0.1);
+    let frames = [
+        fixtures::ticker_notification(&fixtures::ticker(fixtures::INSTRUMENT)),
+        fixtures::orders_notification(std::slice::from_ref(&order)),
+        fixtures::rpc_result(101, fixtures::order_json(&order)),
+        fixtures::rpc_error(7, 1, "rate limited"),
+    ];
+    for frame in frames {
+        let from_slice = InboundMessage::from_slice(frame.to_string().as_bytes()).unwrap().unwrap();
+        let from_json = InboundMessage::from_json(frame.clone()).unwrap();
+        assert_eq!(format!("{:?}", from_slice), format!("{:?}", from_json), "frame {}", frame);
+        assert_eq!(from_slice.priority(), from_json.priority());
+    }
+}
+
+#[test]
+fn test_from_slice_keeps_null_results_and_escaped_channels() {
+    let message = InboundMessage::from_slice(br#"{"id": 3, "result": null}"#).unwrap().unwrap();
+    assert!(matches!(message, InboundMessage::Result { cid: 3, result: serde_json::Value::Null }));
+
+    let message = InboundMessage::from_slice(br#"{"channel_name": "session\u002eorders", "notification": []}"#).unwrap().unwrap();
+    match &message {
+        InboundMessage::Notification { channel, .. } => assert_eq!(channel, "session.orders"),
+        other => panic!("Expected Notification, got {:?}", other),
+    }
+    assert_eq!(message.priority(), Priority::High);
+}
+
+#[test]
+fn test_from_slice_unroutable_and_malformed_frames() {
+    assert!(InboundMessage::from_slice(br#"{"foo": "bar"}"#).unwrap().is_none());
+    assert!(InboundMessage::from_slice(br#"{"channel_name": "session.orders"}"#).unwrap().is_none());
+    assert!(InboundMessage::from_slice(b"{not json").is_err());
+}
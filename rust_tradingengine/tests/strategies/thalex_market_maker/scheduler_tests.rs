@@ -0,0 +1,108 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use cryptics_lab_bot::config_loader::ScheduleConfig;
+use cryptics_lab_bot::strategies::thalex_market_maker::{ParameterScheduler, QuoteParams, SPREAD};
+
+fn scheduler() -> Result<ParameterScheduler> {
+    let schedule: ScheduleConfig = serde_json::from_value(json!({
+        "windows": [
+            { "name": "asia_night", "start": "22:00", "end": "06:00", "spread": 40.0 },
+            { "name": "early", "start": "05:00", "end": "07:00", "size_multiplier": 0.5 }
+        ],
+        "events": [
+            { "name": "cpi", "at": "2026-10-15T12:30:00Z", "before_sec": 300, "after_sec": 600,
+              "size_multiplier": 0.25, "spread": 60.0 }
+        ]
+    }))?;
+    ParameterScheduler::from_config(&schedule)
+}
+
+fn at(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+}
+
+#[test]
+fn test_no_override_uses_defaults() -> Result<()> {
+    let (params, active) = scheduler()?.params_at(at("2026-10-15T10:00:00Z"));
+    assert_eq!(params, QuoteParams::default());
+    assert_eq!(params.spread, SPREAD);
+    assert!(active.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_window_wrapping_midnight() -> Result<()> {
+    let scheduler = scheduler()?;
+    assert_eq!(scheduler.params_at(at("2026-10-15T23:30:00Z")).0.spread, 40.0);
+    assert_eq!(scheduler.params_at(at("2026-10-15T02:00:00Z")).0.spread, 40.0);
+    assert_eq!(scheduler.params_at(at("2026-10-15T06:00:00Z")).0.spread, SPREAD);
+    Ok(())
+}
+
+#[test]
+fn test_overlapping_overrides_combine() -> Result<()> {
+    let (params, active) = scheduler()?.params_at(at("2026-10-15T05:30:00Z"));
+    assert_eq!(params.spread, 40.0);
+    assert_eq!(params.size_multiplier, 0.5);
+    assert_eq!(active, vec!["asia_night", "early"]);
+    Ok(())
+}
+
+#[test]
+fn test_event_window_around_release() -> Result<()> {
+    let scheduler = scheduler()?;
+    let (params, active) = scheduler.params_at(at("2026-10-15T12:26:00Z"));
+    assert_eq!(params.size_multiplier, 0.25);
+    assert_eq!(params.spread, 60.0);
+    assert_eq!(active, vec!["cpi"]);
+    
+    assert_eq!(scheduler.params_at(at("2026-10-15T12:41:00Z")).0, QuoteParams::default());
+    Ok(())
+}
+
+#[test]
+fn test_invalid_times_rejected() {
+    let schedule: ScheduleConfig = serde_json::from_value(json!({
+        "windows": [{ "name": "bad", "start": "25:00", "end": "06:00" }]
+    })).unwrap();
+    assert!(ParameterScheduler::from_config(&schedule).is_err());
+}
+
+fn funding_scheduler(funding: serde_json::Value) -> Result<ParameterScheduler> {
+    let schedule: ScheduleConfig = serde_json::from_value(json!({ "funding": funding }))?;
+    ParameterScheduler::from_config(&schedule)
+}
+
+#[test]
+fn test_funding_window_around_each_settlement() -> Result<()> {
+    let scheduler = funding_scheduler(json!({ "before_sec": 60, "after_sec": 120, "spread": 40.0 }))?;
+    
+    for settlement in ["00", "08", "16"] {
+        let (params, active) = scheduler.params_at(at(&format!("2026-10-15T{}:01:00Z", settlement)));
+        assert_eq!(params.spread, 40.0);
+        assert_eq!(active, vec!["funding"]);
+    }
+    // Window opens before the settlement, including across midnight
+    assert_eq!(scheduler.params_at(at("2026-10-15T23:59:30Z")).0.spread, 40.0);
+    assert_eq!(scheduler.params_at(at("2026-10-15T07:58:59Z")).0.spread, SPREAD);
+    assert_eq!(scheduler.params_at(at("2026-10-15T08:02:00Z")).0.spread, SPREAD);
+    Ok(())
+}
+
+#[test]
+fn test_funding_window_pause_and_offset() -> Result<()> {
+    let scheduler = funding_scheduler(json!({
+        "interval_sec": 3600, "offset_sec": 1800, "before_sec": 30, "after_sec": 30, "pause": true
+    }))?;
+    assert!(scheduler.params_at(at("2026-10-15T10:30:10Z")).0.paused);
+    assert!(!scheduler.params_at(at("2026-10-15T11:00:00Z")).0.paused);
+    Ok(())
+}
+
+#[test]
+fn test_funding_window_validated() {
+    assert!(funding_scheduler(json!({ "interval_sec": 0 })).is_err());
+    assert!(funding_scheduler(json!({ "interval_sec": 600, "before_sec": 300, "after_sec": 300 })).is_err());
+}
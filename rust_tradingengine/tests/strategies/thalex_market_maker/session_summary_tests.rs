@@ -0,0 +1,38 @@
+use cryptics_lab_bot::infrastructure::metrics::Metrics;
+use cryptics_lab_bot::infrastructure::reconnect::METRIC_RECONNECTS;
+use cryptics_lab_bot::strategies::thalex_market_maker::{
+    summarize_session, METRIC_FILLS, METRIC_FILL_VOLUME, METRIC_MAX_INVENTORY, METRIC_ORDERS_AMENDED,
+    METRIC_ORDERS_CANCELLED, METRIC_ORDERS_INSERTED, METRIC_REALIZED_PNL,
+};
+
+#[test]
+fn test_summary_is_assembled_from_metrics() {
+    let metrics = Metrics::default();
+    metrics.incr(METRIC_ORDERS_INSERTED, 12);
+    metrics.incr(METRIC_ORDERS_AMENDED, 40);
+    metrics.incr(METRIC_ORDERS_CANCELLED, 3);
+    metrics.incr(METRIC_FILLS, 2);
+    metrics.add_gauge(METRIC_FILL_VOLUME, 0.1);
+    metrics.add_gauge(METRIC_FILL_VOLUME, 0.2);
+    metrics.add_gauge(METRIC_REALIZED_PNL, 15.5);
+    metrics.max_gauge(METRIC_MAX_INVENTORY, 0.3);
+    metrics.incr(METRIC_RECONNECTS, 1);
+
+    let summary = summarize_session(&metrics, "instance", 1_792_000_000.0, 1_792_003_600.0);
+    assert_eq!(summary.instance_id, "instance");
+    assert_eq!(summary.duration_secs(), 3600.0);
+    assert_eq!((summary.quotes_sent, summary.amends, summary.cancels, summary.fills), (12, 40, 3, 2));
+    assert!((summary.volume - 0.3).abs() < 1e-12);
+    assert_eq!(summary.realized_pnl, 15.5);
+    assert_eq!(summary.max_inventory, 0.3);
+    assert_eq!(summary.reconnects, 1);
+    assert_eq!(summary.processing_timestamp, Some(1_792_003_600.0));
+}
+
+#[test]
+fn test_summary_of_an_idle_run_is_zero() {
+    let summary = summarize_session(&Metrics::default(), "instance", 100.0, 90.0);
+    assert_eq!(summary.duration_secs(), 0.0);
+    assert_eq!((summary.quotes_sent, summary.fills, summary.reconnects), (0, 0, 0));
+    assert_eq!((summary.volume, summary.realized_pnl, summary.max_inventory), (0.0, 0.0, 0.0));
+}
@@ -0,0 +1,38 @@
+use std::time::{Duration, Instant};
+
+use cryptics_lab_bot::config_loader::SideBudgetConfig;
+use cryptics_lab_bot::strategies::thalex_market_maker::SideBudget;
+
+#[test]
+fn test_sides_spend_separate_budgets() {
+    let start = Instant::now();
+    let mut budget = SideBudget::new(2, Duration::from_secs(1)).unwrap();
+    
+    assert!(budget.try_acquire(0, start));
+    assert!(budget.try_acquire(0, start));
+    assert!(!budget.try_acquire(0, start));
+    
+    // Bids used up their allowance, asks still have all of theirs
+    assert_eq!(budget.available(1, start), 2);
+    assert!(budget.try_acquire(1, start));
+}
+
+#[test]
+fn test_budget_refills_as_the_window_slides() {
+    let start = Instant::now();
+    let mut budget = SideBudget::new(2, Duration::from_secs(1)).unwrap();
+    assert!(budget.try_acquire(0, start));
+    assert!(budget.try_acquire(0, start + Duration::from_millis(600)));
+    assert_eq!(budget.available(0, start + Duration::from_millis(900)), 0);
+    
+    // The first update leaves the window, the second is still in it
+    assert_eq!(budget.available(0, start + Duration::from_secs(1)), 1);
+    assert_eq!(budget.available(0, start + Duration::from_millis(1600)), 2);
+}
+
+#[test]
+fn test_budget_config_is_validated() {
+    assert!(SideBudget::from_config(&SideBudgetConfig { max_updates: 10, window_ms: 500 }).is_ok());
+    assert!(SideBudget::from_config(&SideBudgetConfig { max_updates: 0, window_ms: 500 }).is_err());
+    assert!(SideBudget::from_config(&SideBudgetConfig { max_updates: 10, window_ms: 0 }).is_err());
+}
@@ -0,0 +1,38 @@
+use cryptics_lab_bot::strategies::thalex_market_maker::SizeScaler;
+
+fn scaler() -> SizeScaler {
+    SizeScaler::new(0.002, 0.0005, 0.1, 2.0).unwrap()
+}
+
+#[test]
+fn test_constant_risk_per_level() {
+    // 10k equity risking 0.2% = 20 USD on a 1% move of a 50k instrument: 0.04 contracts
+    let multiplier = scaler().multiplier(10_000.0, 50_000.0, 0.01, 0.2);
+    assert!((multiplier - 0.2).abs() < 1e-12);
+    
+    // Twice the equity, twice the size; twice the vol, half the size
+    assert!((scaler().multiplier(20_000.0, 50_000.0, 0.01, 0.2) - 0.4).abs() < 1e-12);
+    assert!((scaler().multiplier(10_000.0, 50_000.0, 0.02, 0.2) - 0.1).abs() < 1e-12);
+}
+
+#[test]
+fn test_multiplier_capped() {
+    // Quiet market: vol floored, then capped at the maximum
+    assert_eq!(scaler().multiplier(1_000_000.0, 50_000.0, 0.0, 0.2), 2.0);
+    // Wild market: capped at the minimum
+    assert_eq!(scaler().multiplier(10_000.0, 50_000.0, 0.5, 0.2), 0.1);
+}
+
+#[test]
+fn test_unusable_inputs_use_minimum() {
+    assert_eq!(scaler().multiplier(0.0, 50_000.0, 0.01, 0.2), 0.1);
+    assert_eq!(scaler().multiplier(10_000.0, 0.0, 0.01, 0.2), 0.1);
+    assert_eq!(scaler().multiplier(10_000.0, 50_000.0, f64::NAN, 0.2), 0.1);
+}
+
+#[test]
+fn test_invalid_settings_rejected() {
+    assert!(SizeScaler::new(0.0, 0.0005, 0.1, 2.0).is_err());
+    assert!(SizeScaler::new(0.002, 0.0, 0.1, 2.0).is_err());
+    assert!(SizeScaler::new(0.002, 0.0005, 3.0, 2.0).is_err());
+}
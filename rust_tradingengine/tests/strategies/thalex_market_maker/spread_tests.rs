@@ -0,0 +1,81 @@
+use cryptics_lab_bot::domain::enums::OrderSide;
+use cryptics_lab_bot::strategies::thalex_market_maker::{SpreadLegs, HEDGE_MAX_ATTEMPTS};
+
+fn legs() -> SpreadLegs {
+    SpreadLegs::new("BTC-27DEC24", 2.0, 0.1).unwrap()
+}
+
+#[test]
+fn test_quote_fill_hedged_opposite_at_ratio() {
+    let mut legs = legs();
+    let hedge = legs.on_quote_fill(Some(7), &OrderSide::Sell, 0.3).unwrap();
+    assert!(matches!(hedge.side, OrderSide::Buy));
+    assert!((hedge.amount - 0.6).abs() < 1e-12);
+    assert_eq!((hedge.quote_order_id, hedge.attempt), (Some(7), 1));
+    assert!((legs.leg_risk() + 0.3).abs() < 1e-12);
+    assert!(legs.on_quote_fill(None, &OrderSide::Buy, 0.0).is_none());
+}
+
+#[test]
+fn test_leg_risk_falls_as_hedge_fills() {
+    let mut legs = legs();
+    let hedge = legs.on_quote_fill(Some(7), &OrderSide::Buy, 0.3).unwrap();
+    legs.hedge_sent(100, &hedge);
+    assert!(legs.check_leg_risk());
+    
+    assert!(legs.on_hedge_update(100, 0.4, true).is_none());
+    assert!((legs.leg_risk() - 0.1).abs() < 1e-12);
+    assert!(!legs.check_leg_risk());
+    
+    assert!(legs.on_hedge_update(100, 0.6, false).is_none());
+    assert!(legs.leg_risk().abs() < 1e-12);
+    assert!(!legs.is_hedge(100));
+}
+
+#[test]
+fn test_unfilled_remainder_retried_up_to_limit() {
+    let mut legs = legs();
+    let mut hedge = legs.on_quote_fill(None, &OrderSide::Buy, 0.3).unwrap();
+    for cid in 100..100 + HEDGE_MAX_ATTEMPTS as u64 {
+        legs.hedge_sent(cid, &hedge);
+        match legs.hedge_failed(cid) {
+            Some(retry) => {
+                assert_eq!(retry.attempt, hedge.attempt + 1);
+                assert!((retry.amount - 0.6).abs() < 1e-12);
+                hedge = retry;
+            }
+            None => assert_eq!(hedge.attempt, HEDGE_MAX_ATTEMPTS),
+        }
+    }
+    assert_eq!(hedge.attempt, HEDGE_MAX_ATTEMPTS);
+    assert!(legs.check_leg_risk());
+    assert_eq!(legs.open_hedges().count(), 0);
+}
+
+#[test]
+fn test_hedges_rounded_to_the_hedge_amount_step() {
+    let mut legs = SpreadLegs::new("BTC-27DEC24", 1.5, 1.0).unwrap();
+    legs.set_hedge_step(0.1);
+    
+    // 0.51 rounds to 0.5, the 0.01 left is added to the next hedge
+    let hedge = legs.on_quote_fill(Some(7), &OrderSide::Buy, 0.34).unwrap();
+    assert!(matches!(hedge.side, OrderSide::Sell));
+    assert!((hedge.amount - 0.5).abs() < 1e-12);
+    
+    // 0.15 and the 0.01 carried round to 0.2, over-hedging by 0.04
+    let hedge = legs.on_quote_fill(Some(8), &OrderSide::Buy, 0.1).unwrap();
+    assert!((hedge.amount - 0.2).abs() < 1e-12);
+    
+    // Amounts rounding to less than a step are carried until they add up to one
+    assert!(legs.on_quote_fill(Some(9), &OrderSide::Sell, 0.005).is_none());
+    let hedge = legs.on_quote_fill(Some(10), &OrderSide::Sell, 0.01).unwrap();
+    assert!(matches!(hedge.side, OrderSide::Buy));
+    assert!((hedge.amount - 0.1).abs() < 1e-12);
+}
+
+#[test]
+fn test_invalid_settings_rejected() {
+    assert!(SpreadLegs::new("", 1.0, 0.1).is_err());
+    assert!(SpreadLegs::new("BTC-27DEC24", 0.0, 0.1).is_err());
+    assert!(SpreadLegs::new("BTC-27DEC24", 1.0, -0.1).is_err());
+}
@@ -0,0 +1,37 @@
+use cryptics_lab_bot::infrastructure::metrics;
+use cryptics_lab_bot::strategies::thalex_market_maker::{age_metric, consumer_lag, lag_metric, Staleness, StalenessGuard};
+
+const NOW: f64 = 1_700_000_000.0;
+
+#[test]
+fn test_recent_messages_are_fresh() {
+    let guard = StalenessGuard::new("staleness.fresh", 5.0, 100);
+    assert_eq!(guard.check(Some(NOW - 1.0), Some(NOW + 10.0), Some(3), NOW), Staleness::Fresh);
+    // Without a timestamp or a known lag only the deadline is checked
+    assert_eq!(guard.check(None, None, None, NOW), Staleness::Fresh);
+    
+    assert_eq!(metrics::global().gauge_value(&lag_metric("staleness.fresh")), Some(3.0));
+    assert_eq!(metrics::global().gauge_value(&age_metric("staleness.fresh")), Some(1000.0));
+}
+
+#[test]
+fn test_delayed_messages_are_stale() {
+    let guard = StalenessGuard::new("staleness.stale", 5.0, 100);
+    assert_eq!(guard.check(Some(NOW - 60.0), None, Some(0), NOW), Staleness::TooOld { age_sec: 60.0 });
+    assert_eq!(guard.check(Some(NOW - 1.0), Some(NOW - 0.5), Some(0), NOW), Staleness::Expired { expires_at: NOW - 0.5 });
+    assert_eq!(guard.check(Some(NOW - 1.0), None, Some(250), NOW), Staleness::Behind { lag: 250 });
+}
+
+#[test]
+fn test_zero_max_lag_leaves_lag_unchecked() {
+    let guard = StalenessGuard::new("staleness.unchecked", 5.0, 0);
+    assert!(guard.check(Some(NOW), None, Some(10_000), NOW).is_fresh());
+}
+
+#[test]
+fn test_consumer_lag_counts_records_behind() {
+    assert_eq!(consumer_lag(10, 9), 0);
+    assert_eq!(consumer_lag(10, 4), 5);
+    // A watermark fetched before the record arrived
+    assert_eq!(consumer_lag(3, 7), 0);
+}
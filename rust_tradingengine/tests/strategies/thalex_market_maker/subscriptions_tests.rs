@@ -0,0 +1,61 @@
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+use cryptics_lab_bot::strategies::thalex_market_maker::{SubscriptionState, SubscriptionTracker};
+
+/// 2026-10-15T00:00:00Z
+const NOW: f64 = 1_792_022_400.0;
+
+fn ticker() -> Channel {
+    "ticker.BTC-PERPETUAL.1000ms".parse().unwrap()
+}
+
+#[test]
+fn test_ready_once_required_channels_are_confirmed() {
+    let mut tracker = SubscriptionTracker::new(1.0, 60.0);
+    tracker.require(&[Channel::Orders, ticker()]);
+    assert!(!tracker.is_ready());
+
+    tracker.requested(&[Channel::Orders, Channel::Trades, ticker()]);
+    tracker.confirmed(&[Channel::Orders, Channel::Trades]);
+    assert!(!tracker.is_ready());
+    assert_eq!(tracker.unconfirmed(), vec![ticker()]);
+
+    tracker.confirmed(&[ticker()]);
+    assert!(tracker.is_ready());
+}
+
+#[test]
+fn test_failed_channels_retry_with_doubling_backoff() {
+    let mut tracker = SubscriptionTracker::new(1.0, 3.0);
+    tracker.requested(&[ticker()]);
+    tracker.failed(&[ticker()], NOW);
+    assert_eq!(tracker.state(&ticker()), Some(SubscriptionState::Failed { attempts: 1, retry_at: NOW + 1.0 }));
+    assert!(tracker.due(NOW + 0.5).is_empty());
+    assert_eq!(tracker.due(NOW + 1.0), vec![ticker()]);
+
+    // A retry in flight isn't due again, and keeps its attempt count
+    tracker.requested(&[ticker()]);
+    assert_eq!(tracker.state(&ticker()), Some(SubscriptionState::Pending { attempts: 1 }));
+    assert!(tracker.due(NOW + 10.0).is_empty());
+
+    tracker.failed(&[ticker()], NOW + 1.0);
+    assert_eq!(tracker.state(&ticker()), Some(SubscriptionState::Failed { attempts: 2, retry_at: NOW + 3.0 }));
+    tracker.requested(&[ticker()]);
+    tracker.failed(&[ticker()], NOW + 3.0);
+    // Capped at the maximum
+    assert_eq!(tracker.state(&ticker()), Some(SubscriptionState::Failed { attempts: 3, retry_at: NOW + 6.0 }));
+
+    tracker.requested(&[ticker()]);
+    tracker.confirmed(&[ticker()]);
+    tracker.failed(&[ticker()], NOW + 10.0);
+    assert_eq!(tracker.state(&ticker()), Some(SubscriptionState::Failed { attempts: 1, retry_at: NOW + 11.0 }));
+}
+
+#[test]
+fn test_unsubscribed_channels_are_forgotten() {
+    let mut tracker = SubscriptionTracker::new(1.0, 60.0);
+    tracker.requested(&[ticker()]);
+    tracker.failed(&[ticker()], NOW);
+    tracker.removed(&[ticker()]);
+    assert_eq!(tracker.state(&ticker()), None);
+    assert!(tracker.due(NOW + 60.0).is_empty());
+}
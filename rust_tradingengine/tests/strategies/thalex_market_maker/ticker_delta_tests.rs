@@ -0,0 +1,80 @@
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::domain::model::ticker_delta::TickerDeltaKind;
+use cryptics_lab_bot::infrastructure::exchange::thalex::channel::Channel;
+use cryptics_lab_bot::strategies::thalex_market_maker::TickerDeltaRecorder;
+
+fn channel() -> Channel {
+    Channel::ticker("BTC-PERPETUAL")
+}
+
+fn ticker(mark_price: f64) -> Ticker {
+    let mut ticker = Ticker::new("BTC-PERPETUAL".to_string());
+    ticker.mark_price = mark_price;
+    ticker.index_price = 65000.0;
+    ticker.volume_24h = 1200.0;
+    ticker
+}
+
+#[test]
+fn test_first_ticker_is_a_full_snapshot() {
+    let mut recorder = TickerDeltaRecorder::new(60.0);
+    let record = recorder.on_ticker(&channel(), &ticker(65010.0), 100.0).expect("snapshot");
+    
+    assert_eq!(record.kind, TickerDeltaKind::Snapshot);
+    assert_eq!(record.sequence, 1);
+    assert_eq!(record.changes.len(), Ticker::NUMERIC_FIELDS.len());
+    assert_eq!(record.get("volume_24h"), Some(1200.0));
+}
+
+#[test]
+fn test_delta_carries_only_changed_fields() {
+    let mut recorder = TickerDeltaRecorder::new(60.0);
+    recorder.on_ticker(&channel(), &ticker(65010.0), 100.0);
+    
+    assert!(recorder.on_ticker(&channel(), &ticker(65010.0), 101.0).is_none());
+    
+    let record = recorder.on_ticker(&channel(), &ticker(65012.5), 102.0).expect("delta");
+    assert_eq!(record.kind, TickerDeltaKind::Delta);
+    assert_eq!(record.sequence, 2);
+    assert_eq!(record.changes, vec![("mark_price".to_string(), 65012.5)]);
+    assert_eq!(record.get("volume_24h"), None);
+}
+
+#[test]
+fn test_periodic_and_forced_snapshots() {
+    let mut recorder = TickerDeltaRecorder::new(60.0);
+    recorder.on_ticker(&channel(), &ticker(65010.0), 100.0);
+    
+    // Unchanged tickers still produce the periodic snapshot
+    let record = recorder.on_ticker(&channel(), &ticker(65010.0), 160.0).expect("snapshot");
+    assert_eq!(record.kind, TickerDeltaKind::Snapshot);
+    assert_eq!(record.sequence, 2);
+    
+    let record = recorder.on_ticker(&channel(), &ticker(65011.0), 170.0).unwrap();
+    assert_eq!(record.kind, TickerDeltaKind::Delta);
+    
+    // The sequence carries on past a forced snapshot, so consumers never see a number twice
+    recorder.clear();
+    let record = recorder.on_ticker(&channel(), &ticker(65011.0), 171.0).expect("snapshot");
+    assert_eq!(record.kind, TickerDeltaKind::Snapshot);
+    assert_eq!(record.sequence, 4);
+    
+    recorder.resync(&channel());
+    let record = recorder.on_ticker(&channel(), &ticker(65011.0), 172.0).expect("snapshot");
+    assert_eq!(record.kind, TickerDeltaKind::Snapshot);
+    assert_eq!(record.sequence, 5);
+}
+
+#[test]
+fn test_applying_records_rebuilds_the_ticker() {
+    let mut recorder = TickerDeltaRecorder::new(60.0);
+    let mut latest = ticker(65010.0);
+    let mut rebuilt = Ticker::new("BTC-PERPETUAL".to_string());
+    recorder.on_ticker(&channel(), &latest, 100.0).unwrap().apply(&mut rebuilt);
+    
+    latest.mark_price = 65020.0;
+    latest.best_bid_price = 65015.0;
+    recorder.on_ticker(&channel(), &latest, 101.0).unwrap().apply(&mut rebuilt);
+    
+    assert_eq!(rebuilt.numeric_values(), latest.numeric_values());
+}
@@ -0,0 +1,56 @@
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::config_loader::TopicType;
+use cryptics_lab_bot::strategies::thalex_market_maker::TickerSampler;
+
+fn ticker(instrument: &str, mark_price: f64) -> Ticker {
+    let mut ticker = Ticker::new(instrument.to_string());
+    ticker.mark_price = mark_price;
+    ticker
+}
+
+#[test]
+fn test_take_keeps_latest_per_instrument() {
+    let mut sampler = TickerSampler::new();
+    sampler.record(&ticker("ETH-PERPETUAL", 3000.0));
+    sampler.record(&ticker("BTC-PERPETUAL", 50000.0));
+    sampler.record(&ticker("BTC-PERPETUAL", 50010.0));
+    
+    let latest: Vec<(String, f64)> = sampler.take().into_iter()
+        .map(|t| (t.instrument_name, t.mark_price))
+        .collect();
+    assert_eq!(latest, vec![
+        ("BTC-PERPETUAL".to_string(), 50010.0),
+        ("ETH-PERPETUAL".to_string(), 3000.0),
+    ]);
+}
+
+#[test]
+fn test_take_only_returns_updated_instruments() {
+    let mut sampler = TickerSampler::new();
+    sampler.record(&ticker("BTC-PERPETUAL", 50000.0));
+    sampler.record(&ticker("ETH-PERPETUAL", 3000.0));
+    sampler.take();
+    
+    assert!(sampler.take().is_empty());
+    
+    sampler.record(&ticker("ETH-PERPETUAL", 3001.0));
+    let latest = sampler.take();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0].instrument_name, "ETH-PERPETUAL");
+}
+
+#[test]
+fn test_clear_drops_unsent_updates() {
+    let mut sampler = TickerSampler::new();
+    sampler.record(&ticker("BTC-PERPETUAL", 50000.0));
+    sampler.clear();
+    assert!(sampler.take().is_empty());
+}
+
+#[test]
+fn test_latest_topic_reuses_ticker_schema() {
+    let latest = TopicType::builtin("ticker_latest", "cryptics.thalex.ticker_latest.avro");
+    assert_eq!(latest.schema_name("ticker_latest"), "ticker");
+    assert!(latest.compacted);
+    assert_eq!(TopicType::builtin("book", "cryptics.thalex.book.avro").schema_name("book"), "book");
+}
@@ -0,0 +1,45 @@
+use cryptics_lab_bot::strategies::thalex_market_maker::UptimeTracker;
+
+/// 2026-10-15T00:00:00Z
+const DAY_START: f64 = 1_792_022_400.0;
+
+#[test]
+fn test_uptime_integrates_compliant_time() {
+    let mut tracker = UptimeTracker::new("BTC-PERPETUAL", 50.0);
+    let t0 = DAY_START + 3600.0;
+    
+    assert!(tracker.sample(t0, true).is_none());
+    assert!(tracker.sample(t0 + 30.0, false).is_none());
+    assert!(tracker.sample(t0 + 40.0, true).is_none());
+    
+    let stats = tracker.current(t0 + 40.0);
+    assert_eq!(stats.observed_secs, 40.0);
+    assert_eq!(stats.compliant_secs, 30.0);
+    assert_eq!(stats.uptime_pct, 75.0);
+    assert_eq!(stats.date, "2026-10-15");
+}
+
+#[test]
+fn test_day_rollover_reports_previous_day() {
+    let mut tracker = UptimeTracker::new("BTC-PERPETUAL", 50.0);
+    let next_day = DAY_START + 86_400.0;
+    
+    tracker.sample(next_day - 100.0, true);
+    let completed = tracker.sample(next_day + 20.0, true).expect("day completed");
+    
+    assert_eq!(completed.date, "2026-10-15");
+    assert_eq!(completed.observed_secs, 100.0);
+    assert_eq!(completed.uptime_pct, 100.0);
+    assert_eq!(completed.period_end, next_day);
+    
+    let today = tracker.current(next_day + 20.0);
+    assert_eq!(today.date, "2026-10-16");
+    assert_eq!(today.observed_secs, 20.0);
+}
+
+#[test]
+fn test_no_observation_means_zero_uptime() {
+    let mut tracker = UptimeTracker::new("BTC-PERPETUAL", 50.0);
+    tracker.sample(DAY_START, false);
+    assert_eq!(tracker.current(DAY_START).uptime_pct, 0.0);
+}
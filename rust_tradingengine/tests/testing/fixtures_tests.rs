@@ -0,0 +1,116 @@
+use cryptics_lab_bot::domain::enums::{OrderSide, OrderStatus};
+use cryptics_lab_bot::domain::model::account_event::AccountEventType;
+use cryptics_lab_bot::domain::model::book::BookSide;
+use cryptics_lab_bot::domain::model::ticker::Ticker;
+use cryptics_lab_bot::domain::model::trade_correction::CorrectionKind;
+use cryptics_lab_bot::infrastructure::exchange::thalex::parsers::ThaleParser;
+use cryptics_lab_bot::testing::fixtures::{self, Fixtures};
+
+#[test]
+fn test_order_notification_parses_back() {
+    let order = fixtures::order(7, OrderSide::Sell, 65001.0, 0.2);
+    let frame = fixtures::orders_notification(std::slice::from_ref(&order));
+    assert_eq!(frame["channel_name"], "session.orders");
+
+    let parsed = ThaleParser::parse_order_json(&frame["notification"][0]).unwrap();
+    assert_eq!(parsed.order_id, order.order_id);
+    assert_eq!(parsed.client_order_id, Some(7));
+    assert!(matches!(parsed.direction, OrderSide::Sell));
+    assert_eq!(parsed.price, Some(65001.0));
+    assert_eq!(parsed.remaining_amount, 0.2);
+    assert!(matches!(parsed.status, OrderStatus::Open));
+}
+
+#[test]
+fn test_trade_notification_parses_back() {
+    let trade = fixtures::trade(7, 65001.0, 0.1);
+    let frame = fixtures::trades_notification(std::slice::from_ref(&trade));
+
+    let parsed = ThaleParser::parse_trade_json(&frame["notification"][0], false).unwrap();
+    assert_eq!(parsed.trade_id, trade.trade_id);
+    assert_eq!(parsed.client_order_id, Some(7));
+    assert_eq!(parsed.price, 65001.0);
+    assert_eq!(parsed.maker_taker, trade.maker_taker);
+}
+
+#[test]
+fn test_order_fills_parse_back() {
+    let order = fixtures::order(7, OrderSide::Buy, 65001.0, 0.2);
+    let fills = [fixtures::trade(7, 65001.0, 0.1), fixtures::trade(7, 65001.0, 0.1)];
+
+    let parsed = ThaleParser::extract_trades_from_order(&fixtures::order_with_fills_json(&order, &fills), true).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert!(parsed.iter().all(|trade| trade.order_id == order.order_id && trade.maker_taker == fills[0].maker_taker));
+}
+
+#[test]
+fn test_account_notifications_parse_back() {
+    let event = fixtures::account_event(AccountEventType::Withdrawal);
+    let parsed = ThaleParser::parse_account_event_json(&fixtures::account_event_json(&event)).unwrap();
+    assert_eq!((parsed.event_type, parsed.amount, parsed.timestamp), (event.event_type, event.amount, event.timestamp));
+
+    let bust = fixtures::trade_bust(7, 65001.0, 0.1);
+    let parsed = ThaleParser::parse_trade_correction_json(&fixtures::trade_correction_json(&bust)).unwrap().unwrap();
+    assert_eq!(parsed.kind, CorrectionKind::Bust);
+    assert_eq!(parsed.trade_id, fixtures::trade(7, 65001.0, 0.1).trade_id);
+    assert_eq!((parsed.original_price, parsed.original_amount), (Some(65001.0), Some(0.1)));
+}
+
+#[test]
+fn test_ticker_notification_parses_back() {
+    let ticker = Ticker { mark_price: 65010.0, ..fixtures::ticker("ETH-PERPETUAL") };
+    let frame = fixtures::ticker_notification(&ticker);
+    assert_eq!(frame["channel_name"], "ticker.ETH-PERPETUAL.raw");
+
+    let parsed = Ticker::from_json(&frame["notification"], "ETH-PERPETUAL".to_string()).unwrap();
+    assert_eq!(parsed.mark_price, 65010.0);
+    assert_eq!(parsed.best_bid_price, ticker.best_bid_price);
+    assert_eq!(parsed.index_price, ticker.index_price);
+}
+
+#[test]
+fn test_ack_is_partially_filled() {
+    let ack = fixtures::ack();
+    assert!(matches!(ack.status, OrderStatus::PartiallyFilled));
+    assert_eq!(ack.filled_amount + ack.remaining_amount, ack.amount);
+}
+
+#[test]
+fn test_book_snapshot_is_uncrossed() {
+    let book = fixtures::book_snapshot(fixtures::INSTRUMENT, 65000.0, 3, 4);
+    assert_eq!(book.len(), 6);
+    assert!(book.iter().all(|level| level.sequence == 4));
+    let best_bid = book.iter().filter(|l| l.side == BookSide::Bid).map(|l| l.price).fold(f64::MIN, f64::max);
+    let best_ask = book.iter().filter(|l| l.side == BookSide::Ask).map(|l| l.price).fold(f64::MAX, f64::min);
+    assert_eq!((best_bid, best_ask), (64999.0, 65001.0));
+}
+
+#[test]
+fn test_seeded_fixtures_repeat() {
+    let mut a = Fixtures::seeded(42);
+    let mut b = Fixtures::seeded(42);
+    for _ in 0..20 {
+        let (x, y) = (a.ticker(fixtures::INSTRUMENT), b.ticker(fixtures::INSTRUMENT));
+        assert_eq!(x.mark_price, y.mark_price);
+        assert!(x.best_bid_price < x.best_ask_price);
+
+        let (x, y) = (a.order(), b.order());
+        assert_eq!((x.client_order_id, x.price, x.amount), (y.client_order_id, y.price, y.amount));
+        let (x, y) = (a.trade(), b.trade());
+        assert_eq!((x.price, x.amount), (y.price, y.amount));
+        assert!(x.amount > 0.0);
+    }
+}
+
+#[test]
+fn test_seeded_orders_rest_on_their_side() {
+    let mut fixtures = Fixtures::seeded(7);
+    for _ in 0..50 {
+        let order = fixtures.order();
+        let price = order.price.unwrap();
+        match order.direction {
+            OrderSide::Buy => assert!(price < fixtures::MARK),
+            OrderSide::Sell => assert!(price > fixtures::MARK),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+//! Tests for the shared test data
+
+pub mod fixtures_tests;